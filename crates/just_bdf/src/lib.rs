@@ -7,6 +7,12 @@
 
 mod lexer;
 mod parser;
+pub mod stream;
+pub mod xlfd;
+
+pub use just_geometry::Vector2;
+use std::str::FromStr;
+use xlfd::{Xlfd, XlfdError};
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
 pub struct Location {
@@ -74,12 +80,6 @@ pub struct Glyph {
     pub bitmap: Vec<u8>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
-pub struct Vector2<T> {
-    pub width: T,
-    pub height: T,
-}
-
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
 pub struct Property {
     pub name: String,
@@ -109,6 +109,62 @@ pub struct Font {
     pub glyphs: Vec<Glyph>,
 }
 
+impl Font {
+    pub fn property(&self, name: &str) -> Option<&PropertyValue> {
+        self.properties
+            .iter()
+            .find_map(|property| (property.name == name).then_some(&property.value))
+    }
+
+    pub fn integer_property(&self, name: &str) -> Option<i32> {
+        match self.property(name)? {
+            PropertyValue::Number(Number::Integer(integer)) => Some(*integer),
+            _ => None,
+        }
+    }
+
+    pub fn string_property(&self, name: &str) -> Option<&str> {
+        match self.property(name)? {
+            PropertyValue::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    /// Distance from the baseline to the top of the line, in pixels
+    pub fn font_ascent(&self) -> Option<i32> {
+        self.integer_property("FONT_ASCENT")
+    }
+
+    /// Distance from the baseline to the bottom of the line, in pixels
+    pub fn font_descent(&self) -> Option<i32> {
+        self.integer_property("FONT_DESCENT")
+    }
+
+    /// Encoding of the glyph drawn for characters missing in the font
+    pub fn default_char(&self) -> Option<u32> {
+        self.integer_property("DEFAULT_CHAR")
+            .and_then(|default_char| u32::try_from(default_char).ok())
+    }
+
+    pub fn charset_registry(&self) -> Option<&str> {
+        self.string_property("CHARSET_REGISTRY")
+    }
+
+    pub fn charset_encoding(&self) -> Option<&str> {
+        self.string_property("CHARSET_ENCODING")
+    }
+
+    /// Calculated weight of the font, larger for bolder fonts
+    pub fn weight(&self) -> Option<i32> {
+        self.integer_property("WEIGHT")
+    }
+
+    /// `FONT` name split into fields
+    pub fn xlfd(&self) -> Result<Xlfd, XlfdError> {
+        Xlfd::from_str(&self.font)
+    }
+}
+
 pub fn parse(input: &str) -> Result<Font, ParserError> {
     let lexer = lexer::Lexer::new(input);
     let parser = parser::Parser::new(lexer);
@@ -157,4 +213,7 @@ ENDFONT
     let font = parse(unparsed_font).expect("Could not parse font file");
     assert_eq!(font.version, Number::Float(2.1));
     assert_eq!(font.glyphs.len(), 1);
+    assert_eq!(font.font_ascent(), Some(14));
+    assert_eq!(font.default_char(), None);
+    assert_eq!(font.xlfd().unwrap().family_name, "unifont");
 }
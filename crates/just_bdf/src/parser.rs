@@ -11,6 +11,8 @@ where
 {
     lexer: Peekable<Lexer<'src, I>>,
     font: Font,
+    /// Leave [`Glyph::bitmap`] empty
+    skip_bitmaps: bool,
 }
 
 impl<'src, I> Parser<'src, I>
@@ -44,9 +46,18 @@ where
                 v_vector: None,
                 glyphs: Vec::new(),
             },
+            skip_bitmaps: false,
         }
     }
 
+    /// Parser of glyphs only, `metric_set` comes from the font header
+    pub fn for_glyphs(lexer: Lexer<'src, I>, metric_set: i32, skip_bitmaps: bool) -> Self {
+        let mut parser = Self::new(lexer);
+        parser.font.metric_set = metric_set;
+        parser.skip_bitmaps = skip_bitmaps;
+        parser
+    }
+
     fn next_token(&mut self) -> Result<Spanned<Token<'src>>, ParserError> {
         self.lexer.next().ok_or(ParserError::UnexpectedEof)
     }
@@ -99,7 +110,7 @@ where
         }
     }
 
-    fn glyph(&mut self) -> Result<Glyph, ParserError> {
+    pub fn glyph(&mut self) -> Result<Glyph, ParserError> {
         self.keyword("STARTCHAR")?;
         let name = self.any_keyword()?.to_string();
 
@@ -107,21 +118,15 @@ where
             name: name.clone(),
             encoding: Encoding::AdobeStandard(0),
             s_width: Vector2 {
-                width: Number::Integer(0),
-                height: Number::Integer(0),
-            },
-            d_width: Vector2 {
-                width: 0,
-                height: 0,
+                x: Number::Integer(0),
+                y: Number::Integer(0),
             },
+            d_width: Vector2 { x: 0, y: 0 },
             s_width1: Vector2 {
-                width: Number::Integer(0),
-                height: Number::Integer(0),
-            },
-            d_width1: Vector2 {
-                width: 0,
-                height: 0,
+                x: Number::Integer(0),
+                y: Number::Integer(0),
             },
+            d_width1: Vector2 { x: 0, y: 0 },
             v_vector: None,
             bounding_box: FontBoundingBox {
                 width: 0,
@@ -182,33 +187,33 @@ where
                 "SWIDTH" => {
                     check_duplicate!(s_width_set, "SWIDTH");
 
-                    glyph.s_width.width = self.number()?;
-                    glyph.s_width.height = self.number()?;
+                    glyph.s_width.x = self.number()?;
+                    glyph.s_width.y = self.number()?;
                 }
                 "DWIDTH" => {
                     check_duplicate!(d_width_set, "DWIDTH");
 
-                    glyph.d_width.width = self.integer()?;
-                    glyph.d_width.height = self.integer()?;
+                    glyph.d_width.x = self.integer()?;
+                    glyph.d_width.y = self.integer()?;
                 }
                 "SWIDTH1" if metric_set != 0 => {
                     check_duplicate!(s_width1_set, "SWIDTH1");
 
-                    glyph.s_width1.width = self.number()?;
-                    glyph.s_width1.height = self.number()?;
+                    glyph.s_width1.x = self.number()?;
+                    glyph.s_width1.y = self.number()?;
                 }
                 "DWIDTH1" if metric_set != 0 => {
                     check_duplicate!(d_width1_set, "DWIDTH1");
 
-                    glyph.d_width1.width = self.integer()?;
-                    glyph.d_width1.height = self.integer()?;
+                    glyph.d_width1.x = self.integer()?;
+                    glyph.d_width1.y = self.integer()?;
                 }
                 "VVECTOR" => {
                     check_duplicate!(v_vector_set, "VVECTOR");
 
                     let v_vector = Vector2 {
-                        width: self.integer()?,
-                        height: self.integer()?,
+                        x: self.integer()?,
+                        y: self.integer()?,
                     };
                     glyph.v_vector = Some(v_vector);
                 }
@@ -236,6 +241,11 @@ where
                     }
                     check_missing!(bbx_set, "BBX");
 
+                    if self.skip_bitmaps {
+                        while !matches!(self.next_token()?.value, Token::Keyword("ENDCHAR")) {}
+                        return Ok(glyph);
+                    }
+
                     let w = (glyph.bounding_box.width + 7) / 8;
 
                     glyph.bitmap = Vec::with_capacity(w as usize);
@@ -259,7 +269,29 @@ where
         }
     }
 
+    /// Expect `ENDFONT` after the last glyph
+    pub fn end(&mut self) -> Result<(), ParserError> {
+        self.keyword("ENDFONT")
+    }
+
+    pub fn into_font(self) -> Font {
+        self.font
+    }
+
     pub fn parse(mut self) -> Result<Font, ParserError> {
+        let n = self.header()?;
+        self.font.glyphs = Vec::with_capacity(n as usize);
+        for _ in 0..n {
+            let glyph = self.glyph()?;
+            self.font.glyphs.push(glyph);
+        }
+        self.end()?;
+
+        Ok(self.font)
+    }
+
+    /// Parse everything up to and including `CHARS`, returns the number of glyphs
+    pub fn header(&mut self) -> Result<i32, ParserError> {
         self.keyword("STARTFONT")?;
         self.font.version = self.number()?;
 
@@ -339,15 +371,7 @@ where
                     check_missing!(size_set, "SIZE");
                     check_missing!(font_set, "FONT");
 
-                    let n = self.integer()?;
-                    self.font.glyphs = Vec::with_capacity(n as usize);
-                    for _ in 0..n {
-                        let glyph = self.glyph()?;
-                        self.font.glyphs.push(glyph);
-                    }
-                    self.keyword("ENDFONT")?;
-
-                    return Ok(self.font);
+                    return self.integer();
                 }
                 invalid => return Err(ParserError::InvalidGlobalProperty(invalid.to_string())),
             }
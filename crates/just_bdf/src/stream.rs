@@ -0,0 +1,231 @@
+//! Parser reading fonts incrementally from [`BufRead`], one glyph at a time
+//!
+//! BDF is line based, so the header and each glyph are read into a reused line buffer and parsed
+//! on their own. Only the glyph being parsed is kept in memory, which matters for fonts with tens
+//! of thousands of glyphs.
+
+use crate::{lexer::Lexer, parser::Parser, Font, Glyph, Location, ParserError, Span};
+use std::{fmt::Display, io};
+
+#[derive(Debug)]
+pub enum StreamError {
+    Io(io::Error),
+    Parser(ParserError),
+}
+
+impl From<io::Error> for StreamError {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<ParserError> for StreamError {
+    fn from(value: ParserError) -> Self {
+        Self::Parser(value)
+    }
+}
+
+impl Display for StreamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StreamError::Io(err) => write!(f, "Could not read font: {}", err),
+            StreamError::Parser(err) => write!(f, "Could not parse font: {:?}", err),
+        }
+    }
+}
+
+/// Iterator over glyphs of a font, header is parsed by [`Self::new`]
+pub struct StreamingParser<R> {
+    reader: R,
+    header: Font,
+    remaining_glyphs: u32,
+    skip_bitmaps: bool,
+    /// Bytes read so far, used to make spans in errors relative to the whole input
+    offset: usize,
+    chunk: String,
+    line: String,
+    failed: bool,
+}
+
+impl<R: io::BufRead> StreamingParser<R> {
+    pub fn new(reader: R) -> Result<Self, StreamError> {
+        let mut parser = Self {
+            reader,
+            header: Parser::new(Lexer::new("")).into_font(),
+            remaining_glyphs: 0,
+            skip_bitmaps: false,
+            offset: 0,
+            chunk: String::new(),
+            line: String::new(),
+            failed: false,
+        };
+
+        let chunk_offset = parser.read_chunk("CHARS")?;
+        let mut header_parser = Parser::new(Lexer::new(&parser.chunk));
+        let glyphs = header_parser
+            .header()
+            .map_err(|err| offset_error(err, chunk_offset))?;
+        parser.header = header_parser.into_font();
+        parser.remaining_glyphs = glyphs.max(0) as u32;
+
+        Ok(parser)
+    }
+
+    /// Don't keep glyph bitmaps, e.g. when only metrics are needed. Bitmap lines are skipped
+    /// without being parsed and [`Glyph::bitmap`] is left empty.
+    pub fn skip_bitmaps(mut self, skip: bool) -> Self {
+        self.skip_bitmaps = skip;
+        self
+    }
+
+    /// Everything but the glyphs, [`Font::glyphs`] is empty
+    pub fn header(&self) -> &Font {
+        &self.header
+    }
+
+    /// Number of glyphs not read yet
+    pub fn remaining_glyphs(&self) -> u32 {
+        self.remaining_glyphs
+    }
+
+    /// Read lines into `chunk` until line starting with `last_keyword`, returns offset of the
+    /// chunk
+    fn read_chunk(&mut self, last_keyword: &str) -> Result<usize, StreamError> {
+        self.chunk.clear();
+        let chunk_offset = self.offset;
+        let mut in_bitmap = false;
+
+        loop {
+            self.line.clear();
+            let read = self.reader.read_line(&mut self.line)?;
+            if read == 0 {
+                return Err(ParserError::UnexpectedEof.into());
+            }
+            self.offset += read;
+
+            let keyword = self.line.split_whitespace().next();
+            if in_bitmap && keyword != Some("ENDCHAR") {
+                continue;
+            }
+            if self.skip_bitmaps && keyword == Some("BITMAP") {
+                in_bitmap = true;
+            }
+
+            self.chunk.push_str(&self.line);
+            if keyword == Some(last_keyword) {
+                return Ok(chunk_offset);
+            }
+        }
+    }
+
+    fn next_glyph(&mut self) -> Result<Glyph, StreamError> {
+        let chunk_offset = self.read_chunk("ENDCHAR")?;
+        let mut parser = Parser::for_glyphs(
+            Lexer::new(&self.chunk),
+            self.header.metric_set,
+            self.skip_bitmaps,
+        );
+        let glyph = parser
+            .glyph()
+            .map_err(|err| offset_error(err, chunk_offset))?;
+        self.remaining_glyphs -= 1;
+
+        if self.remaining_glyphs == 0 {
+            let chunk_offset = self.read_chunk("ENDFONT")?;
+            Parser::new(Lexer::new(&self.chunk))
+                .end()
+                .map_err(|err| offset_error(err, chunk_offset))?;
+        }
+
+        Ok(glyph)
+    }
+}
+
+impl<R: io::BufRead> Iterator for StreamingParser<R> {
+    type Item = Result<Glyph, StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining_glyphs == 0 || self.failed {
+            return None;
+        }
+
+        let glyph = self.next_glyph();
+        self.failed = glyph.is_err();
+        Some(glyph)
+    }
+}
+
+fn offset_error(err: ParserError, offset: usize) -> ParserError {
+    let offset_span = |span: Span| Span {
+        start: Location {
+            offset: span.start.offset + offset,
+        },
+        end: Location {
+            offset: span.end.offset + offset,
+        },
+    };
+
+    match err {
+        ParserError::InvalidArgument(span) => ParserError::InvalidArgument(offset_span(span)),
+        ParserError::UnclosedString(span) => ParserError::UnclosedString(offset_span(span)),
+        err => err,
+    }
+}
+
+#[test]
+fn streaming_matches_parse() {
+    let unparsed_font = "STARTFONT 2.1
+FONT -misc-test-medium-r-normal--8-80-75-75-c-80-iso10646-1
+SIZE 8 75 75
+FONTBOUNDINGBOX 8 2 0 0
+STARTPROPERTIES 1
+FONT_ASCENT 2
+ENDPROPERTIES
+CHARS 2
+STARTCHAR A
+ENCODING 65
+SWIDTH 500 0
+DWIDTH 8 0
+BBX 8 2 0 0
+BITMAP
+18
+24
+ENDCHAR
+COMMENT between glyphs
+STARTCHAR B
+ENCODING 66
+SWIDTH 500 0
+DWIDTH 8 0
+BBX 8 2 0 0
+BITMAP
+7E
+42
+ENDCHAR
+ENDFONT
+";
+
+    let font = crate::parse(unparsed_font).unwrap();
+    let streamed = StreamingParser::new(unparsed_font.as_bytes()).unwrap();
+    assert_eq!(streamed.header().font, font.font);
+    assert_eq!(streamed.remaining_glyphs(), 2);
+    let glyphs = streamed.collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(glyphs, font.glyphs);
+
+    let metrics_only = StreamingParser::new(unparsed_font.as_bytes())
+        .unwrap()
+        .skip_bitmaps(true)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(metrics_only.len(), 2);
+    assert!(metrics_only[1].bitmap.is_empty());
+    assert_eq!(metrics_only[1].encoding, font.glyphs[1].encoding);
+
+    let truncated = &unparsed_font[..unparsed_font.find("STARTCHAR B").unwrap()];
+    let mut streamed = StreamingParser::new(truncated.as_bytes()).unwrap();
+    assert!(streamed.next().unwrap().is_ok());
+    assert!(matches!(
+        streamed.next(),
+        Some(Err(StreamError::Parser(ParserError::UnexpectedEof)))
+    ));
+    assert!(streamed.next().is_none());
+}
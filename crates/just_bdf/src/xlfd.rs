@@ -0,0 +1,148 @@
+//! X Logical Font Description names, e.g. `-misc-fixed-medium-r-normal--13-120-75-75-c-70-iso10646-1`
+
+use std::{fmt::Display, str::FromStr};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Xlfd {
+    pub foundry: String,
+    pub family_name: String,
+    pub weight_name: String,
+    /// `r` for roman, `i` for italic, `o` for oblique
+    pub slant: String,
+    pub setwidth_name: String,
+    pub add_style_name: String,
+    pub pixel_size: Option<u32>,
+    /// In decipoints
+    pub point_size: Option<u32>,
+    pub resolution_x: Option<u32>,
+    pub resolution_y: Option<u32>,
+    /// `p` for proportional, `m` for monospace, `c` for monospace with cells
+    pub spacing: String,
+    /// In tenths of pixels, negative for right-to-left fonts
+    pub average_width: Option<i32>,
+    pub charset_registry: String,
+    pub charset_encoding: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum XlfdError {
+    /// Name does not start with `-`
+    NotXlfd,
+    /// XLFD names have 14 fields
+    FieldCount(usize),
+    InvalidNumber(&'static str, String),
+}
+
+impl Display for XlfdError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            XlfdError::NotXlfd => write!(f, "XLFD name must start with '-'"),
+            XlfdError::FieldCount(count) => {
+                write!(f, "XLFD name must have 14 fields, got {}", count)
+            }
+            XlfdError::InvalidNumber(field, value) => {
+                write!(f, "Invalid XLFD {} '{}'", field, value)
+            }
+        }
+    }
+}
+
+impl Xlfd {
+    /// Charset as used in locale or encoding names, e.g. `iso10646-1`
+    pub fn charset(&self) -> String {
+        format!("{}-{}", self.charset_registry, self.charset_encoding)
+    }
+}
+
+impl FromStr for Xlfd {
+    type Err = XlfdError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let fields = name
+            .strip_prefix('-')
+            .ok_or(XlfdError::NotXlfd)?
+            .split('-')
+            .collect::<Vec<_>>();
+        let [foundry, family_name, weight_name, slant, setwidth_name, add_style_name, pixel_size, point_size, resolution_x, resolution_y, spacing, average_width, charset_registry, charset_encoding] =
+            fields[..]
+        else {
+            return Err(XlfdError::FieldCount(fields.len()));
+        };
+
+        fn number<T: FromStr>(field: &'static str, value: &str) -> Result<Option<T>, XlfdError> {
+            if value.is_empty() {
+                return Ok(None);
+            }
+            value
+                .parse()
+                .map(Some)
+                .map_err(|_| XlfdError::InvalidNumber(field, value.to_string()))
+        }
+
+        Ok(Self {
+            foundry: foundry.to_string(),
+            family_name: family_name.to_string(),
+            weight_name: weight_name.to_string(),
+            slant: slant.to_string(),
+            setwidth_name: setwidth_name.to_string(),
+            add_style_name: add_style_name.to_string(),
+            pixel_size: number("PIXEL_SIZE", pixel_size)?,
+            point_size: number("POINT_SIZE", point_size)?,
+            resolution_x: number("RESOLUTION_X", resolution_x)?,
+            resolution_y: number("RESOLUTION_Y", resolution_y)?,
+            spacing: spacing.to_string(),
+            // `~` is used instead of `-` that would be taken as field separator
+            average_width: number("AVERAGE_WIDTH", &average_width.replace('~', "-"))?,
+            charset_registry: charset_registry.to_string(),
+            charset_encoding: charset_encoding.to_string(),
+        })
+    }
+}
+
+impl Display for Xlfd {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn number<T: Display>(n: Option<T>) -> String {
+            n.map(|n| n.to_string()).unwrap_or_default()
+        }
+
+        write!(
+            f,
+            "-{}-{}-{}-{}-{}-{}-{}-{}-{}-{}-{}-{}-{}-{}",
+            self.foundry,
+            self.family_name,
+            self.weight_name,
+            self.slant,
+            self.setwidth_name,
+            self.add_style_name,
+            number(self.pixel_size),
+            number(self.point_size),
+            number(self.resolution_x),
+            number(self.resolution_y),
+            self.spacing,
+            number(self.average_width).replace('-', "~"),
+            self.charset_registry,
+            self.charset_encoding,
+        )
+    }
+}
+
+#[test]
+fn xlfd_roundtrip() {
+    let name = "-gnu-unifont-medium-r-normal--16-160-75-75-c-80-iso10646-1";
+    let xlfd = Xlfd::from_str(name).unwrap();
+    assert_eq!(xlfd.family_name, "unifont");
+    assert_eq!(xlfd.add_style_name, "");
+    assert_eq!(xlfd.pixel_size, Some(16));
+    assert_eq!(xlfd.average_width, Some(80));
+    assert_eq!(xlfd.charset(), "iso10646-1");
+    assert_eq!(xlfd.to_string(), name);
+
+    assert_eq!(
+        Xlfd::from_str("-misc-fixed-medium-r-normal--13-120-75-75-c-~70-iso10646-1")
+            .unwrap()
+            .average_width,
+        Some(-70)
+    );
+    assert_eq!(Xlfd::from_str("fixed"), Err(XlfdError::NotXlfd));
+    assert_eq!(Xlfd::from_str("-misc-fixed"), Err(XlfdError::FieldCount(2)));
+}
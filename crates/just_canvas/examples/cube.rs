@@ -0,0 +1,92 @@
+// CLIPPY CONFIG
+#![allow(
+    clippy::new_without_default,
+    clippy::unnecessary_cast,
+    clippy::identity_op
+)]
+
+//! Spinning cube drawn with the software rasterizer, `cargo run --example cube --features raster3d`
+
+use just_canvas::{
+    draw,
+    raster3d::{Matrix4, Rasterizer, Vector3, Vertex},
+    Canvas, Color, Result, Vector2,
+};
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+const FRAME_INTERVAL: Duration = Duration::from_micros(16667);
+const BACKGROUND: Color = Color::from_raw(0xff272822);
+
+/// Corners of a unit cube, index bits are `x`, `y` and `z`
+fn corner(idx: usize) -> Vector3 {
+    let coordinate = |bit: usize| if idx & bit == 0 { -1.0 } else { 1.0 };
+    Vector3::new(coordinate(1), coordinate(2), coordinate(4))
+}
+
+/// Corners of each face, counter-clockwise when seen from outside
+const FACES: [([usize; 4], u32); 6] = [
+    ([1, 3, 7, 5], 0xfff92672),
+    ([0, 4, 6, 2], 0xffa6e22e),
+    ([2, 6, 7, 3], 0xff66d9ef),
+    ([0, 1, 5, 4], 0xfffd971f),
+    ([4, 5, 7, 6], 0xffae81ff),
+    ([0, 2, 3, 1], 0xffe6db74),
+];
+
+fn draw_cube(canvas: &mut Canvas, rasterizer: &mut Rasterizer, transform: &Matrix4) {
+    for (corners, color) in FACES {
+        let vertex = |idx: usize, shade: f32| {
+            let color = Color::from_raw(color);
+            let shade = |channel: u8| (channel as f32 * shade) as u8;
+            Vertex {
+                position: corner(corners[idx]),
+                color: Color {
+                    a: color.a,
+                    r: shade(color.r),
+                    g: shade(color.g),
+                    b: shade(color.b),
+                },
+            }
+        };
+        // Darker corners show the interpolation
+        let quad = [
+            vertex(0, 0.6),
+            vertex(1, 1.0),
+            vertex(2, 0.8),
+            vertex(3, 1.0),
+        ];
+        rasterizer.triangle(canvas, transform, [quad[0], quad[1], quad[2]]);
+        rasterizer.triangle(canvas, transform, [quad[0], quad[2], quad[3]]);
+    }
+}
+
+fn main() -> Result<()> {
+    let mut canvas = Canvas::new("cube")?;
+    let mut rasterizer = Rasterizer::new();
+    let start = Instant::now();
+
+    while !canvas.should_close() {
+        canvas.process_events()?;
+
+        let size = canvas.window_size();
+        draw::rectangle_replace(&mut canvas, Vector2::<i32>::zero(), size, BACKGROUND);
+        rasterizer.clear_depth(&canvas);
+
+        let time = start.elapsed().as_secs_f32();
+        let aspect = size.x as f32 / size.y.max(1) as f32;
+        let transform = Matrix4::perspective(1.0, aspect, 0.1, 100.0)
+            * Matrix4::translation(Vector3::new(0.0, 0.0, -5.0))
+            * Matrix4::rotation_x(time * 0.7)
+            * Matrix4::rotation_y(time);
+        draw_cube(&mut canvas, &mut rasterizer, &transform);
+
+        canvas.flush()?;
+        let next = canvas.request_frame(FRAME_INTERVAL)?;
+        thread::sleep(next.saturating_duration_since(Instant::now()));
+    }
+
+    Ok(())
+}
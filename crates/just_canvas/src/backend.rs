@@ -1,14 +1,66 @@
-use crate::{Event, Result, Vector2};
+use crate::{
+    backend::owned_bitmap::OwnedBitmapBackend, frame::Vblank, DamageRect, Event, PixelFormat,
+    PopupShadow, Result, Vector2,
+};
+use just_x11_simple::clipboard::Offer;
 
 pub(crate) mod owned_bitmap;
+pub(crate) mod present_thread;
+pub(crate) mod shadow;
 pub(crate) mod shared_bitmap;
+pub(crate) mod supersampled;
 pub(crate) mod x11_mit_shm;
 
 pub(crate) trait Backend {
     fn flush_window(&mut self) -> Result<()>;
 
+    /// Present only part of the window, backends without partial presentation present all of it
+    fn flush_region(&mut self, _position: Vector2<u32>, _size: Vector2<u32>) -> Result<()> {
+        self.flush_window()
+    }
+
+    /// Move `size` area of the presented window from `source` to `destination` without
+    /// presenting it again. Returns parts of the destination that could not be copied, e.g.
+    /// because the source was covered by other window, or `None` if the backend can't copy.
+    fn copy_presented(
+        &mut self,
+        _source: Vector2<u32>,
+        _destination: Vector2<u32>,
+        _size: Vector2<u32>,
+    ) -> Result<Option<Vec<DamageRect>>> {
+        Ok(None)
+    }
+
     fn events(&mut self) -> Result<Vec<Event>>;
 
+    /// Present from a separate thread, see [`Canvas::present_in_background`](crate::Canvas).
+    /// Returns `false` if the backend can't.
+    fn present_in_background(&mut self) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Ask to report the next vertical blank in [`Backend::vblanks`], once per request.
+    /// Returns `false` if the backend can't tell when vertical blanks happen.
+    fn request_vblank(&mut self) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Vertical blanks received by the last [`Backend::events`]
+    fn vblanks(&mut self) -> Vec<Vblank> {
+        Vec::new()
+    }
+
+    /// Own the clipboard with `offers` until other client takes it. Returns `false` if the
+    /// backend has no clipboard.
+    fn copy_to_clipboard(&mut self, _offers: Vec<Offer>) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Clipboard contents in the first of `targets` the owner offers, with that target
+    fn paste_from_clipboard(&mut self, _targets: &[&str]) -> Result<Option<(String, Vec<u8>)>> {
+        Ok(None)
+    }
+
     fn resize(&mut self, size: Vector2<u32>) -> Result<()>;
 
     fn size(&self) -> Vector2<u32>;
@@ -16,4 +68,30 @@ pub(crate) trait Backend {
     fn buf_mut(&mut self) -> &mut [u8];
 
     fn buf(&self) -> &[u8];
+
+    /// Layout of pixels in [`Backend::buf`], what the presented window expects
+    fn pixel_format(&self) -> PixelFormat {
+        PixelFormat::Bgra8888
+    }
+
+    /// Ring the keyboard bell, `percent` is relative to the base volume in `-100..=100` range
+    fn bell(&mut self, percent: i8) -> Result<()>;
+
+    /// Move pointer to `position` relative to the window
+    fn warp_pointer(&mut self, position: Vector2<u32>) -> Result<()>;
+
+    /// Hide pointer and confine it to the window. Returns `false` if pointer could not be captured.
+    fn set_pointer_capture(&mut self, captured: bool) -> Result<bool>;
+
+    /// Separate window at `position` relative to this one, not clipped by its bounds. Backends
+    /// without windows give an offscreen bitmap. Backends that can't blend `shadow` with what is
+    /// below the popup ignore it.
+    fn open_popup(
+        &mut self,
+        _position: Vector2<i32>,
+        size: Vector2<u32>,
+        _shadow: Option<PopupShadow>,
+    ) -> Result<Box<dyn Backend>> {
+        Ok(Box::new(OwnedBitmapBackend::new(size)))
+    }
 }
@@ -0,0 +1,504 @@
+//! Presenting frames of an X11 window from a separate thread, so flush returns without waiting
+//! for the server to read the shared memory
+//!
+//! Three buffers are passed around: one is drawn into, one waits to be presented and one is
+//! being presented. The thread has its own connection, so requests of the UI thread are not
+//! ordered with its `PutImage` requests.
+
+use crate::{CanvasError, Result, Vector2, BYTES_PER_PIXEL};
+use core::cmp;
+use just_shared_memory::{SharedMemory, SharedMemoryId};
+use just_x11::{
+    connection::DisplayVar,
+    display,
+    extensions::mit_shm::{self, ShmSegId},
+    requests::PutImageFormat,
+    Drawable, GContextId, WindowId, XDisplay,
+};
+use std::{
+    collections::HashMap,
+    sync::mpsc::{self, Receiver, Sender},
+    thread::{self, JoinHandle},
+};
+
+const BUFFER_COUNT: usize = 3;
+
+struct Buffer {
+    mem: SharedMemory,
+    size: Vector2<u32>,
+}
+
+// SAFETY: Memory is used only by the owner of the buffer, which is passed between threads
+unsafe impl Send for Buffer {}
+
+impl Buffer {
+    fn new(size: Vector2<u32>) -> Self {
+        Self {
+            // Empty segments can't be attached
+            mem: SharedMemory::zeroed(cmp::max(size.x * size.y * BYTES_PER_PIXEL, 1)),
+            size,
+        }
+    }
+
+    fn len(&self) -> usize {
+        (self.size.x * self.size.y * BYTES_PER_PIXEL) as usize
+    }
+
+    fn data(&self) -> &[u8] {
+        let len = self.len();
+        unsafe { &self.mem.data()[..len] }
+    }
+
+    fn data_mut(&mut self) -> &mut [u8] {
+        let len = self.len();
+        unsafe { &mut self.mem.data_mut()[..len] }
+    }
+}
+
+impl Drop for Buffer {
+    fn drop(&mut self) {
+        // Segment is removed once the server detaches it too
+        unsafe { std::ptr::read(&self.mem).free() }
+    }
+}
+
+enum Request {
+    Present {
+        buffer: Buffer,
+        position: Vector2<u32>,
+        size: Vector2<u32>,
+    },
+    /// Buffer of old size after resize
+    Free(Buffer),
+}
+
+pub(crate) struct PresentThread {
+    requests: Option<Sender<Request>>,
+    /// Buffers that were presented or skipped, ready to be drawn into
+    presented: Receiver<Buffer>,
+    /// Drawn into by the UI thread
+    back: Buffer,
+    thread: Option<JoinHandle<Result<()>>>,
+}
+
+impl PresentThread {
+    /// Start presenting to `window` on a new connection to `display`, with `contents` of the
+    /// window size already drawn
+    pub(crate) fn spawn(
+        display: DisplayVar,
+        window: WindowId,
+        size: Vector2<u32>,
+        contents: &[u8],
+    ) -> Result<Self> {
+        let (requests, received) = mpsc::channel();
+        let (presented_sender, presented) = mpsc::channel();
+        for _ in 0..BUFFER_COUNT - 1 {
+            presented_sender.send(Buffer::new(size)).unwrap();
+        }
+        let mut back = Buffer::new(size);
+        let len = back.len();
+        back.data_mut().copy_from_slice(&contents[..len]);
+
+        // Connection can't be moved to the thread, so errors of opening it are sent back
+        let (opened_sender, opened) = mpsc::channel();
+        let thread = thread::Builder::new()
+            .name("just_canvas present".to_string())
+            .spawn(move || {
+                let mut presenter = match Presenter::new(display, window) {
+                    Ok(presenter) => {
+                        let _ = opened_sender.send(Ok(()));
+                        presenter
+                    }
+                    Err(err) => {
+                        let _ = opened_sender.send(Err(err));
+                        return Ok(());
+                    }
+                };
+                presenter.run(received, presented_sender)
+            })
+            .map_err(|err| CanvasError::X11ProtocolError(err.into()))?;
+        if let Ok(Err(err)) = opened.recv() {
+            let _ = thread.join();
+            return Err(err);
+        }
+
+        Ok(Self {
+            requests: Some(requests),
+            presented,
+            back,
+            thread: Some(thread),
+        })
+    }
+
+    pub(crate) fn buf(&self) -> &[u8] {
+        self.back.data()
+    }
+
+    pub(crate) fn buf_mut(&mut self) -> &mut [u8] {
+        self.back.data_mut()
+    }
+
+    /// Error of the stopped thread
+    fn stopped(&mut self) -> CanvasError {
+        self.requests = None;
+        match self.thread.take().map(JoinHandle::join) {
+            Some(Ok(Err(err))) => err,
+            Some(Err(panic)) => std::panic::resume_unwind(panic),
+            _ => CanvasError::X11ProtocolError(just_x11::error::Error::ConnectionClosed),
+        }
+    }
+
+    fn send(&mut self, request: Request) -> Result<()> {
+        let sent = self
+            .requests
+            .as_ref()
+            .is_some_and(|requests| requests.send(request).is_ok());
+        if sent {
+            Ok(())
+        } else {
+            Err(self.stopped())
+        }
+    }
+
+    /// Queue `size` area at `position` to be presented and continue drawing into a copy of the
+    /// frame. Blocks only if both other buffers are still used by the thread.
+    pub(crate) fn present(&mut self, position: Vector2<u32>, size: Vector2<u32>) -> Result<()> {
+        let Ok(mut next) = self.presented.recv() else {
+            return Err(self.stopped());
+        };
+        if next.size != self.back.size {
+            let stale = std::mem::replace(&mut next, Buffer::new(self.back.size));
+            self.send(Request::Free(stale))?;
+        }
+        next.data_mut().copy_from_slice(self.back.data());
+
+        let buffer = std::mem::replace(&mut self.back, next);
+        self.send(Request::Present {
+            buffer,
+            position,
+            size,
+        })
+    }
+
+    /// Draw into a buffer of `new_size` keeping the overlapping part of the frame, buffers of old
+    /// size are replaced when they come back from the thread
+    pub(crate) fn resize(&mut self, new_size: Vector2<u32>) -> Result<()> {
+        let mut back = Buffer::new(new_size);
+        let old_size = self.back.size;
+        let row_len = (cmp::min(new_size.x, old_size.x) * BYTES_PER_PIXEL) as usize;
+        for y in 0..cmp::min(new_size.y, old_size.y) {
+            let old_offset = (old_size.x * y * BYTES_PER_PIXEL) as usize;
+            let new_offset = (new_size.x * y * BYTES_PER_PIXEL) as usize;
+            back.data_mut()[new_offset..new_offset + row_len]
+                .copy_from_slice(&self.back.data()[old_offset..old_offset + row_len]);
+        }
+
+        let old_back = std::mem::replace(&mut self.back, back);
+        self.send(Request::Free(old_back))
+    }
+}
+
+impl Drop for PresentThread {
+    fn drop(&mut self) {
+        // Thread detaches its segments once the channel is closed
+        self.requests = None;
+        if let Some(Ok(Err(err))) = self.thread.take().map(JoinHandle::join) {
+            eprintln!("just_canvas: Presenting thread failed: {:?}", err);
+        }
+    }
+}
+
+/// Newest of `frame` and `newer` present requests, with damage of the skipped frames of the same
+/// size. Skipped buffers are sent to `presented`, buffers to free are put into `stale`.
+fn skip_to_newest(
+    frame: (Buffer, Vector2<u32>, Vector2<u32>),
+    newer: impl Iterator<Item = Request>,
+    presented: &Sender<Buffer>,
+    stale: &mut Vec<Buffer>,
+) -> (Buffer, Vector2<u32>, Vector2<u32>) {
+    let (mut buffer, mut position, mut size) = frame;
+    for request in newer {
+        match request {
+            Request::Present {
+                buffer: newer,
+                position: newer_position,
+                size: newer_size,
+            } => {
+                let skipped = std::mem::replace(&mut buffer, newer);
+                (position, size) = if skipped.size == buffer.size {
+                    merge_damage((position, size), (newer_position, newer_size))
+                } else {
+                    (newer_position, newer_size)
+                };
+                let _ = presented.send(skipped);
+            }
+            Request::Free(buffer) => stale.push(buffer),
+        }
+    }
+    (buffer, position, size)
+}
+
+/// Bounding box of two damaged areas
+fn merge_damage(
+    (position, size): (Vector2<u32>, Vector2<u32>),
+    (other_position, other_size): (Vector2<u32>, Vector2<u32>),
+) -> (Vector2<u32>, Vector2<u32>) {
+    let start = Vector2 {
+        x: cmp::min(position.x, other_position.x),
+        y: cmp::min(position.y, other_position.y),
+    };
+    let end = Vector2 {
+        x: cmp::max(position.x + size.x, other_position.x + other_size.x),
+        y: cmp::max(position.y + size.y, other_position.y + other_size.y),
+    };
+    (start, end - start)
+}
+
+struct Presenter {
+    display: XDisplay,
+    mit_shm_major_opcode: u8,
+    window: WindowId,
+    gc: GContextId,
+    /// Depth of the root window of the default screen, the window is created with it too
+    depth: u8,
+    /// Segments attached on this connection, attached on the first present of a buffer
+    attached: HashMap<SharedMemoryId, ShmSegId>,
+}
+
+impl Presenter {
+    fn new(display: DisplayVar, window: WindowId) -> Result<Self> {
+        let mut display = display::connect(display)?;
+        let Some(mit_shm) = mit_shm::negotiate(&mut display)? else {
+            return Err(CanvasError::X11ProtocolError(
+                just_x11::error::Error::InvalidResponse("MIT-SHM extension missing"),
+            ));
+        };
+        let gc = display.default_gc(display.default_screen_index())?;
+        let depth = display.default_screen().root_depth;
+
+        Ok(Self {
+            display,
+            mit_shm_major_opcode: mit_shm.major_opcode,
+            window,
+            gc,
+            depth,
+            attached: HashMap::new(),
+        })
+    }
+
+    fn run(&mut self, requests: Receiver<Request>, presented: Sender<Buffer>) -> Result<()> {
+        let result = self.present_requests(&requests, &presented);
+        for shmseg in self.attached.drain().map(|(_, shmseg)| shmseg) {
+            self.display.send_extension_request(
+                &mit_shm::requests::Detach { shmseg },
+                self.mit_shm_major_opcode,
+            )?;
+        }
+        self.display.flush()?;
+        result
+    }
+
+    fn present_requests(
+        &mut self,
+        requests: &Receiver<Request>,
+        presented: &Sender<Buffer>,
+    ) -> Result<()> {
+        while let Ok(request) = requests.recv() {
+            let frame = match request {
+                Request::Present {
+                    buffer,
+                    position,
+                    size,
+                } => (buffer, position, size),
+                Request::Free(buffer) => {
+                    self.free(buffer)?;
+                    continue;
+                }
+            };
+
+            // Only the newest frame is presented, with areas of the skipped ones
+            let mut stale = Vec::new();
+            let (mut buffer, position, size) =
+                skip_to_newest(frame, requests.try_iter(), presented, &mut stale);
+            for buffer in stale {
+                self.free(buffer)?;
+            }
+
+            self.put_image(&mut buffer, position, size)?;
+            if presented.send(buffer).is_err() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn put_image(
+        &mut self,
+        buffer: &mut Buffer,
+        position: Vector2<u32>,
+        size: Vector2<u32>,
+    ) -> Result<()> {
+        let id = buffer.mem.id();
+        let shmseg = match self.attached.get(&id) {
+            Some(&shmseg) => shmseg,
+            None => {
+                let shmseg = ShmSegId::from_resource(self.display.id_allocator().allocate_id());
+                self.display.send_extension_request(
+                    &mit_shm::requests::Attach {
+                        shmseg,
+                        shmid: id.inner() as u32,
+                        read_only: true,
+                    },
+                    self.mit_shm_major_opcode,
+                )?;
+                self.attached.insert(id, shmseg);
+                shmseg
+            }
+        };
+
+        let image_format = self.display.image_format();
+        let bits_per_pixel = (BYTES_PER_PIXEL * 8) as u8;
+        // Buffer is copied over before it is drawn into again, so it's not converted back
+        image_format.convert_zpixmap(buffer.data_mut(), bits_per_pixel);
+        self.display.send_extension_request(
+            &mit_shm::requests::PutImage {
+                drawable: Drawable::Window(self.window),
+                gc: self.gc,
+                total_width: buffer.size.x as u16,
+                total_height: buffer.size.y as u16,
+                src_x: position.x as u16,
+                src_y: position.y as u16,
+                src_width: size.x as u16,
+                src_height: size.y as u16,
+                dst_x: position.x as i16,
+                dst_y: position.y as i16,
+                depth: self.depth,
+                format: PutImageFormat::ZPixmap,
+                send_event: false,
+                bpad: 0,
+                shmseg,
+                offset: 0,
+            },
+            self.mit_shm_major_opcode,
+        )?;
+
+        // Server reads shared memory asynchronously, buffer can be reused after a round trip
+        let pending = self
+            .display
+            .send_request(&just_x11::requests::GetInputFocus)?;
+        self.display.flush()?;
+        let _ = self.display.await_pending_reply(pending)?;
+        // Only errors of `PutImage` could be queued, e.g. of a window that was destroyed
+        let _ = self.display.events()?.count();
+
+        Ok(())
+    }
+
+    fn free(&mut self, buffer: Buffer) -> Result<()> {
+        if let Some(shmseg) = self.attached.remove(&buffer.mem.id()) {
+            self.display.send_extension_request(
+                &mit_shm::requests::Detach { shmseg },
+                self.mit_shm_major_opcode,
+            )?;
+            self.display.flush()?;
+        }
+        drop(buffer);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+fn test_thread(size: Vector2<u32>) -> (PresentThread, Receiver<Request>, Sender<Buffer>) {
+    let (requests, received) = mpsc::channel();
+    let (presented_sender, presented) = mpsc::channel();
+    for _ in 0..BUFFER_COUNT - 1 {
+        presented_sender.send(Buffer::new(size)).unwrap();
+    }
+    let thread = PresentThread {
+        requests: Some(requests),
+        presented,
+        back: Buffer::new(size),
+        thread: None,
+    };
+    (thread, received, presented_sender)
+}
+
+#[test]
+fn present_cycles_buffers() {
+    let size = Vector2 { x: 2, y: 2 };
+    let (mut thread, received, presented) = test_thread(size);
+
+    thread.buf_mut()[0] = 7;
+    thread.present(Vector2 { x: 0, y: 0 }, size).unwrap();
+    let Ok(Request::Present { buffer, .. }) = received.try_recv() else {
+        panic!("expected present request");
+    };
+    assert_eq!(buffer.data()[0], 7);
+    // Drawing continues on a copy of the presented frame
+    assert_eq!(thread.buf()[0], 7);
+    assert_ne!(buffer.mem.id(), thread.back.mem.id());
+    presented.send(buffer).unwrap();
+
+    let new_size = Vector2 { x: 3, y: 2 };
+    thread.resize(new_size).unwrap();
+    let Ok(Request::Free(old_back)) = received.try_recv() else {
+        panic!("expected free request");
+    };
+    assert_eq!(old_back.size, size);
+    assert_eq!(thread.buf()[0], 7);
+
+    // Buffers of old size are freed when they come back
+    thread.present(Vector2 { x: 0, y: 0 }, new_size).unwrap();
+    let Ok(Request::Free(stale)) = received.try_recv() else {
+        panic!("expected free request");
+    };
+    assert_eq!(stale.size, size);
+    let Ok(Request::Present { buffer, .. }) = received.try_recv() else {
+        panic!("expected present request");
+    };
+    assert_eq!((buffer.size, buffer.data()[0]), (new_size, 7));
+}
+
+#[test]
+fn skipped_frames_merge_damage() {
+    let size = Vector2 { x: 4, y: 4 };
+    let (presented, returned) = mpsc::channel();
+    let present = |size, position, damage| Request::Present {
+        buffer: Buffer::new(size),
+        position,
+        size: damage,
+    };
+    let newer = vec![
+        present(size, Vector2 { x: 2, y: 0 }, Vector2 { x: 1, y: 1 }),
+        Request::Free(Buffer::new(Vector2 { x: 1, y: 1 })),
+        present(size, Vector2 { x: 0, y: 3 }, Vector2 { x: 1, y: 1 }),
+    ];
+    let mut stale = Vec::new();
+    let frame = (
+        Buffer::new(size),
+        Vector2 { x: 1, y: 1 },
+        Vector2 { x: 1, y: 1 },
+    );
+    let (_, position, damage) = skip_to_newest(frame, newer.into_iter(), &presented, &mut stale);
+    assert_eq!(
+        (position, damage),
+        (Vector2 { x: 0, y: 0 }, Vector2 { x: 3, y: 4 })
+    );
+    assert_eq!(returned.try_iter().count(), 2);
+    assert_eq!(stale.len(), 1);
+
+    // Frame of another size is presented with only its own damage
+    let newer = vec![present(
+        Vector2 { x: 8, y: 8 },
+        Vector2 { x: 5, y: 5 },
+        Vector2 { x: 2, y: 2 },
+    )];
+    let frame = (Buffer::new(size), Vector2 { x: 0, y: 0 }, size);
+    let (buffer, position, damage) =
+        skip_to_newest(frame, newer.into_iter(), &presented, &mut stale);
+    assert_eq!(buffer.size, Vector2 { x: 8, y: 8 });
+    assert_eq!(
+        (position, damage),
+        (Vector2 { x: 5, y: 5 }, Vector2 { x: 2, y: 2 })
+    );
+}
@@ -0,0 +1,274 @@
+use crate::{
+    backend::Backend, frame::Vblank, Event, PixelFormat, PopupShadow, Result, Vector2,
+    BYTES_PER_PIXEL,
+};
+use just_x11_simple::clipboard::Offer;
+
+/// Popup drawn into the middle of a larger ARGB window of `inner`, with [`PopupShadow`] in the
+/// margins around it. Shadow is drawn once, flush copies only the popup contents.
+pub(crate) struct ShadowBackend {
+    inner: Box<dyn Backend>,
+    shadow: PopupShadow,
+    /// Position of the popup in the window of `inner`
+    margin: Vector2<u32>,
+    size: Vector2<u32>,
+    buf: Vec<u8>,
+    /// Margins are presented with the first flush
+    shadow_presented: bool,
+}
+
+impl PopupShadow {
+    /// Space the shadow needs above and left of the popup, and below and right of it
+    pub(crate) fn margins(&self) -> (Vector2<u32>, Vector2<u32>) {
+        let radius = self.radius as i32;
+        let margin = |offset: i32| (radius + offset).max(0) as u32;
+        (
+            Vector2 {
+                x: margin(-self.offset.x),
+                y: margin(-self.offset.y),
+            },
+            Vector2 {
+                x: margin(self.offset.x),
+                y: margin(self.offset.y),
+            },
+        )
+    }
+
+    /// Opacity from `0.0` to `1.0` at `point` of a window with popup of `size` at `margin`
+    fn coverage(&self, margin: Vector2<u32>, size: Vector2<u32>, point: Vector2<u32>) -> f32 {
+        let radius = self.radius.max(1) as f32;
+        // Distance into the shadow, which fades over `radius` on both sides of its edges
+        let axis = |point: u32, start: i32, len: u32| {
+            let point = point as f32 + 0.5;
+            let inside = (point - start as f32).min(start as f32 + len as f32 - point);
+            let t = ((inside + radius) / (2.0 * radius)).clamp(0.0, 1.0);
+            t * t * (3.0 - 2.0 * t)
+        };
+        axis(point.x, margin.x as i32 + self.offset.x, size.x)
+            * axis(point.y, margin.y as i32 + self.offset.y, size.y)
+    }
+}
+
+impl ShadowBackend {
+    /// `inner` must be large enough for the popup of `size` and [`PopupShadow::margins`]
+    pub(crate) fn new(inner: Box<dyn Backend>, size: Vector2<u32>, shadow: PopupShadow) -> Self {
+        let mut backend = Self {
+            inner,
+            shadow,
+            margin: shadow.margins().0,
+            size,
+            buf: Vec::new(),
+            shadow_presented: false,
+        };
+        backend.draw_shadow();
+        backend
+    }
+
+    /// Fill the window of `inner` with the shadow and allocate popup buffer
+    fn draw_shadow(&mut self) {
+        self.buf =
+            vec![0u8; self.size.x as usize * self.size.y as usize * BYTES_PER_PIXEL as usize];
+        let (margin, size, shadow) = (self.margin, self.size, self.shadow);
+        let window_size = self.inner.size();
+        let format = self.inner.pixel_format();
+        let buf = self.inner.buf_mut();
+        for y in 0..window_size.y {
+            for x in 0..window_size.x {
+                let alpha = shadow.color.a as f32 * shadow.coverage(margin, size, Vector2 { x, y });
+                // Compositors expect premultiplied alpha
+                let premultiply = |channel: u8| (channel as f32 * alpha / 255.0).round() as u8;
+                let color = crate::Color::from_components(
+                    alpha.round() as u8,
+                    premultiply(shadow.color.r),
+                    premultiply(shadow.color.g),
+                    premultiply(shadow.color.b),
+                );
+                let offset = (y * window_size.x + x) as usize * BYTES_PER_PIXEL as usize;
+                buf[offset..offset + BYTES_PER_PIXEL as usize]
+                    .copy_from_slice(&format.encode(color));
+            }
+        }
+        self.shadow_presented = false;
+    }
+
+    /// Copy region of the popup into the window, opaque as the popup is drawn without alpha
+    fn copy_to_window(&mut self, position: Vector2<u32>, size: Vector2<u32>) {
+        let bpp = BYTES_PER_PIXEL as usize;
+        let window_width = self.inner.size().x as usize;
+        let alpha = match self.inner.pixel_format() {
+            PixelFormat::Bgra8888 | PixelFormat::Rgba8888 => 3,
+        };
+        let window = self.inner.buf_mut();
+        for y in position.y..position.y + size.y {
+            let src = (y * self.size.x + position.x) as usize * bpp;
+            let src = &self.buf[src..src + size.x as usize * bpp];
+            let dst = ((y + self.margin.y) as usize * window_width
+                + (position.x + self.margin.x) as usize)
+                * bpp;
+            let dst = &mut window[dst..dst + size.x as usize * bpp];
+            dst.copy_from_slice(src);
+            for pixel in dst.chunks_exact_mut(bpp) {
+                pixel[alpha] = 0xff;
+            }
+        }
+    }
+}
+
+impl Backend for ShadowBackend {
+    fn flush_window(&mut self) -> Result<()> {
+        self.flush_region(Vector2 { x: 0, y: 0 }, self.size)
+    }
+
+    fn flush_region(&mut self, position: Vector2<u32>, size: Vector2<u32>) -> Result<()> {
+        self.copy_to_window(position, size);
+        if !self.shadow_presented {
+            self.shadow_presented = true;
+            return self.inner.flush_window();
+        }
+        self.inner.flush_region(position + self.margin, size)
+    }
+
+    fn events(&mut self) -> Result<Vec<Event>> {
+        let (margin, size) = (self.margin, self.size);
+        let mut events = self.inner.events()?;
+        for event in events.iter_mut() {
+            match event {
+                Event::Resize { new_size } => *new_size = size,
+                // Pointer over the shadow is reported at the nearest edge of the popup
+                Event::PointerMotion { position, .. } => {
+                    *position = Vector2 {
+                        x: position
+                            .x
+                            .saturating_sub(margin.x)
+                            .min(size.x.saturating_sub(1)),
+                        y: position
+                            .y
+                            .saturating_sub(margin.y)
+                            .min(size.y.saturating_sub(1)),
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(events)
+    }
+
+    #[inline]
+    fn request_vblank(&mut self) -> Result<bool> {
+        self.inner.request_vblank()
+    }
+
+    #[inline]
+    fn vblanks(&mut self) -> Vec<Vblank> {
+        self.inner.vblanks()
+    }
+
+    #[inline]
+    fn copy_to_clipboard(&mut self, offers: Vec<Offer>) -> Result<bool> {
+        self.inner.copy_to_clipboard(offers)
+    }
+
+    #[inline]
+    fn paste_from_clipboard(&mut self, targets: &[&str]) -> Result<Option<(String, Vec<u8>)>> {
+        self.inner.paste_from_clipboard(targets)
+    }
+
+    fn resize(&mut self, size: Vector2<u32>) -> Result<()> {
+        let (before, after) = self.shadow.margins();
+        self.inner.resize(size + before + after)?;
+        self.size = size;
+        self.draw_shadow();
+        Ok(())
+    }
+
+    #[inline]
+    fn size(&self) -> Vector2<u32> {
+        self.size
+    }
+
+    #[inline]
+    fn buf_mut(&mut self) -> &mut [u8] {
+        &mut self.buf
+    }
+
+    #[inline]
+    fn buf(&self) -> &[u8] {
+        &self.buf
+    }
+
+    #[inline]
+    fn pixel_format(&self) -> PixelFormat {
+        self.inner.pixel_format()
+    }
+
+    #[inline]
+    fn bell(&mut self, percent: i8) -> Result<()> {
+        self.inner.bell(percent)
+    }
+
+    #[inline]
+    fn warp_pointer(&mut self, position: Vector2<u32>) -> Result<()> {
+        self.inner.warp_pointer(position + self.margin)
+    }
+
+    #[inline]
+    fn set_pointer_capture(&mut self, captured: bool) -> Result<bool> {
+        self.inner.set_pointer_capture(captured)
+    }
+
+    fn open_popup(
+        &mut self,
+        position: Vector2<i32>,
+        size: Vector2<u32>,
+        shadow: Option<PopupShadow>,
+    ) -> Result<Box<dyn Backend>> {
+        self.inner
+            .open_popup(position + self.margin.as_i32(), size, shadow)
+    }
+}
+
+#[test]
+fn shadow_around_popup() {
+    use crate::{backend::owned_bitmap::OwnedBitmapBackend, Color};
+
+    let shadow = PopupShadow {
+        radius: 4,
+        offset: Vector2 { x: 0, y: 2 },
+        color: Color::from_raw(0x80000000),
+    };
+    let (before, after) = shadow.margins();
+    assert_eq!(
+        (before, after),
+        (Vector2 { x: 4, y: 2 }, Vector2 { x: 4, y: 6 })
+    );
+
+    let size = Vector2 { x: 10, y: 6 };
+    let inner = OwnedBitmapBackend::new(size + before + after);
+    let mut backend = ShadowBackend::new(Box::new(inner), size, shadow);
+    backend.buf_mut().fill(0x40);
+    backend.flush_window().unwrap();
+
+    let window_width = backend.inner.size().x;
+    let alpha = |backend: &ShadowBackend, x: u32, y: u32| {
+        backend.inner.buf()[((y * window_width + x) * BYTES_PER_PIXEL + 3) as usize]
+    };
+    // Popup is opaque whatever alpha it was drawn with
+    assert_eq!(alpha(&backend, 4, 2), 0xff);
+    assert_eq!(alpha(&backend, 13, 7), 0xff);
+    // Shadow fades out towards the edges of the window, strongest below the popup
+    assert_eq!(alpha(&backend, 0, 0), 0);
+    assert!(alpha(&backend, 8, 9) > alpha(&backend, 8, 11));
+    assert!(alpha(&backend, 8, 11) > alpha(&backend, 8, 13));
+    assert!(alpha(&backend, 8, 9) <= 0x80);
+
+    // Flushing contents keeps the shadow
+    backend.buf_mut().fill(0);
+    backend
+        .flush_region(Vector2 { x: 1, y: 1 }, Vector2 { x: 2, y: 2 })
+        .unwrap();
+    assert_eq!(alpha(&backend, 5, 3), 0xff);
+    assert_eq!(
+        backend.inner.buf()[((3 * window_width + 5) * BYTES_PER_PIXEL) as usize],
+        0
+    );
+}
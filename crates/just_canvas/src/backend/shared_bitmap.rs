@@ -45,4 +45,19 @@ impl<'buf> Backend for SharedBitmapBackend<'buf> {
     fn buf(&self) -> &[u8] {
         self.buf
     }
+
+    #[inline]
+    fn bell(&mut self, _percent: i8) -> Result<()> {
+        Ok(())
+    }
+
+    #[inline]
+    fn warp_pointer(&mut self, _position: Vector2<u32>) -> Result<()> {
+        Ok(())
+    }
+
+    #[inline]
+    fn set_pointer_capture(&mut self, _captured: bool) -> Result<bool> {
+        Ok(false)
+    }
 }
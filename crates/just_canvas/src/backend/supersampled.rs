@@ -0,0 +1,242 @@
+use crate::{
+    backend::Backend, frame::Vblank, Event, PixelFormat, PopupShadow, Result, Vector2,
+    BYTES_PER_PIXEL,
+};
+use just_x11_simple::clipboard::Offer;
+
+/// Buffer `factor` times larger than the window of `inner` in each dimension, downscaled with
+/// box filter into `inner` on flush
+pub(crate) struct SupersampledBackend {
+    inner: Box<dyn Backend>,
+    factor: u32,
+    buf: Vec<u8>,
+}
+
+impl SupersampledBackend {
+    pub(crate) fn new(inner: Box<dyn Backend>, factor: u32) -> Self {
+        let mut backend = Self {
+            inner,
+            factor: factor.max(1),
+            buf: Vec::new(),
+        };
+        backend.allocate_buf();
+        backend
+    }
+
+    fn allocate_buf(&mut self) {
+        let size = self.size();
+        self.buf = vec![0u8; size.x as usize * size.y as usize * BYTES_PER_PIXEL as usize];
+    }
+
+    /// Average `factor`x`factor` blocks into window pixels in given window region
+    fn downsample(&mut self, position: Vector2<u32>, size: Vector2<u32>) {
+        let factor = self.factor as usize;
+        let samples = (factor * factor) as u32;
+        let window_width = self.inner.size().x as usize;
+        let buf_width = window_width * factor;
+        let bpp = BYTES_PER_PIXEL as usize;
+        let dst = self.inner.buf_mut();
+
+        for y in position.y as usize..(position.y + size.y) as usize {
+            for x in position.x as usize..(position.x + size.x) as usize {
+                let mut sum = [0u32; BYTES_PER_PIXEL as usize];
+                for sy in y * factor..(y + 1) * factor {
+                    let row = sy * buf_width;
+                    for sx in x * factor..(x + 1) * factor {
+                        let offset = (row + sx) * bpp;
+                        for (channel, sum) in sum.iter_mut().enumerate() {
+                            *sum += self.buf[offset + channel] as u32;
+                        }
+                    }
+                }
+
+                let offset = (y * window_width + x) * bpp;
+                for (channel, sum) in sum.iter().enumerate() {
+                    dst[offset + channel] = ((sum + samples / 2) / samples) as u8;
+                }
+            }
+        }
+    }
+
+    fn to_window(&self, position: Vector2<u32>) -> Vector2<u32> {
+        Vector2 {
+            x: position.x / self.factor,
+            y: position.y / self.factor,
+        }
+    }
+}
+
+impl Backend for SupersampledBackend {
+    fn flush_window(&mut self) -> Result<()> {
+        self.downsample(Vector2 { x: 0, y: 0 }, self.inner.size());
+        self.inner.flush_window()
+    }
+
+    fn flush_region(&mut self, position: Vector2<u32>, size: Vector2<u32>) -> Result<()> {
+        // Include partially covered window pixels
+        let start = self.to_window(position);
+        let end = self.to_window(Vector2 {
+            x: position.x + size.x + self.factor - 1,
+            y: position.y + size.y + self.factor - 1,
+        });
+        let window_size = self.inner.size();
+        let end = Vector2 {
+            x: end.x.min(window_size.x),
+            y: end.y.min(window_size.y),
+        };
+        let size = end - start;
+
+        self.downsample(start, size);
+        self.inner.flush_region(start, size)
+    }
+
+    fn events(&mut self) -> Result<Vec<Event>> {
+        let factor = self.factor;
+        let scale = |v: Vector2<u32>| Vector2 {
+            x: v.x * factor,
+            y: v.y * factor,
+        };
+
+        let mut events = self.inner.events()?;
+        for event in events.iter_mut() {
+            match event {
+                Event::Resize { new_size } => *new_size = scale(*new_size),
+                // Center of the block covered by window pixel
+                Event::PointerMotion { position, .. } => {
+                    *position = scale(*position)
+                        + Vector2 {
+                            x: factor / 2,
+                            y: factor / 2,
+                        }
+                }
+                _ => {}
+            }
+        }
+        Ok(events)
+    }
+
+    #[inline]
+    fn present_in_background(&mut self) -> Result<bool> {
+        self.inner.present_in_background()
+    }
+
+    #[inline]
+    fn request_vblank(&mut self) -> Result<bool> {
+        self.inner.request_vblank()
+    }
+
+    #[inline]
+    fn vblanks(&mut self) -> Vec<Vblank> {
+        self.inner.vblanks()
+    }
+
+    #[inline]
+    fn copy_to_clipboard(&mut self, offers: Vec<Offer>) -> Result<bool> {
+        self.inner.copy_to_clipboard(offers)
+    }
+
+    #[inline]
+    fn paste_from_clipboard(&mut self, targets: &[&str]) -> Result<Option<(String, Vec<u8>)>> {
+        self.inner.paste_from_clipboard(targets)
+    }
+
+    fn resize(&mut self, size: Vector2<u32>) -> Result<()> {
+        self.inner.resize(self.to_window(size))?;
+        self.allocate_buf();
+        Ok(())
+    }
+
+    #[inline]
+    fn size(&self) -> Vector2<u32> {
+        let size = self.inner.size();
+        Vector2 {
+            x: size.x * self.factor,
+            y: size.y * self.factor,
+        }
+    }
+
+    #[inline]
+    fn buf_mut(&mut self) -> &mut [u8] {
+        &mut self.buf
+    }
+
+    #[inline]
+    fn buf(&self) -> &[u8] {
+        &self.buf
+    }
+
+    #[inline]
+    fn pixel_format(&self) -> PixelFormat {
+        // Channels are averaged independently so they are downsampled in place
+        self.inner.pixel_format()
+    }
+
+    #[inline]
+    fn bell(&mut self, percent: i8) -> Result<()> {
+        self.inner.bell(percent)
+    }
+
+    #[inline]
+    fn warp_pointer(&mut self, position: Vector2<u32>) -> Result<()> {
+        let position = self.to_window(position);
+        self.inner.warp_pointer(position)
+    }
+
+    #[inline]
+    fn set_pointer_capture(&mut self, captured: bool) -> Result<bool> {
+        self.inner.set_pointer_capture(captured)
+    }
+
+    fn open_popup(
+        &mut self,
+        position: Vector2<i32>,
+        size: Vector2<u32>,
+        shadow: Option<PopupShadow>,
+    ) -> Result<Box<dyn Backend>> {
+        let factor = self.factor;
+        // Shadow is drawn in window pixels
+        let shadow = shadow.map(|shadow| PopupShadow {
+            radius: shadow.radius / factor,
+            offset: Vector2 {
+                x: shadow.offset.x / factor as i32,
+                y: shadow.offset.y / factor as i32,
+            },
+            ..shadow
+        });
+        let inner = self.inner.open_popup(
+            Vector2 {
+                x: position.x / factor as i32,
+                y: position.y / factor as i32,
+            },
+            self.to_window(size),
+            shadow,
+        )?;
+        Ok(Box::new(Self::new(inner, factor)))
+    }
+}
+
+#[test]
+fn supersampled_box_filter() {
+    use crate::backend::owned_bitmap::OwnedBitmapBackend;
+
+    let inner = OwnedBitmapBackend::new(Vector2 { x: 2, y: 1 });
+    let mut backend = SupersampledBackend::new(Box::new(inner), 2);
+    assert_eq!((backend.size().x, backend.size().y), (4, 2));
+
+    // Left block is half white, right block has single white pixel
+    for (x, y) in [(0, 0), (0, 1), (2, 0)] {
+        let offset = (y * 4 + x) * BYTES_PER_PIXEL as usize;
+        backend.buf_mut()[offset..offset + 4].fill(0xff);
+    }
+    backend.flush_window().unwrap();
+    assert_eq!(
+        backend.inner.buf(),
+        &[0x80, 0x80, 0x80, 0x80, 0x40, 0x40, 0x40, 0x40]
+    );
+
+    backend.buf_mut().fill(0);
+    backend
+        .flush_region(Vector2 { x: 3, y: 1 }, Vector2 { x: 1, y: 1 })
+        .unwrap();
+    assert_eq!(backend.inner.buf(), &[0x80, 0x80, 0x80, 0x80, 0, 0, 0, 0]);
+}
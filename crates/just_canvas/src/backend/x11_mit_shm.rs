@@ -1,19 +1,137 @@
 use crate::{
-    backend::Backend, keyboard::KeyboardButton, Event, PointerButton, Result, Vector2,
-    BYTES_PER_PIXEL,
+    backend::{present_thread::PresentThread, shadow::ShadowBackend, Backend},
+    frame::Vblank,
+    keyboard::KeyboardButton,
+    CanvasError, DamageRect, Event, InputDevice, PixelFormat, PointerButton, PopupShadow, Result,
+    Vector2, Visibility, BYTES_PER_PIXEL,
 };
 use core::cmp;
 use just_shared_memory::SharedMemory;
 use just_x11::{
     atoms::AtomId,
-    events::{self, EventType, KeyPressRelease},
-    extensions::mit_shm::{self, ShmSegId},
+    connection::DisplayVar,
+    display,
+    events::{self, EventType, VisibilityNotifyState},
+    extensions::{
+        mit_shm::{self, ShmSegId},
+        present::{self, CompleteKind, CompleteNotify},
+        shape::{self, ShapeKind, ShapeOperation},
+        xinput::{self, replies::ValuatorClass, DeviceEvent, DeviceEventType, DeviceId},
+        xkb,
+    },
     keysym::KeySym,
     replies::String8,
-    requests::{GContextSettings, KeyModifier, PutImageFormat, WindowCreationAttributes},
-    Drawable, GContextId, WindowClass, WindowId, WindowVisual, XDisplay,
+    requests::{
+        KeyCode, KeyModifier, Ordering, PointerEventMask, PutImageFormat, WindowCreationAttributes,
+    },
+    ColormapId, Drawable, GContextId, ImageByteOrder, OrNone, Rectangle, VisualClass, VisualId,
+    WindowClass, WindowId, WindowVisual, XDisplay,
 };
-use just_x11_simple::keys::{KeySymColumn, KeySymbols};
+use just_x11_simple::{
+    clipboard::{self, Offer},
+    keys::{KeySymColumn, KeySymbols},
+    monitors::Monitors,
+    X11Connection,
+};
+use std::{
+    cell::{RefCell, RefMut},
+    collections::HashMap,
+    mem,
+    rc::Rc,
+    sync::mpsc,
+    thread,
+};
+
+/// Labels of valuators read as pen axes, set by input drivers
+const PEN_VALUATOR_LABELS: [&[u8]; 3] = [b"Abs Pressure", b"Abs Tilt X", b"Abs Tilt Y"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct PenAxes {
+    pressure: f32,
+    tilt: (f32, f32),
+}
+
+impl PenAxes {
+    /// Axes of devices that are not pens
+    const NONE: Self = Self {
+        pressure: 1.0,
+        tilt: (0.0, 0.0),
+    };
+}
+
+/// Pen axes of a physical device and their last values, as not every event carries all of them
+#[derive(Debug, Clone)]
+struct PenDevice {
+    pressure: Option<ValuatorClass>,
+    tilt_x: Option<ValuatorClass>,
+    tilt_y: Option<ValuatorClass>,
+    axes: PenAxes,
+}
+
+impl PenDevice {
+    fn update(&mut self, valuators: &[(u16, f64)]) -> PenAxes {
+        let find = |class: &Option<ValuatorClass>| {
+            let class = class.as_ref()?;
+            let (_, value) = valuators
+                .iter()
+                .find(|(number, _)| *number == class.number)?;
+            class.normalize(*value).map(|value| value as f32)
+        };
+        if let Some(pressure) = find(&self.pressure) {
+            self.axes.pressure = pressure;
+        }
+        if let Some(tilt_x) = find(&self.tilt_x) {
+            self.axes.tilt.0 = tilt_x * 2.0 - 1.0;
+        }
+        if let Some(tilt_y) = find(&self.tilt_y) {
+            self.axes.tilt.1 = tilt_y * 2.0 - 1.0;
+        }
+        self.axes
+    }
+}
+
+/// Connection of a window and its popups. Events are read by whichever backend asks first and
+/// queued for the others, each backend keeps only the events of its own window.
+struct SharedConnection {
+    conn: X11Connection,
+    /// Display of the connection, the present thread opens its own connection to it
+    display: DisplayVar,
+    /// Events read from the connection and not yet taken by the backend of each window
+    queues: HashMap<WindowId, Vec<just_x11::events::SomeEvent>>,
+    /// Queried when the first popup is placed
+    monitors: Option<Monitors>,
+}
+
+impl SharedConnection {
+    /// Read available events and queue them for every window
+    fn read_events(&mut self) -> Result<()> {
+        let events = self.conn.display_mut().events()?.collect::<Vec<_>>();
+        for queue in self.queues.values_mut() {
+            queue.extend(events.iter().cloned());
+        }
+        Ok(())
+    }
+
+    /// Geometry of monitors relative to the root window, queried again on each call as popups
+    /// are rare and monitors may change
+    fn monitors(&mut self) -> Result<Vec<Rectangle>> {
+        match &mut self.monitors {
+            Some(monitors) => {
+                monitors.refresh(&mut self.conn)?;
+            }
+            None => {
+                let screen = self.conn.display().default_screen_index();
+                self.monitors = Some(Monitors::new(&mut self.conn, screen)?);
+            }
+        }
+        Ok(self
+            .monitors
+            .iter()
+            .flat_map(|monitors| monitors.monitors())
+            .map(|monitor| monitor.geometry)
+            .collect())
+    }
+}
 
 // TODO: This should use double buffering
 
@@ -43,73 +161,156 @@ impl MitShmCanvas {
 }
 
 pub(crate) struct X11MitShmBackend {
-    display: XDisplay,
+    /// Shared with popups of the window, or with the window of this popup
+    connection: Rc<RefCell<SharedConnection>>,
+    /// Popups don't report the keyboard bell, their window does
+    is_popup: bool,
     mit_shm_major_opcode: u8,
     canvas: MitShmCanvas,
     window: WindowId,
+    /// Root depth, or 32 for ARGB popups
+    depth: u8,
     gc: GContextId,
+    /// Colormap and GC created for ARGB popups, freed with the window
+    argb_resources: Option<(ColormapId, GContextId)>,
     wm_delete_window: AtomId,
     key_symbols: KeySymbols,
+    xkb_first_event: Option<u8>,
+    /// Major opcode of XInput if device events of master devices were selected
+    xinput_major_opcode: Option<u8>,
+    /// Atoms of [`PEN_VALUATOR_LABELS`], `None` atom if no driver created them
+    pen_labels: Option<[AtomId; 3]>,
+    /// Physical devices by id, queried on their first event
+    pen_devices: HashMap<u16, PenDevice>,
+    /// Last sent axes of each master device, and last sent axes of any device as they also
+    /// change the shared pointer
+    sent_axes: HashMap<InputDevice, PenAxes>,
+    last_sent_axes: PenAxes,
+    pixel_format: PixelFormat,
+    /// Major opcode of Present if its completion events were selected
+    present_major_opcode: Option<u8>,
+    /// Serial of the `NotifyMSC` request waiting for its vertical blank
+    vblank_pending: Option<u32>,
+    vblank_serial: u32,
+    vblanks: Vec<Vblank>,
+    /// Presents frames instead of this connection, [`Self::canvas`] keeps only the size then
+    present_thread: Option<PresentThread>,
 }
 
 impl X11MitShmBackend {
     pub(crate) fn new(title: &str) -> Result<Self> {
-        use just_x11::requests;
-
-        let mut display = XDisplay::open()?;
-        let mit_shm = {
-            let pending_reply = display.send_request(&requests::QueryExtension {
-                name: mit_shm::EXTENSION_NAME.to_vec(),
-            })?;
-            let reply = display.await_pending_reply(pending_reply)?;
-            reply.unwrap()
+        let display = display::resolve(None)?;
+        let connection = SharedConnection {
+            conn: X11Connection::new(display::connect(display.clone())?),
+            display,
+            queues: HashMap::new(),
+            monitors: None,
         };
+        Self::open(title, None, None, Rc::new(RefCell::new(connection)))
+    }
+
+    /// Override-redirect window at `position` relative to the root, not managed by the window
+    /// manager, on the connection of this window. With `argb_visual` from [`Self::argb_visual`]
+    /// the window has alpha channel which compositors blend with windows below.
+    fn new_popup(
+        &self,
+        position: Vector2<i32>,
+        size: Vector2<u32>,
+        argb_visual: Option<VisualId>,
+    ) -> Result<Self> {
+        Self::open(
+            "",
+            Some((position, size)),
+            argb_visual,
+            self.connection.clone(),
+        )
+    }
 
+    fn open(
+        title: &str,
+        popup: Option<(Vector2<i32>, Vector2<u32>)>,
+        argb_visual: Option<VisualId>,
+        connection: Rc<RefCell<SharedConnection>>,
+    ) -> Result<Self> {
+        use just_x11::requests;
+
+        let mut shared = connection.borrow_mut();
+        let display = shared.conn.display_mut();
         // TODO: Graceful error
-        assert!(mit_shm.present);
+        let mit_shm = mit_shm::negotiate(display)?.expect("MIT-SHM extension missing");
 
         let mit_shm_major_opcode = mit_shm.major_opcode;
 
-        let canvas_size = Vector2 { x: 800, y: 600 };
-        let canvas = Self::attach_new_shm_seg(&mut display, mit_shm_major_opcode, canvas_size)?;
+        let (window_position, canvas_size) =
+            popup.unwrap_or((Vector2 { x: 0, y: 0 }, Vector2 { x: 800, y: 600 }));
+        let canvas = Self::attach_new_shm_seg(display, mit_shm_major_opcode, canvas_size)?;
 
         // create window
 
+        let depth = match argb_visual {
+            Some(_) => 32,
+            None => display.default_screen().root_depth,
+        };
+        let mut argb_colormap = None;
         let window = {
-            let window_id = WindowId::from(display.id_allocator().allocate_id());
-            let window_attributes = WindowCreationAttributes::new().set_event_mask(
-                EventType::KEY_PRESS
-                    | EventType::KEY_RELEASE
-                    | EventType::BUTTON_PRESS
-                    | EventType::BUTTON_RELEASE
-                    | EventType::POINTER_MOTION
-                    | EventType::STRUCTURE_NOTIFY,
-            );
+            let window_id = WindowId::from_resource(display.id_allocator().allocate_id());
+            let event_mask = EventType::KEY_PRESS
+                | EventType::KEY_RELEASE
+                | EventType::BUTTON_PRESS
+                | EventType::BUTTON_RELEASE
+                | EventType::POINTER_MOTION
+                | EventType::STRUCTURE_NOTIFY
+                | EventType::VISIBILITY_CHANGE;
+            // Whole window is redrawn from the shared buffer, so no background
+            let mut window_attributes = match popup {
+                Some(_) => {
+                    WindowCreationAttributes::override_redirect_popup().set_event_mask(event_mask)
+                }
+                None => WindowCreationAttributes::for_top_level(event_mask, None),
+            };
+            // Depth differs from the root, so colormap and border can't be copied from it
+            if let Some(visual) = argb_visual {
+                let colormap = ColormapId::from_resource(display.id_allocator().allocate_id());
+                display.send_request(&requests::CreateColormap {
+                    alloc: requests::CreateColormapAlloc::None,
+                    mid: colormap,
+                    window: display.default_screen().root,
+                    visual,
+                })?;
+                window_attributes = window_attributes
+                    .set_colormap(colormap.into())
+                    .set_border_pixel(0);
+                argb_colormap = Some(colormap);
+            }
             let create_window = requests::CreateWindow {
-                depth: 24,
+                depth,
                 wid: window_id,
-                parent: display.screens()[0].root,
-                x: 0,
-                y: 0,
-                width: 600,
-                height: 800,
+                parent: display.default_screen().root,
+                x: window_position.x as i16,
+                y: window_position.y as i16,
+                width: canvas_size.x as u16,
+                height: canvas_size.y as u16,
                 border_width: 0,
                 window_class: WindowClass::CopyFromParent,
-                visual: WindowVisual::CopyFromParent,
+                visual: argb_visual.map_or(WindowVisual::CopyFromParent, WindowVisual::Id),
                 attributes: window_attributes,
             };
             display.send_request(&create_window)?;
             window_id
         };
 
-        let gc = {
-            let gc_id = GContextId::from(display.id_allocator().allocate_id());
-            display.send_request(&requests::CreateGC {
-                cid: gc_id,
-                drawable: Drawable::Window(window),
-                values: GContextSettings::new(),
-            })?;
-            gc_id
+        // Default GC is for the root depth
+        let gc = match argb_visual {
+            Some(_) => {
+                let gc = GContextId::from_resource(display.id_allocator().allocate_id());
+                display.send_request(&requests::CreateGC {
+                    cid: gc,
+                    drawable: Drawable::Window(window),
+                    values: requests::GContextSettings::new(),
+                })?;
+                gc
+            }
+            None => display.default_gc(display.default_screen_index())?,
         };
 
         display.send_request(&requests::MapWindow { window })?;
@@ -166,35 +367,248 @@ impl X11MitShmBackend {
 
         display.flush()?;
 
-        let key_symbols = KeySymbols::new(&mut display)?;
+        let key_symbols = KeySymbols::new(display)?;
 
+        let xkb_first_event = Self::select_bell_events(display)?;
+        let xinput_major_opcode = Self::select_device_events(display, window)?;
+        let present_major_opcode = Self::select_present_events(display, window)?;
+
+        // TODO: Visuals with other channel layouts, e.g. 16 bit ones
+        let screen = display.default_screen();
+        let pixel_format = match argb_visual {
+            Some(id) => screen
+                .allowed_depths
+                .iter()
+                .flat_map(|depth| depth.visuals.iter())
+                .find(|visual| visual.id == id),
+            None => screen.root_visual_type(),
+        }
+        .and_then(|visual| {
+            PixelFormat::from_visual_masks(visual.red_mask, visual.green_mask, visual.blue_mask)
+        })
+        .unwrap_or(PixelFormat::Bgra8888);
+
+        shared.queues.insert(window, Vec::new());
+        drop(shared);
         Ok(Self {
-            display,
+            connection,
+            is_popup: popup.is_some(),
             mit_shm_major_opcode,
             canvas,
             window,
+            depth,
             gc,
+            argb_resources: argb_colormap.map(|colormap| (colormap, gc)),
             wm_delete_window,
             key_symbols,
+            xkb_first_event,
+            xinput_major_opcode,
+            pen_labels: None,
+            pen_devices: HashMap::new(),
+            sent_axes: HashMap::new(),
+            last_sent_axes: PenAxes::NONE,
+            pixel_format,
+            present_major_opcode,
+            vblank_pending: None,
+            vblank_serial: 0,
+            vblanks: Vec::new(),
+            present_thread: None,
+        })
+    }
+
+    fn display(&self) -> RefMut<'_, XDisplay> {
+        RefMut::map(self.connection.borrow_mut(), |connection| {
+            connection.conn.display_mut()
         })
     }
+
+    /// Vertical blanks are reported by Present as completion of `NotifyMSC` requests. Returns
+    /// major opcode of the extension if completion events were selected.
+    fn select_present_events(display: &mut XDisplay, window: WindowId) -> Result<Option<u8>> {
+        let Some(present) = present::negotiate(display)? else {
+            return Ok(None);
+        };
+
+        let event_id = display.id_allocator().allocate_id();
+        display.send_extension_request(
+            &present::requests::SelectInput {
+                event_id: event_id.value(),
+                window,
+                event_mask: present::EventMask::COMPLETE_NOTIFY,
+            },
+            present.major_opcode,
+        )?;
+        display.flush()?;
+
+        Ok(Some(present.major_opcode))
+    }
+
+    /// Core protocol has no bell event so XKB is used to get notified. Returns extension's first
+    /// event code if bell events were selected.
+    fn select_bell_events(display: &mut XDisplay) -> Result<Option<u8>> {
+        let Some(xkb) = xkb::negotiate(display)? else {
+            return Ok(None);
+        };
+
+        display.send_extension_request(
+            &xkb::requests::SelectEvents {
+                device_spec: xkb::DeviceSpec::USE_CORE_KBD,
+                select: xkb::EventMask::BELL_NOTIFY,
+                clear: xkb::EventMask::EMPTY_MASK,
+            },
+            xkb.major_opcode,
+        )?;
+        display.flush()?;
+
+        Ok(Some(xkb.first_event))
+    }
+
+    /// Core events don't tell which master device sent them, so with XInput 2 key, button and
+    /// motion events are selected for all master devices instead. They replace the core events
+    /// of the window, except during core pointer grabs. Returns major opcode of the extension if
+    /// events were selected.
+    fn select_device_events(display: &mut XDisplay, window: WindowId) -> Result<Option<u8>> {
+        let Some(xinput) = xinput::negotiate(display)? else {
+            return Ok(None);
+        };
+
+        display.send_extension_request(
+            &xinput::requests::XISelectEvents {
+                window,
+                masks: vec![(
+                    xinput::DeviceId::ALL_MASTER_DEVICES,
+                    xinput::EventMask::KEY_PRESS
+                        | xinput::EventMask::KEY_RELEASE
+                        | xinput::EventMask::BUTTON_PRESS
+                        | xinput::EventMask::BUTTON_RELEASE
+                        | xinput::EventMask::MOTION,
+                )],
+            },
+            xinput.major_opcode,
+        )?;
+        display.flush()?;
+
+        Ok(Some(xinput.major_opcode))
+    }
+
+    /// Find pen valuators of physical device `source`
+    fn query_pen_device(&mut self, source: DeviceId) -> Result<PenDevice> {
+        use just_x11::requests;
+
+        let labels = match self.pen_labels {
+            Some(labels) => labels,
+            None => {
+                let pending = PEN_VALUATOR_LABELS
+                    .map(|label| {
+                        self.display().send_request(&requests::InternAtom {
+                            only_if_exists: true,
+                            name: String8::from_bytes(label.to_vec()).unwrap(),
+                        })
+                    })
+                    .into_iter()
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                self.display().flush()?;
+                let mut labels = [AtomId::ANY_PROPERTY_TYPE; 3];
+                for (label, pending) in labels.iter_mut().zip(pending) {
+                    *label = self.display().await_pending_reply(pending)?.unwrap().atom;
+                }
+                self.pen_labels = Some(labels);
+                labels
+            }
+        };
+
+        let mut device = PenDevice {
+            pressure: None,
+            tilt_x: None,
+            tilt_y: None,
+            axes: PenAxes::NONE,
+        };
+        let pending = self.display().send_extension_request(
+            &xinput::requests::XIQueryDevice { device_id: source },
+            self.xinput_major_opcode
+                .expect("device events are selected"),
+        )?;
+        self.display().flush()?;
+        // Device could be unplugged since the event was sent
+        let Ok(reply) = self.display().await_pending_reply(pending)? else {
+            return Ok(device);
+        };
+
+        let find = |label: AtomId| {
+            reply
+                .devices
+                .iter()
+                .flat_map(|info| &info.valuators)
+                .find(|valuator| u32::from(label) != 0 && valuator.label == label)
+                .cloned()
+        };
+        device.pressure = find(labels[0]);
+        device.tilt_x = find(labels[1]);
+        device.tilt_y = find(labels[2]);
+        Ok(device)
+    }
+
+    /// Axes event if pen axes of `event` changed for its master device or the shared pointer
+    fn pen_axes_event(&mut self, event: &DeviceEvent) -> Result<Option<Event>> {
+        let source = event.source_id.raw();
+        if !self.pen_devices.contains_key(&source) {
+            let device = self.query_pen_device(event.source_id)?;
+            self.pen_devices.insert(source, device);
+        }
+        let axes = self
+            .pen_devices
+            .get_mut(&source)
+            .unwrap()
+            .update(&event.valuators);
+
+        let device = InputDevice {
+            id: event.device_id.raw(),
+        };
+        let sent = self.sent_axes.insert(device, axes).unwrap_or(PenAxes::NONE);
+        let last_sent = std::mem::replace(&mut self.last_sent_axes, axes);
+        if sent == axes && last_sent == axes {
+            return Ok(None);
+        }
+
+        Ok(Some(Event::PointerAxes {
+            device,
+            pressure: axes.pressure,
+            tilt: Vector2 {
+                x: axes.tilt.0,
+                y: axes.tilt.1,
+            },
+        }))
+    }
 }
 
 impl Backend for X11MitShmBackend {
     fn flush_window(&mut self) -> Result<()> {
-        self.display.send_extension_request(
+        let size = self.canvas.size;
+        self.flush_region(Vector2 { x: 0, y: 0 }, size)
+    }
+
+    fn flush_region(&mut self, position: Vector2<u32>, size: Vector2<u32>) -> Result<()> {
+        if let Some(present_thread) = &mut self.present_thread {
+            return present_thread.present(position, size);
+        }
+
+        let image_format = self.display().image_format();
+        let bits_per_pixel = (BYTES_PER_PIXEL * 8) as u8;
+        image_format.convert_zpixmap(self.canvas.mem_mut(), bits_per_pixel);
+
+        self.display().send_extension_request(
             &mit_shm::requests::PutImage {
                 drawable: Drawable::Window(self.window),
                 gc: self.gc,
                 total_width: self.canvas.size.x as u16,
                 total_height: self.canvas.size.y as u16,
-                src_x: 0,
-                src_y: 0,
-                src_width: self.canvas.size.x as u16,
-                src_height: self.canvas.size.y as u16,
-                dst_x: 0,
-                dst_y: 0,
-                depth: 24,
+                src_x: position.x as u16,
+                src_y: position.y as u16,
+                src_width: size.x as u16,
+                src_height: size.y as u16,
+                dst_x: position.x as i16,
+                dst_y: position.y as i16,
+                depth: self.depth,
                 format: PutImageFormat::ZPixmap,
                 send_event: false, // should be true for double buffering tracking?
                 bpad: 0,
@@ -203,11 +617,81 @@ impl Backend for X11MitShmBackend {
             },
             self.mit_shm_major_opcode,
         )?;
-        self.display.flush()?;
+
+        if image_format.image_byte_order != ImageByteOrder::LsbFirst {
+            // Server reads shared memory asynchronously so wait for a round trip before converting
+            // the buffer back
+            let pending_reply = self
+                .display()
+                .send_request(&just_x11::requests::GetInputFocus)?;
+            self.display().flush()?;
+            let _ = self.display().await_pending_reply(pending_reply)?;
+            image_format.convert_zpixmap(self.canvas.mem_mut(), bits_per_pixel);
+        }
+        self.display().flush()?;
 
         Ok(())
     }
 
+    fn copy_presented(
+        &mut self,
+        source: Vector2<u32>,
+        destination: Vector2<u32>,
+        size: Vector2<u32>,
+    ) -> Result<Option<Vec<DamageRect>>> {
+        if self.present_thread.is_some() {
+            // Window may not show the last flushed frame yet
+            return Ok(None);
+        }
+
+        let exposed = self.display().copy_area(&just_x11::requests::CopyArea {
+            src_drawable: Drawable::Window(self.window),
+            dst_drawable: Drawable::Window(self.window),
+            gc: self.gc,
+            src_x: source.x as i16,
+            src_y: source.y as i16,
+            dst_x: destination.x as i16,
+            dst_y: destination.y as i16,
+            width: size.x as u16,
+            height: size.y as u16,
+        })?;
+
+        Ok(Some(
+            exposed
+                .into_iter()
+                .map(|rectangle| {
+                    DamageRect::new(
+                        Vector2 {
+                            x: rectangle.x as i32,
+                            y: rectangle.y as i32,
+                        },
+                        Vector2 {
+                            x: rectangle.width as u32,
+                            y: rectangle.height as u32,
+                        },
+                    )
+                })
+                .collect(),
+        ))
+    }
+
+    fn present_in_background(&mut self) -> Result<bool> {
+        // Present thread draws with the default GC of the root depth
+        if self.depth != self.display().default_screen().root_depth {
+            return Ok(false);
+        }
+        if self.present_thread.is_none() {
+            let display = self.connection.borrow().display.clone();
+            self.present_thread = Some(PresentThread::spawn(
+                display,
+                self.window,
+                self.canvas.size,
+                self.canvas.mem(),
+            )?);
+        }
+        Ok(true)
+    }
+
     fn events(&mut self) -> Result<Vec<Event>> {
         use just_x11::events::SomeEvent;
 
@@ -243,8 +727,17 @@ impl Backend for X11MitShmBackend {
 
         let mut events = Vec::new();
 
-        // TODO: Keyboard events
-        for event in self.display.events()? {
+        // Taken out so pen devices can be queried while handling them
+        let x_events = {
+            let mut connection = self.connection.borrow_mut();
+            connection.read_events()?;
+            connection
+                .queues
+                .get_mut(&self.window)
+                .map(mem::take)
+                .unwrap_or_default()
+        };
+        for event in x_events {
             match event {
                 SomeEvent::ConfigureNotify(event) => {
                     if event.event == self.window {
@@ -260,7 +753,10 @@ impl Backend for X11MitShmBackend {
                     if event.event == self.window {
                         if let Ok(button) = events::PointerButton::try_from(event.detail.raw()) {
                             if let Some(button) = PointerButton::from_x11(button) {
-                                events.push(Event::PointerButtonPress { button });
+                                events.push(Event::PointerButtonPress {
+                                    device: InputDevice::CORE,
+                                    button,
+                                });
                             }
                         }
                     }
@@ -269,7 +765,10 @@ impl Backend for X11MitShmBackend {
                     if event.event == self.window {
                         if let Ok(button) = events::PointerButton::try_from(event.detail.raw()) {
                             if let Some(button) = PointerButton::from_x11(button) {
-                                events.push(Event::PointerButtonRelease { button });
+                                events.push(Event::PointerButtonRelease {
+                                    device: InputDevice::CORE,
+                                    button,
+                                });
                             }
                         }
                     }
@@ -277,14 +776,35 @@ impl Backend for X11MitShmBackend {
                 SomeEvent::MotionNotify(event) => {
                     if event.event == self.window {
                         events.push(Event::PointerMotion {
+                            device: InputDevice::CORE,
                             position: Vector2 {
                                 x: x_to_u32!(event.event_x),
                                 y: y_to_u32!(event.event_y),
                             },
+                            time: event.time,
                         });
                     }
                 }
-                SomeEvent::ClientMessage(event) => {
+                SomeEvent::VisibilityNotify(event) if event.window == self.window => {
+                    let visibility = match event.state {
+                        VisibilityNotifyState::Unobscured => Visibility::Unobscured,
+                        VisibilityNotifyState::PartiallyObscured => Visibility::PartiallyObscured,
+                        VisibilityNotifyState::FullyObscured => Visibility::FullyObscured,
+                    };
+                    events.push(Event::VisibilityChange { visibility });
+                }
+                // Actual state will be reported by following VisibilityNotify
+                SomeEvent::MapNotify(event) if event.window == self.window => {
+                    events.push(Event::VisibilityChange {
+                        visibility: Visibility::Unobscured,
+                    });
+                }
+                SomeEvent::UnmapNotify(event) if event.window == self.window => {
+                    events.push(Event::VisibilityChange {
+                        visibility: Visibility::Unmapped,
+                    });
+                }
+                SomeEvent::ClientMessage(event) if event.window == self.window => {
                     let val = u32::from_le_bytes([
                         event.data[0],
                         event.data[1],
@@ -295,18 +815,76 @@ impl Backend for X11MitShmBackend {
                         events.push(Event::Shutdown);
                     }
                 }
-                SomeEvent::KeyPress(event) => {
-                    if let Ok(button) =
-                        KeyboardButton::try_from(get_key_sym(event, &self.key_symbols))
+                SomeEvent::KeyPress(event) if event.event == self.window => {
+                    let key_sym = get_key_sym(event.detail, event.state, &self.key_symbols);
+                    if let Ok(button) = KeyboardButton::try_from(key_sym) {
+                        events.push(Event::KeyboardButtonPress {
+                            device: InputDevice::CORE,
+                            key_sym,
+                            button,
+                        })
+                    }
+                }
+                SomeEvent::KeyRelease(event) if event.event == self.window => {
+                    let key_sym = get_key_sym(event.detail, event.state, &self.key_symbols);
+                    if let Ok(button) = KeyboardButton::try_from(key_sym) {
+                        events.push(Event::KeyboardButtonRelease {
+                            device: InputDevice::CORE,
+                            key_sym,
+                            button,
+                        })
+                    }
+                }
+                SomeEvent::GenericEvent(event)
+                    if Some(event.extension) == self.present_major_opcode =>
+                {
+                    let Some(complete) = CompleteNotify::from_generic_event(
+                        &event,
+                        self.present_major_opcode.unwrap(),
+                    ) else {
+                        continue;
+                    };
+                    if complete.kind == CompleteKind::NotifyMsc
+                        && complete.window == self.window
+                        && Some(complete.serial) == self.vblank_pending
                     {
-                        events.push(Event::KeyboardButtonPress { button })
+                        self.vblank_pending = None;
+                        self.vblanks.push(Vblank {
+                            ust: complete.ust,
+                            msc: complete.msc,
+                        });
                     }
                 }
-                SomeEvent::KeyRelease(event) => {
-                    if let Ok(button) =
-                        KeyboardButton::try_from(get_key_sym(event, &self.key_symbols))
+                SomeEvent::GenericEvent(event) => {
+                    let Some(event) = self.xinput_major_opcode.and_then(|major_opcode| {
+                        DeviceEvent::from_generic_event(&event, major_opcode)
+                    }) else {
+                        continue;
+                    };
+                    if event.event == self.window
+                        && matches!(
+                            event.event_type,
+                            DeviceEventType::ButtonPress
+                                | DeviceEventType::ButtonRelease
+                                | DeviceEventType::Motion
+                        )
+                    {
+                        events.extend(self.pen_axes_event(&event)?);
+                    }
+                    if let Some(event) =
+                        device_event(event, self.window, self.canvas.size, &self.key_symbols)
                     {
-                        events.push(Event::KeyboardButtonRelease { button })
+                        events.push(event);
+                    }
+                }
+                SomeEvent::UnknownEvent(event) if !self.is_popup => {
+                    let bell = self.xkb_first_event.and_then(|first_event| {
+                        xkb::BellNotify::from_unknown_event(&event, first_event)
+                    });
+                    if let Some(bell) = bell {
+                        events.push(Event::Bell {
+                            percent: bell.percent,
+                        });
                     }
                 }
                 _event => {}
@@ -316,7 +894,85 @@ impl Backend for X11MitShmBackend {
         Ok(events)
     }
 
+    fn request_vblank(&mut self) -> Result<bool> {
+        let Some(major_opcode) = self.present_major_opcode else {
+            return Ok(false);
+        };
+        if self.vblank_pending.is_some() {
+            return Ok(true);
+        }
+
+        self.vblank_serial = self.vblank_serial.wrapping_add(1);
+        // Target in the past means the next MSC matching divisor and remainder
+        self.display().send_extension_request(
+            &present::requests::NotifyMsc {
+                window: self.window,
+                serial: self.vblank_serial,
+                target_msc: 0,
+                divisor: 1,
+                remainder: 0,
+            },
+            major_opcode,
+        )?;
+        self.display().flush()?;
+        self.vblank_pending = Some(self.vblank_serial);
+        Ok(true)
+    }
+
+    fn vblanks(&mut self) -> Vec<Vblank> {
+        mem::take(&mut self.vblanks)
+    }
+
+    fn copy_to_clipboard(&mut self, offers: Vec<Offer>) -> Result<bool> {
+        // Owner answers requests until other client takes the clipboard, so it runs on its own
+        // connection in a thread that exits then. Connection can't be moved to the thread, so
+        // errors of opening it are sent back.
+        let display = self.connection.borrow().display.clone();
+        let (opened_sender, opened) = mpsc::channel();
+        thread::Builder::new()
+            .name("just_canvas clipboard".to_string())
+            .spawn(move || {
+                let mut conn = match display::connect(display) {
+                    Ok(display) => {
+                        let _ = opened_sender.send(Ok(()));
+                        X11Connection::new(display)
+                    }
+                    Err(err) => {
+                        let _ = opened_sender.send(Err(err));
+                        return;
+                    }
+                };
+                if let Err(err) = clipboard::serve(&mut conn, offers) {
+                    eprintln!("just_canvas: Clipboard owner failed: {:?}", err);
+                }
+            })
+            .map_err(|err| CanvasError::X11ProtocolError(err.into()))?;
+        if let Ok(Err(err)) = opened.recv() {
+            return Err(err.into());
+        }
+        Ok(true)
+    }
+
+    fn paste_from_clipboard(&mut self, targets: &[&str]) -> Result<Option<(String, Vec<u8>)>> {
+        // Requester drops events of other windows, so it can't share the connection of the window
+        let display = self.connection.borrow().display.clone();
+        let mut conn = X11Connection::new(display::connect(display)?);
+        let offered = clipboard::targets(&mut conn)?;
+        let Some(target) = targets
+            .iter()
+            .find(|target| offered.iter().any(|offered| offered == *target))
+        else {
+            return Ok(None);
+        };
+        Ok(clipboard::request(&mut conn, target)?.map(|data| (target.to_string(), data)))
+    }
+
     fn resize(&mut self, new_size: Vector2<u32>) -> Result<()> {
+        if let Some(present_thread) = &mut self.present_thread {
+            self.canvas.size = new_size;
+            return present_thread.resize(new_size);
+        }
+
         let old_buf = self.canvas.mem_mut().to_vec();
         let old_size = self.canvas.size;
 
@@ -324,7 +980,7 @@ impl Backend for X11MitShmBackend {
             self.canvas.mem_mut().fill(0);
             self.canvas.size = new_size;
         } else {
-            self.display.send_extension_request(
+            self.display().send_extension_request(
                 &mit_shm::requests::Detach {
                     shmseg: self.canvas.shmseg,
                 },
@@ -332,8 +988,8 @@ impl Backend for X11MitShmBackend {
             )?;
 
             let new_canvas =
-                Self::attach_new_shm_seg(&mut self.display, self.mit_shm_major_opcode, new_size)?;
-            self.display.flush()?;
+                Self::attach_new_shm_seg(&mut self.display(), self.mit_shm_major_opcode, new_size)?;
+            self.display().flush()?;
             let old_canvas = core::mem::replace(&mut self.canvas, new_canvas);
             unsafe { old_canvas.mem.free() }
         }
@@ -360,22 +1016,245 @@ impl Backend for X11MitShmBackend {
 
     #[inline]
     fn buf_mut(&mut self) -> &mut [u8] {
-        self.canvas.mem_mut()
+        match &mut self.present_thread {
+            Some(present_thread) => present_thread.buf_mut(),
+            None => self.canvas.mem_mut(),
+        }
     }
 
     #[inline]
     fn buf(&self) -> &[u8] {
-        self.canvas.mem()
+        match &self.present_thread {
+            Some(present_thread) => present_thread.buf(),
+            None => self.canvas.mem(),
+        }
+    }
+
+    #[inline]
+    fn pixel_format(&self) -> PixelFormat {
+        self.pixel_format
+    }
+
+    fn bell(&mut self, percent: i8) -> Result<()> {
+        self.display()
+            .send_request(&just_x11::requests::Bell { percent })?;
+        self.display().flush()?;
+        Ok(())
+    }
+
+    fn warp_pointer(&mut self, position: Vector2<u32>) -> Result<()> {
+        self.display()
+            .send_request(&just_x11::requests::WarpPointer {
+                src_window: OrNone::none(),
+                dst_window: OrNone::new(self.window),
+                src_x: 0,
+                src_y: 0,
+                src_width: 0,
+                src_height: 0,
+                dst_x: position.x as i16,
+                dst_y: position.y as i16,
+            })?;
+        self.display().flush()?;
+        Ok(())
+    }
+
+    fn set_pointer_capture(&mut self, captured: bool) -> Result<bool> {
+        use just_x11::requests;
+
+        if !captured {
+            self.display().ungrab_pointer()?;
+            self.display()
+                .send_request(&requests::ChangeWindowAttributes {
+                    window: self.window,
+                    attributes: WindowCreationAttributes::new().set_cursor(0),
+                })?;
+            self.display().flush()?;
+            return Ok(true);
+        }
+
+        let invisible_cursor = self.display().invisible_cursor(0)?;
+        match self.display().grab_pointer(
+            &requests::GrabPointer::new(
+                self.window,
+                PointerEventMask::BUTTON_PRESS
+                    | PointerEventMask::BUTTON_RELEASE
+                    | PointerEventMask::POINTER_MOTION,
+            )
+            .with_owner_events()
+            .confined_to(self.window)
+            .with_cursor(invisible_cursor),
+        )? {
+            // Held until capture is released above
+            Ok(grab) => grab.leak(),
+            Err(_) => return Ok(false),
+        }
+
+        // Cursor of the grab is used only while pointer is in the window, set it on the window too
+        // in case the capture is lost
+        self.display()
+            .send_request(&requests::ChangeWindowAttributes {
+                window: self.window,
+                attributes: WindowCreationAttributes::new().set_cursor(invisible_cursor.into()),
+            })?;
+        self.display().flush()?;
+        Ok(true)
+    }
+
+    fn open_popup(
+        &mut self,
+        position: Vector2<i32>,
+        size: Vector2<u32>,
+        shadow: Option<PopupShadow>,
+    ) -> Result<Box<dyn Backend>> {
+        let argb_visual = match shadow {
+            Some(_) if self.compositor_running()? => self.argb_visual(),
+            _ => None,
+        };
+        let (Some(shadow), Some(argb_visual)) = (shadow, argb_visual) else {
+            let position = self.popup_position(position, size)?;
+            return Ok(Box::new(self.new_popup(position, size, None)?));
+        };
+
+        // Window grows by the shadow around the popup
+        let (before, after) = shadow.margins();
+        let window_size = size + before + after;
+        let position = self.popup_position(position - before.as_i32(), window_size)?;
+        let mut popup = self.new_popup(position, window_size, Some(argb_visual))?;
+        popup.set_input_area(before, size)?;
+        Ok(Box::new(ShadowBackend::new(Box::new(popup), size, shadow)))
     }
 }
 
 impl X11MitShmBackend {
+    /// Compositing managers own `_NET_WM_CM_S<screen>` selection, without one alpha of windows
+    /// is ignored
+    fn compositor_running(&mut self) -> Result<bool> {
+        use just_x11::requests;
+
+        let name = format!("_NET_WM_CM_S{}", self.display().default_screen_index());
+        let pending = self.display().send_request(&requests::InternAtom {
+            only_if_exists: false,
+            name: String8::from_bytes(name.into_bytes()).unwrap(),
+        })?;
+        self.display().flush()?;
+        let selection = self.display().await_pending_reply(pending)?.unwrap().atom;
+
+        let pending = self
+            .display()
+            .send_request(&requests::GetSelectionOwner { selection })?;
+        self.display().flush()?;
+        let owner = self.display().await_pending_reply(pending)?.unwrap().owner;
+        Ok(u32::from(owner) != 0)
+    }
+
+    /// 32 bit TrueColor visual with alpha in the remaining byte and a pixel layout of
+    /// [`PixelFormat`]
+    fn argb_visual(&self) -> Option<VisualId> {
+        self.display()
+            .default_screen()
+            .allowed_depths
+            .iter()
+            .filter(|depth| depth.depth == 32)
+            .flat_map(|depth| depth.visuals.iter())
+            .find(|visual| {
+                visual.class == VisualClass::TrueColor
+                    && PixelFormat::from_visual_masks(
+                        visual.red_mask,
+                        visual.green_mask,
+                        visual.blue_mask,
+                    )
+                    .is_some()
+            })
+            .map(|visual| visual.id)
+    }
+
+    /// Limit pointer input to `size` area at `position`, so clicks on the rest of the window go
+    /// to windows below. Without SHAPE the whole window takes input.
+    fn set_input_area(&mut self, position: Vector2<u32>, size: Vector2<u32>) -> Result<()> {
+        use just_x11::requests;
+
+        let pending = self.display().send_request(&requests::QueryExtension {
+            name: shape::EXTENSION_NAME.to_vec(),
+        })?;
+        self.display().flush()?;
+        let shape = match self.display().await_pending_reply(pending)? {
+            Ok(query) if query.present => query.major_opcode,
+            _ => return Ok(()),
+        };
+
+        self.display().send_extension_request(
+            &shape::requests::Rectangles {
+                operation: ShapeOperation::Set,
+                kind: ShapeKind::Input,
+                ordering: Ordering::YXBanded,
+                window: self.window,
+                x_offset: 0,
+                y_offset: 0,
+                rectangles: vec![Rectangle {
+                    x: position.x as i16,
+                    y: position.y as i16,
+                    width: size.x as u16,
+                    height: size.y as u16,
+                }],
+            },
+            shape,
+        )?;
+        self.display().flush()?;
+        Ok(())
+    }
+
+    /// Translate `position` relative to the window to the root, moved so `size` fits on the
+    /// monitor showing the center of the window
+    fn popup_position(
+        &mut self,
+        position: Vector2<i32>,
+        size: Vector2<u32>,
+    ) -> Result<Vector2<i32>> {
+        use just_x11::requests;
+
+        let (root, screen) = {
+            let display = self.display();
+            let screen = display.default_screen();
+            let area = Rectangle {
+                x: 0,
+                y: 0,
+                width: screen.width_in_pixels,
+                height: screen.height_in_pixels,
+            };
+            (screen.root, area)
+        };
+        let translated = {
+            let mut display = self.display();
+            let pending = display.send_request(&requests::TranslateCoordinates {
+                src_window: self.window,
+                dst_window: root,
+                src_x: position.x as i16,
+                src_y: position.y as i16,
+            })?;
+            display.flush()?;
+            let translated = display.await_pending_reply(pending)?.unwrap();
+            Vector2 {
+                x: translated.dst_x as i32,
+                y: translated.dst_y as i32,
+            }
+        };
+
+        let window_center = translated - position
+            + Vector2 {
+                x: self.canvas.size.x as i32 / 2,
+                y: self.canvas.size.y as i32 / 2,
+            };
+        let monitors = self.connection.borrow_mut().monitors()?;
+        let monitor = monitor_at(&monitors, window_center).unwrap_or(screen);
+        Ok(fit_into(translated, size, monitor))
+    }
+
     fn attach_new_shm_seg(
         display: &mut XDisplay,
         mit_shm_major_opcode: u8,
         size: Vector2<u32>,
     ) -> Result<MitShmCanvas> {
-        let new_shmseg = ShmSegId::from(display.id_allocator().allocate_id());
+        let new_shmseg = ShmSegId::from_resource(display.id_allocator().allocate_id());
         let new_canvas = MitShmCanvas::new(size, new_shmseg);
         display.send_extension_request(
             &mit_shm::requests::Attach {
@@ -389,16 +1268,136 @@ impl X11MitShmBackend {
     }
 }
 
-fn get_key_sym(event: KeyPressRelease, key_symbols: &KeySymbols) -> KeySym {
+impl Drop for X11MitShmBackend {
+    /// Popups share the connection with their window, so closing it doesn't free their resources
+    fn drop(&mut self) {
+        use just_x11::requests;
+
+        // Thread detaches its own segments
+        self.present_thread = None;
+        let mut connection = self.connection.borrow_mut();
+        connection.queues.remove(&self.window);
+        let display = connection.conn.display_mut();
+        let _ = display.send_request(&requests::DestroyWindow {
+            window: self.window,
+        });
+        let _ = display.send_extension_request(
+            &mit_shm::requests::Detach {
+                shmseg: self.canvas.shmseg,
+            },
+            self.mit_shm_major_opcode,
+        );
+        if let Some((colormap, gc)) = self.argb_resources {
+            let _ = display.send_request(&requests::FreeGC { gc });
+            let _ = display.send_request(&requests::FreeColormap { cmap: colormap });
+        }
+        let _ = display.flush();
+        // Segment is removed once the server detaches it too
+        unsafe { std::ptr::read(&self.canvas.mem).free() }
+    }
+}
+
+/// Monitor containing `point`, or the first one if none does
+fn monitor_at(monitors: &[Rectangle], point: Vector2<i32>) -> Option<Rectangle> {
+    monitors
+        .iter()
+        .find(|monitor| {
+            point.x >= monitor.x as i32
+                && point.y >= monitor.y as i32
+                && point.x < monitor.x as i32 + monitor.width as i32
+                && point.y < monitor.y as i32 + monitor.height as i32
+        })
+        .or(monitors.first())
+        .copied()
+}
+
+/// `position` moved so `size` fits into `area`, to its top left corner if it is too large
+fn fit_into(position: Vector2<i32>, size: Vector2<u32>, area: Rectangle) -> Vector2<i32> {
+    let fit = |position: i32, size: u32, start: i16, length: u16| {
+        cmp::max(
+            start as i32,
+            cmp::min(position, start as i32 + length as i32 - size as i32),
+        )
+    };
+    Vector2 {
+        x: fit(position.x, size.x, area.x, area.width),
+        y: fit(position.y, size.y, area.y, area.height),
+    }
+}
+
+/// Key, button or motion event of the window, `None` for other events or unknown keys and buttons
+fn device_event(
+    event: DeviceEvent,
+    window: WindowId,
+    size: Vector2<u32>,
+    key_symbols: &KeySymbols,
+) -> Option<Event> {
+    if event.event != window {
+        return None;
+    }
+
+    let device = InputDevice {
+        id: event.device_id.raw(),
+    };
+    let button = || {
+        let button = events::PointerButton::try_from(event.detail as u8).ok()?;
+        PointerButton::from_x11(button)
+    };
+    let key = || {
+        let key_sym = get_key_sym(
+            KeyCode::from(event.detail as u8),
+            KeyModifier::from(event.mods as u16),
+            key_symbols,
+        );
+        Some((key_sym, KeyboardButton::try_from(key_sym).ok()?))
+    };
+
+    match event.event_type {
+        DeviceEventType::KeyPress => {
+            let (key_sym, button) = key()?;
+            Some(Event::KeyboardButtonPress {
+                device,
+                key_sym,
+                button,
+            })
+        }
+        DeviceEventType::KeyRelease => {
+            let (key_sym, button) = key()?;
+            Some(Event::KeyboardButtonRelease {
+                device,
+                key_sym,
+                button,
+            })
+        }
+        DeviceEventType::ButtonPress => Some(Event::PointerButtonPress {
+            device,
+            button: button()?,
+        }),
+        DeviceEventType::ButtonRelease => Some(Event::PointerButtonRelease {
+            device,
+            button: button()?,
+        }),
+        DeviceEventType::Motion => Some(Event::PointerMotion {
+            device,
+            position: Vector2 {
+                x: (event.event_x.max(0.0) as u32).min(size.x),
+                y: (event.event_y.max(0.0) as u32).min(size.y),
+            },
+            time: event.time,
+        }),
+    }
+}
+
+fn get_key_sym(detail: KeyCode, state: KeyModifier, key_symbols: &KeySymbols) -> KeySym {
     let k0;
     let k1;
 
-    if event.state.has(KeyModifier::MOD_5) {
-        k0 = key_symbols.get_keysym(event.detail, KeySymColumn::Column2);
-        k1 = key_symbols.get_keysym(event.detail, KeySymColumn::Column3);
+    if state.has(KeyModifier::MOD_5) {
+        k0 = key_symbols.get_keysym(detail, KeySymColumn::Column2);
+        k1 = key_symbols.get_keysym(detail, KeySymColumn::Column3);
     } else {
-        k0 = key_symbols.get_keysym(event.detail, KeySymColumn::Column0);
-        k1 = key_symbols.get_keysym(event.detail, KeySymColumn::Column1);
+        k0 = key_symbols.get_keysym(detail, KeySymColumn::Column0);
+        k1 = key_symbols.get_keysym(detail, KeySymColumn::Column1);
     }
 
     // Handles released shift
@@ -406,9 +1405,36 @@ fn get_key_sym(event: KeyPressRelease, key_symbols: &KeySymbols) -> KeySym {
         return k0;
     }
 
-    if event.state.has(KeyModifier::SHIFT) || event.state.has(KeyModifier::LOCK) {
+    if state.has(KeyModifier::SHIFT) || state.has(KeyModifier::LOCK) {
         return k1;
     }
 
     k0
 }
+
+#[test]
+fn popup_stays_on_monitor() {
+    let left = Rectangle {
+        x: 0,
+        y: 0,
+        width: 1920,
+        height: 1080,
+    };
+    let right = Rectangle { x: 1920, ..left };
+    let monitors = [left, right];
+
+    let monitor = monitor_at(&monitors, Vector2 { x: 2000, y: 500 }).unwrap();
+    assert_eq!(monitor.x, 1920);
+    assert_eq!(monitor_at(&monitors, Vector2 { x: -5, y: 0 }).unwrap().x, 0);
+
+    // Popup past the right edge of the left monitor doesn't spill onto the right one
+    let size = Vector2 { x: 200, y: 100 };
+    assert_eq!(
+        fit_into(Vector2 { x: 1850, y: 1050 }, size, left),
+        Vector2 { x: 1720, y: 980 }
+    );
+    assert_eq!(
+        fit_into(Vector2 { x: 1900, y: -20 }, size, right),
+        Vector2 { x: 1920, y: 0 }
+    );
+}
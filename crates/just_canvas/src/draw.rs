@@ -1,4 +1,5 @@
-use crate::{Canvas, Color, Vector2, BYTES_PER_PIXEL};
+use crate::{image::Image, Canvas, Color, PixelFormat, Vector2, BYTES_PER_PIXEL};
+use core::cmp;
 use just_bdf::Glyph;
 
 // macro_rules! check_in_view {
@@ -46,20 +47,24 @@ macro_rules! check_in_view {
 }
 
 #[inline(always)]
-pub fn set_pixel(buf: &mut [u8], window_size: Vector2<u32>, position: Vector2<i32>, color: Color) {
+pub fn set_pixel(
+    buf: &mut [u8],
+    format: PixelFormat,
+    window_size: Vector2<u32>,
+    position: Vector2<i32>,
+    color: Color,
+) {
     check_in_view!(window_size, position);
     let position = position.as_u32();
     let offset = (window_size.x * position.y + position.x) as usize * BYTES_PER_PIXEL as usize;
 
-    buf[offset + 0] = color.b;
-    buf[offset + 1] = color.g;
-    buf[offset + 2] = color.r;
-    buf[offset + 3] = color.a;
+    buf[offset..offset + BYTES_PER_PIXEL as usize].copy_from_slice(&format.encode(color));
 }
 
 #[inline(always)]
 pub fn blend_pixel(
     buf: &mut [u8],
+    format: PixelFormat,
     window_size: Vector2<u32>,
     position: Vector2<i32>,
     color: Color,
@@ -67,30 +72,22 @@ pub fn blend_pixel(
     check_in_view!(window_size, position);
     let position = position.as_u32();
     let offset = (window_size.x * position.y + position.x) as usize * BYTES_PER_PIXEL as usize;
+    let pixel = &mut buf[offset..offset + BYTES_PER_PIXEL as usize];
 
-    let old = Color {
-        b: buf[offset + 0],
-        g: buf[offset + 1],
-        r: buf[offset + 2],
-        a: buf[offset + 3],
-    };
-
-    let blended = Color::blend(old, color);
+    let blended = Color::blend(format.decode(pixel), color);
 
-    buf[offset + 0] = blended.b;
-    buf[offset + 1] = blended.g;
-    buf[offset + 2] = blended.r;
-    buf[offset + 3] = blended.a;
+    pixel.copy_from_slice(&format.encode(blended));
 }
 
 macro_rules! define_rectangle {
     ($canvas:expr, $position:expr, $size:expr, $color:expr, $set_pixel:ident) => {
         let window_size = $canvas.window_size();
+        let format = $canvas.pixel_format();
         let buf = $canvas.raw_buf_mut();
 
         for cy in $position.y..($position.y + $size.y as i32) {
             for cx in $position.x..($position.x + $size.x as i32) {
-                $set_pixel(buf, window_size, Vector2 { x: cx, y: cy }, $color);
+                $set_pixel(buf, format, window_size, Vector2 { x: cx, y: cy }, $color);
             }
         }
     };
@@ -116,9 +113,85 @@ pub fn rectangle_blend(
     define_rectangle!(canvas, position, size, color, blend_pixel);
 }
 
+/// Fill `size` at `position` with colors interpolated between `corners`, which are top left, top
+/// right, bottom left and bottom right. Only `visible` part from the top left corner is drawn,
+/// so clipping doesn't change the gradient. Pixels that are not opaque are blended.
+pub fn rectangle_gradient(
+    canvas: &mut Canvas,
+    position: Vector2<i32>,
+    size: Vector2<u32>,
+    visible: Vector2<u32>,
+    corners: [Color; 4],
+) {
+    let [top_left, top_right, bottom_left, bottom_right] = corners;
+    let window_size = canvas.window_size();
+    let format = canvas.pixel_format();
+    let buf = canvas.raw_buf_mut();
+    // Last pixel gets the corner color exactly
+    let fraction =
+        |offset: u32, length: u32| offset as f32 / length.saturating_sub(1).max(1) as f32;
+
+    for y in 0..cmp::min(size.y, visible.y) {
+        let ty = fraction(y, size.y);
+        let left = Color::lerp(top_left, bottom_left, ty);
+        let right = Color::lerp(top_right, bottom_right, ty);
+        for x in 0..cmp::min(size.x, visible.x) {
+            let color = Color::lerp(left, right, fraction(x, size.x));
+            let position = Vector2 {
+                x: position.x + x as i32,
+                y: position.y + y as i32,
+            };
+            if color.a == 255 {
+                set_pixel(buf, format, window_size, position, color);
+            } else {
+                blend_pixel(buf, format, window_size, position, color);
+            }
+        }
+    }
+}
+
+/// Draw `source_size` area of `image` at `source_position` scaled to `size` with nearest
+/// neighbor sampling, blending pixels that are not opaque
+pub fn image_blend(
+    canvas: &mut Canvas,
+    position: Vector2<i32>,
+    size: Vector2<u32>,
+    image: &Image,
+    source_position: Vector2<u32>,
+    source_size: Vector2<u32>,
+) {
+    if size.x == 0 || size.y == 0 {
+        return;
+    }
+
+    let window_size = canvas.window_size();
+    let format = canvas.pixel_format();
+    let buf = canvas.raw_buf_mut();
+    let start = position.clamp_non_negative();
+    let end = Vector2 {
+        x: cmp::min(position.x + size.x as i32, window_size.x as i32),
+        y: cmp::min(position.y + size.y as i32, window_size.y as i32),
+    };
+
+    for cy in start.y..end.y {
+        let sy = source_position.y + (cy - position.y) as u32 * source_size.y / size.y;
+        for cx in start.x..end.x {
+            let sx = source_position.x + (cx - position.x) as u32 * source_size.x / size.x;
+            let color = image.pixel(Vector2 { x: sx, y: sy });
+            let position = Vector2 { x: cx, y: cy };
+            if color.a == 255 {
+                set_pixel(buf, format, window_size, position, color);
+            } else {
+                blend_pixel(buf, format, window_size, position, color);
+            }
+        }
+    }
+}
+
 #[inline]
 pub fn circle_replace(ui: &mut Canvas, center: Vector2<i32>, radius: u32, color: Color) {
     let window_size = ui.window_size();
+    let format = ui.pixel_format();
     let buf = ui.raw_buf_mut();
 
     let x = center.x - radius as i32;
@@ -128,7 +201,7 @@ pub fn circle_replace(ui: &mut Canvas, center: Vector2<i32>, radius: u32, color:
         for cx in x..(x + radius as i32 * 2) {
             let point = Vector2 { x: cx, y: cy };
             if inside_circle(center, radius, point) {
-                set_pixel(buf, window_size, Vector2 { x: cx, y: cy }, color);
+                set_pixel(buf, format, window_size, Vector2 { x: cx, y: cy }, color);
             }
         }
     }
@@ -145,6 +218,7 @@ pub fn circle_blend_with_anti_aliasing(
     color: Color,
 ) {
     let window_size = ui.window_size();
+    let format = ui.pixel_format();
     let buf = ui.raw_buf_mut();
 
     let x = center.x - radius as i32;
@@ -175,6 +249,130 @@ pub fn circle_blend_with_anti_aliasing(
             c.a = a as u8;
             blend_pixel(
                 buf,
+                format,
+                window_size,
+                Vector2 {
+                    x: current_x,
+                    y: current_y,
+                },
+                c,
+            );
+        }
+    }
+}
+
+/// Like [`circle_blend_with_anti_aliasing`] but only the ring `stroke_width` wide inside of
+/// `radius`
+#[inline]
+pub fn circle_outline_blend_with_anti_aliasing(
+    ui: &mut Canvas,
+    center: Vector2<i32>,
+    radius: u32,
+    stroke_width: u32,
+    color: Color,
+) {
+    annular_sector_blend(
+        ui,
+        center,
+        radius,
+        radius.saturating_sub(stroke_width),
+        None,
+        color,
+    );
+}
+
+/// Stroked part of a circle from `start_angle` to `end_angle` in radians. Angle `0` points
+/// right and angles grow clockwise, as y axis points down.
+#[inline]
+pub fn arc_blend_with_anti_aliasing(
+    ui: &mut Canvas,
+    center: Vector2<i32>,
+    radius: u32,
+    stroke_width: u32,
+    start_angle: f32,
+    end_angle: f32,
+    color: Color,
+) {
+    annular_sector_blend(
+        ui,
+        center,
+        radius,
+        radius.saturating_sub(stroke_width),
+        Some((start_angle, end_angle)),
+        color,
+    );
+}
+
+/// Filled slice of a circle, angles like in [`arc_blend_with_anti_aliasing`]
+#[inline]
+pub fn pie_blend_with_anti_aliasing(
+    ui: &mut Canvas,
+    center: Vector2<i32>,
+    radius: u32,
+    start_angle: f32,
+    end_angle: f32,
+    color: Color,
+) {
+    annular_sector_blend(ui, center, radius, 0, Some((start_angle, end_angle)), color);
+}
+
+/// Pixels between `inner_radius` and `outer_radius`, optionally only in the clockwise
+/// sweep between given angles
+fn annular_sector_blend(
+    ui: &mut Canvas,
+    center: Vector2<i32>,
+    outer_radius: u32,
+    inner_radius: u32,
+    angles: Option<(f32, f32)>,
+    color: Color,
+) {
+    use std::f32::consts::TAU;
+
+    let window_size = ui.window_size();
+    let format = ui.pixel_format();
+    let buf = ui.raw_buf_mut();
+
+    let outer_r2 = outer_radius as f32 * outer_radius as f32;
+    let inner_r2 = inner_radius as f32 * inner_radius as f32;
+    let angles = angles
+        .map(|(start, end)| (start.rem_euclid(TAU), end - start))
+        .filter(|(_, sweep)| *sweep < TAU);
+
+    let x = center.x - outer_radius as i32;
+    let y = center.y - outer_radius as i32;
+
+    for current_y in y..(y + outer_radius as i32 * 2) {
+        for current_x in x..(x + outer_radius as i32 * 2) {
+            let mut count = 0;
+            for subpixel_offset_x in 0..CIRCLE_AA_RES {
+                for subpixel_offset_y in 0..CIRCLE_AA_RES {
+                    let subpixel_x: f32 =
+                        current_x as f32 + CIRCLE_AA_PAD * (1.0 + subpixel_offset_x as f32);
+                    let subpixel_y: f32 =
+                        current_y as f32 + CIRCLE_AA_PAD * (1.0 + subpixel_offset_y as f32);
+
+                    let dx: f32 = subpixel_x - center.x as f32;
+                    let dy: f32 = subpixel_y - center.y as f32;
+                    let d2 = dx * dx + dy * dy;
+                    let in_ring = d2 <= outer_r2 && d2 >= inner_r2;
+                    let in_sweep = angles.is_none_or(|(start, sweep)| {
+                        (dy.atan2(dx) - start).rem_euclid(TAU) <= sweep
+                    });
+                    count += (in_ring && in_sweep) as u32;
+                }
+            }
+            if count == 0 {
+                continue;
+            }
+
+            let mut c = color;
+            let mut a = c.a as u32;
+            a *= count;
+            a /= CIRCLE_AA_RES * CIRCLE_AA_RES;
+            c.a = a as u8;
+            blend_pixel(
+                buf,
+                format,
                 window_size,
                 Vector2 {
                     x: current_x,
@@ -189,16 +387,18 @@ pub fn circle_blend_with_anti_aliasing(
 #[inline]
 pub fn thin_line(ui: &mut Canvas, start: Vector2<i32>, end: Vector2<i32>, color: Color) {
     let window_size = ui.window_size();
+    let format = ui.pixel_format();
     let buf = ui.raw_buf_mut();
 
     for (x, y) in LineIter::new(start, end) {
-        set_pixel(buf, window_size, Vector2 { x, y }, color);
+        set_pixel(buf, format, window_size, Vector2 { x, y }, color);
     }
 }
 
 #[inline]
 pub fn thin_dashed_line(ui: &mut Canvas, start: Vector2<i32>, end: Vector2<i32>, color: Color) {
     let window_size = ui.window_size();
+    let format = ui.pixel_format();
     let buf = ui.raw_buf_mut();
 
     // chosen arbitrarily
@@ -208,7 +408,7 @@ pub fn thin_dashed_line(ui: &mut Canvas, start: Vector2<i32>, end: Vector2<i32>,
     let mut n = 0;
     for (x, y) in LineIter::new(start, end) {
         if n < dash_length {
-            set_pixel(buf, window_size, Vector2 { x, y }, color);
+            set_pixel(buf, format, window_size, Vector2 { x, y }, color);
         }
         n += 1;
         if n >= dash_length + gap_length {
@@ -361,3 +561,163 @@ pub fn inside_rectangle(position: Vector2<i32>, size: Vector2<u32>, point: Vecto
         && point.y >= position.y
         && point.y <= position.y + size.y as i32
 }
+
+#[inline]
+/// Like [`inside_rectangle`] but with corners cut by circles of `radius`
+pub fn inside_rounded_rectangle(
+    position: Vector2<i32>,
+    size: Vector2<u32>,
+    radius: u32,
+    point: Vector2<i32>,
+) -> bool {
+    if !inside_rectangle(position, size, point) {
+        return false;
+    }
+
+    let radius = cmp::min(radius, cmp::min(size.x, size.y) / 2) as i32;
+    // Closest point of the rectangle shrunk by radius, distance to it matters only in corners
+    let inner = Vector2 {
+        x: point
+            .x
+            .clamp(position.x + radius, position.x + size.x as i32 - radius),
+        y: point
+            .y
+            .clamp(position.y + radius, position.y + size.y as i32 - radius),
+    };
+    inside_circle(inner, radius as u32, point)
+}
+
+/// Whether point is inside of convex polygon with vertices in either winding order. Points on
+/// the edges are inside. Polygons without area, with less than 3 vertices or all of them on one
+/// line, contain no points.
+pub fn inside_convex_polygon(vertices: &[Vector2<i32>], point: Vector2<i32>) -> bool {
+    let cross = |a: Vector2<i32>, b: Vector2<i32>, c: Vector2<i32>| {
+        (b.x - a.x) as i64 * (c.y - a.y) as i64 - (b.y - a.y) as i64 * (c.x - a.x) as i64
+    };
+    let Some(&first) = vertices.first() else {
+        return false;
+    };
+    if vertices
+        .windows(2)
+        .all(|edge| cross(first, edge[0], edge[1]) == 0)
+    {
+        return false;
+    }
+
+    let mut sign = 0i64;
+    for (idx, start) in vertices.iter().enumerate() {
+        let end = vertices[(idx + 1) % vertices.len()];
+        let side = cross(*start, end, point);
+        if side == 0 {
+            continue;
+        }
+        if sign == 0 {
+            sign = side.signum();
+        } else if sign != side.signum() {
+            return false;
+        }
+    }
+    true
+}
+
+#[test]
+fn inside_shapes() {
+    let triangle = [
+        Vector2 { x: 0, y: 0 },
+        Vector2 { x: 10, y: 0 },
+        Vector2 { x: 0, y: 10 },
+    ];
+    assert!(inside_convex_polygon(&triangle, Vector2 { x: 5, y: 5 }));
+    assert!(inside_convex_polygon(&triangle, Vector2 { x: 0, y: 0 }));
+    assert!(!inside_convex_polygon(&triangle, Vector2 { x: 6, y: 6 }));
+
+    let origin = Vector2 { x: 0, y: 0 };
+    assert!(!inside_convex_polygon(&[], origin));
+    assert!(!inside_convex_polygon(&[origin], origin));
+    assert!(!inside_convex_polygon(
+        &triangle[..2],
+        Vector2 { x: 5, y: 0 }
+    ));
+    let collinear = [origin, Vector2 { x: 5, y: 5 }, Vector2 { x: 10, y: 10 }];
+    assert!(!inside_convex_polygon(&collinear, Vector2 { x: 5, y: 5 }));
+    assert!(!inside_convex_polygon(&collinear, Vector2 { x: 0, y: 10 }));
+
+    let position = Vector2 { x: 0, y: 0 };
+    let size = Vector2 { x: 20, y: 20 };
+    assert!(inside_rounded_rectangle(
+        position,
+        size,
+        5,
+        Vector2 { x: 10, y: 0 }
+    ));
+    assert!(!inside_rounded_rectangle(
+        position,
+        size,
+        5,
+        Vector2 { x: 0, y: 0 }
+    ));
+    assert!(inside_rounded_rectangle(
+        position,
+        size,
+        5,
+        Vector2 { x: 20, y: 10 }
+    ));
+}
+
+#[test]
+fn arc_quadrant() {
+    use crate::BackendType;
+    use std::f32::consts::FRAC_PI_2;
+
+    let size = Vector2 { x: 40, y: 40 };
+    let mut canvas = Canvas::with_backend_type("", BackendType::Bitmap { size }).unwrap();
+    let center = Vector2 { x: 20, y: 20 };
+    // Bottom right quadrant, as angles grow clockwise
+    arc_blend_with_anti_aliasing(
+        &mut canvas,
+        center,
+        16,
+        4,
+        0.0,
+        FRAC_PI_2,
+        Color::from_raw(0xffffffff),
+    );
+
+    let pixel = |x: u32, y: u32| canvas.raw_buf()[((y * size.x + x) * BYTES_PER_PIXEL) as usize];
+    assert_ne!(pixel(30, 30), 0);
+    // Inside of the stroke
+    assert_eq!(pixel(24, 24), 0);
+    // Other quadrants
+    assert_eq!(pixel(30, 10), 0);
+    assert_eq!(pixel(10, 30), 0);
+}
+
+#[test]
+fn gradient_corners() {
+    use crate::BackendType;
+
+    let size = Vector2 { x: 11, y: 11 };
+    let mut canvas = Canvas::with_backend_type("", BackendType::Bitmap { size }).unwrap();
+    let white = Color::from_raw(0xffffffff);
+    let red = Color::from_raw(0xffff0000);
+    let black = Color::from_raw(0xff000000);
+    rectangle_gradient(
+        &mut canvas,
+        Vector2 { x: 0, y: 0 },
+        size,
+        Vector2 { x: 6, y: 11 },
+        [white, red, black, black],
+    );
+
+    let format = canvas.pixel_format();
+    let pixel = |x: u32, y: u32| {
+        let offset = ((y * size.x + x) * BYTES_PER_PIXEL) as usize;
+        format.decode(&canvas.raw_buf()[offset..offset + BYTES_PER_PIXEL as usize])
+    };
+    assert_eq!(pixel(0, 0), white);
+    assert_eq!(pixel(0, 10), black);
+    assert_eq!(pixel(5, 0), Color::from_raw(0xffff8080));
+    assert_eq!(pixel(5, 5), Color::from_raw(0xff804040));
+    // Right part is clipped
+    assert_eq!(pixel(6, 0).r, 0);
+}
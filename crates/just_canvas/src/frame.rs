@@ -0,0 +1,122 @@
+//! Pacing of frames by vertical blanks reported by the server, with a steady timer when they are
+//! not available
+
+use std::time::{Duration, Instant};
+
+/// Vertical blank of the monitor showing the window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Vblank {
+    /// Server time of the vertical blank in microseconds
+    pub ust: u64,
+    /// Media stream counter, incremented by every vertical blank
+    pub msc: u64,
+}
+
+/// Vertical blanks older than this are not used to predict the next one, e.g. after the window
+/// was hidden
+const STALE_VBLANK: Duration = Duration::from_secs(1);
+
+/// Predicts when the next frame should be drawn, see [`crate::Canvas::request_frame`]
+#[derive(Debug, Clone, Default)]
+pub struct FrameClock {
+    /// Server time of a vertical blank and when it was received, received with the least delay
+    /// seen so far, to convert server times to [`Instant`]s
+    anchor: Option<(Instant, u64)>,
+    last: Option<Vblank>,
+    /// Time between vertical blanks
+    interval: Option<Duration>,
+    /// Deadline of the last frame paced by the timer
+    tick: Option<Instant>,
+}
+
+impl FrameClock {
+    /// Record vertical blank that arrived at `received`
+    pub fn vblank(&mut self, vblank: Vblank, received: Instant) {
+        if let Some(last) = self.last {
+            if vblank.msc > last.msc && vblank.ust > last.ust {
+                self.interval = Some(Duration::from_micros(
+                    (vblank.ust - last.ust) / (vblank.msc - last.msc),
+                ));
+            }
+        }
+        self.last = Some(vblank);
+
+        // Vertical blank can't be received before it happened, so the anchor giving the
+        // earliest time is the closest one
+        let closer = match self.anchor {
+            Some(anchor) => self
+                .ust_instant(anchor, vblank.ust)
+                .is_none_or(|predicted| received < predicted),
+            None => true,
+        };
+        if closer {
+            self.anchor = Some((received, vblank.ust));
+        }
+    }
+
+    fn ust_instant(&self, (instant, ust): (Instant, u64), other: u64) -> Option<Instant> {
+        if other >= ust {
+            instant.checked_add(Duration::from_micros(other - ust))
+        } else {
+            instant.checked_sub(Duration::from_micros(ust - other))
+        }
+    }
+
+    /// When the next frame should be drawn: the first vertical blank after `now`, or `interval`
+    /// after the previous deadline if vertical blanks are unknown. Timer skips deadlines that
+    /// already passed instead of drawing frames in a burst.
+    pub fn next_frame(&mut self, now: Instant, interval: Duration) -> Instant {
+        if let Some(next) = self.next_vblank(now) {
+            self.tick = None;
+            return next;
+        }
+
+        let next = match self.tick {
+            Some(tick) if tick + interval > now => tick + interval,
+            _ => now + interval,
+        };
+        self.tick = Some(next);
+        next
+    }
+
+    pub(crate) fn next_vblank(&self, now: Instant) -> Option<Instant> {
+        let (anchor, last, interval) = (self.anchor?, self.last?, self.interval?);
+        let last = self.ust_instant(anchor, last.ust)?;
+        if interval.is_zero() || now.checked_duration_since(last)? > STALE_VBLANK {
+            return None;
+        }
+        let passed = (now - last).as_micros() / interval.as_micros() + 1;
+        Some(last + interval * passed as u32)
+    }
+}
+
+#[test]
+fn frame_clock_pacing() {
+    let start = Instant::now();
+    let ms = Duration::from_millis;
+    let mut clock = FrameClock::default();
+
+    // Steady timer without vertical blanks
+    assert_eq!(clock.next_frame(start, ms(10)), start + ms(10));
+    assert_eq!(clock.next_frame(start + ms(4), ms(10)), start + ms(20));
+    // Slow frame skips missed deadlines
+    assert_eq!(clock.next_frame(start + ms(35), ms(10)), start + ms(45));
+
+    // Vertical blanks every 16 ms, the second one received with less delay
+    let vblank = |ms: u64, msc| Vblank {
+        ust: 1_000_000 + ms * 1000,
+        msc,
+    };
+    clock.vblank(vblank(0, 100), start + ms(103));
+    // Interval is not known yet
+    assert_eq!(clock.next_frame(start + ms(104), ms(10)), start + ms(114));
+    clock.vblank(vblank(32, 102), start + ms(133));
+    assert_eq!(clock.interval, Some(ms(16)));
+    assert_eq!(clock.next_frame(start + ms(134), ms(10)), start + ms(149));
+    // Later vertical blank with more delay keeps the anchor
+    clock.vblank(vblank(48, 103), start + ms(152));
+    assert_eq!(clock.next_frame(start + ms(152), ms(10)), start + ms(165));
+
+    // Stale vertical blanks fall back to the timer
+    assert_eq!(clock.next_frame(start + ms(2000), ms(10)), start + ms(2010));
+}
@@ -0,0 +1,292 @@
+//! Decoded images and decoders of image files
+
+use crate::{Color, PixelFormat, Vector2, BYTES_PER_PIXEL};
+use std::fmt::Display;
+
+mod bmp;
+mod png;
+mod qoi;
+mod zlib;
+
+#[derive(Debug, Clone)]
+pub struct Image {
+    pub size: Vector2<u32>,
+    /// Row-major, `size.x * size.y` long
+    pub pixels: Vec<Color>,
+}
+
+/// Formats [`Image`] can be encoded to, e.g. to offer clipboard targets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Qoi,
+    Bmp,
+}
+
+impl ImageFormat {
+    pub const ALL: [Self; 3] = [Self::Png, Self::Qoi, Self::Bmp];
+
+    /// Name of the format in MIME types, which are also the X11 selection targets
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Qoi => "image/qoi",
+            Self::Bmp => "image/bmp",
+        }
+    }
+
+    /// Accepts `image/x-bmp` and `image/x-ms-bmp` used by older applications
+    pub fn from_mime_type(mime_type: &str) -> Option<Self> {
+        match mime_type {
+            "image/x-bmp" | "image/x-ms-bmp" => Some(Self::Bmp),
+            _ => Self::ALL
+                .into_iter()
+                .find(|format| format.mime_type() == mime_type),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImageError {
+    /// Not a format that can be decoded
+    UnknownFormat,
+    /// Format is known but header or data is malformed
+    Invalid(&'static str),
+    /// Buffer length does not match the size
+    SizeMismatch,
+}
+
+impl Display for ImageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImageError::UnknownFormat => write!(f, "Unknown image format"),
+            ImageError::Invalid(reason) => write!(f, "Invalid image: {}", reason),
+            ImageError::SizeMismatch => write!(f, "Image data does not match its size"),
+        }
+    }
+}
+
+impl Image {
+    /// Image from pixels laid out in `format`, like [`crate::Canvas::raw_buf`] in
+    /// [`crate::Canvas::pixel_format`]
+    pub fn from_raw(
+        size: Vector2<u32>,
+        format: PixelFormat,
+        raw: &[u8],
+    ) -> Result<Self, ImageError> {
+        if raw.len() != size.x as usize * size.y as usize * BYTES_PER_PIXEL as usize {
+            return Err(ImageError::SizeMismatch);
+        }
+
+        let pixels = raw
+            .chunks_exact(BYTES_PER_PIXEL as usize)
+            .map(|p| format.decode(p))
+            .collect();
+        Ok(Self { size, pixels })
+    }
+
+    /// Image from bytes in `R, G, B, A` order
+    pub fn from_rgba(size: Vector2<u32>, rgba: &[u8]) -> Result<Self, ImageError> {
+        Self::from_raw(size, PixelFormat::Rgba8888, rgba)
+    }
+
+    /// Image from bytes in `B, G, R, A` order
+    pub fn from_bgra(size: Vector2<u32>, bgra: &[u8]) -> Result<Self, ImageError> {
+        Self::from_raw(size, PixelFormat::Bgra8888, bgra)
+    }
+
+    /// Pixels laid out in `format`, inverse of [`Image::from_raw`]
+    pub fn to_raw(&self, format: PixelFormat) -> Vec<u8> {
+        self.pixels
+            .iter()
+            .flat_map(|&color| format.encode(color))
+            .collect()
+    }
+
+    /// Decode file contents, format is detected from the contents. Supports PNG (not
+    /// interlaced), QOI, uncompressed BMP, and binary PGM and PPM.
+    pub fn decode(bytes: &[u8]) -> Result<Self, ImageError> {
+        match bytes {
+            _ if bytes.starts_with(png::SIGNATURE) => png::decode(bytes),
+            _ if bytes.starts_with(qoi::MAGIC) => qoi::decode(bytes),
+            _ if bytes.starts_with(bmp::MAGIC) => bmp::decode(bytes),
+            [b'P', b'5' | b'6', ..] => decode_ppm(bytes),
+            _ => Err(ImageError::UnknownFormat),
+        }
+    }
+
+    /// Encode as 8 bit PNG, without alpha channel if all pixels are opaque
+    pub fn encode_png(&self) -> Vec<u8> {
+        png::encode(self)
+    }
+
+    /// Encode as QOI, faster than PNG with similar size for UI screenshots
+    pub fn encode_qoi(&self) -> Vec<u8> {
+        qoi::encode(self)
+    }
+
+    /// Encode as 32 bit BMP with alpha, read by most applications pasting images
+    pub fn encode_bmp(&self) -> Vec<u8> {
+        bmp::encode(self)
+    }
+
+    pub fn encode(&self, format: ImageFormat) -> Vec<u8> {
+        match format {
+            ImageFormat::Png => self.encode_png(),
+            ImageFormat::Qoi => self.encode_qoi(),
+            ImageFormat::Bmp => self.encode_bmp(),
+        }
+    }
+
+    #[inline]
+    pub fn pixel(&self, position: Vector2<u32>) -> Color {
+        self.pixels[(position.y * self.size.x + position.x) as usize]
+    }
+}
+
+/// Binary PGM (`P5`) or PPM (`P6`) with at most 8 bits per sample
+fn decode_ppm(bytes: &[u8]) -> Result<Image, ImageError> {
+    let mut rest = &bytes[2..];
+    let mut header = [0u32; 3];
+    for value in header.iter_mut() {
+        // Whitespace and comments between header values
+        loop {
+            match rest.first() {
+                Some(c) if c.is_ascii_whitespace() => rest = &rest[1..],
+                Some(b'#') => {
+                    let end = rest.iter().position(|&c| c == b'\n').unwrap_or(rest.len());
+                    rest = &rest[end..];
+                }
+                _ => break,
+            }
+        }
+        let digits = rest.iter().take_while(|c| c.is_ascii_digit()).count();
+        *value = std::str::from_utf8(&rest[..digits])
+            .ok()
+            .and_then(|digits| digits.parse().ok())
+            .ok_or(ImageError::Invalid("PPM header"))?;
+        rest = &rest[digits..];
+    }
+    let [width, height, max_value] = header;
+    if max_value == 0 || max_value > 255 {
+        return Err(ImageError::Invalid("PPM with more than 8 bits per sample"));
+    }
+    // Single whitespace before the data
+    rest = rest.get(1..).ok_or(ImageError::Invalid("PPM header"))?;
+
+    let channels = if bytes[1] == b'5' { 1 } else { 3 };
+    let len = width as usize * height as usize * channels;
+    let data = rest.get(..len).ok_or(ImageError::SizeMismatch)?;
+    let scale = |value: u8| (value as u32 * 255 / max_value) as u8;
+    let pixels = data
+        .chunks_exact(channels)
+        .map(|p| match p {
+            [v] => Color::from_components(255, scale(*v), scale(*v), scale(*v)),
+            p => Color::from_components(255, scale(p[0]), scale(p[1]), scale(p[2])),
+        })
+        .collect();
+
+    Ok(Image {
+        size: Vector2 {
+            x: width,
+            y: height,
+        },
+        pixels,
+    })
+}
+
+#[test]
+fn png_and_qoi_roundtrip() {
+    let size = Vector2 { x: 7, y: 5 };
+    let rgba = (0..size.x * size.y)
+        .flat_map(|i| {
+            [
+                (i * 37) as u8,
+                (i / 7 * 50) as u8,
+                0x40,
+                255 - (i % 3) as u8 * 100,
+            ]
+        })
+        .collect::<Vec<_>>();
+    let image = Image::from_rgba(size, &rgba).unwrap();
+
+    for encoded in [image.encode_png(), image.encode_qoi()] {
+        let decoded = Image::decode(&encoded).unwrap();
+        assert_eq!((decoded.size.x, decoded.size.y), (7, 5));
+        assert!(decoded
+            .pixels
+            .iter()
+            .zip(&image.pixels)
+            .all(|(a, b)| (a.a, a.r, a.g, a.b) == (b.a, b.r, b.g, b.b)));
+    }
+
+    assert_eq!(image.to_raw(PixelFormat::Rgba8888), rgba);
+    let bgra = image.to_raw(PixelFormat::Bgra8888);
+    assert_eq!(bgra[..4], [rgba[2], rgba[1], rgba[0], rgba[3]]);
+
+    // 1x1 gray PNG with a single pixel, as written by other encoders
+    let gray = Image::decode(
+        b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR\0\0\0\x01\0\0\0\x01\x08\0\0\0\0:~\x9bU\0\0\0\nIDATx\x9cc\xf8\x0f\0\x01\x01\x01\0\xb18\xf6\x14\0\0\0\0IEND\xaeB`\x82",
+    )
+    .unwrap();
+    assert_eq!((gray.pixels[0].r, gray.pixels[0].a), (0xff, 0xff));
+}
+
+#[test]
+fn decode_ppm_with_comment() {
+    let image = Image::decode(b"P6\n# comment\n2 1\n255\n\xff\x00\x00\x00\x00\xff").unwrap();
+    assert_eq!((image.size.x, image.size.y), (2, 1));
+    assert_eq!(image.pixel(Vector2 { x: 0, y: 0 }).r, 0xff);
+    assert_eq!(image.pixel(Vector2 { x: 1, y: 0 }).b, 0xff);
+
+    let gray = Image::decode(b"P5 1 1 15 \x0f").unwrap();
+    assert_eq!(gray.pixels[0].g, 0xff);
+
+    assert_eq!(
+        Image::decode(b"P6 2 2 255 \x00").unwrap_err(),
+        ImageError::SizeMismatch
+    );
+    assert_eq!(
+        Image::decode(b"GIF89a").unwrap_err(),
+        ImageError::UnknownFormat
+    );
+}
+
+#[test]
+fn bmp_roundtrip_and_mime_types() {
+    let size = Vector2 { x: 3, y: 2 };
+    let rgba = (0..size.x * size.y)
+        .flat_map(|i| [(i * 40) as u8, 0x10, (i * 7) as u8, 255 - i as u8 * 30])
+        .collect::<Vec<_>>();
+    let image = Image::from_rgba(size, &rgba).unwrap();
+    let decoded = Image::decode(&image.encode(ImageFormat::Bmp)).unwrap();
+    assert_eq!((decoded.size.x, decoded.size.y), (3, 2));
+    assert_eq!(decoded.to_raw(PixelFormat::Rgba8888), rgba);
+
+    // 24 bit bottom-up 1x2 with padded rows, as written by other encoders
+    let mut bmp =
+        b"BM\x3e\0\0\0\0\0\0\0\x36\0\0\0\x28\0\0\0\x01\0\0\0\x02\0\0\0\x01\0\x18\0".to_vec();
+    bmp.resize(0x36, 0);
+    bmp.extend_from_slice(b"\xff\0\0\0\0\0\xff\0");
+    let decoded = Image::decode(&bmp).unwrap();
+    assert_eq!(
+        decoded.pixel(Vector2 { x: 0, y: 0 }),
+        Color::from_raw(0xffff0000)
+    );
+    assert_eq!(
+        decoded.pixel(Vector2 { x: 0, y: 1 }),
+        Color::from_raw(0xff0000ff)
+    );
+
+    for format in ImageFormat::ALL {
+        assert_eq!(
+            ImageFormat::from_mime_type(format.mime_type()),
+            Some(format)
+        );
+    }
+    assert_eq!(
+        ImageFormat::from_mime_type("image/x-ms-bmp"),
+        Some(ImageFormat::Bmp)
+    );
+    assert_eq!(ImageFormat::from_mime_type("text/plain"), None);
+}
@@ -0,0 +1,157 @@
+//! Uncompressed BMP with 24 or 32 bits per pixel, which is what clipboards and screenshot tools
+//! exchange
+
+use super::{Image, ImageError};
+use crate::{Color, Vector2};
+
+pub(crate) const MAGIC: &[u8; 2] = b"BM";
+
+const FILE_HEADER_SIZE: u32 = 14;
+/// `BITMAPV4HEADER`, the smallest header with an alpha mask
+const V4_HEADER_SIZE: u32 = 108;
+const BI_RGB: u32 = 0;
+const BI_BITFIELDS: u32 = 3;
+const BI_ALPHABITFIELDS: u32 = 6;
+/// Red, green, blue and alpha masks of 32 bit pixels stored as `B, G, R, A`
+const BGRA_MASKS: [u32; 4] = [0x00ff0000, 0x0000ff00, 0x000000ff, 0xff000000];
+/// `LCS_sRGB`
+const SRGB: u32 = 0x73524742;
+/// 72 DPI
+const PIXELS_PER_METER: u32 = 2835;
+
+fn u16_at(bytes: &[u8], offset: usize) -> Result<u16, ImageError> {
+    let bytes = bytes
+        .get(offset..offset + 2)
+        .ok_or(ImageError::Invalid("BMP header"))?;
+    Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> Result<u32, ImageError> {
+    let bytes = bytes
+        .get(offset..offset + 4)
+        .ok_or(ImageError::Invalid("BMP header"))?;
+    Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// Channel of `value` selected by `mask` scaled to 8 bits, `default` if the mask is empty
+fn channel(value: u32, mask: u32, default: u8) -> u8 {
+    if mask == 0 {
+        return default;
+    }
+    let max = mask >> mask.trailing_zeros();
+    (((value & mask) >> mask.trailing_zeros()) as u64 * 255 / max as u64) as u8
+}
+
+pub(crate) fn decode(bytes: &[u8]) -> Result<Image, ImageError> {
+    let data_offset = u32_at(bytes, 10)? as usize;
+    let header_size = u32_at(bytes, 14)?;
+    if header_size < 40 {
+        return Err(ImageError::Invalid("BMP core header"));
+    }
+    let width = u32_at(bytes, 18)? as i32;
+    let height = u32_at(bytes, 22)? as i32;
+    let bits_per_pixel = u16_at(bytes, 28)?;
+    let compression = u32_at(bytes, 30)?;
+    if width < 0 {
+        return Err(ImageError::Invalid("BMP width"));
+    }
+
+    let masks = match (compression, bits_per_pixel) {
+        (BI_RGB, 24) => [0x00ff0000, 0x0000ff00, 0x000000ff, 0],
+        // Fourth byte is unused without masks, so pixels are opaque
+        (BI_RGB, 32) => [0x00ff0000, 0x0000ff00, 0x000000ff, 0],
+        (BI_BITFIELDS | BI_ALPHABITFIELDS, 32) => {
+            // Masks are part of V2 and later headers, or follow the info header
+            let masks_at = FILE_HEADER_SIZE as usize + 40;
+            let alpha = if header_size >= 56 || compression == BI_ALPHABITFIELDS {
+                u32_at(bytes, masks_at + 12)?
+            } else {
+                0
+            };
+            [
+                u32_at(bytes, masks_at)?,
+                u32_at(bytes, masks_at + 4)?,
+                u32_at(bytes, masks_at + 8)?,
+                alpha,
+            ]
+        }
+        _ => return Err(ImageError::Invalid("BMP compression or bit depth")),
+    };
+
+    let bytes_per_pixel = bits_per_pixel as usize / 8;
+    // Rows are padded to 4 bytes
+    let stride = (width as usize * bytes_per_pixel).div_ceil(4) * 4;
+    let rows = height.unsigned_abs() as usize;
+    let data = bytes
+        .get(data_offset..)
+        .and_then(|data| data.get(..stride * rows))
+        .ok_or(ImageError::SizeMismatch)?;
+
+    let mut pixels = Vec::with_capacity(width as usize * rows);
+    for y in 0..rows {
+        // Positive height is stored bottom-up
+        let row = if height > 0 { rows - 1 - y } else { y };
+        let row = &data[row * stride..][..width as usize * bytes_per_pixel];
+        for pixel in row.chunks_exact(bytes_per_pixel) {
+            let value = pixel
+                .iter()
+                .rev()
+                .fold(0u32, |acc, byte| acc << 8 | *byte as u32);
+            pixels.push(Color::from_components(
+                channel(value, masks[3], 255),
+                channel(value, masks[0], 0),
+                channel(value, masks[1], 0),
+                channel(value, masks[2], 0),
+            ));
+        }
+    }
+
+    Ok(Image {
+        size: Vector2 {
+            x: width as u32,
+            y: rows as u32,
+        },
+        pixels,
+    })
+}
+
+/// 32 bit bottom-up with alpha mask in a V4 header
+pub(crate) fn encode(image: &Image) -> Vec<u8> {
+    let data_offset = FILE_HEADER_SIZE + V4_HEADER_SIZE;
+    let data_size = image.size.x * image.size.y * 4;
+    let mut out = Vec::with_capacity((data_offset + data_size) as usize);
+
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&(data_offset + data_size).to_le_bytes());
+    out.extend_from_slice(&[0; 4]);
+    out.extend_from_slice(&data_offset.to_le_bytes());
+
+    out.extend_from_slice(&V4_HEADER_SIZE.to_le_bytes());
+    out.extend_from_slice(&image.size.x.to_le_bytes());
+    out.extend_from_slice(&image.size.y.to_le_bytes());
+    out.extend_from_slice(&1u16.to_le_bytes());
+    out.extend_from_slice(&32u16.to_le_bytes());
+    out.extend_from_slice(&BI_BITFIELDS.to_le_bytes());
+    out.extend_from_slice(&data_size.to_le_bytes());
+    out.extend_from_slice(&PIXELS_PER_METER.to_le_bytes());
+    out.extend_from_slice(&PIXELS_PER_METER.to_le_bytes());
+    // Palette sizes
+    out.extend_from_slice(&[0; 8]);
+    for mask in BGRA_MASKS {
+        out.extend_from_slice(&mask.to_le_bytes());
+    }
+    out.extend_from_slice(&SRGB.to_le_bytes());
+    // Endpoints and gamma, unused with sRGB
+    out.extend_from_slice(&[0; 48]);
+
+    for pixels in image
+        .pixels
+        .chunks_exact(image.size.x.max(1) as usize)
+        .rev()
+    {
+        for p in pixels {
+            out.extend_from_slice(&[p.b, p.g, p.r, p.a]);
+        }
+    }
+    out
+}
@@ -0,0 +1,356 @@
+//! PNG without interlacing and ancillary chunks other than palette transparency, which covers
+//! icons and screenshots
+
+use super::{zlib, Image, ImageError};
+use crate::{Color, PixelFormat, Vector2};
+
+pub(crate) const SIGNATURE: &[u8; 8] = b"\x89PNG\r\n\x1a\n";
+
+const COLOR_GRAY: u8 = 0;
+const COLOR_RGB: u8 = 2;
+const COLOR_PALETTE: u8 = 3;
+const COLOR_GRAY_ALPHA: u8 = 4;
+const COLOR_RGBA: u8 = 6;
+
+/// Larger images are rejected before decompressing them, 16384 x 16384 pixels
+const MAX_PIXELS: usize = 1 << 28;
+
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut n = 0;
+    while n < 256 {
+        let mut c = n as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 != 0 {
+                0xedb88320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+            k += 1;
+        }
+        table[n] = c;
+        n += 1;
+    }
+    table
+};
+
+fn crc32(chunks: &[&[u8]]) -> u32 {
+    let mut crc = u32::MAX;
+    for &byte in chunks.iter().flat_map(|chunk| chunk.iter()) {
+        crc = CRC_TABLE[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
+
+#[inline]
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let (pa, pb, pc) = (
+        (p - a as i16).abs(),
+        (p - b as i16).abs(),
+        (p - c as i16).abs(),
+    );
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+/// Undo filter of `row` in place, `previous` is the already unfiltered row above
+fn unfilter(
+    filter: u8,
+    row: &mut [u8],
+    previous: &[u8],
+    bytes_per_pixel: usize,
+) -> Result<(), ImageError> {
+    for i in 0..row.len() {
+        let left = if i >= bytes_per_pixel {
+            row[i - bytes_per_pixel]
+        } else {
+            0
+        };
+        let up = previous[i];
+        let up_left = if i >= bytes_per_pixel {
+            previous[i - bytes_per_pixel]
+        } else {
+            0
+        };
+        let predicted = match filter {
+            0 => 0,
+            1 => left,
+            2 => up,
+            3 => ((left as u16 + up as u16) / 2) as u8,
+            4 => paeth(left, up, up_left),
+            _ => return Err(ImageError::Invalid("PNG filter")),
+        };
+        row[i] = row[i].wrapping_add(predicted);
+    }
+    Ok(())
+}
+
+struct Header {
+    width: u32,
+    height: u32,
+    bit_depth: u8,
+    color_type: u8,
+}
+
+impl Header {
+    fn channels(&self) -> usize {
+        match self.color_type {
+            COLOR_RGB => 3,
+            COLOR_GRAY_ALPHA => 2,
+            COLOR_RGBA => 4,
+            _ => 1,
+        }
+    }
+}
+
+pub(crate) fn decode(bytes: &[u8]) -> Result<Image, ImageError> {
+    let mut rest = &bytes[SIGNATURE.len()..];
+    let mut header = None;
+    let mut palette = Vec::new();
+    let mut palette_alpha = Vec::<u8>::new();
+    let mut compressed = Vec::new();
+    loop {
+        let length = rest
+            .get(..4)
+            .map(|l| u32::from_be_bytes([l[0], l[1], l[2], l[3]]) as usize)
+            .ok_or(ImageError::Invalid("PNG chunk"))?;
+        let chunk = rest
+            .get(4..12 + length)
+            .ok_or(ImageError::Invalid("PNG chunk"))?;
+        let (kind, data) = (&chunk[..4], &chunk[4..4 + length]);
+        let crc = &chunk[4 + length..];
+        if crc32(&[kind, data]) != u32::from_be_bytes([crc[0], crc[1], crc[2], crc[3]]) {
+            return Err(ImageError::Invalid("PNG chunk checksum"));
+        }
+        rest = &rest[12 + length..];
+
+        match kind {
+            b"IHDR" => {
+                let [w0, w1, w2, w3, h0, h1, h2, h3, bit_depth, color_type, 0, 0, interlace] =
+                    *data
+                else {
+                    return Err(ImageError::Invalid("PNG header"));
+                };
+                if interlace != 0 {
+                    return Err(ImageError::Invalid("interlaced PNG"));
+                }
+                let valid = match color_type {
+                    COLOR_GRAY => matches!(bit_depth, 1 | 2 | 4 | 8 | 16),
+                    COLOR_PALETTE => matches!(bit_depth, 1 | 2 | 4 | 8),
+                    COLOR_RGB | COLOR_GRAY_ALPHA | COLOR_RGBA => matches!(bit_depth, 8 | 16),
+                    _ => false,
+                };
+                if !valid {
+                    return Err(ImageError::Invalid("PNG color type"));
+                }
+                header = Some(Header {
+                    width: u32::from_be_bytes([w0, w1, w2, w3]),
+                    height: u32::from_be_bytes([h0, h1, h2, h3]),
+                    bit_depth,
+                    color_type,
+                });
+            }
+            b"PLTE" => {
+                palette = data
+                    .chunks_exact(3)
+                    .map(|p| Color::from_components(255, p[0], p[1], p[2]))
+                    .collect();
+            }
+            b"tRNS" => palette_alpha = data.to_vec(),
+            b"IDAT" => compressed.extend_from_slice(data),
+            b"IEND" => break,
+            // Ancillary chunks have lowercase first letter and can be skipped
+            _ if kind[0].is_ascii_lowercase() => {}
+            _ => return Err(ImageError::Invalid("unsupported PNG chunk")),
+        }
+    }
+
+    let header = header.ok_or(ImageError::Invalid("PNG header"))?;
+    let bits_per_pixel = header.channels() * header.bit_depth as usize;
+    let bytes_per_pixel = (bits_per_pixel / 8).max(1);
+    let too_large = ImageError::Invalid("PNG size");
+    let pixel_count = (header.width as usize)
+        .checked_mul(header.height as usize)
+        .filter(|&count| count <= MAX_PIXELS)
+        .ok_or(too_large.clone())?;
+    let stride = (header.width as usize)
+        .checked_mul(bits_per_pixel)
+        .ok_or(too_large.clone())?
+        .div_ceil(8);
+    let data_len = (stride + 1)
+        .checked_mul(header.height as usize)
+        .ok_or(too_large)?;
+
+    let mut data = zlib::decompress(&compressed)?;
+    if data.len() != data_len {
+        return Err(ImageError::SizeMismatch);
+    }
+
+    let mut pixels = Vec::with_capacity(pixel_count);
+    let mut previous = vec![0u8; stride];
+    for row in data.chunks_exact_mut(stride + 1) {
+        let (filter, row) = row.split_first_mut().unwrap();
+        unfilter(*filter, row, &previous, bytes_per_pixel)?;
+
+        // High byte of 16 bit samples
+        let sample = |x: usize, channel: usize| -> u8 {
+            match header.bit_depth {
+                16 => row[(x * header.channels() + channel) * 2],
+                8 => row[x * header.channels() + channel],
+                depth => {
+                    let bit = x * depth as usize;
+                    let shift = 8 - depth as usize - bit % 8;
+                    (row[bit / 8] >> shift) & ((1 << depth) - 1)
+                }
+            }
+        };
+        // Scale gray samples of lower bit depths to the full range
+        let gray_scale = match header.bit_depth {
+            1 => 255,
+            2 => 85,
+            4 => 17,
+            _ => 1,
+        };
+
+        for x in 0..header.width as usize {
+            let color = match header.color_type {
+                COLOR_GRAY => {
+                    let v = sample(x, 0) * gray_scale;
+                    Color::from_components(255, v, v, v)
+                }
+                COLOR_GRAY_ALPHA => {
+                    let v = sample(x, 0);
+                    Color::from_components(sample(x, 1), v, v, v)
+                }
+                COLOR_RGB => Color::from_components(255, sample(x, 0), sample(x, 1), sample(x, 2)),
+                COLOR_RGBA => {
+                    Color::from_components(sample(x, 3), sample(x, 0), sample(x, 1), sample(x, 2))
+                }
+                _ => {
+                    let index = sample(x, 0) as usize;
+                    let mut color = *palette
+                        .get(index)
+                        .ok_or(ImageError::Invalid("PNG palette index"))?;
+                    color.a = palette_alpha.get(index).copied().unwrap_or(255);
+                    color
+                }
+            };
+            pixels.push(color);
+        }
+        previous.copy_from_slice(row);
+    }
+
+    Ok(Image {
+        size: Vector2 {
+            x: header.width,
+            y: header.height,
+        },
+        pixels,
+    })
+}
+
+fn write_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    out.extend_from_slice(&crc32(&[kind, data]).to_be_bytes());
+}
+
+/// 8 bit RGB, or RGBA if any pixel is not opaque
+pub(crate) fn encode(image: &Image) -> Vec<u8> {
+    let opaque = image.pixels.iter().all(|p| p.a == 255);
+    let (color_type, channels) = if opaque {
+        (COLOR_RGB, 3)
+    } else {
+        (COLOR_RGBA, 4)
+    };
+    let stride = image.size.x as usize * channels;
+
+    // Pixels are already in memory, so this can't overflow unlike their size
+    let mut raw = Vec::with_capacity(image.pixels.len() * channels + image.size.y as usize);
+    let mut previous = vec![0u8; stride];
+    let mut row = Vec::with_capacity(stride);
+    let mut filtered = vec![0u8; stride];
+    let mut best = vec![0u8; stride];
+    for pixels in image.pixels.chunks_exact(image.size.x.max(1) as usize) {
+        row.clear();
+        for p in pixels {
+            row.extend_from_slice(&PixelFormat::Rgba8888.encode(*p)[..channels]);
+        }
+
+        // Filter with the smallest sum of absolute differences, usual heuristic from libpng
+        let mut best_filter = 0;
+        let mut best_score = u64::MAX;
+        for filter in 0..=4 {
+            for i in 0..stride {
+                let left = if i >= channels { row[i - channels] } else { 0 };
+                let up_left = if i >= channels {
+                    previous[i - channels]
+                } else {
+                    0
+                };
+                let predicted = match filter {
+                    0 => 0,
+                    1 => left,
+                    2 => previous[i],
+                    3 => ((left as u16 + previous[i] as u16) / 2) as u8,
+                    _ => paeth(left, previous[i], up_left),
+                };
+                filtered[i] = row[i].wrapping_sub(predicted);
+            }
+            let score = filtered
+                .iter()
+                .map(|&v| (v as i8).unsigned_abs() as u64)
+                .sum();
+            if score < best_score {
+                best_score = score;
+                best_filter = filter;
+                best.copy_from_slice(&filtered);
+            }
+        }
+        raw.push(best_filter);
+        raw.extend_from_slice(&best);
+        previous.copy_from_slice(&row);
+    }
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&image.size.x.to_be_bytes());
+    header.extend_from_slice(&image.size.y.to_be_bytes());
+    // Bit depth, color type, compression, filter method, interlace
+    header.extend_from_slice(&[8, color_type, 0, 0, 0]);
+
+    let mut out = SIGNATURE.to_vec();
+    write_chunk(&mut out, b"IHDR", &header);
+    write_chunk(&mut out, b"IDAT", &zlib::compress(&raw));
+    write_chunk(&mut out, b"IEND", &[]);
+    out
+}
+
+#[test]
+fn oversized_png_is_rejected() {
+    let png = |width: u32, height: u32| {
+        let mut header = width.to_be_bytes().to_vec();
+        header.extend(height.to_be_bytes());
+        header.extend([8, COLOR_RGBA, 0, 0, 0]);
+        let mut out = SIGNATURE.to_vec();
+        write_chunk(&mut out, b"IHDR", &header);
+        write_chunk(&mut out, b"IDAT", &zlib::compress(&[0; 5]));
+        write_chunk(&mut out, b"IEND", &[]);
+        out
+    };
+
+    assert_eq!(decode(&png(1, 1)).unwrap().size, Vector2 { x: 1, y: 1 });
+    for (width, height) in [(u32::MAX, u32::MAX), (1 << 15, 1 << 15), (u32::MAX, 1)] {
+        assert_eq!(
+            decode(&png(width, height)).unwrap_err(),
+            ImageError::Invalid("PNG size")
+        );
+    }
+}
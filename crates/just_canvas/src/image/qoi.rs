@@ -0,0 +1,150 @@
+//! The Quite OK Image format, see <https://qoiformat.org/qoi-specification.pdf>
+
+use super::{Image, ImageError};
+use crate::{Color, PixelFormat, Vector2};
+
+pub(crate) const MAGIC: &[u8; 4] = b"qoif";
+const END_MARKER: [u8; 8] = [0, 0, 0, 0, 0, 0, 0, 1];
+
+const OP_INDEX: u8 = 0x00;
+const OP_DIFF: u8 = 0x40;
+const OP_LUMA: u8 = 0x80;
+const OP_RUN: u8 = 0xc0;
+const OP_RGB: u8 = 0xfe;
+const OP_RGBA: u8 = 0xff;
+const TAG_MASK: u8 = 0xc0;
+
+const MAX_RUN: u8 = 62;
+
+#[inline]
+fn hash([r, g, b, a]: [u8; 4]) -> usize {
+    (r as usize * 3 + g as usize * 5 + b as usize * 7 + a as usize * 11) % 64
+}
+
+pub(crate) fn decode(bytes: &[u8]) -> Result<Image, ImageError> {
+    let header = bytes.get(..14).ok_or(ImageError::Invalid("QOI header"))?;
+    let width = u32::from_be_bytes([header[4], header[5], header[6], header[7]]);
+    let height = u32::from_be_bytes([header[8], header[9], header[10], header[11]]);
+    if !matches!(header[12], 3 | 4) {
+        return Err(ImageError::Invalid("QOI channels"));
+    }
+    let len = width as usize * height as usize;
+    // Each pixel takes at least a quarter of a byte, also guards against huge allocations
+    if len / 62 > bytes.len() {
+        return Err(ImageError::SizeMismatch);
+    }
+
+    let mut data = bytes[14..].iter().copied();
+    let mut next = || data.next().ok_or(ImageError::SizeMismatch);
+    let mut index = [[0u8; 4]; 64];
+    let mut pixel = [0, 0, 0, 255];
+    let mut run = 0;
+    let mut pixels = Vec::with_capacity(len);
+    while pixels.len() < len {
+        if run > 0 {
+            run -= 1;
+        } else {
+            let op = next()?;
+            match op {
+                OP_RGB => pixel[..3].copy_from_slice(&[next()?, next()?, next()?]),
+                OP_RGBA => pixel = [next()?, next()?, next()?, next()?],
+                _ => match op & TAG_MASK {
+                    OP_INDEX => pixel = index[op as usize],
+                    OP_DIFF => {
+                        pixel[0] = pixel[0].wrapping_add((op >> 4) & 3).wrapping_sub(2);
+                        pixel[1] = pixel[1].wrapping_add((op >> 2) & 3).wrapping_sub(2);
+                        pixel[2] = pixel[2].wrapping_add(op & 3).wrapping_sub(2);
+                    }
+                    OP_LUMA => {
+                        let second = next()?;
+                        let green = (op & 0x3f).wrapping_sub(32);
+                        pixel[0] = pixel[0]
+                            .wrapping_add(green)
+                            .wrapping_add(second >> 4)
+                            .wrapping_sub(8);
+                        pixel[1] = pixel[1].wrapping_add(green);
+                        pixel[2] = pixel[2]
+                            .wrapping_add(green)
+                            .wrapping_add(second & 0x0f)
+                            .wrapping_sub(8);
+                    }
+                    _ => run = op & 0x3f,
+                },
+            }
+            index[hash(pixel)] = pixel;
+        }
+        let [r, g, b, a] = pixel;
+        pixels.push(Color::from_components(a, r, g, b));
+    }
+
+    Ok(Image {
+        size: Vector2 {
+            x: width,
+            y: height,
+        },
+        pixels,
+    })
+}
+
+pub(crate) fn encode(image: &Image) -> Vec<u8> {
+    let opaque = image.pixels.iter().all(|p| p.a == 255);
+    let mut out = Vec::with_capacity(image.pixels.len() + 22);
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&image.size.x.to_be_bytes());
+    out.extend_from_slice(&image.size.y.to_be_bytes());
+    out.push(if opaque { 3 } else { 4 });
+    // sRGB with linear alpha
+    out.push(0);
+
+    let mut index = [[0u8; 4]; 64];
+    let mut previous = [0, 0, 0, 255];
+    let mut run = 0;
+    for (i, color) in image.pixels.iter().enumerate() {
+        let pixel = PixelFormat::Rgba8888.encode(*color);
+        if pixel == previous {
+            run += 1;
+            if run == MAX_RUN || i == image.pixels.len() - 1 {
+                out.push(OP_RUN | (run - 1));
+                run = 0;
+            }
+            continue;
+        }
+
+        if run > 0 {
+            out.push(OP_RUN | (run - 1));
+            run = 0;
+        }
+        let hash = hash(pixel);
+        if index[hash] == pixel {
+            out.push(OP_INDEX | hash as u8);
+        } else {
+            index[hash] = pixel;
+            if pixel[3] == previous[3] {
+                let dr = pixel[0].wrapping_sub(previous[0]) as i8;
+                let dg = pixel[1].wrapping_sub(previous[1]) as i8;
+                let db = pixel[2].wrapping_sub(previous[2]) as i8;
+                let dr_dg = dr.wrapping_sub(dg);
+                let db_dg = db.wrapping_sub(dg);
+                if (-2..=1).contains(&dr) && (-2..=1).contains(&dg) && (-2..=1).contains(&db) {
+                    out.push(
+                        OP_DIFF | ((dr + 2) as u8) << 4 | ((dg + 2) as u8) << 2 | (db + 2) as u8,
+                    );
+                } else if (-8..=7).contains(&dr_dg)
+                    && (-32..=31).contains(&dg)
+                    && (-8..=7).contains(&db_dg)
+                {
+                    out.push(OP_LUMA | (dg + 32) as u8);
+                    out.push(((dr_dg + 8) as u8) << 4 | (db_dg + 8) as u8);
+                } else {
+                    out.extend_from_slice(&[OP_RGB, pixel[0], pixel[1], pixel[2]]);
+                }
+            } else {
+                out.extend_from_slice(&[OP_RGBA, pixel[0], pixel[1], pixel[2], pixel[3]]);
+            }
+        }
+        previous = pixel;
+    }
+
+    out.extend_from_slice(&END_MARKER);
+    out
+}
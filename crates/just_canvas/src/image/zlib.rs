@@ -0,0 +1,433 @@
+//! zlib streams (RFC 1950) with DEFLATE (RFC 1951) inflate and a fixed Huffman compressor
+
+use super::ImageError;
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+/// Order of code length code lengths in dynamic block header
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+const MAX_BITS: usize = 15;
+const WINDOW_SIZE: usize = 32768;
+const MAX_MATCH: usize = 258;
+const MIN_MATCH: usize = 3;
+
+const INVALID: ImageError = ImageError::Invalid("zlib stream");
+
+pub(crate) fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    // Largest chunk that can't overflow before the modulo
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    (b << 16) | a
+}
+
+struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+    buf: u32,
+    buffered: u32,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            position: 0,
+            buf: 0,
+            buffered: 0,
+        }
+    }
+
+    /// `count` bits, least significant first
+    fn bits(&mut self, count: u32) -> Result<u32, ImageError> {
+        while self.buffered < count {
+            let byte = *self.data.get(self.position).ok_or(INVALID)?;
+            self.position += 1;
+            self.buf |= (byte as u32) << self.buffered;
+            self.buffered += 8;
+        }
+        let value = self.buf & ((1u64 << count) - 1) as u32;
+        self.buf >>= count;
+        self.buffered -= count;
+        Ok(value)
+    }
+
+    /// Drop bits up to the next byte boundary
+    fn align(&mut self) {
+        self.buf = 0;
+        self.buffered = 0;
+    }
+}
+
+/// Canonical Huffman code as counts of codes of each length and symbols ordered by code
+struct Huffman {
+    counts: [u16; MAX_BITS + 1],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Result<Self, ImageError> {
+        let mut counts = [0u16; MAX_BITS + 1];
+        for &length in lengths {
+            counts[length as usize] += 1;
+        }
+        counts[0] = 0;
+
+        // Over-subscribed codes can't be decoded, incomplete are allowed
+        let mut left = 1i32;
+        for &count in &counts[1..] {
+            left = left * 2 - count as i32;
+            if left < 0 {
+                return Err(INVALID);
+            }
+        }
+
+        let mut offsets = [0u16; MAX_BITS + 2];
+        for length in 1..=MAX_BITS {
+            offsets[length + 1] = offsets[length] + counts[length];
+        }
+        let mut symbols = vec![0; lengths.len()];
+        for (symbol, &length) in lengths.iter().enumerate() {
+            if length != 0 {
+                symbols[offsets[length as usize] as usize] = symbol as u16;
+                offsets[length as usize] += 1;
+            }
+        }
+        Ok(Self { counts, symbols })
+    }
+
+    fn decode(&self, reader: &mut BitReader) -> Result<u16, ImageError> {
+        // Codes are packed starting from the most significant bit
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for &count in &self.counts[1..] {
+            code |= reader.bits(1)? as i32;
+            let count = count as i32;
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(INVALID)
+    }
+}
+
+fn fixed_codes() -> (Huffman, Huffman) {
+    let mut lengths = [0u8; 288];
+    lengths[..144].fill(8);
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    lengths[280..].fill(8);
+    // Always valid
+    (
+        Huffman::new(&lengths).unwrap(),
+        Huffman::new(&[5; 30]).unwrap(),
+    )
+}
+
+fn dynamic_codes(reader: &mut BitReader) -> Result<(Huffman, Huffman), ImageError> {
+    let literals = reader.bits(5)? as usize + 257;
+    let distances = reader.bits(5)? as usize + 1;
+    let code_lengths = reader.bits(4)? as usize + 4;
+    if literals > 286 || distances > 30 {
+        return Err(INVALID);
+    }
+
+    let mut lengths = [0u8; 19];
+    for &symbol in &CODE_LENGTH_ORDER[..code_lengths] {
+        lengths[symbol] = reader.bits(3)? as u8;
+    }
+    let code_length_code = Huffman::new(&lengths)?;
+
+    let mut lengths = vec![0u8; literals + distances];
+    let mut filled = 0;
+    while filled < lengths.len() {
+        let (value, repeat) = match code_length_code.decode(reader)? {
+            length @ 0..=15 => (length as u8, 1),
+            16 => {
+                let previous = *lengths[..filled].last().ok_or(INVALID)?;
+                (previous, 3 + reader.bits(2)? as usize)
+            }
+            17 => (0, 3 + reader.bits(3)? as usize),
+            _ => (0, 11 + reader.bits(7)? as usize),
+        };
+        lengths
+            .get_mut(filled..filled + repeat)
+            .ok_or(INVALID)?
+            .fill(value);
+        filled += repeat;
+    }
+    if lengths[256] == 0 {
+        // No end of block code
+        return Err(INVALID);
+    }
+
+    Ok((
+        Huffman::new(&lengths[..literals])?,
+        Huffman::new(&lengths[literals..])?,
+    ))
+}
+
+fn inflate_block(
+    reader: &mut BitReader,
+    out: &mut Vec<u8>,
+    literal_code: &Huffman,
+    distance_code: &Huffman,
+) -> Result<(), ImageError> {
+    loop {
+        let symbol = literal_code.decode(reader)? as usize;
+        match symbol {
+            0..=255 => out.push(symbol as u8),
+            256 => return Ok(()),
+            _ => {
+                let index = symbol - 257;
+                let length = *LENGTH_BASE.get(index).ok_or(INVALID)? as usize
+                    + reader.bits(LENGTH_EXTRA[index] as u32)? as usize;
+                let index = distance_code.decode(reader)? as usize;
+                let distance = *DISTANCE_BASE.get(index).ok_or(INVALID)? as usize
+                    + reader.bits(DISTANCE_EXTRA[index] as u32)? as usize;
+                if distance > out.len() {
+                    return Err(INVALID);
+                }
+                // Byte by byte, source can overlap with the copied bytes
+                let start = out.len() - distance;
+                for i in 0..length {
+                    out.push(out[start + i]);
+                }
+            }
+        }
+    }
+}
+
+/// Decompress zlib stream, checking its checksum
+pub(crate) fn decompress(data: &[u8]) -> Result<Vec<u8>, ImageError> {
+    let [cmf, flg, ..] = *data else {
+        return Err(INVALID);
+    };
+    // Only deflate, without preset dictionary
+    if cmf & 0x0f != 8 || !(cmf as u16 * 256 + flg as u16).is_multiple_of(31) || flg & 0x20 != 0 {
+        return Err(INVALID);
+    }
+
+    let mut reader = BitReader::new(&data[2..]);
+    let mut out = Vec::new();
+    loop {
+        let last = reader.bits(1)? == 1;
+        match reader.bits(2)? {
+            0 => {
+                reader.align();
+                let rest = &reader.data[reader.position..];
+                let [len_lo, len_hi, nlen_lo, nlen_hi, ..] = *rest else {
+                    return Err(INVALID);
+                };
+                let len = u16::from_le_bytes([len_lo, len_hi]);
+                if len != !u16::from_le_bytes([nlen_lo, nlen_hi]) {
+                    return Err(INVALID);
+                }
+                let stored = rest.get(4..4 + len as usize).ok_or(INVALID)?;
+                out.extend_from_slice(stored);
+                reader.position += 4 + len as usize;
+            }
+            1 => {
+                let (literal_code, distance_code) = fixed_codes();
+                inflate_block(&mut reader, &mut out, &literal_code, &distance_code)?;
+            }
+            2 => {
+                let (literal_code, distance_code) = dynamic_codes(&mut reader)?;
+                inflate_block(&mut reader, &mut out, &literal_code, &distance_code)?;
+            }
+            _ => return Err(INVALID),
+        }
+        if last {
+            break;
+        }
+    }
+
+    reader.align();
+    let checksum = reader
+        .data
+        .get(reader.position..reader.position + 4)
+        .ok_or(INVALID)?;
+    if u32::from_be_bytes([checksum[0], checksum[1], checksum[2], checksum[3]]) != adler32(&out) {
+        return Err(ImageError::Invalid("zlib checksum"));
+    }
+    Ok(out)
+}
+
+struct BitWriter {
+    out: Vec<u8>,
+    buf: u32,
+    buffered: u32,
+}
+
+impl BitWriter {
+    /// `count` bits of `value`, least significant first
+    fn bits(&mut self, value: u32, count: u32) {
+        self.buf |= value << self.buffered;
+        self.buffered += count;
+        while self.buffered >= 8 {
+            self.out.push(self.buf as u8);
+            self.buf >>= 8;
+            self.buffered -= 8;
+        }
+    }
+
+    /// Huffman code of `length` bits, most significant first
+    fn code(&mut self, code: u32, length: u32) {
+        self.bits(code.reverse_bits() >> (32 - length), length);
+    }
+
+    fn literal(&mut self, symbol: u32) {
+        match symbol {
+            0..=143 => self.code(0x30 + symbol, 8),
+            144..=255 => self.code(0x190 + symbol - 144, 9),
+            256..=279 => self.code(symbol - 256, 7),
+            _ => self.code(0xc0 + symbol - 280, 8),
+        }
+    }
+
+    fn copy(&mut self, length: usize, distance: usize) {
+        let index = LENGTH_BASE
+            .iter()
+            .rposition(|&base| base as usize <= length)
+            .unwrap();
+        self.literal(257 + index as u32);
+        self.bits(
+            (length - LENGTH_BASE[index] as usize) as u32,
+            LENGTH_EXTRA[index] as u32,
+        );
+
+        let index = DISTANCE_BASE
+            .iter()
+            .rposition(|&base| base as usize <= distance)
+            .unwrap();
+        self.code(index as u32, 5);
+        self.bits(
+            (distance - DISTANCE_BASE[index] as usize) as u32,
+            DISTANCE_EXTRA[index] as u32,
+        );
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.buffered > 0 {
+            self.out.push(self.buf as u8);
+        }
+        self.out
+    }
+}
+
+/// Compress into zlib stream with single fixed Huffman block and hash chain matching. Not as
+/// small as dynamic codes, but good enough for screenshots and icons.
+pub(crate) fn compress(data: &[u8]) -> Vec<u8> {
+    const HASH_SIZE: usize = 1 << 15;
+    const MAX_CHAIN: usize = 32;
+
+    let hash = |i: usize| {
+        let value = (data[i] as usize) << 16 | (data[i + 1] as usize) << 8 | data[i + 2] as usize;
+        (value.wrapping_mul(2654435761) >> 8) & (HASH_SIZE - 1)
+    };
+
+    let mut writer = BitWriter {
+        // Compression method 8 with 32K window, no dictionary, checked by (CMF * 256 + FLG) % 31
+        out: vec![0x78, 0x01],
+        buf: 0,
+        buffered: 0,
+    };
+    // Final block with fixed codes
+    writer.bits(1, 1);
+    writer.bits(1, 2);
+
+    // Most recent position with given hash and previous position with the same hash
+    let mut head = vec![usize::MAX; HASH_SIZE];
+    let mut previous = vec![usize::MAX; WINDOW_SIZE];
+    let insert = |i: usize, head: &mut [usize], previous: &mut [usize]| {
+        if i + MIN_MATCH <= data.len() {
+            let h = hash(i);
+            previous[i % WINDOW_SIZE] = head[h];
+            head[h] = i;
+        }
+    };
+
+    let mut i = 0;
+    while i < data.len() {
+        let mut best = (0, 0);
+        if i + MIN_MATCH <= data.len() {
+            let max_length = (data.len() - i).min(MAX_MATCH);
+            let mut candidate = head[hash(i)];
+            for _ in 0..MAX_CHAIN {
+                if candidate == usize::MAX || i - candidate > WINDOW_SIZE {
+                    break;
+                }
+                let length = data[candidate..]
+                    .iter()
+                    .zip(&data[i..i + max_length])
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                if length > best.0 {
+                    best = (length, i - candidate);
+                    if length == max_length {
+                        break;
+                    }
+                }
+                // Slots of positions inside of the window were not reused yet
+                candidate = previous[candidate % WINDOW_SIZE];
+            }
+        }
+
+        if best.0 >= MIN_MATCH {
+            writer.copy(best.0, best.1);
+            for j in i..i + best.0 {
+                insert(j, &mut head, &mut previous);
+            }
+            i += best.0;
+        } else {
+            writer.literal(data[i] as u32);
+            insert(i, &mut head, &mut previous);
+            i += 1;
+        }
+    }
+    writer.literal(256);
+
+    let mut out = writer.finish();
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+#[test]
+fn zlib_roundtrip() {
+    let mut data = b"just just just canvas ".repeat(100);
+    data.extend((0..5000u32).map(|i| (i * i % 251) as u8));
+    let compressed = compress(&data);
+    assert!(compressed.len() < data.len());
+    assert_eq!(decompress(&compressed).unwrap(), data);
+    assert_eq!(decompress(&compress(b"")).unwrap(), b"");
+
+    // Stored block from other encoders
+    let stored = [
+        0x78, 0x01, 0x01, 0x03, 0x00, 0xfc, 0xff, b'a', b'b', b'c', 0x02, 0x4d, 0x01, 0x27,
+    ];
+    assert_eq!(decompress(&stored).unwrap(), b"abc");
+}
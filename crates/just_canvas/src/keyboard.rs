@@ -1,7 +1,7 @@
 use just_x11::keysym::KeySym;
 
 /// Not a character
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum SpecialKeyboardButton {
     // TTY function keys
     BackSpace,
@@ -108,7 +108,7 @@ pub enum SpecialKeyboardButton {
     // TODO: Keypad functions
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum KeyboardButton {
     Special(SpecialKeyboardButton),
     Unicode(char),
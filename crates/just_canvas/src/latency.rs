@@ -0,0 +1,101 @@
+//! Input to photon latency, see [`crate::Canvas::start_latency_profiling`]
+//!
+//! Input events are timestamped when the canvas receives them. The first flush after an event
+//! presents the frame reflecting it, which becomes visible on the following vertical blank when
+//! vertical blanks are known, otherwise latency ends when the flush returns. Events followed by a
+//! frame that flushed nothing did not change anything visible and are not counted.
+
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// Only the latest samples are kept, so a long running profile does not grow without bounds
+const MAX_SAMPLES: usize = 10_000;
+
+/// Latency percentiles of profiled events
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencyReport {
+    /// Number of events the percentiles are computed from
+    pub samples: usize,
+    pub p50: Duration,
+    pub p90: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LatencyProfiler {
+    /// Arrival of input events not presented yet
+    pending: Vec<Instant>,
+    samples: VecDeque<Duration>,
+}
+
+impl LatencyProfiler {
+    /// Forget events from the previous frame if it did not flush anything
+    pub(crate) fn begin_frame(&mut self) {
+        self.pending.clear();
+    }
+
+    pub(crate) fn input(&mut self, received: Instant) {
+        self.pending.push(received);
+    }
+
+    /// Frame reflecting all pending events becomes visible at `visible`
+    pub(crate) fn presented(&mut self, visible: Instant) {
+        for received in self.pending.drain(..) {
+            if self.samples.len() == MAX_SAMPLES {
+                self.samples.pop_front();
+            }
+            self.samples
+                .push_back(visible.saturating_duration_since(received));
+        }
+    }
+
+    /// `None` if no event was presented yet
+    pub(crate) fn report(&self) -> Option<LatencyReport> {
+        let mut sorted = self.samples.iter().copied().collect::<Vec<_>>();
+        sorted.sort_unstable();
+        let max = *sorted.last()?;
+        // Nearest rank
+        let percentile = |p: usize| sorted[(sorted.len() * p).div_ceil(100).max(1) - 1];
+        Some(LatencyReport {
+            samples: sorted.len(),
+            p50: percentile(50),
+            p90: percentile(90),
+            p99: percentile(99),
+            max,
+        })
+    }
+}
+
+#[test]
+fn latency_percentiles() {
+    let start = Instant::now();
+    let ms = Duration::from_millis;
+    let mut profiler = LatencyProfiler::default();
+    assert_eq!(profiler.report(), None);
+
+    // Events of a frame that flushed nothing are dropped
+    profiler.input(start);
+    profiler.begin_frame();
+    profiler.presented(start + ms(500));
+    assert_eq!(profiler.report(), None);
+
+    for latency in 1..=100 {
+        profiler.begin_frame();
+        profiler.input(start + ms(1000 * latency));
+        profiler.input(start + ms(1000 * latency + 1));
+        profiler.presented(start + ms(1000 * latency + latency + 1));
+    }
+    assert_eq!(
+        profiler.report(),
+        Some(LatencyReport {
+            samples: 200,
+            p50: ms(51),
+            p90: ms(91),
+            p99: ms(100),
+            max: ms(101),
+        })
+    );
+}
@@ -6,25 +6,116 @@
 )]
 
 use backend::shared_bitmap;
+pub use just_geometry::{Rect, Vector2};
+use just_x11::keysym::KeySym;
+use just_x11_simple::clipboard::Offer;
 use keyboard::KeyboardButton;
 
-use crate::backend::{owned_bitmap::OwnedBitmapBackend, x11_mit_shm::X11MitShmBackend, Backend};
+use crate::{
+    backend::{
+        owned_bitmap::OwnedBitmapBackend, supersampled::SupersampledBackend,
+        x11_mit_shm::X11MitShmBackend, Backend,
+    },
+    frame::FrameClock,
+    latency::{LatencyProfiler, LatencyReport},
+    record::{PlaybackBackend, Recorder, Recording},
+};
 use std::{
     cmp,
     fmt::Debug,
-    ops::{Add, Sub},
+    time::{Duration, Instant},
 };
 
 mod backend;
 pub mod draw;
+pub mod frame;
+pub mod image;
 pub mod keyboard;
+pub mod latency;
+#[cfg(feature = "raster3d")]
+pub mod raster3d;
+pub mod record;
 
 pub const BYTES_PER_PIXEL: u32 = 4;
 
+/// Order of channels of a pixel in memory, [`BYTES_PER_PIXEL`] bytes each
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelFormat {
+    /// `B, G, R, A`, i.e. `0xAARRGGBB` little endian, layout of most X11 TrueColor visuals
+    Bgra8888,
+    /// `R, G, B, A`, i.e. `0xAABBGGRR` little endian, layout of PNG and QOI
+    Rgba8888,
+}
+
+impl PixelFormat {
+    /// Format of little endian pixels of a visual with given masks, `None` if channels are not
+    /// whole bytes in one of the supported orders
+    pub fn from_visual_masks(red_mask: u32, green_mask: u32, blue_mask: u32) -> Option<Self> {
+        match (red_mask, green_mask, blue_mask) {
+            (0xff0000, 0xff00, 0xff) => Some(Self::Bgra8888),
+            (0xff, 0xff00, 0xff0000) => Some(Self::Rgba8888),
+            _ => None,
+        }
+    }
+
+    #[inline(always)]
+    pub fn encode(self, color: Color) -> [u8; 4] {
+        match self {
+            Self::Bgra8888 => [color.b, color.g, color.r, color.a],
+            Self::Rgba8888 => [color.r, color.g, color.b, color.a],
+        }
+    }
+
+    /// `pixel` must be at least [`BYTES_PER_PIXEL`] long
+    #[inline(always)]
+    pub fn decode(self, pixel: &[u8]) -> Color {
+        match self {
+            Self::Bgra8888 => Color::from_components(pixel[3], pixel[2], pixel[1], pixel[0]),
+            Self::Rgba8888 => Color::from_components(pixel[3], pixel[0], pixel[1], pixel[2]),
+        }
+    }
+
+    /// Rewrite pixels in `buf` from this format to `target` in place
+    pub fn convert(self, target: Self, buf: &mut [u8]) {
+        if self == target {
+            return;
+        }
+        // Both formats differ only by swapped red and blue
+        buf.chunks_exact_mut(BYTES_PER_PIXEL as usize)
+            .for_each(|pixel| pixel.swap(0, 2));
+    }
+}
+
+/// Offset of [`Canvas::open_tooltip`] from the pointer, so the pointer doesn't cover it
+const TOOLTIP_OFFSET: Vector2<i32> = Vector2 { x: 12, y: 16 };
+
+/// Soft shadow around a popup, see [`Canvas::open_popup_with_shadow`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PopupShadow {
+    /// Distance over which the shadow fades out around the edges of the popup
+    pub radius: u32,
+    /// Shift of the shadow relative to the popup, positive `y` casts it downwards
+    pub offset: Vector2<i32>,
+    /// Color of the darkest part of the shadow, usually black with partial alpha
+    pub color: Color,
+}
+
+impl Default for PopupShadow {
+    fn default() -> Self {
+        Self {
+            radius: 8,
+            offset: Vector2 { x: 0, y: 3 },
+            color: Color::from_raw(0x50000000),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum CanvasError {
     X11ProtocolError(just_x11::error::Error),
     SharedBitmapError(shared_bitmap::Error),
+    DisplayError(just_x11::display::DisplayError),
+    ImageError(image::ImageError),
 }
 
 impl From<just_x11::error::Error> for CanvasError {
@@ -33,6 +124,18 @@ impl From<just_x11::error::Error> for CanvasError {
     }
 }
 
+impl From<just_x11::display::DisplayError> for CanvasError {
+    fn from(err: just_x11::display::DisplayError) -> Self {
+        Self::DisplayError(err)
+    }
+}
+
+impl From<image::ImageError> for CanvasError {
+    fn from(err: image::ImageError) -> Self {
+        Self::ImageError(err)
+    }
+}
+
 pub type Result<T> = core::result::Result<T, CanvasError>;
 
 #[derive(Debug)]
@@ -95,21 +198,97 @@ impl ButtonMask {
     }
 }
 
+/// Master device that generated an input event. Multi-pointer setups have more than one pair of
+/// master pointer and keyboard, otherwise all events come from the same device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct InputDevice {
+    pub id: u16,
+}
+
+impl InputDevice {
+    /// Events of the core protocol, which does not tell devices apart, and of backends without
+    /// input devices
+    pub const CORE: Self = Self { id: 0 };
+}
+
+/// Pointer position reported by one motion event, see [`Pointer::motion_history`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MotionSample {
+    pub position: Vector2<u32>,
+    /// Server time of the event in milliseconds, wraps around after about 49.7 days
+    pub time: u32,
+}
+
 #[derive(Debug)]
 pub struct Pointer {
     pub position: Vector2<u32>,
+    /// Device that moved or pressed the pointer last
+    device: InputDevice,
     pressed_mask: ButtonMask,
     clicked_this_frame: ButtonMask,
+    /// Pressed since the last [`Canvas::process_events`]
+    pressed_this_frame: ButtonMask,
+    /// When each button was last pressed, indexed by [`PointerButton`]
+    pressed_at: [Option<Instant>; 5],
+    pressure: f32,
+    tilt: Vector2<f32>,
+    /// Motion since the last [`Canvas::process_events`]
+    motion_history: Vec<MotionSample>,
 }
 
 impl Pointer {
     #[inline]
     /// Pointer state at `x = 0, y = 0` with no buttons pressed
-    fn new() -> Self {
+    fn new(device: InputDevice) -> Self {
         Self {
             position: Vector2 { x: 0, y: 0 },
+            device,
             pressed_mask: ButtonMask::new(),
             clicked_this_frame: ButtonMask::new(),
+            pressed_this_frame: ButtonMask::new(),
+            pressed_at: [None; 5],
+            pressure: 1.0,
+            tilt: Vector2 { x: 0.0, y: 0.0 },
+            motion_history: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn device(&self) -> InputDevice {
+        self.device
+    }
+
+    /// Release buttons that were clicked in the previous frame
+    fn begin_frame(&mut self) {
+        for n in 0..u8::MAX {
+            if self.clicked_this_frame.buttons.get(n as usize) {
+                self.pressed_mask.buttons.clear(n as usize);
+                self.clicked_this_frame.buttons.clear(n as usize);
+            }
+        }
+        self.pressed_this_frame = ButtonMask::new();
+        self.motion_history.clear();
+    }
+
+    fn motion(&mut self, position: Vector2<u32>, time: u32) {
+        self.position = position;
+        self.motion_history.push(MotionSample { position, time });
+    }
+
+    fn press(&mut self, button: PointerButton, at: Instant) {
+        self.pressed_this_frame.set_pressed(button);
+        self.set_pressed(button);
+        self.pressed_at[button as usize] = Some(at);
+    }
+
+    // NOTE: During quick clicks pressed and released event may come in one frame
+    // thus we keep track of these and release after rendering so the user code
+    // can detect the click. This assumes that release event will come after press
+    fn release(&mut self, button: PointerButton) {
+        if self.pressed_this_frame.is_pressed(button) {
+            self.clicked_this_frame.set_pressed(button);
+        } else {
+            self.set_released(button);
         }
     }
 
@@ -127,24 +306,176 @@ impl Pointer {
     pub fn is_pressed(&self, key: PointerButton) -> bool {
         self.pressed_mask.is_pressed(key)
     }
+
+    #[inline]
+    /// When `key` was pressed if it's still held. Taken when the press event is processed, so it
+    /// does not depend on how long the frame takes to draw.
+    pub fn pressed_at(&self, key: PointerButton) -> Option<Instant> {
+        self.pressed_at[key as usize].filter(|_| self.is_pressed(key))
+    }
+
+    #[inline]
+    /// Positions of all motion events received by the last [`Canvas::process_events`] in order,
+    /// the last one is [`Pointer::position`]. Drawing apps can connect them to keep the shape of
+    /// fast strokes at low frame rates. Empty if the pointer did not move.
+    pub fn motion_history(&self) -> &[MotionSample] {
+        &self.motion_history
+    }
+
+    #[inline]
+    /// Pen pressure from `0.0` to `1.0` at the last event, always `1.0` for devices without
+    /// pressure like mice
+    pub fn pressure(&self) -> f32 {
+        self.pressure
+    }
+
+    #[inline]
+    /// Pen tilt from `-1.0` to `1.0` on each axis at the last event, zero for devices without tilt
+    pub fn tilt(&self) -> Vector2<f32> {
+        self.tilt
+    }
 }
 
 pub enum BackendType {
     X11MitShm,
-    Bitmap { size: Vector2<u32> },
+    Bitmap {
+        size: Vector2<u32>,
+    },
+    /// Bitmap receiving events from a recording, see [`Canvas::start_recording`]
+    Playback {
+        size: Vector2<u32>,
+        recording: Recording,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visibility {
+    Unobscured,
+    PartiallyObscured,
+    FullyObscured,
+    /// Window is not mapped, e.g. minimized or on other workspace
+    Unmapped,
+}
+
+/// Change of the window passed to [`Canvas::on_lifecycle`] callbacks, derived from
+/// [`Visibility`] changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lifecycle {
+    /// Part of the window can be seen again
+    Shown,
+    /// Window got fully covered or unmapped, background work only drawn to the window can pause
+    Hidden,
+    /// Window was unmapped, e.g. minimized or on other workspace, follows [`Lifecycle::Hidden`]
+    Minimized,
+    /// Window was mapped again, [`Lifecycle::Shown`] follows unless it is fully covered
+    Restored,
+}
+
+impl Lifecycle {
+    /// Changes from `from` to `to` in the order they are reported
+    fn transitions(from: Visibility, to: Visibility) -> impl Iterator<Item = Self> {
+        let visible = |visibility| {
+            matches!(
+                visibility,
+                Visibility::Unobscured | Visibility::PartiallyObscured
+            )
+        };
+        let mapped = |visibility| visibility != Visibility::Unmapped;
+        [
+            (!mapped(from) && mapped(to), Self::Restored),
+            (!visible(from) && visible(to), Self::Shown),
+            (visible(from) && !visible(to), Self::Hidden),
+            (mapped(from) && !mapped(to), Self::Minimized),
+        ]
+        .into_iter()
+        .filter_map(|(changed, lifecycle)| changed.then_some(lifecycle))
+    }
+}
+
+/// Answer of [`Canvas::set_on_close_requested`] hook
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloseRequest {
+    Close,
+    /// Keep the window open, e.g. to ask user to save changes first
+    Cancel,
 }
 
 #[derive(Debug)]
 pub enum KeyboardEvent {
-    Pressed(KeyboardButton),
-    Released(KeyboardButton),
+    Pressed(KeyboardButton, InputDevice),
+    Released(KeyboardButton, InputDevice),
+}
+
+impl KeyboardEvent {
+    /// Master keyboard that generated the event
+    #[inline]
+    pub fn device(&self) -> InputDevice {
+        match self {
+            KeyboardEvent::Pressed(_, device) | KeyboardEvent::Released(_, device) => *device,
+        }
+    }
+}
+
+/// Part of the window changed since the last flush, see [`Canvas::mark_damaged`]
+#[derive(Debug, Clone, Copy)]
+pub struct DamageRect {
+    pub position: Vector2<u32>,
+    pub size: Vector2<u32>,
+}
+
+impl DamageRect {
+    /// Rectangle at `position` clipped to non-negative coordinates
+    pub fn new(position: Vector2<i32>, size: Vector2<u32>) -> Self {
+        let clipped = position.clamp_non_negative();
+        Self {
+            position: clipped.as_u32(),
+            size: Vector2 {
+                x: size.x.saturating_sub((clipped.x - position.x) as u32),
+                y: size.y.saturating_sub((clipped.y - position.y) as u32),
+            },
+        }
+    }
+
+    #[inline(always)]
+    fn rect(self) -> Rect {
+        Rect::new(self.position.as_i32(), self.size)
+    }
+
+    #[inline(always)]
+    fn from_rect(rect: Rect) -> Self {
+        Self::new(rect.position, rect.size)
+    }
+
+    /// Smallest rectangle containing both
+    pub fn union(self, other: Self) -> Self {
+        Self::from_rect(self.rect().union(other.rect()))
+    }
+
+    fn clip(self, window_size: Vector2<u32>) -> Option<Self> {
+        self.rect()
+            .intersection(Rect::new(Vector2::<i32>::zero(), window_size))
+            .map(Self::from_rect)
+    }
 }
 
 pub struct Canvas {
     backend: Box<dyn Backend>,
+    /// Moved by all master pointers
     pointer: Pointer,
+    /// One per master pointer that sent any event
+    pointers: Vec<Pointer>,
     resized: bool,
     should_close: bool,
+    on_close_requested: Option<Box<dyn FnMut() -> CloseRequest>>,
+    on_shutdown: Vec<Box<dyn FnOnce()>>,
+    on_lifecycle: Vec<Box<dyn FnMut(Lifecycle)>>,
+    bell: Option<u8>,
+    visibility: Visibility,
+    pointer_captured: bool,
+    recorder: Option<Recorder>,
+    damage: Option<DamageRect>,
+    frame_clock: FrameClock,
+    latency: Option<LatencyProfiler>,
     pub keyboard_events: Vec<KeyboardEvent>,
 }
 
@@ -159,9 +490,20 @@ impl Canvas {
     fn with_backend(backend: Box<dyn Backend>) -> Self {
         Self {
             backend,
-            pointer: Pointer::new(),
+            pointer: Pointer::new(InputDevice::CORE),
+            pointers: Vec::new(),
             resized: false,
             should_close: false,
+            on_close_requested: None,
+            on_shutdown: Vec::new(),
+            on_lifecycle: Vec::new(),
+            bell: None,
+            visibility: Visibility::Unobscured,
+            pointer_captured: false,
+            recorder: None,
+            damage: None,
+            frame_clock: FrameClock::default(),
+            latency: None,
             keyboard_events: Vec::new(),
         }
     }
@@ -171,15 +513,99 @@ impl Canvas {
         let backend: Box<dyn Backend> = match backend {
             BackendType::X11MitShm => Box::new(X11MitShmBackend::new(title)?),
             BackendType::Bitmap { size } => Box::new(OwnedBitmapBackend::new(size)),
+            BackendType::Playback { size, recording } => {
+                Box::new(PlaybackBackend::new(size, recording))
+            }
         };
         Ok(Self::with_backend(backend))
     }
 
+    /// Draw into a buffer `factor` times larger than the window in each dimension, downscaled
+    /// with box filter on flush. [`Canvas::window_size`], pointer position and damage are in
+    /// buffer pixels. Drawing at integer scale `3` with `factor` `2` gives crisp 1.5x UI.
+    pub fn supersampled(self, factor: u32) -> Self {
+        if factor <= 1 {
+            return self;
+        }
+        Self {
+            backend: Box::new(SupersampledBackend::new(self.backend, factor)),
+            ..self
+        }
+    }
+
+    /// Present frames from a separate thread with its own connection to the server, so
+    /// [`Canvas::flush`] only copies the frame into one of three buffers instead of waiting for
+    /// the server to read it. Frames flushed faster than they are presented are skipped. Moving
+    /// presented pixels is not available then, so [`Canvas::scroll`] damages the whole area.
+    /// Returns `false` if the backend presents without a server, e.g. bitmaps.
+    pub fn present_in_background(&mut self) -> Result<bool> {
+        self.backend.present_in_background()
+    }
+
+    /// Borderless window at `position` relative to this one, e.g. for menus. Unlike drawing
+    /// over this window it is not clipped by its bounds and it is moved to stay on the screen.
+    /// Popup has its own buffer and events, and closes when dropped.
+    pub fn open_popup(&mut self, position: Vector2<i32>, size: Vector2<u32>) -> Result<Canvas> {
+        let backend = self.backend.open_popup(position, size, None)?;
+        Ok(Self::with_backend(backend))
+    }
+
+    /// Popup with `shadow` around it, see [`Canvas::open_popup`]. The shadow needs a compositor
+    /// to blend it with windows below, without one the popup opens without shadow. Popup size,
+    /// position and events exclude the shadow.
+    pub fn open_popup_with_shadow(
+        &mut self,
+        position: Vector2<i32>,
+        size: Vector2<u32>,
+        shadow: PopupShadow,
+    ) -> Result<Canvas> {
+        let backend = self.backend.open_popup(position, size, Some(shadow))?;
+        Ok(Self::with_backend(backend))
+    }
+
+    /// Popup next to the pointer, see [`Canvas::open_popup`]
+    pub fn open_tooltip(&mut self, size: Vector2<u32>) -> Result<Canvas> {
+        let position = self.pointer.position.as_i32() + TOOLTIP_OFFSET;
+        self.open_popup(position, size)
+    }
+
     #[inline]
+    /// Pointer moved by any of the master pointers, enough unless multiple people share the screen
     pub fn pointer(&self) -> &Pointer {
         &self.pointer
     }
 
+    #[inline]
+    /// Separate state of each master pointer, in order of their first events. Empty until the
+    /// first pointer event arrives.
+    pub fn pointers(&self) -> &[Pointer] {
+        &self.pointers
+    }
+
+    #[inline]
+    pub fn pointer_of(&self, device: InputDevice) -> Option<&Pointer> {
+        self.pointers
+            .iter()
+            .find(|pointer| pointer.device == device)
+    }
+
+    /// Shared pointer and the one of `device`
+    fn device_pointers(&mut self, device: InputDevice) -> [&mut Pointer; 2] {
+        let idx = match self
+            .pointers
+            .iter()
+            .position(|pointer| pointer.device == device)
+        {
+            Some(idx) => idx,
+            None => {
+                self.pointers.push(Pointer::new(device));
+                self.pointers.len() - 1
+            }
+        };
+        self.pointer.device = device;
+        [&mut self.pointer, &mut self.pointers[idx]]
+    }
+
     #[inline]
     pub fn resized(&self) -> bool {
         self.resized
@@ -190,6 +616,144 @@ impl Canvas {
         self.should_close
     }
 
+    #[inline]
+    /// Called when window manager or [`Canvas::request_close`] asks to close the window
+    pub fn set_on_close_requested<F>(&mut self, hook: F)
+    where
+        F: FnMut() -> CloseRequest + 'static,
+    {
+        self.on_close_requested = Some(Box::new(hook));
+    }
+
+    #[inline]
+    /// Called once when canvas is about to close, in order of registration
+    pub fn on_shutdown<F>(&mut self, callback: F)
+    where
+        F: FnOnce() + 'static,
+    {
+        self.on_shutdown.push(Box::new(callback));
+    }
+
+    #[inline]
+    /// Called from [`Canvas::process_events`] when the window is shown, hidden, minimized or
+    /// restored, in order of registration. Unlike checking [`Canvas::is_visible`] before drawing,
+    /// it lets applications pause work that is not drawing, e.g. polling or simulation.
+    pub fn on_lifecycle<F>(&mut self, callback: F)
+    where
+        F: FnMut(Lifecycle) + 'static,
+    {
+        self.on_lifecycle.push(Box::new(callback));
+    }
+
+    /// Ask to close the window, same as closing it from window manager. Returns `false` if
+    /// [`Canvas::set_on_close_requested`] hook cancelled it.
+    pub fn request_close(&mut self) -> bool {
+        let request = self
+            .on_close_requested
+            .as_mut()
+            .map_or(CloseRequest::Close, |hook| hook());
+        if request == CloseRequest::Close {
+            self.close();
+        }
+        request == CloseRequest::Close
+    }
+
+    /// Close the window without asking [`Canvas::set_on_close_requested`] hook
+    pub fn close(&mut self) {
+        if self.should_close {
+            return;
+        }
+
+        self.should_close = true;
+        for callback in self.on_shutdown.drain(..) {
+            callback();
+        }
+    }
+
+    #[inline]
+    pub fn visibility(&self) -> Visibility {
+        self.visibility
+    }
+
+    #[inline]
+    /// Whether any part of the window can be seen, drawing can be skipped otherwise
+    pub fn is_visible(&self) -> bool {
+        matches!(
+            self.visibility,
+            Visibility::Unobscured | Visibility::PartiallyObscured
+        )
+    }
+
+    #[inline]
+    /// Volume of the keyboard bell if it was rung since last [`Canvas::process_events`]
+    pub fn bell(&self) -> Option<u8> {
+        self.bell
+    }
+
+    #[inline]
+    /// Ring the keyboard bell, `percent` is relative to the base volume in `-100..=100` range
+    pub fn ring_bell(&mut self, percent: i8) -> Result<()> {
+        self.backend.bell(percent)
+    }
+
+    #[inline]
+    /// Move pointer to `position` relative to the window
+    pub fn warp_pointer_to(&mut self, position: Vector2<u32>) -> Result<()> {
+        self.backend.warp_pointer(position)?;
+        let device = self.pointer.device;
+        for pointer in self.device_pointers(device) {
+            pointer.position = position;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    /// Hide pointer and keep it inside of the window, e.g. for infinite dragging. Combine with
+    /// [`Canvas::warp_pointer_to`] to keep pointer away from window edges.
+    /// Returns `false` if capture failed, e.g. because other client grabbed the pointer.
+    pub fn set_pointer_capture(&mut self, captured: bool) -> Result<bool> {
+        let res = self.backend.set_pointer_capture(captured)?;
+        self.pointer_captured = captured && res;
+        Ok(res)
+    }
+
+    #[inline]
+    pub fn pointer_captured(&self) -> bool {
+        self.pointer_captured
+    }
+
+    #[inline]
+    /// Record all events received from now on, restarting recording if it is already running
+    pub fn start_recording(&mut self) {
+        self.recorder = Some(Recorder::new());
+    }
+
+    #[inline]
+    /// Returns `None` if [`Canvas::start_recording`] was not called
+    pub fn stop_recording(&mut self) -> Option<Recording> {
+        self.recorder.take().map(Recorder::finish)
+    }
+
+    #[inline]
+    /// Measure time from receiving each input event to presenting the frame reflecting it,
+    /// restarting profiling if it is already running. See [`latency`] for what is measured.
+    pub fn start_latency_profiling(&mut self) {
+        self.latency = Some(LatencyProfiler::default());
+    }
+
+    #[inline]
+    /// Latencies measured so far, `None` if profiling is not running or no event was presented
+    pub fn latency_report(&self) -> Option<LatencyReport> {
+        self.latency.as_ref()?.report()
+    }
+
+    #[inline]
+    /// Returns `None` if [`Canvas::start_latency_profiling`] was not called or no event was
+    /// presented
+    pub fn stop_latency_profiling(&mut self) -> Option<LatencyReport> {
+        self.latency.take()?.report()
+    }
+
     #[inline]
     pub fn raw_buf_mut(&mut self) -> &mut [u8] {
         self.backend.buf_mut()
@@ -200,66 +764,299 @@ impl Canvas {
         self.backend.buf()
     }
 
+    #[inline]
+    /// Layout of pixels in [`Canvas::raw_buf`]
+    pub fn pixel_format(&self) -> PixelFormat {
+        self.backend.pixel_format()
+    }
+
+    /// Copy of the drawn window contents. Alpha is ignored by the window, so all pixels are
+    /// opaque.
+    pub fn capture(&self) -> image::Image {
+        let mut image =
+            image::Image::from_raw(self.window_size(), self.pixel_format(), self.raw_buf())
+                .expect("Backend buffer matches its size");
+        for pixel in image.pixels.iter_mut() {
+            pixel.a = 255;
+        }
+        image
+    }
+
+    /// Own the clipboard with `image` encoded in every [`image::ImageFormat`], until other
+    /// client takes it. Returns `false` if the backend has no clipboard.
+    pub fn copy_image(&mut self, image: &image::Image) -> Result<bool> {
+        let offers = image::ImageFormat::ALL
+            .into_iter()
+            .map(|format| Offer {
+                target: format.mime_type().to_string(),
+                data: image.encode(format),
+            })
+            .collect();
+        self.backend.copy_to_clipboard(offers)
+    }
+
+    /// Image in the clipboard, `None` if the clipboard is empty or has no image in a format that
+    /// can be decoded
+    pub fn paste_image(&mut self) -> Result<Option<image::Image>> {
+        let targets = image::ImageFormat::ALL.map(|format| format.mime_type());
+        let Some((_, data)) = self.backend.paste_from_clipboard(&targets)? else {
+            return Ok(None);
+        };
+        Ok(Some(image::Image::decode(&data)?))
+    }
+
     pub fn process_events(&mut self) -> Result<()> {
         self.resized = false;
+        self.bell = None;
 
-        // FIXME
+        self.pointer.begin_frame();
+        self.pointers.iter_mut().for_each(Pointer::begin_frame);
 
-        for n in 0..u8::MAX {
-            if self.pointer.clicked_this_frame.buttons.get(n as usize) {
-                self.pointer.pressed_mask.buttons.clear(n as usize);
-                self.pointer.clicked_this_frame.buttons.clear(n as usize);
+        let events = self.backend.events()?;
+        let events_received = Instant::now();
+        if let Some(recorder) = &mut self.recorder {
+            events.iter().for_each(|event| recorder.record(event));
+            recorder.next_frame();
+        }
+        if let Some(latency) = &mut self.latency {
+            latency.begin_frame();
+            for event in &events {
+                if event.is_input() {
+                    latency.input(events_received);
+                }
             }
         }
 
-        // NOTE: During quick clicks pressed and released event may come in one frame
-        // thus we keep track of these and release after rendering so the user code
-        // can detect the click. This assumes that release event will come after press
-        let mut pressed_this_frame = ButtonMask::new();
-
-        for event in self.backend.events()? {
+        for event in events {
             match event {
                 Event::Resize { new_size } => {
                     self.backend.resize(new_size)?;
                     self.resized = true;
                 }
-                Event::PointerButtonPress { button } => {
-                    pressed_this_frame.set_pressed(button);
-                    self.pointer.set_pressed(button);
+                Event::PointerButtonPress { device, button } => {
+                    for pointer in self.device_pointers(device) {
+                        pointer.press(button, events_received);
+                    }
                 }
-                Event::PointerButtonRelease { button } => {
-                    if pressed_this_frame.is_pressed(button) {
-                        self.pointer.clicked_this_frame.set_pressed(button);
-                    } else {
-                        self.pointer.set_released(button);
+                Event::PointerButtonRelease { device, button } => {
+                    for pointer in self.device_pointers(device) {
+                        pointer.release(button);
                     }
                 }
-                Event::PointerMotion { position } => {
-                    self.pointer.position = position;
+                Event::PointerMotion {
+                    device,
+                    position,
+                    time,
+                } => {
+                    for pointer in self.device_pointers(device) {
+                        pointer.motion(position, time);
+                    }
                 }
-                Event::KeyboardButtonPress { button } => {
-                    self.keyboard_events.push(KeyboardEvent::Pressed(button));
+                Event::PointerAxes {
+                    device,
+                    pressure,
+                    tilt,
+                } => {
+                    for pointer in self.device_pointers(device) {
+                        pointer.pressure = pressure;
+                        pointer.tilt = tilt;
+                    }
                 }
-                Event::KeyboardButtonRelease { button } => {
-                    self.keyboard_events.push(KeyboardEvent::Released(button));
+                Event::KeyboardButtonPress { device, button, .. } => {
+                    self.keyboard_events
+                        .push(KeyboardEvent::Pressed(button, device));
+                }
+                Event::KeyboardButtonRelease { device, button, .. } => {
+                    self.keyboard_events
+                        .push(KeyboardEvent::Released(button, device));
                 }
                 Event::Shutdown => {
-                    self.should_close = true;
+                    self.request_close();
+                }
+                Event::Bell { percent } => {
+                    self.bell = Some(percent);
+                }
+                Event::VisibilityChange { visibility } => {
+                    for lifecycle in Lifecycle::transitions(self.visibility, visibility) {
+                        for callback in self.on_lifecycle.iter_mut() {
+                            callback(lifecycle);
+                        }
+                    }
+                    self.visibility = visibility;
                 }
             }
         }
+        for vblank in self.backend.vblanks() {
+            self.frame_clock.vblank(vblank, events_received);
+        }
 
         Ok(())
     }
 
+    /// When the next frame should be drawn. With X11 Present that is the next vertical blank
+    /// of the monitor showing the window, otherwise a steady timer ticking every `interval`.
+    /// Call once per frame after drawing, vertical blank is received by the following
+    /// [`Canvas::process_events`].
+    pub fn request_frame(&mut self, interval: Duration) -> Result<Instant> {
+        self.backend.request_vblank()?;
+        Ok(self.frame_clock.next_frame(Instant::now(), interval))
+    }
+
     #[inline]
     pub fn window_size(&self) -> Vector2<u32> {
         self.backend.size()
     }
 
+    /// Present only the union of damaged areas on the next [`Canvas::flush`]. Without any
+    /// damage, or after resize, whole window is presented.
+    pub fn mark_damaged(&mut self, damage: DamageRect) {
+        self.damage = Some(match self.damage {
+            Some(previous) => previous.union(damage),
+            None => damage,
+        });
+    }
+
+    /// Move contents of the `size` area at `position` by `delta`, e.g. to scroll a list without
+    /// drawing all of it again. Pixels moved out of the area are dropped and the uncovered part
+    /// keeps its old pixels, so it has to be drawn over. Call it before drawing into the area in
+    /// a frame.
+    ///
+    /// With backends that can move already presented pixels, like X11, only the uncovered part
+    /// and parts the server could not copy are damaged, otherwise whole area. Like with
+    /// [`Canvas::mark_damaged`], everything else drawn in the frame has to be damaged as well.
+    pub fn scroll(
+        &mut self,
+        position: Vector2<u32>,
+        size: Vector2<u32>,
+        delta: Vector2<i32>,
+    ) -> Result<()> {
+        let Some(area) = (DamageRect { position, size }).clip(self.window_size()) else {
+            return Ok(());
+        };
+        if delta.x == 0 && delta.y == 0 {
+            return Ok(());
+        }
+        let shift = Vector2 {
+            x: delta.x.unsigned_abs(),
+            y: delta.y.unsigned_abs(),
+        };
+        if shift.x >= area.size.x || shift.y >= area.size.y {
+            self.mark_damaged(area);
+            return Ok(());
+        }
+
+        let copy_size = Vector2 {
+            x: area.size.x - shift.x,
+            y: area.size.y - shift.y,
+        };
+        let source = Vector2 {
+            x: area.position.x + (-delta.x).max(0) as u32,
+            y: area.position.y + (-delta.y).max(0) as u32,
+        };
+        let destination = Vector2 {
+            x: area.position.x + delta.x.max(0) as u32,
+            y: area.position.y + delta.y.max(0) as u32,
+        };
+
+        let stride = (self.window_size().x * BYTES_PER_PIXEL) as usize;
+        let row_len = (copy_size.x * BYTES_PER_PIXEL) as usize;
+        let buf = self.backend.buf_mut();
+        let mut copy_row = |row: u32| {
+            let from = (source.y + row) as usize * stride + (source.x * BYTES_PER_PIXEL) as usize;
+            let to = (destination.y + row) as usize * stride
+                + (destination.x * BYTES_PER_PIXEL) as usize;
+            buf.copy_within(from..from + row_len, to);
+        };
+        // Rows are moved in the direction of the scroll so none is overwritten before it's moved
+        if delta.y > 0 {
+            (0..copy_size.y).rev().for_each(&mut copy_row);
+        } else {
+            (0..copy_size.y).for_each(&mut copy_row);
+        }
+
+        let copied = if self.resized {
+            // Whole window is presented anyway
+            None
+        } else {
+            self.backend
+                .copy_presented(source, destination, copy_size)?
+        };
+        let Some(exposed) = copied else {
+            self.mark_damaged(area);
+            return Ok(());
+        };
+
+        // Pixels damaged earlier in the frame were moved onto the window stale
+        if let Some(damage) = self.damage {
+            self.mark_damaged(DamageRect::new(
+                damage.position.as_i32() + delta,
+                damage.size,
+            ));
+        }
+        for damage in exposed {
+            self.mark_damaged(damage);
+        }
+        // Uncovered columns and rows
+        if shift.x > 0 {
+            let x = if delta.x > 0 {
+                area.position.x
+            } else {
+                area.position.x + copy_size.x
+            };
+            self.mark_damaged(DamageRect {
+                position: Vector2 {
+                    x,
+                    y: area.position.y,
+                },
+                size: Vector2 {
+                    x: shift.x,
+                    y: area.size.y,
+                },
+            });
+        }
+        if shift.y > 0 {
+            let y = if delta.y > 0 {
+                area.position.y
+            } else {
+                area.position.y + copy_size.y
+            };
+            self.mark_damaged(DamageRect {
+                position: Vector2 {
+                    x: area.position.x,
+                    y,
+                },
+                size: Vector2 {
+                    x: area.size.x,
+                    y: shift.y,
+                },
+            });
+        }
+
+        Ok(())
+    }
+
     #[inline]
+    /// Damage marked since the last flush, the next flush presents whole window
+    pub fn take_damage(&mut self) -> Option<DamageRect> {
+        self.damage.take()
+    }
+
     pub fn flush(&mut self) -> Result<()> {
-        self.backend.flush_window()
+        let damage = self.damage.take().filter(|_| !self.resized);
+        match damage {
+            Some(damage) => match damage.clip(self.backend.size()) {
+                Some(damage) => self.backend.flush_region(damage.position, damage.size)?,
+                // Nothing visible changed
+                None => return Ok(()),
+            },
+            None => self.backend.flush_window()?,
+        }
+
+        if let Some(latency) = &mut self.latency {
+            let flushed = Instant::now();
+            latency.presented(self.frame_clock.next_vblank(flushed).unwrap_or(flushed));
+        }
+        Ok(())
     }
 }
 
@@ -273,6 +1070,9 @@ pub enum PointerButton {
 }
 
 impl PointerButton {
+    /// `button` must be a logical button. Server applies the pointer mapping, e.g. swapped
+    /// buttons of left-handed users, before reporting core events and XI2 events of master
+    /// devices, so their buttons must not be translated with `GetPointerMapping` again.
     fn from_x11(button: just_x11::events::PointerButton) -> Option<Self> {
         match button {
             just_x11::events::PointerButton::Left => Some(PointerButton::Left),
@@ -284,20 +1084,66 @@ impl PointerButton {
     }
 }
 
-// TODO: Transalte button codes
-
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) enum Event {
-    Resize { new_size: Vector2<u32> },
-    PointerButtonPress { button: PointerButton },
-    PointerButtonRelease { button: PointerButton },
-    PointerMotion { position: Vector2<u32> },
-    KeyboardButtonPress { button: KeyboardButton },
-    KeyboardButtonRelease { button: KeyboardButton },
+    Resize {
+        new_size: Vector2<u32>,
+    },
+    PointerButtonPress {
+        device: InputDevice,
+        button: PointerButton,
+    },
+    PointerButtonRelease {
+        device: InputDevice,
+        button: PointerButton,
+    },
+    PointerMotion {
+        device: InputDevice,
+        position: Vector2<u32>,
+        /// Server time in milliseconds
+        time: u32,
+    },
+    /// Sent before pointer events of `device` when its axes change, see [`Pointer::pressure`]
+    PointerAxes {
+        device: InputDevice,
+        pressure: f32,
+        tilt: Vector2<f32>,
+    },
+    KeyboardButtonPress {
+        device: InputDevice,
+        /// Kept so recordings can be decoded with [`KeyboardButton::try_from`]
+        key_sym: KeySym,
+        button: KeyboardButton,
+    },
+    KeyboardButtonRelease {
+        device: InputDevice,
+        key_sym: KeySym,
+        button: KeyboardButton,
+    },
+    Bell {
+        percent: u8,
+    },
+    VisibilityChange {
+        visibility: Visibility,
+    },
     Shutdown,
 }
 
-#[derive(Debug, Clone, Copy)]
+impl Event {
+    /// Pointer and keyboard events, counted by [`Canvas::start_latency_profiling`]
+    fn is_input(&self) -> bool {
+        matches!(
+            self,
+            Event::PointerButtonPress { .. }
+                | Event::PointerButtonRelease { .. }
+                | Event::PointerMotion { .. }
+                | Event::KeyboardButtonPress { .. }
+                | Event::KeyboardButtonRelease { .. }
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Color {
     pub a: u8,
     pub r: u8,
@@ -318,6 +1164,18 @@ impl Color {
         Self { a, r, g, b }
     }
 
+    /// Color `t` of the way from `c1` to `c2`, all channels including alpha are interpolated
+    #[inline(always)]
+    pub fn lerp(c1: Self, c2: Self, t: f32) -> Self {
+        let channel = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+        Self {
+            a: channel(c1.a, c2.a),
+            r: channel(c1.r, c2.r),
+            g: channel(c1.g, c2.g),
+            b: channel(c1.b, c2.b),
+        }
+    }
+
     #[inline(always)]
     pub fn blend(c1: Self, c2: Self) -> Self {
         let r = cmp::min(
@@ -337,104 +1195,110 @@ impl Color {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-pub struct Vector2<T> {
-    pub x: T,
-    pub y: T,
-}
-
-impl<T> Vector2<T> {
-    #[inline(always)]
-    pub fn clamp(self, lower_bound: Self, upper_bound: Self) -> Self
-    where
-        T: Ord,
-    {
-        Self {
-            x: self.x.clamp(lower_bound.x, upper_bound.x),
-            y: self.y.clamp(lower_bound.y, upper_bound.y),
-        }
-    }
-}
-
-impl Vector2<i32> {
-    #[inline(always)]
-    pub fn as_u32(self) -> Vector2<u32> {
-        Vector2 {
-            x: self.x as u32,
-            y: self.y as u32,
-        }
-    }
-
-    #[inline(always)]
-    pub fn zero() -> Self {
-        Vector2 { x: 0, y: 0 }
-    }
-
-    #[inline(always)]
-    pub fn clamp_non_negative(self) -> Self {
-        Self {
-            x: cmp::max(0, self.x),
-            y: cmp::max(0, self.y),
-        }
-    }
-
-    #[inline(always)]
-    pub fn linear_interpolation(p1: Self, p2: Self, t: f32) -> Self {
-        let mut x = p1.x as f32 + (p2.x as f32 - p1.x as f32) * t;
-        if x < 0.0 {
-            x = 0.0;
-        }
-        let x = x as i32;
-
-        let mut y = p1.y as f32 + (p2.y as f32 - p1.y as f32) * t;
-        if y < 0.0 {
-            y = 0.0;
-        }
-        let y = y as i32;
-
-        Vector2 { x, y }
-    }
+#[test]
+fn damage_union() {
+    let damage = DamageRect::new(Vector2 { x: -5, y: 10 }, Vector2 { x: 20, y: 10 }).union(
+        DamageRect::new(Vector2 { x: 30, y: 0 }, Vector2 { x: 10, y: 5 }),
+    );
+    assert_eq!((damage.position.x, damage.position.y), (0, 0));
+    assert_eq!((damage.size.x, damage.size.y), (40, 20));
+
+    let clipped = damage.clip(Vector2 { x: 32, y: 64 }).unwrap();
+    assert_eq!((clipped.size.x, clipped.size.y), (32, 20));
+    assert!(
+        DamageRect::new(Vector2 { x: 40, y: 0 }, Vector2 { x: 4, y: 4 })
+            .clip(Vector2 { x: 32, y: 32 })
+            .is_none()
+    );
 }
 
-impl Vector2<u32> {
-    #[inline(always)]
-    pub fn as_i32(self) -> Vector2<i32> {
-        Vector2 {
-            x: self.x as i32,
-            y: self.y as i32,
-        }
-    }
-
-    #[inline(always)]
-    pub fn zero() -> Self {
-        Vector2 { x: 0, y: 0 }
-    }
+#[test]
+fn pixel_format_conversion() {
+    let color = Color::from_raw(0x80112233);
+    let mut buf = PixelFormat::Bgra8888.encode(color);
+    assert_eq!(buf, [0x33, 0x22, 0x11, 0x80]);
+    PixelFormat::Bgra8888.convert(PixelFormat::Rgba8888, &mut buf);
+    assert_eq!(buf, PixelFormat::Rgba8888.encode(color));
+    let decoded = PixelFormat::Rgba8888.decode(&buf);
+    assert_eq!(
+        (decoded.a, decoded.r, decoded.g, decoded.b),
+        (0x80, 0x11, 0x22, 0x33)
+    );
+
+    assert_eq!(
+        PixelFormat::from_visual_masks(0xff0000, 0xff00, 0xff),
+        Some(PixelFormat::Bgra8888)
+    );
+    assert_eq!(PixelFormat::from_visual_masks(0xf800, 0x7e0, 0x1f), None);
 }
 
-impl<T> Add for Vector2<T>
-where
-    T: Add<Output = T>,
-{
-    type Output = Vector2<<T as Add>::Output>;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Self {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-        }
-    }
+#[test]
+fn popup_of_bitmap_is_offscreen() {
+    let mut canvas = Canvas::with_backend_type(
+        "test",
+        BackendType::Bitmap {
+            size: Vector2 { x: 4, y: 4 },
+        },
+    )
+    .unwrap();
+    let popup = canvas.open_tooltip(Vector2 { x: 8, y: 2 }).unwrap();
+    assert_eq!((popup.window_size().x, popup.window_size().y), (8, 2));
+    assert_eq!(popup.raw_buf().len(), 8 * 2 * BYTES_PER_PIXEL as usize);
+
+    // Bitmaps can't blend the shadow with anything
+    let popup = canvas
+        .open_popup_with_shadow(
+            Vector2 { x: 0, y: 0 },
+            Vector2 { x: 8, y: 2 },
+            PopupShadow::default(),
+        )
+        .unwrap();
+    assert_eq!(popup.raw_buf().len(), 8 * 2 * BYTES_PER_PIXEL as usize);
+
+    let popup = canvas
+        .supersampled(2)
+        .open_popup(Vector2 { x: 2, y: 2 }, Vector2 { x: 8, y: 4 })
+        .unwrap();
+    assert_eq!((popup.window_size().x, popup.window_size().y), (8, 4));
 }
 
-impl<T> Sub for Vector2<T>
-where
-    T: Sub<Output = T>,
-{
-    type Output = Vector2<<T as Sub>::Output>;
-
-    fn sub(self, rhs: Self) -> Self::Output {
-        Self {
-            x: self.x - rhs.x,
-            y: self.y - rhs.y,
-        }
+#[test]
+fn scroll_moves_pixels() {
+    let mut canvas = Canvas::with_backend_type(
+        "test",
+        BackendType::Bitmap {
+            size: Vector2 { x: 4, y: 4 },
+        },
+    )
+    .unwrap();
+    // Every pixel holds its own index
+    for (idx, pixel) in canvas
+        .raw_buf_mut()
+        .chunks_exact_mut(BYTES_PER_PIXEL as usize)
+        .enumerate()
+    {
+        pixel[0] = idx as u8;
     }
+    let pixel = |canvas: &Canvas, x: u32, y: u32| {
+        canvas.raw_buf()[((y * 4 + x) * BYTES_PER_PIXEL) as usize]
+    };
+
+    // Bottom right 3x3 area scrolled one row down and one column left
+    canvas
+        .scroll(
+            Vector2 { x: 1, y: 1 },
+            Vector2 { x: 3, y: 3 },
+            Vector2 { x: -1, y: 1 },
+        )
+        .unwrap();
+    assert_eq!(pixel(&canvas, 1, 2), 6);
+    assert_eq!(pixel(&canvas, 2, 3), 11);
+    // Outside of the area and uncovered pixels are left alone
+    assert_eq!(pixel(&canvas, 0, 2), 8);
+    assert_eq!(pixel(&canvas, 3, 3), 15);
+
+    // Bitmaps can't copy presented pixels so the whole area is damaged
+    let damage = canvas.take_damage().unwrap();
+    assert_eq!((damage.position.x, damage.position.y), (1, 1));
+    assert_eq!((damage.size.x, damage.size.y), (3, 3));
 }
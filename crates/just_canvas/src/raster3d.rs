@@ -0,0 +1,400 @@
+//! Perspective correct triangle rasterizer with depth buffer, for simple 3D visualizations
+//! without GL
+//!
+//! Positions are transformed by a [`Matrix4`] to clip space with OpenGL conventions: camera
+//! looks down negative `z`, visible points end up with `x`, `y` and `z` in `-1..=1` after
+//! perspective division. Triangles are clipped by the near plane, parts beyond the far plane are
+//! discarded per pixel.
+
+use crate::{draw, Canvas, Color, Vector2};
+use core::ops::{Add, Mul, Sub};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Vector3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+impl Vector3 {
+    #[inline(always)]
+    pub const fn new(x: f32, y: f32, z: f32) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl Add for Vector3 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl Sub for Vector3 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+/// Transformation of column vectors, so `a * b` applies `b` first
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Matrix4 {
+    pub rows: [[f32; 4]; 4],
+}
+
+impl Matrix4 {
+    pub const IDENTITY: Self = Self {
+        rows: [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ],
+    };
+
+    /// Projection of a camera at the origin with vertical field of view `fov_y` in radians and
+    /// `aspect` ratio of width to height. `near` and `far` are distances to the clipping planes.
+    pub fn perspective(fov_y: f32, aspect: f32, near: f32, far: f32) -> Self {
+        let f = 1.0 / (fov_y / 2.0).tan();
+        Self {
+            rows: [
+                [f / aspect, 0.0, 0.0, 0.0],
+                [0.0, f, 0.0, 0.0],
+                [
+                    0.0,
+                    0.0,
+                    (far + near) / (near - far),
+                    2.0 * far * near / (near - far),
+                ],
+                [0.0, 0.0, -1.0, 0.0],
+            ],
+        }
+    }
+
+    pub fn translation(offset: Vector3) -> Self {
+        let mut res = Self::IDENTITY;
+        res.rows[0][3] = offset.x;
+        res.rows[1][3] = offset.y;
+        res.rows[2][3] = offset.z;
+        res
+    }
+
+    pub fn scale(factor: Vector3) -> Self {
+        let mut res = Self::IDENTITY;
+        res.rows[0][0] = factor.x;
+        res.rows[1][1] = factor.y;
+        res.rows[2][2] = factor.z;
+        res
+    }
+
+    /// Counter-clockwise rotation around `x` axis when looking from positive `x`
+    pub fn rotation_x(angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        let mut res = Self::IDENTITY;
+        res.rows[1][1] = cos;
+        res.rows[1][2] = -sin;
+        res.rows[2][1] = sin;
+        res.rows[2][2] = cos;
+        res
+    }
+
+    pub fn rotation_y(angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        let mut res = Self::IDENTITY;
+        res.rows[0][0] = cos;
+        res.rows[0][2] = sin;
+        res.rows[2][0] = -sin;
+        res.rows[2][2] = cos;
+        res
+    }
+
+    pub fn rotation_z(angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        let mut res = Self::IDENTITY;
+        res.rows[0][0] = cos;
+        res.rows[0][1] = -sin;
+        res.rows[1][0] = sin;
+        res.rows[1][1] = cos;
+        res
+    }
+
+    /// Homogeneous coordinates of `point`, before perspective division
+    pub fn transform(&self, point: Vector3) -> [f32; 4] {
+        self.rows
+            .map(|row| row[0] * point.x + row[1] * point.y + row[2] * point.z + row[3])
+    }
+}
+
+impl Mul for Matrix4 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self {
+            rows: core::array::from_fn(|row| {
+                core::array::from_fn(|col| {
+                    (0..4).map(|k| self.rows[row][k] * rhs.rows[k][col]).sum()
+                })
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Vertex {
+    pub position: Vector3,
+    /// Interpolated across the triangle
+    pub color: Color,
+}
+
+/// Vertex in clip space with color channels in `a`, `r`, `g`, `b` order
+#[derive(Debug, Clone, Copy)]
+struct ClipVertex {
+    position: [f32; 4],
+    color: [f32; 4],
+}
+
+impl ClipVertex {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        let lerp =
+            |a: [f32; 4], b: [f32; 4]| core::array::from_fn(|idx| a[idx] + (b[idx] - a[idx]) * t);
+        Self {
+            position: lerp(self.position, other.position),
+            color: lerp(self.color, other.color),
+        }
+    }
+}
+
+/// Part of the triangle in front of the near plane, a triangle, a quad or nothing
+fn clip_near(triangle: [ClipVertex; 3]) -> Vec<ClipVertex> {
+    // Non-negative in front of the plane, where `z >= -w`
+    let distance = |vertex: ClipVertex| vertex.position[2] + vertex.position[3];
+    let mut res = Vec::with_capacity(4);
+    for (idx, current) in triangle.iter().copied().enumerate() {
+        let next = triangle[(idx + 1) % 3];
+        let (current_distance, next_distance) = (distance(current), distance(next));
+        if current_distance >= 0.0 {
+            res.push(current);
+        }
+        if (current_distance >= 0.0) != (next_distance >= 0.0) {
+            res.push(current.lerp(next, current_distance / (current_distance - next_distance)));
+        }
+    }
+    res
+}
+
+/// Twice the signed area of triangle `a`, `b`, `p`, positive if `p` is clockwise from `a` to
+/// `b` on screen
+#[inline(always)]
+fn edge(a: [f32; 4], b: [f32; 4], p: (f32, f32)) -> f32 {
+    (b[0] - a[0]) * (p.1 - a[1]) - (b[1] - a[1]) * (p.0 - a[0])
+}
+
+/// Draws triangles into the canvas buffer, keeping depth of drawn pixels between calls
+#[derive(Debug, Clone)]
+pub struct Rasterizer {
+    depth: Vec<f32>,
+    size: Vector2<u32>,
+    /// Skip triangles that are clockwise after projection, i.e. seen from the back when front
+    /// faces are counter-clockwise
+    pub cull_back_faces: bool,
+}
+
+impl Rasterizer {
+    pub fn new() -> Self {
+        Self {
+            depth: Vec::new(),
+            size: Vector2::<u32>::zero(),
+            cull_back_faces: true,
+        }
+    }
+
+    /// Forget depth of drawn pixels, call at the start of each frame
+    pub fn clear_depth(&mut self, canvas: &Canvas) {
+        self.size = canvas.window_size();
+        self.depth.clear();
+        self.depth
+            .resize((self.size.x * self.size.y) as usize, f32::INFINITY);
+    }
+
+    /// Draw triangle with vertices transformed by `transform` to clip space, hidden by closer
+    /// pixels drawn since [`Rasterizer::clear_depth`]
+    pub fn triangle(&mut self, canvas: &mut Canvas, transform: &Matrix4, vertices: [Vertex; 3]) {
+        if self.size != canvas.window_size() {
+            self.clear_depth(canvas);
+        }
+
+        let clipped = clip_near(vertices.map(|vertex| {
+            ClipVertex {
+                position: transform.transform(vertex.position),
+                color: [
+                    vertex.color.a,
+                    vertex.color.r,
+                    vertex.color.g,
+                    vertex.color.b,
+                ]
+                .map(|channel| channel as f32),
+            }
+        }));
+        for idx in 1..clipped.len().saturating_sub(1) {
+            self.rasterize(canvas, [clipped[0], clipped[idx], clipped[idx + 1]]);
+        }
+    }
+
+    fn rasterize(&mut self, canvas: &mut Canvas, triangle: [ClipVertex; 3]) {
+        let size = self.size;
+        // Screen `x` and `y`, depth and `1/w` used to interpolate attributes in perspective
+        let screen = triangle.map(|vertex| {
+            let [x, y, z, w] = vertex.position;
+            let inv_w = 1.0 / w;
+            [
+                (x * inv_w + 1.0) * 0.5 * size.x as f32,
+                (1.0 - y * inv_w) * 0.5 * size.y as f32,
+                z * inv_w,
+                inv_w,
+            ]
+        });
+        // Negative for counter-clockwise triangles, as `y` is flipped on screen
+        let area = edge(screen[0], screen[1], (screen[2][0], screen[2][1]));
+        if area == 0.0 || !area.is_finite() || (self.cull_back_faces && area > 0.0) {
+            return;
+        }
+
+        let axis_range = |axis: usize, max: u32| {
+            let (min_value, max_value) = screen.iter().fold((f32::MAX, f32::MIN), |(lo, hi), v| {
+                (lo.min(v[axis]), hi.max(v[axis]))
+            });
+            let start = min_value.floor().clamp(0.0, max as f32) as u32;
+            let end = max_value.ceil().clamp(0.0, max as f32) as u32;
+            start..end
+        };
+        let (xs, ys) = (axis_range(0, size.x), axis_range(1, size.y));
+
+        let format = canvas.pixel_format();
+        let buf = canvas.raw_buf_mut();
+        for y in ys {
+            for x in xs.clone() {
+                let p = (x as f32 + 0.5, y as f32 + 0.5);
+                let weights = [
+                    edge(screen[1], screen[2], p) / area,
+                    edge(screen[2], screen[0], p) / area,
+                    edge(screen[0], screen[1], p) / area,
+                ];
+                if weights.iter().any(|weight| *weight < 0.0) {
+                    continue;
+                }
+
+                // Depth after perspective division is linear on screen
+                let depth = (0..3).map(|idx| weights[idx] * screen[idx][2]).sum::<f32>();
+                let depth_idx = (y * size.x + x) as usize;
+                if depth > 1.0 || depth >= self.depth[depth_idx] {
+                    continue;
+                }
+                self.depth[depth_idx] = depth;
+
+                let inv_w = (0..3).map(|idx| weights[idx] * screen[idx][3]).sum::<f32>();
+                let [a, r, g, b] = core::array::from_fn(|channel| {
+                    let value = (0..3)
+                        .map(|idx| weights[idx] * triangle[idx].color[channel] * screen[idx][3])
+                        .sum::<f32>();
+                    (value / inv_w).round().clamp(0.0, 255.0) as u8
+                });
+                draw::set_pixel(
+                    buf,
+                    format,
+                    size,
+                    Vector2 {
+                        x: x as i32,
+                        y: y as i32,
+                    },
+                    Color { a, r, g, b },
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+fn test_canvas() -> Canvas {
+    Canvas::with_backend_type(
+        "",
+        crate::BackendType::Bitmap {
+            size: Vector2 { x: 20, y: 20 },
+        },
+    )
+    .unwrap()
+}
+
+#[test]
+fn depth_and_culling() {
+    let mut canvas = test_canvas();
+    let mut rasterizer = Rasterizer::new();
+    let projection = Matrix4::perspective(core::f32::consts::FRAC_PI_2, 1.0, 0.1, 10.0);
+    let quad = |z: f32, color: Color| {
+        let vertex = |x, y| Vertex {
+            position: Vector3::new(x, y, z),
+            color,
+        };
+        [
+            [vertex(-1.0, -1.0), vertex(1.0, -1.0), vertex(1.0, 1.0)],
+            [vertex(-1.0, -1.0), vertex(1.0, 1.0), vertex(-1.0, 1.0)],
+        ]
+    };
+    let pixel = |canvas: &Canvas, x: u32, y: u32| {
+        let offset = ((y * 20 + x) * crate::BYTES_PER_PIXEL) as usize;
+        let raw = &canvas.raw_buf()[offset..offset + crate::BYTES_PER_PIXEL as usize];
+        canvas.pixel_format().decode(raw)
+    };
+    let red = Color::from_raw(0xffff0000);
+    let blue = Color::from_raw(0xff0000ff);
+    let blank = pixel(&test_canvas(), 0, 0);
+
+    // Far quad covers half of the view, drawn after the near one but hidden by it at the center
+    rasterizer.clear_depth(&canvas);
+    for triangle in quad(-1.0, red) {
+        rasterizer.triangle(&mut canvas, &projection, triangle);
+    }
+    for triangle in quad(-2.0, blue) {
+        rasterizer.triangle(&mut canvas, &projection, triangle);
+    }
+    assert_eq!(pixel(&canvas, 10, 10), red);
+    assert_eq!(pixel(&canvas, 0, 0), red);
+
+    // Quad seen from the back is culled
+    let mut canvas = test_canvas();
+    rasterizer.clear_depth(&canvas);
+    let back = Matrix4::rotation_y(core::f32::consts::PI);
+    for triangle in quad(1.0, blue) {
+        rasterizer.triangle(&mut canvas, &(projection * back), triangle);
+    }
+    assert_eq!(pixel(&canvas, 10, 10), blank);
+    rasterizer.cull_back_faces = false;
+    for triangle in quad(1.0, blue) {
+        rasterizer.triangle(&mut canvas, &(projection * back), triangle);
+    }
+    assert_eq!(pixel(&canvas, 10, 10), blue);
+
+    // Floor triangle crossing the near plane is clipped instead of wrapping around
+    let mut canvas = test_canvas();
+    rasterizer.clear_depth(&canvas);
+    let crossing = [
+        Vertex {
+            position: Vector3::new(-1.0, -0.5, -1.0),
+            color: red,
+        },
+        Vertex {
+            position: Vector3::new(1.0, -0.5, -1.0),
+            color: red,
+        },
+        Vertex {
+            position: Vector3::new(0.0, -0.5, 1.0),
+            color: red,
+        },
+    ];
+    rasterizer.triangle(&mut canvas, &projection, crossing);
+    assert_eq!(pixel(&canvas, 10, 19), red);
+    assert_eq!(pixel(&canvas, 10, 10), blank);
+    assert_eq!(pixel(&canvas, 10, 0), blank);
+}
@@ -0,0 +1,605 @@
+//! Recording of input events and their playback into a bitmap backed [`crate::Canvas`], so
+//! interaction bugs can be reproduced and turned into deterministic tests.
+//!
+//! Recordings are stored as text, one event per line:
+//!
+//! ```text
+//! <frame> <microseconds since start> <event> [arguments] [@<device>]
+//! ```
+//!
+//! Device is written only for input events that don't come from [`InputDevice::CORE`].
+//!
+//! Playback is driven by frames, i.e. calls to [`crate::Canvas::process_events`], not by time, so
+//! it does not depend on speed of the machine running it.
+
+use crate::{
+    backend::{owned_bitmap::OwnedBitmapBackend, Backend},
+    keyboard::KeyboardButton,
+    Event, InputDevice, PointerButton, Result, Vector2, Visibility,
+};
+use just_x11::keysym::KeySym;
+use std::{
+    collections::VecDeque,
+    fs,
+    io::{self, BufRead, BufReader, Write},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, Clone)]
+struct RecordedEvent {
+    frame: u64,
+    time: Duration,
+    event: Event,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Recording {
+    events: Vec<RecordedEvent>,
+}
+
+impl Recording {
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Number of frames until the last recorded event
+    #[inline]
+    pub fn frames(&self) -> u64 {
+        self.events.last().map_or(0, |event| event.frame + 1)
+    }
+
+    pub fn write(&self, w: &mut impl Write) -> io::Result<()> {
+        for recorded in &self.events {
+            write!(w, "{} {} ", recorded.frame, recorded.time.as_micros())?;
+            let device = match &recorded.event {
+                Event::Resize { new_size } => {
+                    write!(w, "resize {} {}", new_size.x, new_size.y)?;
+                    None
+                }
+                Event::PointerButtonPress { device, button } => {
+                    write!(w, "pointer_press {}", pointer_button_name(*button))?;
+                    Some(device)
+                }
+                Event::PointerButtonRelease { device, button } => {
+                    write!(w, "pointer_release {}", pointer_button_name(*button))?;
+                    Some(device)
+                }
+                Event::PointerMotion {
+                    device,
+                    position,
+                    time,
+                } => {
+                    write!(w, "pointer_motion {} {} {}", position.x, position.y, time)?;
+                    Some(device)
+                }
+                Event::PointerAxes {
+                    device,
+                    pressure,
+                    tilt,
+                } => {
+                    write!(w, "pointer_axes {} {} {}", pressure, tilt.x, tilt.y)?;
+                    Some(device)
+                }
+                Event::KeyboardButtonPress {
+                    device, key_sym, ..
+                } => {
+                    write!(w, "key_press 0x{:x}", key_sym.inner)?;
+                    Some(device)
+                }
+                Event::KeyboardButtonRelease {
+                    device, key_sym, ..
+                } => {
+                    write!(w, "key_release 0x{:x}", key_sym.inner)?;
+                    Some(device)
+                }
+                Event::Bell { percent } => {
+                    write!(w, "bell {}", percent)?;
+                    None
+                }
+                Event::VisibilityChange { visibility } => {
+                    write!(w, "visibility {}", visibility_name(*visibility))?;
+                    None
+                }
+                Event::Shutdown => {
+                    write!(w, "shutdown")?;
+                    None
+                }
+            };
+            match device {
+                Some(device) if *device != InputDevice::CORE => writeln!(w, " @{}", device.id)?,
+                _ => writeln!(w)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn read(r: impl BufRead) -> io::Result<Self> {
+        let mut events = Vec::new();
+        for (idx, line) in r.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let recorded = parse_line(&line).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Invalid event recording at line {}: '{}'", idx + 1, line),
+                )
+            })?;
+            events.push(recorded);
+        }
+
+        Ok(Self { events })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut file = io::BufWriter::new(fs::File::create(path)?);
+        self.write(&mut file)?;
+        file.flush()
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::read(BufReader::new(fs::File::open(path)?))
+    }
+}
+
+pub(crate) struct Recorder {
+    start: Instant,
+    frame: u64,
+    recording: Recording,
+}
+
+impl Recorder {
+    pub(crate) fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            frame: 0,
+            recording: Recording::default(),
+        }
+    }
+
+    pub(crate) fn record(&mut self, event: &Event) {
+        self.recording.events.push(RecordedEvent {
+            frame: self.frame,
+            time: self.start.elapsed(),
+            event: event.clone(),
+        });
+    }
+
+    pub(crate) fn next_frame(&mut self) {
+        self.frame += 1;
+    }
+
+    pub(crate) fn finish(self) -> Recording {
+        self.recording
+    }
+}
+
+/// Bitmap backend returning recorded events. Sends [`Event::Shutdown`] after the last one.
+pub(crate) struct PlaybackBackend {
+    bitmap: OwnedBitmapBackend,
+    events: VecDeque<RecordedEvent>,
+    frame: u64,
+    finished: bool,
+}
+
+impl PlaybackBackend {
+    pub(crate) fn new(size: Vector2<u32>, recording: Recording) -> Self {
+        Self {
+            bitmap: OwnedBitmapBackend::new(size),
+            events: recording.events.into(),
+            frame: 0,
+            finished: false,
+        }
+    }
+}
+
+impl Backend for PlaybackBackend {
+    #[inline]
+    fn flush_window(&mut self) -> Result<()> {
+        self.bitmap.flush_window()
+    }
+
+    fn events(&mut self) -> Result<Vec<Event>> {
+        let mut events = Vec::new();
+        while self
+            .events
+            .front()
+            .is_some_and(|recorded| recorded.frame <= self.frame)
+        {
+            events.push(self.events.pop_front().unwrap().event);
+        }
+        if self.events.is_empty() && !self.finished {
+            self.finished = true;
+            events.push(Event::Shutdown);
+        }
+        self.frame += 1;
+
+        Ok(events)
+    }
+
+    #[inline]
+    fn resize(&mut self, size: Vector2<u32>) -> Result<()> {
+        self.bitmap.resize(size)
+    }
+
+    #[inline]
+    fn size(&self) -> Vector2<u32> {
+        self.bitmap.size()
+    }
+
+    #[inline]
+    fn buf_mut(&mut self) -> &mut [u8] {
+        self.bitmap.buf_mut()
+    }
+
+    #[inline]
+    fn buf(&self) -> &[u8] {
+        self.bitmap.buf()
+    }
+
+    #[inline]
+    fn bell(&mut self, percent: i8) -> Result<()> {
+        self.bitmap.bell(percent)
+    }
+
+    #[inline]
+    fn warp_pointer(&mut self, position: Vector2<u32>) -> Result<()> {
+        self.bitmap.warp_pointer(position)
+    }
+
+    #[inline]
+    fn set_pointer_capture(&mut self, captured: bool) -> Result<bool> {
+        self.bitmap.set_pointer_capture(captured)
+    }
+}
+
+const POINTER_BUTTONS: [(PointerButton, &str); 5] = [
+    (PointerButton::Left, "left"),
+    (PointerButton::Middle, "middle"),
+    (PointerButton::Right, "right"),
+    (PointerButton::ScrollUp, "scroll_up"),
+    (PointerButton::ScrollDown, "scroll_down"),
+];
+
+const VISIBILITIES: [(Visibility, &str); 4] = [
+    (Visibility::Unobscured, "unobscured"),
+    (Visibility::PartiallyObscured, "partially_obscured"),
+    (Visibility::FullyObscured, "fully_obscured"),
+    (Visibility::Unmapped, "unmapped"),
+];
+
+fn pointer_button_name(button: PointerButton) -> &'static str {
+    POINTER_BUTTONS
+        .iter()
+        .find_map(|(b, name)| (*b == button).then_some(*name))
+        .unwrap()
+}
+
+fn visibility_name(visibility: Visibility) -> &'static str {
+    VISIBILITIES
+        .iter()
+        .find_map(|(v, name)| (*v == visibility).then_some(*name))
+        .unwrap()
+}
+
+fn parse_line(line: &str) -> Option<RecordedEvent> {
+    let mut words = line.split_whitespace();
+    let frame = words.next()?.parse().ok()?;
+    let time = Duration::from_micros(words.next()?.parse().ok()?);
+
+    let vector = |words: &mut std::vec::IntoIter<&str>| -> Option<Vector2<u32>> {
+        Some(Vector2 {
+            x: words.next()?.parse().ok()?,
+            y: words.next()?.parse().ok()?,
+        })
+    };
+    let pointer_button = |name: &str| {
+        POINTER_BUTTONS
+            .iter()
+            .find_map(|(button, n)| (*n == name).then_some(*button))
+    };
+    let key_sym = |raw: &str| {
+        let key_sym = KeySym {
+            inner: u32::from_str_radix(raw.strip_prefix("0x")?, 16).ok()?,
+        };
+        Some((key_sym, KeyboardButton::try_from(key_sym).ok()?))
+    };
+
+    let kind = words.next()?;
+    let mut arguments = words.collect::<Vec<_>>();
+    let device = match arguments.last().and_then(|last| last.strip_prefix('@')) {
+        Some(id) => {
+            let id = id.parse().ok()?;
+            arguments.pop();
+            InputDevice { id }
+        }
+        None => InputDevice::CORE,
+    };
+    let mut words = arguments.into_iter();
+
+    let event = match kind {
+        "resize" => Event::Resize {
+            new_size: vector(&mut words)?,
+        },
+        "pointer_press" => Event::PointerButtonPress {
+            device,
+            button: pointer_button(words.next()?)?,
+        },
+        "pointer_release" => Event::PointerButtonRelease {
+            device,
+            button: pointer_button(words.next()?)?,
+        },
+        "pointer_motion" => Event::PointerMotion {
+            device,
+            position: vector(&mut words)?,
+            // Server time was not recorded by older versions
+            time: match words.next() {
+                Some(time) => time.parse().ok()?,
+                None => 0,
+            },
+        },
+        "pointer_axes" => Event::PointerAxes {
+            device,
+            pressure: words.next()?.parse().ok()?,
+            tilt: Vector2 {
+                x: words.next()?.parse().ok()?,
+                y: words.next()?.parse().ok()?,
+            },
+        },
+        "key_press" => {
+            let (key_sym, button) = key_sym(words.next()?)?;
+            Event::KeyboardButtonPress {
+                device,
+                key_sym,
+                button,
+            }
+        }
+        "key_release" => {
+            let (key_sym, button) = key_sym(words.next()?)?;
+            Event::KeyboardButtonRelease {
+                device,
+                key_sym,
+                button,
+            }
+        }
+        "bell" => Event::Bell {
+            percent: words.next()?.parse().ok()?,
+        },
+        "visibility" => {
+            let name = words.next()?;
+            Event::VisibilityChange {
+                visibility: VISIBILITIES
+                    .iter()
+                    .find_map(|(visibility, n)| (*n == name).then_some(*visibility))?,
+            }
+        }
+        "shutdown" => Event::Shutdown,
+        _ => return None,
+    };
+
+    words
+        .next()
+        .is_none()
+        .then_some(RecordedEvent { frame, time, event })
+}
+
+#[test]
+fn playback_recording() {
+    use crate::{BackendType, Canvas};
+
+    let recording = Recording::read(
+        "0 0 pointer_motion 10 20\n\
+         0 100 pointer_press left\n\
+         2 33000 key_press 0x61\n\
+         3 50000 pointer_release left\n"
+            .as_bytes(),
+    )
+    .unwrap();
+    assert_eq!(recording.len(), 4);
+    assert_eq!(recording.frames(), 4);
+
+    let mut written = Vec::new();
+    recording.write(&mut written).unwrap();
+    assert_eq!(
+        Recording::read(written.as_slice()).unwrap().len(),
+        recording.len()
+    );
+
+    let mut canvas = Canvas::with_backend_type(
+        "",
+        BackendType::Playback {
+            size: Vector2 { x: 64, y: 64 },
+            recording,
+        },
+    )
+    .unwrap();
+
+    canvas.process_events().unwrap();
+    assert_eq!(canvas.pointer().position.x, 10);
+    assert!(canvas.pointer().is_pressed(PointerButton::Left));
+
+    canvas.process_events().unwrap();
+    canvas.process_events().unwrap();
+    assert_eq!(canvas.keyboard_events.len(), 1);
+
+    canvas.process_events().unwrap();
+    assert!(!canvas.pointer().is_pressed(PointerButton::Left));
+    assert!(canvas.should_close());
+}
+
+#[test]
+fn playback_multiple_pointers() {
+    use crate::{BackendType, Canvas, KeyboardEvent};
+
+    let recording = Recording::read(
+        "0 0 pointer_motion 10 20 @2\n\
+         0 10 pointer_motion 30 40 @4\n\
+         0 20 pointer_press left @4\n\
+         0 30 key_press 0x61 @3\n"
+            .as_bytes(),
+    )
+    .unwrap();
+    let mut written = Vec::new();
+    recording.write(&mut written).unwrap();
+    assert!(String::from_utf8(written)
+        .unwrap()
+        .ends_with("key_press 0x61 @3\n"));
+
+    let mut canvas = Canvas::with_backend_type(
+        "",
+        BackendType::Playback {
+            size: Vector2 { x: 64, y: 64 },
+            recording,
+        },
+    )
+    .unwrap();
+    canvas.process_events().unwrap();
+
+    let first = canvas.pointer_of(InputDevice { id: 2 }).unwrap();
+    assert_eq!(first.position.x, 10);
+    assert!(!first.is_pressed(PointerButton::Left));
+    let second = canvas.pointer_of(InputDevice { id: 4 }).unwrap();
+    assert_eq!(second.position.x, 30);
+    assert!(second.is_pressed(PointerButton::Left));
+    assert_eq!(canvas.pointers().len(), 2);
+
+    // Shared pointer follows the last one
+    assert_eq!(canvas.pointer().device(), InputDevice { id: 4 });
+    assert_eq!(canvas.pointer().position.x, 30);
+
+    assert!(matches!(
+        canvas.keyboard_events[..],
+        [KeyboardEvent::Pressed(_, InputDevice { id: 3 })]
+    ));
+}
+
+#[test]
+fn playback_pen_axes() {
+    use crate::{BackendType, Canvas};
+
+    let recording = Recording::read(
+        "0 0 pointer_motion 10 20 @2\n\
+         0 10 pointer_axes 0.5 -0.25 0.75 @4\n\
+         0 20 pointer_motion 30 40 @4\n"
+            .as_bytes(),
+    )
+    .unwrap();
+    let mut written = Vec::new();
+    recording.write(&mut written).unwrap();
+    assert!(String::from_utf8(written)
+        .unwrap()
+        .contains("pointer_axes 0.5 -0.25 0.75 @4\n"));
+
+    let mut canvas = Canvas::with_backend_type(
+        "",
+        BackendType::Playback {
+            size: Vector2 { x: 64, y: 64 },
+            recording,
+        },
+    )
+    .unwrap();
+    canvas.process_events().unwrap();
+
+    // Mice keep full pressure
+    assert_eq!(
+        canvas.pointer_of(InputDevice { id: 2 }).unwrap().pressure(),
+        1.0
+    );
+    let pen = canvas.pointer_of(InputDevice { id: 4 }).unwrap();
+    assert_eq!(pen.pressure(), 0.5);
+    assert_eq!((pen.tilt().x, pen.tilt().y), (-0.25, 0.75));
+    assert_eq!(canvas.pointer().pressure(), 0.5);
+}
+
+#[test]
+fn motion_history_in_frame() {
+    use crate::{BackendType, Canvas, MotionSample};
+
+    let recording = Recording::read(
+        "0 0 pointer_motion 10 20 1000\n\
+         0 10 pointer_motion 12 24 1008\n\
+         0 20 pointer_motion 15 30 1016\n\
+         1 30 pointer_press left\n\
+         2 40 pointer_motion 1 2\n"
+            .as_bytes(),
+    )
+    .unwrap();
+    let mut canvas = Canvas::with_backend_type(
+        "",
+        BackendType::Playback {
+            size: Vector2 { x: 64, y: 64 },
+            recording,
+        },
+    )
+    .unwrap();
+
+    canvas.process_events().unwrap();
+    assert_eq!(
+        canvas.pointer().motion_history(),
+        [(10, 20, 1000), (12, 24, 1008), (15, 30, 1016)].map(|(x, y, time)| MotionSample {
+            position: Vector2 { x, y },
+            time
+        })
+    );
+    assert_eq!(canvas.pointer().position, Vector2 { x: 15, y: 30 });
+
+    canvas.process_events().unwrap();
+    assert!(canvas.pointer().motion_history().is_empty());
+
+    // Recordings without server time
+    canvas.process_events().unwrap();
+    assert_eq!(canvas.pointer().motion_history()[0].time, 0);
+}
+
+#[test]
+fn playback_lifecycle() {
+    use crate::{BackendType, Canvas, Lifecycle};
+    use std::{cell::RefCell, rc::Rc};
+
+    let recording = Recording::read(
+        "0 0 visibility fully_obscured\n\
+         1 0 visibility partially_obscured\n\
+         2 0 visibility unmapped\n\
+         3 0 visibility unobscured\n\
+         3 10 visibility unobscured\n"
+            .as_bytes(),
+    )
+    .unwrap();
+    let mut canvas = Canvas::with_backend_type(
+        "",
+        BackendType::Playback {
+            size: Vector2 { x: 64, y: 64 },
+            recording,
+        },
+    )
+    .unwrap();
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    canvas.on_lifecycle({
+        let changes = changes.clone();
+        move |lifecycle| changes.borrow_mut().push(lifecycle)
+    });
+
+    for _ in 0..4 {
+        canvas.process_events().unwrap();
+    }
+    assert_eq!(
+        *changes.borrow(),
+        [
+            Lifecycle::Hidden,
+            Lifecycle::Shown,
+            Lifecycle::Hidden,
+            Lifecycle::Minimized,
+            Lifecycle::Restored,
+            Lifecycle::Shown,
+        ]
+    );
+}
@@ -0,0 +1,300 @@
+// CLIPPY CONFIG
+#![allow(
+    clippy::new_without_default,
+    clippy::unnecessary_cast,
+    clippy::identity_op
+)]
+//
+#![no_std]
+
+use core::{
+    cmp,
+    ops::{Add, Sub},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Hash, Default)]
+pub struct Vector2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Vector2<T> {
+    #[inline(always)]
+    pub fn clamp(self, lower_bound: Self, upper_bound: Self) -> Self
+    where
+        T: Ord,
+    {
+        Self {
+            x: self.x.clamp(lower_bound.x, upper_bound.x),
+            y: self.y.clamp(lower_bound.y, upper_bound.y),
+        }
+    }
+}
+
+impl Vector2<i32> {
+    #[inline(always)]
+    pub fn as_u32(self) -> Vector2<u32> {
+        Vector2 {
+            x: self.x as u32,
+            y: self.y as u32,
+        }
+    }
+
+    #[inline(always)]
+    pub fn zero() -> Self {
+        Vector2 { x: 0, y: 0 }
+    }
+
+    #[inline(always)]
+    pub fn clamp_non_negative(self) -> Self {
+        Self {
+            x: cmp::max(0, self.x),
+            y: cmp::max(0, self.y),
+        }
+    }
+
+    #[inline(always)]
+    pub fn linear_interpolation(p1: Self, p2: Self, t: f32) -> Self {
+        let mut x = p1.x as f32 + (p2.x as f32 - p1.x as f32) * t;
+        if x < 0.0 {
+            x = 0.0;
+        }
+        let x = x as i32;
+
+        let mut y = p1.y as f32 + (p2.y as f32 - p1.y as f32) * t;
+        if y < 0.0 {
+            y = 0.0;
+        }
+        let y = y as i32;
+
+        Vector2 { x, y }
+    }
+}
+
+impl Vector2<u32> {
+    #[inline(always)]
+    pub fn as_i32(self) -> Vector2<i32> {
+        Vector2 {
+            x: self.x as i32,
+            y: self.y as i32,
+        }
+    }
+
+    #[inline(always)]
+    pub fn zero() -> Self {
+        Vector2 { x: 0, y: 0 }
+    }
+}
+
+impl<T> Add for Vector2<T>
+where
+    T: Add<Output = T>,
+{
+    type Output = Vector2<<T as Add>::Output>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+        }
+    }
+}
+
+impl<T> Sub for Vector2<T>
+where
+    T: Sub<Output = T>,
+{
+    type Output = Vector2<<T as Sub>::Output>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+        }
+    }
+}
+
+/// Axis aligned rectangle covering points from `position` up to, but not including,
+/// `position + size`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rect {
+    pub position: Vector2<i32>,
+    pub size: Vector2<u32>,
+}
+
+impl Rect {
+    #[inline(always)]
+    pub fn new(position: Vector2<i32>, size: Vector2<u32>) -> Self {
+        Self { position, size }
+    }
+
+    /// Rectangle from `start` to `end`, empty if `end` is not below and right of `start`
+    #[inline(always)]
+    pub fn from_corners(start: Vector2<i32>, end: Vector2<i32>) -> Self {
+        Self {
+            position: start,
+            size: Vector2 {
+                x: end.x.saturating_sub(start.x).max(0) as u32,
+                y: end.y.saturating_sub(start.y).max(0) as u32,
+            },
+        }
+    }
+
+    /// First point right of and below the rectangle
+    #[inline(always)]
+    pub fn end(self) -> Vector2<i32> {
+        Vector2 {
+            x: self.position.x.saturating_add_unsigned(self.size.x),
+            y: self.position.y.saturating_add_unsigned(self.size.y),
+        }
+    }
+
+    #[inline(always)]
+    pub fn is_empty(self) -> bool {
+        self.size.x == 0 || self.size.y == 0
+    }
+
+    #[inline(always)]
+    pub fn area(self) -> u64 {
+        self.size.x as u64 * self.size.y as u64
+    }
+
+    #[inline(always)]
+    pub fn contains(self, point: Vector2<i32>) -> bool {
+        let end = self.end();
+        point.x >= self.position.x
+            && point.y >= self.position.y
+            && point.x < end.x
+            && point.y < end.y
+    }
+
+    /// Area covered by both, `None` if they don't overlap. Touching edges don't overlap.
+    pub fn intersection(self, other: Self) -> Option<Self> {
+        let (end, other_end) = (self.end(), other.end());
+        let res = Self::from_corners(
+            Vector2 {
+                x: cmp::max(self.position.x, other.position.x),
+                y: cmp::max(self.position.y, other.position.y),
+            },
+            Vector2 {
+                x: cmp::min(end.x, other_end.x),
+                y: cmp::min(end.y, other_end.y),
+            },
+        );
+        (!res.is_empty()).then_some(res)
+    }
+
+    /// Smallest rectangle containing both. Empty rectangles don't cover any points, so they
+    /// don't grow the result.
+    pub fn union(self, other: Self) -> Self {
+        if other.is_empty() {
+            return self;
+        }
+        if self.is_empty() {
+            return other;
+        }
+
+        let (end, other_end) = (self.end(), other.end());
+        Self::from_corners(
+            Vector2 {
+                x: cmp::min(self.position.x, other.position.x),
+                y: cmp::min(self.position.y, other.position.y),
+            },
+            Vector2 {
+                x: cmp::max(end.x, other_end.x),
+                y: cmp::max(end.y, other_end.y),
+            },
+        )
+    }
+
+    /// Closest point of the rectangle to `point`, `position` if the rectangle is empty
+    pub fn clamp(self, point: Vector2<i32>) -> Vector2<i32> {
+        if self.is_empty() {
+            return self.position;
+        }
+        point.clamp(self.position, self.end() - Vector2 { x: 1, y: 1 })
+    }
+}
+
+#[test]
+fn rect_contains() {
+    let rect = Rect::new(Vector2 { x: -2, y: 3 }, Vector2 { x: 4, y: 2 });
+    assert_eq!(rect.end(), Vector2 { x: 2, y: 5 });
+    assert_eq!(rect.area(), 8);
+    assert!(rect.contains(Vector2 { x: -2, y: 3 }));
+    assert!(rect.contains(Vector2 { x: 1, y: 4 }));
+    assert!(!rect.contains(Vector2 { x: 2, y: 4 }));
+    assert!(!rect.contains(Vector2 { x: 1, y: 5 }));
+    assert!(!Rect::default().contains(Vector2::<i32>::zero()));
+
+    assert_eq!(
+        rect.clamp(Vector2 { x: 10, y: -10 }),
+        Vector2 { x: 1, y: 3 }
+    );
+    assert_eq!(rect.clamp(Vector2 { x: 0, y: 4 }), Vector2 { x: 0, y: 4 });
+
+    assert!(Rect::from_corners(Vector2 { x: 5, y: 0 }, Vector2 { x: 0, y: 5 }).is_empty());
+    assert_eq!(
+        Rect::new(
+            Vector2 {
+                x: i32::MAX - 1,
+                y: 0
+            },
+            Vector2 { x: 10, y: 1 }
+        )
+        .end(),
+        Vector2 { x: i32::MAX, y: 1 }
+    );
+}
+
+#[test]
+fn rect_intersection_and_union() {
+    let rect = |x, y, width, height| Rect {
+        position: Vector2 { x, y },
+        size: Vector2 {
+            x: width,
+            y: height,
+        },
+    };
+
+    assert_eq!(
+        rect(0, 0, 10, 10).intersection(rect(5, -5, 10, 10)),
+        Some(rect(5, 0, 5, 5))
+    );
+    assert_eq!(
+        rect(0, 0, 10, 10).intersection(rect(2, 2, 2, 2)),
+        Some(rect(2, 2, 2, 2))
+    );
+    // Touching edges don't overlap
+    assert_eq!(rect(0, 0, 10, 10).intersection(rect(10, 0, 5, 5)), None);
+    assert_eq!(rect(0, 0, 10, 10).intersection(rect(2, 2, 0, 5)), None);
+
+    assert_eq!(
+        rect(-5, 10, 20, 10).union(rect(30, 0, 10, 5)),
+        rect(-5, 0, 45, 20)
+    );
+    assert_eq!(
+        rect(0, 0, 10, 10).union(rect(100, 100, 0, 0)),
+        rect(0, 0, 10, 10)
+    );
+    assert_eq!(
+        rect(100, 100, 0, 5).union(rect(1, 2, 3, 4)),
+        rect(1, 2, 3, 4)
+    );
+}
+
+#[test]
+fn vector_arithmetic() {
+    let a = Vector2 { x: 3, y: -4 };
+    assert_eq!(a + Vector2 { x: 1, y: 1 }, Vector2 { x: 4, y: -3 });
+    assert_eq!(a - a, Vector2::<i32>::zero());
+    assert_eq!(a.clamp_non_negative(), Vector2 { x: 3, y: 0 });
+    assert_eq!(
+        a.clamp_non_negative().as_u32().as_i32(),
+        Vector2 { x: 3, y: 0 }
+    );
+    assert_eq!(
+        Vector2::linear_interpolation(Vector2 { x: 0, y: 10 }, Vector2 { x: 10, y: 20 }, 0.5),
+        Vector2 { x: 5, y: 15 }
+    );
+}
@@ -5,12 +5,9 @@
     clippy::identity_op
 )]
 
-use just_canvas::{
-    draw::{inside_circle, inside_rectangle},
-    Color, Result, Vector2,
-};
+use just_canvas::{Color, Result, Vector2};
 use just_immui::{
-    invisible_button, invisible_draggable,
+    hit, invisible_button, invisible_draggable,
     monokaish::{self, Slider},
     Ui, UiId,
 };
@@ -174,9 +171,7 @@ fn checkbox(ui: &mut Ui, id: UiId, state: &mut bool, position: Vector2<i32>) {
         monokaish::BLACK
     };
 
-    let button = invisible_button(ui, id, |cursor| {
-        inside_rectangle(position, size, cursor.as_i32())
-    });
+    let button = invisible_button(ui, id, hit::rect(position, size));
 
     if button.got_hovered || button.got_released || button.got_pressed || button.got_unhovered {
         ui.set_dirty();
@@ -225,9 +220,7 @@ fn endpoint(ui: &mut Ui, id: UiId, state: &mut Endpoint) {
     ui.circle(state.position, r - 5, monokaish::BLACK);
     ui.circle(state.position, r - 12, monokaish::BLUE);
 
-    let dragged = invisible_draggable(ui, id, |pointer| {
-        inside_circle(state.position, r, pointer.as_i32())
-    });
+    let dragged = invisible_draggable(ui, id, hit::circle(state.position, r));
 
     let pointer = ui.pointer_position();
 
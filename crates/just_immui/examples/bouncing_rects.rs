@@ -0,0 +1,161 @@
+// CLIPPY CONFIG
+#![allow(
+    clippy::new_without_default,
+    clippy::unnecessary_cast,
+    clippy::identity_op
+)]
+
+//! Stress test redrawing thousands of moving rectangles with labels every frame
+//!
+//! Prints one tab separated line per frame with draw and flush time of the previous frame and
+//! achieved frames per second, e.g. `cargo run --release --example bouncing_rects -- 5000 600`
+//! draws 5000 rectangles for 600 frames. With `--vsync` frames are paced by vertical blanks,
+//! to check that animation stays smooth at the refresh rate of the monitor.
+
+use just_canvas::{Color, Result, Vector2};
+use just_immui::{monokaish, Ui};
+use std::{env, process, time::Instant};
+
+const USAGE: &str = "Usage: bouncing_rects [--vsync] [RECTS] [FRAMES]";
+const RECT_SIZE: u32 = 24;
+
+struct Rect {
+    position: Vector2<f32>,
+    velocity: Vector2<f32>,
+    color: Color,
+    label: String,
+}
+
+/// Deterministic so runs are comparable
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> f32 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+struct State {
+    rects: Vec<Rect>,
+    frames: Option<u64>,
+    frame: u64,
+    last_frame: Option<Instant>,
+}
+
+fn draw(ui: &mut Ui, state: &mut State) {
+    let now = Instant::now();
+    if let Some(last_frame) = state.last_frame {
+        let timings = ui.frame_timings();
+        println!(
+            "{}\t{}\t{}\t{}\t{:.1}",
+            state.frame,
+            timings.draw.as_micros(),
+            timings.flush.as_micros(),
+            timings.frame.as_micros(),
+            1.0 / (now - last_frame).as_secs_f64(),
+        );
+    }
+    state.last_frame = Some(now);
+    state.frame += 1;
+    if state.frames.is_some_and(|frames| state.frame > frames) {
+        ui.close();
+        return;
+    }
+
+    let size = ui.current_view().size;
+    let bounds = Vector2 {
+        x: size.x.saturating_sub(RECT_SIZE) as f32,
+        y: size.y.saturating_sub(RECT_SIZE) as f32,
+    };
+    ui.background(monokaish::BLACK);
+    for rect in &mut state.rects {
+        rect.position.x += rect.velocity.x;
+        rect.position.y += rect.velocity.y;
+        if !(0.0..=bounds.x).contains(&rect.position.x) {
+            rect.velocity.x = -rect.velocity.x;
+            rect.position.x = rect.position.x.clamp(0.0, bounds.x);
+        }
+        if !(0.0..=bounds.y).contains(&rect.position.y) {
+            rect.velocity.y = -rect.velocity.y;
+            rect.position.y = rect.position.y.clamp(0.0, bounds.y);
+        }
+
+        let position = Vector2 {
+            x: rect.position.x as i32,
+            y: rect.position.y as i32,
+        };
+        ui.rectangle(
+            position,
+            Vector2 {
+                x: RECT_SIZE,
+                y: RECT_SIZE,
+            },
+            rect.color,
+        );
+        ui.text(
+            position + Vector2 { x: 2, y: 8 },
+            1,
+            rect.label.chars(),
+            monokaish::BLACK,
+        );
+    }
+
+    // Everything moves so whole window is redrawn each frame
+    ui.set_dirty();
+}
+
+fn parse_arg(arg: Option<String>) -> Option<u64> {
+    arg.map(|arg| {
+        arg.parse().unwrap_or_else(|_| {
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        })
+    })
+}
+
+fn main() -> Result<()> {
+    let mut args = env::args().skip(1).peekable();
+    let vsync = args.next_if(|arg| arg == "--vsync").is_some();
+    let count = parse_arg(args.next()).unwrap_or(2000);
+    let frames = parse_arg(args.next());
+
+    let colors = [
+        monokaish::RED,
+        monokaish::GREEN,
+        monokaish::YELLOW,
+        monokaish::BLUE,
+        monokaish::ORANGE,
+        monokaish::PURPLE,
+    ];
+    let mut rng = Lcg(count);
+    let rects = (0..count)
+        .map(|idx| Rect {
+            position: Vector2 {
+                x: rng.next() * 800.0,
+                y: rng.next() * 600.0,
+            },
+            velocity: Vector2 {
+                x: rng.next() * 6.0 - 3.0,
+                y: rng.next() * 6.0 - 3.0,
+            },
+            color: colors[idx as usize % colors.len()],
+            label: format!("{}", idx % 100),
+        })
+        .collect();
+    let mut state = State {
+        rects,
+        frames,
+        frame: 0,
+        last_frame: None,
+    };
+
+    println!("frame\tdraw_us\tflush_us\tframe_us\tfps");
+    let mut ui = Ui::new("Bouncing rectangles")?;
+    ui.set_vsync(vsync);
+    // Not limited in practice, sleeps only if a frame takes less than a millisecond
+    ui.fps_limited_loop(1000, |ui| draw(ui, &mut state))
+}
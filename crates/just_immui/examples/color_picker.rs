@@ -0,0 +1,31 @@
+use just_canvas::{Color, Result, Vector2};
+use just_immui::{color_picker::ColorPicker, monokaish, Ui, UiId};
+
+fn draw(ui: &mut Ui, picker: &mut ColorPicker) {
+    ui.background(monokaish::BLACK);
+
+    if picker.draw(
+        ui,
+        UiId {
+            id: 0,
+            parent: 0,
+            index: 0,
+        },
+        Vector2 { x: 20, y: 20 },
+    ) {
+        println!("{}", just_immui::color_picker::format_hex(picker.color()));
+    }
+}
+
+fn ui() -> Result<()> {
+    let mut ui = Ui::new("Color picker")?;
+    ui.set_dirty();
+
+    let mut picker = ColorPicker::new(Color::from_raw(0xffa6e22e));
+    ui.fps_limited_loop(60, |ui| draw(ui, &mut picker))?;
+    Ok(())
+}
+
+fn main() {
+    ui().unwrap();
+}
@@ -0,0 +1,68 @@
+use just_canvas::{Result, Vector2};
+use just_immui::{
+    hit, invisible_button,
+    menu::{self, MenuItem},
+    monokaish, Ui, UiId,
+};
+
+const ITEMS: &[MenuItem] = &[
+    MenuItem::Action {
+        label: "Cut",
+        enabled: true,
+    },
+    MenuItem::Action {
+        label: "Copy",
+        enabled: true,
+    },
+    MenuItem::Action {
+        label: "Paste",
+        enabled: false,
+    },
+    MenuItem::Separator,
+    MenuItem::Action {
+        label: "Select all",
+        enabled: true,
+    },
+];
+
+fn draw(ui: &mut Ui) {
+    ui.background(monokaish::BLACK);
+
+    let position = Vector2 { x: 50, y: 50 };
+    let size = Vector2 { x: 300, y: 200 };
+    ui.rectangle(position, size, monokaish::GRAY);
+    ui.text(
+        Vector2 { x: 60, y: 60 },
+        2,
+        "Right click me".chars(),
+        monokaish::WHITE,
+    );
+
+    let id = UiId {
+        id: 0,
+        parent: 0,
+        index: 0,
+    };
+    let area_id = UiId { id: 1, ..id };
+    let area = invisible_button(ui, area_id, hit::rect(position, size));
+    if area.got_hovered || area.got_unhovered {
+        ui.set_dirty();
+    }
+    // Before the menu so it is covered when the menu opens
+    ui.tooltip(area_id, "Has a context menu");
+
+    if let Some(selected) = menu::context_menu(ui, id, hit::rect(position, size), ITEMS) {
+        if let MenuItem::Action { label, .. } = ITEMS[selected] {
+            println!("Selected: {}", label);
+        }
+    }
+}
+
+fn ui() -> Result<()> {
+    let mut ui = Ui::new("Context menu")?;
+    ui.fps_limited_loop(60, draw)
+}
+
+fn main() {
+    ui().unwrap();
+}
@@ -0,0 +1,78 @@
+use just_canvas::{Result, Vector2};
+use just_immui::{
+    form::{self, Form, FormEnum, FormFields},
+    monokaish, Ui,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Shape {
+    Circle,
+    Square,
+    Triangle,
+}
+
+impl FormEnum for Shape {
+    const VARIANTS: &'static [Self] = &[Shape::Circle, Shape::Square, Shape::Triangle];
+
+    fn name(self) -> &'static str {
+        match self {
+            Shape::Circle => "Circle",
+            Shape::Square => "Square",
+            Shape::Triangle => "Triangle",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Settings {
+    name: String,
+    visible: bool,
+    opacity: f32,
+    size: i32,
+    shape: Shape,
+}
+
+impl FormFields for Settings {
+    fn form_fields(&mut self, form: &mut Form) {
+        form.text_input("Name", &mut self.name)
+            .checkbox("Visible", &mut self.visible)
+            .slider("Opacity", &mut self.opacity, 0.0..=1.0)
+            .spinner("Size", &mut self.size, 1..=100)
+            .dropdown("Shape", &mut self.shape);
+    }
+}
+
+fn draw(ui: &mut Ui, settings: &mut Settings) {
+    ui.background(monokaish::BLACK);
+
+    if form::form(ui, 0, Vector2 { x: 50, y: 50 }, settings) {
+        println!("{:?}", settings);
+    }
+
+    if let Some(err) = ui.theme_error() {
+        let err = err.to_string();
+        ui.text(Vector2 { x: 50, y: 20 }, 1, err.chars(), monokaish::RED);
+    }
+}
+
+fn ui() -> Result<()> {
+    let mut ui = Ui::new("Form")?;
+    // Theme file given as the first argument is reloaded whenever it's saved
+    if let Some(path) = std::env::args().nth(1) {
+        if let Err(err) = ui.watch_theme(path) {
+            eprintln!("form: {}", err);
+        }
+    }
+    let mut settings = Settings {
+        name: "Hello".to_string(),
+        visible: true,
+        opacity: 0.5,
+        size: 10,
+        shape: Shape::Circle,
+    };
+    ui.fps_limited_loop(60, |ui| draw(ui, &mut settings))
+}
+
+fn main() {
+    ui().unwrap();
+}
@@ -5,8 +5,8 @@
     clippy::identity_op
 )]
 
-use just_canvas::{draw::inside_rectangle, Result, Vector2};
-use just_immui::{invisible_button, monokaish, Button, Ui, UiId};
+use just_canvas::{Result, Vector2};
+use just_immui::{hit, invisible_button, monokaish, Button, Ui, UiId};
 
 /// Main UI loop
 fn draw(ui: &mut Ui, state: &mut State) {
@@ -81,9 +81,7 @@ fn counter_button(ui: &mut Ui, id: UiId, position: Vector2<i32>, state: &mut u32
     };
     let font_size = 2;
 
-    let button = invisible_button(ui, id, |pointer| {
-        inside_rectangle(position, size, pointer.as_i32())
-    });
+    let button = invisible_button(ui, id, hit::rect(position, size));
 
     if button.got_hovered || button.got_released || button.got_pressed || button.got_unhovered {
         ui.set_dirty();
@@ -0,0 +1,45 @@
+use just_canvas::{Result, Vector2};
+use just_immui::{
+    monokaish::{self, Knob, KnobDrag},
+    Ui, UiId,
+};
+
+fn draw(ui: &mut Ui, left: &mut Knob, right: &mut Knob) {
+    ui.background(monokaish::BLACK);
+
+    left.draw(
+        ui,
+        UiId {
+            id: 0,
+            parent: 0,
+            index: 0,
+        },
+        Vector2 { x: 50, y: 50 },
+        ui.scaled(40),
+    );
+
+    right.draw(
+        ui,
+        UiId {
+            id: 1,
+            parent: 0,
+            index: 0,
+        },
+        Vector2 { x: 200, y: 50 },
+        ui.scaled(40),
+    );
+}
+
+fn ui() -> Result<()> {
+    let mut ui = Ui::new("Knob")?;
+    ui.set_dirty();
+
+    let mut left = Knob::new(0.25, KnobDrag::Vertical);
+    let mut right = Knob::new(0.75, KnobDrag::Angular);
+    ui.fps_limited_loop(60, |ui| draw(ui, &mut left, &mut right))?;
+    Ok(())
+}
+
+fn main() {
+    ui().unwrap();
+}
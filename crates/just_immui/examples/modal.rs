@@ -0,0 +1,68 @@
+use just_canvas::{Result, Vector2};
+use just_immui::{
+    hit, invisible_button,
+    modal::{self, ModalResult},
+    monokaish, Ui, UiId,
+};
+
+struct State {
+    dialog_open: bool,
+}
+
+fn draw(ui: &mut Ui, state: &mut State) {
+    ui.background(monokaish::BLACK);
+
+    let id = UiId {
+        id: 0,
+        parent: 0,
+        index: 0,
+    };
+    let position = Vector2 { x: 50, y: 50 };
+    let size = Vector2 { x: 200, y: 50 };
+    let button = invisible_button(ui, id, hit::rect(position, size));
+    if button.got_hovered || button.got_unhovered {
+        ui.set_dirty();
+    }
+    let color = if button.is_hovered {
+        monokaish::BLUE
+    } else {
+        monokaish::GRAY
+    };
+    ui.rectangle(position, size, color);
+    ui.text(
+        Vector2 { x: 60, y: 68 },
+        2,
+        "Open dialog".chars(),
+        monokaish::WHITE,
+    );
+    if button.got_released {
+        state.dialog_open = true;
+    }
+
+    if state.dialog_open {
+        let dialog_id = UiId { id: 1, ..id };
+        let result = modal::modal(ui, dialog_id, Vector2 { x: 300, y: 100 }, |ui| {
+            ui.text(
+                Vector2 { x: 10, y: 10 },
+                2,
+                "Return or Escape".chars(),
+                monokaish::WHITE,
+            );
+            ModalResult::Open
+        });
+        if result != ModalResult::Open {
+            println!("Dialog: {:?}", result);
+            state.dialog_open = false;
+        }
+    }
+}
+
+fn ui() -> Result<()> {
+    let mut ui = Ui::new("Modal")?;
+    let mut state = State { dialog_open: false };
+    ui.fps_limited_loop(60, |ui| draw(ui, &mut state))
+}
+
+fn main() {
+    ui().unwrap();
+}
@@ -0,0 +1,599 @@
+// CLIPPY CONFIG
+#![allow(
+    clippy::new_without_default,
+    clippy::unnecessary_cast,
+    clippy::identity_op
+)]
+
+//! Every built-in widget on tabs, with a switch between dark and light theme. Run it after
+//! changing widgets to check them by hand, with `screenshot` feature it writes
+//! `widget_gallery.png` of the first tab instead. The copy button puts a screenshot of the
+//! gallery in the clipboard.
+
+use just_canvas::{Color, Result, Vector2};
+use just_immui::{
+    color_picker::ColorPicker,
+    form::{self, Form, FormEnum, FormFields},
+    hit, invisible_button,
+    menu::{self, MenuItem},
+    modal::{self, ModalResult},
+    monokaish::{self, Knob, KnobDrag, Slider, TextInput},
+    theme::{Theme, WidgetKind},
+    tree::{self, TreeNode, TreeView},
+    Ui, UiId,
+};
+
+const MARGIN: i32 = 20;
+/// Top of the area below the tab bar
+const CONTENT_Y: i32 = 70;
+const LABEL_FONT_SIZE: u32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tab {
+    Controls,
+    Lists,
+    Popups,
+    Form,
+    Colors,
+}
+
+impl Tab {
+    const ALL: [Self; 5] = [
+        Self::Controls,
+        Self::Lists,
+        Self::Popups,
+        Self::Form,
+        Self::Colors,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Controls => "Controls",
+            Self::Lists => "Lists",
+            Self::Popups => "Popups",
+            Self::Form => "Form",
+            Self::Colors => "Colors",
+        }
+    }
+
+    /// Parent of ids of widgets on the tab, tab bar uses `0`
+    fn parent(self) -> u32 {
+        self as u32 + 1
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ThemeChoice {
+    Dark,
+    Light,
+}
+
+impl ThemeChoice {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Dark => "Dark",
+            Self::Light => "Light",
+        }
+    }
+
+    fn next(self) -> Self {
+        match self {
+            Self::Dark => Self::Light,
+            Self::Light => Self::Dark,
+        }
+    }
+
+    fn background(self) -> Color {
+        match self {
+            Self::Dark => monokaish::BLACK,
+            Self::Light => Color::from_raw(0xfff4f4f4),
+        }
+    }
+
+    fn theme(self) -> Theme {
+        match self {
+            Self::Dark => Theme::default(),
+            Self::Light => {
+                let mut theme = Theme::default();
+                for kind in WidgetKind::ALL {
+                    let style = theme.style_mut(kind);
+                    style.background = Color::from_raw(0xffdedede);
+                    style.foreground = Color::from_raw(0xffbbbbbb);
+                    style.text = monokaish::BLACK;
+                    style.accent = monokaish::DARK_BLUE;
+                    style.highlight = Color::from_raw(0xff999999);
+                }
+                theme.style_mut(WidgetKind::Tooltip).background = Color::from_raw(0xffffffe0);
+                theme
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Shape {
+    Circle,
+    Square,
+    Triangle,
+}
+
+impl FormEnum for Shape {
+    const VARIANTS: &'static [Self] = &[Shape::Circle, Shape::Square, Shape::Triangle];
+
+    fn name(self) -> &'static str {
+        match self {
+            Shape::Circle => "Circle",
+            Shape::Square => "Square",
+            Shape::Triangle => "Triangle",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Settings {
+    name: String,
+    visible: bool,
+    opacity: f32,
+    size: i32,
+    shape: Shape,
+}
+
+impl FormFields for Settings {
+    fn form_fields(&mut self, form: &mut Form) {
+        form.text_input("Name", &mut self.name)
+            .checkbox("Visible", &mut self.visible)
+            .slider("Opacity", &mut self.opacity, 0.0..=1.0)
+            .spinner("Size", &mut self.size, 1..=100)
+            .dropdown("Shape", &mut self.shape);
+    }
+}
+
+const MENU_ITEMS: &[MenuItem] = &[
+    MenuItem::Action {
+        label: "Cut",
+        enabled: true,
+    },
+    MenuItem::Action {
+        label: "Copy",
+        enabled: true,
+    },
+    MenuItem::Action {
+        label: "Paste",
+        enabled: false,
+    },
+    MenuItem::Separator,
+    MenuItem::Action {
+        label: "Select all",
+        enabled: true,
+    },
+];
+
+/// Persistent state between UI frames
+struct State {
+    tab: Tab,
+    theme: ThemeChoice,
+    clicks: u32,
+    slider: Slider,
+    knob: Knob,
+    text_input: TextInput,
+    files: Vec<TreeNode>,
+    tree: TreeView,
+    dialog_open: bool,
+    /// Last selected menu item or dialog result
+    last_action: String,
+    settings: Settings,
+    picker: ColorPicker,
+}
+
+impl State {
+    fn new() -> Self {
+        Self {
+            tab: Tab::Controls,
+            theme: ThemeChoice::Dark,
+            clicks: 0,
+            slider: Slider {
+                min: 0,
+                max: 100,
+                value: 30,
+            },
+            knob: Knob::new(0.5, KnobDrag::Vertical),
+            text_input: TextInput {
+                value: "Hello, World!".to_string(),
+                cursor: 0,
+            },
+            files: vec![
+                TreeNode::branch(
+                    "src",
+                    vec![
+                        TreeNode::leaf("lib.rs"),
+                        TreeNode::leaf("menu.rs"),
+                        TreeNode::branch("widgets", vec![TreeNode::leaf("tree.rs")]),
+                    ],
+                ),
+                TreeNode::branch("examples", vec![TreeNode::leaf("widget_gallery.rs")]),
+                TreeNode::leaf("Cargo.toml"),
+                TreeNode::leaf("README.md"),
+            ],
+            tree: TreeView::new(),
+            dialog_open: false,
+            last_action: String::new(),
+            settings: Settings {
+                name: "Hello".to_string(),
+                visible: true,
+                opacity: 0.5,
+                size: 10,
+                shape: Shape::Circle,
+            },
+            picker: ColorPicker::new(Color::from_raw(0xffa6e22e)),
+        }
+    }
+}
+
+fn new_id(parent: u32, id: u32) -> UiId {
+    UiId {
+        id,
+        parent,
+        index: 0,
+    }
+}
+
+fn label(ui: &mut Ui, position: Vector2<i32>, text: &str) {
+    let color = ui.style(WidgetKind::Form).text;
+    let font_size = ui.scaled(LABEL_FONT_SIZE);
+    ui.text(position, font_size, text.chars(), color);
+}
+
+fn button_size(ui: &Ui, text: &str) -> Vector2<u32> {
+    let style = ui.style(WidgetKind::Form);
+    let padding = ui.scaled(style.padding) * 2;
+    let text_size = ui.text_size(ui.scaled(style.font_size), text.chars());
+    Vector2 {
+        x: text_size.x + padding * 2,
+        y: text_size.y + padding * 2,
+    }
+}
+
+/// Push button in colors of the form style, `selected` ones use the accent color. Returns `true`
+/// when clicked.
+fn button(ui: &mut Ui, id: UiId, position: Vector2<i32>, text: &str, selected: bool) -> bool {
+    let style = ui.style(WidgetKind::Form);
+    let size = button_size(ui, text);
+    let padding = ui.scaled(style.padding) as i32 * 2;
+
+    let button = invisible_button(ui, id, hit::rect(position, size));
+    if button.got_hovered || button.got_unhovered || button.got_pressed || button.got_released {
+        ui.set_dirty();
+    }
+
+    let color = if button.is_pressed || selected {
+        style.accent
+    } else if button.is_hovered {
+        style.highlight
+    } else {
+        style.foreground
+    };
+    ui.rectangle(position, size, color);
+    ui.text(
+        Vector2 {
+            x: position.x + padding,
+            y: position.y + padding,
+        },
+        ui.scaled(style.font_size),
+        text.chars(),
+        style.text,
+    );
+
+    button.got_released
+}
+
+/// Main UI loop
+fn draw(ui: &mut Ui, state: &mut State) {
+    // Redraws everything when the theme changes
+    ui.set_theme(state.theme.theme());
+    ui.background(state.theme.background());
+
+    let mut x = MARGIN;
+    for (idx, tab) in Tab::ALL.into_iter().enumerate() {
+        let id = new_id(0, idx as u32);
+        if button(
+            ui,
+            id,
+            Vector2 { x, y: MARGIN },
+            tab.name(),
+            tab == state.tab,
+        ) {
+            state.tab = tab;
+            ui.set_dirty();
+        }
+        x += button_size(ui, tab.name()).x as i32 + MARGIN / 2;
+    }
+
+    let theme_label = state.theme.name();
+    let theme_x = ui.current_view().size.x as i32 - MARGIN - button_size(ui, theme_label).x as i32;
+    let theme_id = new_id(0, Tab::ALL.len() as u32);
+    if button(
+        ui,
+        theme_id,
+        Vector2 {
+            x: theme_x,
+            y: MARGIN,
+        },
+        theme_label,
+        false,
+    ) {
+        state.theme = state.theme.next();
+        ui.set_dirty();
+    }
+    ui.tooltip(theme_id, "Switch between dark and light theme");
+
+    let copy_label = "Copy";
+    let copy_x = theme_x - MARGIN / 2 - button_size(ui, copy_label).x as i32;
+    let copy_id = new_id(0, Tab::ALL.len() as u32 + 1);
+    let copy = button(
+        ui,
+        copy_id,
+        Vector2 {
+            x: copy_x,
+            y: MARGIN,
+        },
+        copy_label,
+        false,
+    );
+    ui.tooltip(copy_id, "Copy screenshot to the clipboard");
+
+    match state.tab {
+        Tab::Controls => controls(ui, state),
+        Tab::Lists => lists(ui, state),
+        Tab::Popups => popups(ui, state),
+        Tab::Form => {
+            if form::form(
+                ui,
+                Tab::Form.parent(),
+                Vector2 {
+                    x: MARGIN,
+                    y: CONTENT_Y,
+                },
+                &mut state.settings,
+            ) {
+                println!("{:?}", state.settings);
+            }
+        }
+        Tab::Colors => {
+            let id = new_id(Tab::Colors.parent(), 0);
+            if state.picker.draw(
+                ui,
+                id,
+                Vector2 {
+                    x: MARGIN,
+                    y: CONTENT_Y,
+                },
+            ) {
+                println!(
+                    "{}",
+                    just_immui::color_picker::format_hex(state.picker.color())
+                );
+            }
+        }
+    }
+
+    // After everything else is drawn, so the screenshot has all of it
+    if copy {
+        match ui.copy_screenshot() {
+            Ok(true) => println!("Copied screenshot"),
+            Ok(false) => println!("No clipboard"),
+            Err(err) => println!("Could not copy screenshot: {:?}", err),
+        }
+    }
+}
+
+fn controls(ui: &mut Ui, state: &mut State) {
+    let parent = Tab::Controls.parent();
+    let column = MARGIN + 160;
+    let row = |idx: i32| CONTENT_Y + idx * 70;
+
+    label(
+        ui,
+        Vector2 {
+            x: MARGIN,
+            y: row(0) + 8,
+        },
+        "Button",
+    );
+    let clicked = button(
+        ui,
+        new_id(parent, 0),
+        Vector2 {
+            x: column,
+            y: row(0),
+        },
+        "Click me",
+        false,
+    );
+    if clicked {
+        state.clicks += 1;
+    }
+    let clicks = format!("Clicked {} times", state.clicks);
+    label(
+        ui,
+        Vector2 {
+            x: column + 180,
+            y: row(0) + 8,
+        },
+        &clicks,
+    );
+
+    label(
+        ui,
+        Vector2 {
+            x: MARGIN,
+            y: row(1),
+        },
+        "Slider",
+    );
+    state.slider.draw(
+        ui,
+        new_id(parent, 1),
+        Vector2 {
+            x: column,
+            y: row(1) + 6,
+        },
+        ui.scaled(300),
+    );
+
+    label(
+        ui,
+        Vector2 {
+            x: MARGIN,
+            y: row(2),
+        },
+        "Knob",
+    );
+    state.knob.draw(
+        ui,
+        new_id(parent, 2),
+        Vector2 {
+            x: column,
+            y: row(2),
+        },
+        ui.scaled(25),
+    );
+
+    label(
+        ui,
+        Vector2 {
+            x: MARGIN,
+            y: row(3) + 4,
+        },
+        "Text input",
+    );
+    state.text_input.draw(
+        ui,
+        new_id(parent, 3),
+        Vector2 {
+            x: column,
+            y: row(3),
+        },
+    );
+}
+
+fn lists(ui: &mut Ui, state: &mut State) {
+    let id = new_id(Tab::Lists.parent(), 0);
+    let size = Vector2 { x: 300, y: 300 };
+    state.tree.draw(
+        ui,
+        id,
+        &state.files,
+        Vector2 {
+            x: MARGIN,
+            y: CONTENT_Y,
+        },
+        size,
+    );
+
+    let selected = match state.tree.selected.as_deref() {
+        Some(path) => match tree::node_at(&state.files, path) {
+            Some(node) => format!("Selected: {}", node.label),
+            None => "Nothing selected".to_string(),
+        },
+        None => "Nothing selected".to_string(),
+    };
+    label(
+        ui,
+        Vector2 {
+            x: MARGIN * 2 + size.x as i32,
+            y: CONTENT_Y,
+        },
+        &selected,
+    );
+}
+
+fn popups(ui: &mut Ui, state: &mut State) {
+    let parent = Tab::Popups.parent();
+
+    let dialog_button = new_id(parent, 0);
+    let position = Vector2 {
+        x: MARGIN,
+        y: CONTENT_Y,
+    };
+    if button(ui, dialog_button, position, "Open dialog", false) {
+        state.dialog_open = true;
+    }
+    ui.tooltip(dialog_button, "Return confirms, Escape cancels");
+
+    let area_id = new_id(parent, 1);
+    let area_position = Vector2 {
+        x: MARGIN,
+        y: CONTENT_Y + 70,
+    };
+    let area_size = Vector2 { x: 300, y: 150 };
+    let area_color = ui.style(WidgetKind::Menu).background;
+    ui.rectangle(area_position, area_size, area_color);
+    label(
+        ui,
+        Vector2 {
+            x: area_position.x + 10,
+            y: area_position.y + 10,
+        },
+        "Right click me",
+    );
+    let area = invisible_button(ui, area_id, hit::rect(area_position, area_size));
+    if area.got_hovered || area.got_unhovered {
+        ui.set_dirty();
+    }
+    // Before the menu so it is covered when the menu opens
+    ui.tooltip(area_id, "Has a context menu");
+
+    label(
+        ui,
+        Vector2 {
+            x: MARGIN,
+            y: area_position.y + area_size.y as i32 + 20,
+        },
+        &state.last_action,
+    );
+
+    let menu_id = new_id(parent, 2);
+    if let Some(selected) =
+        menu::context_menu(ui, menu_id, hit::rect(area_position, area_size), MENU_ITEMS)
+    {
+        if let MenuItem::Action { label, .. } = MENU_ITEMS[selected] {
+            state.last_action = format!("Selected: {}", label);
+            ui.set_dirty();
+        }
+    }
+
+    if state.dialog_open {
+        let text_color = ui.style(WidgetKind::Modal).text;
+        let result = modal::modal(ui, new_id(parent, 3), Vector2 { x: 300, y: 100 }, |ui| {
+            ui.text(
+                Vector2 { x: 10, y: 10 },
+                ui.scaled(LABEL_FONT_SIZE),
+                "Return or Escape".chars(),
+                text_color,
+            );
+            ModalResult::Open
+        });
+        if result != ModalResult::Open {
+            state.last_action = format!("Dialog: {:?}", result);
+            state.dialog_open = false;
+        }
+    }
+}
+
+fn ui() -> Result<()> {
+    let mut state = State::new();
+
+    #[cfg(not(feature = "screenshot"))]
+    {
+        let mut ui = Ui::new("Widget gallery")?;
+        ui.fps_limited_loop(60, |ui| draw(ui, &mut state))
+    }
+    #[cfg(feature = "screenshot")]
+    {
+        just_immui::screenshot!("widget_gallery.png", state, Vector2 { x: 800, y: 600 })
+    }
+}
+
+fn main() {
+    ui().unwrap();
+}
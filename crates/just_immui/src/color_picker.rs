@@ -0,0 +1,433 @@
+//! Color picker with a saturation and value square, hue and alpha bars, hex entry and swatches of
+//! recently picked colors
+
+use just_canvas::{Color, Vector2};
+
+use crate::{
+    hit, invisible_button, invisible_draggable,
+    monokaish::{self, TextInput},
+    Ui, UiId,
+};
+
+/// Number of swatches kept by [`ColorPicker::add_recent`]
+pub const RECENT_COLORS: usize = 8;
+
+const CHECKER_LIGHT: Color = Color::from_raw(0xff999999);
+const CHECKER_DARK: Color = Color::from_raw(0xff666666);
+
+/// Color from hue in degrees and saturation, value and alpha from `0.0` to `1.0`
+pub fn hsv_to_color(hue: f32, saturation: f32, value: f32, alpha: f32) -> Color {
+    let hue = hue.rem_euclid(360.0) / 60.0;
+    let chroma = value * saturation;
+    let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+    let (r, g, b) = match hue as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = value - chroma;
+    let channel = |c: f32| ((c + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+    Color::from_components(
+        (alpha * 255.0).round().clamp(0.0, 255.0) as u8,
+        channel(r),
+        channel(g),
+        channel(b),
+    )
+}
+
+/// Hue in degrees, saturation and value of `color`. Hue of grays is `0.0`.
+pub fn color_to_hsv(color: Color) -> (f32, f32, f32) {
+    let [r, g, b] = [color.r, color.g, color.b].map(|c| c as f32 / 255.0);
+    let max = r.max(g).max(b);
+    let chroma = max - r.min(g).min(b);
+    let hue = if chroma == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / chroma).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / chroma + 2.0)
+    } else {
+        60.0 * ((r - g) / chroma + 4.0)
+    };
+    let saturation = if max == 0.0 { 0.0 } else { chroma / max };
+    (hue, saturation, max)
+}
+
+/// `#rrggbb`, or `#rrggbbaa` if `color` is not opaque
+pub fn format_hex(color: Color) -> String {
+    if color.a == 255 {
+        format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b)
+    } else {
+        format!(
+            "#{:02x}{:02x}{:02x}{:02x}",
+            color.r, color.g, color.b, color.a
+        )
+    }
+}
+
+/// Parse `rrggbb` or `rrggbbaa` with optional leading `#`
+pub fn parse_hex(hex: &str) -> Option<Color> {
+    let hex = hex.trim();
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
+    if !matches!(hex.len(), 6 | 8) || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |idx: usize| u8::from_str_radix(&hex[idx * 2..idx * 2 + 2], 16).ok();
+    let alpha = if hex.len() == 8 { channel(3)? } else { 255 };
+    Some(Color::from_components(
+        alpha,
+        channel(0)?,
+        channel(1)?,
+        channel(2)?,
+    ))
+}
+
+pub struct ColorPicker {
+    /// In degrees, kept when saturation or value is zero so dragging back restores it
+    hue: f32,
+    saturation: f32,
+    value: f32,
+    alpha: f32,
+    hex: TextInput,
+    /// Most recent first
+    recent: Vec<Color>,
+    /// Index of the part dragged in the previous frame
+    dragged: Option<u32>,
+}
+
+impl ColorPicker {
+    pub fn new(color: Color) -> Self {
+        let mut picker = Self {
+            hue: 0.0,
+            saturation: 0.0,
+            value: 0.0,
+            alpha: 1.0,
+            hex: TextInput {
+                value: format_hex(color),
+                cursor: 0,
+            },
+            recent: Vec::new(),
+            dragged: None,
+        };
+        picker.set_color(color);
+        picker
+    }
+
+    pub fn color(&self) -> Color {
+        hsv_to_color(self.hue, self.saturation, self.value, self.alpha)
+    }
+
+    pub fn set_color(&mut self, color: Color) {
+        let (hue, saturation, value) = color_to_hsv(color);
+        if saturation != 0.0 && value != 0.0 {
+            self.hue = hue;
+        }
+        self.saturation = saturation;
+        self.value = value;
+        self.alpha = color.a as f32 / 255.0;
+    }
+
+    /// Swatches of recently picked colors, most recent first
+    pub fn recent(&self) -> &[Color] {
+        &self.recent
+    }
+
+    /// Put `color` first in the swatches, colors are added when a drag ends or a valid hex code
+    /// is typed
+    pub fn add_recent(&mut self, color: Color) {
+        self.recent.retain(|recent| *recent != color);
+        self.recent.insert(0, color);
+        self.recent.truncate(RECENT_COLORS);
+    }
+
+    fn checkerboard(ui: &mut Ui, position: Vector2<i32>, size: Vector2<u32>) {
+        let cell = ui.scaled(6).max(1);
+        for row in 0..size.y.div_ceil(cell) {
+            for column in 0..size.x.div_ceil(cell) {
+                let offset = Vector2 {
+                    x: column * cell,
+                    y: row * cell,
+                };
+                ui.rectangle(
+                    position + offset.as_i32(),
+                    Vector2 {
+                        x: cell.min(size.x - offset.x),
+                        y: cell.min(size.y - offset.y),
+                    },
+                    if (row + column) % 2 == 0 {
+                        CHECKER_LIGHT
+                    } else {
+                        CHECKER_DARK
+                    },
+                );
+            }
+        }
+    }
+
+    /// Fraction of `length` at which the pointer is, clamped to the area
+    fn pointer_fraction(ui: &Ui, start: i32, length: u32, vertical: bool) -> f32 {
+        let pointer = ui.pointer_position().as_i32();
+        let pointer = if vertical { pointer.y } else { pointer.x };
+        ((pointer - start) as f32 / length.max(1) as f32).clamp(0.0, 1.0)
+    }
+
+    /// Marker over a hue or alpha bar at `fraction` of its width
+    fn bar_marker(ui: &mut Ui, position: Vector2<i32>, size: Vector2<u32>, fraction: f32) {
+        let width = ui.scaled(4);
+        let x = position.x + (fraction * size.x as f32) as i32 - width as i32 / 2;
+        let overhang = ui.scaled(2);
+        ui.rectangle(
+            Vector2 {
+                x,
+                y: position.y - overhang as i32,
+            },
+            Vector2 {
+                x: width,
+                y: size.y + overhang * 2,
+            },
+            monokaish::WHITE,
+        );
+    }
+
+    /// Draw the picker with its top left corner at `position`. Parts use `id` with indices
+    /// from `0` up to `4 + RECENT_COLORS`. Returns whether the color was changed this frame.
+    pub fn draw(&mut self, ui: &mut Ui, id: UiId, position: Vector2<i32>) -> bool {
+        let old_color = self.color();
+        let part = |index| UiId { index, ..id };
+        let pad = ui.scaled(8);
+        let square = Vector2 {
+            x: ui.scaled(160),
+            y: ui.scaled(160),
+        };
+        let bar = Vector2 {
+            x: square.x,
+            y: ui.scaled(14),
+        };
+        let hue_position = Vector2 {
+            x: position.x,
+            y: position.y + (square.y + pad) as i32,
+        };
+        let alpha_position = Vector2 {
+            x: position.x,
+            y: hue_position.y + (bar.y + pad) as i32,
+        };
+        let column_x = position.x + (square.x + pad * 2) as i32;
+        let preview = Vector2 {
+            x: ui.scaled(240),
+            y: ui.scaled(40),
+        };
+        let hex_position = Vector2 {
+            x: column_x,
+            y: position.y + (preview.y + pad) as i32,
+        };
+        let swatch = ui.scaled(24);
+        let swatches_y = hex_position.y + (ui.scaled(26) + pad) as i32;
+
+        // Dragging
+        let mut dragged = None;
+        if invisible_draggable(ui, part(0), hit::rect(position, square)) {
+            self.saturation = Self::pointer_fraction(ui, position.x, square.x, false);
+            self.value = 1.0 - Self::pointer_fraction(ui, position.y, square.y, true);
+            dragged = Some(0);
+        }
+        if invisible_draggable(ui, part(1), hit::rect(hue_position, bar)) {
+            // Right edge would wrap around to the left one
+            self.hue = Self::pointer_fraction(ui, hue_position.x, bar.x, false) * 359.99;
+            dragged = Some(1);
+        }
+        if invisible_draggable(ui, part(2), hit::rect(alpha_position, bar)) {
+            self.alpha = Self::pointer_fraction(ui, alpha_position.x, bar.x, false);
+            dragged = Some(2);
+        }
+        if self.dragged.is_some() && dragged.is_none() {
+            self.add_recent(self.color());
+        }
+        self.dragged = dragged;
+
+        for (idx, color) in self.recent.clone().into_iter().enumerate() {
+            let swatch_position = Vector2 {
+                x: column_x + idx as i32 * (swatch + pad) as i32,
+                y: swatches_y,
+            };
+            let swatch_size = Vector2 {
+                x: swatch,
+                y: swatch,
+            };
+            Self::checkerboard(ui, swatch_position, swatch_size);
+            ui.rectangle(swatch_position, swatch_size, color);
+            ui.register_widget(part(4 + idx as u32), swatch_position, swatch_size);
+            let button = invisible_button(
+                ui,
+                part(4 + idx as u32),
+                hit::rect(swatch_position, swatch_size),
+            );
+            if button.got_released {
+                self.set_color(color);
+            }
+        }
+
+        // Hex entry follows the color unless it is being edited
+        let hex_id = part(3);
+        let color = self.color();
+        if !ui.is_active(hex_id) && parse_hex(&self.hex.value) != Some(color) {
+            self.hex.value = format_hex(color);
+        }
+        let typed = self.hex.value.clone();
+        self.hex.draw(ui, hex_id, hex_position);
+        if self.hex.value != typed {
+            if let Some(typed) = parse_hex(&self.hex.value) {
+                self.set_color(typed);
+                self.add_recent(typed);
+            }
+        }
+
+        let color = self.color();
+        ui.gradient_rectangle(
+            position,
+            square,
+            [
+                Color::from_raw(0xffffffff),
+                hsv_to_color(self.hue, 1.0, 1.0, 1.0),
+                Color::from_raw(0xff000000),
+                Color::from_raw(0xff000000),
+            ],
+        );
+        ui.register_widget(part(0), position, square);
+        let marker = Vector2 {
+            x: position.x + (self.saturation * square.x as f32) as i32,
+            y: position.y + ((1.0 - self.value) * square.y as f32) as i32,
+        };
+        let marker_radius = ui.scaled(6);
+        let marker_color = if self.value > 0.5 {
+            monokaish::BLACK
+        } else {
+            monokaish::WHITE
+        };
+        ui.arc(
+            marker,
+            marker_radius,
+            ui.scaled(2),
+            0.0,
+            std::f32::consts::TAU,
+            marker_color,
+        );
+
+        // Six segments between the primary and secondary colors
+        let segment = bar.x / 6;
+        for idx in 0..6 {
+            let width = if idx == 5 {
+                bar.x - segment * 5
+            } else {
+                segment
+            };
+            let from = hsv_to_color(idx as f32 * 60.0, 1.0, 1.0, 1.0);
+            let to = hsv_to_color((idx + 1) as f32 * 60.0, 1.0, 1.0, 1.0);
+            ui.gradient_rectangle(
+                Vector2 {
+                    x: hue_position.x + (idx * segment) as i32,
+                    y: hue_position.y,
+                },
+                Vector2 { x: width, y: bar.y },
+                [from, to, from, to],
+            );
+        }
+        ui.register_widget(part(1), hue_position, bar);
+        Self::bar_marker(ui, hue_position, bar, self.hue / 360.0);
+
+        let opaque = Color { a: 255, ..color };
+        let transparent = Color { a: 0, ..color };
+        Self::checkerboard(ui, alpha_position, bar);
+        ui.gradient_rectangle(
+            alpha_position,
+            bar,
+            [transparent, opaque, transparent, opaque],
+        );
+        ui.register_widget(part(2), alpha_position, bar);
+        Self::bar_marker(ui, alpha_position, bar, self.alpha);
+
+        let preview_position = Vector2 {
+            x: column_x,
+            y: position.y,
+        };
+        Self::checkerboard(ui, preview_position, preview);
+        ui.rectangle(preview_position, preview, color);
+
+        let changed = color != old_color;
+        if changed {
+            ui.set_dirty();
+        }
+        changed
+    }
+}
+
+#[test]
+fn color_picker_conversions_and_drag() {
+    use just_canvas::{record::Recording, BackendType, Canvas};
+
+    for raw in [
+        0xffff0000, 0xff00ff00, 0x800000ff, 0xff123456, 0xffffffff, 0xff000000,
+    ] {
+        let color = Color::from_raw(raw);
+        let (hue, saturation, value) = color_to_hsv(color);
+        assert_eq!(
+            hsv_to_color(hue, saturation, value, color.a as f32 / 255.0),
+            color
+        );
+        assert_eq!(parse_hex(&format_hex(color)), Some(color));
+    }
+    assert_eq!(color_to_hsv(Color::from_raw(0xff00ffff)), (180.0, 1.0, 1.0));
+    assert_eq!(format_hex(Color::from_raw(0x80123456)), "#12345680");
+    assert_eq!(parse_hex("ff8000"), Some(Color::from_raw(0xffff8000)));
+    assert_eq!(parse_hex("#ff80"), None);
+    assert_eq!(parse_hex("#ff800g"), None);
+
+    // Gray keeps the hue, so only saturation has to be dragged back
+    let mut picker = ColorPicker::new(Color::from_raw(0xff00ff00));
+    picker.set_color(Color::from_raw(0xff808080));
+    assert_eq!(picker.hue, 120.0);
+
+    for idx in 0..10u8 {
+        picker.add_recent(Color::from_raw(0xff000000 | idx as u32));
+    }
+    picker.add_recent(Color::from_raw(0xff000005));
+    assert_eq!(picker.recent().len(), RECENT_COLORS);
+    assert_eq!(picker.recent()[0], Color::from_raw(0xff000005));
+    assert_eq!(picker.recent()[1], Color::from_raw(0xff000009));
+
+    // Drag from the square past its top right corner, fully saturated and bright
+    let recording = Recording::read(
+        "0 0 pointer_motion 100 100\n\
+         1 0 pointer_press left\n\
+         2 0 pointer_motion 200 0\n\
+         3 0 pointer_release left\n"
+            .as_bytes(),
+    )
+    .unwrap();
+    let canvas = Canvas::with_backend_type(
+        "",
+        BackendType::Playback {
+            size: Vector2 { x: 500, y: 300 },
+            recording,
+        },
+    )
+    .unwrap();
+    let mut ui = Ui::with_canvas(canvas);
+    let id = UiId {
+        id: 0,
+        parent: 0,
+        index: 0,
+    };
+    let mut picker = ColorPicker::new(Color::from_raw(0xff408040));
+    let mut changed = false;
+    for _ in 0..4 {
+        ui.canvas.process_events().unwrap();
+        changed |= picker.draw(&mut ui, id, Vector2 { x: 10, y: 10 });
+    }
+    assert!(changed);
+    assert_eq!(picker.color(), Color::from_raw(0xff00ff00));
+    assert_eq!(picker.recent(), &[Color::from_raw(0xff00ff00)]);
+    assert_eq!(picker.hex.value, "#00ff00");
+}
@@ -0,0 +1,209 @@
+use just_canvas::{draw, latency::LatencyReport, Color, Vector2};
+use std::{collections::VecDeque, time::Duration};
+
+use crate::{Ui, UiId};
+
+const FRAME_HISTORY: usize = 120;
+const BOUNDS_COLOR: Color = Color::from_raw(0xff00ffff);
+const HOT_COLOR: Color = Color::from_raw(0xffffff00);
+const ACTIVE_COLOR: Color = Color::from_raw(0xffff0000);
+const GRAPH_BACKGROUND: Color = Color::from_raw(0xc0000000);
+const GRAPH_BAR: Color = Color::from_raw(0xff00ff00);
+const GRAPH_SLOW_BAR: Color = Color::from_raw(0xffff0000);
+/// Frames taking longer than that are drawn in different color
+const GRAPH_BUDGET: Duration = Duration::from_micros(16667);
+
+#[derive(Debug, Clone, Copy)]
+struct WidgetBounds {
+    id: UiId,
+    absolute_position: Vector2<i32>,
+    size: Vector2<u32>,
+}
+
+/// Overlay with widget bounds, ids, hot/active state and frame time graph, toggled with F12
+pub(crate) struct DebugOverlay {
+    pub(crate) enabled: bool,
+    widgets: Vec<WidgetBounds>,
+    frame_times: VecDeque<Duration>,
+}
+
+impl DebugOverlay {
+    pub(crate) fn new() -> Self {
+        Self {
+            enabled: false,
+            widgets: Vec::new(),
+            frame_times: VecDeque::with_capacity(FRAME_HISTORY),
+        }
+    }
+
+    pub(crate) fn push_frame_time(&mut self, frame_time: Duration) {
+        if self.frame_times.len() == FRAME_HISTORY {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(frame_time);
+    }
+}
+
+impl Ui {
+    #[inline]
+    /// Show or hide debug overlay. Can be also toggled with F12.
+    pub fn set_debug_overlay(&mut self, enabled: bool) {
+        self.debug.enabled = enabled;
+        self.set_dirty();
+    }
+
+    #[inline]
+    pub fn debug_overlay(&self) -> bool {
+        self.debug.enabled
+    }
+
+    #[inline]
+    /// Report hit area of a widget, position is relative to the current view. Custom widgets
+    /// should call it so widgets drawn later can cover them and so they can cover widgets drawn
+    /// earlier, see [`Ui::is_occluded`]. Bounds are also shown in the debug overlay.
+    pub fn register_widget(&mut self, id: UiId, position: Vector2<i32>, size: Vector2<u32>) {
+        self.register_hit_area(id, position, size);
+        if !self.debug.enabled {
+            return;
+        }
+
+        self.debug.widgets.push(WidgetBounds {
+            id,
+            absolute_position: position + self.current_view().absolute_offset.as_i32(),
+            size,
+        });
+    }
+
+    /// Measure input to photon latency, reported above the frame time graph of the debug
+    /// overlay, see [`just_canvas::latency`]
+    pub fn set_latency_profiling(&mut self, enabled: bool) {
+        if enabled {
+            self.canvas.start_latency_profiling();
+        } else {
+            self.canvas.stop_latency_profiling();
+        }
+    }
+
+    #[inline]
+    pub fn latency_report(&self) -> Option<LatencyReport> {
+        self.canvas.latency_report()
+    }
+
+    pub(crate) fn draw_debug_overlay(&mut self) {
+        let widgets = std::mem::take(&mut self.debug.widgets);
+        for widget in &widgets {
+            let color = if self.is_active(widget.id) {
+                ACTIVE_COLOR
+            } else if self.is_hot(widget.id) {
+                HOT_COLOR
+            } else {
+                BOUNDS_COLOR
+            };
+            self.outline(widget.absolute_position, widget.size, color);
+
+            let label = format!("{}.{}.{}", widget.id.id, widget.id.parent, widget.id.index);
+            self.text(
+                Vector2 {
+                    x: widget.absolute_position.x,
+                    y: widget.absolute_position.y - 9,
+                },
+                1,
+                label.chars(),
+                color,
+            );
+        }
+
+        let bar_width = 2;
+        let graph_size = Vector2 {
+            x: FRAME_HISTORY as u32 * bar_width,
+            y: 64,
+        };
+        let window_size = self.canvas.window_size();
+        let graph_position = Vector2 {
+            x: window_size.x as i32 - graph_size.x as i32,
+            y: window_size.y as i32 - graph_size.y as i32,
+        };
+        draw::rectangle_blend(
+            &mut self.canvas,
+            graph_position,
+            graph_size,
+            GRAPH_BACKGROUND,
+        );
+        // Budget of one frame is at half of graph height
+        let budget_y = graph_position.y + graph_size.y as i32 / 2;
+        draw::thin_dashed_line(
+            &mut self.canvas,
+            Vector2 {
+                x: graph_position.x,
+                y: budget_y,
+            },
+            Vector2 {
+                x: graph_position.x + graph_size.x as i32,
+                y: budget_y,
+            },
+            GRAPH_SLOW_BAR,
+        );
+        for (idx, frame_time) in self.debug.frame_times.iter().enumerate() {
+            let height = ((frame_time.as_secs_f32() / GRAPH_BUDGET.as_secs_f32())
+                * (graph_size.y / 2) as f32)
+                .min(graph_size.y as f32) as u32;
+            let color = if *frame_time > GRAPH_BUDGET {
+                GRAPH_SLOW_BAR
+            } else {
+                GRAPH_BAR
+            };
+            draw::rectangle_replace(
+                &mut self.canvas,
+                Vector2 {
+                    x: graph_position.x + (idx as u32 * bar_width) as i32,
+                    y: graph_position.y + (graph_size.y - height) as i32,
+                },
+                Vector2 {
+                    x: bar_width,
+                    y: height,
+                },
+                color,
+            );
+        }
+
+        if let Some(report) = self.canvas.latency_report() {
+            let label = format!(
+                "latency p50 {:.1} p90 {:.1} p99 {:.1} max {:.1} ms ({})",
+                report.p50.as_secs_f32() * 1000.0,
+                report.p90.as_secs_f32() * 1000.0,
+                report.p99.as_secs_f32() * 1000.0,
+                report.max.as_secs_f32() * 1000.0,
+                report.samples,
+            );
+            let label_size = self.text_size(1, label.chars());
+            self.text(
+                Vector2 {
+                    x: window_size.x as i32 - label_size.x as i32,
+                    y: graph_position.y - label_size.y as i32,
+                },
+                1,
+                label.chars(),
+                GRAPH_BAR,
+            );
+        }
+    }
+
+    fn outline(&mut self, position: Vector2<i32>, size: Vector2<u32>, color: Color) {
+        let end = Vector2 {
+            x: position.x + size.x as i32 - 1,
+            y: position.y + size.y as i32 - 1,
+        };
+        let top_right = Vector2 {
+            x: end.x,
+            y: position.y,
+        };
+        let bottom_left = Vector2 {
+            x: position.x,
+            y: end.y,
+        };
+        draw::thin_line(&mut self.canvas, position, top_right, color);
+        draw::thin_line(&mut self.canvas, top_right, end, color);
+        draw::thin_line(&mut self.canvas, end, bottom_left, color);
+        draw::thin_line(&mut self.canvas, bottom_left, position, color);
+    }
+}
@@ -0,0 +1,374 @@
+use just_canvas::Vector2;
+use std::{mem, ops::RangeInclusive};
+
+use crate::{
+    hit, invisible_button,
+    monokaish::{Slider, TextInput},
+    repeat::RepeatRate,
+    theme::{Style, WidgetKind},
+    Ui, UiId,
+};
+
+/// Resolution of sliders created for `f32` fields
+const SLIDER_STEPS: u32 = 1000;
+
+/// Enum that can be edited with a dropdown
+pub trait FormEnum: Copy + PartialEq + 'static {
+    const VARIANTS: &'static [Self];
+
+    fn name(self) -> &'static str;
+}
+
+/// Struct that can be edited with a [`Form`], see [`form`]
+pub trait FormFields {
+    fn form_fields(&mut self, form: &mut Form);
+}
+
+/// Form with one labeled row per field, built each frame
+pub struct Form<'a> {
+    ui: &'a mut Ui,
+    parent: u32,
+    position: Vector2<i32>,
+    row: u32,
+    y_offset: i32,
+    changed: bool,
+}
+
+impl<'a> Form<'a> {
+    pub fn new(ui: &'a mut Ui, parent: u32, position: Vector2<i32>) -> Self {
+        Self {
+            ui,
+            parent,
+            position,
+            row: 0,
+            y_offset: 0,
+            changed: false,
+        }
+    }
+
+    /// Whether any field was changed this frame
+    pub fn finish(self) -> bool {
+        self.changed
+    }
+
+    fn row_height(&self) -> u32 {
+        self.ui.scaled(32)
+    }
+
+    fn font_size(&self) -> u32 {
+        self.ui.scaled(self.style().font_size)
+    }
+
+    fn style(&self) -> Style {
+        self.ui.style(WidgetKind::Form)
+    }
+
+    /// Draw label of the next row and return its id and position of the field
+    fn next_row(&mut self, label: &str) -> (UiId, Vector2<i32>) {
+        let id = UiId {
+            id: self.row,
+            parent: self.parent,
+            index: 0,
+        };
+        let row_position = Vector2 {
+            x: self.position.x,
+            y: self.position.y + self.y_offset,
+        };
+        let font_size = self.font_size();
+        // Center 8px font vertically in the row
+        let label_y = (self.row_height() as i32 - 8 * font_size as i32) / 2;
+        self.ui.text(
+            Vector2 {
+                x: row_position.x,
+                y: row_position.y + label_y,
+            },
+            font_size,
+            label.chars(),
+            self.style().text,
+        );
+
+        self.row += 1;
+        self.y_offset += self.row_height() as i32;
+
+        let field_position = Vector2 {
+            x: row_position.x + self.ui.scaled(200) as i32,
+            y: row_position.y,
+        };
+        (id, field_position)
+    }
+
+    pub fn checkbox(&mut self, label: &str, value: &mut bool) -> &mut Self {
+        let (id, position) = self.next_row(label);
+        let size = Vector2 {
+            x: self.ui.scaled(20),
+            y: self.ui.scaled(20),
+        };
+        let position = Vector2 {
+            x: position.x,
+            y: position.y + (self.row_height() - size.y) as i32 / 2,
+        };
+
+        let button = invisible_button(self.ui, id, hit::rect(position, size));
+        if button.got_released {
+            *value = !*value;
+            self.changed = true;
+            self.ui.set_dirty();
+        }
+
+        self.ui.rectangle(position, size, self.style().foreground);
+        self.ui.register_widget(id, position, size);
+        if *value {
+            let pad = self.ui.scaled(self.style().padding);
+            self.ui.rectangle(
+                Vector2 {
+                    x: position.x + pad as i32,
+                    y: position.y + pad as i32,
+                },
+                Vector2 {
+                    x: size.x - pad * 2,
+                    y: size.y - pad * 2,
+                },
+                self.style().accent,
+            );
+        }
+
+        self
+    }
+
+    pub fn slider(
+        &mut self,
+        label: &str,
+        value: &mut f32,
+        range: RangeInclusive<f32>,
+    ) -> &mut Self {
+        let (id, position) = self.next_row(label);
+        let (start, end) = (*range.start(), *range.end());
+        let normalized = if end > start {
+            ((*value - start) / (end - start)).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+
+        let mut slider = Slider {
+            min: 0,
+            max: SLIDER_STEPS,
+            value: (normalized * SLIDER_STEPS as f32).round() as u32,
+        };
+        let old_value = slider.value;
+        let length = self.ui.scaled(200);
+        slider.draw(
+            self.ui,
+            id,
+            Vector2 {
+                x: position.x,
+                y: position.y + self.row_height() as i32 / 2,
+            },
+            length,
+        );
+
+        if slider.value != old_value {
+            *value = start + (end - start) * slider.value as f32 / SLIDER_STEPS as f32;
+            self.changed = true;
+        }
+
+        self
+    }
+
+    pub fn text_input(&mut self, label: &str, value: &mut String) -> &mut Self {
+        let (id, position) = self.next_row(label);
+
+        // Only focused input has a cursor so it is enough to keep one
+        let cursor = if self.ui.is_active(id) {
+            self.ui.form_cursor
+        } else {
+            value.chars().count()
+        };
+        let mut input = TextInput {
+            value: mem::take(value),
+            cursor,
+        };
+        let old_value = input.value.clone();
+        input.draw(
+            self.ui,
+            id,
+            Vector2 {
+                x: position.x,
+                y: position.y + (self.row_height() - self.ui.scaled(26)) as i32 / 2,
+            },
+        );
+
+        if self.ui.is_active(id) {
+            self.ui.form_cursor = input.cursor;
+        }
+        if input.value != old_value {
+            self.changed = true;
+        }
+        *value = input.value;
+
+        self
+    }
+
+    /// Number with `-` and `+` buttons, held buttons repeat with [`RepeatRate::default`]
+    pub fn spinner(
+        &mut self,
+        label: &str,
+        value: &mut i32,
+        range: RangeInclusive<i32>,
+    ) -> &mut Self {
+        let (id, position) = self.next_row(label);
+        let font_size = self.font_size();
+        let size = Vector2 {
+            x: self.ui.scaled(26),
+            y: self.ui.scaled(26),
+        };
+        let y = position.y + (self.row_height() - size.y) as i32 / 2;
+        let text_width = self.ui.scaled(200) - size.x * 2;
+
+        let mut delta = 0;
+        for (index, x, sign, step) in [
+            (1, position.x, '-', -1),
+            (2, position.x + (size.x + text_width) as i32, '+', 1),
+        ] {
+            let button_id = UiId { index, ..id };
+            let button_position = Vector2 { x, y };
+            let button = invisible_button(self.ui, button_id, hit::rect(button_position, size));
+            delta += step * self.ui.repeat(button_id, &button, RepeatRate::default()) as i32;
+
+            let color = if button.is_pressed {
+                self.style().accent
+            } else {
+                self.style().foreground
+            };
+            self.ui.rectangle(button_position, size, color);
+            self.ui.register_widget(button_id, button_position, size);
+            let glyph = self.ui.text_size(font_size, [sign]);
+            self.ui.text(
+                Vector2 {
+                    x: x + (size.x as i32 - glyph.x as i32) / 2,
+                    y: y + (size.y as i32 - glyph.y as i32) / 2,
+                },
+                font_size,
+                [sign],
+                self.style().text,
+            );
+        }
+
+        let new_value = value
+            .saturating_add(delta)
+            .clamp(*range.start(), *range.end());
+        if new_value != *value {
+            *value = new_value;
+            self.changed = true;
+            self.ui.set_dirty();
+        }
+
+        let text = value.to_string();
+        let text_size = self.ui.text_size(font_size, text.chars());
+        self.ui.text(
+            Vector2 {
+                x: position.x + size.x as i32 + (text_width as i32 - text_size.x as i32) / 2,
+                y: y + (size.y as i32 - text_size.y as i32) / 2,
+            },
+            font_size,
+            text.chars(),
+            self.style().text,
+        );
+
+        self
+    }
+
+    /// Dropdown that expands in place, pushing following rows down while open
+    pub fn dropdown<T: FormEnum>(&mut self, label: &str, value: &mut T) -> &mut Self {
+        let (id, position) = self.next_row(label);
+        let font_size = self.font_size();
+        let size = Vector2 {
+            x: self.ui.scaled(200),
+            y: self.ui.scaled(26),
+        };
+        let pad = (self.row_height() - size.y) as i32 / 2;
+        let text_pad = (size.y as i32 - 8 * font_size as i32) / 2;
+
+        let header_position = Vector2 {
+            x: position.x,
+            y: position.y + pad,
+        };
+        let header = invisible_button(self.ui, id, hit::rect(header_position, size));
+        let is_open = self.ui.form_open_dropdown == Some(id);
+        if header.got_released {
+            self.ui.form_open_dropdown = if is_open { None } else { Some(id) };
+            self.ui.set_dirty();
+        }
+
+        self.ui
+            .rectangle(header_position, size, self.style().foreground);
+        self.ui.register_widget(id, header_position, size);
+        self.ui.text(
+            Vector2 {
+                x: header_position.x + text_pad,
+                y: header_position.y + text_pad,
+            },
+            font_size,
+            value.name().chars(),
+            self.style().accent,
+        );
+
+        if !is_open {
+            return self;
+        }
+
+        for (idx, variant) in T::VARIANTS.iter().enumerate() {
+            let item_id = UiId {
+                index: idx as u32 + 1,
+                ..id
+            };
+            let item_position = Vector2 {
+                x: position.x,
+                y: position.y + self.row_height() as i32 * (idx as i32 + 1) + pad,
+            };
+            let item = invisible_button(self.ui, item_id, hit::rect(item_position, size));
+            if item.got_released {
+                if *value != *variant {
+                    *value = *variant;
+                    self.changed = true;
+                }
+                self.ui.form_open_dropdown = None;
+                self.ui.set_dirty();
+            }
+            if item.got_hovered || item.got_unhovered {
+                self.ui.set_dirty();
+            }
+
+            let color = if item.is_hovered {
+                self.style().highlight
+            } else {
+                self.style().background
+            };
+            self.ui.rectangle(item_position, size, color);
+            self.ui.register_widget(item_id, item_position, size);
+            self.ui.text(
+                Vector2 {
+                    x: item_position.x + text_pad,
+                    y: item_position.y + text_pad,
+                },
+                font_size,
+                variant.name().chars(),
+                self.style().text,
+            );
+        }
+        self.y_offset += self.row_height() as i32 * T::VARIANTS.len() as i32;
+
+        self
+    }
+}
+
+/// Draw form for all fields of `value`. Returns `true` if anything changed.
+pub fn form<T: FormFields>(
+    ui: &mut Ui,
+    parent: u32,
+    position: Vector2<i32>,
+    value: &mut T,
+) -> bool {
+    let mut form = Form::new(ui, parent, position);
+    value.form_fields(&mut form);
+    form.finish()
+}
@@ -0,0 +1,412 @@
+//! Gamepad input read directly from Linux evdev devices (`/dev/input/event*`)
+
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Read},
+    os::unix::{fs::OpenOptionsExt, io::AsRawFd},
+    path::{Path, PathBuf},
+};
+
+const O_NONBLOCK: i32 = 0o4000;
+
+const EV_KEY: u16 = 0x01;
+const EV_ABS: u16 = 0x03;
+
+const ABS_HAT0X: u16 = 0x10;
+const ABS_HAT0Y: u16 = 0x11;
+const ABS_CNT: usize = 0x40;
+
+/// `struct input_event` on 64-bit targets
+const INPUT_EVENT_SIZE: usize = 24;
+
+extern "C" {
+    fn ioctl(fd: i32, request: u64, ...) -> i32;
+}
+
+/// `struct input_absinfo`
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+struct AbsInfo {
+    value: i32,
+    minimum: i32,
+    maximum: i32,
+    fuzz: i32,
+    flat: i32,
+    resolution: i32,
+}
+
+/// `EVIOCGABS(abs)`
+#[inline(always)]
+const fn eviocgabs(abs: u16) -> u64 {
+    const IOC_READ: u64 = 2;
+    (IOC_READ << 30)
+        | ((std::mem::size_of::<AbsInfo>() as u64) << 16)
+        | ((b'E' as u64) << 8)
+        | (0x40 + abs as u64)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GamepadButton {
+    South,
+    East,
+    North,
+    West,
+    LeftBumper,
+    RightBumper,
+    LeftTrigger,
+    RightTrigger,
+    Select,
+    Start,
+    Mode,
+    LeftThumb,
+    RightThumb,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+impl GamepadButton {
+    const COUNT: usize = 17;
+
+    fn from_evdev_code(code: u16) -> Option<Self> {
+        match code {
+            0x130 => Some(Self::South),
+            0x131 => Some(Self::East),
+            0x133 => Some(Self::North),
+            0x134 => Some(Self::West),
+            0x136 => Some(Self::LeftBumper),
+            0x137 => Some(Self::RightBumper),
+            0x138 => Some(Self::LeftTrigger),
+            0x139 => Some(Self::RightTrigger),
+            0x13a => Some(Self::Select),
+            0x13b => Some(Self::Start),
+            0x13c => Some(Self::Mode),
+            0x13d => Some(Self::LeftThumb),
+            0x13e => Some(Self::RightThumb),
+            0x220 => Some(Self::DPadUp),
+            0x221 => Some(Self::DPadDown),
+            0x222 => Some(Self::DPadLeft),
+            0x223 => Some(Self::DPadRight),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GamepadAxis {
+    LeftX,
+    LeftY,
+    LeftTrigger,
+    RightX,
+    RightY,
+    RightTrigger,
+}
+
+impl GamepadAxis {
+    const COUNT: usize = 6;
+
+    fn from_evdev_code(code: u16) -> Option<Self> {
+        match code {
+            0x00 => Some(Self::LeftX),
+            0x01 => Some(Self::LeftY),
+            0x02 => Some(Self::LeftTrigger),
+            0x03 => Some(Self::RightX),
+            0x04 => Some(Self::RightY),
+            0x05 => Some(Self::RightTrigger),
+            _ => None,
+        }
+    }
+}
+
+/// State of a single controller, updated once per frame
+#[derive(Debug)]
+pub struct Gamepad {
+    path: PathBuf,
+    file: Option<File>,
+    abs_info: [AbsInfo; ABS_CNT],
+    buttons: [bool; GamepadButton::COUNT],
+    previous_buttons: [bool; GamepadButton::COUNT],
+    axes: [f32; GamepadAxis::COUNT],
+}
+
+impl Gamepad {
+    fn open(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .custom_flags(O_NONBLOCK)
+            .open(path)?;
+
+        let mut abs_info = [AbsInfo::default(); ABS_CNT];
+        for (code, info) in abs_info.iter_mut().enumerate() {
+            // Not every device reports every axis, failed query leaves the range empty
+            unsafe {
+                ioctl(
+                    file.as_raw_fd(),
+                    eviocgabs(code as u16),
+                    info as *mut AbsInfo,
+                );
+            }
+        }
+
+        Ok(Self::with_abs_info(
+            path.to_path_buf(),
+            Some(file),
+            abs_info,
+        ))
+    }
+
+    fn with_abs_info(path: PathBuf, file: Option<File>, abs_info: [AbsInfo; ABS_CNT]) -> Self {
+        let mut gamepad = Self {
+            path,
+            file,
+            abs_info,
+            buttons: [false; GamepadButton::COUNT],
+            previous_buttons: [false; GamepadButton::COUNT],
+            axes: [0.0; GamepadAxis::COUNT],
+        };
+        for code in 0..ABS_CNT as u16 {
+            let value = gamepad.abs_info[code as usize].value;
+            gamepad.handle_abs(code, value);
+        }
+        gamepad
+    }
+
+    /// Device node this gamepad is read from
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    #[inline]
+    pub fn is_connected(&self) -> bool {
+        self.file.is_some()
+    }
+
+    #[inline]
+    pub fn is_pressed(&self, button: GamepadButton) -> bool {
+        self.buttons[button as usize]
+    }
+
+    #[inline]
+    /// Return `true` if button was pressed since previous frame
+    pub fn got_pressed(&self, button: GamepadButton) -> bool {
+        self.buttons[button as usize] && !self.previous_buttons[button as usize]
+    }
+
+    #[inline]
+    /// Return `true` if button was released since previous frame
+    pub fn got_released(&self, button: GamepadButton) -> bool {
+        !self.buttons[button as usize] && self.previous_buttons[button as usize]
+    }
+
+    #[inline]
+    /// Sticks are in `-1.0..=1.0` range, triggers in `0.0..=1.0`
+    pub fn axis(&self, axis: GamepadAxis) -> f32 {
+        self.axes[axis as usize]
+    }
+
+    #[inline]
+    /// Force feedback is not supported yet, this is a no-op
+    pub fn rumble(&mut self, _strength: f32, _duration: std::time::Duration) {}
+
+    fn poll(&mut self) {
+        self.previous_buttons = self.buttons;
+
+        let Some(mut file) = self.file.take() else {
+            return;
+        };
+
+        let mut buf = [0u8; INPUT_EVENT_SIZE * 64];
+        loop {
+            match file.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => self.handle_raw_events(&buf[..n]),
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                    self.file = Some(file);
+                    return;
+                }
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(_) => break,
+            }
+        }
+
+        self.disconnect();
+    }
+
+    fn disconnect(&mut self) {
+        self.file = None;
+        self.buttons = [false; GamepadButton::COUNT];
+        self.axes = [0.0; GamepadAxis::COUNT];
+    }
+
+    fn handle_raw_events(&mut self, raw: &[u8]) {
+        for event in raw.chunks_exact(INPUT_EVENT_SIZE) {
+            let ty = u16::from_ne_bytes([event[16], event[17]]);
+            let code = u16::from_ne_bytes([event[18], event[19]]);
+            let value = i32::from_ne_bytes([event[20], event[21], event[22], event[23]]);
+
+            match ty {
+                EV_KEY => {
+                    if let Some(button) = GamepadButton::from_evdev_code(code) {
+                        // value 2 is autorepeat
+                        self.buttons[button as usize] = value != 0;
+                    }
+                }
+                EV_ABS => self.handle_abs(code, value),
+                _ => {}
+            }
+        }
+    }
+
+    fn handle_abs(&mut self, code: u16, value: i32) {
+        match code {
+            ABS_HAT0X => {
+                self.buttons[GamepadButton::DPadLeft as usize] = value < 0;
+                self.buttons[GamepadButton::DPadRight as usize] = value > 0;
+            }
+            ABS_HAT0Y => {
+                self.buttons[GamepadButton::DPadUp as usize] = value < 0;
+                self.buttons[GamepadButton::DPadDown as usize] = value > 0;
+            }
+            _ => {
+                let Some(axis) = GamepadAxis::from_evdev_code(code) else {
+                    return;
+                };
+                let info = self.abs_info[code as usize];
+                if info.maximum <= info.minimum {
+                    return;
+                }
+
+                let centered = matches!(
+                    axis,
+                    GamepadAxis::LeftX
+                        | GamepadAxis::LeftY
+                        | GamepadAxis::RightX
+                        | GamepadAxis::RightY
+                );
+                let range = (info.maximum - info.minimum) as f32;
+                let normalized = (value - info.minimum) as f32 / range;
+                let normalized = if centered {
+                    let mid = info.minimum as f32 + range / 2.0;
+                    if (value as f32 - mid).abs() <= info.flat as f32 {
+                        0.0
+                    } else {
+                        normalized * 2.0 - 1.0
+                    }
+                } else {
+                    normalized
+                };
+                self.axes[axis as usize] = normalized.clamp(-1.0, 1.0);
+            }
+        }
+    }
+}
+
+/// All gamepads found in `/dev/input/by-id`
+#[derive(Debug)]
+pub struct Gamepads {
+    gamepads: Vec<Gamepad>,
+}
+
+impl Gamepads {
+    pub fn new() -> Self {
+        let mut gamepads = Self {
+            gamepads: Vec::new(),
+        };
+        gamepads.rescan();
+        gamepads
+    }
+
+    /// Look for newly connected controllers and forget disconnected ones
+    pub fn rescan(&mut self) {
+        self.gamepads.retain(|gamepad| gamepad.is_connected());
+
+        let Ok(entries) = fs::read_dir("/dev/input/by-id") else {
+            return;
+        };
+
+        for entry in entries.flatten() {
+            let is_joystick = entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| name.ends_with("-event-joystick"));
+            if !is_joystick {
+                continue;
+            }
+
+            let Ok(path) = fs::canonicalize(entry.path()) else {
+                continue;
+            };
+            if self.gamepads.iter().any(|gamepad| gamepad.path == path) {
+                continue;
+            }
+
+            if let Ok(gamepad) = Gamepad::open(&path) {
+                self.gamepads.push(gamepad);
+            }
+        }
+    }
+
+    pub(crate) fn poll(&mut self) {
+        for gamepad in self.gamepads.iter_mut() {
+            gamepad.poll();
+        }
+    }
+
+    #[inline]
+    pub fn as_slice(&self) -> &[Gamepad] {
+        &self.gamepads
+    }
+
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [Gamepad] {
+        &mut self.gamepads
+    }
+}
+
+#[cfg(test)]
+fn raw_event(ty: u16, code: u16, value: i32) -> [u8; INPUT_EVENT_SIZE] {
+    let mut event = [0u8; INPUT_EVENT_SIZE];
+    event[16..18].copy_from_slice(&ty.to_ne_bytes());
+    event[18..20].copy_from_slice(&code.to_ne_bytes());
+    event[20..24].copy_from_slice(&value.to_ne_bytes());
+    event
+}
+
+#[test]
+fn gamepad_raw_events() {
+    let mut abs_info = [AbsInfo::default(); ABS_CNT];
+    abs_info[0] = AbsInfo {
+        value: 128,
+        minimum: 0,
+        maximum: 256,
+        flat: 8,
+        ..AbsInfo::default()
+    };
+    abs_info[2] = AbsInfo {
+        minimum: 0,
+        maximum: 1023,
+        ..AbsInfo::default()
+    };
+    let mut gamepad = Gamepad::with_abs_info(PathBuf::new(), None, abs_info);
+    assert_eq!(gamepad.axis(GamepadAxis::LeftX), 0.0);
+
+    let mut raw = Vec::new();
+    raw.extend(raw_event(EV_KEY, 0x130, 1));
+    raw.extend(raw_event(EV_ABS, 0x00, 256));
+    raw.extend(raw_event(EV_ABS, 0x02, 1023));
+    raw.extend(raw_event(EV_ABS, ABS_HAT0X, -1));
+    gamepad.handle_raw_events(&raw);
+
+    assert!(gamepad.is_pressed(GamepadButton::South));
+    assert!(gamepad.got_pressed(GamepadButton::South));
+    assert!(gamepad.is_pressed(GamepadButton::DPadLeft));
+    assert!(!gamepad.is_pressed(GamepadButton::DPadRight));
+    assert_eq!(gamepad.axis(GamepadAxis::LeftX), 1.0);
+    assert_eq!(gamepad.axis(GamepadAxis::LeftTrigger), 1.0);
+
+    gamepad.poll();
+    gamepad.handle_raw_events(&raw_event(EV_KEY, 0x130, 0));
+    assert!(gamepad.got_released(GamepadButton::South));
+}
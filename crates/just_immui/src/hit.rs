@@ -0,0 +1,49 @@
+//! Standard `in_bounds` helpers for [`crate::invisible_button`] and friends. All shapes include
+//! their edges and take positions relative to the current view, like the pointer passed to
+//! `in_bounds`.
+
+use just_canvas::{draw, Vector2};
+
+pub fn rect(position: Vector2<i32>, size: Vector2<u32>) -> impl Fn(Vector2<u32>) -> bool {
+    move |pointer| draw::inside_rectangle(position, size, pointer.as_i32())
+}
+
+pub fn circle(center: Vector2<i32>, r: u32) -> impl Fn(Vector2<u32>) -> bool {
+    move |pointer| draw::inside_circle(center, r, pointer.as_i32())
+}
+
+pub fn rounded_rect(
+    position: Vector2<i32>,
+    size: Vector2<u32>,
+    radius: u32,
+) -> impl Fn(Vector2<u32>) -> bool {
+    move |pointer| draw::inside_rounded_rectangle(position, size, radius, pointer.as_i32())
+}
+
+pub fn convex_polygon(vertices: &[Vector2<i32>]) -> impl Fn(Vector2<u32>) -> bool + '_ {
+    move |pointer| draw::inside_convex_polygon(vertices, pointer.as_i32())
+}
+
+/// Pointer is inside of either shape
+pub fn union(
+    a: impl Fn(Vector2<u32>) -> bool,
+    b: impl Fn(Vector2<u32>) -> bool,
+) -> impl Fn(Vector2<u32>) -> bool {
+    move |pointer| a(pointer) || b(pointer)
+}
+
+/// Pointer is inside of both shapes
+pub fn intersection(
+    a: impl Fn(Vector2<u32>) -> bool,
+    b: impl Fn(Vector2<u32>) -> bool,
+) -> impl Fn(Vector2<u32>) -> bool {
+    move |pointer| a(pointer) && b(pointer)
+}
+
+/// Pointer is inside of `a` but not inside of `b`
+pub fn difference(
+    a: impl Fn(Vector2<u32>) -> bool,
+    b: impl Fn(Vector2<u32>) -> bool,
+) -> impl Fn(Vector2<u32>) -> bool {
+    move |pointer| a(pointer) && !b(pointer)
+}
@@ -0,0 +1,258 @@
+use just_canvas::{
+    draw,
+    image::{Image, ImageError},
+    Vector2,
+};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    thread,
+};
+
+use crate::{invisible_button, Button, Ui, UiId};
+
+#[derive(Debug, Clone, Copy)]
+pub enum ImageSource<'a> {
+    /// Already decoded image, e.g. from [`Image::from_rgba`]
+    Image(&'a Image),
+    /// File decoded on a background thread and cached by path, nothing is drawn until it's
+    /// decoded. See [`Ui::invalidate_image`].
+    File(&'a Path),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFit {
+    /// Whole image is visible, keeping aspect ratio and centered in the view
+    Contain,
+    /// Image covers whole view, keeping aspect ratio and cropping it equally on both sides
+    Cover,
+    /// Image is scaled to the view size
+    Stretch,
+}
+
+#[derive(Debug, Clone)]
+enum CacheEntry {
+    /// Decode started with this id, results of older decodes of the path are ignored
+    Loading(u64),
+    Loaded(Arc<Image>),
+    Failed(ImageError),
+}
+
+/// Decoded files by path
+#[derive(Debug, Default)]
+pub(crate) struct ImageCache {
+    entries: HashMap<PathBuf, CacheEntry>,
+    next_load: u64,
+}
+
+/// Sent from the decoding thread
+struct Decoded {
+    path: PathBuf,
+    load: u64,
+    image: Result<Image, ImageError>,
+}
+
+/// Where to draw and which part of the image, as destination position, destination size,
+/// source position and source size
+fn fit_image(
+    image_size: Vector2<u32>,
+    view_size: Vector2<u32>,
+    fit: ImageFit,
+) -> (Vector2<i32>, Vector2<u32>, Vector2<u32>, Vector2<u32>) {
+    let origin = Vector2 { x: 0, y: 0 };
+    if image_size.x == 0 || image_size.y == 0 {
+        return (origin.as_i32(), origin, origin, origin);
+    }
+
+    // Compare image_size.x / image_size.y with view_size.x / view_size.y without floats
+    let wider = image_size.x as u64 * view_size.y as u64 > view_size.x as u64 * image_size.y as u64;
+    match fit {
+        ImageFit::Stretch => (origin.as_i32(), view_size, origin, image_size),
+        ImageFit::Contain => {
+            let size = if wider {
+                Vector2 {
+                    x: view_size.x,
+                    y: (image_size.y as u64 * view_size.x as u64 / image_size.x as u64) as u32,
+                }
+            } else {
+                Vector2 {
+                    x: (image_size.x as u64 * view_size.y as u64 / image_size.y as u64) as u32,
+                    y: view_size.y,
+                }
+            };
+            let position = Vector2 {
+                x: ((view_size.x - size.x) / 2) as i32,
+                y: ((view_size.y - size.y) / 2) as i32,
+            };
+            (position, size, origin, image_size)
+        }
+        ImageFit::Cover => {
+            let source_size = if wider {
+                Vector2 {
+                    x: (view_size.x as u64 * image_size.y as u64 / view_size.y.max(1) as u64)
+                        as u32,
+                    y: image_size.y,
+                }
+            } else {
+                Vector2 {
+                    x: image_size.x,
+                    y: (view_size.y as u64 * image_size.x as u64 / view_size.x.max(1) as u64)
+                        as u32,
+                }
+            };
+            let source_position = Vector2 {
+                x: (image_size.x - source_size.x) / 2,
+                y: (image_size.y - source_size.y) / 2,
+            };
+            (origin.as_i32(), view_size, source_position, source_size)
+        }
+    }
+}
+
+impl Ui {
+    /// Draw image fitted into the current view. Returns state of the image as an invisible
+    /// button, e.g. for clickable thumbnails or [`Ui::tooltip`].
+    pub fn image(&mut self, id: UiId, source: ImageSource, fit: ImageFit) -> Button {
+        let view = self.current_view();
+        let button = invisible_button(self, id, |pointer| {
+            pointer.x < view.size.x && pointer.y < view.size.y
+        });
+        if !self.is_dirty() {
+            return button;
+        }
+
+        let image = match source {
+            ImageSource::Image(image) => {
+                self.draw_image(image, fit);
+                return button;
+            }
+            ImageSource::File(path) => match self.images.entries.get(path) {
+                Some(CacheEntry::Loaded(image)) => image.clone(),
+                Some(CacheEntry::Loading(_) | CacheEntry::Failed(_)) => return button,
+                None => {
+                    self.load_image(path);
+                    return button;
+                }
+            },
+        };
+        self.draw_image(&image, fit);
+        button
+    }
+
+    /// Error of the last decode of `path`, if it failed
+    pub fn image_error(&self, path: &Path) -> Option<&ImageError> {
+        match self.images.entries.get(path) {
+            Some(CacheEntry::Failed(err)) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// Decode `path` again the next time it's drawn, e.g. after the file changed
+    pub fn invalidate_image(&mut self, path: &Path) {
+        if self.images.entries.remove(path).is_some() {
+            self.set_dirty();
+        }
+    }
+
+    /// Forget all decoded files
+    pub fn clear_image_cache(&mut self) {
+        self.images.entries.clear();
+    }
+
+    /// Own the clipboard with `image` in every format it can be encoded to, see
+    /// [`Canvas::copy_image`](just_canvas::Canvas::copy_image)
+    pub fn copy_image(&mut self, image: &Image) -> just_canvas::Result<bool> {
+        self.canvas_mut().copy_image(image)
+    }
+
+    /// Own the clipboard with what is drawn in the window so far, call it after drawing the
+    /// frame to copy all of it
+    pub fn copy_screenshot(&mut self) -> just_canvas::Result<bool> {
+        let screenshot = self.canvas().capture();
+        self.copy_image(&screenshot)
+    }
+
+    /// Image in the clipboard, `None` if there is none in a supported format
+    pub fn paste_image(&mut self) -> just_canvas::Result<Option<Image>> {
+        self.canvas_mut().paste_image()
+    }
+
+    fn draw_image(&mut self, image: &Image, fit: ImageFit) {
+        let view = self.current_view();
+        let (position, size, source_position, source_size) = fit_image(image.size, view.size, fit);
+        draw::image_blend(
+            self.canvas_mut(),
+            position + view.absolute_offset.as_i32(),
+            size,
+            image,
+            source_position,
+            source_size,
+        );
+    }
+
+    fn load_image(&mut self, path: &Path) {
+        let load = self.images.next_load;
+        self.images.next_load += 1;
+        self.images
+            .entries
+            .insert(path.to_path_buf(), CacheEntry::Loading(load));
+
+        let waker = self.waker::<Decoded>();
+        let path = path.to_path_buf();
+        thread::spawn(move || {
+            let image = match fs::read(&path) {
+                Ok(bytes) => Image::decode(&bytes),
+                Err(_) => Err(ImageError::Invalid("could not read file")),
+            };
+            waker.send(Decoded { path, load, image })
+        });
+    }
+
+    /// Move decoded images from received messages to the cache
+    pub(crate) fn receive_images(&mut self) {
+        for decoded in self.take_messages::<Decoded>() {
+            let current = matches!(
+                self.images.entries.get(&decoded.path),
+                Some(CacheEntry::Loading(load)) if *load == decoded.load
+            );
+            if !current {
+                continue;
+            }
+            let entry = match decoded.image {
+                Ok(image) => CacheEntry::Loaded(Arc::new(image)),
+                Err(err) => CacheEntry::Failed(err),
+            };
+            self.images.entries.insert(decoded.path, entry);
+        }
+    }
+}
+
+#[test]
+fn image_fit_modes() {
+    let image = Vector2 { x: 40, y: 20 };
+    let view = Vector2 { x: 20, y: 20 };
+
+    let (position, size, _, source_size) = fit_image(image, view, ImageFit::Contain);
+    assert_eq!((position.x, position.y, size.x, size.y), (0, 5, 20, 10));
+    assert_eq!((source_size.x, source_size.y), (40, 20));
+
+    let (position, size, source_position, source_size) = fit_image(image, view, ImageFit::Cover);
+    assert_eq!((position.x, position.y, size.x, size.y), (0, 0, 20, 20));
+    assert_eq!(
+        (
+            source_position.x,
+            source_position.y,
+            source_size.x,
+            source_size.y
+        ),
+        (10, 0, 20, 20)
+    );
+
+    let (_, size, _, source_size) = fit_image(image, view, ImageFit::Stretch);
+    assert_eq!(
+        (size.x, size.y, source_size.x, source_size.y),
+        (20, 20, 40, 20)
+    );
+}
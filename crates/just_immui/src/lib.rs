@@ -6,11 +6,45 @@
 )]
 
 use bdf::BdfCharMap;
-use just_canvas::{draw, Canvas, Color, Pointer, PointerButton, Result, Vector2};
-use std::{cmp, time::Duration};
+use just_canvas::{
+    draw,
+    keyboard::{KeyboardButton, SpecialKeyboardButton},
+    Canvas, CloseRequest, Color, DamageRect, InputDevice, KeyboardEvent, Lifecycle, Pointer,
+    PointerButton, Result, Vector2,
+};
+use std::{
+    any::Any,
+    cmp,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+const SCALE_STEP: f32 = 0.25;
+const MIN_SCALE: f32 = 0.5;
+const MAX_SCALE: f32 = 4.0;
+
+const VISUAL_BELL_DURATION: Duration = Duration::from_millis(100);
+const VISUAL_BELL_COLOR: Color = Color::from_raw(0x80ffffff);
 
 mod bdf;
+pub mod color_picker;
+mod debug;
+pub mod form;
+#[cfg(feature = "gamepad")]
+pub mod gamepad;
+pub mod hit;
+pub mod image;
+pub mod menu;
+pub mod modal;
 pub mod monokaish;
+mod occlusion;
+pub mod repeat;
+pub mod theme;
+mod tooltip;
+pub mod tree;
+mod waker;
+
+pub use waker::UiWaker;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct UiId {
@@ -19,6 +53,17 @@ pub struct UiId {
     pub index: u32,
 }
 
+/// Time spent in parts of the last frame of [`Ui::fps_limited_loop`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FrameTimings {
+    /// Drawing, including overlays drawn by the loop
+    pub draw: Duration,
+    /// Presenting the frame, zero if nothing was damaged
+    pub flush: Duration,
+    /// Whole frame without sleeping until the next one
+    pub frame: Duration,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct BoundedView {
     pub absolute_offset: Vector2<u32>,
@@ -40,14 +85,63 @@ impl View {
     }
 }
 
+/// Hot and active widgets of one master pointer, see [`Ui::set_per_device_pointers`]
+#[derive(Debug, Clone, Copy)]
+struct PointerSlot {
+    device: InputDevice,
+    hot: Option<UiId>,
+    active: Option<UiId>,
+}
+
 pub struct Ui {
     canvas: Canvas,
     hot: Option<UiId>,
     active: Option<UiId>,
+    /// `None` if all pointers share `hot` and `active`
+    pointer_slots: Option<Vec<PointerSlot>>,
+    /// Pointer a widget is evaluated for, its slot is swapped into `hot` and `active` meanwhile
+    current_pointer: Option<InputDevice>,
     font_char_map: BdfCharMap,
     dirty: bool,
     dirty_next: bool,
+    /// Areas to present in the next frame if it's not fully dirty, see [`Ui::mark_damaged`]
+    damage_next: Option<DamageRect>,
+    /// Present only this area of the current frame
+    damage: Option<DamageRect>,
     view: View,
+    scale: f32,
+    scale_keybindings: bool,
+    vsync: bool,
+    control_held: bool,
+    visual_bell: bool,
+    visual_bell_until: Option<Instant>,
+    form_cursor: usize,
+    form_open_dropdown: Option<UiId>,
+    context_menu: Option<menu::ContextMenuState>,
+    tooltip: Option<tooltip::TooltipState>,
+    /// Modal drawn in the previous frame, blocks input of everything else
+    modal: Option<UiId>,
+    /// Modal drawn in the current frame
+    modal_next: Option<UiId>,
+    /// Currently drawing contents of the modal
+    in_modal: bool,
+    /// Keyboard events hidden from widgets below the modal
+    modal_keyboard_events: Vec<KeyboardEvent>,
+    tooltip_delay: Duration,
+    theme: theme::Theme,
+    theme_watcher: Option<theme::ThemeWatcher>,
+    /// Held button of [`Ui::repeat`]
+    repeat: Option<repeat::RepeatState>,
+    images: image::ImageCache,
+    redraw_at: Option<Instant>,
+    waker: Arc<waker::Shared>,
+    /// Sent with [`UiWaker`], cleared after each drawn frame
+    messages: Vec<Box<dyn Any + Send>>,
+    debug: debug::DebugOverlay,
+    z_order: occlusion::ZOrder,
+    frame_timings: FrameTimings,
+    #[cfg(feature = "gamepad")]
+    gamepads: gamepad::Gamepads,
 }
 
 impl Ui {
@@ -63,10 +157,41 @@ impl Ui {
             canvas,
             hot: None,
             active: None,
+            pointer_slots: None,
+            current_pointer: None,
             font_char_map: BdfCharMap::ib8x8u(),
             dirty: true,
             dirty_next: false,
+            damage_next: None,
+            damage: None,
             view: View::Unbounded,
+            scale: 1.0,
+            scale_keybindings: true,
+            vsync: false,
+            control_held: false,
+            visual_bell: false,
+            visual_bell_until: None,
+            form_cursor: 0,
+            form_open_dropdown: None,
+            context_menu: None,
+            tooltip: None,
+            modal: None,
+            modal_next: None,
+            in_modal: false,
+            modal_keyboard_events: Vec::new(),
+            tooltip_delay: tooltip::DEFAULT_TOOLTIP_DELAY,
+            theme: theme::Theme::default(),
+            theme_watcher: None,
+            repeat: None,
+            images: image::ImageCache::default(),
+            redraw_at: None,
+            waker: Arc::default(),
+            messages: Vec::new(),
+            debug: debug::DebugOverlay::new(),
+            z_order: occlusion::ZOrder::default(),
+            frame_timings: FrameTimings::default(),
+            #[cfg(feature = "gamepad")]
+            gamepads: gamepad::Gamepads::new(),
         }
     }
 
@@ -95,10 +220,11 @@ impl Ui {
     }
 
     #[inline]
-    /// Return `true` if no other element was hot and this one was made hot
+    /// Return `true` if no other element was hot and this one was made hot. Widgets covered by
+    /// ones drawn after them can't become hot, see [`Ui::is_occluded`].
     pub fn make_hot(&mut self, id: UiId) -> bool {
         match self.hot {
-            None => {
+            None if !self.is_occluded(id) => {
                 self.hot = Some(id);
                 true
             }
@@ -119,13 +245,90 @@ impl Ui {
     }
 
     #[inline]
+    /// Widget is hot for the pointer it is evaluated for, or for any pointer outside of widgets
     pub fn is_hot(&self, id: UiId) -> bool {
-        self.hot.is_some_and(|hot| hot == id)
+        self.hot.is_some_and(|hot| hot == id) || self.other_slots().any(|slot| slot.hot == Some(id))
     }
 
     #[inline]
+    /// Widget is active for the pointer it is evaluated for, or for any pointer outside of widgets
     pub fn is_active(&self, id: UiId) -> bool {
         self.active.is_some_and(|active| active == id)
+            || self.other_slots().any(|slot| slot.active == Some(id))
+    }
+
+    /// Slots of all pointers unless a widget is evaluated for one of them
+    fn other_slots(&self) -> impl Iterator<Item = &PointerSlot> {
+        self.pointer_slots
+            .iter()
+            .flatten()
+            .filter(|_| self.current_pointer.is_none())
+    }
+
+    /// Track hot and active widgets separately for each master pointer, so people sharing the
+    /// screen with multiple pointers don't take widgets away from each other. Built-in widgets
+    /// then react to all pointers. Disabled by default.
+    pub fn set_per_device_pointers(&mut self, enabled: bool) {
+        if enabled != self.pointer_slots.is_some() {
+            self.pointer_slots = enabled.then(Vec::new);
+            self.reset_hot();
+        }
+    }
+
+    /// No widget is hot or active for any pointer
+    pub(crate) fn reset_hot(&mut self) {
+        self.hot = None;
+        self.active = None;
+        if let Some(slots) = &mut self.pointer_slots {
+            slots.clear();
+        }
+    }
+
+    /// Evaluate widget with `f` once for each master pointer with per-device pointers, results
+    /// are combined with `merge`. Otherwise `f` is called once for the shared pointer. `None` if
+    /// no pointer has been seen yet.
+    pub(crate) fn each_pointer<T>(
+        &mut self,
+        mut f: impl FnMut(&mut Self) -> T,
+        merge: impl Fn(T, T) -> T,
+    ) -> Option<T> {
+        if self.pointer_slots.is_none() || self.current_pointer.is_some() {
+            return Some(f(self));
+        }
+
+        let mut res = None;
+        for idx in 0..self.canvas.pointers().len() {
+            let device = self.canvas.pointers()[idx].device();
+            let slots = self.pointer_slots.as_mut().unwrap();
+            let slot = match slots.iter().position(|slot| slot.device == device) {
+                Some(slot) => slot,
+                None => {
+                    slots.push(PointerSlot {
+                        device,
+                        hot: None,
+                        active: None,
+                    });
+                    slots.len() - 1
+                }
+            };
+
+            let slot = &mut self.pointer_slots.as_mut().unwrap()[slot];
+            self.hot = slot.hot.take();
+            self.active = slot.active.take();
+            self.current_pointer = Some(device);
+            let pointer_res = f(self);
+            self.current_pointer = None;
+            let slots = self.pointer_slots.as_mut().unwrap();
+            let slot = slots.iter_mut().find(|slot| slot.device == device).unwrap();
+            slot.hot = self.hot.take();
+            slot.active = self.active.take();
+
+            res = Some(match res {
+                Some(res) => merge(res, pointer_res),
+                None => pointer_res,
+            });
+        }
+        res
     }
 
     #[inline]
@@ -138,11 +341,37 @@ impl Ui {
         self.dirty_next = true
     }
 
+    /// Redraw the next frame like [`Ui::set_dirty`] but present only the damaged area, which is
+    /// cheaper for small changes like a blinking cursor. Area is relative to the current view.
+    pub fn mark_damaged(&mut self, position: Vector2<i32>, size: Vector2<u32>) {
+        let damage = DamageRect::new(position + self.view.absolute_offset().as_i32(), size);
+        self.damage_next = Some(match self.damage_next {
+            Some(previous) => previous.union(damage),
+            None => damage,
+        });
+    }
+
+    /// Redraw and present whole current frame
+    pub(crate) fn redraw_all(&mut self) {
+        self.dirty = true;
+        self.damage = None;
+    }
+
     #[inline]
     pub(crate) fn canvas_mut(&mut self) -> &mut Canvas {
         &mut self.canvas
     }
 
+    #[inline]
+    /// Redraw once `delay` passes even if nothing else changed, e.g. to start or stop an
+    /// animation. Earlier request wins if there are multiple.
+    pub fn redraw_after(&mut self, delay: Duration) {
+        let at = Instant::now() + delay;
+        if self.redraw_at.is_none_or(|redraw_at| at < redraw_at) {
+            self.redraw_at = Some(at);
+        }
+    }
+
     #[inline]
     pub fn canvas(&self) -> &Canvas {
         &self.canvas
@@ -191,6 +420,27 @@ impl Ui {
         }
     }
 
+    /// Rectangle with colors interpolated between `corners`, see [`draw::rectangle_gradient`]
+    #[inline]
+    pub fn gradient_rectangle(
+        &mut self,
+        position: Vector2<i32>,
+        size: Vector2<u32>,
+        corners: [Color; 4],
+    ) {
+        if !self.is_dirty() {
+            return;
+        }
+
+        let absolute_position = position + self.view.absolute_offset().as_i32();
+        let visible = Vector2 {
+            x: (self.current_view().size.x as i32 - position.x).max(0) as u32,
+            y: (self.current_view().size.y as i32 - position.y).max(0) as u32,
+        };
+
+        draw::rectangle_gradient(&mut self.canvas, absolute_position, size, visible, corners);
+    }
+
     #[inline]
     pub fn thin_line(&mut self, start: Vector2<i32>, end: Vector2<i32>, color: Color) {
         if !self.is_dirty() {
@@ -225,54 +475,367 @@ impl Ui {
     }
 
     #[inline]
-    /// Raw pointer, with position being relative to the top level canvas, NOT to current view
+    /// Stroked part of a circle, angles like in [`draw::arc_blend_with_anti_aliasing`]
+    pub fn arc(
+        &mut self,
+        center: Vector2<i32>,
+        r: u32,
+        stroke_width: u32,
+        start_angle: f32,
+        end_angle: f32,
+        color: Color,
+    ) {
+        if !self.is_dirty() {
+            return;
+        }
+
+        let off = self.current_view().absolute_offset.as_i32();
+
+        draw::arc_blend_with_anti_aliasing(
+            &mut self.canvas,
+            center + off,
+            r,
+            stroke_width,
+            start_angle,
+            end_angle,
+            color,
+        );
+    }
+
+    #[inline]
+    /// Raw pointer, with position being relative to the top level canvas, NOT to current view.
+    /// With per-device pointers it is the one the widget is evaluated for.
     pub fn pointer_absolute(&self) -> &Pointer {
-        self.canvas.pointer()
+        self.current_pointer
+            .and_then(|device| self.canvas.pointer_of(device))
+            .unwrap_or(self.canvas.pointer())
     }
 
     #[inline]
     /// Pointer position relative to the current view
     pub fn pointer_position(&self) -> Vector2<u32> {
-        (self.canvas.pointer().position.as_i32() - self.current_view().absolute_offset.as_i32())
+        (self.pointer_absolute().position.as_i32() - self.current_view().absolute_offset.as_i32())
             .clamp_non_negative()
             .as_u32()
     }
 
+    #[inline]
+    /// Whether pointer is inside of the current view. Views are clipped to their parents, so
+    /// widgets drawn outside of the visible area, e.g. scrolled out of a list, can't be hovered.
+    /// Always `false` for views below an open [`modal::modal`].
+    pub fn pointer_in_view(&self) -> bool {
+        if self.is_blocked_by_modal() {
+            return false;
+        }
+
+        let view = self.current_view();
+        draw::inside_rectangle(
+            view.absolute_offset.as_i32(),
+            view.size,
+            self.pointer_absolute().position.as_i32(),
+        ) && view.size.x > 0
+            && view.size.y > 0
+    }
+
+    /// Pointer is in the visible part of the current view and inside of the widget, which is not
+    /// covered by a widget drawn after it
+    pub(crate) fn pointer_hits(
+        &self,
+        id: UiId,
+        in_bounds: impl FnOnce(Vector2<u32>) -> bool,
+    ) -> bool {
+        self.pointer_in_view() && in_bounds(self.pointer_position()) && !self.is_occluded(id)
+    }
+
+    #[inline]
+    /// Move pointer to `position` relative to the current view
+    pub fn warp_pointer_to(&mut self, position: Vector2<u32>) -> Result<()> {
+        let absolute = position + self.current_view().absolute_offset;
+        self.canvas.warp_pointer_to(absolute)
+    }
+
+    #[inline]
+    /// See [`Canvas::set_pointer_capture`]
+    pub fn set_pointer_capture(&mut self, captured: bool) -> Result<bool> {
+        self.canvas.set_pointer_capture(captured)
+    }
+
+    #[inline]
+    /// See [`Canvas::set_on_close_requested`]
+    pub fn set_on_close_requested<F>(&mut self, hook: F)
+    where
+        F: FnMut() -> CloseRequest + 'static,
+    {
+        self.canvas.set_on_close_requested(hook)
+    }
+
+    #[inline]
+    /// See [`Canvas::on_lifecycle`]
+    pub fn on_lifecycle<F>(&mut self, callback: F)
+    where
+        F: FnMut(Lifecycle) + 'static,
+    {
+        self.canvas.on_lifecycle(callback)
+    }
+
+    #[inline]
+    /// See [`Canvas::request_close`]
+    pub fn request_close(&mut self) -> bool {
+        self.canvas.request_close()
+    }
+
+    #[inline]
+    /// See [`Canvas::close`]
+    pub fn close(&mut self) {
+        self.canvas.close()
+    }
+
     #[inline]
     pub fn resized(&self) -> bool {
         self.canvas.resized()
     }
 
+    #[inline]
+    /// UI scale factor applied to font sizes, paddings and hit targets of built-in widgets.
+    /// Independent of the display DPI.
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    #[inline]
+    pub fn set_scale(&mut self, scale: f32) {
+        let scale = scale.clamp(MIN_SCALE, MAX_SCALE);
+        if scale != self.scale {
+            self.scale = scale;
+            self.set_dirty();
+        }
+    }
+
+    #[inline]
+    /// Scale widget dimension by current UI scale factor. Non-zero values never scale to zero.
+    pub fn scaled(&self, value: u32) -> u32 {
+        if value == 0 {
+            return 0;
+        }
+        cmp::max(1, (value as f32 * self.scale).round() as u32)
+    }
+
+    #[inline]
+    /// Enable or disable changing scale with `Ctrl +`, `Ctrl -` and `Ctrl 0`. Enabled by default.
+    pub fn set_scale_keybindings(&mut self, enabled: bool) {
+        self.scale_keybindings = enabled;
+    }
+
+    fn handle_debug_keybinding(&mut self) {
+        let mut toggle = false;
+        self.canvas.keyboard_events.retain(|event| match event {
+            KeyboardEvent::Pressed(KeyboardButton::Special(SpecialKeyboardButton::F12), _) => {
+                toggle = !toggle;
+                false
+            }
+            _ => true,
+        });
+        if toggle {
+            let enabled = !self.debug.enabled;
+            self.set_debug_overlay(enabled);
+        }
+    }
+
+    fn handle_scale_keybindings(&mut self) {
+        let mut new_scale = self.scale;
+        let mut control_held = self.control_held;
+
+        self.canvas.keyboard_events.retain(|event| match event {
+            KeyboardEvent::Pressed(
+                KeyboardButton::Special(
+                    SpecialKeyboardButton::ControlL | SpecialKeyboardButton::ControlR,
+                ),
+                _,
+            ) => {
+                control_held = true;
+                true
+            }
+            KeyboardEvent::Released(
+                KeyboardButton::Special(
+                    SpecialKeyboardButton::ControlL | SpecialKeyboardButton::ControlR,
+                ),
+                _,
+            ) => {
+                control_held = false;
+                true
+            }
+            KeyboardEvent::Pressed(KeyboardButton::Unicode(c), _) if control_held => match c {
+                '+' | '=' => {
+                    new_scale += SCALE_STEP;
+                    false
+                }
+                '-' => {
+                    new_scale -= SCALE_STEP;
+                    false
+                }
+                '0' => {
+                    new_scale = 1.0;
+                    false
+                }
+                _ => true,
+            },
+            _ => true,
+        });
+
+        self.control_held = control_held;
+        self.set_scale(new_scale);
+    }
+
+    #[inline]
+    /// Pace [`Ui::fps_limited_loop`] by vertical blanks of the monitor instead of a timer, see
+    /// [`Canvas::request_frame`]. Its `fps` is then used only if the server can't report them.
+    pub fn set_vsync(&mut self, enabled: bool) {
+        self.vsync = enabled;
+    }
+
+    #[inline]
+    /// Briefly flash the window when keyboard bell rings
+    pub fn set_visual_bell(&mut self, enabled: bool) {
+        self.visual_bell = enabled;
+    }
+
+    #[inline]
+    /// Ring the keyboard bell, `percent` is relative to the base volume in `-100..=100` range
+    pub fn ring_bell(&mut self, percent: i8) -> Result<()> {
+        self.canvas.ring_bell(percent)
+    }
+
+    /// Return `true` if window is flashing and must be flushed
+    fn draw_visual_bell(&mut self, now: Instant) -> bool {
+        if self.visual_bell && self.canvas.bell().is_some() {
+            self.visual_bell_until = Some(now + VISUAL_BELL_DURATION);
+        }
+
+        match self.visual_bell_until {
+            Some(until) if now < until => {
+                let size = self.canvas.window_size();
+                draw::rectangle_blend(
+                    &mut self.canvas,
+                    Vector2::<i32>::zero(),
+                    size,
+                    VISUAL_BELL_COLOR,
+                );
+                // Redraw without the flash on the next frame
+                self.dirty_next = true;
+                true
+            }
+            Some(_) => {
+                self.visual_bell_until = None;
+                self.dirty_next = true;
+                false
+            }
+            None => false,
+        }
+    }
+
+    #[cfg(feature = "gamepad")]
+    #[inline]
+    pub fn gamepads(&self) -> &[gamepad::Gamepad] {
+        self.gamepads.as_slice()
+    }
+
+    #[cfg(feature = "gamepad")]
+    #[inline]
+    pub fn gamepads_mut(&mut self) -> &mut gamepad::Gamepads {
+        &mut self.gamepads
+    }
+
     pub fn fps_limited_loop<F>(&mut self, fps: u64, mut draw: F) -> Result<()>
     where
         F: FnMut(&mut Self),
     {
         while !self.canvas_mut().should_close() {
-            let frame_start = std::time::Instant::now();
+            let frame_start = Instant::now();
             self.canvas_mut().process_events()?;
+            self.receive_messages();
+            self.receive_images();
+            self.poll_theme();
+            if self.scale_keybindings {
+                self.handle_scale_keybindings();
+            }
+            self.handle_debug_keybinding();
+            self.begin_modal_frame();
+            if self.debug.enabled {
+                // Overlay is drawn on top of everything so whole frame has to be redrawn
+                self.redraw_all();
+            }
+            #[cfg(feature = "gamepad")]
+            self.gamepads.poll();
+            if self
+                .redraw_at
+                .is_some_and(|redraw_at| redraw_at <= frame_start)
+            {
+                self.redraw_at = None;
+                self.redraw_all();
+            }
 
-            draw(self);
-
-            if self.is_dirty() {
-                self.canvas_mut().flush()?;
+            if self.canvas.is_visible() {
+                self.begin_z_order_frame();
+                let draw_start = Instant::now();
+                draw(self);
+                let flashing = self.draw_visual_bell(frame_start);
+                if self.debug.enabled {
+                    self.draw_debug_overlay();
+                }
+                self.frame_timings.draw = draw_start.elapsed();
+
+                let flush_start = Instant::now();
+                if self.is_dirty() || flashing {
+                    match self.damage.take() {
+                        // Bell covers whole frame
+                        Some(damage) if !flashing => self.canvas.mark_damaged(damage),
+                        _ => drop(self.canvas.take_damage()),
+                    }
+                    self.canvas_mut().flush()?;
+                }
+                self.frame_timings.flush = flush_start.elapsed();
+
+                self.dirty = self.dirty_next || self.damage_next.is_some();
+                self.damage = if self.dirty_next {
+                    None
+                } else {
+                    self.damage_next
+                };
+                self.dirty_next = false;
+                self.damage_next = None;
+                // Kept while hidden so they are not lost before the next draw
+                self.messages.clear();
+            } else {
+                // Server does not keep contents of hidden windows so everything is redrawn
+                // once visible again
+                self.redraw_all();
             }
 
             self.canvas.keyboard_events.clear();
 
-            self.dirty = self.dirty_next;
-            self.dirty_next = false;
-
-            let frame_end = std::time::Instant::now();
+            let frame_end = Instant::now();
             let frame_duration = frame_end - frame_start;
-            let final_sleep = Duration::from_micros(1000000 / fps).checked_sub(frame_duration);
-            if let Some(final_sleep) = final_sleep {
-                std::thread::sleep(final_sleep);
+            self.debug.push_frame_time(frame_duration);
+            self.frame_timings.frame = frame_duration;
+            let interval = Duration::from_micros(1000000 / fps);
+            if self.vsync {
+                let next_frame = self.canvas.request_frame(interval)?;
+                self.waker
+                    .sleep(next_frame.saturating_duration_since(Instant::now()));
+            } else if let Some(final_sleep) = interval.checked_sub(frame_duration) {
+                self.waker.sleep(final_sleep);
             }
         }
 
         Ok(())
     }
 
+    #[inline]
+    /// Timings of the previous frame, useful for benchmarks
+    pub fn frame_timings(&self) -> FrameTimings {
+        self.frame_timings
+    }
+
     pub fn text<T>(&mut self, mut position: Vector2<i32>, size: u32, text: T, color: Color)
     where
         T: IntoIterator<Item = char>,
@@ -331,8 +894,15 @@ impl Ui {
     ) {
         let old_view = self.view;
 
-        size.x = cmp::min(size.x, self.current_view().size.x - position.x);
-        size.y = cmp::min(size.y, self.current_view().size.y - position.y);
+        // Clipped to the parent, views outside of it are empty
+        size.x = cmp::min(
+            size.x,
+            self.current_view().size.x.saturating_sub(position.x),
+        );
+        size.y = cmp::min(
+            size.y,
+            self.current_view().size.y.saturating_sub(position.y),
+        );
 
         self.view = View::Bounded(BoundedView {
             absolute_offset: position + old_view.absolute_offset(),
@@ -345,36 +915,11 @@ impl Ui {
     }
 }
 
-#[cfg(feature = "screenshot")]
-pub fn to_ppm(
-    img: &[u8],
-    size: just_canvas::Vector2<u32>,
-    mut f: impl std::io::Write,
-) -> std::io::Result<()> {
-    writeln!(f, "P6")?;
-    writeln!(f, "{} {}", size.x, size.y)?;
-    writeln!(f, "255")?;
-
-    for y in 0..(size.y as usize) {
-        for x in 0..(size.x as usize) {
-            f.write_all(&[img[(y * size.x as usize + x as usize) * 4 + 2]])?;
-            f.write_all(&[img[(y * size.x as usize + x as usize) * 4 + 1]])?;
-            f.write_all(&[img[(y * size.x as usize + x as usize) * 4 + 0]])?;
-        }
-    }
-
-    f.flush()
-}
-
 #[cfg(feature = "screenshot")]
 #[macro_export]
 macro_rules! screenshot {
     ($file_name:literal, $state:expr, $size:expr) => {{
-        use std::{
-            io::Write,
-            path::Path,
-            process::{Command, Stdio},
-        };
+        use std::path::Path;
 
         let size = $size;
         let canvas =
@@ -383,27 +928,15 @@ macro_rules! screenshot {
         let mut ui = Ui::with_canvas(canvas);
         draw(&mut ui, &mut $state);
 
-        let mut ppm = Vec::<u8>::new();
-        just_immui::to_ppm(ui.canvas().raw_buf(), size, &mut ppm).unwrap();
-
         let out_png_path = Path::new(file!()).with_file_name($file_name);
-        let mut convert = Command::new("convert")
-            .arg("-")
-            .arg(format!("{}", out_png_path.display()))
-            .stdin(Stdio::piped())
-            .spawn()
-            .expect("Could not spawn `convert`");
-
-        let mut stdin = convert.stdin.take().unwrap();
-        stdin.write_all(&ppm).unwrap();
-        drop(stdin);
-        convert.wait().unwrap();
+        std::fs::write(out_png_path, ui.canvas().capture().encode_png())
+            .expect("Could not write screenshot");
 
         Ok(())
     }};
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct Button {
     pub is_hovered: bool,
     pub is_pressed: bool,
@@ -413,23 +946,31 @@ pub struct Button {
     pub got_released: bool,
 }
 
-pub fn invisible_button(
-    ui: &mut Ui,
-    id: UiId,
-    in_bounds: impl FnOnce(Vector2<u32>) -> bool,
-) -> Button {
-    let mut button = Button {
-        is_hovered: false,
-        is_pressed: false,
-        got_pressed: false,
-        got_hovered: false,
-        got_unhovered: false,
-        got_released: false,
-    };
+impl Button {
+    /// State of a button that reacts to multiple pointers
+    fn merge(self, other: Self) -> Self {
+        Self {
+            is_hovered: self.is_hovered || other.is_hovered,
+            is_pressed: self.is_pressed || other.is_pressed,
+            got_pressed: self.got_pressed || other.got_pressed,
+            got_hovered: self.got_hovered || other.got_hovered,
+            got_unhovered: self.got_unhovered || other.got_unhovered,
+            got_released: self.got_released || other.got_released,
+        }
+    }
+}
+
+pub fn invisible_button(ui: &mut Ui, id: UiId, in_bounds: impl Fn(Vector2<u32>) -> bool) -> Button {
+    ui.each_pointer(|ui| pointer_button(ui, id, &in_bounds), Button::merge)
+        .unwrap_or_default()
+}
+
+fn pointer_button(ui: &mut Ui, id: UiId, in_bounds: impl Fn(Vector2<u32>) -> bool) -> Button {
+    let mut button = Button::default();
 
     let is_mouse_pressed = ui.pointer_absolute().is_pressed(PointerButton::Left);
 
-    if in_bounds(ui.pointer_position()) {
+    if ui.pointer_hits(id, in_bounds) {
         if ui.is_hot(id) {
             button.is_hovered = true;
         } else if !is_mouse_pressed {
@@ -455,6 +996,7 @@ pub fn invisible_button(
 
 // FIXME: in_bounds must be about absolute position
 
+#[derive(Debug, Default)]
 pub struct Focusable {
     pub is_focused: bool,
     pub got_focused: bool,
@@ -464,17 +1006,25 @@ pub struct Focusable {
 pub fn invisible_focusable(
     ui: &mut Ui,
     id: UiId,
-    in_bounds: impl FnOnce(Vector2<u32>) -> bool,
+    in_bounds: impl Fn(Vector2<u32>) -> bool,
 ) -> Focusable {
-    let mut res = Focusable {
-        is_focused: false,
-        got_focused: false,
-        got_unfocused: false,
-    };
+    ui.each_pointer(
+        |ui| pointer_focusable(ui, id, &in_bounds),
+        |a, b| Focusable {
+            is_focused: a.is_focused || b.is_focused,
+            got_focused: a.got_focused || b.got_focused,
+            got_unfocused: a.got_unfocused || b.got_unfocused,
+        },
+    )
+    .unwrap_or_default()
+}
+
+fn pointer_focusable(ui: &mut Ui, id: UiId, in_bounds: impl Fn(Vector2<u32>) -> bool) -> Focusable {
+    let mut res = Focusable::default();
 
     let is_mouse_pressed = ui.pointer_absolute().is_pressed(PointerButton::Left);
 
-    if in_bounds(ui.pointer_position()) {
+    if ui.pointer_hits(id, in_bounds) {
         ui.make_hot(id);
 
         if is_mouse_pressed {
@@ -497,11 +1047,16 @@ pub fn invisible_focusable(
 pub fn invisible_draggable(
     ui: &mut Ui,
     id: UiId,
-    in_bounds: impl FnOnce(Vector2<u32>) -> bool,
+    in_bounds: impl Fn(Vector2<u32>) -> bool,
 ) -> bool {
+    ui.each_pointer(|ui| pointer_draggable(ui, id, &in_bounds), |a, b| a || b)
+        .unwrap_or(false)
+}
+
+fn pointer_draggable(ui: &mut Ui, id: UiId, in_bounds: impl Fn(Vector2<u32>) -> bool) -> bool {
     let is_mouse_pressed = ui.pointer_absolute().is_pressed(PointerButton::Left);
 
-    if in_bounds(ui.pointer_position()) {
+    if ui.pointer_hits(id, in_bounds) {
         if !ui.is_hot(id) && is_mouse_pressed {
             false
         } else {
@@ -520,3 +1075,87 @@ pub fn invisible_draggable(
         false
     }
 }
+
+#[test]
+fn clipped_views_hit_testing() {
+    let canvas = Canvas::with_backend_type(
+        "",
+        just_canvas::BackendType::Bitmap {
+            size: Vector2 { x: 100, y: 100 },
+        },
+    )
+    .unwrap();
+    let mut ui = Ui::with_canvas(canvas);
+    let id = UiId {
+        id: 0,
+        parent: 0,
+        index: 0,
+    };
+    // Button taller than its view, as if the bottom part was scrolled out
+    let tall_button = hit::rect(Vector2 { x: 0, y: 0 }, Vector2 { x: 20, y: 80 });
+
+    ui.canvas.warp_pointer_to(Vector2 { x: 15, y: 50 }).unwrap();
+    ui.with_view(Vector2 { x: 10, y: 10 }, Vector2 { x: 30, y: 30 }, |ui| {
+        assert!(!ui.pointer_in_view());
+        assert!(!invisible_button(ui, id, &tall_button).is_hovered);
+    });
+
+    // Pointer left of the view is not clamped onto its edge
+    ui.canvas.warp_pointer_to(Vector2 { x: 5, y: 15 }).unwrap();
+    ui.with_view(Vector2 { x: 10, y: 10 }, Vector2 { x: 30, y: 30 }, |ui| {
+        assert!(!invisible_button(ui, id, &tall_button).is_hovered);
+    });
+
+    ui.canvas.warp_pointer_to(Vector2 { x: 15, y: 30 }).unwrap();
+    ui.with_view(Vector2 { x: 10, y: 10 }, Vector2 { x: 30, y: 30 }, |ui| {
+        assert!(invisible_button(ui, id, &tall_button).is_hovered);
+        // Nested view outside of its parent is empty
+        ui.with_view(Vector2 { x: 40, y: 0 }, Vector2 { x: 10, y: 10 }, |ui| {
+            assert_eq!(ui.current_view().size.x, 0);
+            assert!(!ui.pointer_in_view());
+        });
+    });
+}
+
+#[test]
+fn per_device_hot_widgets() {
+    use just_canvas::record::Recording;
+
+    // Two people hover different buttons, then the first one presses
+    let recording = Recording::read(
+        "0 0 pointer_motion 10 10 @2\n\
+         0 0 pointer_motion 60 10 @3\n\
+         1 0 pointer_press left @2\n"
+            .as_bytes(),
+    )
+    .unwrap();
+    let canvas = Canvas::with_backend_type(
+        "",
+        just_canvas::BackendType::Playback {
+            size: Vector2 { x: 100, y: 100 },
+            recording,
+        },
+    )
+    .unwrap();
+    let mut ui = Ui::with_canvas(canvas);
+    ui.set_per_device_pointers(true);
+    let left = UiId {
+        id: 0,
+        parent: 0,
+        index: 0,
+    };
+    let right = UiId { id: 1, ..left };
+    let size = Vector2 { x: 50, y: 50 };
+
+    ui.canvas.process_events().unwrap();
+    assert!(invisible_button(&mut ui, left, hit::rect(Vector2 { x: 0, y: 0 }, size)).is_hovered);
+    assert!(invisible_button(&mut ui, right, hit::rect(Vector2 { x: 50, y: 0 }, size)).is_hovered);
+    assert!(ui.is_hot(left) && ui.is_hot(right));
+
+    ui.canvas.process_events().unwrap();
+    let button = invisible_button(&mut ui, left, hit::rect(Vector2 { x: 0, y: 0 }, size));
+    assert!(button.got_pressed);
+    let button = invisible_button(&mut ui, right, hit::rect(Vector2 { x: 50, y: 0 }, size));
+    assert!(button.is_hovered && !button.is_pressed);
+    assert!(ui.is_active(left) && !ui.is_active(right));
+}
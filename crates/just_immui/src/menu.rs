@@ -0,0 +1,244 @@
+use just_canvas::{
+    keyboard::{KeyboardButton, SpecialKeyboardButton},
+    KeyboardEvent, PointerButton, Vector2,
+};
+
+use crate::{hit, invisible_button, theme::WidgetKind, Ui, UiId};
+
+#[derive(Debug, Clone, Copy)]
+pub enum MenuItem<'a> {
+    Action { label: &'a str, enabled: bool },
+    Separator,
+}
+
+impl<'a> MenuItem<'a> {
+    #[inline]
+    pub fn action(label: &'a str) -> Self {
+        Self::Action {
+            label,
+            enabled: true,
+        }
+    }
+
+    #[inline]
+    pub fn disabled(label: &'a str) -> Self {
+        Self::Action {
+            label,
+            enabled: false,
+        }
+    }
+
+    #[inline]
+    fn is_selectable(self) -> bool {
+        matches!(self, Self::Action { enabled: true, .. })
+    }
+}
+
+/// Currently open context menu, only one can be open at a time
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ContextMenuState {
+    id: UiId,
+    position: Vector2<i32>,
+    /// Item highlighted with pointer or keyboard
+    highlighted: Option<usize>,
+    /// Right button that opened the menu is still held
+    opening_press: bool,
+}
+
+/// Menu opened with right click inside of `in_bounds`, drawn at the pointer position.
+/// Call it after widgets it should cover. Returns index of the selected item, menu is closed
+/// after selection, with Escape or with click outside of it. Items can be also selected with
+/// arrow keys and Return.
+pub fn context_menu(
+    ui: &mut Ui,
+    id: UiId,
+    in_bounds: impl FnOnce(Vector2<u32>) -> bool,
+    items: &[MenuItem],
+) -> Option<usize> {
+    let pointer = ui.pointer_absolute();
+    let right_pressed = pointer.is_pressed(PointerButton::Right);
+    let left_pressed = pointer.is_pressed(PointerButton::Left);
+    let pointer_position = ui.pointer_position();
+
+    let is_open = ui.context_menu.is_some_and(|menu| menu.id == id);
+    if right_pressed && !is_open && ui.pointer_hits(id, in_bounds) {
+        ui.context_menu = Some(ContextMenuState {
+            id,
+            position: pointer_position.as_i32(),
+            highlighted: None,
+            opening_press: true,
+        });
+        ui.set_dirty();
+    }
+
+    let mut menu = ui.context_menu.filter(|menu| menu.id == id)?;
+
+    let style = ui.style(WidgetKind::Menu);
+    let font_size = ui.scaled(style.font_size);
+    let pad = ui.scaled(style.padding) as i32;
+    let item_height = ui.scaled(24);
+    let separator_height = ui.scaled(9);
+    let width = items
+        .iter()
+        .map(|item| match item {
+            MenuItem::Action { label, .. } => ui.text_size(font_size, label.chars()).x,
+            MenuItem::Separator => 0,
+        })
+        .max()
+        .unwrap_or(0)
+        + pad as u32 * 2;
+    let height = items
+        .iter()
+        .map(|item| match item {
+            MenuItem::Action { .. } => item_height,
+            MenuItem::Separator => separator_height,
+        })
+        .sum::<u32>();
+    let size = Vector2 {
+        x: width,
+        y: height,
+    };
+
+    // Keep whole menu inside of the view
+    let view_size = ui.current_view().size;
+    menu.position = Vector2 {
+        x: menu
+            .position
+            .x
+            .min(view_size.x as i32 - size.x as i32)
+            .max(0),
+        y: menu
+            .position
+            .y
+            .min(view_size.y as i32 - size.y as i32)
+            .max(0),
+    };
+
+    let mut selected = None;
+    let mut close = false;
+
+    let mut keyboard_events = std::mem::take(&mut ui.canvas.keyboard_events);
+    let events_count = keyboard_events.len();
+    keyboard_events.retain(|event| match event {
+        KeyboardEvent::Pressed(KeyboardButton::Special(SpecialKeyboardButton::Escape), _) => {
+            close = true;
+            false
+        }
+        KeyboardEvent::Pressed(KeyboardButton::Special(SpecialKeyboardButton::Down), _) => {
+            menu.highlighted = next_selectable(items, menu.highlighted, true);
+            false
+        }
+        KeyboardEvent::Pressed(KeyboardButton::Special(SpecialKeyboardButton::Up), _) => {
+            menu.highlighted = next_selectable(items, menu.highlighted, false);
+            false
+        }
+        KeyboardEvent::Pressed(KeyboardButton::Special(SpecialKeyboardButton::Return), _) => {
+            selected = menu.highlighted;
+            false
+        }
+        _ => true,
+    });
+    if keyboard_events.len() != events_count {
+        ui.set_dirty();
+    }
+    ui.canvas.keyboard_events = keyboard_events;
+
+    ui.rectangle(menu.position, size, style.background);
+    ui.register_widget(id, menu.position, size);
+
+    let mut y = menu.position.y;
+    for (idx, item) in items.iter().enumerate() {
+        let MenuItem::Action { label, enabled } = *item else {
+            ui.rectangle(
+                Vector2 {
+                    x: menu.position.x + pad,
+                    y: y + separator_height as i32 / 2,
+                },
+                Vector2 {
+                    x: width - pad as u32 * 2,
+                    y: ui.scaled(1),
+                },
+                style.foreground,
+            );
+            y += separator_height as i32;
+            continue;
+        };
+
+        let item_id = UiId {
+            index: idx as u32 + 1,
+            ..id
+        };
+        let item_position = Vector2 {
+            x: menu.position.x,
+            y,
+        };
+        let item_size = Vector2 {
+            x: width,
+            y: item_height,
+        };
+        let button = invisible_button(ui, item_id, hit::rect(item_position, item_size));
+        if enabled && button.is_hovered && menu.highlighted != Some(idx) {
+            menu.highlighted = Some(idx);
+            ui.set_dirty();
+        }
+        if enabled && button.got_released {
+            selected = Some(idx);
+        }
+
+        if enabled && menu.highlighted == Some(idx) {
+            ui.rectangle(item_position, item_size, style.accent);
+        }
+        ui.register_widget(item_id, item_position, item_size);
+        let text_color = if enabled {
+            style.text
+        } else {
+            style.foreground
+        };
+        ui.text(
+            Vector2 {
+                x: item_position.x + pad,
+                y: item_position.y + (item_height as i32 - 8 * font_size as i32) / 2,
+            },
+            font_size,
+            label.chars(),
+            text_color,
+        );
+
+        y += item_height as i32;
+    }
+
+    menu.opening_press &= right_pressed;
+    let pointer_inside = hit::rect(menu.position, size)(pointer_position);
+    if (left_pressed || (right_pressed && !menu.opening_press)) && !pointer_inside {
+        close = true;
+    }
+
+    if selected.is_some() || close {
+        ui.context_menu = None;
+        ui.set_dirty();
+    } else {
+        ui.context_menu = Some(menu);
+    }
+
+    selected
+}
+
+fn next_selectable(items: &[MenuItem], from: Option<usize>, forward: bool) -> Option<usize> {
+    let len = items.len();
+    let start = match (from, forward) {
+        (Some(from), true) => from + 1,
+        (Some(from), false) => from + len - 1,
+        (None, true) => 0,
+        (None, false) => len.saturating_sub(1),
+    };
+    (0..len)
+        .map(|offset| {
+            if forward {
+                (start + offset) % len
+            } else {
+                (start + len - offset) % len
+            }
+        })
+        .find(|idx| items[*idx].is_selectable())
+        .or(from)
+}
@@ -0,0 +1,177 @@
+use just_canvas::{
+    draw,
+    keyboard::{KeyboardButton, SpecialKeyboardButton},
+    Color, KeyboardEvent, Vector2,
+};
+use std::mem;
+
+use crate::{theme::WidgetKind, Ui, UiId, View};
+
+/// Drawn over the whole window behind the dialog
+const DIM_COLOR: Color = Color::from_raw(0x80000000);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModalResult {
+    /// Dialog is still shown
+    Open,
+    /// Confirmed with Return or by the dialog contents
+    Confirmed,
+    /// Cancelled with Escape or by the dialog contents
+    Cancelled,
+}
+
+impl Ui {
+    /// Block input to everything except the modal drawn in the previous frame. Called before
+    /// each frame is drawn.
+    pub(crate) fn begin_modal_frame(&mut self) {
+        let previous = mem::replace(&mut self.modal, self.modal_next.take());
+        if previous != self.modal {
+            // Widgets that got blocked or were inside of the closed modal can't stay hot
+            self.reset_hot();
+        }
+        if previous.is_some() && self.modal.is_none() {
+            // Erase dimming of a modal that was not drawn anymore
+            self.redraw_all();
+        }
+
+        self.modal_keyboard_events.clear();
+        if self.modal.is_some() {
+            self.modal_keyboard_events = mem::take(&mut self.canvas.keyboard_events);
+        }
+    }
+
+    /// Pointer and keyboard input is blocked by a modal and this widget is not inside of it
+    pub(crate) fn is_blocked_by_modal(&self) -> bool {
+        self.modal.is_some() && !self.in_modal
+    }
+}
+
+/// Dialog of `size` centered in the window with rest of the window dimmed. Call it after
+/// everything it should cover, for as long as it returns [`ModalResult::Open`]. Starting with the
+/// next frame, widgets outside of it don't get any pointer or keyboard input.
+///
+/// `draw` is called inside of the dialog view and can close it by returning other result than
+/// [`ModalResult::Open`], otherwise Return confirms and Escape cancels the dialog. Keys consumed
+/// by widgets inside of the dialog are not handled.
+pub fn modal(
+    ui: &mut Ui,
+    id: UiId,
+    size: Vector2<u32>,
+    draw: impl FnOnce(&mut Ui) -> ModalResult,
+) -> ModalResult {
+    if ui.modal != Some(id) {
+        // Underlying widgets could be drawn without dimming if nothing else changed
+        ui.set_dirty();
+    }
+
+    if ui.is_dirty() {
+        let window_size = ui.canvas.window_size();
+        draw::rectangle_blend(
+            &mut ui.canvas,
+            Vector2::<i32>::zero(),
+            window_size,
+            DIM_COLOR,
+        );
+    }
+
+    let window_size = ui.canvas.window_size();
+    let position = Vector2 {
+        x: window_size.x.saturating_sub(size.x) / 2,
+        y: window_size.y.saturating_sub(size.y) / 2,
+    };
+
+    let old_view = mem::replace(&mut ui.view, View::Unbounded);
+    let was_in_modal = mem::replace(&mut ui.in_modal, true);
+    mem::swap(
+        &mut ui.canvas.keyboard_events,
+        &mut ui.modal_keyboard_events,
+    );
+
+    let mut result = ModalResult::Open;
+    ui.with_view(position, size, |ui| {
+        ui.background(ui.style(WidgetKind::Modal).background);
+        ui.register_widget(id, Vector2::<i32>::zero(), size);
+        result = draw(ui);
+    });
+
+    if result == ModalResult::Open {
+        ui.canvas.keyboard_events.retain(|event| match event {
+            KeyboardEvent::Pressed(KeyboardButton::Special(SpecialKeyboardButton::Escape), _) => {
+                result = ModalResult::Cancelled;
+                false
+            }
+            KeyboardEvent::Pressed(KeyboardButton::Special(SpecialKeyboardButton::Return), _) => {
+                result = ModalResult::Confirmed;
+                false
+            }
+            _ => true,
+        });
+    }
+
+    mem::swap(
+        &mut ui.canvas.keyboard_events,
+        &mut ui.modal_keyboard_events,
+    );
+    ui.in_modal = was_in_modal;
+    ui.view = old_view;
+
+    if result == ModalResult::Open {
+        ui.modal_next = Some(id);
+    } else {
+        ui.set_dirty();
+    }
+
+    result
+}
+
+#[test]
+fn modal_blocks_input() {
+    use crate::{hit, invisible_button};
+    use just_canvas::{BackendType, Canvas, InputDevice};
+
+    let canvas = Canvas::with_backend_type(
+        "",
+        BackendType::Bitmap {
+            size: Vector2 { x: 100, y: 100 },
+        },
+    )
+    .unwrap();
+    let mut ui = Ui::with_canvas(canvas);
+    let below_id = UiId {
+        id: 0,
+        parent: 0,
+        index: 0,
+    };
+    let modal_id = UiId { id: 1, ..below_id };
+    let inside_id = UiId { id: 2, ..below_id };
+    let size = Vector2 { x: 50, y: 50 };
+
+    // Pointer is over both the widget below and the dialog
+    ui.canvas.warp_pointer_to(Vector2 { x: 50, y: 50 }).unwrap();
+    let below = hit::rect(Vector2 { x: 0, y: 0 }, Vector2 { x: 100, y: 100 });
+
+    ui.begin_modal_frame();
+    assert!(invisible_button(&mut ui, below_id, &below).is_hovered);
+    let result = modal(&mut ui, modal_id, size, |_| ModalResult::Open);
+    assert_eq!(result, ModalResult::Open);
+
+    ui.canvas.keyboard_events.push(KeyboardEvent::Pressed(
+        KeyboardButton::Special(SpecialKeyboardButton::Escape),
+        InputDevice::CORE,
+    ));
+    ui.begin_modal_frame();
+    assert!(!invisible_button(&mut ui, below_id, &below).is_hovered);
+    assert!(ui.canvas.keyboard_events.is_empty());
+    let result = modal(&mut ui, modal_id, size, |ui| {
+        // Dialog is at 25x25, so the pointer is in its middle
+        let inside = hit::rect(Vector2 { x: 0, y: 0 }, Vector2 { x: 50, y: 50 });
+        assert!(invisible_button(ui, inside_id, inside).is_hovered);
+        assert_eq!(ui.canvas.keyboard_events.len(), 1);
+        ModalResult::Open
+    });
+    assert_eq!(result, ModalResult::Cancelled);
+
+    // Closed dialog does not block anymore
+    ui.begin_modal_frame();
+    assert!(invisible_button(&mut ui, below_id, &below).is_hovered);
+}
@@ -1,10 +1,11 @@
 use just_canvas::{
-    draw::inside_rectangle,
     keyboard::{KeyboardButton, SpecialKeyboardButton},
     Color, KeyboardEvent, PointerButton, Vector2,
 };
 
-use crate::{invisible_draggable, invisible_focusable, Ui, UiId};
+use std::f32::consts::PI;
+
+use crate::{hit, invisible_draggable, invisible_focusable, theme::WidgetKind, Ui, UiId};
 
 pub const BLACK: Color = Color::from_raw(0xff222222);
 pub const DARK_GRAY: Color = Color::from_raw(0xff333333);
@@ -41,11 +42,15 @@ impl Slider {
         // chosen arbitrarily
         let size = Vector2 {
             x: slider_length,
-            y: 6,
+            y: ui.scaled(6),
+        };
+        let handle_size = Vector2 {
+            x: ui.scaled(8),
+            y: ui.scaled(20),
         };
-        let handle_size = Vector2 { x: 8, y: 20 };
 
-        ui.rectangle(position, size, GRAY);
+        let style = ui.style(WidgetKind::Slider);
+        ui.rectangle(position, size, style.background);
 
         let handle_position = Vector2 {
             x: map_range!(
@@ -58,18 +63,27 @@ impl Slider {
             y: position.y - handle_size.y as i32 / 2 + size.y as i32 / 2,
         };
 
-        ui.rectangle(handle_position, handle_size, BLUE);
+        ui.rectangle(handle_position, handle_size, style.accent);
+        ui.register_widget(
+            id,
+            position,
+            Vector2 {
+                x: size.x,
+                y: handle_size.y,
+            },
+        );
 
-        let dragged = invisible_draggable(ui, id, |pointer| {
-            inside_rectangle(
+        let dragged = invisible_draggable(
+            ui,
+            id,
+            hit::rect(
                 position,
                 Vector2 {
                     x: size.x,
-                    y: handle_size.y as u32,
+                    y: handle_size.y,
                 },
-                pointer.as_i32(),
-            )
-        });
+            ),
+        );
         if dragged {
             let px = (ui.pointer_position().x as i32)
                 .clamp(position.x as i32, position.x as i32 + size.x as i32)
@@ -86,6 +100,202 @@ impl Slider {
     }
 }
 
+/// Angle of a [`Knob`] at value `0.0`, bottom left
+const KNOB_START_ANGLE: f32 = 0.75 * PI;
+/// Clockwise angle of a [`Knob`] from value `0.0` to `1.0`, leaving a gap at the bottom
+const KNOB_SWEEP: f32 = 1.5 * PI;
+/// Unscaled pointer travel changing the value from `0.0` to `1.0` with [`KnobDrag::Vertical`]
+const KNOB_DRAG_LENGTH: u32 = 200;
+/// Value change of one arrow key press
+const KNOB_KEY_STEP: f32 = 0.05;
+/// Multiplier of drag and key changes while Shift is held
+const KNOB_FINE_FACTOR: f32 = 0.1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KnobDrag {
+    /// Dragging up increases the value, like in most audio software
+    Vertical,
+    /// Value follows rotation of the pointer around the knob center
+    Angular,
+}
+
+pub struct Knob {
+    /// From `0.0` to `1.0`
+    pub value: f32,
+    pub drag: KnobDrag,
+    /// Arrow keys change the value after the knob is clicked, until clicking elsewhere
+    focused: bool,
+    shift_held: bool,
+    /// Pointer relative to the knob center in the previous frame of a drag
+    last_pointer: Option<Vector2<f32>>,
+}
+
+impl Knob {
+    pub fn new(value: f32, drag: KnobDrag) -> Self {
+        Self {
+            value: value.clamp(0.0, 1.0),
+            drag,
+            focused: false,
+            shift_held: false,
+            last_pointer: None,
+        }
+    }
+
+    /// Value change of moving the pointer from `from` to `to`, both relative to the center
+    fn drag_delta(&self, ui: &Ui, from: Vector2<f32>, to: Vector2<f32>) -> f32 {
+        let delta = match self.drag {
+            KnobDrag::Vertical => (from.y - to.y) / ui.scaled(KNOB_DRAG_LENGTH) as f32,
+            KnobDrag::Angular => {
+                // Shortest way around, so going over the gap at the bottom does not jump
+                let turn = to.y.atan2(to.x) - from.y.atan2(from.x);
+                ((turn + PI).rem_euclid(2.0 * PI) - PI) / KNOB_SWEEP
+            }
+        };
+        if self.shift_held {
+            delta * KNOB_FINE_FACTOR
+        } else {
+            delta
+        }
+    }
+
+    /// Knob of `radius` with its bounding box at `position`. Drag changes the value, arrow keys
+    /// do when focused, and holding Shift makes both finer.
+    pub fn draw(&mut self, ui: &mut Ui, id: UiId, position: Vector2<i32>, radius: u32) {
+        let center = Vector2 {
+            x: position.x + radius as i32,
+            y: position.y + radius as i32,
+        };
+        let old_value = self.value;
+        let was_focused = self.focused;
+
+        ui.register_widget(
+            id,
+            position,
+            Vector2 {
+                x: radius * 2,
+                y: radius * 2,
+            },
+        );
+
+        // Shift is tracked even when not focused, to make the next drag fine
+        let focused = self.focused;
+        let mut shift_held = self.shift_held;
+        let mut value = self.value;
+        ui.canvas.keyboard_events.retain(|event| {
+            let step = if shift_held {
+                KNOB_KEY_STEP * KNOB_FINE_FACTOR
+            } else {
+                KNOB_KEY_STEP
+            };
+            match event {
+                KeyboardEvent::Pressed(
+                    KeyboardButton::Special(
+                        SpecialKeyboardButton::ShiftL | SpecialKeyboardButton::ShiftR,
+                    ),
+                    _,
+                ) => {
+                    shift_held = true;
+                    true
+                }
+                KeyboardEvent::Released(
+                    KeyboardButton::Special(
+                        SpecialKeyboardButton::ShiftL | SpecialKeyboardButton::ShiftR,
+                    ),
+                    _,
+                ) => {
+                    shift_held = false;
+                    true
+                }
+                KeyboardEvent::Pressed(
+                    KeyboardButton::Special(
+                        SpecialKeyboardButton::Up | SpecialKeyboardButton::Right,
+                    ),
+                    _,
+                ) if focused => {
+                    value += step;
+                    false
+                }
+                KeyboardEvent::Pressed(
+                    KeyboardButton::Special(
+                        SpecialKeyboardButton::Down | SpecialKeyboardButton::Left,
+                    ),
+                    _,
+                ) if focused => {
+                    value -= step;
+                    false
+                }
+                _ => true,
+            }
+        });
+        self.shift_held = shift_held;
+        self.value = value;
+
+        let dragged = invisible_draggable(ui, id, hit::circle(center, radius));
+        if dragged {
+            let pointer = ui.pointer_position().as_i32() - center;
+            let pointer = Vector2 {
+                x: pointer.x as f32,
+                y: pointer.y as f32,
+            };
+            if let Some(last_pointer) = self.last_pointer {
+                self.value += self.drag_delta(ui, last_pointer, pointer);
+            }
+            self.last_pointer = Some(pointer);
+            self.focused = true;
+        } else {
+            self.last_pointer = None;
+            if ui.pointer_absolute().is_pressed(PointerButton::Left) {
+                self.focused = false;
+            }
+        }
+
+        self.value = self.value.clamp(0.0, 1.0);
+        if self.value != old_value || self.focused != was_focused {
+            ui.set_dirty();
+        }
+
+        let style = ui.style(WidgetKind::Knob);
+        let stroke_width = ui.scaled(4).min(radius);
+        let value_angle = KNOB_START_ANGLE + self.value * KNOB_SWEEP;
+        ui.circle(
+            center,
+            radius.saturating_sub(stroke_width),
+            style.background,
+        );
+        ui.arc(
+            center,
+            radius,
+            stroke_width,
+            KNOB_START_ANGLE,
+            KNOB_START_ANGLE + KNOB_SWEEP,
+            style.foreground,
+        );
+        ui.arc(
+            center,
+            radius,
+            stroke_width,
+            KNOB_START_ANGLE,
+            value_angle,
+            style.accent,
+        );
+
+        // Indicator line from the middle towards the value
+        let indicator_length = radius.saturating_sub(stroke_width * 2) as f32;
+        ui.thin_line(
+            center,
+            Vector2 {
+                x: center.x + (value_angle.cos() * indicator_length).round() as i32,
+                y: center.y + (value_angle.sin() * indicator_length).round() as i32,
+            },
+            if self.focused {
+                style.text
+            } else {
+                style.highlight
+            },
+        );
+    }
+}
+
 pub struct TextInput {
     pub value: String,
     pub cursor: usize,
@@ -93,14 +303,17 @@ pub struct TextInput {
 
 impl TextInput {
     pub fn draw(&mut self, ui: &mut Ui, id: UiId, position: Vector2<i32>) {
-        let size = Vector2 { x: 240, y: 26 };
-        let font_size = 2;
+        let size = Vector2 {
+            x: ui.scaled(240),
+            y: ui.scaled(26),
+        };
+        let style = ui.style(WidgetKind::TextInput);
+        let font_size = ui.scaled(style.font_size);
 
-        ui.rectangle(position, size, GRAY);
+        ui.rectangle(position, size, style.background);
+        ui.register_widget(id, position, size);
 
-        let focusable = invisible_focusable(ui, id, |pointer| {
-            inside_rectangle(position, size, pointer.as_i32())
-        });
+        let focusable = invisible_focusable(ui, id, hit::rect(position, size));
 
         if focusable.got_focused || focusable.got_unfocused {
             ui.set_dirty();
@@ -123,9 +336,10 @@ impl TextInput {
             let mut is_dirty = false;
             for c in &ui.canvas.keyboard_events {
                 match c {
-                    KeyboardEvent::Pressed(KeyboardButton::Special(
-                        SpecialKeyboardButton::BackSpace,
-                    )) => {
+                    KeyboardEvent::Pressed(
+                        KeyboardButton::Special(SpecialKeyboardButton::BackSpace),
+                        _,
+                    ) => {
                         if self.cursor == 0 {
                             continue;
                         }
@@ -133,20 +347,22 @@ impl TextInput {
                         self.cursor = self.cursor.saturating_sub(1);
                         is_dirty = true;
                     }
-                    KeyboardEvent::Pressed(KeyboardButton::Unicode(c)) => {
+                    KeyboardEvent::Pressed(KeyboardButton::Unicode(c), _) => {
                         self.value.insert(self.cursor, *c);
                         self.cursor += 1;
                         is_dirty = true;
                     }
-                    KeyboardEvent::Pressed(KeyboardButton::Special(
-                        SpecialKeyboardButton::Right,
-                    )) => {
+                    KeyboardEvent::Pressed(
+                        KeyboardButton::Special(SpecialKeyboardButton::Right),
+                        _,
+                    ) => {
                         self.cursor = core::cmp::min(self.cursor + 1, char_len);
                         is_dirty = true;
                     }
-                    KeyboardEvent::Pressed(KeyboardButton::Special(
-                        SpecialKeyboardButton::Left,
-                    )) => {
+                    KeyboardEvent::Pressed(
+                        KeyboardButton::Special(SpecialKeyboardButton::Left),
+                        _,
+                    ) => {
                         self.cursor = self.cursor.saturating_sub(1);
                         is_dirty = true;
                     }
@@ -163,7 +379,7 @@ impl TextInput {
         let font_height = 8;
         let cursor_pad = Vector2 {
             x: font_size as i32 * 2,
-            y: 3,
+            y: ui.scaled(3) as i32,
         };
 
         let pre = self.value.chars().take(self.cursor);
@@ -176,7 +392,7 @@ impl TextInput {
             },
             font_size,
             pre,
-            BLUE,
+            style.text,
         );
 
         if focusable.is_focused {
@@ -186,10 +402,10 @@ impl TextInput {
                     y: position.y + cursor_pad.y,
                 },
                 Vector2 {
-                    x: 2,
+                    x: ui.scaled(2),
                     y: size.y - cursor_pad.y as u32 * 2,
                 },
-                RED,
+                style.highlight,
             );
 
             let post = self.value.chars().skip(self.cursor);
@@ -200,8 +416,74 @@ impl TextInput {
                 },
                 font_size,
                 post,
-                BLUE,
+                style.text,
             );
         }
     }
 }
+
+#[test]
+fn knob_drag_and_keys() {
+    use just_canvas::{record::Recording, BackendType, Canvas, InputDevice};
+
+    // Press in the middle of the knob and drag 20 pixels up
+    let recording = Recording::read(
+        "0 0 pointer_motion 50 50\n\
+         1 0 pointer_press left\n\
+         2 0 pointer_motion 50 30\n\
+         3 0 pointer_release left\n\
+         4 0 pointer_motion 95 95\n\
+         5 0 pointer_press left\n"
+            .as_bytes(),
+    )
+    .unwrap();
+    let canvas = Canvas::with_backend_type(
+        "",
+        BackendType::Playback {
+            size: Vector2 { x: 100, y: 100 },
+            recording,
+        },
+    )
+    .unwrap();
+    let mut ui = Ui::with_canvas(canvas);
+    let id = UiId {
+        id: 0,
+        parent: 0,
+        index: 0,
+    };
+    let position = Vector2 { x: 10, y: 10 };
+    let mut knob = Knob::new(0.5, KnobDrag::Vertical);
+
+    for _ in 0..4 {
+        ui.canvas.process_events().unwrap();
+        knob.draw(&mut ui, id, position, 40);
+    }
+    assert!((knob.value - 0.6).abs() < 1e-4);
+
+    // Focused by the drag, Shift makes the step finer
+    let key = |button| KeyboardEvent::Pressed(KeyboardButton::Special(button), InputDevice::CORE);
+    ui.canvas.keyboard_events = vec![
+        key(SpecialKeyboardButton::Up),
+        key(SpecialKeyboardButton::ShiftL),
+        key(SpecialKeyboardButton::Down),
+    ];
+    knob.draw(&mut ui, id, position, 40);
+    assert!((knob.value - 0.645).abs() < 1e-4);
+    assert_eq!(ui.canvas.keyboard_events.len(), 1);
+
+    // Clicking elsewhere takes the focus away
+    for _ in 0..2 {
+        ui.canvas.process_events().unwrap();
+        knob.draw(&mut ui, id, position, 40);
+    }
+    ui.canvas.keyboard_events = vec![key(SpecialKeyboardButton::Up)];
+    knob.draw(&mut ui, id, position, 40);
+    assert!((knob.value - 0.645).abs() < 1e-4);
+
+    // Quarter turn clockwise is a third of the sweep, also across the gap at the bottom
+    let angular = Knob::new(0.5, KnobDrag::Angular);
+    let delta = angular.drag_delta(&ui, Vector2 { x: 1.0, y: 0.0 }, Vector2 { x: 0.0, y: 1.0 });
+    assert!((delta - 1.0 / 3.0).abs() < 1e-4);
+    let delta = angular.drag_delta(&ui, Vector2 { x: 1.0, y: 1.0 }, Vector2 { x: -1.0, y: 1.0 });
+    assert!((delta - 1.0 / 3.0).abs() < 1e-4);
+}
@@ -0,0 +1,120 @@
+//! Widgets drawn later are above the earlier ones, e.g. a popup drawn after a button covers it.
+//! Widgets are evaluated in drawing order, so the topmost widget under the pointer is not known
+//! until the frame is drawn. Hit areas reported with [`Ui::register_widget`] in the previous frame
+//! are used instead, widgets covered by areas registered after them don't get the pointer.
+
+use just_canvas::{draw, Rect, Vector2};
+
+use crate::{Ui, UiId};
+
+/// Visible part of a widget hit area, in absolute coordinates
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct HitArea {
+    id: UiId,
+    absolute_position: Vector2<i32>,
+    size: Vector2<u32>,
+}
+
+/// Hit areas of the previous and the current frame in drawing order
+#[derive(Debug, Default)]
+pub(crate) struct ZOrder {
+    previous: Vec<HitArea>,
+    current: Vec<HitArea>,
+}
+
+impl Ui {
+    /// Areas registered in the drawn frame decide occlusion in the next one. Called before each
+    /// frame is drawn.
+    pub(crate) fn begin_z_order_frame(&mut self) {
+        self.z_order.previous = std::mem::take(&mut self.z_order.current);
+    }
+
+    pub(crate) fn register_hit_area(
+        &mut self,
+        id: UiId,
+        position: Vector2<i32>,
+        size: Vector2<u32>,
+    ) {
+        let view = self.current_view();
+        let view = Rect::new(view.absolute_offset.as_i32(), view.size);
+        let area = Rect::new(position + view.position, size);
+        let Some(visible) = area.intersection(view) else {
+            return;
+        };
+
+        self.z_order.current.push(HitArea {
+            id,
+            absolute_position: visible.position,
+            size: visible.size,
+        });
+    }
+
+    /// Pointer is over a hit area registered after the topmost area of `id` in the previous
+    /// frame. Widgets that didn't register any area are never covered.
+    pub fn is_occluded(&self, id: UiId) -> bool {
+        let areas = &self.z_order.previous;
+        let Some(own) = areas.iter().rposition(|area| area.id == id) else {
+            return false;
+        };
+        let pointer = self.pointer_absolute().position.as_i32();
+        areas[own + 1..].iter().any(|area| {
+            area.id != id && draw::inside_rectangle(area.absolute_position, area.size, pointer)
+        })
+    }
+}
+
+#[test]
+fn topmost_widget_gets_hot() {
+    use crate::{hit, invisible_button};
+    use just_canvas::{BackendType, Canvas};
+
+    let canvas = Canvas::with_backend_type(
+        "",
+        BackendType::Bitmap {
+            size: Vector2 { x: 100, y: 100 },
+        },
+    )
+    .unwrap();
+    let mut ui = Ui::with_canvas(canvas);
+    let button_id = UiId {
+        id: 0,
+        parent: 0,
+        index: 0,
+    };
+    let popup_id = UiId { id: 1, ..button_id };
+    let size = Vector2 { x: 50, y: 50 };
+    let button_position = Vector2 { x: 0, y: 0 };
+    let popup_position = Vector2 { x: 25, y: 25 };
+    let frame = |ui: &mut Ui| {
+        ui.begin_z_order_frame();
+        ui.register_widget(button_id, button_position, size);
+        let button = invisible_button(ui, button_id, hit::rect(button_position, size));
+        ui.register_widget(popup_id, popup_position, size);
+        let popup = invisible_button(ui, popup_id, hit::rect(popup_position, size));
+        (button.is_hovered, popup.is_hovered)
+    };
+
+    // Button is asked first, so it wins until the popup area is known
+    ui.canvas.warp_pointer_to(Vector2 { x: 40, y: 40 }).unwrap();
+    assert_eq!(frame(&mut ui), (true, false));
+    assert_eq!(frame(&mut ui), (false, true));
+    assert!(ui.is_occluded(button_id) && !ui.is_occluded(popup_id));
+    assert_eq!(frame(&mut ui), (false, true));
+
+    // Visible part of the button is not covered
+    ui.canvas.warp_pointer_to(Vector2 { x: 10, y: 10 }).unwrap();
+    assert!(!ui.is_occluded(button_id));
+    // Popup is still hot when the button asks, it's unhovered later in the frame
+    assert_eq!(frame(&mut ui), (false, false));
+    assert_eq!(frame(&mut ui), (true, false));
+
+    // Areas are clipped to their views
+    ui.begin_z_order_frame();
+    ui.register_widget(button_id, button_position, size);
+    ui.with_view(Vector2 { x: 0, y: 0 }, Vector2 { x: 20, y: 20 }, |ui| {
+        ui.register_widget(popup_id, popup_position, size)
+    });
+    ui.begin_z_order_frame();
+    ui.canvas.warp_pointer_to(Vector2 { x: 40, y: 40 }).unwrap();
+    assert!(!ui.is_occluded(button_id));
+}
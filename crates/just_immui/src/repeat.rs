@@ -0,0 +1,179 @@
+//! Repeated activation of held buttons, e.g. `+` and `-` of a spinner
+
+use just_canvas::PointerButton;
+use std::time::{Duration, Instant};
+
+use crate::{Button, Ui, UiId};
+
+/// Schedule of repeats after a press, similar to keyboard auto-repeat
+#[derive(Debug, Clone, Copy)]
+pub struct RepeatRate {
+    /// Before the first repeat
+    pub delay: Duration,
+    /// Between the first and the second repeat
+    pub interval: Duration,
+    /// Each following interval is this many times the previous one, `1.0` disables acceleration
+    pub acceleration: f32,
+    /// Intervals don't get shorter than this
+    pub min_interval: Duration,
+}
+
+impl Default for RepeatRate {
+    fn default() -> Self {
+        Self {
+            delay: Duration::from_millis(400),
+            interval: Duration::from_millis(100),
+            acceleration: 0.9,
+            min_interval: Duration::from_millis(20),
+        }
+    }
+}
+
+impl RepeatRate {
+    /// Number of activations, including the press, after holding for `held`, and time of the
+    /// next one since the press
+    fn activations(self, held: Duration) -> (u32, Duration) {
+        let mut activations = 1;
+        let mut next = self.delay;
+        let mut interval = self.interval.max(self.min_interval);
+        while next <= held {
+            activations += 1;
+            next += interval;
+            // Rounded so intervals in whole milliseconds stay exact
+            interval = Duration::from_nanos(
+                (interval.as_nanos() as f64 * self.acceleration as f64).round() as u64,
+            )
+            .max(self.min_interval.max(Duration::from_millis(1)));
+        }
+        (activations, next)
+    }
+}
+
+/// Held button and how many activations were already reported
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RepeatState {
+    id: UiId,
+    /// Press, or the time pointer got back over the button while holding it
+    pressed_at: Instant,
+    reported: u32,
+}
+
+impl RepeatState {
+    /// State of `id` after this frame, new activations and the time until the next one.
+    /// `pressed_at` is the time of the press event.
+    fn advance(
+        previous: Option<Self>,
+        id: UiId,
+        button: &Button,
+        rate: RepeatRate,
+        pressed_at: Option<Instant>,
+        now: Instant,
+    ) -> (Option<Self>, u32, Option<Duration>) {
+        if !button.is_pressed || !button.is_hovered {
+            return (previous.filter(|repeat| repeat.id != id), 0, None);
+        }
+
+        let mut repeat = match previous {
+            Some(repeat) if repeat.id == id && !button.got_pressed => repeat,
+            // Pointer got back over the held button, it starts repeating again after the delay
+            _ if !button.got_pressed => RepeatState {
+                id,
+                pressed_at: now,
+                reported: 1,
+            },
+            _ => RepeatState {
+                id,
+                pressed_at: pressed_at.unwrap_or(now),
+                reported: 0,
+            },
+        };
+
+        let (activations, next) =
+            rate.activations(now.saturating_duration_since(repeat.pressed_at));
+        let new = activations - repeat.reported;
+        repeat.reported = activations;
+        let next = (repeat.pressed_at + next).saturating_duration_since(now);
+        (Some(repeat), new, Some(next))
+    }
+}
+
+impl Ui {
+    /// Number of activations of `button` in this frame: one when it gets pressed, then repeats
+    /// as long as it's held and hovered. Repeats are counted from the time of the press event,
+    /// so a slow frame reports all repeats it missed at once. When pointer leaves the held
+    /// button and gets back, repeats start again after the delay.
+    pub fn repeat(&mut self, id: UiId, button: &Button, rate: RepeatRate) -> u32 {
+        let pressed_at = self.pointer_absolute().pressed_at(PointerButton::Left);
+        let (repeat, new, next) =
+            RepeatState::advance(self.repeat, id, button, rate, pressed_at, Instant::now());
+        self.repeat = repeat;
+        if let Some(next) = next {
+            self.redraw_after(next);
+        }
+        new
+    }
+}
+
+#[test]
+fn repeat_schedule() {
+    let rate = RepeatRate {
+        delay: Duration::from_millis(300),
+        interval: Duration::from_millis(100),
+        acceleration: 0.5,
+        min_interval: Duration::from_millis(20),
+    };
+    let at = |ms| rate.activations(Duration::from_millis(ms));
+
+    assert_eq!(at(0), (1, Duration::from_millis(300)));
+    assert_eq!(at(299).0, 1);
+    // Repeats at 300, 400, 450, 475, 495, 515, ...
+    assert_eq!(at(300), (2, Duration::from_millis(400)));
+    assert_eq!(at(450).0, 4);
+    assert_eq!(at(515), (7, Duration::from_millis(535)));
+}
+
+#[test]
+fn repeat_restarts_after_reentering() {
+    let rate = RepeatRate {
+        delay: Duration::from_millis(300),
+        interval: Duration::from_millis(100),
+        acceleration: 1.0,
+        min_interval: Duration::from_millis(20),
+    };
+    let id = UiId {
+        id: 1,
+        parent: 0,
+        index: 0,
+    };
+    let press = Instant::now();
+    let at = |ms| press + Duration::from_millis(ms);
+    let held = |got_pressed, is_hovered| Button {
+        is_pressed: true,
+        is_hovered,
+        got_pressed,
+        ..Button::default()
+    };
+
+    let (repeat, new, next) =
+        RepeatState::advance(None, id, &held(true, true), rate, Some(press), at(10));
+    assert_eq!((new, next), (1, Some(Duration::from_millis(290))));
+    let (repeat, new, _) =
+        RepeatState::advance(repeat, id, &held(false, true), rate, Some(press), at(450));
+    assert_eq!(new, 2);
+
+    // Held outside of the button for a long time
+    let (repeat, new, next) =
+        RepeatState::advance(repeat, id, &held(false, false), rate, Some(press), at(500));
+    assert_eq!((new, next), (0, None));
+    assert!(repeat.is_none());
+
+    let (repeat, new, next) =
+        RepeatState::advance(repeat, id, &held(false, true), rate, Some(press), at(5000));
+    assert_eq!((new, next), (0, Some(Duration::from_millis(300))));
+    let (repeat, new, _) =
+        RepeatState::advance(repeat, id, &held(false, true), rate, Some(press), at(5299));
+    assert_eq!(new, 0);
+    let (_, new, _) =
+        RepeatState::advance(repeat, id, &held(false, true), rate, Some(press), at(5300));
+    assert_eq!(new, 1);
+}
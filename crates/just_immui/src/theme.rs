@@ -0,0 +1,389 @@
+//! Colors, paddings and font sizes of built-in widgets, optionally loaded from a file and
+//! reloaded live when it changes, see [`Ui::watch_theme`]
+//!
+//! File has a section for each widget kind with `key = value` lines, lines starting with `#` are
+//! comments. Colors are `#rrggbb` or `#rrggbbaa` like in [`color_picker`](crate::color_picker),
+//! sizes are unscaled like arguments of [`Ui::scaled`]. Keys missing from the file keep their
+//! default values.
+//!
+//! ```text
+//! [menu]
+//! background = #272822
+//! accent = #a6e22e
+//! padding = 8
+//!
+//! [tooltip]
+//! font_size = 2
+//! ```
+
+use just_canvas::Color;
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::{color_picker::parse_hex, monokaish, Ui};
+
+/// How often the watched file is checked for modifications
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WidgetKind {
+    Slider,
+    Knob,
+    TextInput,
+    Menu,
+    Tooltip,
+    Tree,
+    Form,
+    Modal,
+}
+
+impl WidgetKind {
+    pub const ALL: [Self; 8] = [
+        Self::Slider,
+        Self::Knob,
+        Self::TextInput,
+        Self::Menu,
+        Self::Tooltip,
+        Self::Tree,
+        Self::Form,
+        Self::Modal,
+    ];
+
+    /// Name of the section in theme files
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Slider => "slider",
+            Self::Knob => "knob",
+            Self::TextInput => "text_input",
+            Self::Menu => "menu",
+            Self::Tooltip => "tooltip",
+            Self::Tree => "tree",
+            Self::Form => "form",
+            Self::Modal => "modal",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Style {
+    /// Area behind the widget
+    pub background: Color,
+    /// Secondary parts, e.g. tracks, separators and fields
+    pub foreground: Color,
+    pub text: Color,
+    /// Values, selection and pressed parts
+    pub accent: Color,
+    /// Hovered parts and cursors
+    pub highlight: Color,
+    pub padding: u32,
+    pub font_size: u32,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            background: monokaish::DARK_GRAY,
+            foreground: monokaish::GRAY,
+            text: monokaish::WHITE,
+            accent: monokaish::BLUE,
+            highlight: monokaish::LIGHT_GRAY,
+            padding: 4,
+            font_size: 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+    styles: [Style; WidgetKind::ALL.len()],
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        let mut theme = Self {
+            styles: [Style::default(); WidgetKind::ALL.len()],
+        };
+        theme.style_mut(WidgetKind::Slider).background = monokaish::GRAY;
+        theme.style_mut(WidgetKind::TextInput).background = monokaish::GRAY;
+        theme.style_mut(WidgetKind::TextInput).text = monokaish::BLUE;
+        theme.style_mut(WidgetKind::TextInput).highlight = monokaish::RED;
+        theme.style_mut(WidgetKind::Menu).padding = 6;
+        *theme.style_mut(WidgetKind::Tooltip) = Style {
+            background: monokaish::LIGHT_GRAY,
+            text: monokaish::BLACK,
+            font_size: 1,
+            ..Style::default()
+        };
+        theme
+    }
+}
+
+impl Theme {
+    pub fn style(&self, kind: WidgetKind) -> Style {
+        self.styles[kind as usize]
+    }
+
+    pub fn style_mut(&mut self, kind: WidgetKind) -> &mut Style {
+        &mut self.styles[kind as usize]
+    }
+
+    /// Default theme with values of `source` applied, see [`crate::theme`] for the format
+    pub fn parse(source: &str) -> Result<Self, ThemeError> {
+        let mut theme = Self::default();
+        let mut section = None;
+        for (idx, line) in source.lines().enumerate() {
+            let line_number = idx + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                let name = name.trim();
+                section =
+                    Some(
+                        WidgetKind::from_name(name).ok_or_else(|| ThemeError::UnknownWidget {
+                            line: line_number,
+                            name: name.to_string(),
+                        })?,
+                    );
+                continue;
+            }
+
+            let (Some(kind), Some((key, value))) = (section, line.split_once('=')) else {
+                return Err(ThemeError::Syntax { line: line_number });
+            };
+            let (key, value) = (key.trim(), value.trim());
+            let invalid = || ThemeError::InvalidValue {
+                line: line_number,
+                value: value.to_string(),
+            };
+            let style = theme.style_mut(kind);
+            match key {
+                "background" | "foreground" | "text" | "accent" | "highlight" => {
+                    let color = parse_color(value).ok_or_else(invalid)?;
+                    *match key {
+                        "background" => &mut style.background,
+                        "foreground" => &mut style.foreground,
+                        "text" => &mut style.text,
+                        "accent" => &mut style.accent,
+                        _ => &mut style.highlight,
+                    } = color;
+                }
+                "padding" => style.padding = value.parse().map_err(|_| invalid())?,
+                "font_size" => {
+                    style.font_size = value
+                        .parse()
+                        .ok()
+                        .filter(|size| *size > 0)
+                        .ok_or_else(invalid)?
+                }
+                _ => {
+                    return Err(ThemeError::UnknownKey {
+                        line: line_number,
+                        key: key.to_string(),
+                    })
+                }
+            }
+        }
+        Ok(theme)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, ThemeError> {
+        let source = fs::read_to_string(path).map_err(|err| ThemeError::Io(err.kind()))?;
+        Self::parse(&source)
+    }
+}
+
+/// `#rrggbb` opaque or `#rrggbbaa`
+fn parse_color(s: &str) -> Option<Color> {
+    s.strip_prefix('#').and_then(parse_hex)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ThemeError {
+    Io(io::ErrorKind),
+    /// Line is neither a section header nor a `key = value` inside of a section
+    Syntax {
+        line: usize,
+    },
+    UnknownWidget {
+        line: usize,
+        name: String,
+    },
+    UnknownKey {
+        line: usize,
+        key: String,
+    },
+    InvalidValue {
+        line: usize,
+        value: String,
+    },
+}
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThemeError::Io(kind) => write!(f, "Could not read theme: {}", kind),
+            ThemeError::Syntax { line } => {
+                write!(f, "Line {}: Expected '[widget]' or 'key = value'", line)
+            }
+            ThemeError::UnknownWidget { line, name } => {
+                write!(f, "Line {}: Unknown widget '{}'", line, name)
+            }
+            ThemeError::UnknownKey { line, key } => {
+                write!(f, "Line {}: Unknown key '{}'", line, key)
+            }
+            ThemeError::InvalidValue { line, value } => {
+                write!(f, "Line {}: Invalid value '{}'", line, value)
+            }
+        }
+    }
+}
+
+/// Theme file and its modification time when it was last loaded
+#[derive(Debug)]
+pub(crate) struct ThemeWatcher {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    checked_at: Instant,
+    error: Option<ThemeError>,
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+impl Ui {
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    #[inline]
+    pub fn style(&self, kind: WidgetKind) -> Style {
+        self.theme.style(kind)
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        if theme != self.theme {
+            self.theme = theme;
+            self.redraw_all();
+        }
+    }
+
+    /// Load theme from `path` and load it again whenever the file is modified. Invalid
+    /// modifications keep the previous theme and are reported by [`Ui::theme_error`].
+    pub fn watch_theme(&mut self, path: impl Into<PathBuf>) -> Result<(), ThemeError> {
+        let path = path.into();
+        let modified = modified(&path);
+        let theme = Theme::load(&path)?;
+        self.set_theme(theme);
+        self.theme_watcher = Some(ThemeWatcher {
+            path,
+            modified,
+            checked_at: Instant::now(),
+            error: None,
+        });
+        Ok(())
+    }
+
+    pub fn unwatch_theme(&mut self) {
+        self.theme_watcher = None;
+    }
+
+    /// Error of the last reload of the watched theme, if it failed
+    pub fn theme_error(&self) -> Option<&ThemeError> {
+        self.theme_watcher.as_ref()?.error.as_ref()
+    }
+
+    /// Reload watched theme if the file was modified. Called before each frame is drawn.
+    pub(crate) fn poll_theme(&mut self) {
+        let Some(watcher) = &mut self.theme_watcher else {
+            return;
+        };
+        let now = Instant::now();
+        if now.duration_since(watcher.checked_at) < POLL_INTERVAL {
+            return;
+        }
+        watcher.checked_at = now;
+
+        let modified = modified(&watcher.path);
+        if modified == watcher.modified {
+            return;
+        }
+        watcher.modified = modified;
+        match Theme::load(&watcher.path) {
+            Ok(theme) => {
+                watcher.error = None;
+                self.set_theme(theme);
+            }
+            Err(err) => {
+                watcher.error = Some(err);
+                // Redraw so the new `Ui::theme_error` is shown
+                self.set_dirty();
+            }
+        }
+    }
+}
+
+#[test]
+fn parse_theme() {
+    let theme = Theme::parse(
+        "# Comment\n\
+         [menu]\n\
+         background = #102030\n\
+         padding=10\n\
+         \n\
+         [ tooltip ]\n\
+         text = #ffffff80\n\
+         font_size = 3\n",
+    )
+    .unwrap();
+    let menu = theme.style(WidgetKind::Menu);
+    assert_eq!(menu.background, Color::from_raw(0xff102030));
+    assert_eq!(menu.padding, 10);
+    assert_eq!(menu.accent, Theme::default().style(WidgetKind::Menu).accent);
+    let tooltip = theme.style(WidgetKind::Tooltip);
+    assert_eq!(
+        (tooltip.text, tooltip.font_size),
+        (Color::from_raw(0x80ffffff), 3)
+    );
+    assert_eq!(
+        theme.style(WidgetKind::Tree),
+        Theme::default().style(WidgetKind::Tree)
+    );
+
+    assert_eq!(
+        Theme::parse("padding = 1"),
+        Err(ThemeError::Syntax { line: 1 })
+    );
+    assert_eq!(
+        Theme::parse("[menu]\n[button]"),
+        Err(ThemeError::UnknownWidget {
+            line: 2,
+            name: "button".to_string()
+        })
+    );
+    assert_eq!(
+        Theme::parse("[tree]\nmargin = 1"),
+        Err(ThemeError::UnknownKey {
+            line: 2,
+            key: "margin".to_string()
+        })
+    );
+    assert_eq!(
+        Theme::parse("[tree]\naccent = #12345"),
+        Err(ThemeError::InvalidValue {
+            line: 2,
+            value: "#12345".to_string()
+        })
+    );
+    assert!(Theme::parse("[tree]\nfont_size = 0").is_err());
+}
@@ -0,0 +1,96 @@
+use just_canvas::Vector2;
+use std::time::{Duration, Instant};
+
+use crate::{theme::WidgetKind, Ui, UiId};
+
+pub(crate) const DEFAULT_TOOLTIP_DELAY: Duration = Duration::from_millis(500);
+
+/// Widget hovered by the pointer and since when
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TooltipState {
+    id: UiId,
+    hover_start: Instant,
+    /// Pointer position when the tooltip was shown, it does not follow the pointer afterwards
+    /// so frames don't have to be redrawn on every motion
+    anchor: Option<Vector2<i32>>,
+}
+
+impl Ui {
+    #[inline]
+    /// How long the pointer has to hover a widget before its tooltip is shown
+    pub fn set_tooltip_delay(&mut self, delay: Duration) {
+        self.tooltip_delay = delay;
+    }
+
+    /// Show `text` near the pointer once widget `id` has been hot for the tooltip delay.
+    /// Call it after drawing the widget and anything the tooltip should cover.
+    pub fn tooltip(&mut self, id: UiId, text: &str) {
+        if !self.is_hot(id) {
+            if self.tooltip.is_some_and(|tooltip| tooltip.id == id) {
+                self.tooltip = None;
+                // Erase tooltip drawn in previous frame
+                self.set_dirty();
+            }
+            return;
+        }
+
+        let now = Instant::now();
+        let mut tooltip = match self.tooltip {
+            Some(tooltip) if tooltip.id == id => tooltip,
+            _ => {
+                let tooltip = TooltipState {
+                    id,
+                    hover_start: now,
+                    anchor: None,
+                };
+                self.tooltip = Some(tooltip);
+                tooltip
+            }
+        };
+
+        let hovered_for = now - tooltip.hover_start;
+        if hovered_for < self.tooltip_delay {
+            self.redraw_after(self.tooltip_delay - hovered_for);
+            return;
+        }
+
+        let style = self.style(WidgetKind::Tooltip);
+        let font_size = self.scaled(style.font_size);
+        let pad = self.scaled(style.padding);
+        let text_size = self.text_size(font_size, text.chars());
+        let size = Vector2 {
+            x: text_size.x + pad * 2,
+            y: text_size.y + pad * 2,
+        };
+
+        // Below and to the right of the pointer, flipped if it would not fit the view
+        let pointer = *tooltip
+            .anchor
+            .get_or_insert(self.pointer_position().as_i32());
+        self.tooltip = Some(tooltip);
+        let view_size = self.current_view().size.as_i32();
+        let offset = self.scaled(16) as i32;
+        let mut position = Vector2 {
+            x: pointer.x + offset,
+            y: pointer.y + offset,
+        };
+        if position.x + size.x as i32 > view_size.x {
+            position.x = pointer.x - size.x as i32;
+        }
+        if position.y + size.y as i32 > view_size.y {
+            position.y = pointer.y - size.y as i32;
+        }
+        let position = position.clamp_non_negative();
+
+        self.rectangle(position, size, style.background);
+        self.text(
+            Vector2 {
+                x: position.x + pad as i32,
+                y: position.y + pad as i32,
+            },
+            font_size,
+            text.chars(),
+            style.text,
+        );
+    }
+}
@@ -0,0 +1,482 @@
+//! Collapsible tree, e.g. for file browsers. Only rows in the visible part of the tree are laid
+//! out and drawn, collapsed subtrees are not visited at all, so trees with many nodes stay cheap.
+
+use just_canvas::{
+    keyboard::{KeyboardButton, SpecialKeyboardButton},
+    KeyboardEvent, PointerButton, Vector2,
+};
+use std::{collections::HashSet, ops::Range};
+
+use crate::{hit, invisible_button, theme::WidgetKind, Ui, UiId};
+
+/// Rows scrolled by one step of the scroll wheel
+const SCROLL_STEP: usize = 3;
+
+/// Indices of children from the roots down to a node
+pub type TreePath = Vec<usize>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeNode {
+    pub label: String,
+    pub children: Vec<TreeNode>,
+}
+
+impl TreeNode {
+    pub fn leaf(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            children: Vec::new(),
+        }
+    }
+
+    pub fn branch(label: impl Into<String>, children: Vec<TreeNode>) -> Self {
+        Self {
+            label: label.into(),
+            children,
+        }
+    }
+}
+
+/// Node at `path`, `None` if the path does not exist anymore
+pub fn node_at<'a>(roots: &'a [TreeNode], path: &[usize]) -> Option<&'a TreeNode> {
+    let (first, rest) = path.split_first()?;
+    rest.iter()
+        .try_fold(roots.get(*first)?, |node, idx| node.children.get(*idx))
+}
+
+/// Shown row of the tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Row {
+    path: TreePath,
+    has_children: bool,
+    expanded: bool,
+}
+
+/// Expanded nodes, selection and scroll of a tree drawn with [`TreeView::draw`]. Nodes are
+/// identified by their [`TreePath`], so they should be kept in the same order between frames.
+#[derive(Debug, Default)]
+pub struct TreeView {
+    expanded: HashSet<TreePath>,
+    pub selected: Option<TreePath>,
+    /// Index of the first visible row
+    scroll: usize,
+    /// Keys move the selection after the tree is clicked, until clicking elsewhere
+    focused: bool,
+}
+
+impl TreeView {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_expanded(&self, path: &[usize]) -> bool {
+        self.expanded.contains(path)
+    }
+
+    /// Selection inside of a collapsed node moves to the node
+    pub fn set_expanded(&mut self, path: &[usize], expanded: bool) {
+        if expanded {
+            self.expanded.insert(path.to_vec());
+            return;
+        }
+
+        self.expanded.remove(path);
+        if self
+            .selected
+            .as_ref()
+            .is_some_and(|selected| selected.len() > path.len() && selected.starts_with(path))
+        {
+            self.selected = Some(path.to_vec());
+        }
+    }
+
+    /// Call `visit` with index and path of every shown row, in order. Returns number of rows.
+    fn walk(&self, roots: &[TreeNode], mut visit: impl FnMut(usize, &[usize], &TreeNode)) -> usize {
+        let mut row = 0;
+        let mut path = Vec::new();
+        // Siblings of each level of `path` and index of the next one to visit
+        let mut stack = vec![(roots, 0)];
+        while let Some(&(siblings, next)) = stack.last() {
+            let Some(node) = siblings.get(next) else {
+                stack.pop();
+                continue;
+            };
+            let depth = stack.len();
+            stack[depth - 1].1 += 1;
+            path.truncate(depth - 1);
+            path.push(next);
+
+            visit(row, &path, node);
+            row += 1;
+            if !node.children.is_empty() && self.expanded.contains(&path) {
+                stack.push((&node.children, 0));
+            }
+        }
+        row
+    }
+
+    /// Shown rows in `range` and number of all shown rows
+    fn rows(&self, roots: &[TreeNode], range: Range<usize>) -> (Vec<Row>, usize) {
+        let mut rows = Vec::new();
+        let count = self.walk(roots, |row, path, node| {
+            if range.contains(&row) {
+                rows.push(Row {
+                    path: path.to_vec(),
+                    has_children: !node.children.is_empty(),
+                    expanded: self.expanded.contains(path),
+                });
+            }
+        });
+        (rows, count)
+    }
+
+    /// Row of the selected node, `None` if nothing is selected or it is hidden
+    fn selected_row(&self, roots: &[TreeNode]) -> Option<usize> {
+        let selected = self.selected.as_ref()?;
+        let mut res = None;
+        self.walk(roots, |row, path, _| {
+            if path == selected.as_slice() {
+                res = Some(row);
+            }
+        });
+        res
+    }
+
+    /// Handle navigation `key`, returns `true` if it was used
+    fn navigate(&mut self, roots: &[TreeNode], key: SpecialKeyboardButton, page: usize) -> bool {
+        let selected_row = self.selected_row(roots);
+        let (_, count) = self.rows(roots, 0..0);
+        if count == 0 {
+            return false;
+        }
+        let last = count - 1;
+        let select_row = |tree: &mut Self, row: usize| {
+            let (mut rows, _) = tree.rows(roots, row..row + 1);
+            tree.selected = rows.pop().map(|row| row.path);
+        };
+
+        let Some(row) = selected_row else {
+            return match key {
+                SpecialKeyboardButton::Up
+                | SpecialKeyboardButton::Down
+                | SpecialKeyboardButton::Home
+                | SpecialKeyboardButton::End
+                | SpecialKeyboardButton::PageUp
+                | SpecialKeyboardButton::PageDown => {
+                    select_row(self, self.scroll.min(last));
+                    true
+                }
+                _ => false,
+            };
+        };
+        let path = self.selected.clone().unwrap_or_default();
+        let has_children = node_at(roots, &path).is_some_and(|node| !node.children.is_empty());
+        let expanded = self.is_expanded(&path);
+
+        match key {
+            SpecialKeyboardButton::Up => select_row(self, row.saturating_sub(1)),
+            SpecialKeyboardButton::Down => select_row(self, (row + 1).min(last)),
+            SpecialKeyboardButton::PageUp => select_row(self, row.saturating_sub(page)),
+            SpecialKeyboardButton::PageDown => select_row(self, (row + page).min(last)),
+            SpecialKeyboardButton::Home => select_row(self, 0),
+            SpecialKeyboardButton::End => select_row(self, last),
+            SpecialKeyboardButton::Right if has_children && !expanded => {
+                self.set_expanded(&path, true)
+            }
+            SpecialKeyboardButton::Right if has_children => {
+                self.selected = Some([path.as_slice(), &[0]].concat())
+            }
+            SpecialKeyboardButton::Left if expanded => self.set_expanded(&path, false),
+            SpecialKeyboardButton::Left if path.len() > 1 => {
+                self.selected = Some(path[..path.len() - 1].to_vec())
+            }
+            SpecialKeyboardButton::Return if has_children => self.set_expanded(&path, !expanded),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Scroll so the selected row is one of `visible` rows
+    fn scroll_to_selected(&mut self, roots: &[TreeNode], visible: usize) {
+        if let Some(row) = self.selected_row(roots) {
+            if row < self.scroll {
+                self.scroll = row;
+            } else if row >= self.scroll + visible {
+                self.scroll = row + 1 - visible;
+            }
+        }
+    }
+
+    /// Tree of `roots` in the area of `size` at `position`. Clicking a row selects it and clicking
+    /// its arrow expands or collapses it. When focused, arrow keys move the selection, `Right` and
+    /// `Left` also expand and collapse, and `Return` toggles the selected node. Returns the newly
+    /// selected node.
+    pub fn draw(
+        &mut self,
+        ui: &mut Ui,
+        id: UiId,
+        roots: &[TreeNode],
+        position: Vector2<i32>,
+        size: Vector2<u32>,
+    ) -> Option<TreePath> {
+        let style = ui.style(WidgetKind::Tree);
+        let font_size = ui.scaled(style.font_size);
+        let row_height = ui.scaled(20);
+        let indent = ui.scaled(16);
+        let pad = ui.scaled(style.padding);
+        let visible = (size.y / row_height).max(1) as usize;
+        let old_selected = self.selected.clone();
+        let old_scroll = self.scroll;
+        let was_focused = self.focused;
+
+        // Not `pointer_hits`, rows registered after the tree would occlude it
+        let inside = ui.pointer_in_view() && hit::rect(position, size)(ui.pointer_position());
+        let pointer = ui.pointer_absolute();
+        if inside && pointer.is_pressed(PointerButton::ScrollUp) {
+            self.scroll = self.scroll.saturating_sub(SCROLL_STEP);
+        }
+        if inside && pointer.is_pressed(PointerButton::ScrollDown) {
+            self.scroll += SCROLL_STEP;
+        }
+        if pointer.is_pressed(PointerButton::Left) {
+            self.focused = inside;
+        }
+
+        if self.focused {
+            let mut keyboard_events = std::mem::take(&mut ui.canvas.keyboard_events);
+            keyboard_events.retain(|event| match event {
+                KeyboardEvent::Pressed(KeyboardButton::Special(key), _) => {
+                    let used = self.navigate(roots, *key, visible);
+                    if used {
+                        self.scroll_to_selected(roots, visible);
+                    }
+                    !used
+                }
+                _ => true,
+            });
+            ui.canvas.keyboard_events = keyboard_events;
+        }
+
+        let (_, count) = self.rows(roots, 0..0);
+        self.scroll = self.scroll.min(count.saturating_sub(visible));
+        let (rows, _) = self.rows(roots, self.scroll..self.scroll + visible);
+
+        ui.rectangle(position, size, style.background);
+        ui.register_widget(id, position, size);
+
+        let mut toggled = None;
+        for (idx, row) in rows.iter().enumerate() {
+            let row_id = UiId {
+                index: idx as u32 + 1,
+                ..id
+            };
+            let row_position = Vector2 {
+                x: position.x,
+                y: position.y + (idx as u32 * row_height) as i32,
+            };
+            let row_size = Vector2 {
+                x: size.x,
+                y: row_height,
+            };
+            let arrow_x = row_position.x + ((row.path.len() - 1) as u32 * indent) as i32;
+
+            let button = invisible_button(ui, row_id, hit::rect(row_position, row_size));
+            if button.got_pressed {
+                let on_arrow = (ui.pointer_position().x as i32) < arrow_x + indent as i32;
+                if row.has_children && on_arrow {
+                    toggled = Some((row.path.clone(), !row.expanded));
+                } else {
+                    self.selected = Some(row.path.clone());
+                }
+            }
+
+            if self.selected.as_ref() == Some(&row.path) {
+                let color = if self.focused {
+                    style.accent
+                } else {
+                    style.foreground
+                };
+                ui.rectangle(row_position, row_size, color);
+            }
+            ui.register_widget(row_id, row_position, row_size);
+
+            let center = Vector2 {
+                x: arrow_x + indent as i32 / 2,
+                y: row_position.y + row_height as i32 / 2,
+            };
+            let arm = (indent / 4) as i32;
+            if row.has_children && row.expanded {
+                ui.thin_line(
+                    center
+                        + Vector2 {
+                            x: -arm,
+                            y: -arm / 2,
+                        },
+                    center + Vector2 { x: 0, y: arm / 2 },
+                    style.text,
+                );
+                ui.thin_line(
+                    center + Vector2 { x: 0, y: arm / 2 },
+                    center
+                        + Vector2 {
+                            x: arm,
+                            y: -arm / 2,
+                        },
+                    style.text,
+                );
+            } else if row.has_children {
+                ui.thin_line(
+                    center
+                        + Vector2 {
+                            x: -arm / 2,
+                            y: -arm,
+                        },
+                    center + Vector2 { x: arm / 2, y: 0 },
+                    style.text,
+                );
+                ui.thin_line(
+                    center + Vector2 { x: arm / 2, y: 0 },
+                    center
+                        + Vector2 {
+                            x: -arm / 2,
+                            y: arm,
+                        },
+                    style.text,
+                );
+            }
+
+            let label_x = arrow_x + (indent + pad) as i32;
+            let available = position.x + size.x as i32 - pad as i32 - label_x;
+            let label = node_at(roots, &row.path).map_or("", |node| node.label.as_str());
+            let fitting = ui.char_idx_at(font_size, label.chars(), Vector2 { x: available, y: 0 });
+            ui.text(
+                Vector2 {
+                    x: label_x,
+                    y: row_position.y + (row_height as i32 - 8 * font_size as i32) / 2,
+                },
+                font_size,
+                label.chars().take(fitting),
+                style.text,
+            );
+        }
+
+        if let Some((path, expanded)) = &toggled {
+            self.set_expanded(path, *expanded);
+        }
+
+        if self.selected != old_selected
+            || self.scroll != old_scroll
+            || self.focused != was_focused
+            || toggled.is_some()
+        {
+            ui.set_dirty();
+        }
+        self.selected
+            .clone()
+            .filter(|_| self.selected != old_selected)
+    }
+}
+
+#[test]
+fn tree_navigation() {
+    let roots = vec![
+        TreeNode::branch(
+            "src",
+            vec![
+                TreeNode::leaf("lib.rs"),
+                TreeNode::branch("tree", vec![TreeNode::leaf("mod.rs")]),
+            ],
+        ),
+        TreeNode::leaf("Cargo.toml"),
+    ];
+    let mut tree = TreeView::new();
+    let paths = |tree: &TreeView| {
+        let (rows, count) = tree.rows(&roots, 0..usize::MAX);
+        assert_eq!(rows.len(), count);
+        rows.into_iter().map(|row| row.path).collect::<Vec<_>>()
+    };
+    assert_eq!(paths(&tree), [vec![0], vec![1]]);
+
+    tree.navigate(&roots, SpecialKeyboardButton::Down, 10);
+    assert_eq!(tree.selected, Some(vec![0]));
+    // Right expands, then goes to the first child
+    tree.navigate(&roots, SpecialKeyboardButton::Right, 10);
+    assert_eq!(paths(&tree), [vec![0], vec![0, 0], vec![0, 1], vec![1]]);
+    tree.navigate(&roots, SpecialKeyboardButton::Right, 10);
+    assert_eq!(tree.selected, Some(vec![0, 0]));
+
+    tree.navigate(&roots, SpecialKeyboardButton::Down, 10);
+    tree.navigate(&roots, SpecialKeyboardButton::Return, 10);
+    assert_eq!(tree.selected, Some(vec![0, 1]));
+    assert_eq!(paths(&tree).len(), 5);
+    tree.navigate(&roots, SpecialKeyboardButton::End, 10);
+    assert_eq!(tree.selected, Some(vec![1]));
+    assert_eq!(tree.rows(&roots, 2..4).0[0].path, vec![0, 1]);
+
+    // Collapsing an ancestor moves the selection to it, Left goes to the parent
+    tree.selected = Some(vec![0, 1, 0]);
+    tree.set_expanded(&[0], false);
+    assert_eq!(tree.selected, Some(vec![0]));
+    tree.set_expanded(&[0], true);
+    tree.selected = Some(vec![0, 1]);
+    tree.navigate(&roots, SpecialKeyboardButton::Left, 10);
+    assert!(!tree.is_expanded(&[0, 1]));
+    tree.navigate(&roots, SpecialKeyboardButton::Left, 10);
+    assert_eq!(tree.selected, Some(vec![0]));
+
+    assert_eq!(node_at(&roots, &[0, 1, 0]).unwrap().label, "mod.rs");
+    assert_eq!(node_at(&roots, &[2]), None);
+}
+
+#[test]
+fn tree_rows_keep_focus_and_scroll() {
+    use just_canvas::{record::Recording, BackendType, Canvas};
+
+    // Click the second row once rows are known from the previous frame, then scroll over it
+    let recording = Recording::read(
+        "0 0 pointer_motion 10 30\n\
+         1 0 pointer_press left\n\
+         2 0 pointer_release left\n\
+         2 0 pointer_press scroll_down\n\
+         3 0 pointer_release scroll_down\n"
+            .as_bytes(),
+    )
+    .unwrap();
+    let canvas = Canvas::with_backend_type(
+        "",
+        BackendType::Playback {
+            size: Vector2 { x: 100, y: 100 },
+            recording,
+        },
+    )
+    .unwrap();
+    let mut ui = Ui::with_canvas(canvas);
+    let id = UiId {
+        id: 0,
+        parent: 0,
+        index: 0,
+    };
+    let roots = (0..10)
+        .map(|idx| TreeNode::leaf(idx.to_string()))
+        .collect::<Vec<_>>();
+    let mut tree = TreeView::new();
+    let frame = |ui: &mut Ui, tree: &mut TreeView| {
+        ui.canvas.process_events().unwrap();
+        ui.begin_z_order_frame();
+        tree.draw(
+            ui,
+            id,
+            &roots,
+            Vector2 { x: 0, y: 0 },
+            Vector2 { x: 100, y: 60 },
+        );
+    };
+
+    frame(&mut ui, &mut tree);
+    frame(&mut ui, &mut tree);
+    assert_eq!(tree.selected, Some(vec![1]));
+    assert!(tree.focused);
+    frame(&mut ui, &mut tree);
+    assert_eq!(tree.scroll, SCROLL_STEP);
+    assert!(tree.focused);
+}
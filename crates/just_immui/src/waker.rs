@@ -0,0 +1,133 @@
+use std::{
+    any::Any,
+    marker::PhantomData,
+    mem,
+    sync::{Arc, Condvar, Mutex},
+    time::Duration,
+};
+
+use crate::Ui;
+
+#[derive(Default)]
+struct Inbox {
+    woken: bool,
+    messages: Vec<Box<dyn Any + Send>>,
+}
+
+#[derive(Default)]
+pub(crate) struct Shared {
+    inbox: Mutex<Inbox>,
+    condvar: Condvar,
+}
+
+impl Shared {
+    /// Sleep for `timeout` or until woken by a [`UiWaker`]
+    pub(crate) fn sleep(&self, timeout: Duration) {
+        let inbox = self.inbox.lock().unwrap();
+        let _ = self
+            .condvar
+            .wait_timeout_while(inbox, timeout, |inbox| !inbox.woken)
+            .unwrap();
+    }
+
+    /// Take all messages sent since last call, returns `true` if the UI was woken
+    pub(crate) fn receive(&self, messages: &mut Vec<Box<dyn Any + Send>>) -> bool {
+        let mut inbox = self.inbox.lock().unwrap();
+        messages.append(&mut inbox.messages);
+        mem::take(&mut inbox.woken)
+    }
+}
+
+/// Handle for background threads to redraw the UI and send it messages of type `T`, received
+/// with [`Ui::take_messages`] in the next frame. Created with [`Ui::waker`].
+pub struct UiWaker<T> {
+    shared: Arc<Shared>,
+    _message: PhantomData<fn(T)>,
+}
+
+impl<T> Clone for UiWaker<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+            _message: PhantomData,
+        }
+    }
+}
+
+impl<T: Send + 'static> UiWaker<T> {
+    /// Redraw the next frame and end the sleep between frames early
+    pub fn wake(&self) {
+        self.shared.inbox.lock().unwrap().woken = true;
+        self.shared.condvar.notify_one();
+    }
+
+    /// Deliver `message` with the next frame. Messages are lost if the UI is already closed.
+    pub fn send(&self, message: T) {
+        let mut inbox = self.shared.inbox.lock().unwrap();
+        inbox.messages.push(Box::new(message));
+        inbox.woken = true;
+        drop(inbox);
+        self.shared.condvar.notify_one();
+    }
+}
+
+impl Ui {
+    #[inline]
+    pub fn waker<T: Send + 'static>(&self) -> UiWaker<T> {
+        UiWaker {
+            shared: self.waker.clone(),
+            _message: PhantomData,
+        }
+    }
+
+    /// Messages of type `T` sent with [`UiWaker::send`] received this frame, in order of sending
+    pub fn take_messages<T: 'static>(&mut self) -> Vec<T> {
+        let mut taken = Vec::new();
+        let mut rest = Vec::with_capacity(self.messages.len());
+        for message in self.messages.drain(..) {
+            match message.downcast::<T>() {
+                Ok(message) => taken.push(*message),
+                Err(message) => rest.push(message),
+            }
+        }
+        self.messages = rest;
+        taken
+    }
+
+    pub(crate) fn receive_messages(&mut self) {
+        if self.waker.receive(&mut self.messages) {
+            self.redraw_all();
+        }
+    }
+}
+
+#[test]
+fn waker_delivers_messages() {
+    use just_canvas::{BackendType, Canvas, Vector2};
+
+    let canvas = Canvas::with_backend_type(
+        "",
+        BackendType::Bitmap {
+            size: Vector2 { x: 16, y: 16 },
+        },
+    )
+    .unwrap();
+    let mut ui = Ui::with_canvas(canvas);
+    ui.dirty = false;
+
+    let waker = ui.waker::<u32>();
+    let strings = ui.waker::<String>();
+    std::thread::spawn(move || {
+        waker.send(1);
+        strings.send(String::from("loaded"));
+        waker.send(2);
+    })
+    .join()
+    .unwrap();
+
+    ui.receive_messages();
+    assert!(ui.is_dirty());
+    assert_eq!(ui.take_messages::<u32>(), vec![1, 2]);
+    assert_eq!(ui.take_messages::<u32>(), Vec::<u32>::new());
+    assert_eq!(ui.take_messages::<String>(), vec![String::from("loaded")]);
+}
@@ -0,0 +1,210 @@
+//! Scaling and cropping of an image into a monitor area
+
+use just_canvas::{image::Image, Color, PixelFormat, Vector2, BYTES_PER_PIXEL};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FitMode {
+    /// Cover whole area keeping aspect ratio, cropping edges of the image
+    Fill,
+    /// Show whole image keeping aspect ratio, leaving bars around it
+    Fit,
+    /// Cover whole area ignoring aspect ratio
+    Stretch,
+    /// Image in its original size in the middle of the area
+    Center,
+}
+
+impl FromStr for FitMode {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "fill" => Ok(Self::Fill),
+            "fit" => Ok(Self::Fit),
+            "stretch" => Ok(Self::Stretch),
+            "center" => Ok(Self::Center),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Source rectangle of the image mapped onto destination rectangle of the area
+#[derive(Debug, Clone, Copy)]
+struct Placement {
+    source_position: Vector2<f32>,
+    source_size: Vector2<f32>,
+    /// Relative to the area
+    position: Vector2<u32>,
+    size: Vector2<u32>,
+}
+
+impl Placement {
+    fn new(image_size: Vector2<u32>, area_size: Vector2<u32>, mode: FitMode) -> Self {
+        let image = Vector2 {
+            x: image_size.x as f32,
+            y: image_size.y as f32,
+        };
+        let area = Vector2 {
+            x: area_size.x as f32,
+            y: area_size.y as f32,
+        };
+        let scale = match mode {
+            FitMode::Fill => Some(f32::max(area.x / image.x, area.y / image.y)),
+            FitMode::Fit => Some(f32::min(area.x / image.x, area.y / image.y)),
+            FitMode::Center => Some(1.0),
+            FitMode::Stretch => None,
+        };
+        let Some(scale) = scale else {
+            return Self {
+                source_position: Vector2 { x: 0.0, y: 0.0 },
+                source_size: image,
+                position: Vector2 { x: 0, y: 0 },
+                size: area_size,
+            };
+        };
+
+        // Scaled image clipped to the area, both centered
+        let size = Vector2 {
+            x: f32::min(image.x * scale, area.x),
+            y: f32::min(image.y * scale, area.y),
+        };
+        let source_size = Vector2 {
+            x: size.x / scale,
+            y: size.y / scale,
+        };
+        Self {
+            source_position: Vector2 {
+                x: (image.x - source_size.x) / 2.0,
+                y: (image.y - source_size.y) / 2.0,
+            },
+            source_size,
+            position: Vector2 {
+                x: ((area.x - size.x) / 2.0).round() as u32,
+                y: ((area.y - size.y) / 2.0).round() as u32,
+            },
+            size: Vector2 {
+                x: size.x.round() as u32,
+                y: size.y.round() as u32,
+            },
+        }
+    }
+}
+
+/// Bilinear sample of `image` at continuous coordinates, pixel centers are at `.5`
+fn sample(image: &Image, x: f32, y: f32) -> Color {
+    let x = (x - 0.5).clamp(0.0, (image.size.x - 1) as f32);
+    let y = (y - 0.5).clamp(0.0, (image.size.y - 1) as f32);
+    let (x0, y0) = (x as u32, y as u32);
+    let (x1, y1) = (
+        (x0 + 1).min(image.size.x - 1),
+        (y0 + 1).min(image.size.y - 1),
+    );
+    let (tx, ty) = (x - x0 as f32, y - y0 as f32);
+
+    let corners = [
+        image.pixel(Vector2 { x: x0, y: y0 }),
+        image.pixel(Vector2 { x: x1, y: y0 }),
+        image.pixel(Vector2 { x: x0, y: y1 }),
+        image.pixel(Vector2 { x: x1, y: y1 }),
+    ];
+    let weights = [
+        (1.0 - tx) * (1.0 - ty),
+        tx * (1.0 - ty),
+        (1.0 - tx) * ty,
+        tx * ty,
+    ];
+    let channel = |get: fn(&Color) -> u8| {
+        let value: f32 = corners
+            .iter()
+            .zip(weights)
+            .map(|(color, weight)| get(color) as f32 * weight)
+            .sum();
+        value.round() as u8
+    };
+    Color::from_components(255, channel(|c| c.r), channel(|c| c.g), channel(|c| c.b))
+}
+
+/// Draw `image` into `area_size` rectangle at `area_position` of `buf`, which is `buf_size` large
+/// with pixels in `format`. Parts of the area outside of `buf` are skipped.
+pub fn draw(
+    buf: &mut [u8],
+    buf_size: Vector2<u32>,
+    format: PixelFormat,
+    area_position: Vector2<i32>,
+    area_size: Vector2<u32>,
+    image: &Image,
+    mode: FitMode,
+) {
+    if image.size.x == 0 || image.size.y == 0 {
+        return;
+    }
+
+    let placement = Placement::new(image.size, area_size, mode);
+    for y in 0..placement.size.y {
+        let buf_y = area_position.y + (placement.position.y + y) as i32;
+        if buf_y < 0 || buf_y >= buf_size.y as i32 {
+            continue;
+        }
+        let source_y = placement.source_position.y
+            + (y as f32 + 0.5) * placement.source_size.y / placement.size.y as f32;
+
+        for x in 0..placement.size.x {
+            let buf_x = area_position.x + (placement.position.x + x) as i32;
+            if buf_x < 0 || buf_x >= buf_size.x as i32 {
+                continue;
+            }
+            let source_x = placement.source_position.x
+                + (x as f32 + 0.5) * placement.source_size.x / placement.size.x as f32;
+
+            let offset =
+                (buf_y as usize * buf_size.x as usize + buf_x as usize) * BYTES_PER_PIXEL as usize;
+            buf[offset..offset + BYTES_PER_PIXEL as usize]
+                .copy_from_slice(&format.encode(sample(image, source_x, source_y)));
+        }
+    }
+}
+
+#[test]
+fn placement_modes() {
+    let image = Vector2 { x: 200, y: 100 };
+    let area = Vector2 { x: 100, y: 100 };
+
+    let fill = Placement::new(image, area, FitMode::Fill);
+    assert_eq!((fill.size.x, fill.size.y), (100, 100));
+    assert_eq!((fill.source_position.x, fill.source_size.x), (50.0, 100.0));
+
+    let fit = Placement::new(image, area, FitMode::Fit);
+    assert_eq!((fit.position.y, fit.size.x, fit.size.y), (25, 100, 50));
+    assert_eq!(fit.source_size.x, 200.0);
+
+    let center = Placement::new(image, area, FitMode::Center);
+    assert_eq!(
+        (center.position.x, center.size.x, center.size.y),
+        (0, 100, 100)
+    );
+    assert_eq!(center.source_position.x, 50.0);
+}
+
+#[test]
+fn draw_clips_to_buffer() {
+    let red = [255, 0, 0, 255].repeat(4);
+    let image = Image::from_rgba(Vector2 { x: 2, y: 2 }, &red).unwrap();
+    let size = Vector2 { x: 4, y: 2 };
+    let mut buf = vec![0u8; 4 * 2 * BYTES_PER_PIXEL as usize];
+
+    // Right half of the area is outside of the buffer
+    draw(
+        &mut buf,
+        size,
+        PixelFormat::Bgra8888,
+        Vector2 { x: 2, y: 0 },
+        Vector2 { x: 4, y: 2 },
+        &image,
+        FitMode::Stretch,
+    );
+    let pixel = |x: usize| &buf[x * BYTES_PER_PIXEL as usize..][..4];
+    assert_eq!(pixel(1), [0, 0, 0, 0]);
+    assert_eq!(pixel(2), [0, 0, 255, 255]);
+    assert_eq!(pixel(3), [0, 0, 255, 255]);
+}
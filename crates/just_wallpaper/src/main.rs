@@ -0,0 +1,353 @@
+// CLIPPY CONFIG
+#![allow(
+    clippy::new_without_default,
+    clippy::unnecessary_cast,
+    clippy::identity_op
+)]
+
+use crate::fit::FitMode;
+use just_canvas::{
+    image::{Image, ImageError},
+    PixelFormat, Rect, Vector2, BYTES_PER_PIXEL,
+};
+use just_x11::{
+    atoms::AtomId,
+    error::Error,
+    replies::{PropertyValue, String8},
+    requests::{
+        self, ChangePropertyFormat, ChangePropertyMode, KillClientResource, PutImageFormat,
+        SetCloseDownModeMode, WindowCreationAttributes,
+    },
+    Drawable, PixmapId, WindowId,
+};
+use just_x11_simple::{
+    display::{self as x11_display, DisplayError},
+    monitors::Monitors,
+    X11Connection,
+};
+use std::{env, fmt, fs, io, process, str::FromStr};
+
+mod fit;
+
+/// Root pixmap property read by compositors and pseudo-transparent terminals
+const XROOTPMAP_ID: &str = "_XROOTPMAP_ID";
+/// Root pixmap property of the client that set it, so the next setter can free it
+const ESETROOT_PMAP_ID: &str = "ESETROOT_PMAP_ID";
+
+const USAGE: &str =
+    "Usage: justwallpaper [--display DISPLAY] [--mode MODE] [--monitor NAME IMAGE]... [IMAGE]
+
+Set background of the root window, each RandR monitor gets its own scaled copy of the image.
+Supports PNG, QOI, and binary PGM and PPM images.
+
+Options:
+    --display DISPLAY     Use DISPLAY instead of $DISPLAY
+    --mode MODE           How the image is scaled to a monitor, one of
+                          fill (default), fit, stretch, center
+    --monitor NAME IMAGE  Use IMAGE for monitor NAME instead of the default IMAGE";
+
+#[derive(Debug, Default)]
+struct Args {
+    display: Option<String>,
+    mode: Option<FitMode>,
+    /// Image of monitors not listed in `monitors`
+    image: Option<String>,
+    monitors: Vec<(String, String)>,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut res = Self::default();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--display" => {
+                    res.display = Some(args.next().ok_or("--display expects an argument")?);
+                }
+                "--mode" => {
+                    let mode = args.next().ok_or("--mode expects an argument")?;
+                    res.mode = Some(
+                        FitMode::from_str(&mode)
+                            .map_err(|()| format!("Unknown mode '{}'\n\n{}", mode, USAGE))?,
+                    );
+                }
+                "--monitor" => {
+                    let (Some(name), Some(image)) = (args.next(), args.next()) else {
+                        return Err("--monitor expects monitor name and image".to_string());
+                    };
+                    res.monitors.push((name, image));
+                }
+                "--help" | "-h" => return Err(USAGE.to_string()),
+                _ if arg.starts_with('-') => {
+                    return Err(format!("Unknown argument '{}'\n\n{}", arg, USAGE))
+                }
+                _ if res.image.is_none() => res.image = Some(arg),
+                _ => return Err(format!("Only one default image can be given\n\n{}", USAGE)),
+            }
+        }
+
+        if res.image.is_none() && res.monitors.is_empty() {
+            return Err(USAGE.to_string());
+        }
+
+        Ok(res)
+    }
+
+    /// Image path for the monitor with `name`
+    fn image_for(&self, name: &str) -> Option<&str> {
+        self.monitors
+            .iter()
+            .rev()
+            .find(|(monitor, _)| monitor == name)
+            .map(|(_, image)| image.as_str())
+            .or(self.image.as_deref())
+    }
+}
+
+#[derive(Debug)]
+enum WallpaperError {
+    Display(DisplayError),
+    X11(Error),
+    Read(String, io::Error),
+    Decode(String, ImageError),
+    /// Root window pixels are not 32 bit with 8 bit channels
+    UnsupportedVisual,
+}
+
+impl fmt::Display for WallpaperError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WallpaperError::Display(err) => err.fmt(f),
+            WallpaperError::X11(err) => err.fmt(f),
+            WallpaperError::Read(path, err) => write!(f, "Could not read '{}': {}", path, err),
+            WallpaperError::Decode(path, err) => write!(f, "Could not decode '{}': {}", path, err),
+            WallpaperError::UnsupportedVisual => {
+                write!(
+                    f,
+                    "Root window visual is not supported, expected 24 bit TrueColor"
+                )
+            }
+        }
+    }
+}
+
+impl From<Error> for WallpaperError {
+    fn from(err: Error) -> Self {
+        Self::X11(err)
+    }
+}
+
+impl From<DisplayError> for WallpaperError {
+    fn from(err: DisplayError) -> Self {
+        Self::Display(err)
+    }
+}
+
+/// Decoded images by path, so an image shared by monitors is decoded once
+struct Images {
+    images: Vec<(String, Image)>,
+}
+
+impl Images {
+    fn get(&mut self, path: &str) -> Result<&Image, WallpaperError> {
+        let idx = match self.images.iter().position(|(loaded, _)| loaded == path) {
+            Some(idx) => idx,
+            None => {
+                let bytes =
+                    fs::read(path).map_err(|err| WallpaperError::Read(path.to_string(), err))?;
+                let image = Image::decode(&bytes)
+                    .map_err(|err| WallpaperError::Decode(path.to_string(), err))?;
+                self.images.push((path.to_string(), image));
+                self.images.len() - 1
+            }
+        };
+        Ok(&self.images[idx].1)
+    }
+}
+
+/// Pixmap id stored in a root property, if it is set
+fn root_pixmap(
+    conn: &mut X11Connection,
+    root: WindowId,
+    property: AtomId,
+) -> Result<Option<PixmapId>, Error> {
+    let PropertyValue::Value {
+        format: 32, value, ..
+    } = conn.get_property(root, property, AtomId::PIXMAP, 1)?
+    else {
+        return Ok(None);
+    };
+    let Ok(id) = <[u8; 4]>::try_from(value) else {
+        return Ok(None);
+    };
+
+    let id = u32::from_le_bytes(id);
+    Ok((id != 0).then(|| PixmapId::from(id)))
+}
+
+/// Upload `buf` with `size` pixels into `pixmap`, split into requests fitting into maximum
+/// request length
+fn put_image(
+    conn: &mut X11Connection,
+    pixmap: PixmapId,
+    depth: u8,
+    size: Vector2<u32>,
+    buf: &[u8],
+) -> Result<(), Error> {
+    // PutImage header is 6 units of 4 bytes
+    let max_bytes = (conn.display().maximum_request_length() as usize - 6) * 4;
+    let row_bytes = size.x as usize * BYTES_PER_PIXEL as usize;
+    let rows_per_request = (max_bytes / row_bytes.max(1)).max(1);
+    let screen = conn.display().default_screen_index();
+    let gc = conn.display_mut().default_gc(screen)?;
+
+    for (idx, rows) in buf.chunks(rows_per_request * row_bytes).enumerate() {
+        conn.display_mut().send_request(&requests::PutImage {
+            format: PutImageFormat::ZPixmap,
+            drawable: Drawable::Pixmap(pixmap),
+            gc,
+            width: size.x as u16,
+            height: (rows.len() / row_bytes) as u16,
+            dst_x: 0,
+            dst_y: (idx * rows_per_request) as i16,
+            left_pad: 0,
+            depth,
+            data: rows,
+        })?;
+    }
+    Ok(())
+}
+
+fn go(args: Args) -> Result<(), WallpaperError> {
+    let display = x11_display::open(args.display.as_deref())?;
+    let mut conn = X11Connection::new(display);
+    let screen_idx = conn.display().default_screen_index();
+    let screen = conn.display().default_screen().clone();
+
+    let format = screen
+        .root_visual_type()
+        .and_then(|visual| {
+            PixelFormat::from_visual_masks(visual.red_mask, visual.green_mask, visual.blue_mask)
+        })
+        .filter(|_| screen.root_depth == 24 || screen.root_depth == 32)
+        .ok_or(WallpaperError::UnsupportedVisual)?;
+
+    let size = Vector2 {
+        x: screen.width_in_pixels as u32,
+        y: screen.height_in_pixels as u32,
+    };
+    let mut buf = vec![0u8; size.x as usize * size.y as usize * BYTES_PER_PIXEL as usize];
+
+    let monitors = Monitors::new(&mut conn, screen_idx)?;
+    for (name, _) in &args.monitors {
+        if !monitors
+            .monitors()
+            .iter()
+            .any(|monitor| monitor.name == *name)
+        {
+            eprintln!("justwallpaper: warning: No monitor named '{}'", name);
+        }
+    }
+
+    let mut images = Images { images: Vec::new() };
+    for monitor in monitors.monitors() {
+        let Some(path) = args.image_for(&monitor.name) else {
+            continue;
+        };
+        let geometry = Rect::from(monitor.geometry);
+        fit::draw(
+            &mut buf,
+            size,
+            format,
+            geometry.position,
+            geometry.size,
+            images.get(path)?,
+            args.mode.unwrap_or(FitMode::Fill),
+        );
+    }
+    conn.display()
+        .image_format()
+        .convert_zpixmap(&mut buf, (BYTES_PER_PIXEL * 8) as u8);
+
+    let owned_pixmap = conn.create_pixmap(
+        screen.root_depth,
+        Drawable::Window(screen.root),
+        size.x as u16,
+        size.y as u16,
+    )?;
+    let pixmap = owned_pixmap.id();
+    put_image(&mut conn, pixmap, screen.root_depth, size, &buf)?;
+
+    // Pixmap of the previous setter was kept alive by retaining its client's resources
+    let xrootpmap_id = conn.get_atom_id(String8::from_str(XROOTPMAP_ID).unwrap())?;
+    let esetroot_pmap_id = conn.get_atom_id(String8::from_str(ESETROOT_PMAP_ID).unwrap())?;
+    let previous = root_pixmap(&mut conn, screen.root, xrootpmap_id)?;
+    let retained = root_pixmap(&mut conn, screen.root, esetroot_pmap_id)?;
+    if let Some(previous) = previous.filter(|previous| Some(*previous) == retained) {
+        conn.kill_client(KillClientResource::from(previous))?;
+    }
+
+    conn.display_mut()
+        .send_request(&requests::ChangeWindowAttributes {
+            window: screen.root,
+            attributes: WindowCreationAttributes::new().set_background_pixmap(pixmap.into()),
+        })?;
+    conn.display_mut().send_request(&requests::ClearArea {
+        exposures: false,
+        window: screen.root,
+        x: 0,
+        y: 0,
+        width: 0,
+        height: 0,
+    })?;
+    for property in [xrootpmap_id, esetroot_pmap_id] {
+        conn.display_mut().send_request(&requests::ChangeProperty {
+            mode: ChangePropertyMode::Replace,
+            window: screen.root,
+            property,
+            type_: AtomId::PIXMAP,
+            format: ChangePropertyFormat::Format32,
+            data: pixmap.to_le_bytes().to_vec(),
+        })?;
+    }
+
+    // Pixmap must outlive this connection to stay on the root
+    owned_pixmap.leak();
+    conn.display_mut()
+        .send_request(&requests::SetCloseDownMode {
+            mode: SetCloseDownModeMode::RetainPermanent,
+        })?;
+    conn.display_mut().flush()?;
+
+    Ok(())
+}
+
+fn main() {
+    let args = match Args::parse(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(2);
+        }
+    };
+
+    if let Err(err) = go(args) {
+        eprintln!("justwallpaper: error: {}", err);
+        process::exit(1);
+    }
+}
+
+#[test]
+fn monitor_images() {
+    let args = Args::parse(
+        ["--monitor", "DP-1", "a.png", "b.png", "--mode", "fit"]
+            .map(String::from)
+            .into_iter(),
+    )
+    .unwrap();
+    assert_eq!(args.mode, Some(FitMode::Fit));
+    assert_eq!(args.image_for("DP-1"), Some("a.png"));
+    assert_eq!(args.image_for("HDMI-1"), Some("b.png"));
+
+    assert!(Args::parse(["--mode", "tile"].map(String::from).into_iter()).is_err());
+    assert!(Args::parse(std::iter::empty()).is_err());
+}
@@ -0,0 +1,206 @@
+//! Programs spawned by the window manager
+//!
+//! Zombies are reaped by polling with `try_wait`, which is a bit more clean and cross-platform
+//! than catching `SIGCHLD`. Autostart entries are started again when they fail, i.e. exit with
+//! non-zero status or are killed by a signal, unless they failed more than [`RESPAWN_LIMIT`] times
+//! within [`RESPAWN_WINDOW`]. Successful exits are not respawned, so one-shot commands like
+//! `xsetroot` run once.
+
+use std::{
+    collections::VecDeque,
+    fmt, io,
+    path::PathBuf,
+    process::{self, Command, ExitStatus},
+    time::{Duration, Instant},
+};
+
+/// Respawns of one autostart entry allowed within [`RESPAWN_WINDOW`]
+pub const RESPAWN_LIMIT: usize = 5;
+pub const RESPAWN_WINDOW: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChildCommand {
+    /// Executable run without arguments
+    Program(PathBuf),
+    /// Line run with `sh -c`
+    Shell(String),
+}
+
+impl ChildCommand {
+    fn command(&self) -> Command {
+        match self {
+            ChildCommand::Program(path) => Command::new(path),
+            ChildCommand::Shell(line) => {
+                let mut command = Command::new("sh");
+                command.arg("-c").arg(line);
+                command
+            }
+        }
+    }
+}
+
+impl fmt::Display for ChildCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChildCommand::Program(path) => write!(f, "{}", path.display()),
+            ChildCommand::Shell(line) => write!(f, "sh -c {}", line),
+        }
+    }
+}
+
+/// Recent failures of an autostart entry
+#[derive(Debug, Default)]
+pub struct CrashLoop {
+    failures: VecDeque<Instant>,
+}
+
+impl CrashLoop {
+    /// Record a failure at `now`, returns `false` if the entry failed too often to be respawned
+    pub fn failed(&mut self, now: Instant) -> bool {
+        while self
+            .failures
+            .front()
+            .is_some_and(|&failure| now.duration_since(failure) >= RESPAWN_WINDOW)
+        {
+            self.failures.pop_front();
+        }
+        self.failures.push_back(now);
+        self.failures.len() <= RESPAWN_LIMIT
+    }
+}
+
+/// What happened after an autostart entry failed
+#[derive(Debug)]
+pub enum Respawn {
+    /// Child was not started from autostart
+    No,
+    Respawned,
+    /// Failed more than [`RESPAWN_LIMIT`] times within [`RESPAWN_WINDOW`], not started again
+    CrashLoop,
+    Failed(io::Error),
+}
+
+/// Child that exited with non-zero status or was killed by a signal
+#[derive(Debug)]
+pub struct Failed {
+    pub command: ChildCommand,
+    pub status: ExitStatus,
+    pub respawn: Respawn,
+}
+
+struct Running {
+    process: process::Child,
+    command: ChildCommand,
+    /// Index into autostart entries of [`Children`]
+    autostart: Option<usize>,
+}
+
+pub struct Children {
+    running: Vec<Running>,
+    autostart: Vec<CrashLoop>,
+    /// Value of `DISPLAY` for spawned programs
+    display_name: String,
+}
+
+impl Children {
+    pub fn new(display_name: String) -> Self {
+        Self {
+            running: Vec::new(),
+            autostart: Vec::new(),
+            display_name,
+        }
+    }
+
+    fn start(&mut self, command: ChildCommand, autostart: Option<usize>) -> io::Result<()> {
+        let process = command
+            .command()
+            .env("XDG_CURRENT_DESKTOP", crate::WM_NAME)
+            .env("DISPLAY", &self.display_name)
+            .spawn()?;
+        self.running.push(Running {
+            process,
+            command,
+            autostart,
+        });
+        Ok(())
+    }
+
+    pub fn spawn(&mut self, command: ChildCommand) -> io::Result<()> {
+        self.start(command, None)
+    }
+
+    /// Spawn `command` and respawn it when it fails
+    pub fn autostart(&mut self, command: ChildCommand) -> io::Result<()> {
+        self.start(command, Some(self.autostart.len()))?;
+        self.autostart.push(CrashLoop::default());
+        Ok(())
+    }
+
+    /// Reap exited children and respawn failed autostart entries, returns the failed ones
+    pub fn reap(&mut self, now: Instant) -> io::Result<Vec<Failed>> {
+        let mut exited = Vec::new();
+        let mut idx = 0;
+        while idx < self.running.len() {
+            match self.running[idx].process.try_wait()? {
+                Some(status) => exited.push((self.running.swap_remove(idx), status)),
+                None => idx += 1,
+            }
+        }
+
+        let mut failed = Vec::new();
+        for (child, status) in exited {
+            if status.success() {
+                continue;
+            }
+            let respawn = match child.autostart {
+                None => Respawn::No,
+                Some(entry) if !self.autostart[entry].failed(now) => Respawn::CrashLoop,
+                Some(entry) => match self.start(child.command.clone(), Some(entry)) {
+                    Ok(()) => Respawn::Respawned,
+                    Err(err) => Respawn::Failed(err),
+                },
+            };
+            failed.push(Failed {
+                command: child.command,
+                status,
+                respawn,
+            });
+        }
+        Ok(failed)
+    }
+}
+
+#[test]
+fn crash_loop_limits_respawns() {
+    let start = Instant::now();
+    let mut crash_loop = CrashLoop::default();
+    for idx in 0..RESPAWN_LIMIT {
+        assert!(crash_loop.failed(start + Duration::from_secs(idx as u64)));
+    }
+    assert!(!crash_loop.failed(start + Duration::from_secs(10)));
+
+    // Failures older than the window are forgotten
+    assert!(crash_loop.failed(start + RESPAWN_WINDOW + Duration::from_secs(11)));
+}
+
+#[test]
+fn failed_autostart_is_respawned() {
+    let mut children = Children::new(String::new());
+    children
+        .autostart(ChildCommand::Shell("exit 3".to_string()))
+        .unwrap();
+    children
+        .spawn(ChildCommand::Shell("exit 0".to_string()))
+        .unwrap();
+    for child in &mut children.running {
+        child.process.wait().unwrap();
+    }
+
+    let failed = children.reap(Instant::now()).unwrap();
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].command.to_string(), "sh -c exit 3");
+    assert_eq!(failed[0].status.code(), Some(3));
+    assert!(matches!(failed[0].respawn, Respawn::Respawned));
+    assert_eq!(children.running.len(), 1);
+    children.running[0].process.wait().unwrap();
+}
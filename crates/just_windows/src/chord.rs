@@ -0,0 +1,29 @@
+//! Multi-key bindings, a prefix key followed by one more key, e.g. `Ctrl m` then `1`
+//!
+//! Pressing the prefix grabs the keyboard so the next key reaches the window manager whatever
+//! it is, and shows a popup listing the keys that can follow. Any other key, `Escape` or
+//! [`CHORD_TIMEOUT`] cancels the chord.
+
+use crate::{popup::TextPopup, JustAction};
+use just_x11::keysym::KeySym;
+use std::time::{Duration, Instant};
+
+/// How long the window manager waits for the key following a prefix
+pub const CHORD_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Keys that can follow a prefix
+#[derive(Debug, Clone)]
+pub struct Chord {
+    /// Lines of the hint popup
+    pub hint: Vec<String>,
+    pub keys: Vec<(KeySym, JustAction)>,
+}
+
+/// Prefix was pressed and the window manager waits for the next key
+pub struct PendingChord {
+    /// Index into chords of `KeyBindings`
+    pub chord: usize,
+    pub deadline: Instant,
+    /// `None` if the popup could not be shown, e.g. without the `fixed` font
+    pub hint: Option<TextPopup>,
+}
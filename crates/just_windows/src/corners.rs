@@ -0,0 +1,124 @@
+//! Rounded window corners cut with the SHAPE extension, without a compositor
+
+use crate::layout::PositionedWindow;
+use just_x11::{
+    error::Error,
+    extensions::shape::{self, ShapeKind, ShapeOperation},
+    requests::Ordering,
+    OrNone, Rectangle, XDisplay,
+};
+
+#[derive(Debug, Clone, Copy)]
+pub struct RoundedCorners {
+    pub major_opcode: u8,
+    pub radius: u16,
+}
+
+impl RoundedCorners {
+    /// Cut corners of the window including its border, or remove the shape if not `rounded`
+    pub fn apply(
+        self,
+        display: &mut XDisplay,
+        positioned: PositionedWindow,
+        rounded: bool,
+    ) -> Result<(), Error> {
+        if !rounded {
+            display.send_extension_request(
+                &shape::requests::Mask {
+                    operation: ShapeOperation::Set,
+                    kind: ShapeKind::Bounding,
+                    window: positioned.window,
+                    x_offset: 0,
+                    y_offset: 0,
+                    source: OrNone::none(),
+                },
+                self.major_opcode,
+            )?;
+            return Ok(());
+        }
+
+        let border = positioned.border_width;
+        display.send_extension_request(
+            &shape::requests::Rectangles {
+                operation: ShapeOperation::Set,
+                kind: ShapeKind::Bounding,
+                ordering: Ordering::YXBanded,
+                window: positioned.window,
+                // Bounding shape starts at the outer corner of the border
+                x_offset: -(border as i16),
+                y_offset: -(border as i16),
+                rectangles: rounded_rectangles(
+                    positioned.position.width + border * 2,
+                    positioned.position.height + border * 2,
+                    self.radius,
+                ),
+            },
+            self.major_opcode,
+        )?;
+        Ok(())
+    }
+}
+
+/// Rows covering `width`x`height` area with corners cut by circles of `radius`, as YX-banded
+/// rectangles
+fn rounded_rectangles(width: u16, height: u16, radius: u16) -> Vec<Rectangle> {
+    let radius = radius.min(width / 2).min(height / 2);
+    let r = radius as f32;
+
+    let mut top = Vec::with_capacity(radius as usize);
+    for row in 0..radius {
+        // Distance from the circle center to the middle of the row
+        let dy = r - row as f32 - 0.5;
+        let inset = (r - (r * r - dy * dy).sqrt()).round() as u16;
+        top.push((row, inset));
+    }
+
+    let mut rectangles = Vec::with_capacity(radius as usize * 2 + 1);
+    let row = |y: u16, inset: u16| Rectangle {
+        x: inset as i16,
+        y: y as i16,
+        width: width - inset * 2,
+        height: 1,
+    };
+    rectangles.extend(top.iter().map(|&(y, inset)| row(y, inset)));
+    if height > radius * 2 {
+        rectangles.push(Rectangle {
+            x: 0,
+            y: radius as i16,
+            width,
+            height: height - radius * 2,
+        });
+    }
+    rectangles.extend(
+        top.iter()
+            .rev()
+            .map(|&(y, inset)| row(height - 1 - y, inset)),
+    );
+    rectangles
+}
+
+#[test]
+fn rounded_rectangles_cover_area() {
+    assert_eq!(
+        rounded_rectangles(10, 8, 0),
+        vec![Rectangle {
+            x: 0,
+            y: 0,
+            width: 10,
+            height: 8
+        }]
+    );
+
+    let rectangles = rounded_rectangles(20, 10, 4);
+    assert_eq!(rectangles.len(), 9);
+    // Sorted by rows and symmetric
+    assert!(rectangles.windows(2).all(|w| w[0].y < w[1].y));
+    assert_eq!(rectangles[0].x, rectangles[8].x);
+    assert!(rectangles[0].x > 0);
+    assert_eq!(rectangles[4].height, 2);
+    let area = rectangles
+        .iter()
+        .map(|r| r.width as u32 * r.height as u32)
+        .sum::<u32>();
+    assert!(area < 200 && area > 200 - 16);
+}
@@ -0,0 +1,190 @@
+//! Rearranging tiles with the pointer. Dragging a tiled window with the modifier and the left
+//! button held swaps it with the tile it is dropped on, the tile under the pointer is outlined
+//! while dragging.
+
+use crate::layout::PositionedWindow;
+use just_geometry::{Rect, Vector2};
+use just_x11::{
+    error::Error,
+    events::StackMode,
+    requests::{self, ConfigureWindowAttributes, WindowCreationAttributes},
+    Rectangle, WindowClass, WindowId, WindowVisual, XDisplay,
+};
+use just_x11_simple::panic_hook;
+
+/// Left button, grabbed together with the modifier of key bindings
+pub const DRAG_BUTTON: u8 = 1;
+
+const OUTLINE_COLOR: u32 = 0x4eb4fa;
+const OUTLINE_WIDTH: u16 = 4;
+
+/// Area covered by a tile including its border
+pub fn tile_area(positioned: PositionedWindow) -> Rectangle {
+    Rectangle {
+        width: positioned.position.width + positioned.border_width * 2,
+        height: positioned.position.height + positioned.border_width * 2,
+        ..positioned.position
+    }
+}
+
+/// Tile containing the point `(x, y)`
+pub fn tile_at(tiles: &[(WindowId, Rectangle)], (x, y): (i16, i16)) -> Option<WindowId> {
+    tiles
+        .iter()
+        .find(|(_, area)| {
+            Rect::from(*area).contains(Vector2 {
+                x: x as i32,
+                y: y as i32,
+            })
+        })
+        .map(|(window, _)| *window)
+}
+
+/// Top, bottom, left and right edges of the outline drawn inside of `area`
+fn outline_edges(area: Rectangle) -> [Rectangle; 4] {
+    let width = OUTLINE_WIDTH.min(area.width / 2).max(1);
+    let height = OUTLINE_WIDTH.min(area.height / 2).max(1);
+    let inner_height = area.height.saturating_sub(height * 2).max(1);
+    [
+        Rectangle { height, ..area },
+        Rectangle {
+            y: area.y + (area.height - height) as i16,
+            height,
+            ..area
+        },
+        Rectangle {
+            y: area.y + height as i16,
+            width,
+            height: inner_height,
+            ..area
+        },
+        Rectangle {
+            x: area.x + (area.width - width) as i16,
+            y: area.y + height as i16,
+            width,
+            height: inner_height,
+        },
+    ]
+}
+
+/// Tiled window being dragged and the tile it would be swapped with
+pub struct TileDrag {
+    pub window: WindowId,
+    pub target: Option<WindowId>,
+    /// Override redirect windows forming the outline, unmapped when there is no target
+    edges: [WindowId; 4],
+}
+
+impl TileDrag {
+    pub fn start(display: &mut XDisplay, root: WindowId, window: WindowId) -> Result<Self, Error> {
+        let edges = [(); 4].map(|()| WindowId::from_resource(display.id_allocator().allocate_id()));
+        for edge in edges {
+            display.send_request(&requests::CreateWindow {
+                depth: 0,
+                wid: edge,
+                parent: root,
+                x: 0,
+                y: 0,
+                width: 1,
+                height: 1,
+                border_width: 0,
+                window_class: WindowClass::InputOutput,
+                visual: WindowVisual::CopyFromParent,
+                attributes: WindowCreationAttributes::override_redirect_popup()
+                    .set_background_pixel(OUTLINE_COLOR),
+            })?;
+            panic_hook::destroy_on_panic(edge);
+        }
+
+        Ok(Self {
+            window,
+            target: None,
+            edges,
+        })
+    }
+
+    /// Outline `target` at `area`, or hide the outline if there is no target
+    pub fn set_target(
+        &mut self,
+        display: &mut XDisplay,
+        target: Option<(WindowId, Rectangle)>,
+    ) -> Result<(), Error> {
+        if self.target == target.map(|(window, _)| window) {
+            return Ok(());
+        }
+        self.target = target.map(|(window, _)| window);
+
+        let Some((_, area)) = target else {
+            for window in self.edges {
+                display.send_request(&requests::UnmapWindow { window })?;
+            }
+            return Ok(());
+        };
+        for (window, edge) in self.edges.into_iter().zip(outline_edges(area)) {
+            display.send_request(&requests::ConfigureWindow {
+                window,
+                attributes: ConfigureWindowAttributes::new()
+                    .set_x(edge.x)
+                    .set_y(edge.y)
+                    .set_width(edge.width)
+                    .set_height(edge.height)
+                    .set_stack_mode(StackMode::Above),
+            })?;
+            display.send_request(&requests::MapWindow { window })?;
+        }
+        Ok(())
+    }
+
+    /// Remove the outline, returns the dragged window and the tile it was dropped on
+    pub fn finish(self, display: &mut XDisplay) -> Result<Option<(WindowId, WindowId)>, Error> {
+        for window in self.edges {
+            display.send_request(&requests::DestroyWindow { window })?;
+            panic_hook::forget_on_panic(window);
+        }
+        Ok(self.target.map(|target| (self.window, target)))
+    }
+}
+
+#[test]
+fn drop_target_and_outline() {
+    let [a, b] = [1, 2].map(WindowId::from);
+    let tiles = [
+        (
+            a,
+            Rectangle {
+                x: 0,
+                y: 0,
+                width: 100,
+                height: 50,
+            },
+        ),
+        (
+            b,
+            Rectangle {
+                x: 100,
+                y: 0,
+                width: 100,
+                height: 50,
+            },
+        ),
+    ];
+    assert_eq!(tile_at(&tiles, (99, 49)), Some(a));
+    assert_eq!(tile_at(&tiles, (100, 0)), Some(b));
+    assert_eq!(tile_at(&tiles, (150, 50)), None);
+
+    let rect = |x, y, width, height| Rectangle {
+        x,
+        y,
+        width,
+        height,
+    };
+    assert_eq!(
+        outline_edges(tiles[1].1),
+        [
+            rect(100, 0, 100, 4),
+            rect(100, 46, 100, 4),
+            rect(100, 4, 4, 42),
+            rect(196, 4, 4, 42),
+        ]
+    );
+}
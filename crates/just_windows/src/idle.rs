@@ -0,0 +1,53 @@
+//! Keeping the screen on while a fullscreen window is focused, e.g. a video player
+//!
+//! Idle time of the server is reset with `ForceScreenSaver` every [`RESET_INTERVAL`] instead of
+//! changing screen saver settings, so there is nothing to restore when the window is unfocused or
+//! closed, or when the window manager exits. DPMS timeouts count from the same idle time, so
+//! they are inhibited too.
+
+use just_x11::WindowId;
+use std::time::{Duration, Instant};
+
+/// Shorter than any sensible screen saver timeout
+pub const RESET_INTERVAL: Duration = Duration::from_secs(20);
+
+#[derive(Debug, Default)]
+pub struct IdleInhibitor {
+    /// Window whose `_NET_WM_STATE` was read and whether it has `_NET_WM_STATE_FULLSCREEN`,
+    /// cleared when the property changes
+    pub fullscreen_state: Option<(WindowId, bool)>,
+    last_reset: Option<Instant>,
+}
+
+impl IdleInhibitor {
+    /// Whether idle time should be reset at `now`. Inhibition resets it right away when it
+    /// starts, so focusing a fullscreen window also counts as activity.
+    pub fn should_reset(&mut self, inhibit: bool, now: Instant) -> bool {
+        if !inhibit {
+            self.last_reset = None;
+            return false;
+        }
+        if self
+            .last_reset
+            .is_some_and(|last| now < last + RESET_INTERVAL)
+        {
+            return false;
+        }
+        self.last_reset = Some(now);
+        true
+    }
+}
+
+#[test]
+fn reset_while_inhibited() {
+    let start = Instant::now();
+    let mut inhibitor = IdleInhibitor::default();
+    assert!(!inhibitor.should_reset(false, start));
+    assert!(inhibitor.should_reset(true, start));
+    assert!(!inhibitor.should_reset(true, start + RESET_INTERVAL / 2));
+    assert!(inhibitor.should_reset(true, start + RESET_INTERVAL));
+
+    // Ending inhibition forgets the last reset
+    assert!(!inhibitor.should_reset(false, start + RESET_INTERVAL));
+    assert!(inhibitor.should_reset(true, start + RESET_INTERVAL + Duration::from_secs(1)));
+}
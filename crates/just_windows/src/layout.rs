@@ -1,6 +1,7 @@
 use just_x11::{requests::ConfigureWindowAttributes, Rectangle, WindowId};
+use std::{fmt, str::FromStr};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct PositionedWindow {
     pub window: WindowId,
     pub position: Rectangle,
@@ -19,12 +20,23 @@ impl PositionedWindow {
     }
 }
 
+/// Window passed to a [`Layout`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TiledWindow {
+    pub window: WindowId,
+    /// Minimum `(width, height)` without borders from `WM_NORMAL_HINTS`
+    pub min_size: (u16, u16),
+}
+
 pub trait Layout {
+    /// Windows are never made smaller than their minimum size, even if they would not fit in
+    /// `area` then. Windows sharing the same position are returned from the bottom to the top
+    /// one.
     fn position_windows(
         &self,
         area: Rectangle,
         active_window: Option<WindowId>,
-        windows: &[WindowId],
+        windows: &[TiledWindow],
     ) -> Vec<PositionedWindow>;
 }
 
@@ -40,9 +52,9 @@ impl Layout for SingleWindow {
         &self,
         area: Rectangle,
         active_window: Option<WindowId>,
-        windows: &[WindowId],
+        windows: &[TiledWindow],
     ) -> Vec<PositionedWindow> {
-        if let Some(&window) = windows.get(0) {
+        if let Some(&TiledWindow { window, min_size }) = windows.get(0) {
             let master_positioned = {
                 let border_color = if active_window == Some(window) {
                     self.active_border
@@ -50,8 +62,14 @@ impl Layout for SingleWindow {
                     self.inactive_border
                 };
 
-                let width = area.width - self.border_width * 2 - self.window_pad * 2;
-                let height = (area.height - (self.window_pad * 2)) - self.border_width * 2;
+                let width = area
+                    .width
+                    .saturating_sub(self.border_width * 2 + self.window_pad * 2)
+                    .max(min_size.0);
+                let height = area
+                    .height
+                    .saturating_sub(self.border_width * 2 + self.window_pad * 2)
+                    .max(min_size.1);
                 let x = self.window_pad as i16 + area.x;
                 let y = self.window_pad as i16 + area.y;
 
@@ -74,12 +92,54 @@ impl Layout for SingleWindow {
     }
 }
 
-/// Vertical screen split with master window on the left and rest on the right.
+/// Layout of a workspace, switched with `JustAction::NextLayout`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutKind {
+    /// [`VerticalMasterSplit`] with [`VerticalStack`] on the right
+    MasterStack,
+    /// [`VerticalStack`] of all windows
+    Stack,
+}
+
+impl LayoutKind {
+    pub fn next(self) -> Self {
+        match self {
+            Self::MasterStack => Self::Stack,
+            Self::Stack => Self::MasterStack,
+        }
+    }
+}
+
+impl fmt::Display for LayoutKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MasterStack => write!(f, "master-stack"),
+            Self::Stack => write!(f, "stack"),
+        }
+    }
+}
+
+impl FromStr for LayoutKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "master-stack" => Ok(Self::MasterStack),
+            "stack" => Ok(Self::Stack),
+            _ => Err(format!("Unknown layout '{}'", s)),
+        }
+    }
+}
+
+/// Vertical screen split with master window on the left and rest on the right. The split is
+/// moved so both sides fit their minimum widths, the master one is preferred if they can't.
 pub struct VerticalMasterSplit {
     pub border_width: u16,
     pub window_pad: u16,
     pub active_border: u32,
     pub inactive_border: u32,
+    /// Part of the width taken by the master window, from `0.0` to `1.0`
+    pub ratio: f32,
     pub right: Box<dyn Layout>,
 }
 
@@ -88,7 +148,7 @@ impl Layout for VerticalMasterSplit {
         &self,
         area: Rectangle,
         active_window: Option<WindowId>,
-        windows: &[WindowId],
+        windows: &[TiledWindow],
     ) -> Vec<PositionedWindow> {
         if let Some((&master_window, rest_windows)) = windows.split_first() {
             if rest_windows.is_empty() {
@@ -100,6 +160,19 @@ impl Layout for VerticalMasterSplit {
                 }
                 .position_windows(area, active_window, &[master_window])
             } else {
+                // Space taken by borders and pads of each side around the window, see how
+                // areas of both sides are computed below
+                let decorations = (self.border_width * 2 + self.window_pad * 2) as i32
+                    - self.window_pad as i32 / 2;
+                let stack_min_width = rest_windows
+                    .iter()
+                    .map(|window| window.min_size.0)
+                    .max()
+                    .unwrap_or(0);
+                let split = ((area.width as f32 * self.ratio).round() as i32)
+                    .min(area.width as i32 - decorations - stack_min_width as i32)
+                    .max(master_window.min_size.0 as i32 + decorations)
+                    .clamp(0, area.width as i32) as u16;
                 let left = SingleWindow {
                     border_width: self.border_width,
                     window_pad: self.window_pad,
@@ -110,7 +183,7 @@ impl Layout for VerticalMasterSplit {
                     Rectangle {
                         x: area.x,
                         y: area.y,
-                        width: area.width / 2 + self.window_pad / 2,
+                        width: split + self.window_pad / 2,
                         height: area.height,
                     },
                     active_window,
@@ -119,9 +192,9 @@ impl Layout for VerticalMasterSplit {
 
                 let right = self.right.position_windows(
                     Rectangle {
-                        x: area.x + (area.width as i16 / 2 - self.window_pad as i16 / 2),
+                        x: area.x + (split as i16 - self.window_pad as i16 / 2),
                         y: area.y,
-                        width: area.width / 2 + self.window_pad / 2,
+                        width: (area.width - split) + self.window_pad / 2,
                         height: area.height,
                     },
                     active_window,
@@ -140,6 +213,8 @@ impl Layout for VerticalMasterSplit {
     }
 }
 
+/// Windows that don't fit in the stack at their minimum heights are pushed to a hidden tail
+/// sharing the last slot, with the active one on top
 #[derive(Clone)]
 pub struct VerticalStack {
     pub border_width: u16,
@@ -155,57 +230,157 @@ impl Layout for VerticalStack {
         &self,
         area: Rectangle,
         active_window: Option<WindowId>,
-        windows: &[WindowId],
+        windows: &[TiledWindow],
     ) -> Vec<PositionedWindow> {
-        let window_count = windows.len() as u16;
+        let min_heights = windows
+            .iter()
+            .map(|window| window.min_size.1)
+            .collect::<Vec<_>>();
+        let heights = stack_heights(
+            area.height.saturating_sub(self.window_pad),
+            self.window_pad + self.border_width * 2,
+            &min_heights,
+        );
 
         let x = self.window_pad as i16 + area.x;
-        let width = area.width - self.border_width * 2 - self.window_pad * 2;
-        let height = (area.height - (self.window_pad * (window_count + 1))) / window_count
-            - self.border_width * 2;
+        let width = area
+            .width
+            .saturating_sub(self.border_width * 2 + self.window_pad * 2);
+
+        let (shown, tail) = windows.split_at(heights.len().saturating_sub(1));
+        let mut tail = tail.to_vec();
+        // Stable sort keeps the order of the rest
+        tail.sort_by_key(|window| active_window == Some(window.window));
 
-        let ret = windows
+        let mut y = self.window_pad as i16 + area.y;
+        let slots = heights.iter().map(|&height| {
+            let slot = (y, height);
+            y += height as i16 + 2 * self.border_width as i16 + self.window_pad as i16;
+            slot
+        });
+        let slots = slots.collect::<Vec<_>>();
+
+        shown
             .iter()
-            .enumerate()
-            .map(|(idx, &window)| {
+            .zip(slots.iter())
+            .chain(tail.iter().map(|window| (window, slots.last().unwrap())))
+            .map(|(&TiledWindow { window, min_size }, &(y, height))| {
                 let border_color = if active_window == Some(window) {
                     self.active_border
                 } else {
                     self.inactive_border
                 };
 
-                let y = (self.window_pad as i16 * 1 + area.y)
-                    + idx as i16
-                        * (height as i16 + 2 * self.border_width as i16 + self.window_pad as i16);
-
-                let effective_height = if idx + 1 == window_count as usize {
-                    // We lose some space due to integer division so if we're at the last (bottom)
-                    // window we override height to take all remaining space. The difference in
-                    // height is invisible (by me) until you stack more than 10 windows which
-                    // is unlikely to happan in real use scenario.
-                    (area.height as i16 - y + area.y
-                        - self.border_width as i16 * 2
-                        - self.window_pad as i16) as u16
-                } else {
-                    height
-                };
-
-                let ret = PositionedWindow {
+                PositionedWindow {
                     window,
                     position: Rectangle {
                         x,
                         y,
-                        width,
-                        height: effective_height,
+                        width: width.max(min_size.0),
+                        height: height.max(min_size.1),
                     },
                     border_color,
                     border_width: self.border_width,
-                };
-
-                ret
+                }
             })
-            .collect();
+            .collect()
+    }
+}
 
-        ret
+/// Heights of stack slots filling `available` space with `spacing` taken after each slot. Slots
+/// get equal heights unless some window needs more. Windows that don't fit with their
+/// `min_heights` share the last slot, so fewer heights than windows can be returned.
+fn stack_heights(available: u16, spacing: u16, min_heights: &[u16]) -> Vec<u16> {
+    let (available, spacing) = (available as u32, spacing as u32);
+    for slot_count in (1..=min_heights.len()).rev() {
+        let (shown, tail) = min_heights.split_at(slot_count - 1);
+        let mut mins = shown.iter().map(|&min| min as u32).collect::<Vec<_>>();
+        mins.push(tail.iter().copied().max().unwrap_or(0) as u32);
+
+        let space = available.saturating_sub(slot_count as u32 * spacing);
+        if mins.iter().sum::<u32>() > space && slot_count > 1 {
+            continue;
+        }
+
+        // Windows that need more than an equal share get their minimum, rest is shared again
+        let mut heights = vec![0; slot_count];
+        let mut remaining = space;
+        let mut free = (0..slot_count).collect::<Vec<_>>();
+        loop {
+            let share = remaining / free.len() as u32;
+            let (fixed, rest): (Vec<usize>, Vec<usize>) =
+                free.iter().partition(|&&idx| mins[idx] > share);
+            if fixed.is_empty() {
+                for &idx in &rest {
+                    heights[idx] = share;
+                }
+                // Integer division leaves few pixels that go to the last slot
+                heights[*rest.last().unwrap()] += remaining - share * rest.len() as u32;
+                break;
+            }
+            for idx in fixed {
+                heights[idx] = mins[idx];
+                remaining = remaining.saturating_sub(mins[idx]);
+            }
+            if rest.is_empty() {
+                break;
+            }
+            free = rest;
+        }
+        return heights.into_iter().map(|height| height as u16).collect();
     }
+    Vec::new()
+}
+
+#[test]
+fn stack_respects_min_heights() {
+    assert_eq!(stack_heights(300, 10, &[0, 0, 0]), vec![90, 90, 90]);
+    assert_eq!(stack_heights(301, 10, &[0, 0, 0]), vec![90, 90, 91]);
+    assert_eq!(stack_heights(300, 10, &[200, 0, 0]), vec![200, 35, 35]);
+    // Last three windows share the slot, it must fit the tallest of them
+    assert_eq!(stack_heights(300, 10, &[100, 50, 150, 60]), vec![130, 150]);
+    assert_eq!(stack_heights(300, 10, &[400, 50]), vec![400]);
+    assert_eq!(stack_heights(300, 10, &[]), vec![]);
+
+    let [a, b, c] = [1, 2, 3].map(WindowId::from);
+    let stack = VerticalStack {
+        border_width: 0,
+        window_pad: 10,
+        active_border: 1,
+        inactive_border: 0,
+    };
+    let area = Rectangle {
+        x: 0,
+        y: 0,
+        width: 200,
+        height: 310,
+    };
+    let windows = [a, b, c].map(|window| TiledWindow {
+        window,
+        min_size: (250, 100),
+    });
+    let positioned = stack.position_windows(area, Some(b), &windows);
+    let summary = positioned
+        .iter()
+        .map(|p| (p.window, p.position.y, p.position.width, p.position.height))
+        .collect::<Vec<_>>();
+    // Active window is raised above the rest of the hidden tail
+    assert_eq!(
+        summary,
+        vec![(a, 10, 250, 140), (c, 160, 250, 140), (b, 160, 250, 140)]
+    );
+
+    let split = VerticalMasterSplit {
+        border_width: 0,
+        window_pad: 10,
+        active_border: 1,
+        inactive_border: 0,
+        ratio: 0.5,
+        right: Box::new(stack),
+    };
+    let windows =
+        [(a, (250, 0)), (b, (0, 0))].map(|(window, min_size)| TiledWindow { window, min_size });
+    let positioned = split.position_windows(Rectangle { width: 400, ..area }, None, &windows);
+    assert_eq!(positioned[0].position.width, 250);
+    assert_eq!(positioned[1].position.x, 270);
 }
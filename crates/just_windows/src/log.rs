@@ -0,0 +1,107 @@
+//! Lines on stderr with a UTC timestamp, level, event name and `key=value` fields, e.g.
+//!
+//! ```text
+//! 2026-10-15T09:30:12.345Z justwindows error spawn-failed command="sh -c picom" error="No such file or directory (os error 2)"
+//! ```
+//!
+//! Values with spaces, quotes or `=` are quoted, so lines can be split without knowing the
+//! fields.
+
+use std::{
+    fmt::{self, Display},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Info,
+    Warn,
+    Error,
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Level::Info => "info",
+            Level::Warn => "warn",
+            Level::Error => "error",
+        })
+    }
+}
+
+pub fn log(level: Level, event: &str, fields: &[(&str, &dyn Display)]) {
+    eprintln!("{}", format_line(SystemTime::now(), level, event, fields));
+}
+
+fn format_line(
+    time: SystemTime,
+    level: Level,
+    event: &str,
+    fields: &[(&str, &dyn Display)],
+) -> String {
+    let mut line = format!("{} {} {} {}", Timestamp(time), crate::WM_NAME, level, event);
+    for (key, value) in fields {
+        let value = value.to_string();
+        if value.is_empty() || value.contains([' ', '"', '=', '\n']) {
+            line.push_str(&format!(" {}={:?}", key, value));
+        } else {
+            line.push_str(&format!(" {}={}", key, value));
+        }
+    }
+    line
+}
+
+/// RFC 3339 in UTC with milliseconds
+struct Timestamp(SystemTime);
+
+impl Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let since_epoch = self.0.duration_since(UNIX_EPOCH).unwrap_or_default();
+        let seconds = since_epoch.as_secs();
+        let (year, month, day) = civil_from_days((seconds / 86400) as i64);
+        let seconds_of_day = seconds % 86400;
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+            year,
+            month,
+            day,
+            seconds_of_day / 3600,
+            seconds_of_day / 60 % 60,
+            seconds_of_day % 60,
+            since_epoch.subsec_millis()
+        )
+    }
+}
+
+/// Date of days since 1970-01-01, from <http://howardhinnant.github.io/date_algorithms.html>
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[test]
+fn log_line_format() {
+    use std::time::Duration;
+
+    let time = UNIX_EPOCH + Duration::from_millis(1_792_056_612_345);
+    assert_eq!(
+        format_line(
+            time,
+            Level::Error,
+            "spawn-failed",
+            &[("command", &"sh -c picom"), ("attempt", &3), ("note", &"")]
+        ),
+        r#"2026-10-15T09:30:12.345Z justwindows error spawn-failed command="sh -c picom" attempt=3 note="""#
+    );
+    assert_eq!(civil_from_days(0), (1970, 1, 1));
+    assert_eq!(civil_from_days(11016), (2000, 2, 29));
+}
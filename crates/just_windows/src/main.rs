@@ -5,20 +5,89 @@
     clippy::identity_op
 )]
 
-use crate::layout::{Layout, VerticalMasterSplit, VerticalStack};
+use crate::{
+    children::{ChildCommand, Children, Respawn},
+    chord::{Chord, PendingChord, CHORD_TIMEOUT},
+    corners::RoundedCorners,
+    drag::TileDrag,
+    idle::IdleInhibitor,
+    layout::{
+        Layout, LayoutKind, PositionedWindow, TiledWindow, VerticalMasterSplit, VerticalStack,
+    },
+    overview::{Overview, OverviewEntry},
+    persist::SavedWorkspace,
+    placement::PlacementPolicy,
+    popup::{Notification, PopupAnchor, TextPopup},
+    selection::{Acquire, WmSelection},
+    state::{Placement, WindowPhase, Windows},
+    stats::HandlerTimes,
+    status::{FocusedStatus, Status, StatusOutput, StatusTarget, WorkspaceStatus},
+    switcher::{Switcher, SwitcherEntry, Thumbnail},
+};
 use just_x11::{
+    atoms::AtomId,
+    connection::DisplayVar,
     error::Error,
-    events::EventType,
-    events::SomeEvent,
+    events::{EventType, MappingNotifyRequest, PropertyNotifyState, SomeEvent, StackMode},
+    extensions::shape,
     keysym::KeySym,
-    requests::{self, ConfigureWindowAttributes, GrabMode, KeyCode, KeyModifier},
+    replies::{self, PropertyValue, String8},
+    requests::{
+        self, AllowEventsMode, ChangePropertyFormat, ConfigureWindowAttributes,
+        ForceScreenSaverMode, GrabMode, KeyCode, KeyModifier, KillClientResource, PointerEventMask,
+        Timestamp,
+    },
     xerror::SomeError,
-    Rectangle, WindowId, XDisplay,
+    OrNone, Rectangle, WindowId, XDisplay,
+};
+use just_x11_simple::{
+    display as x11_display,
+    keys::{KeySymColumn, KeySymbols},
+    monitors::Monitors,
+    WindowKill, WindowManagerHintsFlags, X11Connection,
+};
+use std::{
+    collections::HashMap,
+    env, fs, mem,
+    os::unix::fs::PermissionsExt,
+    path::PathBuf,
+    process,
+    str::FromStr,
+    time::{Duration, Instant},
 };
-use just_x11_simple::{keys::KeySymbols, X11Connection};
-use std::{collections::HashMap, process};
 
+mod children;
+mod chord;
+mod corners;
+mod drag;
+mod idle;
 mod layout;
+mod log;
+mod overview;
+mod persist;
+mod placement;
+mod popup;
+mod priority;
+mod selection;
+mod state;
+mod stats;
+mod status;
+mod switcher;
+mod workspaces;
+
+const WM_NAME: &str = "justwindows";
+const URGENT_BORDER: u32 = 0xfa4e4e;
+/// `AnyButton` of `GrabButton`
+const ANY_BUTTON: u8 = 0;
+/// `AnyModifier` of `GrabButton`
+const ANY_MODIFIER: u16 = 0x8000;
+/// How long clients asked to close a window have before their connection is killed
+const KILL_TIMEOUT: Duration = Duration::from_secs(5);
+const DEFAULT_MASTER_RATIO: f32 = 0.5;
+/// Change of the master ratio with [`JustAction::GrowMaster`] and [`JustAction::ShrinkMaster`]
+const MASTER_RATIO_STEP: f32 = 0.05;
+const MIN_MASTER_RATIO: f32 = 0.1;
+const MAX_MASTER_RATIO: f32 = 0.9;
 
 // TODO: FocusNext, FocusPrevious
 /// Abstract action type
@@ -26,17 +95,110 @@ mod layout;
 enum JustAction {
     KillActive,
     Term,
+    /// Assign numbered mark to the active window
+    Mark(u8),
+    JumpToMark(u8),
+    ToggleGameMode,
+    /// Hide all windows of the active workspace, or bring them back, see
+    /// [`JustWindows::toggle_show_desktop`]
+    ToggleShowDesktop,
+    /// Change master ratio of the active workspace by [`MASTER_RATIO_STEP`]
+    GrowMaster,
+    ShrinkMaster,
+    /// Switch the active workspace to the next [`LayoutKind`]
+    NextLayout,
+    /// Show workspace with given number, see [`JustWindows::show_numbered_workspace`]
+    ShowWorkspace(u8),
+    /// Move the active window to workspace with given number
+    MoveToWorkspace(u8),
+    /// Wait for one more key of the chord with given index, see [`KeyBindings::add_chord`]
+    Chord(usize),
+    /// Show the window switcher, it stays open while given modifiers are held
+    Switcher(KeyModifier),
+    /// Show thumbnails of all windows of the active workspace, or close them, see [`overview`]
+    ToggleOverview,
+}
+
+/// Active window shown fullscreen without borders, see [`JustWindows::toggle_game_mode`]
+#[derive(Debug, Clone, Copy)]
+struct GameMode {
+    /// Window in [`Placement::Fullscreen`]
+    window: WindowId,
+    /// Process id and its niceness before it was changed
+    original_priority: Option<(u32, i32)>,
+}
+
+const USAGE: &str =
+    "Usage: justwindows [--display DISPLAY] [--replace] [--nested] [--status TARGET]
+
+Options:
+    --display DISPLAY  Manage DISPLAY instead of $DISPLAY
+    --replace          Replace window manager that is already running
+    --nested           Run inside of a nested server like Xephyr, requires --display
+                       different from $DISPLAY and skips autostart
+    --status TARGET    Write workspaces, focused window and urgency as line delimited JSON
+                       to stdout if TARGET is -, or to clients of unix socket at TARGET";
+
+#[derive(Debug, Default)]
+struct Args {
+    display: Option<String>,
+    replace: bool,
+    nested: bool,
+    status: Option<StatusTarget>,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut res = Self::default();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--display" => {
+                    res.display = Some(args.next().ok_or("--display expects an argument")?);
+                }
+                "--replace" => res.replace = true,
+                "--nested" => res.nested = true,
+                "--status" => {
+                    let target = args.next().ok_or("--status expects an argument")?;
+                    res.status = Some(StatusTarget::parse(&target));
+                }
+                "--help" | "-h" => return Err(USAGE.to_string()),
+                _ => return Err(format!("Unknown argument '{}'\n\n{}", arg, USAGE)),
+            }
+        }
+
+        if res.nested {
+            match &res.display {
+                None => return Err("--nested requires --display".to_string()),
+                Some(display) if env::var("DISPLAY").is_ok_and(|env| env == *display) => {
+                    return Err(format!(
+                        "--nested display '{}' is the same as $DISPLAY",
+                        display
+                    ))
+                }
+                Some(_) => {}
+            }
+        }
+
+        Ok(res)
+    }
 }
 
 struct KeyBindings {
-    bindings: HashMap<KeyCode, JustAction>,
+    /// Bindings as requested by the user, kept so keycodes can be recomputed after remapping
+    key_syms: Vec<(WindowId, KeySym, KeyModifier, JustAction)>,
+    grabbed: Vec<(WindowId, KeyCode, KeyModifier)>,
+    bindings: HashMap<(KeyCode, KeyModifier), JustAction>,
+    chords: Vec<Chord>,
     key_symbols: KeySymbols,
 }
 
 impl KeyBindings {
     fn new(key_symbols: KeySymbols) -> Self {
         Self {
+            key_syms: Vec::new(),
+            grabbed: Vec::new(),
             bindings: HashMap::new(),
+            chords: Vec::new(),
             key_symbols,
         }
     }
@@ -46,6 +208,19 @@ impl KeyBindings {
         display: &mut XDisplay,
         root: WindowId,
         sym: KeySym,
+        modifiers: KeyModifier,
+        event: JustAction,
+    ) -> Result<(), Error> {
+        self.key_syms.push((root, sym, modifiers, event));
+        self.grab_key_sym(display, root, sym, modifiers, event)
+    }
+
+    fn grab_key_sym(
+        &mut self,
+        display: &mut XDisplay,
+        root: WindowId,
+        sym: KeySym,
+        modifiers: KeyModifier,
         event: JustAction,
     ) -> Result<(), Error> {
         let key_codes = self.key_symbols.get_keycodes(sym);
@@ -53,93 +228,234 @@ impl KeyBindings {
             display.send_request(&requests::GrabKey {
                 owner_events: false,
                 grab_window: root,
-                modifiers: KeyModifier::CONTROL,
+                modifiers,
                 key,
                 pointer_mode: GrabMode::Asynchronous,
                 keyboard_mode: GrabMode::Asynchronous,
             })?;
-            self.bindings.insert(key, event);
+            self.grabbed.push((root, key, modifiers));
+            self.bindings.insert((key, modifiers), event);
+        }
+
+        Ok(())
+    }
+
+    /// Ungrab stale keycodes and grab them again using fresh keyboard mapping
+    fn regrab(&mut self, display: &mut XDisplay) -> Result<(), Error> {
+        for (grab_window, key, modifiers) in self.grabbed.drain(..) {
+            display.send_request(&requests::UngrabKey {
+                key,
+                grab_window,
+                modifiers,
+            })?;
+        }
+        self.bindings.clear();
+        self.key_symbols = KeySymbols::new(display)?;
+
+        for (root, sym, modifiers, event) in self.key_syms.clone() {
+            self.grab_key_sym(display, root, sym, modifiers, event)?;
         }
 
         Ok(())
     }
 
-    fn get_action(&self, key_code: KeyCode) -> Option<JustAction> {
-        self.bindings.get(&key_code).copied()
+    fn get_action(&self, key_code: KeyCode, state: KeyModifier) -> Option<JustAction> {
+        // Ignore locks and buttons that are also part of the state
+        let modifiers = [
+            KeyModifier::SHIFT,
+            KeyModifier::CONTROL,
+            KeyModifier::MOD_1,
+            KeyModifier::MOD_3,
+            KeyModifier::MOD_4,
+            KeyModifier::MOD_5,
+        ]
+        .into_iter()
+        .filter(|modifier| state.has(*modifier))
+        .fold(KeyModifier::EMPTY_MASK, |acc, modifier| acc | modifier);
+        self.bindings.get(&(key_code, modifiers)).copied()
+    }
+
+    /// Register `chord`, bind the returned action to its prefix with [`Self::bind_key_sym`]
+    fn add_chord(&mut self, chord: Chord) -> JustAction {
+        self.chords.push(chord);
+        JustAction::Chord(self.chords.len() - 1)
     }
-}
 
-// struct WindowStack {
+    /// Action of the key following the prefix of chord `chord`, modifiers are not considered
+    fn chord_action(&self, chord: usize, key_code: KeyCode) -> Option<JustAction> {
+        self.chords[chord]
+            .keys
+            .iter()
+            .find(|(sym, _)| self.key_symbols.get_keycodes(*sym).contains(&key_code))
+            .map(|(_, action)| *action)
+    }
+}
 
-// }
+/// Windows hidden by [`JustAction::ToggleShowDesktop`]
+#[derive(Debug, Clone)]
+struct ShownDesktop {
+    /// Placements to restore
+    windows: Vec<(WindowId, Placement)>,
+    /// Active window before the desktop was shown
+    active_window: Option<WindowId>,
+}
 
 struct Workspace {
-    layout: Box<dyn Layout>,
-    windows: Vec<WindowId>,
+    layout: LayoutKind,
+    /// Part of the width taken by the master window of [`LayoutKind::MasterStack`]
+    master_ratio: f32,
+    shown_desktop: Option<ShownDesktop>,
 }
 
 impl Workspace {
     pub fn new() -> Self {
-        let layout = {
-            let border_width = 3;
-            let window_pad = 10;
-            let inactive_border = 0xd0d0d0;
-            let active_border = 0x4eb4fa;
+        Self {
+            layout: LayoutKind::MasterStack,
+            master_ratio: DEFAULT_MASTER_RATIO,
+            shown_desktop: None,
+        }
+    }
+
+    fn layout(&self) -> Box<dyn Layout> {
+        let border_width = 3;
+        let window_pad = 10;
+        let inactive_border = 0xd0d0d0;
+        let active_border = 0x4eb4fa;
 
-            VerticalMasterSplit {
+        let stack = VerticalStack {
+            border_width,
+            window_pad,
+            inactive_border,
+            active_border,
+        };
+        match self.layout {
+            LayoutKind::MasterStack => Box::new(VerticalMasterSplit {
                 border_width,
                 window_pad,
                 inactive_border,
                 active_border,
-                right: Box::new(VerticalStack {
-                    border_width,
-                    window_pad,
-                    inactive_border,
-                    active_border,
-                }),
-            }
-        };
-
-        Self::with_layout(Box::new(layout))
-    }
-
-    pub fn with_layout(layout: Box<dyn Layout>) -> Self {
-        Self {
-            layout,
-            windows: Vec::new(),
+                ratio: self.master_ratio,
+                right: Box::new(stack),
+            }),
+            LayoutKind::Stack => Box::new(stack),
         }
     }
 }
 
+/// Monitor showing a workspace
 #[derive(Debug, Clone)]
 struct WmScreen {
     size: Rectangle,
     workspace_idx: usize,
     root: WindowId,
+    /// Name of the monitor, used to give its workspace back when it is plugged in again
+    monitor: String,
+}
+
+/// Names and geometries of monitors, whole screen if there are none
+fn monitor_geometries(monitors: &Monitors, screen_size: Rectangle) -> Vec<(String, Rectangle)> {
+    let geometries = monitors
+        .monitors()
+        .iter()
+        .map(|monitor| (monitor.name.clone(), monitor.geometry))
+        .collect::<Vec<_>>();
+    if geometries.is_empty() {
+        vec![(String::new(), screen_size)]
+    } else {
+        geometries
+    }
+}
+
+/// Workspace of an unplugged monitor, its windows were moved to a remaining monitor
+#[derive(Debug, Clone)]
+struct DetachedWorkspace {
+    workspace_idx: usize,
+    windows: Vec<WindowId>,
 }
 
 struct JustWindows {
     conn: X11Connection,
-    managed_windows: Vec<WindowId>,
+    windows: Windows,
     active_window: Option<WindowId>,
     bindings: KeyBindings,
 
+    game_mode: Option<GameMode>,
+    /// Niceness given to the game mode client, from `JUSTWINDOWS_GAME_MODE_NICE`
+    game_mode_nice: Option<i32>,
+    /// From `JUSTWINDOWS_CORNER_RADIUS`, `None` if unset or the server has no SHAPE extension
+    rounded_corners: Option<RoundedCorners>,
+    /// Where floating windows without requested position are mapped, from `JUSTWINDOWS_PLACEMENT`
+    placement_policy: PlacementPolicy,
+    /// Raise windows when clicked, from `JUSTWINDOWS_CLICK_TO_RAISE`
+    click_to_raise: bool,
+    /// Keeps the screen on while a fullscreen window is focused, `None` if disabled with
+    /// `JUSTWINDOWS_INHIBIT_IDLE=0`
+    idle_inhibitor: Option<IdleInhibitor>,
+    /// Prefix of a chord was pressed and the keyboard is grabbed until the next key
+    pending_chord: Option<PendingChord>,
+    /// Open window switcher, the keyboard is grabbed until it is closed
+    switcher: Option<Switcher>,
+    /// Open workspace overview, the keyboard is grabbed until it is closed
+    overview: Option<Overview>,
+    /// Tiled window dragged with the modifier held, the pointer is grabbed until it is dropped
+    drag: Option<TileDrag>,
+    /// Managed windows from the most recently focused, order of the window switcher
+    focus_history: Vec<WindowId>,
+    /// Windows asked to close, their clients are killed if they still exist after the deadline
+    pending_kills: Vec<(WindowId, Instant)>,
+
     screens: Vec<WmScreen>,
+    /// Monitors of each X screen, each of them is a [`WmScreen`]
+    monitors: Vec<Monitors>,
     workspaces: Vec<Workspace>,
+    /// Workspaces of unplugged monitors by monitor name
+    detached_workspaces: HashMap<String, DetachedWorkspace>,
+    /// Most workspaces created on use, from `JUSTWINDOWS_WORKSPACES`
+    workspace_limit: usize,
 
     active_workspace: usize,
 
-    /// Processes that we have spawned.
-    /// We use it to clean up zombie children as it's a bit more clean and cross-platform than
-    /// catching sigchld signal.
-    running_children: Vec<process::Child>,
+    /// Processes that we have spawned, including autostart entries that are respawned
+    children: Children,
+    /// Shown when spawning fails, hidden after [`popup::NOTIFICATION_TIMEOUT`]
+    notification: Option<Notification>,
+
+    /// Window used for `_NET_SUPPORTING_WM_CHECK`, kept for the lifetime of the window manager
+    _wm_check_window: WindowId,
+
+    /// `WM_Sn` selections of all screens, window manager exits when any of them is taken
+    wm_selections: Vec<WmSelection>,
+
+    /// From `--status`, disabled if stdout gets closed
+    status: Option<StatusOutput>,
+    /// Title of the focused window, fetched again when it changes
+    focused_title: Option<(WindowId, Option<String>)>,
+    /// Last value of `_JUSTWINDOWS_WORKSPACES`, see [`JustWindows::publish_workspaces`]
+    published_workspaces: Option<String>,
+    /// Last value of `_NET_CLIENT_LIST`, see [`JustWindows::publish_client_list`]
+    published_client_list: Option<Vec<WindowId>>,
+    /// Last number of desktops and current desktop, see [`JustWindows::publish_desktops`]
+    published_desktops: Option<(usize, usize)>,
+    /// Last `_NET_WM_DESKTOP` of managed windows
+    published_window_desktops: HashMap<WindowId, usize>,
+    /// From `JUSTWINDOWS_TIME_HANDLERS`, reported by `query stats`
+    handler_times: Option<HandlerTimes>,
 }
 
 impl JustWindows {
-    fn setup() -> Result<Self, Error> {
-        let mut conn = X11Connection::new(XDisplay::open()?);
+    fn setup(display: DisplayVar, args: &Args) -> Result<Self, Error> {
+        let display_name = display.to_string();
+        let mut conn = X11Connection::new(match x11_display::connect(display) {
+            Ok(display) => display,
+            Err(err) => {
+                eprintln!("justwindows: error: {}", err);
+                process::exit(1);
+            }
+        });
+        // Releases grabs and focus even if a panic does not end the process right away
+        conn.install_panic_hook()?;
 
-        // FIXME: Get this with randr
+        // Replaced with monitors once events are selected on the roots
         let screens = conn
             .display()
             .screens()
@@ -154,14 +470,94 @@ impl JustWindows {
                 },
                 root: screen.root,
                 workspace_idx: idx,
+                monitor: String::new(),
             })
             .collect::<Vec<_>>();
         dbg!(&screens);
 
-        let workspaces = screens.iter().map(|_| Workspace::new()).collect::<Vec<_>>();
+        let mut wm_selections = Vec::new();
+        for (idx, screen) in screens.iter().enumerate() {
+            match selection::acquire(&mut conn, idx, screen.root, args.replace)? {
+                Acquire::Acquired(selection) => wm_selections.push(selection),
+                Acquire::OwnedByOther(owner) => {
+                    eprintln!(
+                    "justwindows: error: Other window manager is running (WM_S{} owner: {:?}), use --replace to replace it",
+                    idx, owner
+                );
+                    process::exit(1);
+                }
+            }
+        }
 
         let key_symbols = KeySymbols::new(conn.display_mut())?;
         let mut bindings = KeyBindings::new(key_symbols);
+        // Held with all bindings, `Shift` is added to it when marking windows
+        let modifier = match env::var("JUSTWINDOWS_MODIFIER") {
+            Ok(modifier) => KeyModifier::from_str(&modifier).unwrap_or_else(|err| {
+                eprintln!("justwindows: {}, using Control", err);
+                KeyModifier::CONTROL
+            }),
+            Err(_) => KeyModifier::CONTROL,
+        };
+
+        let keypad = [
+            KeySym::KP_1,
+            KeySym::KP_2,
+            KeySym::KP_3,
+            KeySym::KP_4,
+            KeySym::KP_5,
+            KeySym::KP_6,
+            KeySym::KP_7,
+            KeySym::KP_8,
+            KeySym::KP_9,
+        ];
+        let digits = [
+            KeySym::DIGIT_1,
+            KeySym::DIGIT_2,
+            KeySym::DIGIT_3,
+            KeySym::DIGIT_4,
+            KeySym::DIGIT_5,
+            KeySym::DIGIT_6,
+            KeySym::DIGIT_7,
+            KeySym::DIGIT_8,
+            KeySym::DIGIT_9,
+        ];
+        let mark_chord = bindings.add_chord(Chord {
+            hint: vec![
+                "1-9     mark active window".to_string(),
+                "Escape  cancel".to_string(),
+            ],
+            keys: (1..)
+                .zip(digits)
+                .chain((1..).zip(keypad))
+                .map(|(mark, sym)| (sym, JustAction::Mark(mark)))
+                .collect(),
+        });
+        // `0` is the tenth workspace, like on the number row
+        let workspace_keys = (1..)
+            .zip(digits.into_iter().chain([KeySym::DIGIT_0]))
+            .chain((1..).zip(keypad.into_iter().chain([KeySym::KP_0])))
+            .collect::<Vec<(u8, KeySym)>>();
+        let show_workspace_chord = bindings.add_chord(Chord {
+            hint: vec![
+                "1-9, 0  show workspace".to_string(),
+                "Escape  cancel".to_string(),
+            ],
+            keys: workspace_keys
+                .iter()
+                .map(|&(number, sym)| (sym, JustAction::ShowWorkspace(number)))
+                .collect(),
+        });
+        let move_to_workspace_chord = bindings.add_chord(Chord {
+            hint: vec![
+                "1-9, 0  move active window to workspace".to_string(),
+                "Escape  cancel".to_string(),
+            ],
+            keys: workspace_keys
+                .iter()
+                .map(|&(number, sym)| (sym, JustAction::MoveToWorkspace(number)))
+                .collect(),
+        });
 
         for screen in &screens {
             conn.select_input(
@@ -177,174 +573,2049 @@ impl JustWindows {
                 conn.display_mut(),
                 screen.root,
                 KeySym::q,
+                modifier,
                 JustAction::KillActive,
             )?;
             bindings.bind_key_sym(
                 conn.display_mut(),
                 screen.root,
                 KeySym::Return,
+                modifier,
                 JustAction::Term,
             )?;
+            bindings.bind_key_sym(
+                conn.display_mut(),
+                screen.root,
+                KeySym::g,
+                modifier,
+                JustAction::ToggleGameMode,
+            )?;
+            bindings.bind_key_sym(
+                conn.display_mut(),
+                screen.root,
+                KeySym::m,
+                modifier,
+                mark_chord,
+            )?;
+            bindings.bind_key_sym(
+                conn.display_mut(),
+                screen.root,
+                KeySym::w,
+                modifier,
+                show_workspace_chord,
+            )?;
+            bindings.bind_key_sym(
+                conn.display_mut(),
+                screen.root,
+                KeySym::w,
+                modifier | KeyModifier::SHIFT,
+                move_to_workspace_chord,
+            )?;
+            bindings.bind_key_sym(
+                conn.display_mut(),
+                screen.root,
+                KeySym::l,
+                modifier,
+                JustAction::GrowMaster,
+            )?;
+            bindings.bind_key_sym(
+                conn.display_mut(),
+                screen.root,
+                KeySym::h,
+                modifier,
+                JustAction::ShrinkMaster,
+            )?;
+            bindings.bind_key_sym(
+                conn.display_mut(),
+                screen.root,
+                KeySym::space,
+                modifier,
+                JustAction::NextLayout,
+            )?;
+            bindings.bind_key_sym(
+                conn.display_mut(),
+                screen.root,
+                KeySym::d,
+                modifier,
+                JustAction::ToggleShowDesktop,
+            )?;
+            bindings.bind_key_sym(
+                conn.display_mut(),
+                screen.root,
+                KeySym::Tab,
+                modifier,
+                JustAction::Switcher(modifier),
+            )?;
+            bindings.bind_key_sym(
+                conn.display_mut(),
+                screen.root,
+                KeySym::e,
+                modifier,
+                JustAction::ToggleOverview,
+            )?;
+            conn.display_mut().send_request(&requests::GrabButton {
+                owner_events: false,
+                grab_window: screen.root,
+                event_mask: PointerEventMask::BUTTON_PRESS
+                    | PointerEventMask::BUTTON_RELEASE
+                    | PointerEventMask::POINTER_MOTION,
+                pointer_mode: GrabMode::Asynchronous,
+                keyboard_mode: GrabMode::Asynchronous,
+                confine_to: OrNone::none(),
+                cursor: OrNone::none(),
+                button: drag::DRAG_BUTTON,
+                modifiers: modifier.raw(),
+            })?;
+
+            for (mark, sym) in (1..).zip(digits) {
+                bindings.bind_key_sym(
+                    conn.display_mut(),
+                    screen.root,
+                    sym,
+                    modifier | KeyModifier::SHIFT,
+                    JustAction::Mark(mark),
+                )?;
+                bindings.bind_key_sym(
+                    conn.display_mut(),
+                    screen.root,
+                    sym,
+                    modifier,
+                    JustAction::JumpToMark(mark),
+                )?;
+            }
+            for (mark, sym) in (1..).zip(keypad) {
+                bindings.bind_key_sym(
+                    conn.display_mut(),
+                    screen.root,
+                    sym,
+                    modifier,
+                    JustAction::JumpToMark(mark),
+                )?;
+            }
+        }
+
+        // After selecting events on the roots, as the core fallback extends their event masks
+        let monitors = (0..screens.len())
+            .map(|idx| Monitors::new(&mut conn, idx))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut workspaces = Vec::new();
+        let screens = screens
+            .into_iter()
+            .zip(&monitors)
+            .flat_map(|(screen, monitors)| {
+                monitor_geometries(monitors, screen.size)
+                    .into_iter()
+                    .map(move |(monitor, size)| WmScreen {
+                        size,
+                        monitor,
+                        ..screen.clone()
+                    })
+            })
+            .map(|screen| {
+                workspaces.push(Workspace::new());
+                WmScreen {
+                    workspace_idx: workspaces.len() - 1,
+                    ..screen
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let wm_check_window = conn.set_supporting_wm_check(screens[0].root, WM_NAME)?;
+        for screen in &screens {
+            conn.set_supported(screen.root)?;
         }
 
+        let rounded_corners = match env::var("JUSTWINDOWS_CORNER_RADIUS")
+            .ok()
+            .and_then(|radius| radius.parse::<u16>().ok())
+        {
+            Some(radius) if radius > 0 => {
+                let display = conn.display_mut();
+                let pending = display.send_request(&requests::QueryExtension {
+                    name: shape::EXTENSION_NAME.to_vec(),
+                })?;
+                display.flush()?;
+                match display.await_pending_reply(pending)? {
+                    Ok(query) if query.present => Some(RoundedCorners {
+                        major_opcode: query.major_opcode,
+                        radius,
+                    }),
+                    _ => {
+                        eprintln!("justwindows: SHAPE extension missing, corners are not rounded");
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+
+        let placement_policy = match env::var("JUSTWINDOWS_PLACEMENT") {
+            Ok(policy) => PlacementPolicy::from_str(&policy).unwrap_or_else(|err| {
+                eprintln!("justwindows: {}, using smart", err);
+                PlacementPolicy::Smart
+            }),
+            Err(_) => PlacementPolicy::Smart,
+        };
+
+        let status = match &args.status {
+            Some(target) => match StatusOutput::open(target) {
+                Ok(status) => Some(status),
+                Err(err) => {
+                    eprintln!("justwindows: error: Could not open status output: {}", err);
+                    process::exit(1);
+                }
+            },
+            None => None,
+        };
+
         conn.flush()?;
 
         Ok(Self {
             conn,
-            managed_windows: Vec::new(),
+            windows: Windows::new(),
             active_window: None,
             bindings,
-            running_children: Vec::new(),
+            game_mode: None,
+            game_mode_nice: env::var("JUSTWINDOWS_GAME_MODE_NICE")
+                .ok()
+                .and_then(|nice| nice.parse().ok()),
+            rounded_corners,
+            placement_policy,
+            click_to_raise: env::var("JUSTWINDOWS_CLICK_TO_RAISE").is_ok_and(|value| value == "1"),
+            idle_inhibitor: (!env::var("JUSTWINDOWS_INHIBIT_IDLE").is_ok_and(|value| value == "0"))
+                .then(IdleInhibitor::default),
+            pending_chord: None,
+            switcher: None,
+            overview: None,
+            drag: None,
+            focus_history: Vec::new(),
+            pending_kills: Vec::new(),
+            children: Children::new(display_name),
+            notification: None,
             screens,
+            monitors,
             workspaces,
+            detached_workspaces: HashMap::new(),
+            workspace_limit: env::var("JUSTWINDOWS_WORKSPACES")
+                .ok()
+                .and_then(|limit| limit.parse().ok())
+                .unwrap_or(workspaces::DEFAULT_LIMIT),
             active_workspace: 0,
+            _wm_check_window: wm_check_window,
+            wm_selections,
+            status,
+            focused_title: None,
+            published_workspaces: None,
+            published_client_list: None,
+            published_desktops: None,
+            published_window_desktops: HashMap::new(),
+            handler_times: env::var("JUSTWINDOWS_TIME_HANDLERS")
+                .is_ok_and(|value| value == "1")
+                .then(HandlerTimes::default),
         })
     }
 
     fn arrange_windows(&mut self) -> Result<(), Error> {
         for screen in self.screens.clone() {
             let workspace = &self.workspaces[screen.workspace_idx];
-            let positioned = workspace.layout.position_windows(
-                screen.size,
-                self.active_window,
-                &workspace.windows,
-            );
+            let fullscreen = self
+                .windows
+                .with_placement(screen.workspace_idx, Placement::Fullscreen);
+            let positioned = match fullscreen.first() {
+                // Other windows are left as they were, fullscreen one is raised above them
+                Some(&window) => {
+                    vec![PositionedWindow {
+                        window,
+                        position: screen.size,
+                        border_width: 0,
+                        border_color: 0,
+                    }]
+                }
+                None => workspace.layout().position_windows(
+                    screen.size,
+                    self.active_window,
+                    &self
+                        .windows
+                        .with_placement(screen.workspace_idx, Placement::Tiled)
+                        .into_iter()
+                        .map(|window| TiledWindow {
+                            window,
+                            min_size: self
+                                .windows
+                                .get(window)
+                                .map_or((0, 0), |state| state.min_size),
+                        })
+                        .collect::<Vec<_>>(),
+                ),
+            };
             dbg!(&positioned);
 
-            positioned.into_iter().try_for_each(|positioned| {
+            // Hidden tail of a layout shares one position, windows are in stacking order
+            let stacked = positioned
+                .iter()
+                .enumerate()
+                .filter(|(idx, window)| {
+                    positioned[..*idx]
+                        .iter()
+                        .any(|other| other.position == window.position)
+                })
+                .map(|(_, window)| window.window)
+                .collect::<Vec<_>>();
+
+            positioned.into_iter().try_for_each(|mut positioned| {
+                let Some(state) = self.windows.get_mut(positioned.window) else {
+                    return Ok(());
+                };
+                if state.urgent && positioned.border_width != 0 {
+                    positioned.border_color = URGENT_BORDER;
+                }
+                let rounded = state.placement != Placement::Fullscreen;
+                let cached = state.geometry.replace(positioned);
+                if cached == Some(positioned) {
+                    return Ok(());
+                }
+
+                if cached.is_none_or(|cached| {
+                    cached.position != positioned.position
+                        || cached.border_width != positioned.border_width
+                }) {
+                    self.conn
+                        .display_mut()
+                        .send_request(&requests::ConfigureWindow {
+                            window: positioned.window,
+                            attributes: positioned.to_attributes(),
+                        })?;
+                }
+
+                if let Some(corners) = self.rounded_corners {
+                    if cached.is_none_or(|cached| {
+                        cached.position.width != positioned.position.width
+                            || cached.position.height != positioned.position.height
+                            || cached.border_width != positioned.border_width
+                    }) {
+                        corners.apply(self.conn.display_mut(), positioned, rounded)?;
+                    }
+                }
+
+                if cached.is_none_or(|cached| cached.border_color != positioned.border_color) {
+                    self.conn
+                        .set_border_color(positioned.window, positioned.border_color)?;
+                }
+                Ok::<(), Error>(())
+            })?;
+
+            for window in stacked {
                 self.conn
                     .display_mut()
                     .send_request(&requests::ConfigureWindow {
-                        window: positioned.window,
-                        attributes: positioned.to_attributes(),
+                        window,
+                        attributes: ConfigureWindowAttributes::new()
+                            .set_stack_mode(StackMode::Above),
                     })?;
-
-                self.conn
-                    .set_border_color(positioned.window, positioned.border_color)?;
-                Ok::<(), Error>(())
-            })?;
+            }
         }
 
         Ok(())
     }
 
-    fn find_managed_window(&self, window: WindowId) -> Option<usize> {
-        self.managed_windows
-            .iter()
-            .enumerate()
-            .find_map(|(idx, w)| (*w == window).then_some(idx))
-    }
+    /// Show active window fullscreen on its monitor without gaps and borders, hint compositor to
+    /// unredirect it and optionally change niceness of its process to reduce latency
+    fn toggle_game_mode(&mut self) -> Result<(), Error> {
+        if let Some(game_mode) = self.game_mode.take() {
+            if let Some(state) = self.windows.get_mut(game_mode.window) {
+                state.placement = Placement::Tiled;
+            }
+            self.conn.set_bypass_compositor(game_mode.window, false)?;
+            self.set_fullscreen_state(game_mode.window, false)?;
+            if let Some((pid, nice)) = game_mode.original_priority {
+                if let Err(err) = priority::set(pid, nice) {
+                    eprintln!(
+                        "justwindows: Could not restore priority of {}: {}",
+                        pid, err
+                    );
+                }
+            }
+            return self.arrange_windows();
+        }
 
-    fn manage_window(&mut self, window: WindowId) -> Result<(), Error> {
-        if self.find_managed_window(window).is_some() {
-            eprintln!(
-                "justwindows: debug: window is already managed: {:?}",
-                window
-            );
-        } else {
-            self.managed_windows.push(window);
-            self.workspaces[self.active_workspace].windows.push(window);
+        let Some(window) = self.active_window else {
+            return Ok(());
+        };
+        let Some(state) = self.windows.get_mut(window) else {
+            return Ok(());
+        };
+        state.placement = Placement::Fullscreen;
+
+        let mut original_priority = None;
+        if let Some(nice) = self.game_mode_nice {
+            if let Some(pid) = self.local_wm_pid(window)? {
+                match priority::get(pid).and_then(|old| priority::set(pid, nice).map(|()| old)) {
+                    Ok(old) => original_priority = Some((pid, old)),
+                    Err(err) => {
+                        eprintln!("justwindows: Could not change priority of {}: {}", pid, err)
+                    }
+                }
+            }
         }
 
-        Ok(())
+        self.game_mode = Some(GameMode {
+            window,
+            original_priority,
+        });
+        self.conn.set_bypass_compositor(window, true)?;
+        self.set_fullscreen_state(window, true)?;
+        self.conn
+            .display_mut()
+            .send_request(&requests::ConfigureWindow {
+                window,
+                attributes: ConfigureWindowAttributes::new().set_stack_mode(StackMode::Above),
+            })?;
+        self.arrange_windows()
     }
 
-    fn unmanage_window(&mut self, window: WindowId) -> Result<(), Error> {
-        self.cleanup_running_children()?;
-        if let Some(destroyed_window_idx) = self.find_managed_window(window) {
-            self.managed_windows.remove(destroyed_window_idx);
-            self.arrange_windows()?;
+    /// Remove (0), add (1) or toggle (2) fullscreen of `window` as asked by its client with a
+    /// `_NET_WM_STATE` message. Fullscreen windows are shown like in game mode without changing
+    /// niceness, they go back to tiled or floating as when they were managed.
+    fn change_fullscreen(&mut self, window: WindowId, action: u32) -> Result<(), Error> {
+        let Some(state) = self.windows.get(window) else {
+            return Ok(());
+        };
+        if state.placement == Placement::Iconified {
+            return Ok(());
+        }
+        let is_fullscreen = state.placement == Placement::Fullscreen;
+        let fullscreen = match action {
+            0 => false,
+            1 => true,
+            2 => !is_fullscreen,
+            _ => return Ok(()),
+        };
+        if fullscreen == is_fullscreen {
+            return self.set_fullscreen_state(window, fullscreen);
+        }
+
+        if !fullscreen
+            && self
+                .game_mode
+                .is_some_and(|game_mode| game_mode.window == window)
+        {
+            return self.toggle_game_mode();
+        }
+        let placement = if fullscreen {
+            Placement::Fullscreen
+        } else if self.conn.get_transient_for(window)?.is_some() {
+            Placement::Floating
         } else {
-            eprintln!(
-                "justwindows: debug: Destroyed window that is not managed: {:?}",
-                window
-            );
+            Placement::Tiled
+        };
+        if let Some(state) = self.windows.get_mut(window) {
+            state.placement = placement;
         }
-        Ok(())
+        self.set_fullscreen_state(window, fullscreen)?;
+        if fullscreen {
+            self.conn
+                .display_mut()
+                .send_request(&requests::ConfigureWindow {
+                    window,
+                    attributes: ConfigureWindowAttributes::new().set_stack_mode(StackMode::Above),
+                })?;
+        }
+        self.arrange_windows()
     }
 
-    fn restore_windows(&mut self) -> Result<(), Error> {
-        let root = self.root_window();
-        let tree = self.conn.query_tree(root)?;
-        for window in tree.children {
-            self.manage_window(window)?;
-            self.set_initial_window_properties(window)?;
+    /// Add or remove `_NET_WM_STATE_FULLSCREEN` of `window`, keeping its other states
+    fn set_fullscreen_state(&mut self, window: WindowId, fullscreen: bool) -> Result<(), Error> {
+        let net_wm_state_fullscreen = self
+            .conn
+            .get_atom_id(String8::from_str("_NET_WM_STATE_FULLSCREEN").unwrap())?;
+        let mut states = self.conn.get_net_wm_state(window)?;
+        if states.contains(&net_wm_state_fullscreen) == fullscreen {
+            return Ok(());
+        }
+        states.retain(|state| *state != net_wm_state_fullscreen);
+        if fullscreen {
+            states.push(net_wm_state_fullscreen);
+        }
+        self.conn.set_net_wm_state(window, &states)?;
+        if let Some(inhibitor) = &mut self.idle_inhibitor {
+            inhibitor.fullscreen_state = None;
         }
-        self.conn.flush()?;
-
         Ok(())
     }
 
-    fn root_window(&self) -> WindowId {
-        self.conn.display().screens()[0].root
-    }
+    /// Hide all windows of the active workspace, or restore them if they were hidden by this
+    fn toggle_show_desktop(&mut self) -> Result<(), Error> {
+        if self.workspaces[self.active_workspace]
+            .shown_desktop
+            .is_some()
+        {
+            return self.restore_desktop(self.active_workspace);
+        }
 
-    fn set_initial_window_properties(&mut self, window: WindowId) -> Result<(), Error> {
-        self.conn.select_input(
-            window,
-            EventType::ENTER_WINDOW | EventType::STRUCTURE_NOTIFY | EventType::PROPERTY_CHANGE,
-        )?;
-        self.conn.flush()?;
-        Ok(())
-    }
+        if self.game_mode.is_some_and(|game_mode| {
+            self.windows
+                .get(game_mode.window)
+                .map(|state| state.workspace)
+                == Some(self.active_workspace)
+        }) {
+            self.toggle_game_mode()?;
+        }
+
+        let mut hidden = Vec::new();
+        for window in self.windows.on_workspace(self.active_workspace) {
+            let Some(state) = self.windows.get_mut(window) else {
+                continue;
+            };
+            if state.placement == Placement::Iconified {
+                continue;
+            }
+            hidden.push((window, state.placement));
+            state.placement = Placement::Iconified;
+            self.conn
+                .display_mut()
+                .send_request(&requests::UnmapWindow { window })?;
+        }
 
-    fn is_client(&self, window: WindowId) -> bool {
-        self.managed_windows.contains(&window)
+        let active_window = self.active_window.take();
+        self.workspaces[self.active_workspace].shown_desktop = Some(ShownDesktop {
+            windows: hidden,
+            active_window,
+        });
+        self.set_showing_desktop(true)?;
+        self.arrange_windows()
     }
 
-    fn rescreen(&mut self) -> Result<(), Error> {
-        // TODO: Run xinerama's `getScreenInfo` when it's implemented.
-        Ok(())
+    /// Map windows hidden by [`Self::toggle_show_desktop`] on `workspace_idx` again
+    fn restore_desktop(&mut self, workspace_idx: usize) -> Result<(), Error> {
+        let Some(shown) = self.workspaces[workspace_idx].shown_desktop.take() else {
+            return Ok(());
+        };
+
+        let visible = self
+            .screens
+            .iter()
+            .any(|screen| screen.workspace_idx == workspace_idx);
+        for (window, placement) in shown.windows {
+            // Windows could have been destroyed or moved meanwhile
+            let Some(state) = self.windows.get_mut(window) else {
+                continue;
+            };
+            if state.workspace != workspace_idx || state.placement != Placement::Iconified {
+                continue;
+            }
+            state.placement = placement;
+            if visible {
+                self.conn.map_window(window)?;
+            }
+        }
+
+        if workspace_idx == self.active_workspace {
+            if let Some(window) = shown
+                .active_window
+                .filter(|window| self.windows.is_managed(*window))
+            {
+                self.active_window = Some(window);
+            }
+            self.set_showing_desktop(false)?;
+        }
+        self.arrange_windows()
     }
 
-    fn cleanup_running_children(&mut self) -> Result<(), Error> {
-        let mut ret = Ok(());
-        self.running_children
-            .retain_mut(|child| match child.try_wait() {
-                Ok(None) => true,
-                Ok(Some(_)) => false,
-                Err(err) => {
-                    ret = Err(err);
-                    true
-                }
-            });
-        ret?;
-        Ok(())
+    /// EWMH `_NET_SHOWING_DESKTOP` of the root, for panels showing the toggle state
+    fn set_showing_desktop(&mut self, showing: bool) -> Result<(), Error> {
+        let property = self
+            .conn
+            .get_atom_id(String8::from_str("_NET_SHOWING_DESKTOP").unwrap())?;
+        let root = self.root_window();
+        self.conn.change_property(
+            root,
+            property,
+            AtomId::CARDINAL,
+            ChangePropertyFormat::Format32,
+            &(showing as u32).to_le_bytes(),
+        )
     }
 
-    fn spawn(&mut self, command: &str) -> Result<(), Error> {
-        let spawned_process = std::process::Command::new(command).spawn()?;
-        self.running_children.push(spawned_process);
+    /// EWMH `_NET_CLIENT_LIST` of the root if managed windows changed, in order of management.
+    /// Windows hidden on other workspaces or by showing the desktop are included.
+    fn publish_client_list(&mut self) -> Result<(), Error> {
+        let windows = self
+            .windows
+            .iter()
+            .map(|(window, _)| window)
+            .collect::<Vec<_>>();
+        if self.published_client_list.as_ref() == Some(&windows) {
+            return Ok(());
+        }
 
+        let property = self
+            .conn
+            .get_atom_id(String8::from_str("_NET_CLIENT_LIST").unwrap())?;
+        let data = windows
+            .iter()
+            .flat_map(|window| window.to_le_bytes())
+            .collect::<Vec<_>>();
+        let root = self.root_window();
+        self.conn.change_property(
+            root,
+            property,
+            AtomId::WINDOW,
+            ChangePropertyFormat::Format32,
+            &data,
+        )?;
+        self.published_client_list = Some(windows);
         Ok(())
     }
 
-    fn handle_event(&mut self, event: SomeEvent) -> Result<(), Error> {
-        match event {
-            SomeEvent::ConfigureRequest(event) => {
-                let attributes = ConfigureWindowAttributes::from(&event);
-                self.conn
+    /// EWMH `_NET_NUMBER_OF_DESKTOPS`, `_NET_DESKTOP_NAMES` and `_NET_CURRENT_DESKTOP` of the root
+    /// and `_NET_WM_DESKTOP` of managed windows, for pagers. Only values that changed are sent.
+    fn publish_desktops(&mut self) -> Result<(), Error> {
+        let root = self.root_window();
+        let desktops = (self.workspaces.len(), self.active_workspace);
+        if self.published_desktops != Some(desktops) {
+            let number_of_desktops = self
+                .conn
+                .get_atom_id(String8::from_str("_NET_NUMBER_OF_DESKTOPS").unwrap())?;
+            let desktop_names = self
+                .conn
+                .get_atom_id(String8::from_str("_NET_DESKTOP_NAMES").unwrap())?;
+            let current_desktop = self
+                .conn
+                .get_atom_id(String8::from_str("_NET_CURRENT_DESKTOP").unwrap())?;
+            let utf8_string = self
+                .conn
+                .get_atom_id(String8::from_str("UTF8_STRING").unwrap())?;
+            self.conn.change_property(
+                root,
+                number_of_desktops,
+                AtomId::CARDINAL,
+                ChangePropertyFormat::Format32,
+                &(desktops.0 as u32).to_le_bytes(),
+            )?;
+            self.conn.change_property(
+                root,
+                desktop_names,
+                utf8_string,
+                ChangePropertyFormat::Format8,
+                &workspaces::desktop_names(desktops.0),
+            )?;
+            self.conn.change_property(
+                root,
+                current_desktop,
+                AtomId::CARDINAL,
+                ChangePropertyFormat::Format32,
+                &(desktops.1 as u32).to_le_bytes(),
+            )?;
+            self.published_desktops = Some(desktops);
+        }
+
+        let windows = &self.windows;
+        self.published_window_desktops
+            .retain(|window, _| windows.is_managed(*window));
+        let changed = self
+            .windows
+            .iter()
+            .filter(|(window, state)| {
+                self.published_window_desktops.get(window) != Some(&state.workspace)
+            })
+            .map(|(window, state)| (window, state.workspace))
+            .collect::<Vec<_>>();
+        if changed.is_empty() {
+            return Ok(());
+        }
+        let wm_desktop = self
+            .conn
+            .get_atom_id(String8::from_str("_NET_WM_DESKTOP").unwrap())?;
+        for (window, workspace) in changed {
+            self.conn.change_property(
+                window,
+                wm_desktop,
+                AtomId::CARDINAL,
+                ChangePropertyFormat::Format32,
+                &(workspace as u32).to_le_bytes(),
+            )?;
+            self.published_window_desktops.insert(window, workspace);
+        }
+        Ok(())
+    }
+
+    /// Expose marks for scripting as `<mark> <window id>` lines, e.g. to read with `xprop -root`
+    fn publish_marks(&mut self) -> Result<(), Error> {
+        let mut marks = self
+            .windows
+            .iter()
+            .flat_map(|(window, state)| state.marks.iter().map(move |mark| (*mark, window)))
+            .collect::<Vec<_>>();
+        marks.sort_by_key(|(mark, _)| *mark);
+        let data = marks
+            .into_iter()
+            .map(|(mark, window)| format!("{} {}\n", mark, window))
+            .collect::<String>();
+
+        let property = self
+            .conn
+            .get_atom_id(String8::from_str("_JUSTWINDOWS_MARKS").unwrap())?;
+        let utf8_string = self
+            .conn
+            .get_atom_id(String8::from_str("UTF8_STRING").unwrap())?;
+        let root = self.root_window();
+        self.conn.change_property(
+            root,
+            property,
+            utf8_string,
+            ChangePropertyFormat::Format8,
+            data.as_bytes(),
+        )?;
+        Ok(())
+    }
+
+    /// Layouts, master ratios and tiling order of workspaces, see [`persist`]
+    fn saved_workspaces(&self) -> Vec<SavedWorkspace> {
+        self.workspaces
+            .iter()
+            .enumerate()
+            .map(|(index, workspace)| SavedWorkspace {
+                index,
+                layout: workspace.layout,
+                master_ratio: workspace.master_ratio,
+                windows: self
+                    .windows
+                    .on_workspace(index)
+                    .into_iter()
+                    .map(u32::from)
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// Keep workspaces in a root property if they changed, so the next instance of the window
+    /// manager can restore them
+    fn publish_workspaces(&mut self) -> Result<(), Error> {
+        let data = persist::write(&self.saved_workspaces());
+        if self.published_workspaces.as_ref() == Some(&data) {
+            return Ok(());
+        }
+
+        let property = self
+            .conn
+            .get_atom_id(String8::from_str("_JUSTWINDOWS_WORKSPACES").unwrap())?;
+        let utf8_string = self
+            .conn
+            .get_atom_id(String8::from_str("UTF8_STRING").unwrap())?;
+        let root = self.root_window();
+        self.conn.change_property(
+            root,
+            property,
+            utf8_string,
+            ChangePropertyFormat::Format8,
+            data.as_bytes(),
+        )?;
+        self.published_workspaces = Some(data);
+        Ok(())
+    }
+
+    /// Workspaces published by the previous instance of the window manager
+    fn read_saved_workspaces(&mut self) -> Result<Vec<SavedWorkspace>, Error> {
+        let property = self
+            .conn
+            .get_atom_id(String8::from_str("_JUSTWINDOWS_WORKSPACES").unwrap())?;
+        let utf8_string = self
+            .conn
+            .get_atom_id(String8::from_str("UTF8_STRING").unwrap())?;
+        let root = self.root_window();
+        let PropertyValue::Value {
+            format: 8, value, ..
+        } = self
+            .conn
+            .get_property(root, property, utf8_string, 1000000)?
+        else {
+            return Ok(Vec::new());
+        };
+        Ok(persist::parse(&String::from_utf8_lossy(&value)))
+    }
+
+    /// Write current state to the `--status` output if it changed
+    fn publish_status(&mut self) -> Result<(), Error> {
+        if self.status.is_none() {
+            return Ok(());
+        }
+
+        let focused = match self.active_window {
+            Some(window) => match self.windows.get(window) {
+                Some(state) => {
+                    let (workspace, urgent) = (state.workspace, state.urgent);
+                    let title = match &self.focused_title {
+                        Some((cached, title)) if *cached == window => title.clone(),
+                        _ => {
+                            let title = self.conn.get_wm_name(window)?;
+                            self.focused_title = Some((window, title.clone()));
+                            title
+                        }
+                    };
+                    Some(FocusedStatus {
+                        window: u32::from(window),
+                        workspace,
+                        title,
+                        urgent,
+                    })
+                }
+                None => None,
+            },
+            None => None,
+        };
+
+        let workspaces = (0..self.workspaces.len())
+            .map(|index| {
+                let windows = self
+                    .windows
+                    .iter()
+                    .filter(|(_, state)| state.workspace == index)
+                    .collect::<Vec<_>>();
+                WorkspaceStatus {
+                    index,
+                    monitor: self
+                        .screens
+                        .iter()
+                        .find(|screen| screen.workspace_idx == index)
+                        .map(|screen| screen.monitor.clone()),
+                    focused: index == self.active_workspace,
+                    urgent: windows.iter().any(|(_, state)| state.urgent),
+                    windows: windows.len(),
+                    layout: self.workspaces[index].layout,
+                    master_ratio: self.workspaces[index].master_ratio,
+                }
+            })
+            .collect();
+
+        let status = Status {
+            workspaces,
+            focused,
+        };
+        if let Err(err) = self.status.as_mut().unwrap().publish(&status) {
+            eprintln!(
+                "justwindows: error: Could not write status, disabling it: {}",
+                err
+            );
+            self.status = None;
+        }
+        Ok(())
+    }
+
+    /// Send current state to bars that connected to the `--status` socket
+    fn accept_status_clients(&mut self) {
+        if let Some(Err(err)) = self.status.as_mut().map(StatusOutput::accept) {
+            eprintln!(
+                "justwindows: error: Could not accept status client: {}",
+                err
+            );
+        }
+    }
+
+    /// Answer queries written to the `--status` socket, see [`status`]
+    fn answer_status_queries(&mut self) {
+        let Some(status) = self.status.as_mut() else {
+            return;
+        };
+        let display = self.conn.display();
+        let handler_times = self.handler_times.as_ref();
+        status.answer_queries(|query| match query {
+            "query stats" => Some(stats::to_json(&display.stats(), handler_times)),
+            _ => None,
+        });
+    }
+
+    /// Show workspace of `window` if it is hidden, activate it and move pointer into it
+    fn jump_to_window(&mut self, window: WindowId) -> Result<(), Error> {
+        let Some(workspace_idx) = self.windows.get(window).map(|state| state.workspace) else {
+            return Ok(());
+        };
+        if self.workspaces[workspace_idx]
+            .shown_desktop
+            .as_ref()
+            .is_some_and(|shown| shown.windows.iter().any(|(w, _)| *w == window))
+        {
+            self.restore_desktop(workspace_idx)?;
+        }
+
+        if !self
+            .screens
+            .iter()
+            .any(|screen| screen.workspace_idx == workspace_idx)
+        {
+            self.show_workspace(workspace_idx)?;
+        }
+
+        self.active_window = Some(window);
+        self.arrange_windows()?;
+
+        // Focus follows pointer so it has to be moved to the window
+        let (x, y) = self
+            .windows
+            .get(window)
+            .and_then(|state| state.geometry)
+            .map_or((0, 0), |positioned| {
+                (
+                    positioned.position.width as i16 / 2,
+                    positioned.position.height as i16 / 2,
+                )
+            });
+        self.conn
+            .display_mut()
+            .send_request(&requests::WarpPointer {
+                src_window: OrNone::none(),
+                dst_window: OrNone::new(window),
+                src_x: 0,
+                src_y: 0,
+                src_width: 0,
+                src_height: 0,
+                dst_x: x,
+                dst_y: y,
+            })?;
+
+        Ok(())
+    }
+
+    /// Replace workspace of the active screen with `workspace_idx`
+    fn show_workspace(&mut self, workspace_idx: usize) -> Result<(), Error> {
+        let Some(screen) = self
+            .screens
+            .iter_mut()
+            .find(|screen| screen.workspace_idx == self.active_workspace)
+        else {
+            return Ok(());
+        };
+        screen.workspace_idx = workspace_idx;
+
+        for window in self.windows.on_workspace(self.active_workspace) {
+            self.conn
+                .display_mut()
+                .send_request(&requests::UnmapWindow { window })?;
+        }
+        for (window, state) in self.windows.iter() {
+            if state.workspace == workspace_idx && state.placement != Placement::Iconified {
+                self.conn.map_window(window)?;
+            }
+        }
+        self.active_workspace = workspace_idx;
+        let showing = self.workspaces[workspace_idx].shown_desktop.is_some();
+        self.set_showing_desktop(showing)?;
+
+        Ok(())
+    }
+
+    /// Create workspaces up to `workspace_idx` unless they exist
+    fn grow_workspaces(&mut self, workspace_idx: usize) {
+        while self.workspaces.len() <= workspace_idx {
+            self.workspaces.push(Workspace::new());
+        }
+    }
+
+    /// Workspace with windows, shown on a monitor or kept for an unplugged one
+    fn is_workspace_used(&self, workspace_idx: usize) -> bool {
+        workspace_idx == self.active_workspace
+            || self.workspaces[workspace_idx].shown_desktop.is_some()
+            || self
+                .screens
+                .iter()
+                .any(|screen| screen.workspace_idx == workspace_idx)
+            || self
+                .detached_workspaces
+                .values()
+                .any(|detached| detached.workspace_idx == workspace_idx)
+            || self
+                .windows
+                .iter()
+                .any(|(_, state)| state.workspace == workspace_idx)
+    }
+
+    /// Drop empty workspaces at the end, their layouts and master ratios are forgotten
+    fn remove_unused_workspaces(&mut self) {
+        let keep = workspaces::keep_count(self.workspaces.len(), |idx| self.is_workspace_used(idx));
+        self.workspaces.truncate(keep);
+    }
+
+    /// Show workspace `workspace_idx` on the active monitor, creating it and the ones before it
+    /// if needed. If another monitor already shows it, that monitor becomes active instead.
+    fn show_numbered_workspace(&mut self, workspace_idx: usize) -> Result<(), Error> {
+        if workspace_idx >= self.workspace_limit.max(self.workspaces.len())
+            || workspace_idx == self.active_workspace
+        {
+            return Ok(());
+        }
+        self.grow_workspaces(workspace_idx);
+
+        if let Some(screen) = self
+            .screens
+            .iter()
+            .find(|screen| screen.workspace_idx == workspace_idx)
+            .cloned()
+        {
+            self.active_workspace = workspace_idx;
+            let showing = self.workspaces[workspace_idx].shown_desktop.is_some();
+            self.set_showing_desktop(showing)?;
+            // Focus follows pointer so it has to be moved to the monitor
+            self.conn
+                .display_mut()
+                .send_request(&requests::WarpPointer {
+                    src_window: OrNone::none(),
+                    dst_window: OrNone::new(screen.root),
+                    src_x: 0,
+                    src_y: 0,
+                    src_width: 0,
+                    src_height: 0,
+                    dst_x: screen.size.x + screen.size.width as i16 / 2,
+                    dst_y: screen.size.y + screen.size.height as i16 / 2,
+                })?;
+        } else {
+            self.show_workspace(workspace_idx)?;
+        }
+
+        // Window under the pointer becomes active when it is entered
+        self.active_window = None;
+        self.arrange_windows()
+    }
+
+    /// Move managed `window` to workspace `workspace_idx`, creating it if needed. Window is
+    /// hidden unless a monitor shows that workspace.
+    fn move_window_to_workspace(
+        &mut self,
+        window: WindowId,
+        workspace_idx: usize,
+    ) -> Result<(), Error> {
+        if workspace_idx >= self.workspace_limit.max(self.workspaces.len()) {
+            return Ok(());
+        }
+        if self
+            .windows
+            .get(window)
+            .is_none_or(|state| state.workspace == workspace_idx)
+        {
+            return Ok(());
+        }
+        self.grow_workspaces(workspace_idx);
+
+        let shown = self
+            .screens
+            .iter()
+            .any(|screen| screen.workspace_idx == workspace_idx);
+        let state = self.windows.get_mut(window).unwrap();
+        state.workspace = workspace_idx;
+        if !shown {
+            self.conn
+                .display_mut()
+                .send_request(&requests::UnmapWindow { window })?;
+            if self.active_window == Some(window) {
+                self.active_window = None;
+            }
+        } else if state.placement != Placement::Iconified {
+            // Could come from a hidden workspace when moved by a pager
+            self.conn.map_window(window)?;
+        }
+        self.arrange_windows()
+    }
+
+    fn manage_window(&mut self, window: WindowId) -> Result<(), Error> {
+        // Dialogs keep geometry requested by the client
+        let placement = if self.conn.get_transient_for(window)?.is_some() {
+            Placement::Floating
+        } else {
+            Placement::Tiled
+        };
+        let min_size = self.get_min_size(window)?;
+        if !self
+            .windows
+            .manage(window, self.active_workspace, placement)
+        {
+            eprintln!(
+                "justwindows: debug: window is already managed: {:?}",
+                window
+            );
+        } else if self.click_to_raise {
+            // Pointer is frozen on press until the click is replayed to the client
+            self.conn
+                .display_mut()
+                .send_request(&requests::GrabButton {
+                    owner_events: false,
+                    grab_window: window,
+                    event_mask: PointerEventMask::BUTTON_PRESS,
+                    pointer_mode: GrabMode::Synchronous,
+                    keyboard_mode: GrabMode::Asynchronous,
+                    confine_to: OrNone::none(),
+                    cursor: OrNone::none(),
+                    button: ANY_BUTTON,
+                    modifiers: ANY_MODIFIER,
+                })?;
+        }
+        if let Some(state) = self.windows.get_mut(window) {
+            state.min_size = min_size;
+        }
+
+        Ok(())
+    }
+
+    /// Minimum size from `WM_NORMAL_HINTS`, which defaults to the base size
+    fn get_min_size(&mut self, window: WindowId) -> Result<(u16, u16), Error> {
+        let Some(hints) = self.conn.get_wm_normal_hints(window)? else {
+            return Ok((0, 0));
+        };
+        let size = if hints
+            .flags
+            .has(WindowManagerHintsFlags::PROGRAM_SPECIFIED_MINSIZE)
+        {
+            hints.min_size
+        } else if hints
+            .flags
+            .has(WindowManagerHintsFlags::PROGRAM_SPECIFIED_BASESIZE)
+        {
+            hints.base_size
+        } else {
+            (0, 0)
+        };
+        Ok((
+            size.0.clamp(0, u16::MAX as i32) as u16,
+            size.1.clamp(0, u16::MAX as i32) as u16,
+        ))
+    }
+
+    /// Move new floating window according to [`PlacementPolicy`], unless its client asked for
+    /// a position in `WM_NORMAL_HINTS`
+    fn place_floating_window(&mut self, window: WindowId) -> Result<(), Error> {
+        if let Some(hints) = self.conn.get_wm_normal_hints(window)? {
+            if hints
+                .flags
+                .has(WindowManagerHintsFlags::USER_SPECIFIED_POSITION)
+                || hints
+                    .flags
+                    .has(WindowManagerHintsFlags::PROGRAM_SPECIFIED_POSITION)
+            {
+                return Ok(());
+            }
+        }
+
+        let Some(screen) = self
+            .screens
+            .iter()
+            .find(|screen| screen.workspace_idx == self.active_workspace)
+            .cloned()
+        else {
+            return Ok(());
+        };
+
+        let outer_size = |geometry: &replies::GetGeometry| {
+            (
+                geometry.width + geometry.border_width * 2,
+                geometry.height + geometry.border_width * 2,
+            )
+        };
+        let geometry = self.conn.get_window_geometry(window)?;
+        let others = self
+            .windows
+            .with_placement(self.active_workspace, Placement::Floating)
+            .into_iter()
+            .filter(|&other| other != window)
+            .map(|other| {
+                let geometry = self.conn.get_window_geometry(other)?;
+                let (width, height) = outer_size(&geometry);
+                Ok(Rectangle {
+                    x: geometry.x,
+                    y: geometry.y,
+                    width,
+                    height,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let pointer = match self.placement_policy {
+            PlacementPolicy::UnderPointer => {
+                let pointer = self.conn.query_pointer(screen.root)?;
+                (pointer.root_x, pointer.root_y)
+            }
+            _ => (0, 0),
+        };
+
+        let (x, y) = placement::place(
+            self.placement_policy,
+            screen.size,
+            outer_size(&geometry),
+            pointer,
+            &others,
+        );
+        self.conn
+            .display_mut()
+            .send_request(&requests::ConfigureWindow {
+                window,
+                attributes: ConfigureWindowAttributes::new().set_x(x).set_y(y),
+            })?;
+        Ok(())
+    }
+
+    fn unmanage_window(&mut self, window: WindowId) -> Result<(), Error> {
+        if let Some(state) = self.windows.unmanage(window) {
+            if !state.marks.is_empty() {
+                self.publish_marks()?;
+            }
+            if self
+                .game_mode
+                .is_some_and(|game_mode| game_mode.window == window)
+            {
+                // Process is most likely gone so priority is not restored
+                self.game_mode = None;
+            }
+            self.arrange_windows()?;
+        } else {
+            eprintln!(
+                "justwindows: debug: Destroyed window that is not managed: {:?}",
+                window
+            );
+        }
+        Ok(())
+    }
+
+    fn restore_windows(&mut self) -> Result<(), Error> {
+        let saved = self.read_saved_workspaces()?;
+        let root = self.root_window();
+        let tree = self.conn.query_tree(root)?;
+        for window in tree.children {
+            self.manage_window(window)?;
+            self.set_initial_window_properties(window)?;
+        }
+
+        // Workspaces over the limit, e.g. after lowering it, are not restored
+        let saved = saved
+            .into_iter()
+            .filter(|saved| saved.index < self.workspace_limit.max(self.workspaces.len()))
+            .collect::<Vec<_>>();
+        if let Some(last) = saved.iter().map(|saved| saved.index).max() {
+            self.grow_workspaces(last);
+        }
+        let mut order = Vec::new();
+        for saved in &saved {
+            let workspace = &mut self.workspaces[saved.index];
+            workspace.layout = saved.layout;
+            workspace.master_ratio = saved.master_ratio.clamp(MIN_MASTER_RATIO, MAX_MASTER_RATIO);
+
+            let shown = self
+                .screens
+                .iter()
+                .any(|screen| screen.workspace_idx == saved.index);
+            for window in saved.windows.iter().copied().map(WindowId::from) {
+                let Some(state) = self.windows.get_mut(window) else {
+                    continue;
+                };
+                state.workspace = saved.index;
+                order.push(window);
+                // Previous instance unmapped windows of hidden workspaces
+                if shown {
+                    self.conn.map_window(window)?;
+                } else {
+                    self.conn
+                        .display_mut()
+                        .send_request(&requests::UnmapWindow { window })?;
+                }
+            }
+        }
+        self.windows.reorder(&order);
+        self.arrange_windows()?;
+        self.remove_unused_workspaces();
+        self.publish_workspaces()?;
+        self.publish_desktops()?;
+        self.publish_client_list()?;
+        self.set_showing_desktop(false)?;
+        self.conn.flush()?;
+
+        Ok(())
+    }
+
+    fn root_window(&self) -> WindowId {
+        self.conn.display().default_screen().root
+    }
+
+    fn set_initial_window_properties(&mut self, window: WindowId) -> Result<(), Error> {
+        self.conn.select_input(
+            window,
+            EventType::ENTER_WINDOW | EventType::STRUCTURE_NOTIFY | EventType::PROPERTY_CHANGE,
+        )?;
+        self.conn.flush()?;
+        Ok(())
+    }
+
+    /// Update screens if `event` changed monitors
+    fn rescreen(&mut self, event: &SomeEvent) -> Result<(), Error> {
+        let mut changed = false;
+        for idx in 0..self.monitors.len() {
+            if self.monitors[idx].handle_event(&mut self.conn, event)? {
+                let screen = &self.conn.display().screens()[idx];
+                let (root, screen_size) = (
+                    screen.root,
+                    Rectangle {
+                        x: 0,
+                        y: 0,
+                        width: screen.width_in_pixels,
+                        height: screen.height_in_pixels,
+                    },
+                );
+                let monitors = monitor_geometries(&self.monitors[idx], screen_size);
+                self.reassign_monitors(root, monitors)?;
+                changed = true;
+            }
+        }
+        if changed {
+            self.arrange_windows()?;
+        }
+        Ok(())
+    }
+
+    /// Replace screens of `root` with `monitors`. Workspaces of unplugged monitors are detached
+    /// and their windows moved to the first remaining monitor, until a monitor with the same name
+    /// comes back.
+    fn reassign_monitors(
+        &mut self,
+        root: WindowId,
+        monitors: Vec<(String, Rectangle)>,
+    ) -> Result<(), Error> {
+        let (old, mut screens): (Vec<_>, Vec<_>) = mem::take(&mut self.screens)
+            .into_iter()
+            .partition(|screen| screen.root == root);
+
+        let mut new = Vec::with_capacity(monitors.len());
+        for (monitor, size) in monitors {
+            let workspace_idx = if let Some(screen) = old.iter().find(|s| s.monitor == monitor) {
+                screen.workspace_idx
+            } else if let Some(detached) = self.detached_workspaces.remove(&monitor) {
+                self.reattach_workspace(&detached)?;
+                detached.workspace_idx
+            } else {
+                self.workspaces.push(Workspace::new());
+                self.workspaces.len() - 1
+            };
+            new.push(WmScreen {
+                size,
+                workspace_idx,
+                root,
+                monitor,
+            });
+        }
+
+        let target = new[0].workspace_idx;
+        for gone in old
+            .into_iter()
+            .filter(|screen| !new.iter().any(|new| new.monitor == screen.monitor))
+        {
+            let windows = self.windows.on_workspace(gone.workspace_idx);
+            for &window in &windows {
+                if let Some(state) = self.windows.get_mut(window) {
+                    state.workspace = target;
+                }
+            }
+            if self.active_workspace == gone.workspace_idx {
+                self.active_workspace = target;
+            }
+            self.detached_workspaces.insert(
+                gone.monitor,
+                DetachedWorkspace {
+                    workspace_idx: gone.workspace_idx,
+                    windows,
+                },
+            );
+        }
+
+        screens.extend(new);
+        self.screens = screens;
+        Ok(())
+    }
+
+    /// Move windows that are still managed back to the workspace of a monitor that came back
+    fn reattach_workspace(&mut self, detached: &DetachedWorkspace) -> Result<(), Error> {
+        for other in self.detached_workspaces.values_mut() {
+            other
+                .windows
+                .retain(|window| !detached.windows.contains(window));
+        }
+        for &window in &detached.windows {
+            let Some(state) = self.windows.get_mut(window) else {
+                continue;
+            };
+            state.workspace = detached.workspace_idx;
+            // Workspace they were moved to could have been hidden since
+            if state.placement != Placement::Iconified {
+                self.conn.map_window(window)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reap exited children, failed autostart entries are respawned unless they crash loop
+    fn reap_children(&mut self) -> Result<(), Error> {
+        for failed in self.children.reap(Instant::now())? {
+            let status = failed.status.to_string();
+            let fields: &[(&str, &dyn std::fmt::Display)] =
+                &[("command", &failed.command), ("status", &status)];
+            match failed.respawn {
+                Respawn::No => log::log(log::Level::Info, "child-failed", fields),
+                Respawn::Respawned => log::log(log::Level::Warn, "autostart-respawned", fields),
+                Respawn::CrashLoop => {
+                    log::log(log::Level::Error, "autostart-crash-loop", fields);
+                    self.notify(format!(
+                        "'{}' failed {} times in a minute, not restarting it",
+                        failed.command,
+                        children::RESPAWN_LIMIT + 1
+                    ))?;
+                }
+                Respawn::Failed(err) => self.spawn_failed(&failed.command, &err)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn spawn(&mut self, command: ChildCommand) -> Result<(), Error> {
+        if let Err(err) = self.children.spawn(command.clone()) {
+            self.spawn_failed(&command, &err)?;
+        }
+        Ok(())
+    }
+
+    fn spawn_failed(&mut self, command: &ChildCommand, err: &std::io::Error) -> Result<(), Error> {
+        log::log(
+            log::Level::Error,
+            "spawn-failed",
+            &[("command", command), ("error", err)],
+        );
+        self.notify(format!("Could not start '{}': {}", command, err))
+    }
+
+    /// Show `message` in the notification popup, below the ones that are still shown
+    fn notify(&mut self, message: String) -> Result<(), Error> {
+        let root = self.root_window();
+        let area = self.active_area();
+        let previous = self.notification.take();
+        let display = self.conn.display_mut();
+        self.notification = Some(Notification::show(display, root, area, previous, message)?);
+        display.flush()?;
+        Ok(())
+    }
+
+    fn expire_notification(&mut self) -> Result<(), Error> {
+        if self
+            .notification
+            .as_ref()
+            .is_some_and(|notification| Instant::now() >= notification.deadline)
+        {
+            let display = self.conn.display_mut();
+            self.notification.take().unwrap().hide(display)?;
+            display.flush()?;
+        }
+        Ok(())
+    }
+
+    /// `$XDG_CONFIG_HOME/justwindows/autostart`, falling back to `~/.config`
+    fn autostart_path() -> Option<PathBuf> {
+        let config_home = env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+        Some(config_home.join(WM_NAME).join("autostart"))
+    }
+
+    /// Run autostart file if it's executable, otherwise treat every non-empty line that is not
+    /// a `#` comment as a shell command to spawn.
+    fn autostart(&mut self) -> Result<(), Error> {
+        let Some(path) = Self::autostart_path() else {
+            return Ok(());
+        };
+        let Ok(metadata) = fs::metadata(&path) else {
+            return Ok(());
+        };
+
+        if metadata.permissions().mode() & 0o111 != 0 {
+            let command = ChildCommand::Program(path);
+            if let Err(err) = self.children.autostart(command.clone()) {
+                self.spawn_failed(&command, &err)?;
+            }
+            return Ok(());
+        }
+
+        for line in fs::read_to_string(&path)?.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let command = ChildCommand::Shell(line.to_string());
+            if let Err(err) = self.children.autostart(command.clone()) {
+                self.spawn_failed(&command, &err)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn run_action(&mut self, action: JustAction, time: u32) -> Result<(), Error> {
+        match action {
+            JustAction::KillActive => {
+                if let Some(active) = self.active_window {
+                    self.unmanage_window(active)?;
+                    if self.conn.kill_window(active)? == WindowKill::DeleteRequested {
+                        self.pending_kills
+                            .push((active, Instant::now() + KILL_TIMEOUT));
+                    }
+                    self.active_window = None;
+                }
+            }
+            JustAction::Term => {
+                self.spawn(ChildCommand::Program(PathBuf::from("xterm")))?;
+            }
+            JustAction::Mark(mark) => {
+                if let Some(active) = self.active_window {
+                    self.windows.set_mark(active, mark);
+                    self.publish_marks()?;
+                }
+            }
+            JustAction::JumpToMark(mark) => {
+                if let Some(window) = self.windows.marked(mark) {
+                    self.jump_to_window(window)?;
+                }
+            }
+            JustAction::ToggleGameMode => {
+                self.toggle_game_mode()?;
+            }
+            JustAction::ToggleShowDesktop => {
+                self.toggle_show_desktop()?;
+            }
+            JustAction::GrowMaster | JustAction::ShrinkMaster => {
+                let step = match action {
+                    JustAction::GrowMaster => MASTER_RATIO_STEP,
+                    _ => -MASTER_RATIO_STEP,
+                };
+                let workspace = &mut self.workspaces[self.active_workspace];
+                // Rounded so repeated steps don't accumulate float errors in the status output
+                workspace.master_ratio = ((workspace.master_ratio + step) * 100.0).round() / 100.0;
+                workspace.master_ratio = workspace
+                    .master_ratio
+                    .clamp(MIN_MASTER_RATIO, MAX_MASTER_RATIO);
+                self.arrange_windows()?;
+            }
+            JustAction::NextLayout => {
+                let workspace = &mut self.workspaces[self.active_workspace];
+                workspace.layout = workspace.layout.next();
+                self.arrange_windows()?;
+            }
+            JustAction::ShowWorkspace(number) => {
+                self.show_numbered_workspace(number as usize - 1)?;
+            }
+            JustAction::MoveToWorkspace(number) => {
+                if let Some(active) = self.active_window {
+                    self.move_window_to_workspace(active, number as usize - 1)?;
+                }
+            }
+            JustAction::Chord(chord) => {
+                self.start_chord(chord, time)?;
+            }
+            JustAction::Switcher(modifiers) => {
+                self.start_switcher(modifiers, time)?;
+            }
+            JustAction::ToggleOverview => {
+                if self.overview.is_some() {
+                    self.end_overview(None)?;
+                } else {
+                    self.start_overview(time)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Grab the keyboard until the next key press or [`CHORD_TIMEOUT`], see [`Self::end_chord`]
+    fn start_chord(&mut self, chord: usize, time: u32) -> Result<(), Error> {
+        let root = self.root_window();
+        let display = self.conn.display_mut();
+        match display.grab_keyboard(&requests::GrabKeyboard {
+            owner_events: false,
+            grab_window: root,
+            time: Timestamp::from(time),
+            pointer_mode: GrabMode::Asynchronous,
+            keyboard_mode: GrabMode::Asynchronous,
+        })? {
+            // Held until the chord ends, see `Self::end_chord`
+            Ok(grab) => grab.leak(),
+            // Another client holds the keyboard so the next key would not reach us
+            Err(_) => return Ok(()),
+        }
+
+        let area = self.active_area();
+        let hint = TextPopup::show(
+            self.conn.display_mut(),
+            root,
+            area,
+            PopupAnchor::Center,
+            &self.bindings.chords[chord].hint,
+        )?;
+        self.pending_chord = Some(PendingChord {
+            chord,
+            deadline: Instant::now() + CHORD_TIMEOUT,
+            hint,
+        });
+        Ok(())
+    }
+
+    /// Release the keyboard and hide the hint, returns index of the chord that was pending
+    fn end_chord(&mut self) -> Result<usize, Error> {
+        let pending = self.pending_chord.take().expect("no chord is pending");
+        let display = self.conn.display_mut();
+        display.ungrab_keyboard()?;
+        if let Some(hint) = pending.hint {
+            hint.hide(display)?;
+        }
+        display.flush()?;
+        Ok(pending.chord)
+    }
+
+    /// Area of the monitor showing the active workspace
+    fn active_area(&self) -> Rectangle {
+        self.screens
+            .iter()
+            .find(|screen| screen.workspace_idx == self.active_workspace)
+            .map_or(
+                Rectangle {
+                    x: 0,
+                    y: 0,
+                    width: 0,
+                    height: 0,
+                },
+                |screen| screen.size,
+            )
+    }
+
+    /// Grab the keyboard synchronously and show the window switcher until one of `modifiers` is
+    /// released, see [`Self::end_switcher`]
+    fn start_switcher(&mut self, modifiers: KeyModifier, time: u32) -> Result<(), Error> {
+        let order = switcher::focus_order(
+            &self.focus_history,
+            self.windows.iter().map(|(window, _)| window),
+        );
+        if order.is_empty() {
+            return Ok(());
+        }
+
+        let root = self.root_window();
+        let display = self.conn.display_mut();
+        match display.grab_keyboard(&requests::GrabKeyboard {
+            owner_events: false,
+            grab_window: root,
+            time: Timestamp::from(time),
+            pointer_mode: GrabMode::Asynchronous,
+            keyboard_mode: GrabMode::Synchronous,
+        })? {
+            // Held until the switcher closes, see `Self::end_switcher`
+            Ok(grab) => grab.leak(),
+            Err(_) => return Ok(()),
+        }
+
+        let pending = display.send_request(&requests::GetModifierMapping)?;
+        display.flush()?;
+        let release_keys = match display.await_pending_reply(pending)? {
+            Ok(mapping) => switcher::modifier_keys(&mapping, modifiers),
+            // Only `Return` and `Escape` close the switcher then
+            Err(_) => Vec::new(),
+        };
+
+        let depth = display.default_screen().root_depth;
+        let mut entries = Vec::with_capacity(order.len());
+        for window in order {
+            let title = self.conn.get_wm_name(window)?.unwrap_or_default();
+            let thumbnail = match self.windows.get(window).and_then(|state| state.geometry) {
+                Some(positioned) => Thumbnail::capture(
+                    self.conn.display_mut(),
+                    window,
+                    positioned.position.width,
+                    positioned.position.height,
+                    depth,
+                )?,
+                None => None,
+            };
+            entries.push(SwitcherEntry {
+                window,
+                title,
+                thumbnail,
+            });
+        }
+
+        let area = self.active_area();
+        self.switcher = Some(Switcher::show(
+            self.conn.display_mut(),
+            root,
+            area,
+            entries,
+            release_keys,
+        )?);
+        self.allow_switcher_key()
+    }
+
+    /// Let the server send the next queued key event while the switcher is open
+    fn allow_switcher_key(&mut self) -> Result<(), Error> {
+        self.conn
+            .display_mut()
+            .send_request(&requests::AllowEvents {
+                mode: AllowEventsMode::SyncKeyboard,
+                time: 0,
+            })?;
+        Ok(())
+    }
+
+    /// Hide the switcher and release the keyboard, key events queued by the server meanwhile
+    /// go to the activated window
+    fn end_switcher(&mut self, activate: bool) -> Result<(), Error> {
+        let switcher = self.switcher.take().expect("switcher is not open");
+        let selected = switcher.selected();
+        switcher.hide(self.conn.display_mut())?;
+        if let Some(window) = selected.filter(|_| activate) {
+            self.jump_to_window(window)?;
+        }
+        self.conn.display_mut().ungrab_keyboard()?;
+        Ok(())
+    }
+
+    /// Show [`Overview`] of windows on the active workspace and grab the keyboard
+    fn start_overview(&mut self, time: u32) -> Result<(), Error> {
+        let windows = self
+            .windows
+            .on_workspace(self.active_workspace)
+            .into_iter()
+            .filter(|window| {
+                self.windows
+                    .get(*window)
+                    .is_some_and(|state| state.placement != Placement::Iconified)
+            })
+            .collect::<Vec<_>>();
+        if windows.is_empty() {
+            return Ok(());
+        }
+
+        let root = self.root_window();
+        let display = self.conn.display_mut();
+        match display.grab_keyboard(&requests::GrabKeyboard {
+            owner_events: false,
+            grab_window: root,
+            time: Timestamp::from(time),
+            pointer_mode: GrabMode::Asynchronous,
+            keyboard_mode: GrabMode::Asynchronous,
+        })? {
+            // Held until the overview closes, see `Self::end_overview`
+            Ok(grab) => grab.leak(),
+            Err(_) => return Ok(()),
+        }
+
+        let mut entries = Vec::with_capacity(windows.len());
+        for window in windows {
+            let title = self.conn.get_wm_name(window)?.unwrap_or_default();
+            let geometry = self.conn.get_window_geometry(window)?;
+            entries.push(OverviewEntry {
+                window,
+                title,
+                size: (geometry.width, geometry.height),
+            });
+        }
+
+        let area = self.active_area();
+        let display = self.conn.display_mut();
+        let depth = display.default_screen().root_depth;
+        self.overview = Some(Overview::show(display, root, area, entries, depth)?);
+        self.conn.flush()?;
+        Ok(())
+    }
+
+    /// Hide the overview, release the keyboard and jump to `activate` if it is chosen
+    fn end_overview(&mut self, activate: Option<WindowId>) -> Result<(), Error> {
+        let overview = self.overview.take().expect("overview is not open");
+        overview.hide(self.conn.display_mut())?;
+        self.conn.display_mut().ungrab_keyboard()?;
+        if let Some(window) = activate {
+            self.jump_to_window(window)?;
+        }
+        self.conn.flush()?;
+        Ok(())
+    }
+
+    /// Start dragging `child` of `root` if it is tiled, see [`drag`]
+    fn start_drag(&mut self, root: WindowId, child: OrNone<WindowId>) -> Result<(), Error> {
+        let Some(window) = child
+            .value()
+            .filter(|window| self.windows.phase(*window) == WindowPhase::Managed(Placement::Tiled))
+        else {
+            return Ok(());
+        };
+        let display = self.conn.display_mut();
+        if let Some(drag) = self.drag.take() {
+            drag.finish(display)?;
+        }
+        self.drag = Some(TileDrag::start(display, root, window)?);
+        Ok(())
+    }
+
+    /// Outline the tile under the pointer at `position` as the drop target
+    fn update_drag(&mut self, position: (i16, i16)) -> Result<(), Error> {
+        let Some(drag) = self.drag.as_mut() else {
+            return Ok(());
+        };
+        let tiles = self
+            .screens
+            .iter()
+            .flat_map(|screen| {
+                self.windows
+                    .with_placement(screen.workspace_idx, Placement::Tiled)
+            })
+            .filter(|window| *window != drag.window)
+            .filter_map(|window| {
+                let positioned = self.windows.get(window)?.geometry?;
+                Some((window, drag::tile_area(positioned)))
+            })
+            .collect::<Vec<_>>();
+        let target = drag::tile_at(&tiles, position)
+            .and_then(|target| tiles.iter().find(|(window, _)| *window == target).copied());
+        drag.set_target(self.conn.display_mut(), target)
+    }
+
+    /// Swap the dragged window with the tile it was dropped on
+    fn end_drag(&mut self) -> Result<(), Error> {
+        let drag = self.drag.take().expect("no window is dragged");
+        if let Some((window, target)) = drag.finish(self.conn.display_mut())? {
+            self.windows.swap(window, target);
+            self.active_window = Some(window);
+            self.arrange_windows()?;
+        }
+        Ok(())
+    }
+
+    /// Move the active window to the front of [`Self::focus_history`]
+    fn track_focus(&mut self) {
+        let windows = &self.windows;
+        self.focus_history
+            .retain(|window| windows.is_managed(*window));
+        if let Some(active) = self
+            .active_window
+            .filter(|window| windows.is_managed(*window))
+        {
+            if self.focus_history.first() != Some(&active) {
+                self.focus_history.retain(|window| *window != active);
+                self.focus_history.insert(0, active);
+            }
+        }
+    }
+
+    /// Kill clients that did not close their windows in time after [`JustAction::KillActive`]
+    fn kill_unresponsive(&mut self) -> Result<(), Error> {
+        let now = Instant::now();
+        let mut killed = false;
+        for (window, deadline) in mem::take(&mut self.pending_kills) {
+            if now < deadline {
+                self.pending_kills.push((window, deadline));
+                continue;
+            }
+            eprintln!(
+                "justwindows: {:?} did not close in {:?}, killing its client",
+                window, KILL_TIMEOUT
+            );
+            self.conn.kill_client(KillClientResource::from(window))?;
+            killed = true;
+        }
+        if killed {
+            self.conn.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Process id from `_NET_WM_PID` if `WM_CLIENT_MACHINE` of `window` is this machine, process
+    /// ids of other machines must not be used for local processes
+    fn local_wm_pid(&mut self, window: WindowId) -> Result<Option<u32>, Error> {
+        let Some(machine) = self.conn.get_wm_client_machine(window)? else {
+            return Ok(None);
+        };
+        match priority::hostname() {
+            Ok(hostname) if hostname == machine => self.conn.get_wm_pid(window),
+            Ok(_) => Ok(None),
+            Err(err) => {
+                eprintln!("justwindows: Could not get hostname: {}", err);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Whether `window` is in game mode or asked to be fullscreen with `_NET_WM_STATE`
+    fn is_fullscreen(&mut self, window: WindowId) -> Result<bool, Error> {
+        if self
+            .windows
+            .get(window)
+            .is_some_and(|state| state.placement == Placement::Fullscreen)
+        {
+            return Ok(true);
+        }
+        let Some(inhibitor) = &self.idle_inhibitor else {
+            return Ok(false);
+        };
+        if let Some((cached, fullscreen)) = inhibitor.fullscreen_state {
+            if cached == window {
+                return Ok(fullscreen);
+            }
+        }
+
+        let net_wm_state_fullscreen = self
+            .conn
+            .get_atom_id(String8::from_str("_NET_WM_STATE_FULLSCREEN").unwrap())?;
+        let fullscreen = self
+            .conn
+            .get_net_wm_state(window)?
+            .contains(&net_wm_state_fullscreen);
+        if let Some(inhibitor) = &mut self.idle_inhibitor {
+            inhibitor.fullscreen_state = Some((window, fullscreen));
+        }
+        Ok(fullscreen)
+    }
+
+    /// Keep the screen saver and DPMS from blanking the screen while the active window is
+    /// fullscreen, see [`idle`]
+    fn inhibit_idle(&mut self) -> Result<(), Error> {
+        if self.idle_inhibitor.is_none() {
+            return Ok(());
+        }
+        let fullscreen = match self.active_window {
+            Some(window) if self.windows.is_managed(window) => self.is_fullscreen(window)?,
+            _ => false,
+        };
+        let Some(inhibitor) = &mut self.idle_inhibitor else {
+            return Ok(());
+        };
+        if inhibitor.should_reset(fullscreen, Instant::now()) {
+            let display = self.conn.display_mut();
+            display.send_request(&requests::ForceScreenSaver {
+                mode: ForceScreenSaverMode::Reset,
+            })?;
+            display.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Cancel the pending chord if no key was pressed in time
+    fn expire_chord(&mut self) -> Result<(), Error> {
+        if self
+            .pending_chord
+            .as_ref()
+            .is_some_and(|pending| Instant::now() >= pending.deadline)
+        {
+            self.end_chord()?;
+        }
+        Ok(())
+    }
+
+    fn handle_event(&mut self, event: SomeEvent) -> Result<(), Error> {
+        self.rescreen(&event)?;
+
+        match event {
+            SomeEvent::ConfigureRequest(event) => {
+                let attributes = ConfigureWindowAttributes::from(&event);
+                self.conn
                     .display_mut()
                     .send_request(&requests::ConfigureWindow {
                         window: event.window,
                         attributes,
                     })?;
                 self.set_initial_window_properties(event.window)?;
+                // Client changed its geometry on its own so layout has to be sent again
+                if let Some(state) = self.windows.get_mut(event.window) {
+                    state.geometry = None;
+                }
             }
             SomeEvent::MapRequest(event) => {
+                // New window would cover the overview
+                if self.overview.is_some() {
+                    self.end_overview(None)?;
+                }
+                // New window would be shown alone on the desktop
+                if !self.windows.is_managed(event.window) {
+                    self.restore_desktop(self.active_workspace)?;
+                }
+                self.manage_window(event.window)?;
+                // Moved before mapping to not show it at the original position first
+                if self.windows.phase(event.window) == WindowPhase::Managed(Placement::Floating) {
+                    self.place_floating_window(event.window)?;
+                }
                 self.conn.display_mut().send_request(&requests::MapWindow {
                     window: event.window,
                 })?;
-                self.manage_window(event.window)?;
                 self.arrange_windows()?;
                 // self.conn.set_focus(event.window)?;
                 self.conn.flush()?;
             }
             SomeEvent::DestroyNotify(event) => {
-                if self.is_client(event.window) {
+                self.pending_kills
+                    .retain(|(window, _)| *window != event.window);
+                if self.windows.is_managed(event.window) {
                     self.unmanage_window(event.window)?;
                 }
             }
+            SomeEvent::SelectionClear(event)
+                if self
+                    .wm_selections
+                    .iter()
+                    .any(|selection| selection.is_cleared_by(&event)) =>
+            {
+                eprintln!("justwindows: Replaced by other window manager, exiting");
+                for selection in self.wm_selections.drain(..) {
+                    selection.release(&mut self.conn)?;
+                }
+                self.conn.flush()?;
+                process::exit(0);
+            }
+            SomeEvent::ClientMessage(event)
+                if event.type_message
+                    == self
+                        .conn
+                        .get_atom_id(String8::from_str("_NET_SHOWING_DESKTOP").unwrap())? =>
+            {
+                let showing = event.data[..4] != [0; 4];
+                if showing
+                    != self.workspaces[self.active_workspace]
+                        .shown_desktop
+                        .is_some()
+                {
+                    self.toggle_show_desktop()?;
+                }
+            }
+            SomeEvent::ClientMessage(event)
+                if event.type_message
+                    == self
+                        .conn
+                        .get_atom_id(String8::from_str("_NET_CURRENT_DESKTOP").unwrap())? =>
+            {
+                let desktop = u32::from_le_bytes(event.data[..4].try_into().unwrap());
+                self.show_numbered_workspace(desktop as usize)?;
+            }
+            SomeEvent::ClientMessage(event)
+                if event.type_message
+                    == self
+                        .conn
+                        .get_atom_id(String8::from_str("_NET_WM_DESKTOP").unwrap())? =>
+            {
+                // Sticky windows (0xffffffff) are not supported and the request is ignored
+                let desktop = u32::from_le_bytes(event.data[..4].try_into().unwrap());
+                self.move_window_to_workspace(event.window, desktop as usize)?;
+            }
+            SomeEvent::ClientMessage(event)
+                if event.type_message
+                    == self
+                        .conn
+                        .get_atom_id(String8::from_str("_NET_WM_STATE").unwrap())? =>
+            {
+                // Action followed by up to two changed states, only fullscreen is supported
+                let word = |idx: usize| {
+                    u32::from_le_bytes(event.data[idx * 4..idx * 4 + 4].try_into().unwrap())
+                };
+                let net_wm_state_fullscreen = self
+                    .conn
+                    .get_atom_id(String8::from_str("_NET_WM_STATE_FULLSCREEN").unwrap())?;
+                if [word(1), word(2)]
+                    .into_iter()
+                    .any(|state| AtomId::unchecked_from(state) == net_wm_state_fullscreen)
+                {
+                    self.change_fullscreen(event.window, word(0))?;
+                }
+            }
             SomeEvent::ClientMessage(event) => {
                 dbg!(event);
             }
@@ -355,6 +2626,9 @@ impl JustWindows {
                 let root = self.root_window();
                 if event.event != root {
                     self.active_window = Some(event.event);
+                    if let Some(state) = self.windows.get_mut(event.event) {
+                        state.urgent = false;
+                    }
                     self.arrange_windows()?;
                 } else {
                     dbg!(event.event);
@@ -366,48 +2640,245 @@ impl JustWindows {
                     // self.conn.set_focus(root)?;
                 }
             }
-            SomeEvent::ConfigureNotify(event) => {
-                let root = self.root_window();
-                if event.window == root {
-                    self.rescreen()?;
+            SomeEvent::KeyPress(event) if self.switcher.is_some() => {
+                let key_sym = self
+                    .bindings
+                    .key_symbols
+                    .get_keysym(event.detail, KeySymColumn::Column0);
+                if key_sym == KeySym::Escape {
+                    self.end_switcher(false)?;
+                } else if key_sym == KeySym::Return {
+                    self.end_switcher(true)?;
+                } else {
+                    if key_sym == KeySym::Tab || key_sym == KeySym::ISO_Left_Tab {
+                        let backwards =
+                            key_sym == KeySym::ISO_Left_Tab || event.state.has(KeyModifier::SHIFT);
+                        if let Some(switcher) = self.switcher.as_mut() {
+                            switcher.step(self.conn.display_mut(), backwards)?;
+                        }
+                    }
+                    self.allow_switcher_key()?;
+                }
+            }
+            SomeEvent::KeyRelease(event) if self.switcher.is_some() => {
+                if self
+                    .switcher
+                    .as_ref()
+                    .is_some_and(|switcher| switcher.is_release_key(event.detail))
+                {
+                    self.end_switcher(true)?;
+                } else {
+                    self.allow_switcher_key()?;
+                }
+            }
+            SomeEvent::Expose(event)
+                if event.count == 0
+                    && self
+                        .switcher
+                        .as_ref()
+                        .is_some_and(|switcher| switcher.is_popup(event.window)) =>
+            {
+                if let Some(switcher) = &self.switcher {
+                    switcher.draw(self.conn.display_mut())?;
+                }
+            }
+            SomeEvent::KeyPress(event) if self.overview.is_some() => {
+                let key_sym = self
+                    .bindings
+                    .key_symbols
+                    .get_keysym(event.detail, KeySymColumn::Column0);
+                if key_sym == KeySym::Return {
+                    let hovered = self.overview.as_ref().and_then(Overview::hovered);
+                    self.end_overview(hovered)?;
+                } else if key_sym == KeySym::Escape
+                    || matches!(
+                        self.bindings.get_action(event.detail, event.state),
+                        Some(JustAction::ToggleOverview)
+                    )
+                {
+                    self.end_overview(None)?;
+                }
+            }
+            SomeEvent::Expose(event)
+                if event.count == 0
+                    && self
+                        .overview
+                        .as_ref()
+                        .is_some_and(|overview| overview.is_popup(event.window)) =>
+            {
+                if let Some(overview) = &self.overview {
+                    overview.draw(self.conn.display_mut())?;
+                }
+            }
+            SomeEvent::ButtonPress(event)
+                if self
+                    .overview
+                    .as_ref()
+                    .is_some_and(|overview| overview.is_popup(event.event)) =>
+            {
+                let clicked = self
+                    .overview
+                    .as_ref()
+                    .and_then(|overview| overview.window_at((event.event_x, event.event_y)));
+                self.end_overview(clicked)?;
+            }
+            SomeEvent::MotionNotify(event)
+                if self
+                    .overview
+                    .as_ref()
+                    .is_some_and(|overview| overview.is_popup(event.event)) =>
+            {
+                if let Some(overview) = self.overview.as_mut() {
+                    overview.hover(self.conn.display_mut(), (event.event_x, event.event_y))?;
+                }
+            }
+            SomeEvent::KeyPress(event) if self.pending_chord.is_some() => {
+                let key_sym = self
+                    .bindings
+                    .key_symbols
+                    .get_keysym(event.detail, KeySymColumn::Column0);
+                // Modifiers held while pressing the key after the prefix
+                if !KeySymbols::is_modifier_key(key_sym) {
+                    let chord = self.end_chord()?;
+                    if let Some(action) = self.bindings.chord_action(chord, event.detail) {
+                        self.run_action(action, event.time)?;
+                    }
                 }
             }
             SomeEvent::KeyPress(event) => {
-                if let Some(event) = self.bindings.get_action(event.detail) {
-                    match event {
-                        JustAction::KillActive => {
-                            if let Some(active) = self.active_window {
-                                self.unmanage_window(active)?;
-                                self.conn.kill_window(active)?;
-                                self.active_window = None;
-                            }
-                        }
-                        JustAction::Term => {
-                            self.spawn("xterm")?;
-                        }
+                if let Some(action) = self.bindings.get_action(event.detail, event.state) {
+                    self.run_action(action, event.time)?;
+                }
+            }
+            SomeEvent::MappingNotify(event) => {
+                if !matches!(event.request, MappingNotifyRequest::Pointer) {
+                    self.bindings.regrab(self.conn.display_mut())?;
+                }
+            }
+            SomeEvent::PropertyNotify(event)
+                if event.atom == AtomId::WM_HINTS
+                    && event.state == PropertyNotifyState::NewValue =>
+            {
+                let urgent = self.conn.get_wm_urgency(event.window)?;
+                let is_active = self.active_window == Some(event.window);
+                if let Some(state) = self.windows.get_mut(event.window) {
+                    if state.urgent != (urgent && !is_active) {
+                        state.urgent = urgent && !is_active;
+                        self.arrange_windows()?;
+                    }
+                }
+            }
+            SomeEvent::PropertyNotify(event)
+                if event.atom == AtomId::WM_NORMAL_HINTS
+                    && self.windows.is_managed(event.window) =>
+            {
+                let min_size = self.get_min_size(event.window)?;
+                if let Some(state) = self.windows.get_mut(event.window) {
+                    if state.min_size != min_size {
+                        state.min_size = min_size;
+                        self.arrange_windows()?;
                     }
                 }
             }
+            SomeEvent::PropertyNotify(event)
+                if self.idle_inhibitor.as_ref().is_some_and(|inhibitor| {
+                    inhibitor
+                        .fullscreen_state
+                        .is_some_and(|(window, _)| window == event.window)
+                }) && event.atom
+                    == self
+                        .conn
+                        .get_atom_id(String8::from_str("_NET_WM_STATE").unwrap())? =>
+            {
+                if let Some(inhibitor) = &mut self.idle_inhibitor {
+                    inhibitor.fullscreen_state = None;
+                }
+            }
+            SomeEvent::PropertyNotify(event)
+                if self.status.is_some()
+                    && self
+                        .focused_title
+                        .as_ref()
+                        .is_some_and(|(window, _)| *window == event.window)
+                    && (event.atom == AtomId::WM_NAME
+                        || event.atom
+                            == self
+                                .conn
+                                .get_atom_id(String8::from_str("_NET_WM_NAME").unwrap())?) =>
+            {
+                self.focused_title = None;
+            }
+            SomeEvent::ButtonPress(event)
+                if event.detail.raw() == drag::DRAG_BUTTON
+                    && self.screens.iter().any(|screen| screen.root == event.event) =>
+            {
+                self.start_drag(event.event, event.child)?;
+            }
+            SomeEvent::MotionNotify(event) if self.drag.is_some() => {
+                self.update_drag((event.root_x, event.root_y))?;
+            }
+            SomeEvent::ButtonRelease(event)
+                if self.drag.is_some() && event.detail.raw() == drag::DRAG_BUTTON =>
+            {
+                self.end_drag()?;
+            }
+            SomeEvent::ButtonPress(event) if self.click_to_raise => {
+                // Focus is left to follow the pointer
+                if self.windows.is_managed(event.event) {
+                    self.conn
+                        .display_mut()
+                        .send_request(&requests::ConfigureWindow {
+                            window: event.event,
+                            attributes: ConfigureWindowAttributes::new()
+                                .set_stack_mode(StackMode::Above),
+                        })?;
+                }
+                self.conn
+                    .display_mut()
+                    .send_request(&requests::AllowEvents {
+                        mode: AllowEventsMode::ReplayPointer,
+                        time: event.time,
+                    })?;
+            }
             SomeEvent::MapNotify(_)
+            | SomeEvent::ConfigureNotify(_)
             | SomeEvent::CreateNotify(_)
             | SomeEvent::UnmapNotify(_)
-            | SomeEvent::MappingNotify(_)
             | SomeEvent::PropertyNotify(_)
             | SomeEvent::KeyRelease(_)
-            | SomeEvent::ButtonPress(_) => {}
+            | SomeEvent::ButtonPress(_)
+            | SomeEvent::ButtonRelease(_)
+            | SomeEvent::MotionNotify(_) => {}
             _ => {
                 dbg!(event);
             }
         }
 
+        self.track_focus();
+        self.remove_unused_workspaces();
+        self.publish_workspaces()?;
+        self.publish_desktops()?;
+        self.publish_client_list()?;
+        self.publish_status()?;
         self.conn.flush()?;
         Ok(())
     }
 }
 
-pub fn go() -> Result<(), Error> {
-    let mut wm = JustWindows::setup()?;
+fn go(args: Args) -> Result<(), Error> {
+    let display = match x11_display::resolve(args.display.as_deref()) {
+        Ok(display) => display,
+        Err(err) => {
+            eprintln!("justwindows: error: {}", err);
+            process::exit(1);
+        }
+    };
+    let mut wm = JustWindows::setup(display, &args)?;
     wm.restore_windows()?;
+    wm.publish_status()?;
+    if !args.nested {
+        wm.autostart()?;
+    }
 
     // wm.spawn("xterm")?;
     // wm.spawn("xterm")?;
@@ -418,7 +2889,12 @@ pub fn go() -> Result<(), Error> {
         for error in wm.conn.display_mut().errors() {
             match error {
                 SomeError::Access(error) => {
-                    panic!("justwindows: Other window manager is running: {:?}", error)
+                    // Window manager not supporting WM_Sn selection still holds the redirect
+                    eprintln!(
+                        "justwindows: error: Other window manager is running: {:?}",
+                        error
+                    );
+                    process::exit(1);
                 }
                 _ => {
                     dbg!(error);
@@ -428,13 +2904,33 @@ pub fn go() -> Result<(), Error> {
         }
 
         while let Some(event) = wm.conn.display_mut().next_event()? {
+            let name = event.name();
+            let start = wm.handler_times.is_some().then(Instant::now);
             wm.handle_event(event)?;
+            if let (Some(times), Some(start)) = (wm.handler_times.as_mut(), start) {
+                times.record(name, start.elapsed());
+            }
         }
+        wm.expire_chord()?;
+        wm.expire_notification()?;
+        wm.reap_children()?;
+        wm.kill_unresponsive()?;
+        wm.inhibit_idle()?;
+        wm.accept_status_clients();
+        wm.answer_status_queries();
     }
 }
 
 fn main() {
-    match go() {
+    let args = match Args::parse(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(2);
+        }
+    };
+
+    match go(args) {
         Ok(()) => {}
         Err(err) => {
             eprintln!("justwindows: error: {}", err);
@@ -0,0 +1,322 @@
+//! Workspace overview, the modifier with `e` shows thumbnails of all windows of the active
+//! workspace in a grid covering its monitor. Clicking a thumbnail or pressing `Return` over it
+//! activates its window, clicking elsewhere, `Escape` or the binding again closes the overview.
+//!
+//! Thumbnails are captured with [`Thumbnail::capture_fit`] before the overview is mapped over
+//! the windows, so they are taken once and don't update while it is open. Like in the window
+//! switcher, parts of windows covered by other windows are undefined without a compositor.
+//!
+//! TODO: Live thumbnails need window contents while the overview covers them, i.e. off-screen
+//! storage from the Composite extension which is not implemented yet.
+
+use crate::switcher::Thumbnail;
+use just_x11::{
+    error::Error,
+    events::EventType,
+    replies,
+    requests::{self, GContextSettings, WindowCreationAttributes},
+    Drawable, FontId, GContextId, Rectangle, WindowClass, WindowId, WindowVisual, XDisplay,
+};
+use just_x11_simple::panic_hook;
+
+const OVERVIEW_FOREGROUND: u32 = 0xeeeeee;
+const OVERVIEW_BACKGROUND: u32 = 0x111111;
+/// Space around the grid and between cells
+const OVERVIEW_PADDING: u16 = 24;
+/// Width of the outline around the cell under the pointer
+const HOVER_WIDTH: u16 = 3;
+
+/// Window shown in the overview with its size
+pub struct OverviewEntry {
+    pub window: WindowId,
+    pub title: String,
+    pub size: (u16, u16),
+}
+
+/// Cells of `count` thumbnails in a grid filling `width` x `height`, row by row from the top
+/// with the last row centered
+pub fn grid(count: usize, width: u16, height: u16) -> Vec<Rectangle> {
+    if count == 0 {
+        return Vec::new();
+    }
+    let columns = (1..).find(|columns| columns * columns >= count).unwrap();
+    let rows = count.div_ceil(columns);
+    let cell_width =
+        (width.saturating_sub(OVERVIEW_PADDING) / columns as u16).saturating_sub(OVERVIEW_PADDING);
+    let cell_height =
+        (height.saturating_sub(OVERVIEW_PADDING) / rows as u16).saturating_sub(OVERVIEW_PADDING);
+
+    (0..count)
+        .map(|idx| {
+            let (row, column) = (idx / columns, idx % columns);
+            let in_row = if row == rows - 1 {
+                count - row * columns
+            } else {
+                columns
+            };
+            let offset = (columns - in_row) as u16 * (cell_width + OVERVIEW_PADDING) / 2;
+            Rectangle {
+                x: (OVERVIEW_PADDING + offset + column as u16 * (cell_width + OVERVIEW_PADDING))
+                    as i16,
+                y: (OVERVIEW_PADDING + row as u16 * (cell_height + OVERVIEW_PADDING)) as i16,
+                width: cell_width,
+                height: cell_height,
+            }
+        })
+        .collect()
+}
+
+fn contains(rectangle: Rectangle, (x, y): (i16, i16)) -> bool {
+    x >= rectangle.x
+        && y >= rectangle.y
+        && x < rectangle.x + rectangle.width as i16
+        && y < rectangle.y + rectangle.height as i16
+}
+
+struct Cell {
+    window: WindowId,
+    title: String,
+    /// Whole cell including the title line, hit by clicks
+    area: Rectangle,
+    thumbnail: Option<Thumbnail>,
+}
+
+/// Override redirect window covering a monitor with thumbnails of windows
+pub struct Overview {
+    cells: Vec<Cell>,
+    /// Index of the cell under the pointer
+    hovered: Option<usize>,
+    window: WindowId,
+    gc: GContextId,
+    /// Draws with the background color, erases outline of the previously hovered cell
+    erase_gc: GContextId,
+    /// `None` without the `fixed` font, titles are not drawn then
+    font: Option<(FontId, replies::QueryFont)>,
+}
+
+impl Overview {
+    /// Capture thumbnails of `entries` with `depth` and show them over `area`
+    pub fn show(
+        display: &mut XDisplay,
+        root: WindowId,
+        area: Rectangle,
+        entries: Vec<OverviewEntry>,
+        depth: u8,
+    ) -> Result<Self, Error> {
+        let font = FontId::from_resource(display.id_allocator().allocate_id());
+        display.send_request(&requests::OpenFont {
+            fid: font,
+            name: b"fixed".to_vec(),
+        })?;
+        let pending = display.send_request(&requests::QueryFont { font })?;
+        display.flush()?;
+        let font = display
+            .await_pending_reply(pending)?
+            .ok()
+            .map(|metrics| (font, metrics));
+        let line_height = font.as_ref().map_or(0, |(_, metrics)| {
+            (metrics.font_ascent + metrics.font_descent).max(1) as u16
+        });
+
+        let areas = grid(entries.len(), area.width, area.height);
+        let mut cells = Vec::with_capacity(entries.len());
+        for (entry, cell) in entries.into_iter().zip(areas) {
+            let max_size = (cell.width, cell.height.saturating_sub(line_height));
+            let thumbnail =
+                Thumbnail::capture_fit(display, entry.window, entry.size, depth, max_size)?;
+            cells.push(Cell {
+                window: entry.window,
+                title: entry.title,
+                area: cell,
+                thumbnail,
+            });
+        }
+
+        let window = WindowId::from_resource(display.id_allocator().allocate_id());
+        display.send_request(&requests::CreateWindow {
+            depth: 0,
+            wid: window,
+            parent: root,
+            x: area.x,
+            y: area.y,
+            width: area.width,
+            height: area.height,
+            border_width: 0,
+            window_class: WindowClass::InputOutput,
+            visual: WindowVisual::CopyFromParent,
+            attributes: WindowCreationAttributes::override_redirect_popup()
+                .set_background_pixel(OVERVIEW_BACKGROUND)
+                .set_event_mask(
+                    EventType::EXPOSURE | EventType::BUTTON_PRESS | EventType::POINTER_MOTION,
+                ),
+        })?;
+        panic_hook::destroy_on_panic(window);
+
+        let gc = GContextId::from_resource(display.id_allocator().allocate_id());
+        let mut values = GContextSettings::new()
+            .set_foreground(OVERVIEW_FOREGROUND)
+            .set_background(OVERVIEW_BACKGROUND)
+            .set_graphics_exposures(false);
+        if let Some((font, _)) = font {
+            values = values.set_font(font);
+        }
+        display.send_request(&requests::CreateGC {
+            cid: gc,
+            drawable: Drawable::Window(window),
+            values,
+        })?;
+        let erase_gc = GContextId::from_resource(display.id_allocator().allocate_id());
+        display.send_request(&requests::CreateGC {
+            cid: erase_gc,
+            drawable: Drawable::Window(window),
+            values: GContextSettings::new()
+                .set_foreground(OVERVIEW_BACKGROUND)
+                .set_graphics_exposures(false),
+        })?;
+        display.send_request(&requests::MapWindow { window })?;
+
+        Ok(Self {
+            cells,
+            hovered: None,
+            window,
+            gc,
+            erase_gc,
+            font,
+        })
+    }
+
+    pub fn is_popup(&self, window: WindowId) -> bool {
+        self.window == window
+    }
+
+    /// Window of the cell at `position` relative to the overview
+    pub fn window_at(&self, position: (i16, i16)) -> Option<WindowId> {
+        self.cells
+            .iter()
+            .find(|cell| contains(cell.area, position))
+            .map(|cell| cell.window)
+    }
+
+    /// Window of the cell under the pointer
+    pub fn hovered(&self) -> Option<WindowId> {
+        self.hovered.map(|idx| self.cells[idx].window)
+    }
+
+    /// Outline the cell under the pointer at `position` relative to the overview
+    pub fn hover(&mut self, display: &mut XDisplay, position: (i16, i16)) -> Result<(), Error> {
+        let hovered = self
+            .cells
+            .iter()
+            .position(|cell| contains(cell.area, position));
+        if hovered == self.hovered {
+            return Ok(());
+        }
+        if let Some(previous) = self.hovered {
+            self.draw_outline(display, previous, self.erase_gc)?;
+        }
+        self.hovered = hovered;
+        if let Some(hovered) = hovered {
+            self.draw_outline(display, hovered, self.gc)?;
+        }
+        Ok(())
+    }
+
+    fn draw_outline(
+        &self,
+        display: &mut XDisplay,
+        idx: usize,
+        gc: GContextId,
+    ) -> Result<(), Error> {
+        let area = self.cells[idx].area;
+        // One pixel wide rectangles, growing outwards from the cell
+        let rectangles = (1..=HOVER_WIDTH)
+            .map(|offset| Rectangle {
+                x: area.x - offset as i16,
+                y: area.y - offset as i16,
+                width: area.width + offset * 2 - 1,
+                height: area.height + offset * 2 - 1,
+            })
+            .collect();
+        display.send_request(&requests::PolyRectangle {
+            drawable: Drawable::Window(self.window),
+            gc,
+            rectangles,
+        })?;
+        Ok(())
+    }
+
+    /// Draw all thumbnails, titles and the hover outline, e.g. after the overview was exposed
+    pub fn draw(&self, display: &mut XDisplay) -> Result<(), Error> {
+        let line_height = self.font.as_ref().map_or(0, |(_, metrics)| {
+            (metrics.font_ascent + metrics.font_descent).max(1) as u16
+        });
+        for cell in &self.cells {
+            let thumbnail_area = Rectangle {
+                height: cell.area.height.saturating_sub(line_height),
+                ..cell.area
+            };
+            if let Some(thumbnail) = &cell.thumbnail {
+                thumbnail.put(display, self.window, self.gc, thumbnail_area)?;
+            }
+
+            if let Some((_, metrics)) = &self.font {
+                let char_width = metrics.max_bounds.character_width.max(1) as usize;
+                // Text of `ImageText8` is Latin-1
+                let title = cell
+                    .title
+                    .chars()
+                    .map(|c| if c.is_ascii() { c as u8 } else { b'?' })
+                    .take(cell.area.width as usize / char_width)
+                    .collect::<Vec<_>>();
+                let title_width = (title.len() * char_width) as i16;
+                display.send_request(&requests::ImageText8 {
+                    drawable: Drawable::Window(self.window),
+                    gc: self.gc,
+                    x: cell.area.x + (cell.area.width as i16 - title_width) / 2,
+                    y: cell.area.y + thumbnail_area.height as i16 + metrics.font_ascent,
+                    string: title,
+                })?;
+            }
+        }
+        if let Some(hovered) = self.hovered {
+            self.draw_outline(display, hovered, self.gc)?;
+        }
+        Ok(())
+    }
+
+    pub fn hide(self, display: &mut XDisplay) -> Result<(), Error> {
+        display.send_request(&requests::DestroyWindow {
+            window: self.window,
+        })?;
+        panic_hook::forget_on_panic(self.window);
+        display.send_request(&requests::FreeGC { gc: self.gc })?;
+        display.send_request(&requests::FreeGC { gc: self.erase_gc })?;
+        if let Some((font, _)) = self.font {
+            display.send_request(&requests::CloseFont { font })?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn overview_grid() {
+    assert!(grid(0, 1000, 600).is_empty());
+
+    let cells = grid(1, 1000, 600);
+    assert_eq!(
+        (cells[0].x, cells[0].y, cells[0].width, cells[0].height),
+        (24, 24, 952, 552)
+    );
+
+    // Three columns, two rows with the last one centered
+    let cells = grid(5, 1000, 600);
+    assert_eq!(cells.len(), 5);
+    assert_eq!((cells[2].x, cells[2].y), (24 + 2 * 325, 24));
+    assert_eq!((cells[3].x, cells[3].y), (24 + 162, 24 + 288));
+    assert!(cells
+        .iter()
+        .all(|cell| cell.width == 301 && cell.height == 264));
+
+    assert!(contains(cells[0], (24, 24)));
+    assert!(!contains(cells[0], (24 + 301, 24)));
+}
@@ -0,0 +1,101 @@
+//! Workspace state kept in the `_JUSTWINDOWS_WORKSPACES` property of the root window, so layouts
+//! survive restarting or replacing the window manager
+//!
+//! JSON array with one object per workspace, with managed windows in tiling order:
+//!
+//! ```text
+//! [{"index":0,"layout":"master-stack","master_ratio":0.65,"windows":[4194313]}]
+//! ```
+
+use crate::layout::LayoutKind;
+use just_x11::{
+    impl_from_json_struct, impl_to_json_struct,
+    json::{self, FromJson, JsonError, JsonValue, ToJson},
+};
+use std::{fmt, str::FromStr};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavedWorkspace {
+    pub index: usize,
+    pub layout: LayoutKind,
+    pub master_ratio: f32,
+    pub windows: Vec<u32>,
+}
+
+impl_to_json_struct!(SavedWorkspace {
+    index,
+    layout,
+    master_ratio,
+    windows,
+});
+
+impl_from_json_struct!(SavedWorkspace {
+    index,
+    layout,
+    master_ratio,
+    windows,
+});
+
+impl ToJson for LayoutKind {
+    fn write_json(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        json::write_json_str(w, &self.to_string())
+    }
+}
+
+impl FromJson for LayoutKind {
+    fn from_json(value: &JsonValue) -> Result<Self, JsonError> {
+        LayoutKind::from_str(&String::from_json(value)?)
+            .map_err(|_| JsonError::Expected("LayoutKind"))
+    }
+}
+
+pub fn write(workspaces: &[SavedWorkspace]) -> String {
+    workspaces.to_json()
+}
+
+/// Workspaces in `data`, workspaces that can't be decoded e.g. because they were written by other
+/// version are skipped
+pub fn parse(data: &str) -> Vec<SavedWorkspace> {
+    match JsonValue::from_str(data) {
+        Ok(JsonValue::Array(workspaces)) => workspaces
+            .iter()
+            .filter_map(|workspace| SavedWorkspace::from_json(workspace).ok())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[test]
+fn saved_workspaces() {
+    let workspaces = vec![
+        SavedWorkspace {
+            index: 0,
+            layout: LayoutKind::MasterStack,
+            master_ratio: 0.65,
+            windows: vec![4194313, 4194320],
+        },
+        SavedWorkspace {
+            index: 1,
+            layout: LayoutKind::Stack,
+            master_ratio: 0.5,
+            windows: Vec::new(),
+        },
+    ];
+    let data = write(&workspaces);
+    assert_eq!(
+        data,
+        r#"[{"index":0,"layout":"master-stack","master_ratio":0.65,"windows":[4194313,4194320]},{"index":1,"layout":"stack","master_ratio":0.5,"windows":[]}]"#
+    );
+    assert_eq!(parse(&data), workspaces);
+
+    assert_eq!(
+        parse(
+            r#"[{"index":0,"layout":"spiral","master_ratio":0.5,"windows":[1]},
+                {"index":1,"layout":"stack","master_ratio":"x","windows":[]},
+                {"index":2,"layout":"stack","master_ratio":0.5,"windows":[1,2]}]"#
+        )
+        .len(),
+        1
+    );
+    assert!(parse("0 stack 0.5 1\n").is_empty());
+}
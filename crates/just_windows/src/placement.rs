@@ -0,0 +1,181 @@
+//! Initial position of floating windows whose clients did not request one
+
+use just_geometry::Rect;
+use just_x11::Rectangle;
+use std::str::FromStr;
+
+/// From `JUSTWINDOWS_PLACEMENT`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PlacementPolicy {
+    /// Spot covering the least of other windows, preferring the top left one on ties
+    #[default]
+    Smart,
+    /// Center of the monitor
+    Center,
+    /// Centered under the pointer
+    UnderPointer,
+}
+
+impl FromStr for PlacementPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "smart" => Ok(Self::Smart),
+            "center" => Ok(Self::Center),
+            "under-pointer" => Ok(Self::UnderPointer),
+            _ => Err(format!("Unknown placement policy '{}'", s)),
+        }
+    }
+}
+
+fn overlap_area(a: Rectangle, b: Rectangle) -> u64 {
+    Rect::from(a).intersection(b.into()).map_or(0, Rect::area)
+}
+
+/// Scan positions where the window touches edges of the area or of other windows, the best spot
+/// is always one of them. `None` if the window covers the same amount everywhere, e.g. when it's
+/// bigger than the area.
+fn smart(area: Rectangle, size: (u16, u16), others: &[Rectangle]) -> Option<(i32, i32)> {
+    let axis = |start: i16, len: u16, size: u16, edges: &mut dyn Iterator<Item = (i16, u16)>| {
+        let (start, end) = (start as i32, start as i32 + len as i32);
+        let mut positions = vec![start, end - size as i32];
+        for (other_start, other_len) in edges {
+            let other_end = other_start as i32 + other_len as i32;
+            // Next to the other window on either side
+            positions.extend([other_end, other_start as i32 - size as i32]);
+        }
+        positions.retain(|&p| p >= start && p + size as i32 <= end);
+        positions.sort_unstable();
+        positions.dedup();
+        positions
+    };
+    let xs = axis(
+        area.x,
+        area.width,
+        size.0,
+        &mut others.iter().map(|o| (o.x, o.width)),
+    );
+    let ys = axis(
+        area.y,
+        area.height,
+        size.1,
+        &mut others.iter().map(|o| (o.y, o.height)),
+    );
+
+    let covered = |x: i32, y: i32| {
+        let window = Rectangle {
+            x: x as i16,
+            y: y as i16,
+            width: size.0,
+            height: size.1,
+        };
+        others
+            .iter()
+            .map(|&other| overlap_area(window, other))
+            .sum::<u64>()
+    };
+    let candidates = ys
+        .iter()
+        .flat_map(|&y| xs.iter().map(move |&x| (covered(x, y), (x, y))))
+        .collect::<Vec<_>>();
+    let (least, best) = candidates.iter().min_by_key(|(covered, _)| *covered)?;
+    // Centering looks better than the top left corner if nothing is gained
+    if candidates.iter().all(|(covered, _)| covered == least) && !others.is_empty() {
+        return None;
+    }
+    Some(*best)
+}
+
+/// Keep `position` so that window of `size` is inside `area`, or at its top left corner if the
+/// window is bigger than the area
+fn clamp(area: Rectangle, (width, height): (u16, u16), (x, y): (i32, i32)) -> (i16, i16) {
+    let max_x = area.x as i32 + (area.width as i32 - width as i32).max(0);
+    let max_y = area.y as i32 + (area.height as i32 - height as i32).max(0);
+    (
+        x.clamp(area.x as i32, max_x) as i16,
+        y.clamp(area.y as i32, max_y) as i16,
+    )
+}
+
+/// Top left corner of a new window of `size`, including its borders, within `area`. `others` are
+/// the windows it should avoid covering.
+pub fn place(
+    policy: PlacementPolicy,
+    area: Rectangle,
+    size: (u16, u16),
+    pointer: (i16, i16),
+    others: &[Rectangle],
+) -> (i16, i16) {
+    let center = (
+        area.x as i32 + (area.width as i32 - size.0 as i32) / 2,
+        area.y as i32 + (area.height as i32 - size.1 as i32) / 2,
+    );
+    let position = match policy {
+        PlacementPolicy::Center => center,
+        PlacementPolicy::UnderPointer => (
+            pointer.0 as i32 - size.0 as i32 / 2,
+            pointer.1 as i32 - size.1 as i32 / 2,
+        ),
+        PlacementPolicy::Smart => smart(area, size, others).unwrap_or(center),
+    };
+    clamp(area, size, position)
+}
+
+#[test]
+fn placement_policies() {
+    let area = Rectangle {
+        x: 100,
+        y: 0,
+        width: 1000,
+        height: 800,
+    };
+    let size = (400, 300);
+
+    assert_eq!(
+        place(PlacementPolicy::Center, area, size, (0, 0), &[]),
+        (400, 250)
+    );
+    // Kept inside the area near the edges
+    assert_eq!(
+        place(PlacementPolicy::UnderPointer, area, size, (1050, 20), &[]),
+        (700, 0)
+    );
+
+    // Right of the existing window
+    let others = [Rectangle {
+        x: 100,
+        y: 0,
+        width: 500,
+        height: 400,
+    }];
+    assert_eq!(
+        place(PlacementPolicy::Smart, area, size, (0, 0), &others),
+        (600, 0)
+    );
+    // No free spot, the least covered one is next to the smaller window
+    let others = [
+        Rectangle {
+            x: 100,
+            y: 0,
+            width: 1000,
+            height: 600,
+        },
+        Rectangle {
+            x: 100,
+            y: 600,
+            width: 500,
+            height: 200,
+        },
+    ];
+    assert_eq!(
+        place(PlacementPolicy::Smart, area, size, (0, 0), &others),
+        (600, 500)
+    );
+    // Same overlap everywhere
+    let others = [area];
+    assert_eq!(
+        place(PlacementPolicy::Smart, area, size, (0, 0), &others),
+        (400, 250)
+    );
+}
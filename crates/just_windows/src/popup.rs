@@ -0,0 +1,169 @@
+//! Override redirect popups with lines of text, used for chord hints and notifications
+
+use just_x11::{
+    error::Error,
+    requests::{self, GContextSettings, WindowCreationAttributes},
+    Drawable, FontId, GContextId, Rectangle, WindowClass, WindowId, WindowVisual, XDisplay,
+};
+use just_x11_simple::panic_hook;
+use std::time::{Duration, Instant};
+
+/// How long a notification stays on the screen after the last message
+pub const NOTIFICATION_TIMEOUT: Duration = Duration::from_secs(8);
+/// Older messages are dropped from the notification
+const NOTIFICATION_LINES: usize = 5;
+
+const POPUP_FOREGROUND: u32 = 0xeeeeee;
+const POPUP_BACKGROUND: u32 = 0x222222;
+const POPUP_PADDING: u16 = 8;
+/// Distance of corner popups from edges of the monitor
+const POPUP_MARGIN: i16 = 16;
+
+/// Where on the monitor the popup is shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PopupAnchor {
+    Center,
+    TopRight,
+}
+
+/// Override redirect popup drawn with the `fixed` font
+pub struct TextPopup {
+    window: WindowId,
+    gc: GContextId,
+    font: FontId,
+}
+
+impl TextPopup {
+    /// Returns `None` if the `fixed` font could not be opened
+    pub fn show(
+        display: &mut XDisplay,
+        root: WindowId,
+        area: Rectangle,
+        anchor: PopupAnchor,
+        lines: &[String],
+    ) -> Result<Option<Self>, Error> {
+        let font = FontId::from_resource(display.id_allocator().allocate_id());
+        display.send_request(&requests::OpenFont {
+            fid: font,
+            name: b"fixed".to_vec(),
+        })?;
+        let pending = display.send_request(&requests::QueryFont { font })?;
+        display.flush()?;
+        let Ok(metrics) = display.await_pending_reply(pending)? else {
+            return Ok(None);
+        };
+
+        let char_width = metrics.max_bounds.character_width.max(1) as u16;
+        let line_height = (metrics.font_ascent + metrics.font_descent).max(1) as u16;
+        let columns = lines.iter().map(|line| line.len()).max().unwrap_or(0) as u16;
+        let width = columns * char_width + POPUP_PADDING * 2;
+        let height = lines.len() as u16 * line_height + POPUP_PADDING * 2;
+        let (x, y) = match anchor {
+            PopupAnchor::Center => (
+                area.x + (area.width as i16 - width as i16) / 2,
+                area.y + (area.height as i16 - height as i16) / 2,
+            ),
+            PopupAnchor::TopRight => (
+                area.x + area.width as i16 - width as i16 - POPUP_MARGIN,
+                area.y + POPUP_MARGIN,
+            ),
+        };
+
+        let window = WindowId::from_resource(display.id_allocator().allocate_id());
+        display.send_request(&requests::CreateWindow {
+            depth: 0,
+            wid: window,
+            parent: root,
+            x,
+            y,
+            width,
+            height,
+            border_width: 1,
+            window_class: WindowClass::InputOutput,
+            visual: WindowVisual::CopyFromParent,
+            attributes: WindowCreationAttributes::override_redirect_popup()
+                .set_background_pixel(POPUP_BACKGROUND)
+                .set_border_pixel(POPUP_FOREGROUND),
+        })?;
+        panic_hook::destroy_on_panic(window);
+
+        let gc = GContextId::from_resource(display.id_allocator().allocate_id());
+        display.send_request(&requests::CreateGC {
+            cid: gc,
+            drawable: Drawable::Window(window),
+            values: GContextSettings::new()
+                .set_foreground(POPUP_FOREGROUND)
+                .set_background(POPUP_BACKGROUND)
+                .set_font(font),
+        })?;
+        display.send_request(&requests::MapWindow { window })?;
+        for (idx, line) in lines.iter().enumerate() {
+            display.send_request(&requests::ImageText8 {
+                drawable: Drawable::Window(window),
+                gc,
+                x: POPUP_PADDING as i16,
+                y: (POPUP_PADDING + idx as u16 * line_height) as i16 + metrics.font_ascent,
+                string: line.as_bytes().to_vec(),
+            })?;
+        }
+
+        Ok(Some(Self { window, gc, font }))
+    }
+
+    pub fn hide(self, display: &mut XDisplay) -> Result<(), Error> {
+        display.send_request(&requests::DestroyWindow {
+            window: self.window,
+        })?;
+        panic_hook::forget_on_panic(self.window);
+        display.send_request(&requests::FreeGC { gc: self.gc })?;
+        display.send_request(&requests::CloseFont { font: self.font })?;
+        Ok(())
+    }
+}
+
+/// Recent messages in the top right corner of the active monitor, e.g. programs that could not
+/// be started
+pub struct Notification {
+    /// Oldest first
+    lines: Vec<String>,
+    pub deadline: Instant,
+    /// `None` if the popup could not be shown
+    popup: Option<TextPopup>,
+}
+
+impl Notification {
+    /// Show `message` below messages of `previous`, replacing its popup
+    pub fn show(
+        display: &mut XDisplay,
+        root: WindowId,
+        area: Rectangle,
+        previous: Option<Notification>,
+        message: String,
+    ) -> Result<Self, Error> {
+        let mut lines = Vec::new();
+        if let Some(previous) = previous {
+            lines = previous.lines;
+            if let Some(popup) = previous.popup {
+                popup.hide(display)?;
+            }
+        }
+        lines.push(message);
+        if lines.len() > NOTIFICATION_LINES {
+            lines.drain(..lines.len() - NOTIFICATION_LINES);
+        }
+
+        let popup = TextPopup::show(display, root, area, PopupAnchor::TopRight, &lines)?;
+        Ok(Self {
+            lines,
+            deadline: Instant::now() + NOTIFICATION_TIMEOUT,
+            popup,
+        })
+    }
+
+    pub fn hide(self, display: &mut XDisplay) -> Result<(), Error> {
+        if let Some(popup) = self.popup {
+            popup.hide(display)?;
+        }
+        Ok(())
+    }
+}
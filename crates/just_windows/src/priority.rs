@@ -0,0 +1,48 @@
+//! Scheduling priority of local processes
+
+use std::{
+    ffi::{c_char, c_int, c_uint, CStr},
+    io,
+};
+
+const PRIO_PROCESS: c_int = 0;
+
+extern "C" {
+    fn getpriority(which: c_int, who: c_uint) -> c_int;
+    fn setpriority(which: c_int, who: c_uint, prio: c_int) -> c_int;
+    fn __errno_location() -> *mut c_int;
+    fn gethostname(name: *mut c_char, len: usize) -> c_int;
+}
+
+/// Name of this machine, compared with `WM_CLIENT_MACHINE` to tell if a process id is local
+pub fn hostname() -> io::Result<String> {
+    let mut buf = [0 as c_char; 256];
+    // Result is truncated without terminating null byte if the name is too long
+    if unsafe { gethostname(buf.as_mut_ptr(), buf.len() - 1) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    let name = unsafe { CStr::from_ptr(buf.as_ptr()) };
+    Ok(name.to_string_lossy().into_owned())
+}
+
+/// Niceness of process `pid`
+pub fn get(pid: u32) -> io::Result<i32> {
+    // -1 is a valid niceness so errno has to be checked
+    unsafe { *__errno_location() = 0 };
+    let res = unsafe { getpriority(PRIO_PROCESS, pid) };
+    if res == -1 {
+        let err = io::Error::last_os_error();
+        if err.raw_os_error() != Some(0) {
+            return Err(err);
+        }
+    }
+    Ok(res)
+}
+
+/// Set niceness of process `pid`, lowering it requires `CAP_SYS_NICE`
+pub fn set(pid: u32, nice: i32) -> io::Result<()> {
+    if unsafe { setpriority(PRIO_PROCESS, pid, nice) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
@@ -0,0 +1,204 @@
+//! ICCCM manager selection `WM_Sn`, used to detect other window managers and to replace them
+//!
+//! See section 2.8 "Manager Selections" and 4.3 "Communication with the Window Manager by Means
+//! of Selections" of ICCCM.
+
+use just_x11::{
+    atoms::AtomId,
+    error::Error,
+    events::{self, EventType, SelectionClear, SomeEvent},
+    replies::String8,
+    requests::{
+        self, ChangePropertyFormat, ChangePropertyMode, Timestamp, WindowCreationAttributes,
+    },
+    OrNone, WindowClass, WindowId, WindowVisual,
+};
+use just_x11_simple::{panic_hook, X11Connection};
+use std::{
+    io::{Cursor, Write},
+    str::FromStr,
+    thread,
+    time::{Duration, Instant},
+};
+
+/// How long to wait for the previous window manager to exit after taking its selection
+const REPLACE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy)]
+pub struct WmSelection {
+    pub selection: AtomId,
+    /// Window owning the selection, lives as long as the window manager
+    pub owner: WindowId,
+    /// Server time when the selection was acquired
+    pub time: u32,
+}
+
+impl WmSelection {
+    /// Whether other manager took this selection
+    pub fn is_cleared_by(&self, event: &SelectionClear) -> bool {
+        event.selection == self.selection && event.owner == self.owner
+    }
+
+    /// Give up the selection and destroy its window, which tells the new manager that we exited
+    pub fn release(self, conn: &mut X11Connection) -> Result<(), Error> {
+        conn.display_mut()
+            .send_request(&requests::SetSelectionOwner {
+                owner: OrNone::none(),
+                selection: self.selection,
+                time: Timestamp::Timestamp(self.time),
+            })?;
+        conn.display_mut()
+            .send_request(&requests::DestroyWindow { window: self.owner })?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Acquire {
+    Acquired(WmSelection),
+    /// Other window manager owns the selection and replacing was not requested
+    OwnedByOther(WindowId),
+}
+
+/// Acquire `WM_Sn` selection of `screen` and announce it with `MANAGER` client message. With
+/// `replace` the current owner is asked to exit by taking the selection from it, otherwise
+/// [`Acquire::OwnedByOther`] is returned.
+pub fn acquire(
+    conn: &mut X11Connection,
+    screen: usize,
+    root: WindowId,
+    replace: bool,
+) -> Result<Acquire, Error> {
+    let selection = conn.get_atom_id(String8::from_str(&format!("WM_S{}", screen)).unwrap())?;
+
+    let previous_owner = selection_owner(conn, selection)?;
+    if let Some(previous_owner) = previous_owner {
+        if !replace {
+            return Ok(Acquire::OwnedByOther(previous_owner));
+        }
+        conn.select_input(previous_owner, EventType::STRUCTURE_NOTIFY)?;
+    }
+
+    let owner = WindowId::from_resource(conn.display_mut().id_allocator().allocate_id());
+    conn.display_mut().send_request(&requests::CreateWindow {
+        depth: 0,
+        wid: owner,
+        parent: root,
+        x: -1,
+        y: -1,
+        width: 1,
+        height: 1,
+        border_width: 0,
+        window_class: WindowClass::InputOnly,
+        visual: WindowVisual::CopyFromParent,
+        attributes: WindowCreationAttributes::input_only_overlay()
+            .set_event_mask(EventType::PROPERTY_CHANGE),
+    })?;
+    panic_hook::destroy_on_panic(owner);
+
+    // Selections must not be acquired with CurrentTime
+    let time = server_time(conn, owner)?;
+    conn.display_mut()
+        .send_request(&requests::SetSelectionOwner {
+            owner: OrNone::new(owner),
+            selection,
+            time: Timestamp::Timestamp(time),
+        })?;
+
+    let current_owner = selection_owner(conn, selection)?;
+    if current_owner != Some(owner) {
+        // Lost a race with other manager starting at the same time
+        conn.display_mut()
+            .send_request(&requests::DestroyWindow { window: owner })?;
+        return Ok(Acquire::OwnedByOther(current_owner.unwrap_or(owner)));
+    }
+
+    if let Some(previous_owner) = previous_owner {
+        wait_for_destroy(conn, previous_owner)?;
+    }
+
+    let manager = conn.get_atom_id(String8::from_str("MANAGER").unwrap())?;
+    let mut data = Cursor::new([0u8; 20]);
+    data.write_all(&time.to_le_bytes()).unwrap();
+    data.write_all(&selection.to_le_bytes()).unwrap();
+    data.write_all(&owner.to_le_bytes()).unwrap();
+    let event = events::ClientMessage {
+        event_code: 33,
+        format: events::MessageFormat::Format32,
+        sequence_number: 0,
+        window: root,
+        type_message: manager,
+        data: data.into_inner(),
+    };
+    conn.display_mut().send_request(&requests::SendEvent {
+        propagate: false,
+        destination: root,
+        event_mask: EventType::STRUCTURE_NOTIFY.raw(),
+        event: event.to_le_bytes(),
+    })?;
+    conn.flush()?;
+
+    Ok(Acquire::Acquired(WmSelection {
+        selection,
+        owner,
+        time,
+    }))
+}
+
+fn selection_owner(conn: &mut X11Connection, selection: AtomId) -> Result<Option<WindowId>, Error> {
+    let pending_reply = conn
+        .display_mut()
+        .send_request(&requests::GetSelectionOwner { selection })?;
+    conn.flush()?;
+    let owner = conn
+        .display_mut()
+        .await_pending_reply(pending_reply)?
+        .unwrap()
+        .owner;
+    Ok((u32::from(owner) != 0).then_some(owner))
+}
+
+/// Current server time from `PropertyNotify` caused by zero-length append to `window`, which
+/// must select `PROPERTY_CHANGE`
+fn server_time(conn: &mut X11Connection, window: WindowId) -> Result<u32, Error> {
+    conn.display_mut().send_request(&requests::ChangeProperty {
+        mode: ChangePropertyMode::Append,
+        window,
+        property: AtomId::WM_NAME,
+        type_: AtomId::STRING,
+        format: ChangePropertyFormat::Format8,
+        data: Vec::new(),
+    })?;
+    conn.flush()?;
+
+    loop {
+        match conn.display_mut().next_event()? {
+            Some(SomeEvent::PropertyNotify(event)) if event.window == window => {
+                return Ok(event.time);
+            }
+            Some(_) => {}
+            None => thread::sleep(Duration::from_millis(1)),
+        }
+    }
+}
+
+/// Previous window manager destroys its selection window when it exits
+fn wait_for_destroy(conn: &mut X11Connection, window: WindowId) -> Result<(), Error> {
+    let deadline = Instant::now() + REPLACE_TIMEOUT;
+    while Instant::now() < deadline {
+        while let Some(event) = conn.display_mut().next_event()? {
+            if let SomeEvent::DestroyNotify(event) = event {
+                if event.window == window {
+                    return Ok(());
+                }
+            }
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    eprintln!(
+        "justwindows: warning: Previous window manager did not exit in {:?}",
+        REPLACE_TIMEOUT
+    );
+    Ok(())
+}
@@ -0,0 +1,233 @@
+//! Per-window state tracked by the window manager
+//!
+//! ```text
+//!              MapRequest                   toggle_game_mode
+//! Unmanaged ──────────────> Managed(Tiled) <────────────────> Managed(Fullscreen)
+//!     ^                          │
+//!     └──── DestroyNotify, ──────┘
+//!           KillActive
+//! ```
+//!
+//! Transient windows, e.g. dialogs, start as `Floating`. `Floating` and `Iconified` windows keep
+//! their workspace but are skipped by the layout.
+
+use crate::layout::PositionedWindow;
+use just_x11::WindowId;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placement {
+    /// Positioned by the layout of its workspace
+    Tiled,
+    /// Keeps geometry requested by the client
+    Floating,
+    /// Covers whole screen without borders, see `JustWindows::toggle_game_mode`
+    Fullscreen,
+    /// Unmapped until restored
+    Iconified,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowPhase {
+    Unmanaged,
+    Managed(Placement),
+}
+
+#[derive(Debug, Clone)]
+pub struct WindowState {
+    pub placement: Placement,
+    pub workspace: usize,
+    /// Last geometry sent to the window, used to skip redundant ConfigureWindow requests
+    pub geometry: Option<PositionedWindow>,
+    /// Numbered marks, see `JustAction::Mark`
+    pub marks: Vec<u8>,
+    /// Urgency hint from `WM_HINTS`
+    pub urgent: bool,
+    /// Minimum `(width, height)` from `WM_NORMAL_HINTS`, respected by layouts
+    pub min_size: (u16, u16),
+}
+
+/// Managed windows in order of management, which is also the order of tiling
+pub struct Windows {
+    windows: Vec<(WindowId, WindowState)>,
+}
+
+impl Windows {
+    pub fn new() -> Self {
+        Self {
+            windows: Vec::new(),
+        }
+    }
+
+    pub fn phase(&self, window: WindowId) -> WindowPhase {
+        self.get(window).map_or(WindowPhase::Unmanaged, |state| {
+            WindowPhase::Managed(state.placement)
+        })
+    }
+
+    pub fn is_managed(&self, window: WindowId) -> bool {
+        self.phase(window) != WindowPhase::Unmanaged
+    }
+
+    pub fn get(&self, window: WindowId) -> Option<&WindowState> {
+        self.windows
+            .iter()
+            .find_map(|(w, state)| (*w == window).then_some(state))
+    }
+
+    pub fn get_mut(&mut self, window: WindowId) -> Option<&mut WindowState> {
+        self.windows
+            .iter_mut()
+            .find_map(|(w, state)| (*w == window).then_some(state))
+    }
+
+    /// Start managing `window`. Returns `false` if it was already managed.
+    pub fn manage(&mut self, window: WindowId, workspace: usize, placement: Placement) -> bool {
+        if self.is_managed(window) {
+            return false;
+        }
+
+        self.windows.push((
+            window,
+            WindowState {
+                placement,
+                workspace,
+                geometry: None,
+                marks: Vec::new(),
+                urgent: false,
+                min_size: (0, 0),
+            },
+        ));
+        true
+    }
+
+    pub fn unmanage(&mut self, window: WindowId) -> Option<WindowState> {
+        let idx = self.windows.iter().position(|(w, _)| *w == window)?;
+        Some(self.windows.remove(idx).1)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (WindowId, &WindowState)> {
+        self.windows.iter().map(|(window, state)| (*window, state))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (WindowId, &mut WindowState)> {
+        self.windows
+            .iter_mut()
+            .map(|(window, state)| (*window, state))
+    }
+
+    /// All windows on `workspace`, including floating and iconified ones
+    pub fn on_workspace(&self, workspace: usize) -> Vec<WindowId> {
+        self.iter()
+            .filter(|(_, state)| state.workspace == workspace)
+            .map(|(window, _)| window)
+            .collect()
+    }
+
+    pub fn with_placement(&self, workspace: usize, placement: Placement) -> Vec<WindowId> {
+        self.iter()
+            .filter(|(_, state)| state.workspace == workspace && state.placement == placement)
+            .map(|(window, _)| window)
+            .collect()
+    }
+
+    /// Move windows listed in `order` to the front of the tiling order, in given order. Other
+    /// windows keep their relative order after them.
+    pub fn reorder(&mut self, order: &[WindowId]) {
+        self.windows.sort_by_key(|(window, _)| {
+            order
+                .iter()
+                .position(|w| w == window)
+                .unwrap_or(order.len())
+        });
+    }
+
+    /// Exchange tiling order and workspaces of `a` and `b`, nothing happens if either of them is
+    /// not managed
+    pub fn swap(&mut self, a: WindowId, b: WindowId) {
+        let position = |window| self.windows.iter().position(|(w, _)| *w == window);
+        let (Some(a), Some(b)) = (position(a), position(b)) else {
+            return;
+        };
+        self.windows.swap(a, b);
+        let workspace = self.windows[a].1.workspace;
+        self.windows[a].1.workspace = self.windows[b].1.workspace;
+        self.windows[b].1.workspace = workspace;
+    }
+
+    pub fn marked(&self, mark: u8) -> Option<WindowId> {
+        self.iter()
+            .find_map(|(window, state)| state.marks.contains(&mark).then_some(window))
+    }
+
+    /// Move `mark` to `window`, it can be assigned to only one window at a time
+    pub fn set_mark(&mut self, window: WindowId, mark: u8) {
+        for (w, state) in self.iter_mut() {
+            if w == window {
+                if !state.marks.contains(&mark) {
+                    state.marks.push(mark);
+                }
+            } else {
+                state.marks.retain(|m| *m != mark);
+            }
+        }
+    }
+}
+
+#[test]
+fn window_state_transitions() {
+    let mut windows = Windows::new();
+    let window = WindowId::from(0x200001);
+
+    assert_eq!(windows.phase(window), WindowPhase::Unmanaged);
+    assert!(windows.manage(window, 0, Placement::Tiled));
+    assert!(!windows.manage(window, 1, Placement::Floating));
+    assert_eq!(
+        windows.phase(window),
+        WindowPhase::Managed(Placement::Tiled)
+    );
+
+    windows.set_mark(window, 1);
+    assert_eq!(windows.marked(1), Some(window));
+
+    windows.get_mut(window).unwrap().placement = Placement::Fullscreen;
+    assert_eq!(
+        windows.with_placement(0, Placement::Fullscreen),
+        vec![window]
+    );
+
+    assert!(windows.unmanage(window).is_some());
+    assert_eq!(windows.phase(window), WindowPhase::Unmanaged);
+    assert_eq!(windows.marked(1), None);
+}
+
+#[test]
+fn restored_order() {
+    let mut windows = Windows::new();
+    let [a, b, c, d] = [1, 2, 3, 4].map(WindowId::from);
+    for window in [a, b, c, d] {
+        windows.manage(window, 0, Placement::Tiled);
+    }
+
+    windows.reorder(&[c, a]);
+    assert_eq!(windows.on_workspace(0), vec![c, a, b, d]);
+}
+
+#[test]
+fn swapped_tiles() {
+    let mut windows = Windows::new();
+    let [a, b, c] = [1, 2, 3].map(WindowId::from);
+    windows.manage(a, 0, Placement::Tiled);
+    windows.manage(b, 0, Placement::Tiled);
+    windows.manage(c, 1, Placement::Tiled);
+
+    windows.swap(a, b);
+    assert_eq!(windows.on_workspace(0), vec![b, a]);
+
+    // Tiles dropped on another monitor move to its workspace
+    windows.swap(a, c);
+    assert_eq!(windows.on_workspace(0), vec![b, c]);
+    assert_eq!(windows.on_workspace(1), vec![a]);
+
+    windows.swap(a, WindowId::from(4));
+    assert_eq!(windows.on_workspace(1), vec![a]);
+}
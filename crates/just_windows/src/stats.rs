@@ -0,0 +1,113 @@
+//! Answer to `query stats` sent to the `--status` socket, to diagnose event storms and slow
+//! handlers
+//!
+//! ```text
+//! {
+//!   "stats": {
+//!     "events": {"MapRequest": 3, "event 89": 1}, // Decoded events by type since start
+//!     "replies": 120,
+//!     "errors": 2,
+//!     "bytes_in": 10240,
+//!     "bytes_out": 4096,
+//!     "handlers": {          // null unless JUSTWINDOWS_TIME_HANDLERS=1
+//!       "MapRequest": {"count": 3, "total_us": 1250, "max_us": 700}
+//!     }
+//!   }
+//! }
+//! ```
+
+use just_x11::{json, stats::Stats};
+use std::{fmt::Write as _, time::Duration};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HandlerTime {
+    pub count: u64,
+    pub total: Duration,
+    pub max: Duration,
+}
+
+/// Time spent handling each event type, in order of first occurrence
+#[derive(Debug, Clone, Default)]
+pub struct HandlerTimes {
+    handlers: Vec<(&'static str, HandlerTime)>,
+}
+
+impl HandlerTimes {
+    pub fn record(&mut self, event: &'static str, elapsed: Duration) {
+        let idx = match self.handlers.iter().position(|(name, _)| *name == event) {
+            Some(idx) => idx,
+            None => {
+                self.handlers.push((event, HandlerTime::default()));
+                self.handlers.len() - 1
+            }
+        };
+        let time = &mut self.handlers[idx].1;
+        time.count += 1;
+        time.total += elapsed;
+        time.max = time.max.max(elapsed);
+    }
+}
+
+/// Single line of JSON, without the trailing newline
+pub fn to_json(x11: &Stats, handlers: Option<&HandlerTimes>) -> String {
+    let mut out = String::from("{\"stats\":{\"events\":{");
+    for (idx, (name, count)) in x11.named_events().enumerate() {
+        if idx != 0 {
+            out.push(',');
+        }
+        json::write_json_str(&mut out, &name).unwrap();
+        write!(out, ":{}", count).unwrap();
+    }
+    write!(
+        out,
+        "}},\"replies\":{},\"errors\":{},\"bytes_in\":{},\"bytes_out\":{},\"handlers\":",
+        x11.replies, x11.errors, x11.bytes_in, x11.bytes_out
+    )
+    .unwrap();
+    match handlers {
+        Some(handlers) => {
+            out.push('{');
+            for (idx, (name, time)) in handlers.handlers.iter().enumerate() {
+                if idx != 0 {
+                    out.push(',');
+                }
+                json::write_json_str(&mut out, name).unwrap();
+                write!(
+                    out,
+                    ":{{\"count\":{},\"total_us\":{},\"max_us\":{}}}",
+                    time.count,
+                    time.total.as_micros(),
+                    time.max.as_micros()
+                )
+                .unwrap();
+            }
+            out.push('}');
+        }
+        None => out.push_str("null"),
+    }
+    out.push_str("}}");
+    out
+}
+
+#[test]
+fn handler_times_json() {
+    let mut handlers = HandlerTimes::default();
+    handlers.record("MapRequest", Duration::from_micros(700));
+    handlers.record("EnterNotify", Duration::from_micros(10));
+    handlers.record("MapRequest", Duration::from_micros(550));
+    assert_eq!(
+        handlers.handlers[0].1,
+        HandlerTime {
+            count: 2,
+            total: Duration::from_micros(1250),
+            max: Duration::from_micros(700),
+        }
+    );
+
+    let json = to_json(&Stats::default(), Some(&handlers));
+    assert_eq!(
+        json,
+        r#"{"stats":{"events":{},"replies":0,"errors":0,"bytes_in":0,"bytes_out":0,"handlers":{"MapRequest":{"count":2,"total_us":1250,"max_us":700},"EnterNotify":{"count":1,"total_us":10,"max_us":10}}}}"#
+    );
+    assert!(to_json(&Stats::default(), None).ends_with(r#""handlers":null}}"#));
+}
@@ -0,0 +1,341 @@
+//! Line delimited JSON stream of window manager state for external bars, enabled with `--status`
+//!
+//! Every line is one JSON object with the whole state, written when any part of it changes.
+//! Clients connecting to the socket get the current state right away. They can also write
+//! queries, one per line, answered with a single line in between status lines:
+//!
+//! - `query stats` gets counters described in [`crate::stats`]
+//!
+//! ```text
+//! {
+//!   "workspaces": [
+//!     {
+//!       "index": 0,          // Stable for the lifetime of the window manager
+//!       "monitor": "DP-1",   // Monitor showing the workspace, null if it is hidden
+//!       "focused": true,     // Workspace of the active monitor
+//!       "urgent": false,     // Any window on the workspace has urgency hint set
+//!       "windows": 2,        // Number of managed windows, including floating ones
+//!       "layout": "master-stack", // Or "stack"
+//!       "master_ratio": 0.5  // Part of the width taken by the master window
+//!     }
+//!   ],
+//!   "focused": {             // null if no window is focused
+//!     "window": 4194313,     // X11 window id
+//!     "workspace": 0,
+//!     "title": "xterm",      // From _NET_WM_NAME or WM_NAME, null if unset
+//!     "urgent": false
+//!   }
+//! }
+//! ```
+
+use crate::layout::LayoutKind;
+use just_x11::json;
+use std::{
+    fmt::Write as _,
+    fs,
+    io::{self, Read, Write},
+    os::unix::net::{UnixListener, UnixStream},
+    path::PathBuf,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkspaceStatus {
+    pub index: usize,
+    pub monitor: Option<String>,
+    pub focused: bool,
+    pub urgent: bool,
+    pub windows: usize,
+    pub layout: LayoutKind,
+    pub master_ratio: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FocusedStatus {
+    pub window: u32,
+    pub workspace: usize,
+    pub title: Option<String>,
+    pub urgent: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Status {
+    pub workspaces: Vec<WorkspaceStatus>,
+    pub focused: Option<FocusedStatus>,
+}
+
+fn write_json_optional_string(out: &mut String, s: Option<&str>) {
+    match s {
+        Some(s) => json::write_json_str(out, s).unwrap(),
+        None => out.push_str("null"),
+    }
+}
+
+impl Status {
+    /// Single line of JSON, without the trailing newline
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("{\"workspaces\":[");
+        for (idx, workspace) in self.workspaces.iter().enumerate() {
+            if idx != 0 {
+                out.push(',');
+            }
+            write!(out, "{{\"index\":{},\"monitor\":", workspace.index).unwrap();
+            write_json_optional_string(&mut out, workspace.monitor.as_deref());
+            write!(
+                out,
+                ",\"focused\":{},\"urgent\":{},\"windows\":{},\"layout\":\"{}\",\"master_ratio\":{}}}",
+                workspace.focused,
+                workspace.urgent,
+                workspace.windows,
+                workspace.layout,
+                workspace.master_ratio
+            )
+            .unwrap();
+        }
+        out.push_str("],\"focused\":");
+        match &self.focused {
+            Some(focused) => {
+                write!(
+                    out,
+                    "{{\"window\":{},\"workspace\":{},\"title\":",
+                    focused.window, focused.workspace
+                )
+                .unwrap();
+                write_json_optional_string(&mut out, focused.title.as_deref());
+                write!(out, ",\"urgent\":{}}}", focused.urgent).unwrap();
+            }
+            None => out.push_str("null"),
+        }
+        out.push('}');
+        out
+    }
+}
+
+/// Where the status is written, from `--status`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StatusTarget {
+    /// `-`
+    Stdout,
+    /// Unix socket created at the path, any number of clients can connect to it
+    Socket(PathBuf),
+}
+
+impl StatusTarget {
+    pub fn parse(s: &str) -> Self {
+        if s == "-" {
+            Self::Stdout
+        } else {
+            Self::Socket(PathBuf::from(s))
+        }
+    }
+}
+
+/// Client of the status socket
+struct Client {
+    stream: UnixStream,
+    /// Query line read so far
+    input: Vec<u8>,
+}
+
+enum Sink {
+    Stdout,
+    Socket {
+        listener: UnixListener,
+        path: PathBuf,
+        clients: Vec<Client>,
+    },
+}
+
+pub struct StatusOutput {
+    sink: Sink,
+    /// Last written line, sent to new clients and used to skip unchanged status
+    last: Option<String>,
+}
+
+impl StatusOutput {
+    pub fn open(target: &StatusTarget) -> io::Result<Self> {
+        let sink = match target {
+            StatusTarget::Stdout => Sink::Stdout,
+            StatusTarget::Socket(path) => {
+                let listener = match UnixListener::bind(path) {
+                    Ok(listener) => listener,
+                    // Left behind by a previous instance that did not exit cleanly
+                    Err(err)
+                        if err.kind() == io::ErrorKind::AddrInUse
+                            && UnixStream::connect(path).is_err() =>
+                    {
+                        fs::remove_file(path)?;
+                        UnixListener::bind(path)?
+                    }
+                    Err(err) => return Err(err),
+                };
+                listener.set_nonblocking(true)?;
+                Sink::Socket {
+                    listener,
+                    path: path.clone(),
+                    clients: Vec::new(),
+                }
+            }
+        };
+        Ok(Self { sink, last: None })
+    }
+
+    /// Accept clients waiting on the socket and send them the current status
+    pub fn accept(&mut self) -> io::Result<()> {
+        let Sink::Socket {
+            listener, clients, ..
+        } = &mut self.sink
+        else {
+            return Ok(());
+        };
+
+        loop {
+            let mut client = match listener.accept() {
+                Ok((client, _)) => client,
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => return Ok(()),
+                Err(err) => return Err(err),
+            };
+            // Clients that don't keep up are dropped instead of blocking the window manager
+            client.set_nonblocking(true)?;
+            if let Some(last) = &self.last {
+                if writeln!(client, "{}", last).is_err() {
+                    continue;
+                }
+            }
+            clients.push(Client {
+                stream: client,
+                input: Vec::new(),
+            });
+        }
+    }
+
+    /// Read queries sent by socket clients and write lines returned by `answer` back to them,
+    /// queries it does not know get an `{"error": ...}` line. Clients that disconnected or send
+    /// lines longer than 4096 bytes are dropped.
+    pub fn answer_queries(&mut self, mut answer: impl FnMut(&str) -> Option<String>) {
+        let Sink::Socket { clients, .. } = &mut self.sink else {
+            return;
+        };
+
+        clients.retain_mut(|client| {
+            let mut buf = [0u8; 512];
+            loop {
+                match client.stream.read(&mut buf) {
+                    Ok(0) => return false,
+                    Ok(n) => client.input.extend_from_slice(&buf[..n]),
+                    Err(err) if err.kind() == io::ErrorKind::WouldBlock => break,
+                    Err(_) => return false,
+                }
+            }
+
+            while let Some(end) = client.input.iter().position(|b| *b == b'\n') {
+                let line = client.input.drain(..=end).collect::<Vec<_>>();
+                let query = String::from_utf8_lossy(&line[..end]);
+                let query = query.trim();
+                let reply = answer(query).unwrap_or_else(|| {
+                    let mut out = String::from("{\"error\":\"Unknown query\",\"query\":");
+                    json::write_json_str(&mut out, query).unwrap();
+                    out.push('}');
+                    out
+                });
+                if writeln!(client.stream, "{}", reply).is_err() {
+                    return false;
+                }
+            }
+            client.input.len() <= 4096
+        });
+    }
+
+    /// Write `status` if it differs from the last one. Socket clients that can't be written to
+    /// are disconnected, error is returned only if stdout is closed.
+    pub fn publish(&mut self, status: &Status) -> io::Result<()> {
+        let line = status.to_json();
+        if self.last.as_ref() == Some(&line) {
+            return Ok(());
+        }
+
+        match &mut self.sink {
+            Sink::Stdout => {
+                let mut stdout = io::stdout().lock();
+                writeln!(stdout, "{}", line)?;
+                stdout.flush()?;
+            }
+            Sink::Socket { clients, .. } => {
+                clients.retain_mut(|client| writeln!(client.stream, "{}", line).is_ok());
+            }
+        }
+        self.last = Some(line);
+        Ok(())
+    }
+}
+
+impl Drop for StatusOutput {
+    fn drop(&mut self) {
+        if let Sink::Socket { path, .. } = &self.sink {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+#[test]
+fn status_json() {
+    let status = Status {
+        workspaces: vec![
+            WorkspaceStatus {
+                index: 0,
+                monitor: Some("DP-1".to_string()),
+                focused: true,
+                urgent: false,
+                windows: 2,
+                layout: LayoutKind::MasterStack,
+                master_ratio: 0.5,
+            },
+            WorkspaceStatus {
+                index: 1,
+                monitor: None,
+                focused: false,
+                urgent: true,
+                windows: 1,
+                layout: LayoutKind::Stack,
+                master_ratio: 0.65,
+            },
+        ],
+        focused: Some(FocusedStatus {
+            window: 42,
+            workspace: 0,
+            title: Some("say \"hi\"\\\n\u{1}".to_string()),
+            urgent: false,
+        }),
+    };
+    assert_eq!(
+        status.to_json(),
+        r#"{"workspaces":[{"index":0,"monitor":"DP-1","focused":true,"urgent":false,"windows":2,"layout":"master-stack","master_ratio":0.5},{"index":1,"monitor":null,"focused":false,"urgent":true,"windows":1,"layout":"stack","master_ratio":0.65}],"focused":{"window":42,"workspace":0,"title":"say \"hi\"\\\n\u0001","urgent":false}}"#
+    );
+
+    let empty = Status {
+        workspaces: Vec::new(),
+        focused: None,
+    };
+    assert_eq!(empty.to_json(), r#"{"workspaces":[],"focused":null}"#);
+}
+
+#[test]
+fn socket_queries() {
+    use std::io::BufRead;
+
+    let path = std::env::temp_dir().join(format!("justwindows-queries-{}", std::process::id()));
+    let mut output = StatusOutput::open(&StatusTarget::Socket(path.clone())).unwrap();
+    let mut client = UnixStream::connect(&path).unwrap();
+    output.accept().unwrap();
+
+    client.write_all(b"query stats\nquery wind").unwrap();
+    output.answer_queries(|query| (query == "query stats").then(|| "{\"stats\":{}}".to_string()));
+    client.write_all(b"ows\n").unwrap();
+    output.answer_queries(|_| None);
+
+    let mut lines = io::BufReader::new(client).lines();
+    assert_eq!(lines.next().unwrap().unwrap(), r#"{"stats":{}}"#);
+    assert_eq!(
+        lines.next().unwrap().unwrap(),
+        r#"{"error":"Unknown query","query":"query windows"}"#
+    );
+}
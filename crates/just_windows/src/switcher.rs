@@ -0,0 +1,507 @@
+//! Window switcher, the modifier with `Tab` lists managed windows of all workspaces with their
+//! titles and thumbnails. `Tab` and `Shift Tab` move the selection, releasing the modifier or
+//! `Return` activates the selected window and `Escape` cancels.
+//!
+//! The keyboard is grabbed synchronously, the server queues key events and releases them one at
+//! a time with `AllowEvents`. Keys typed right after the modifier was released are still queued
+//! when the grab ends, so they reach the activated window and not the previous one.
+//!
+//! Thumbnails are window contents read with `GetImage` and scaled down here. Windows on hidden
+//! workspaces are unmapped and have no contents, they are listed with title only. Without a
+//! compositor, parts of windows covered by other windows are undefined.
+
+use just_x11::{
+    error::Error,
+    events::EventType,
+    replies,
+    requests::{
+        self, GContextSettings, GetImageImageFormat, KeyCode, KeyModifier, PutImageFormat,
+        WindowCreationAttributes,
+    },
+    Drawable, FontId, GContextId, Rectangle, WindowClass, WindowId, WindowVisual, XDisplay,
+};
+use just_x11_simple::panic_hook;
+
+pub const THUMBNAIL_WIDTH: u16 = 192;
+pub const THUMBNAIL_HEIGHT: u16 = 120;
+
+const SWITCHER_FOREGROUND: u32 = 0xeeeeee;
+const SWITCHER_BACKGROUND: u32 = 0x222222;
+/// Space around the popup contents and between windows
+const SWITCHER_PADDING: u16 = 12;
+/// Width of the outline around the selected window
+const SELECTION_WIDTH: u16 = 3;
+
+/// Window listed by the switcher
+pub struct SwitcherEntry {
+    pub window: WindowId,
+    pub title: String,
+    pub thumbnail: Option<Thumbnail>,
+}
+
+/// Scaled down window contents, ZPixmap data with 32 bits per pixel in the server byte order
+pub struct Thumbnail {
+    width: u16,
+    height: u16,
+    depth: u8,
+    data: Vec<u8>,
+}
+
+impl Thumbnail {
+    /// Read contents of `window` of given size. `None` if the window is not viewable, or its
+    /// depth is not `depth` or its pixels are not 32 bits wide.
+    pub fn capture(
+        display: &mut XDisplay,
+        window: WindowId,
+        width: u16,
+        height: u16,
+        depth: u8,
+    ) -> Result<Option<Self>, Error> {
+        Self::capture_fit(
+            display,
+            window,
+            (width, height),
+            depth,
+            (THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT),
+        )
+    }
+
+    /// Like [`Self::capture`] but scaled down to fit into `max_size` instead of the switcher cell
+    pub fn capture_fit(
+        display: &mut XDisplay,
+        window: WindowId,
+        (width, height): (u16, u16),
+        depth: u8,
+        max_size: (u16, u16),
+    ) -> Result<Option<Self>, Error> {
+        if width == 0 || height == 0 || max_size.0 == 0 || max_size.1 == 0 {
+            return Ok(None);
+        }
+
+        let pending = display.send_request(&requests::GetImage {
+            format: GetImageImageFormat::ZPixmap,
+            drawable: Drawable::Window(window),
+            x: 0,
+            y: 0,
+            width,
+            height,
+            plane_mask: u32::MAX,
+        })?;
+        display.flush()?;
+        // Unmapped windows fail with `Match`
+        let Ok(image) = display.await_pending_reply(pending)? else {
+            return Ok(None);
+        };
+        if image.depth != depth || image.data.len() != width as usize * height as usize * 4 {
+            return Ok(None);
+        }
+
+        let (thumbnail_width, thumbnail_height) = fit_size(width, height, max_size.0, max_size.1);
+        Ok(Some(Self {
+            width: thumbnail_width,
+            height: thumbnail_height,
+            depth,
+            data: downscale(
+                &image.data,
+                width,
+                height,
+                thumbnail_width,
+                thumbnail_height,
+            ),
+        }))
+    }
+
+    /// Draw centered in `cell` of `window`
+    pub fn put(
+        &self,
+        display: &mut XDisplay,
+        window: WindowId,
+        gc: GContextId,
+        cell: Rectangle,
+    ) -> Result<(), Error> {
+        let image = requests::PutImage {
+            format: PutImageFormat::ZPixmap,
+            drawable: Drawable::Window(window),
+            gc,
+            width: self.width,
+            height: self.height,
+            dst_x: cell.x + (cell.width - self.width) as i16 / 2,
+            dst_y: cell.y + (cell.height - self.height) as i16 / 2,
+            left_pad: 0,
+            depth: self.depth,
+            data: &self.data,
+        };
+        // Overview thumbnails fill a large part of the monitor and don't fit into one request
+        for band in image.banded(display.maximum_request_length()) {
+            display.send_request(&band)?;
+        }
+        Ok(())
+    }
+}
+
+/// `width` x `height` scaled to fit into `max_width` x `max_height` keeping the aspect ratio,
+/// never scaled up
+fn fit_size(width: u16, height: u16, max_width: u16, max_height: u16) -> (u16, u16) {
+    if width <= max_width && height <= max_height {
+        return (width, height);
+    }
+    let (width, height) = (width as u32, height as u32);
+    let (max_width, max_height) = (max_width as u32, max_height as u32);
+    if width * max_height >= height * max_width {
+        (max_width as u16, (height * max_width / width).max(1) as u16)
+    } else {
+        (
+            (width * max_height / height).max(1) as u16,
+            max_height as u16,
+        )
+    }
+}
+
+/// Average 32 bit pixels of `src` into `width` x `height`. Bytes are averaged one by one so the
+/// byte order of pixels does not matter.
+fn downscale(src: &[u8], src_width: u16, src_height: u16, width: u16, height: u16) -> Vec<u8> {
+    let (src_width, src_height) = (src_width as usize, src_height as usize);
+    let (width, height) = (width as usize, height as usize);
+    let mut out = Vec::with_capacity(width * height * 4);
+    for y in 0..height {
+        let top = y * src_height / height;
+        let bottom = ((y + 1) * src_height / height).max(top + 1);
+        for x in 0..width {
+            let left = x * src_width / width;
+            let right = ((x + 1) * src_width / width).max(left + 1);
+            let mut sum = [0u32; 4];
+            for row in top..bottom {
+                let start = (row * src_width + left) * 4;
+                for pixel in src[start..(row * src_width + right) * 4].chunks_exact(4) {
+                    for (sum, byte) in sum.iter_mut().zip(pixel) {
+                        *sum += *byte as u32;
+                    }
+                }
+            }
+            let count = ((bottom - top) * (right - left)) as u32;
+            out.extend(sum.map(|sum| (sum / count) as u8));
+        }
+    }
+    out
+}
+
+/// Managed windows with the recently focused ones first, `history` is ordered from the most
+/// recently focused
+pub fn focus_order(
+    history: &[WindowId],
+    managed: impl IntoIterator<Item = WindowId>,
+) -> Vec<WindowId> {
+    let managed = managed.into_iter().collect::<Vec<_>>();
+    let mut order = history
+        .iter()
+        .copied()
+        .filter(|window| managed.contains(window))
+        .collect::<Vec<_>>();
+    for window in managed {
+        if !order.contains(&window) {
+            order.push(window);
+        }
+    }
+    order
+}
+
+/// Keys mapped to any of `modifiers` in the modifier mapping
+pub fn modifier_keys(
+    mapping: &replies::GetModifierMapping,
+    modifiers: KeyModifier,
+) -> Vec<KeyCode> {
+    // Eight rows of `keycodes_per_modifier` keys, from Shift to Mod5
+    let keys = mapping
+        .keycodes
+        .iter()
+        .flatten()
+        .copied()
+        .collect::<Vec<_>>();
+    let rows = [
+        KeyModifier::SHIFT,
+        KeyModifier::LOCK,
+        KeyModifier::CONTROL,
+        KeyModifier::MOD_1,
+        KeyModifier::MOD_2,
+        KeyModifier::MOD_3,
+        KeyModifier::MOD_4,
+        KeyModifier::MOD_5,
+    ];
+    keys.chunks(mapping.keycodes_per_modifier.max(1) as usize)
+        .zip(rows)
+        .filter(|(_, modifier)| modifiers.has(*modifier))
+        .flat_map(|(keys, _)| keys.iter().copied())
+        // Unused entries are zero
+        .filter(|key| key.raw() != 0)
+        .collect()
+}
+
+/// Index next to `selected` in a list of `len` entries, wrapping around
+fn cycle(selected: usize, len: usize, backwards: bool) -> usize {
+    if len == 0 {
+        0
+    } else if backwards {
+        (selected + len - 1) % len
+    } else {
+        (selected + 1) % len
+    }
+}
+
+/// Override redirect popup in the center of a monitor, listing windows in rows
+pub struct Switcher {
+    entries: Vec<SwitcherEntry>,
+    selected: usize,
+    /// Keys of the modifiers held with `Tab`, releasing any of them activates the selection
+    release_keys: Vec<KeyCode>,
+    window: WindowId,
+    gc: GContextId,
+    /// Draws with the background color, erases outline of the previous selection
+    erase_gc: GContextId,
+    /// `None` without the `fixed` font, titles are not drawn then
+    font: Option<(FontId, replies::QueryFont)>,
+    columns: usize,
+}
+
+impl Switcher {
+    /// Show `entries` and select the second one, the first one is the focused window. Entries
+    /// that don't fit into `area` are dropped.
+    pub fn show(
+        display: &mut XDisplay,
+        root: WindowId,
+        area: Rectangle,
+        mut entries: Vec<SwitcherEntry>,
+        release_keys: Vec<KeyCode>,
+    ) -> Result<Self, Error> {
+        let font = FontId::from_resource(display.id_allocator().allocate_id());
+        display.send_request(&requests::OpenFont {
+            fid: font,
+            name: b"fixed".to_vec(),
+        })?;
+        let pending = display.send_request(&requests::QueryFont { font })?;
+        display.flush()?;
+        let font = display
+            .await_pending_reply(pending)?
+            .ok()
+            .map(|metrics| (font, metrics));
+
+        let line_height = font.as_ref().map_or(0, |(_, metrics)| {
+            (metrics.font_ascent + metrics.font_descent).max(1) as u16
+        });
+        let cell_width = THUMBNAIL_WIDTH + SWITCHER_PADDING;
+        let cell_height = THUMBNAIL_HEIGHT + line_height + SWITCHER_PADDING * 2;
+        let max_columns = (area.width.saturating_sub(SWITCHER_PADDING) / cell_width).max(1);
+        let max_rows = (area.height.saturating_sub(SWITCHER_PADDING) / cell_height).max(1);
+        entries.truncate(max_columns as usize * max_rows as usize);
+        let columns = (entries.len() as u16).clamp(1, max_columns);
+        let rows = (entries.len() as u16).div_ceil(columns).max(1);
+        let width = columns * cell_width + SWITCHER_PADDING;
+        let height = rows * cell_height + SWITCHER_PADDING;
+
+        let window = WindowId::from_resource(display.id_allocator().allocate_id());
+        display.send_request(&requests::CreateWindow {
+            depth: 0,
+            wid: window,
+            parent: root,
+            x: area.x + (area.width as i16 - width as i16) / 2,
+            y: area.y + (area.height as i16 - height as i16) / 2,
+            width,
+            height,
+            border_width: 1,
+            window_class: WindowClass::InputOutput,
+            visual: WindowVisual::CopyFromParent,
+            attributes: WindowCreationAttributes::override_redirect_popup()
+                .set_background_pixel(SWITCHER_BACKGROUND)
+                .set_border_pixel(SWITCHER_FOREGROUND)
+                .set_event_mask(EventType::EXPOSURE),
+        })?;
+        panic_hook::destroy_on_panic(window);
+
+        let gc = GContextId::from_resource(display.id_allocator().allocate_id());
+        let mut values = GContextSettings::new()
+            .set_foreground(SWITCHER_FOREGROUND)
+            .set_background(SWITCHER_BACKGROUND)
+            .set_graphics_exposures(false);
+        if let Some((font, _)) = font {
+            values = values.set_font(font);
+        }
+        display.send_request(&requests::CreateGC {
+            cid: gc,
+            drawable: Drawable::Window(window),
+            values,
+        })?;
+        let erase_gc = GContextId::from_resource(display.id_allocator().allocate_id());
+        display.send_request(&requests::CreateGC {
+            cid: erase_gc,
+            drawable: Drawable::Window(window),
+            values: GContextSettings::new()
+                .set_foreground(SWITCHER_BACKGROUND)
+                .set_graphics_exposures(false),
+        })?;
+        display.send_request(&requests::MapWindow { window })?;
+
+        let switcher = Self {
+            selected: cycle(0, entries.len(), false),
+            entries,
+            release_keys,
+            window,
+            gc,
+            erase_gc,
+            font,
+            columns: columns as usize,
+        };
+        switcher.draw(display)?;
+        Ok(switcher)
+    }
+
+    /// Window that is activated when the modifier is released
+    pub fn selected(&self) -> Option<WindowId> {
+        self.entries.get(self.selected).map(|entry| entry.window)
+    }
+
+    pub fn is_release_key(&self, key: KeyCode) -> bool {
+        self.release_keys.contains(&key)
+    }
+
+    pub fn is_popup(&self, window: WindowId) -> bool {
+        self.window == window
+    }
+
+    /// Select the next window, or the previous one if `backwards`
+    pub fn step(&mut self, display: &mut XDisplay, backwards: bool) -> Result<(), Error> {
+        self.draw_outline(display, self.selected, self.erase_gc)?;
+        self.selected = cycle(self.selected, self.entries.len(), backwards);
+        self.draw_outline(display, self.selected, self.gc)
+    }
+
+    /// Thumbnail area of entry `idx`
+    fn cell(&self, idx: usize) -> Rectangle {
+        let line_height = self.font.as_ref().map_or(0, |(_, metrics)| {
+            (metrics.font_ascent + metrics.font_descent).max(1) as u16
+        });
+        let column = (idx % self.columns) as u16;
+        let row = (idx / self.columns) as u16;
+        Rectangle {
+            x: (SWITCHER_PADDING + column * (THUMBNAIL_WIDTH + SWITCHER_PADDING)) as i16,
+            y: (SWITCHER_PADDING + row * (THUMBNAIL_HEIGHT + line_height + SWITCHER_PADDING * 2))
+                as i16,
+            width: THUMBNAIL_WIDTH,
+            height: THUMBNAIL_HEIGHT,
+        }
+    }
+
+    fn draw_outline(
+        &self,
+        display: &mut XDisplay,
+        idx: usize,
+        gc: GContextId,
+    ) -> Result<(), Error> {
+        let cell = self.cell(idx);
+        // One pixel wide rectangles, growing outwards from the thumbnail
+        let rectangles = (1..=SELECTION_WIDTH)
+            .map(|offset| Rectangle {
+                x: cell.x - offset as i16,
+                y: cell.y - offset as i16,
+                width: cell.width + offset * 2 - 1,
+                height: cell.height + offset * 2 - 1,
+            })
+            .collect();
+        display.send_request(&requests::PolyRectangle {
+            drawable: Drawable::Window(self.window),
+            gc,
+            rectangles,
+        })?;
+        Ok(())
+    }
+
+    /// Draw all thumbnails, titles and the selection, e.g. after the popup was exposed
+    pub fn draw(&self, display: &mut XDisplay) -> Result<(), Error> {
+        for (idx, entry) in self.entries.iter().enumerate() {
+            let cell = self.cell(idx);
+            if let Some(thumbnail) = &entry.thumbnail {
+                thumbnail.put(display, self.window, self.gc, cell)?;
+            }
+
+            if let Some((_, metrics)) = &self.font {
+                let char_width = metrics.max_bounds.character_width.max(1) as usize;
+                // Text of `ImageText8` is Latin-1
+                let title = entry
+                    .title
+                    .chars()
+                    .map(|c| if c.is_ascii() { c as u8 } else { b'?' })
+                    .take(THUMBNAIL_WIDTH as usize / char_width)
+                    .collect::<Vec<_>>();
+                display.send_request(&requests::ImageText8 {
+                    drawable: Drawable::Window(self.window),
+                    gc: self.gc,
+                    x: cell.x,
+                    y: cell.y + (cell.height + SWITCHER_PADDING) as i16 + metrics.font_ascent,
+                    string: title,
+                })?;
+            }
+        }
+        self.draw_outline(display, self.selected, self.gc)
+    }
+
+    pub fn hide(self, display: &mut XDisplay) -> Result<(), Error> {
+        display.send_request(&requests::DestroyWindow {
+            window: self.window,
+        })?;
+        panic_hook::forget_on_panic(self.window);
+        display.send_request(&requests::FreeGC { gc: self.gc })?;
+        display.send_request(&requests::FreeGC { gc: self.erase_gc })?;
+        if let Some((font, _)) = self.font {
+            display.send_request(&requests::CloseFont { font })?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn switcher_helpers() {
+    let [a, b, c] = [1, 2, 3].map(WindowId::unchecked_from);
+    assert_eq!(focus_order(&[c, 4.into(), a], [a, b, c]), vec![c, a, b]);
+
+    assert_eq!(cycle(0, 3, false), 1);
+    assert_eq!(cycle(2, 3, false), 0);
+    assert_eq!(cycle(0, 3, true), 2);
+    assert_eq!(cycle(0, 1, false), 0);
+    assert_eq!(cycle(0, 0, true), 0);
+
+    assert_eq!(fit_size(1920, 1080, 192, 120), (192, 108));
+    assert_eq!(fit_size(600, 1200, 192, 120), (60, 120));
+    assert_eq!(fit_size(100, 50, 192, 120), (100, 50));
+
+    // 2x2 blocks of a 4x2 image, bytes are averaged separately
+    let src = [
+        [0, 0, 0, 0],
+        [4, 8, 0, 0],
+        [10, 0, 0, 255],
+        [10, 0, 0, 255],
+        [0, 0, 0, 0],
+        [4, 8, 0, 0],
+        [30, 0, 0, 255],
+        [30, 0, 0, 255],
+    ]
+    .concat();
+    assert_eq!(downscale(&src, 4, 2, 2, 1), vec![2, 4, 0, 0, 20, 0, 0, 255]);
+
+    let mapping = replies::GetModifierMapping {
+        keycodes_per_modifier: 2,
+        // Shift, Lock, Control, Mod1, ..., two keys each
+        keycodes: vec![
+            [50u8, 62, 66, 0, 37, 105, 64, 108],
+            [0, 0, 0, 0, 133, 134, 0, 0],
+        ]
+        .into_iter()
+        .map(|row| row.map(KeyCode::from))
+        .collect(),
+    };
+    assert_eq!(
+        modifier_keys(&mapping, KeyModifier::MOD_4),
+        vec![KeyCode::from(133u8), KeyCode::from(134u8)]
+    );
+    assert_eq!(
+        modifier_keys(&mapping, KeyModifier::CONTROL | KeyModifier::MOD_1),
+        [37u8, 105, 64, 108].map(KeyCode::from).to_vec()
+    );
+}
@@ -0,0 +1,31 @@
+//! Numbered workspaces beyond one per monitor
+//!
+//! Workspace `n` is index `n - 1`. Workspaces up to the shown one are created on first use and
+//! empty ones at the end are removed again, so EWMH pagers only list the ones in use.
+
+/// Most workspaces that can be created on use, unless `JUSTWINDOWS_WORKSPACES` says otherwise.
+/// Monitors get a workspace each even if there are more of them.
+pub const DEFAULT_LIMIT: usize = 10;
+
+/// Number of workspaces to keep out of `len`, dropping trailing ones that are not `in_use`
+pub fn keep_count(len: usize, in_use: impl Fn(usize) -> bool) -> usize {
+    (0..len)
+        .rev()
+        .find(|&idx| in_use(idx))
+        .map_or(0, |idx| idx + 1)
+}
+
+/// EWMH `_NET_DESKTOP_NAMES`, workspace numbers as NUL terminated UTF-8 strings
+pub fn desktop_names(count: usize) -> Vec<u8> {
+    (1..=count)
+        .flat_map(|number| format!("{}\0", number).into_bytes())
+        .collect()
+}
+
+#[test]
+fn trailing_unused_workspaces() {
+    let used = [true, false, true, false, false];
+    assert_eq!(keep_count(used.len(), |idx| used[idx]), 3);
+    assert_eq!(keep_count(2, |_| false), 0);
+    assert_eq!(desktop_names(3), b"1\x002\x003\x00");
+}
@@ -0,0 +1,353 @@
+// CLIPPY CONFIG
+#![allow(
+    clippy::new_without_default,
+    clippy::unnecessary_cast,
+    clippy::identity_op
+)]
+
+//! Zoomed view of the area around the pointer in a small window kept above other windows
+//!
+//! `cargo run --example magnifier [zoom]`, scrolling over the window changes the zoom and closing
+//! it exits. Screen is captured with MIT-SHM `GetImage` if the server supports it, otherwise with
+//! core `GetImage`. Pixels are copied in the server's format, so no conversion is needed.
+
+use just_shared_memory::SharedMemory;
+use just_x11::{
+    atoms::{wm, AtomId},
+    display,
+    error::Error,
+    events::{EventType, SomeEvent},
+    extensions::mit_shm::{self, ShmSegId},
+    replies::String8,
+    requests::{self, GetImageImageFormat, PutImageFormat, WindowCreationAttributes},
+    Drawable, GContextId, WindowClass, WindowId, WindowVisual, XDisplay,
+};
+use std::{env, process, str::FromStr, thread, time::Duration};
+
+const WINDOW_SIZE: u16 = 240;
+const DEFAULT_ZOOM: u16 = 4;
+const MAX_ZOOM: u16 = 16;
+const FRAME_INTERVAL: Duration = Duration::from_millis(33);
+const BYTES_PER_PIXEL: usize = 4;
+/// `detail` of `ButtonPress` for scrolling up and down
+const SCROLL_UP: u8 = 4;
+const SCROLL_DOWN: u8 = 5;
+
+enum Capture {
+    Shm {
+        major_opcode: u8,
+        shmseg: ShmSegId,
+        mem: SharedMemory,
+    },
+    Core,
+}
+
+impl Capture {
+    /// Capture of at most `WINDOW_SIZE` squared pixels
+    fn new(display: &mut XDisplay) -> Result<Self, Error> {
+        let Some(mit_shm) = mit_shm::negotiate(display)? else {
+            return Ok(Capture::Core);
+        };
+
+        let size = WINDOW_SIZE as usize * WINDOW_SIZE as usize * BYTES_PER_PIXEL;
+        let mem = SharedMemory::zeroed(size as u32);
+        let shmseg = ShmSegId::from_resource(display.id_allocator().allocate_id());
+        display.send_extension_request(
+            &mit_shm::requests::Attach {
+                shmseg,
+                shmid: mem.id().inner() as u32,
+                read_only: false,
+            },
+            mit_shm.major_opcode,
+        )?;
+        Ok(Capture::Shm {
+            major_opcode: mit_shm.major_opcode,
+            shmseg,
+            mem,
+        })
+    }
+
+    /// Copy `size` squared pixels of `root` at `x`, `y` into `out`
+    fn grab(
+        &mut self,
+        display: &mut XDisplay,
+        root: WindowId,
+        (x, y): (i16, i16),
+        size: u16,
+        out: &mut Vec<u8>,
+    ) -> Result<(), Error> {
+        let len = size as usize * size as usize * BYTES_PER_PIXEL;
+        match self {
+            Capture::Shm {
+                major_opcode,
+                shmseg,
+                mem,
+            } => {
+                let pending = display.send_extension_request(
+                    &mit_shm::requests::GetImage {
+                        drawable: Drawable::Window(root),
+                        x,
+                        y,
+                        width: size,
+                        height: size,
+                        plane_mask: -1, // AllPlanes
+                        format: GetImageImageFormat::ZPixmap as u8,
+                        shmseg: *shmseg,
+                        offset: 0,
+                    },
+                    *major_opcode,
+                )?;
+                display.flush()?;
+                // Reply means the server has written the memory
+                display.await_pending_reply(pending)?.unwrap();
+                out.clear();
+                out.extend_from_slice(unsafe { &mem.data()[..len] });
+            }
+            Capture::Core => {
+                let pending = display.send_request(&requests::GetImage {
+                    format: GetImageImageFormat::ZPixmap,
+                    drawable: Drawable::Window(root),
+                    x,
+                    y,
+                    width: size,
+                    height: size,
+                    plane_mask: u32::MAX,
+                })?;
+                display.flush()?;
+                let image = display.await_pending_reply(pending)?.unwrap();
+                out.clear();
+                out.extend_from_slice(&image.data[..len]);
+            }
+        }
+        Ok(())
+    }
+
+    fn free(self, display: &mut XDisplay) -> Result<(), Error> {
+        if let Capture::Shm {
+            major_opcode,
+            shmseg,
+            mem,
+        } = self
+        {
+            display.send_extension_request(&mit_shm::requests::Detach { shmseg }, major_opcode)?;
+            display.flush()?;
+            unsafe { mem.free() }
+        }
+        Ok(())
+    }
+}
+
+/// Scale `src` of `src_size` squared pixels by `zoom` into `WINDOW_SIZE` squared `dst`, with the
+/// source pixel at `marked` outlined
+fn magnify(src: &[u8], src_size: u16, zoom: u16, marked: (u16, u16), dst: &mut [u8]) {
+    let (src_size, zoom, size) = (src_size as usize, zoom as usize, WINDOW_SIZE as usize);
+    for y in 0..size {
+        let src_row = &src[(y / zoom) * src_size * BYTES_PER_PIXEL..];
+        let dst_row = &mut dst[y * size * BYTES_PER_PIXEL..(y + 1) * size * BYTES_PER_PIXEL];
+        for (x, pixel) in dst_row.chunks_exact_mut(BYTES_PER_PIXEL).enumerate() {
+            let src_offset = (x / zoom) * BYTES_PER_PIXEL;
+            pixel.copy_from_slice(&src_row[src_offset..src_offset + BYTES_PER_PIXEL]);
+        }
+    }
+
+    // Inverted outline stays visible on any color
+    let start = (marked.0 as usize * zoom, marked.1 as usize * zoom);
+    let end = (
+        (start.0 + zoom).min(size) - 1,
+        (start.1 + zoom).min(size) - 1,
+    );
+    for y in start.1..=end.1 {
+        for x in start.0..=end.0 {
+            if x == start.0 || x == end.0 || y == start.1 || y == end.1 {
+                let offset = (y * size + x) * BYTES_PER_PIXEL;
+                for channel in &mut dst[offset..offset + 3] {
+                    *channel = !*channel;
+                }
+            }
+        }
+    }
+}
+
+fn intern_atom(display: &mut XDisplay, name: String8) -> Result<AtomId, Error> {
+    let pending = display.send_request(&requests::InternAtom {
+        only_if_exists: false,
+        name,
+    })?;
+    display.flush()?;
+    Ok(display.await_pending_reply(pending)?.unwrap().atom)
+}
+
+fn create_window(display: &mut XDisplay) -> Result<(WindowId, AtomId), Error> {
+    let screen = display.default_screen().clone();
+    let window = WindowId::from_resource(display.id_allocator().allocate_id());
+    display.send_request(&requests::CreateWindow {
+        depth: screen.root_depth,
+        wid: window,
+        parent: screen.root,
+        x: 0,
+        y: 0,
+        width: WINDOW_SIZE,
+        height: WINDOW_SIZE,
+        border_width: 0,
+        window_class: WindowClass::InputOutput,
+        visual: WindowVisual::CopyFromParent,
+        attributes: WindowCreationAttributes::for_top_level(EventType::BUTTON_PRESS, None),
+    })?;
+
+    display.send_request(&requests::ChangeProperty {
+        mode: requests::ChangePropertyMode::Replace,
+        window,
+        property: AtomId::WM_NAME,
+        type_: AtomId::STRING,
+        format: requests::ChangePropertyFormat::Format8,
+        data: b"magnifier".to_vec(),
+    })?;
+
+    let wm_protocols = intern_atom(display, String8::from_str("WM_PROTOCOLS").unwrap())?;
+    let wm_delete_window = intern_atom(display, String8::from_str("WM_DELETE_WINDOW").unwrap())?;
+    display.send_request(&requests::ChangeProperty {
+        mode: requests::ChangePropertyMode::Replace,
+        window,
+        property: wm_protocols,
+        type_: AtomId::ATOM,
+        format: requests::ChangePropertyFormat::Format32,
+        data: wm_delete_window.to_le_bytes().to_vec(),
+    })?;
+
+    // Window managers read the initial state when the window is mapped
+    let net_wm_state = intern_atom(display, wm::_NET_WM_STATE())?;
+    let above = intern_atom(display, String8::from_str("_NET_WM_STATE_ABOVE").unwrap())?;
+    display.send_request(&requests::ChangeProperty {
+        mode: requests::ChangePropertyMode::Replace,
+        window,
+        property: net_wm_state,
+        type_: AtomId::ATOM,
+        format: requests::ChangePropertyFormat::Format32,
+        data: above.to_le_bytes().to_vec(),
+    })?;
+
+    display.send_request(&requests::MapWindow { window })?;
+    display.flush()?;
+    Ok((window, wm_delete_window))
+}
+
+/// Put `WINDOW_SIZE` squared `image` in as many requests as the maximum request length needs
+fn put_image(
+    display: &mut XDisplay,
+    window: WindowId,
+    gc: GContextId,
+    image: &[u8],
+) -> Result<(), Error> {
+    // PutImage header is 6 units of 4 bytes
+    let max_bytes = (display.maximum_request_length() as usize - 6) * 4;
+    let row_bytes = WINDOW_SIZE as usize * BYTES_PER_PIXEL;
+    let rows_per_request = (max_bytes / row_bytes).max(1);
+    let depth = display.default_screen().root_depth;
+    for (idx, rows) in image.chunks(rows_per_request * row_bytes).enumerate() {
+        display.send_request(&requests::PutImage {
+            format: PutImageFormat::ZPixmap,
+            drawable: Drawable::Window(window),
+            gc,
+            width: WINDOW_SIZE,
+            height: (rows.len() / row_bytes) as u16,
+            dst_x: 0,
+            dst_y: (idx * rows_per_request) as i16,
+            left_pad: 0,
+            depth,
+            data: rows,
+        })?;
+    }
+    Ok(())
+}
+
+fn go(mut display: XDisplay, mut zoom: u16) -> Result<(), Error> {
+    let screen = display.default_screen().clone();
+    // Depths 24 and 32 are stored in 32 bits per pixel by all common servers
+    if !matches!(screen.root_depth, 24 | 32) {
+        eprintln!("magnifier: Only screens of depth 24 or 32 are supported");
+        process::exit(1);
+    }
+
+    let (window, wm_delete_window) = create_window(&mut display)?;
+    let gc = display.default_gc(display.default_screen_index())?;
+    let mut capture = Capture::new(&mut display)?;
+    let mut captured = Vec::new();
+    let mut magnified = vec![0u8; WINDOW_SIZE as usize * WINDOW_SIZE as usize * BYTES_PER_PIXEL];
+
+    loop {
+        let mut closed = false;
+        for event in display.events()? {
+            match event {
+                SomeEvent::ClientMessage(message)
+                    if message.data[..4] == wm_delete_window.to_le_bytes() =>
+                {
+                    closed = true;
+                }
+                SomeEvent::ButtonPress(event) => match event.detail.raw() {
+                    SCROLL_UP => zoom = (zoom + 1).min(MAX_ZOOM),
+                    SCROLL_DOWN => zoom = (zoom - 1).max(1),
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+        if closed {
+            return capture.free(&mut display);
+        }
+
+        let pending = display.send_request(&requests::QueryPointer {
+            window: screen.root,
+        })?;
+        display.flush()?;
+        let pointer = display.await_pending_reply(pending)?.unwrap();
+
+        // Area around the pointer, moved to stay on the screen
+        let src_size = WINDOW_SIZE.div_ceil(zoom);
+        let origin = (
+            (pointer.root_x - src_size as i16 / 2)
+                .clamp(0, screen.width_in_pixels.saturating_sub(src_size) as i16),
+            (pointer.root_y - src_size as i16 / 2)
+                .clamp(0, screen.height_in_pixels.saturating_sub(src_size) as i16),
+        );
+        capture.grab(&mut display, screen.root, origin, src_size, &mut captured)?;
+        let marked = (
+            (pointer.root_x - origin.0).clamp(0, src_size as i16 - 1) as u16,
+            (pointer.root_y - origin.1).clamp(0, src_size as i16 - 1) as u16,
+        );
+        magnify(&captured, src_size, zoom, marked, &mut magnified);
+        put_image(&mut display, window, gc, &magnified)?;
+        display.flush()?;
+
+        thread::sleep(FRAME_INTERVAL);
+    }
+}
+
+fn main() {
+    let mut args = env::args().skip(1).collect::<Vec<_>>();
+    let display = match display::take_display_arg(&mut args)
+        .and_then(|name| display::open(name.as_deref()))
+    {
+        Ok(display) => display,
+        Err(err) => {
+            eprintln!("magnifier: error: {}", err);
+            process::exit(1);
+        }
+    };
+
+    let zoom = match args.first().map(|zoom| zoom.parse::<u16>()) {
+        None => DEFAULT_ZOOM,
+        Some(Ok(zoom)) if (1..=MAX_ZOOM).contains(&zoom) => zoom,
+        Some(_) => {
+            eprintln!(
+                "Usage: magnifier [--display DISPLAY] [zoom from 1 to {}]",
+                MAX_ZOOM
+            );
+            process::exit(2);
+        }
+    };
+
+    if let Err(err) = go(display, zoom) {
+        eprintln!("magnifier: error: {}", err);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,120 @@
+// CLIPPY CONFIG
+#![allow(
+    clippy::new_without_default,
+    clippy::unnecessary_cast,
+    clippy::identity_op
+)]
+
+//! Minimal `xmodmap`, keysyms are given by name or by value, e.g. `Caps_Lock` or `0xffe5`
+//!
+//! `cargo run --example remap -- swap Caps_Lock Escape` swaps `Caps_Lock` and `Escape`,
+//! `map <from> <to>` remaps one way and `print` lists modifiers and keys with their keysyms.
+
+use just_x11::{
+    display,
+    error::Error,
+    keymap::{KeyRemap, MODIFIER_COUNT},
+    keysym::{self, KeySym},
+    replies::SetModifierMappingStatus,
+    XDisplay,
+};
+use std::process::ExitCode;
+
+const MODIFIER_NAMES: [&str; MODIFIER_COUNT] = [
+    "shift", "lock", "control", "mod1", "mod2", "mod3", "mod4", "mod5",
+];
+
+enum Mode {
+    Print,
+    Remap(KeyRemap),
+}
+
+fn parse_keysym(s: &str) -> Option<KeySym> {
+    keysym::keysym_from_name(s).or_else(|| {
+        Some(KeySym {
+            inner: s.parse().ok()?,
+        })
+    })
+}
+
+fn print(display: &mut XDisplay) -> Result<(), Error> {
+    let modifiers = display.get_modifier_mapping()?;
+    for (idx, name) in MODIFIER_NAMES.iter().enumerate() {
+        let keycodes = modifiers
+            .modifier(idx)
+            .map(|keycode| keycode.raw().to_string())
+            .collect::<Vec<_>>();
+        println!("{:<8} {}", name, keycodes.join(", "));
+    }
+    println!();
+
+    let keyboard = display.get_keyboard_mapping()?;
+    for keycode in keyboard.keycodes() {
+        let keysyms = keyboard.keysyms(keycode);
+        if keysyms.iter().all(|keysym| *keysym == KeySym::NO_SYMBOL) {
+            continue;
+        }
+        let keysyms = keysyms
+            .iter()
+            .map(|keysym| match keysym::name_from_keysym(*keysym) {
+                Some(name) => name.to_string(),
+                None => format!("0x{:04x}", keysym.inner),
+            })
+            .collect::<Vec<_>>();
+        println!("keycode {:>3} = {}", keycode.raw(), keysyms.join(" "));
+    }
+    Ok(())
+}
+
+fn go(mut display: XDisplay, mode: Mode) -> Result<ExitCode, Error> {
+    match mode {
+        Mode::Print => print(&mut display)?,
+        Mode::Remap(remap) => match display.remap_keys(&remap)? {
+            SetModifierMappingStatus::Success => {}
+            SetModifierMappingStatus::Busy => {
+                eprintln!("remap: Modifier keys are held down, release them and try again");
+                return Ok(ExitCode::FAILURE);
+            }
+            SetModifierMappingStatus::Failed => {
+                eprintln!("remap: Server rejected the modifier mapping");
+                return Ok(ExitCode::FAILURE);
+            }
+        },
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn main() -> ExitCode {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let display = match display::take_display_arg(&mut args)
+        .and_then(|name| display::open(name.as_deref()))
+    {
+        Ok(display) => display,
+        Err(err) => {
+            eprintln!("remap: error: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let keysyms = |a: &str, b: &str| Some((parse_keysym(a)?, parse_keysym(b)?));
+    let mode = match args.as_slice() {
+        ["print"] => Some(Mode::Print),
+        ["swap", a, b] => keysyms(a, b).map(|(a, b)| Mode::Remap(KeyRemap::new().swap(a, b))),
+        ["map", from, to] => {
+            keysyms(from, to).map(|(from, to)| Mode::Remap(KeyRemap::new().map(from, to)))
+        }
+        _ => None,
+    };
+    let Some(mode) = mode else {
+        eprintln!("Usage: remap print | swap <keysym> <keysym> | map <from> <to>");
+        return ExitCode::from(2);
+    };
+
+    match go(display, mode) {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("remap: error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -6,9 +6,10 @@
 )]
 
 use just_x11::{
+    display,
     error::Error,
     extensions::mit_shm::{self, ShmSegId},
-    requests, Drawable, XDisplay,
+    requests, Drawable, Visual, XDisplay,
 };
 use std::{
     fs::File,
@@ -30,7 +31,14 @@ fn bits_per_pixel(depth: u8) -> u8 {
     }
 }
 
-fn save_as_ppm<P>(img: &[u8], width: u16, height: u16, filepath: P) -> io::Result<()>
+/// `img` has 32 bit pixels with channels where `visual` masks say, 8 bits each
+fn save_as_ppm<P>(
+    img: &[u8],
+    visual: &Visual,
+    width: u16,
+    height: u16,
+    filepath: P,
+) -> io::Result<()>
 where
     P: AsRef<Path>,
 {
@@ -39,20 +47,20 @@ where
     writeln!(f, "{} {}", width, height)?;
     writeln!(f, "255")?;
 
-    for y in 0..(height as usize) {
-        for x in 0..(width as usize) {
-            f.write_all(&[img[(y * width as usize + x) * 4 + 2]])?;
-            f.write_all(&[img[(y * width as usize + x) * 4 + 1]])?;
-            f.write_all(&[img[(y * width as usize + x) * 4 + 0]])?;
-        }
+    let channel = |pixel: u32, mask: u32| (pixel >> mask.trailing_zeros()) as u8;
+    for pixel in img[..width as usize * height as usize * 4].chunks_exact(4) {
+        let pixel = u32::from_le_bytes([pixel[0], pixel[1], pixel[2], pixel[3]]);
+        f.write_all(&[
+            channel(pixel, visual.red_mask),
+            channel(pixel, visual.green_mask),
+            channel(pixel, visual.blue_mask),
+        ])?;
     }
 
     f.flush()
 }
 
-fn go() -> Result<(), Error> {
-    let mut display = XDisplay::open()?;
-
+fn go(mut display: XDisplay) -> Result<(), Error> {
     macro_rules! send_request {
         ($request:expr) => {{
             let pending_reply = display.send_request(&($request))?;
@@ -82,7 +90,7 @@ fn go() -> Result<(), Error> {
 
     let version = send_mit_shm_request_reply!(&mit_shm::requests::QueryVersion);
 
-    let root_window = display.screens()[0].root;
+    let root_window = display.default_screen().root;
 
     println!(
         "SHM Version {}.{}, Pixmaps supported: {}",
@@ -105,7 +113,7 @@ fn go() -> Result<(), Error> {
     let size = geometry.width as u32 * geometry.height as u32 * (bits_per_pixel(depth) as u32 / 8);
     let shm = just_shared_memory::SharedMemory::zeroed(size);
 
-    let shmseg = ShmSegId::from(display.id_allocator().allocate_id());
+    let shmseg = ShmSegId::from_resource(display.id_allocator().allocate_id());
 
     send_mit_shm_request!(&mit_shm::requests::Attach {
         shmseg,
@@ -125,9 +133,16 @@ fn go() -> Result<(), Error> {
         offset: 0,
     });
 
+    let visual = display
+        .default_screen()
+        .root_visual_type()
+        .expect("Root visual is listed in the setup")
+        .clone();
+
     unsafe {
         save_as_ppm(
             shm.data(),
+            &visual,
             geometry.width,
             geometry.height,
             "screenshot.ppm",
@@ -143,5 +158,7 @@ fn go() -> Result<(), Error> {
 }
 
 fn main() {
-    go().unwrap();
+    let mut args = std::env::args().skip(1).collect::<Vec<_>>();
+    let name = display::take_display_arg(&mut args).unwrap();
+    go(display::open(name.as_deref()).unwrap()).unwrap();
 }
@@ -6,6 +6,7 @@
 )]
 
 use just_x11::{
+    display,
     error::Error,
     extensions::randr,
     replies,
@@ -118,7 +119,7 @@ fn lsmonitors(display: &mut XDisplay) -> Result<(), Error> {
 
     let monitors_reply = {
         let request = randr::requests::GetMonitors {
-            window: display.screens()[0].root,
+            window: display.default_screen().root,
             get_active: false,
         };
 
@@ -138,19 +139,20 @@ fn lsmonitors(display: &mut XDisplay) -> Result<(), Error> {
         };
         dbg!(&name);
 
-        let crtc = {
-            let pending = display.send_extension_request(
-                &randr::requests::GetCrtcInfo {
-                    crtc: dbg!(monitor.crtcs[0]),
-                    // crtc: CrtcId::unchecked_from(62),
-                    timestamp: dbg!(monitors_reply.timestamp),
-                },
-                randr_major_opcode,
-            )?;
-            display.flush()?;
-            display.await_pending_reply(pending)?
-        };
-        dbg!(&crtc);
+        for output in monitor.outputs.iter().copied() {
+            let output_info = {
+                let pending = display.send_extension_request(
+                    &randr::requests::GetOutputInfo {
+                        output,
+                        timestamp: monitors_reply.timestamp,
+                    },
+                    randr_major_opcode,
+                )?;
+                display.flush()?;
+                display.await_pending_reply(pending)?
+            };
+            dbg!(&output_info);
+        }
 
         eprintln!(
             "{} {}x{}+{}+{} (...) {}mmx{}mm",
@@ -178,9 +180,7 @@ enum Mode {
     LsMonitors,
 }
 
-fn go(mode: Mode) -> Result<(), Error> {
-    let mut display = XDisplay::open()?;
-
+fn go(mut display: XDisplay, mode: Mode) -> Result<(), Error> {
     match mode {
         Mode::LsFonts => lsfonts(&mut display),
         Mode::LsExtensions => lsextensions(&mut display),
@@ -189,7 +189,16 @@ fn go(mode: Mode) -> Result<(), Error> {
 }
 
 fn main() -> ExitCode {
-    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let mut args = std::env::args().skip(1).collect::<Vec<_>>();
+    let display = match display::take_display_arg(&mut args)
+        .and_then(|name| display::open(name.as_deref()))
+    {
+        Ok(display) => display,
+        Err(err) => {
+            eprintln!("xinfo: error: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
     let args = args.iter().map(|s| s.as_ref()).collect::<Vec<_>>();
     let mode = match &args.as_slice() {
         ["ls", "fonts"] => Mode::LsFonts,
@@ -201,7 +210,7 @@ fn main() -> ExitCode {
         }
     };
 
-    match go(mode) {
+    match go(display, mode) {
         Ok(()) => ExitCode::SUCCESS,
         Err(err) => {
             eprintln!("xinfo: error: {}", err);
@@ -9,6 +9,10 @@ macro_rules! define_atom_id {
 }
 
 impl AtomId {
+    /// `AnyPropertyType` of [`GetProperty`](crate::requests::GetProperty), matches property of
+    /// any type
+    pub const ANY_PROPERTY_TYPE: Self = Self(ResourceId { value: 0 });
+
     define_atom_id! {
         PRIMARY = 1,
         SECONDARY = 2,
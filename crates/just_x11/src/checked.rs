@@ -0,0 +1,160 @@
+//! Requests without replies whose errors are awaited individually, like checked requests of xcb
+//!
+//! Errors of plain requests without replies go to [`XDisplay::errors`] and nothing is stored while
+//! they are in flight, which is what fire-and-forget traffic wants. A checked request is tracked
+//! until [`CheckedRequest::check`], which tells whether exactly that request failed, or until its
+//! handle is dropped.
+
+use crate::{
+    error::Error,
+    requests::{self, NoReply, XExtensionRequest, XRequest},
+    xerror::SomeError,
+    SequenceNumber, XDisplay,
+};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+/// Checked requests in flight with their error once it arrives, shared with their handles
+pub(crate) type CheckedRequests = Rc<RefCell<HashMap<SequenceNumber, Option<SomeError>>>>;
+
+/// Handle to a request sent with [`XDisplay::send_request_checked`]. Its error is kept until
+/// [`CheckedRequest::check`] takes it. Dropping the handle stops tracking the request, an error
+/// that already arrived is discarded and a later one goes to [`XDisplay::errors`].
+// NOTE: Don't derive Clone and Copy, same as `PendingReply`
+#[must_use = "error of the request is discarded unless it is checked"]
+pub struct CheckedRequest {
+    sequence_number: SequenceNumber,
+    checked_requests: CheckedRequests,
+}
+
+impl std::fmt::Debug for CheckedRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CheckedRequest")
+            .field("sequence_number", &self.sequence_number)
+            .finish()
+    }
+}
+
+impl CheckedRequest {
+    pub fn sequence_number(&self) -> SequenceNumber {
+        self.sequence_number
+    }
+
+    /// Error of the request, if any. Blocks for a round trip unless the error already arrived.
+    pub fn check(self, display: &mut XDisplay) -> Result<Result<(), SomeError>, Error> {
+        let arrived = matches!(
+            self.checked_requests.borrow().get(&self.sequence_number),
+            Some(Some(_))
+        );
+        if !arrived {
+            // Replies come in order, so once this one arrives the request was processed
+            let sync = display.send_request(&requests::GetInputFocus)?;
+            let _ = display.await_pending_reply(sync)?;
+        }
+
+        // Entry is missing only if a request with the same sequence number replaced it after
+        // the sequence number wrapped around and that handle was dropped
+        match self
+            .checked_requests
+            .borrow_mut()
+            .remove(&self.sequence_number)
+        {
+            Some(Some(error)) => Ok(Err(error)),
+            Some(None) | None => Ok(Ok(())),
+        }
+    }
+}
+
+impl Drop for CheckedRequest {
+    fn drop(&mut self) {
+        self.checked_requests
+            .borrow_mut()
+            .remove(&self.sequence_number);
+    }
+}
+
+impl XDisplay {
+    /// Like [`Self::send_request`] but the error of the request is returned by
+    /// [`CheckedRequest::check`] instead of [`Self::errors`]
+    pub fn send_request_checked<Request: XRequest<Reply = NoReply>>(
+        &mut self,
+        request: &Request,
+    ) -> Result<CheckedRequest, Error> {
+        let pending = self.send_request(request)?;
+        Ok(self.track_checked(pending.sequence_number()))
+    }
+
+    /// Like [`Self::send_request_checked`] but for X11 extensions requests
+    pub fn send_extension_request_checked<Request: XExtensionRequest<Reply = NoReply>>(
+        &mut self,
+        request: &Request,
+        major_opcode: u8,
+    ) -> Result<CheckedRequest, Error> {
+        let pending = self.send_extension_request(request, major_opcode)?;
+        Ok(self.track_checked(pending.sequence_number()))
+    }
+
+    fn track_checked(&mut self, sequence_number: SequenceNumber) -> CheckedRequest {
+        // After the sequence number wraps around an unchecked request that is still tracked is
+        // long processed, its entry is replaced
+        self.checked_requests
+            .borrow_mut()
+            .insert(sequence_number, None);
+        CheckedRequest {
+            sequence_number,
+            checked_requests: self.checked_requests.clone(),
+        }
+    }
+}
+
+#[test]
+fn checked_request_errors() {
+    use crate::{connection::ScriptStep, input_focus_reply, scripted_display, WindowId};
+
+    // BadWindow errors of the first two requests
+    let bad_window = |sequence_number: u16| {
+        let mut error = vec![0, 3];
+        error.extend(sequence_number.to_le_bytes());
+        error.extend([0u8; 28]);
+        error
+    };
+    let mut display = scripted_display([
+        ScriptStep::data(bad_window(1)),
+        ScriptStep::data(bad_window(2)),
+        ScriptStep::data(input_focus_reply(4, 0)),
+        ScriptStep::data(input_focus_reply(5, 0)),
+        ScriptStep::data(bad_window(6)),
+        ScriptStep::data(input_focus_reply(7, 0)),
+    ])
+    .unwrap();
+    let map_window = requests::MapWindow {
+        window: WindowId::from(0x400001),
+    };
+
+    display.send_request(&map_window).unwrap();
+    let failed = display.send_request_checked(&map_window).unwrap();
+    let succeeded = display.send_request_checked(&map_window).unwrap();
+
+    let error = failed.check(&mut display).unwrap().unwrap_err();
+    assert_eq!(error.sequence_number(), SequenceNumber { value: 2 });
+    assert!(succeeded.check(&mut display).unwrap().is_ok());
+
+    // Error of the unchecked request is where it always was
+    let errors = display.errors().collect::<Vec<_>>();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].sequence_number(), SequenceNumber { value: 1 });
+    assert!(display.checked_requests.borrow().is_empty());
+
+    // Error of a dropped handle goes to the other errors
+    drop(display.send_request_checked(&map_window).unwrap());
+    assert!(display.checked_requests.borrow().is_empty());
+    let sync = display.send_request(&requests::GetInputFocus).unwrap();
+    display.await_pending_reply(sync).unwrap().unwrap();
+    let errors = display.errors().collect::<Vec<_>>();
+    assert_eq!(errors[0].sequence_number(), SequenceNumber { value: 6 });
+
+    // Sequence number of a request still tracked after wrapping around
+    let stale = display.track_checked(SequenceNumber { value: 8 });
+    let fresh = display.track_checked(SequenceNumber { value: 8 });
+    drop((stale, fresh));
+    assert!(display.checked_requests.borrow().is_empty());
+}
@@ -4,16 +4,46 @@ use crate::{
 };
 use std::{
     collections::{vec_deque::Drain, VecDeque},
+    ffi::c_int,
     fmt::Display,
     io::{self, BufWriter, Read, Write},
-    os::unix::net::UnixStream,
+    os::{
+        fd::{FromRawFd, RawFd},
+        unix::net::UnixStream,
+    },
     str::FromStr,
 };
 
+const F_GETFD: c_int = 1;
+const F_SETFD: c_int = 2;
+const FD_CLOEXEC: c_int = 1;
+
+extern "C" {
+    fn fcntl(fd: c_int, cmd: c_int, ...) -> c_int;
+}
+
+/// Set or clear `FD_CLOEXEC` of `fd`. Descriptors opened by the standard library have it set,
+/// so they are closed when the process calls `exec`.
+pub fn set_close_on_exec(fd: RawFd, close: bool) -> io::Result<()> {
+    let flags = unsafe { fcntl(fd, F_GETFD) };
+    if flags == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    let flags = if close {
+        flags | FD_CLOEXEC
+    } else {
+        flags & !FD_CLOEXEC
+    };
+    if unsafe { fcntl(fd, F_SETFD, flags) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
 pub(crate) enum XConnectionReader {
     UnixStream(UnixStream),
     #[cfg(test)]
-    Empty,
+    Test(TestConnection),
 }
 
 impl Read for XConnectionReader {
@@ -21,7 +51,70 @@ impl Read for XConnectionReader {
         match self {
             XConnectionReader::UnixStream(stream) => stream.read(buf),
             #[cfg(test)]
-            XConnectionReader::Empty => Err(io::Error::new(io::ErrorKind::UnexpectedEof, "EOF")),
+            XConnectionReader::Test(test) => test.read(buf),
+        }
+    }
+}
+
+/// What the scripted server does on the next read, see [`TestConnection`]
+#[cfg(test)]
+#[derive(Debug, Clone)]
+pub(crate) enum ScriptStep {
+    /// Bytes sent by the server, each read gets at most `chunk` of them
+    Data { bytes: Vec<u8>, chunk: usize },
+    /// Nothing arrived yet
+    WouldBlock,
+    /// Read fails, e.g. with [`io::ErrorKind::ConnectionReset`]
+    Error(io::ErrorKind),
+}
+
+#[cfg(test)]
+impl ScriptStep {
+    /// Bytes delivered in a single read
+    pub(crate) fn data(bytes: impl Into<Vec<u8>>) -> Self {
+        let bytes = bytes.into();
+        let chunk = bytes.len();
+        Self::Data { bytes, chunk }
+    }
+}
+
+/// Server side of a connection replaying scripted reads, to inject short reads and errors at
+/// exact points of the stream. Server closes the connection when the script ends.
+#[cfg(test)]
+pub(crate) struct TestConnection {
+    script: VecDeque<ScriptStep>,
+    /// Bytes sent by the client
+    written: std::rc::Rc<std::cell::RefCell<Vec<u8>>>,
+}
+
+#[cfg(test)]
+impl TestConnection {
+    pub(crate) fn new(script: impl IntoIterator<Item = ScriptStep>) -> Self {
+        Self {
+            script: script.into_iter().collect(),
+            written: Default::default(),
+        }
+    }
+
+    /// Bytes flushed by the client so far, including the connection setup
+    pub(crate) fn written(&self) -> std::rc::Rc<std::cell::RefCell<Vec<u8>>> {
+        self.written.clone()
+    }
+
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.script.pop_front() {
+            None => Ok(0),
+            Some(ScriptStep::Data { mut bytes, chunk }) => {
+                let n = bytes.len().min(chunk.max(1)).min(buf.len());
+                buf[..n].copy_from_slice(&bytes[..n]);
+                bytes.drain(..n);
+                if !bytes.is_empty() {
+                    self.script.push_front(ScriptStep::Data { bytes, chunk });
+                }
+                Ok(n)
+            }
+            Some(ScriptStep::WouldBlock) => Err(io::ErrorKind::WouldBlock.into()),
+            Some(ScriptStep::Error(kind)) => Err(kind.into()),
         }
     }
 }
@@ -29,11 +122,13 @@ impl Read for XConnectionReader {
 // We need non-blocking socket for reading so we have this wrapper to block on writes
 struct BlockingWriter<W> {
     inner: W,
+    /// Bytes accepted by `inner`
+    written: u64,
 }
 
 impl<W> BlockingWriter<W> {
     fn new(inner: W) -> Self {
-        Self { inner }
+        Self { inner, written: 0 }
     }
 
     fn do_blocking<T>(&mut self, f: impl Fn(&mut Self) -> io::Result<T>) -> io::Result<T> {
@@ -52,7 +147,9 @@ where
     W: Write,
 {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        self.do_blocking(|w| w.inner.write(buf))
+        let n = self.do_blocking(|w| w.inner.write(buf))?;
+        self.written += n as u64;
+        Ok(n)
     }
 
     fn flush(&mut self) -> io::Result<()> {
@@ -70,6 +167,14 @@ pub struct XConnection {
     fill_buf: Box<[u8]>,
 
     write_end: BlockingWriter<BufWriter<Box<dyn Write>>>,
+
+    /// Request being serialized. Requests are passed to `write_end` whole, so the buffer is only
+    /// ever flushed between requests and a cloned socket can be written to after a flush, see
+    /// [`crate::XDisplay::try_clone_socket`].
+    request_buf: Vec<u8>,
+
+    /// Bytes received from the server, see [`crate::stats::Stats`]
+    bytes_read: u64,
 }
 
 // Arbitrarly chosen
@@ -87,28 +192,87 @@ impl TryFrom<UnixStream> for XConnection {
         Ok(Self {
             read_end: XConnectionReader::UnixStream(read_end),
             write_end: BlockingWriter::new(BufWriter::new(Box::new(write_end))),
+            request_buf: Vec::new(),
             read_buf: VecDeque::new(),
             fill_buf: vec![0u8; FILL_BUFF_SIZE].into_boxed_slice(),
+            bytes_read: 0,
         })
     }
 }
 
 pub(crate) enum ConnectionKind {
     UnixStream,
+    /// Scripted connection that does not need authorization
+    #[cfg(test)]
+    Test,
 }
 
 impl XConnection {
     #[cfg(test)]
     /// Create dummy connection with some pre-filled data, not connected to anything
     pub fn dummy(data: VecDeque<u8>) -> Self {
+        let mut conn = Self::scripted(TestConnection::new([]));
+        conn.read_buf = data;
+        conn
+    }
+
+    #[cfg(test)]
+    /// Connection reading from scripted server, requests written to it are collected in
+    /// [`TestConnection::written`]
+    pub(crate) fn scripted(server: TestConnection) -> Self {
+        struct Written(std::rc::Rc<std::cell::RefCell<Vec<u8>>>);
+
+        impl Write for Written {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0.borrow_mut().write(buf)
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let written = Written(server.written());
         Self {
-            read_end: XConnectionReader::Empty,
-            read_buf: data,
-            fill_buf: vec![].into_boxed_slice(),
-            write_end: BlockingWriter::new(BufWriter::new(Box::new(std::io::empty()))),
+            read_end: XConnectionReader::Test(server),
+            request_buf: Vec::new(),
+            read_buf: VecDeque::new(),
+            fill_buf: vec![0u8; FILL_BUFF_SIZE].into_boxed_slice(),
+            write_end: BlockingWriter::new(BufWriter::new(Box::new(written))),
+            bytes_read: 0,
         }
     }
 
+    /// Connection on a socket that is already connected, e.g. inherited through `exec`. Unlike
+    /// [`Self::with_display`] the connection setup was done by whoever opened it, see
+    /// [`crate::XDisplay::resume`].
+    ///
+    /// # Safety
+    ///
+    /// `fd` must be an open unix socket connected to an X server and must not be used by anything
+    /// else, it is closed when the connection is dropped.
+    pub unsafe fn from_raw_fd(fd: RawFd) -> Result<Self, Error> {
+        Self::try_from(UnixStream::from_raw_fd(fd))
+    }
+
+    pub(crate) fn try_clone_stream(&self) -> io::Result<UnixStream> {
+        match &self.read_end {
+            XConnectionReader::UnixStream(stream) => stream.try_clone(),
+            #[cfg(test)]
+            XConnectionReader::Test(_) => Err(io::ErrorKind::Unsupported.into()),
+        }
+    }
+
+    /// Bytes received and sent, requests still in the write buffer are counted as sent
+    pub(crate) fn bytes_transferred(&self) -> (u64, u64) {
+        (self.bytes_read, self.write_end.written)
+    }
+
+    pub(crate) fn reset_bytes_transferred(&mut self) {
+        self.bytes_read = 0;
+        self.write_end.written = 0;
+    }
+
     pub(crate) fn has_unconsumed_data(&self) -> bool {
         !self.read_buf.is_empty()
     }
@@ -117,7 +281,7 @@ impl XConnection {
         match self.read_end {
             XConnectionReader::UnixStream(_) => ConnectionKind::UnixStream,
             #[cfg(test)]
-            XConnectionReader::Empty => unimplemented!(),
+            XConnectionReader::Test(_) => ConnectionKind::Test,
         }
     }
 
@@ -128,6 +292,19 @@ impl XConnection {
         Ok(())
     }
 
+    /// Next `len` bytes, they are still returned by following reads
+    pub(crate) fn peek_n_bytes(&mut self, len: usize) -> Result<Vec<u8>, Error> {
+        self.ensure_buffer_size(len)?;
+        Ok(self.read_buf.range(..len).copied().collect())
+    }
+
+    /// Put `data` in front of bytes that were not read yet
+    pub(crate) fn unread(&mut self, data: &[u8]) {
+        for byte in data.iter().rev() {
+            self.read_buf.push_front(*byte);
+        }
+    }
+
     pub(crate) fn drain(&mut self, len: usize) -> Result<Drain<'_, u8>, Error> {
         self.ensure_buffer_size(len)?;
         Ok(self.read_buf.drain(0..len))
@@ -197,12 +374,14 @@ impl XConnection {
     }
 
     pub(crate) fn peek(&mut self, index: usize) -> Result<u8, Error> {
-        self.ensure_buffer_size(index)?;
+        self.ensure_buffer_size(index + 1)?;
         Ok(*self.read_buf.get(index).unwrap())
     }
 
     pub(crate) fn send_request<R: XRequest>(&mut self, request: &R) -> Result<(), Error> {
-        request.to_le_bytes(&mut self.write_end)?;
+        self.request_buf.clear();
+        request.to_le_bytes(&mut self.request_buf)?;
+        self.write_end.write_all(&self.request_buf)?;
         Ok(())
     }
 
@@ -211,8 +390,10 @@ impl XConnection {
         request: &R,
         major_opcode: u8,
     ) -> Result<(), Error> {
-        self.write_end.write_all(&major_opcode.to_le_bytes())?;
-        request.to_le_bytes(&mut self.write_end)?;
+        self.request_buf.clear();
+        self.request_buf.push(major_opcode);
+        request.to_le_bytes(&mut self.request_buf)?;
+        self.write_end.write_all(&self.request_buf)?;
         Ok(())
     }
 
@@ -227,12 +408,7 @@ impl XConnection {
             return Err(Error::CouldNotConnectTo(display.to_string()));
         }
 
-        // TODO: Use display.screen for something
-        assert_eq!(
-            display.screen, None,
-            "Display screen is not implemented yet"
-        );
-
+        // Screen is selected by `XDisplay::open_display`, it does not change the socket
         let socket_path = format!("/tmp/.X11-unix/X{}", display.display_sequence);
         let stream = UnixStream::connect(&socket_path)
             .map_err(|err| Error::CouldNotOpenUnixSocket(socket_path, err))?;
@@ -244,10 +420,24 @@ impl XConnection {
         Ok(())
     }
 
+    /// Flush and shut down both directions of the socket
+    pub(crate) fn shutdown(&mut self) -> Result<(), Error> {
+        self.flush()?;
+        match &self.read_end {
+            XConnectionReader::UnixStream(stream) => stream.shutdown(std::net::Shutdown::Both)?,
+            #[cfg(test)]
+            XConnectionReader::Test(_) => {}
+        }
+        Ok(())
+    }
+
     /// `true` if read any new data
     pub(crate) fn fill_buf_nonblocking(&mut self) -> Result<bool, Error> {
         match self.read_end.read(&mut self.fill_buf) {
+            // Would otherwise be retried forever by callers waiting for more data
+            Ok(0) => Err(Error::ConnectionClosed),
             Ok(n) => {
+                self.bytes_read += n as u64;
                 self.read_buf.extend(&self.fill_buf[0..n]);
                 Ok(true)
             }
@@ -257,7 +447,7 @@ impl XConnection {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct DisplayVar {
     pub hostname: String,
     pub display_sequence: u32,
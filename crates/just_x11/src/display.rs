@@ -0,0 +1,162 @@
+//! Display selection shared by all binaries: `--display` flag first, `$DISPLAY` otherwise.
+//!
+//! Errors explain what is wrong with the session, e.g. missing `$DISPLAY` in a Wayland session
+//! without Xwayland or a display number no server listens on.
+
+use crate::{connection::DisplayVar, error::Error, XDisplay};
+use std::{env, fmt, io, str::FromStr};
+
+#[derive(Debug)]
+pub enum DisplayError {
+    /// Neither `--display` nor `$DISPLAY` is set, with `$XDG_SESSION_TYPE` if known
+    NotSet(Option<String>),
+    /// `--display` flag without a value
+    MissingArgument,
+    Invalid(String),
+    /// Only local unix sockets are supported
+    Remote(String),
+    /// Socket of the display does not exist or nobody is listening on it
+    NoServer(DisplayVar, String, io::Error),
+    /// Screen of the display name and number of screens the server has
+    NoSuchScreen(DisplayVar, u32, usize),
+    X11(Error),
+}
+
+impl fmt::Display for DisplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DisplayError::NotSet(session) => {
+                write!(f, "No display given, pass --display or set $DISPLAY")?;
+                match session.as_deref() {
+                    Some("wayland") => write!(f, " (Wayland session, is Xwayland running?)"),
+                    Some("tty") => write!(f, " (text console session, start X first)"),
+                    _ => Ok(()),
+                }
+            }
+            DisplayError::MissingArgument => write!(f, "--display expects an argument"),
+            DisplayError::Invalid(name) => write!(
+                f,
+                "Invalid display '{}', expected ':NUMBER' or ':NUMBER.SCREEN', e.g. ':0' or ':1.0'",
+                name
+            ),
+            DisplayError::Remote(name) => write!(
+                f,
+                "Display '{}' is on another host, only local displays are supported",
+                name
+            ),
+            DisplayError::NoServer(display, socket, err)
+                if err.kind() == io::ErrorKind::NotFound =>
+            {
+                write!(
+                    f,
+                    "No X server is running on display '{}', socket '{}' does not exist",
+                    display, socket
+                )
+            }
+            DisplayError::NoServer(display, socket, err) => write!(
+                f,
+                "Could not connect to display '{}' at '{}': {}",
+                display, socket, err
+            ),
+            DisplayError::NoSuchScreen(display, screen, screens) => write!(
+                f,
+                "Display '{}' has no screen {}, it has {} screen{}",
+                display,
+                screen,
+                screens,
+                if *screens == 1 { "" } else { "s" }
+            ),
+            DisplayError::X11(err) => err.fmt(f),
+        }
+    }
+}
+
+impl From<Error> for DisplayError {
+    fn from(err: Error) -> Self {
+        Self::X11(err)
+    }
+}
+
+/// Remove `--display DISPLAY` or `--display=DISPLAY` from `args`, leaving other arguments for the
+/// program to parse
+pub fn take_display_arg(args: &mut Vec<String>) -> Result<Option<String>, DisplayError> {
+    let Some(idx) = args
+        .iter()
+        .position(|arg| arg == "--display" || arg.starts_with("--display="))
+    else {
+        return Ok(None);
+    };
+
+    let arg = args.remove(idx);
+    match arg.strip_prefix("--display=") {
+        Some(display) => Ok(Some(display.to_string())),
+        None if idx < args.len() => Ok(Some(args.remove(idx))),
+        None => Err(DisplayError::MissingArgument),
+    }
+}
+
+/// Display named by the `--display` flag, or `$DISPLAY` if it's `None`
+pub fn resolve(name: Option<&str>) -> Result<DisplayVar, DisplayError> {
+    let name = match name {
+        Some(name) => name.to_string(),
+        None => match env::var("DISPLAY") {
+            Ok(name) if !name.is_empty() => name,
+            _ => return Err(DisplayError::NotSet(env::var("XDG_SESSION_TYPE").ok())),
+        },
+    };
+
+    let display = DisplayVar::from_str(&name).map_err(|_| DisplayError::Invalid(name.clone()))?;
+    if !display.hostname.is_empty() && display.hostname != "unix" {
+        return Err(DisplayError::Remote(name));
+    }
+    Ok(DisplayVar {
+        hostname: String::new(),
+        ..display
+    })
+}
+
+/// Connect to `display`, its screen becomes [`XDisplay::default_screen`]
+pub fn connect(display: DisplayVar) -> Result<XDisplay, DisplayError> {
+    XDisplay::open_display(display.clone()).map_err(|err| match err {
+        Error::CouldNotOpenUnixSocket(socket, err) => DisplayError::NoServer(display, socket, err),
+        Error::NoSuchScreen(screen, screens) => {
+            DisplayError::NoSuchScreen(display, screen, screens)
+        }
+        err => DisplayError::X11(err),
+    })
+}
+
+/// [`resolve`] and [`connect`]
+pub fn open(name: Option<&str>) -> Result<XDisplay, DisplayError> {
+    connect(resolve(name)?)
+}
+
+#[test]
+fn display_args() {
+    let mut args = ["--fast", "--display", ":1.0", "file"]
+        .map(String::from)
+        .to_vec();
+    assert_eq!(
+        take_display_arg(&mut args).unwrap().as_deref(),
+        Some(":1.0")
+    );
+    assert_eq!(args, ["--fast", "file"]);
+
+    let mut args = vec!["--display=:2".to_string()];
+    assert_eq!(take_display_arg(&mut args).unwrap().as_deref(), Some(":2"));
+    assert!(args.is_empty());
+
+    let mut args = vec!["--display".to_string()];
+    assert!(matches!(
+        take_display_arg(&mut args),
+        Err(DisplayError::MissingArgument)
+    ));
+
+    let display = resolve(Some("unix:3.1")).unwrap();
+    assert_eq!((display.display_sequence, display.screen), (3, Some(1)));
+    assert!(matches!(
+        resolve(Some("example.com:0")),
+        Err(DisplayError::Remote(_))
+    ));
+    assert!(matches!(resolve(Some("0")), Err(DisplayError::Invalid(_))));
+}
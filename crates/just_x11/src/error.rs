@@ -13,8 +13,12 @@ pub enum Error {
     UnknownErrorCode(u8),
     CouldNotOpenUnixSocket(String, io::Error),
     CouldNotConnectTo(String),
+    /// Screen requested in the display name and number of screens the server has
+    NoSuchScreen(u32, usize),
     UnexpectedReply,
     InvalidEnum(&'static str, u64),
+    /// Server closed the connection, e.g. because it shut down or killed this client
+    ConnectionClosed,
 }
 
 impl From<io::Error> for Error {
@@ -44,6 +48,13 @@ impl Display for Error {
             }
             Error::NoEnv(env_var) => write!(f, "Environment variable '{}' is not set", env_var),
             Error::IOError(inner) => write!(f, "Unexpected IO error: {}", inner),
+            Error::CouldNotOpenDisplay(response) if response.server_version().is_none() => write!(
+                f,
+                "Could not open connection to the server: server speaks unsupported protocol version {}.{}: {}",
+                response.protocol_major_version,
+                response.protocol_minor_version,
+                display_maybe_utf8(&response.reason)
+            ),
             Error::CouldNotOpenDisplay(response) => write!(
                 f,
                 "Could not open connection to the server: {}",
@@ -60,6 +71,9 @@ impl Display for Error {
             Error::CouldNotConnectTo(display) => {
                 write!(f, "Could not connect to display '{}'", display)
             }
+            Error::NoSuchScreen(screen, screens) => {
+                write!(f, "Display has no screen {} (it has {})", screen, screens)
+            }
             Error::UnexpectedReply => {
                 write!(f, "Server sent reply in different format than expected")
             }
@@ -68,6 +82,7 @@ impl Display for Error {
                 "Server sent invalid enum '{}' value: {}",
                 enum_name, invalid_value
             ),
+            Error::ConnectionClosed => write!(f, "X server closed the connection"),
         }
     }
 }
@@ -4,14 +4,85 @@ use crate::{
     utils::{bitmask, impl_enum},
     ColormapId, OrNone, ResourceId, WindowId,
 };
-use std::mem;
 
-fn invalid_bool(value: u8) -> bool {
-    value != 0 && value != 1
+/// Fields of a 32 byte event at fixed offsets, getters return `None` for invalid enums and bools
+struct EventFields([u8; 32]);
+
+impl EventFields {
+    fn u8(&self, offset: usize) -> u8 {
+        self.0[offset]
+    }
+
+    fn u16(&self, offset: usize) -> u16 {
+        u16::from_le_bytes([self.0[offset], self.0[offset + 1]])
+    }
+
+    fn i16(&self, offset: usize) -> i16 {
+        self.u16(offset) as i16
+    }
+
+    fn u32(&self, offset: usize) -> u32 {
+        u32::from_le_bytes([
+            self.0[offset],
+            self.0[offset + 1],
+            self.0[offset + 2],
+            self.0[offset + 3],
+        ])
+    }
+
+    fn id<T: From<u32>>(&self, offset: usize) -> T {
+        T::from(self.u32(offset))
+    }
+
+    fn or_none<T>(&self, offset: usize) -> OrNone<T>
+    where
+        T: From<u32> + Into<u32> + Copy,
+    {
+        OrNone::new(self.id(offset))
+    }
+
+    fn bool(&self, offset: usize) -> Option<bool> {
+        match self.0[offset] {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+
+    fn enum_u8<T: TryFrom<u8>>(&self, offset: usize) -> Option<T> {
+        T::try_from(self.0[offset]).ok()
+    }
+}
+
+/// Counterpart of [`EventFields`] for events that clients send with `SendEvent`
+struct EventWriter([u8; 32]);
+
+impl EventWriter {
+    fn new(event_code: u8) -> Self {
+        let mut raw = [0; 32];
+        raw[0] = event_code;
+        Self(raw)
+    }
+
+    fn bytes(mut self, offset: usize, bytes: &[u8]) -> Self {
+        self.0[offset..offset + bytes.len()].copy_from_slice(bytes);
+        self
+    }
+
+    fn u8(self, offset: usize, value: u8) -> Self {
+        self.bytes(offset, &[value])
+    }
+
+    fn u16(self, offset: usize, value: u16) -> Self {
+        self.bytes(offset, &value.to_le_bytes())
+    }
+
+    fn u32(self, offset: usize, value: impl Into<u32>) -> Self {
+        self.bytes(offset, &value.into().to_le_bytes())
+    }
 }
 
 #[derive(Debug, Clone)]
-#[repr(C)]
 pub struct KeyPressRelease {
     _event_code: u8,
     pub detail: KeyCode,
@@ -25,16 +96,25 @@ pub struct KeyPressRelease {
     pub event_y: i16,
     pub state: KeyModifier,
     pub same_screen: bool,
-    _pad: [u8; 1],
 }
 
 impl KeyPressRelease {
     pub(crate) fn from_le_bytes(raw: [u8; 32]) -> Option<Self> {
-        if invalid_bool(raw[0x1e]) {
-            return None;
-        }
-
-        Some(unsafe { mem::transmute(raw) })
+        let raw = EventFields(raw);
+        Some(Self {
+            _event_code: raw.u8(0x00),
+            detail: KeyCode::from(raw.u8(0x01)),
+            time: raw.u32(0x04),
+            root: raw.id(0x08),
+            event: raw.id(0x0c),
+            child: raw.or_none(0x10),
+            root_x: raw.i16(0x14),
+            root_y: raw.i16(0x16),
+            event_x: raw.i16(0x18),
+            event_y: raw.i16(0x1a),
+            state: KeyModifier::from(raw.u16(0x1c)),
+            same_screen: raw.bool(0x1e)?,
+        })
     }
 }
 
@@ -47,7 +127,6 @@ impl_enum! {
 }
 
 #[derive(Debug, Clone)]
-#[repr(C)]
 pub struct MotionNotify {
     _event_code: u8,
     pub detail: MotionNotifyDetail,
@@ -62,20 +141,26 @@ pub struct MotionNotify {
     pub event_y: i16,
     pub state: u16, // TODO: SETofKEYBUTMASK
     pub same_screen: bool,
-    _pad: [u8; 1],
 }
 
 impl MotionNotify {
     pub(crate) fn from_le_bytes(raw: [u8; 32]) -> Option<Self> {
-        if raw[0x01] > 1 {
-            return None;
-        }
-
-        if invalid_bool(raw[0x1e]) {
-            return None;
-        }
-
-        Some(unsafe { mem::transmute(raw) })
+        let raw = EventFields(raw);
+        Some(Self {
+            _event_code: raw.u8(0x00),
+            detail: raw.enum_u8(0x01)?,
+            sequence_number: raw.u16(0x02),
+            time: raw.u32(0x04),
+            root: raw.id(0x08),
+            event: raw.id(0x0c),
+            child: raw.or_none(0x10),
+            root_x: raw.i16(0x14),
+            root_y: raw.i16(0x16),
+            event_x: raw.i16(0x18),
+            event_y: raw.i16(0x1a),
+            state: raw.u16(0x1c),
+            same_screen: raw.bool(0x1e)?,
+        })
     }
 }
 
@@ -100,7 +185,6 @@ impl_enum! {
 }
 
 #[derive(Debug, Clone)]
-#[repr(C)]
 pub struct EnterLeaveNotify {
     _event_code: u8,
     pub detail: EnterLeaveNotifyDetail,
@@ -130,15 +214,23 @@ impl EnterLeaveNotify {
 
 impl EnterLeaveNotify {
     pub(crate) fn from_le_bytes(raw: [u8; 32]) -> Option<Self> {
-        if raw[0x1e] > 2 {
-            return None;
-        }
-
-        if raw[0x01] > 4 {
-            return None;
-        }
-
-        Some(unsafe { mem::transmute(raw) })
+        let raw = EventFields(raw);
+        Some(Self {
+            _event_code: raw.u8(0x00),
+            detail: raw.enum_u8(0x01)?,
+            sequence_number: raw.u16(0x02),
+            time: raw.u32(0x04),
+            root: raw.id(0x08),
+            event: raw.id(0x0c),
+            child: raw.or_none(0x10),
+            root_x: raw.i16(0x14),
+            root_y: raw.i16(0x16),
+            event_x: raw.i16(0x18),
+            event_y: raw.i16(0x1a),
+            state: raw.u16(0x1c),
+            mode: raw.enum_u8(0x1e)?,
+            _same_screen_focus: raw.u8(0x1f),
+        })
     }
 }
 
@@ -167,32 +259,28 @@ impl_enum! {
 }
 
 #[derive(Debug, Clone)]
-#[repr(C)]
 pub struct FocusInOut {
     _event_code: u8,
     pub detail: FocusInOutDetail,
     pub sequence_number: u16,
     pub event: WindowId,
     pub mode: FocusInOutMode,
-    _pad: [u8; 23],
 }
 
 impl FocusInOut {
     pub(crate) fn from_le_bytes(raw: [u8; 32]) -> Option<Self> {
-        if raw[0x01] > 7 {
-            return None;
-        }
-
-        if raw[0x08] > 3 {
-            return None;
-        }
-
-        Some(unsafe { mem::transmute(raw) })
+        let raw = EventFields(raw);
+        Some(Self {
+            _event_code: raw.u8(0x00),
+            detail: raw.enum_u8(0x01)?,
+            sequence_number: raw.u16(0x02),
+            event: raw.id(0x04),
+            mode: raw.enum_u8(0x08)?,
+        })
     }
 }
 
 #[derive(Debug, Clone)]
-#[repr(C)]
 pub struct KeymapNotify {
     _event_code: u8,
     pub keys: [u8; 31],
@@ -200,15 +288,17 @@ pub struct KeymapNotify {
 
 impl KeymapNotify {
     pub(crate) fn from_le_bytes(raw: [u8; 32]) -> Option<Self> {
-        Some(unsafe { mem::transmute(raw) })
+        let (event_code, keys) = raw.split_first()?;
+        Some(Self {
+            _event_code: *event_code,
+            keys: keys.try_into().ok()?,
+        })
     }
 }
 
 #[derive(Debug, Clone)]
-#[repr(C)]
 pub struct Expose {
     _event_code: u8,
-    _unused: u8,
     pub sequence_number: u16,
     pub window: WindowId,
     pub x: u16,
@@ -216,20 +306,27 @@ pub struct Expose {
     pub width: u16,
     pub height: u16,
     pub count: u16,
-    _pad: [u8; 14],
 }
 
 impl Expose {
     pub(crate) fn from_le_bytes(raw: [u8; 32]) -> Option<Self> {
-        Some(unsafe { mem::transmute(raw) })
+        let raw = EventFields(raw);
+        Some(Self {
+            _event_code: raw.u8(0x00),
+            sequence_number: raw.u16(0x02),
+            window: raw.id(0x04),
+            x: raw.u16(0x08),
+            y: raw.u16(0x0a),
+            width: raw.u16(0x0c),
+            height: raw.u16(0x0e),
+            count: raw.u16(0x10),
+        })
     }
 }
 
 #[derive(Debug, Clone)]
-#[repr(C)]
 pub struct GraphicsExposure {
     _event_code: u8,
-    _unused: u8,
     pub sequence_number: u16,
     pub drawable: ResourceId,
     pub x: u16,
@@ -239,30 +336,45 @@ pub struct GraphicsExposure {
     pub minor_opcode: u16,
     pub count: u16,
     pub major_opcode: u8,
-    _pad: [u8; 11],
 }
 
 impl GraphicsExposure {
     pub(crate) fn from_le_bytes(raw: [u8; 32]) -> Option<Self> {
-        Some(unsafe { mem::transmute(raw) })
+        let raw = EventFields(raw);
+        Some(Self {
+            _event_code: raw.u8(0x00),
+            sequence_number: raw.u16(0x02),
+            drawable: raw.id(0x04),
+            x: raw.u16(0x08),
+            y: raw.u16(0x0a),
+            width: raw.u16(0x0c),
+            height: raw.u16(0x0e),
+            minor_opcode: raw.u16(0x10),
+            count: raw.u16(0x12),
+            major_opcode: raw.u8(0x14),
+        })
     }
 }
 
 #[derive(Debug, Clone)]
-#[repr(C)]
 pub struct NoExposure {
     _event_code: u8,
-    _unused: u8,
     pub sequence_number: u16,
     pub drawable: ResourceId,
     pub minor_opcode: u16,
     pub major_opcode: u8,
-    _pad: [u8; 21],
 }
 
 impl NoExposure {
     pub(crate) fn from_le_bytes(raw: [u8; 32]) -> Option<Self> {
-        Some(unsafe { mem::transmute(raw) })
+        let raw = EventFields(raw);
+        Some(Self {
+            _event_code: raw.u8(0x00),
+            sequence_number: raw.u16(0x02),
+            drawable: raw.id(0x04),
+            minor_opcode: raw.u16(0x08),
+            major_opcode: raw.u8(0x0a),
+        })
     }
 }
 
@@ -276,31 +388,28 @@ impl_enum! {
 }
 
 #[derive(Debug, Clone)]
-#[repr(C)]
 pub struct VisibilityNotify {
     _event_code: u8,
-    _unused: u8,
     pub sequence_number: u16,
     pub window: WindowId,
     pub state: VisibilityNotifyState,
-    _pad: [u8; 23],
 }
 
 impl VisibilityNotify {
     pub(crate) fn from_le_bytes(raw: [u8; 32]) -> Option<Self> {
-        if raw[0x08] > 2 {
-            return None;
-        }
-
-        Some(unsafe { mem::transmute(raw) })
+        let raw = EventFields(raw);
+        Some(Self {
+            _event_code: raw.u8(0x00),
+            sequence_number: raw.u16(0x02),
+            window: raw.id(0x04),
+            state: raw.enum_u8(0x08)?,
+        })
     }
 }
 
 #[derive(Debug, Clone)]
-#[repr(C)]
 pub struct CreateNotify {
     _event_code: u8,
-    _unused: u8,
     pub sequence_number: u16,
     pub parent: WindowId,
     pub window: WindowId,
@@ -310,101 +419,111 @@ pub struct CreateNotify {
     pub height: u16,
     pub border_width: u16,
     pub override_redirect: bool,
-    _pad: [u8; 9],
 }
 
 impl CreateNotify {
     pub(crate) fn from_le_bytes(raw: [u8; 32]) -> Option<Self> {
-        if invalid_bool(raw[0x16]) {
-            return None;
-        }
-
-        Some(unsafe { mem::transmute(raw) })
+        let raw = EventFields(raw);
+        Some(Self {
+            _event_code: raw.u8(0x00),
+            sequence_number: raw.u16(0x02),
+            parent: raw.id(0x04),
+            window: raw.id(0x08),
+            x: raw.i16(0x0c),
+            y: raw.i16(0x0e),
+            width: raw.u16(0x10),
+            height: raw.u16(0x12),
+            border_width: raw.u16(0x14),
+            override_redirect: raw.bool(0x16)?,
+        })
     }
 }
 
 #[derive(Debug, Clone)]
-#[repr(C)]
 pub struct DestroyNotify {
     _event_code: u8,
-    _unused: u8,
     pub sequence_number: u16,
     pub event: WindowId,
     pub window: WindowId,
-    _pad: [u8; 20],
 }
 
 impl DestroyNotify {
     pub(crate) fn from_le_bytes(raw: [u8; 32]) -> Option<Self> {
-        Some(unsafe { mem::transmute(raw) })
+        let raw = EventFields(raw);
+        Some(Self {
+            _event_code: raw.u8(0x00),
+            sequence_number: raw.u16(0x02),
+            event: raw.id(0x04),
+            window: raw.id(0x08),
+        })
     }
 }
 
 #[derive(Debug, Clone)]
-#[repr(C)]
 pub struct UnmapNotify {
     _event_code: u8,
-    _unused: u8,
     pub sequence_number: u16,
     pub event: WindowId,
     pub window: WindowId,
     pub from_configure: bool,
-    _pad: [u8; 19],
 }
 
 impl UnmapNotify {
     pub(crate) fn from_le_bytes(raw: [u8; 32]) -> Option<Self> {
-        if invalid_bool(raw[0x0c]) {
-            return None;
-        }
-
-        Some(unsafe { mem::transmute(raw) })
+        let raw = EventFields(raw);
+        Some(Self {
+            _event_code: raw.u8(0x00),
+            sequence_number: raw.u16(0x02),
+            event: raw.id(0x04),
+            window: raw.id(0x08),
+            from_configure: raw.bool(0x0c)?,
+        })
     }
 }
 
 #[derive(Debug, Clone)]
-#[repr(C)]
 pub struct MapNotify {
     _event_code: u8,
-    _unused: u8,
     pub event: WindowId,
     pub window: WindowId,
     pub override_redirect: bool,
-    _pad: [u8; 19],
 }
 
 impl MapNotify {
     pub(crate) fn from_le_bytes(raw: [u8; 32]) -> Option<Self> {
-        if invalid_bool(raw[0x0c]) {
-            return None;
-        }
-
-        Some(unsafe { mem::transmute(raw) })
+        let raw = EventFields(raw);
+        Some(Self {
+            _event_code: raw.u8(0x00),
+            event: raw.id(0x04),
+            window: raw.id(0x08),
+            override_redirect: raw.bool(0x0c)?,
+        })
     }
 }
 
 #[derive(Debug, Clone)]
-#[repr(C)]
 pub struct MapRequest {
     _event_code: u8,
-    _unused: u8,
     pub sequence_number: u16,
     pub parent: WindowId,
     pub window: WindowId,
-    _pad: [u8; 20],
 }
 
 impl MapRequest {
     pub(crate) fn from_le_bytes(raw: [u8; 32]) -> Option<Self> {
-        Some(unsafe { mem::transmute(raw) })
+        let raw = EventFields(raw);
+        Some(Self {
+            _event_code: raw.u8(0x00),
+            sequence_number: raw.u16(0x02),
+            parent: raw.id(0x04),
+            window: raw.id(0x08),
+        })
     }
 }
 
 #[derive(Debug, Clone)]
-#[repr(C)]
 pub struct ReparentNotify {
     _event_code: u8,
-    _unused: u8,
     pub sequence_number: u16,
     pub event: WindowId,
     pub window: WindowId,
@@ -412,24 +531,27 @@ pub struct ReparentNotify {
     pub x: i16,
     pub y: i16,
     pub override_redirect: bool,
-    _pad: [u8; 11],
 }
 
 impl ReparentNotify {
     pub(crate) fn from_le_bytes(raw: [u8; 32]) -> Option<Self> {
-        if invalid_bool(raw[0x14]) {
-            return None;
-        }
-
-        Some(unsafe { mem::transmute(raw) })
+        let raw = EventFields(raw);
+        Some(Self {
+            _event_code: raw.u8(0x00),
+            sequence_number: raw.u16(0x02),
+            event: raw.id(0x04),
+            window: raw.id(0x08),
+            parent: raw.id(0x0c),
+            x: raw.i16(0x10),
+            y: raw.i16(0x12),
+            override_redirect: raw.bool(0x14)?,
+        })
     }
 }
 
 #[derive(Debug, Clone)]
-#[repr(C)]
 pub struct ConfigureNotify {
     _event_code: u8,
-    _unused: u8,
     pub sequence_number: u16,
     pub event: WindowId,
     pub window: WindowId,
@@ -440,22 +562,41 @@ pub struct ConfigureNotify {
     pub height: u16,
     pub border_width: u16,
     pub override_redirect: bool,
-    _pad: [u8; 5],
 }
 
 impl ConfigureNotify {
     pub fn to_le_bytes(self) -> [u8; 32] {
-        unsafe { mem::transmute(self) }
+        EventWriter::new(self._event_code)
+            .u16(0x02, self.sequence_number)
+            .u32(0x04, self.event)
+            .u32(0x08, self.window)
+            .u32(0x0c, self.above_sibling)
+            .u16(0x10, self.x as u16)
+            .u16(0x12, self.y as u16)
+            .u16(0x14, self.width)
+            .u16(0x16, self.height)
+            .u16(0x18, self.border_width)
+            .u8(0x1a, self.override_redirect as u8)
+            .0
     }
 }
 
 impl ConfigureNotify {
     pub(crate) fn from_le_bytes(raw: [u8; 32]) -> Option<Self> {
-        if invalid_bool(raw[0x1a]) {
-            return None;
-        }
-
-        Some(unsafe { mem::transmute(raw) })
+        let raw = EventFields(raw);
+        Some(Self {
+            _event_code: raw.u8(0x00),
+            sequence_number: raw.u16(0x02),
+            event: raw.id(0x04),
+            window: raw.id(0x08),
+            above_sibling: raw.or_none(0x0c),
+            x: raw.i16(0x10),
+            y: raw.i16(0x12),
+            width: raw.u16(0x14),
+            height: raw.u16(0x16),
+            border_width: raw.u16(0x18),
+            override_redirect: raw.bool(0x1a)?,
+        })
     }
 }
 
@@ -477,7 +618,6 @@ impl From<StackMode> for u32 {
 }
 
 #[derive(Debug, Clone)]
-#[repr(C)]
 pub struct ConfigureRequest {
     _event_code: u8,
     pub stack_mode: StackMode,
@@ -491,53 +631,71 @@ pub struct ConfigureRequest {
     pub height: u16,
     pub border_width: u16,
     pub value_mask: u16,
-    _pad: [u8; 4],
 }
 
 impl ConfigureRequest {
     pub(crate) fn from_le_bytes(raw: [u8; 32]) -> Option<Self> {
-        if raw[0x01] > 4 {
-            return None;
-        }
-
-        Some(unsafe { mem::transmute(raw) })
+        let raw = EventFields(raw);
+        Some(Self {
+            _event_code: raw.u8(0x00),
+            stack_mode: raw.enum_u8(0x01)?,
+            sequence_number: raw.u16(0x02),
+            parent: raw.id(0x04),
+            window: raw.id(0x08),
+            sibling: raw.or_none(0x0c),
+            x: raw.i16(0x10),
+            y: raw.i16(0x12),
+            width: raw.u16(0x14),
+            height: raw.u16(0x16),
+            border_width: raw.u16(0x18),
+            value_mask: raw.u16(0x1a),
+        })
     }
 }
 
 #[derive(Debug, Clone)]
-#[repr(C)]
 pub struct GravityNotify {
     _event_code: u8,
-    _unused: u8,
     pub sequence_number: u16,
     pub event: WindowId,
     pub window: WindowId,
     pub x: i16,
     pub y: i16,
-    _pad: [u8; 16],
 }
 
 impl GravityNotify {
     pub(crate) fn from_le_bytes(raw: [u8; 32]) -> Option<Self> {
-        Some(unsafe { mem::transmute(raw) })
+        let raw = EventFields(raw);
+        Some(Self {
+            _event_code: raw.u8(0x00),
+            sequence_number: raw.u16(0x02),
+            event: raw.id(0x04),
+            window: raw.id(0x08),
+            x: raw.i16(0x0c),
+            y: raw.i16(0x0e),
+        })
     }
 }
 
 #[derive(Debug, Clone)]
-#[repr(C)]
 pub struct ResizeRequest {
     _event_code: u8,
-    _unused: u8,
     pub sequence_number: u16,
     pub window: WindowId,
     pub width: u16,
     pub height: u16,
-    _pad: [u8; 20],
 }
 
 impl ResizeRequest {
     pub(crate) fn from_le_bytes(raw: [u8; 32]) -> Option<Self> {
-        Some(unsafe { mem::transmute(raw) })
+        let raw = EventFields(raw);
+        Some(Self {
+            _event_code: raw.u8(0x00),
+            sequence_number: raw.u16(0x02),
+            window: raw.id(0x04),
+            width: raw.u16(0x08),
+            height: raw.u16(0x0a),
+        })
     }
 }
 
@@ -550,48 +708,46 @@ impl_enum! {
 }
 
 #[derive(Debug, Clone)]
-#[repr(C)]
 pub struct CirculateNotify {
     _event_code: u8,
-    _unused1: u8,
     pub sequence_number: u16,
     pub event: WindowId,
     pub window: WindowId,
-    _unused2: WindowId, // correct
     pub place: CirculateNotifyPlace,
-    _pad: [u8; 15],
 }
 
 impl CirculateNotify {
     pub(crate) fn from_le_bytes(raw: [u8; 32]) -> Option<Self> {
-        if raw[0x0d] > 1 {
-            return None;
-        }
-
-        Some(unsafe { mem::transmute(raw) })
+        let raw = EventFields(raw);
+        Some(Self {
+            _event_code: raw.u8(0x00),
+            sequence_number: raw.u16(0x02),
+            event: raw.id(0x04),
+            window: raw.id(0x08),
+            place: raw.enum_u8(0x10)?,
+        })
     }
 }
 
 #[derive(Debug, Clone)]
-#[repr(C)]
 pub struct CirculateRequest {
     _event_code: u8,
-    _unused1: u8,
     pub sequence_number: u16,
     pub event: WindowId,
     pub window: WindowId,
-    _unused2: u32,
     pub place: CirculateNotifyPlace,
-    _pad: [u8; 15],
 }
 
 impl CirculateRequest {
     pub(crate) fn from_le_bytes(raw: [u8; 32]) -> Option<Self> {
-        if raw[0x10] > 1 {
-            return None;
-        }
-
-        Some(unsafe { mem::transmute(raw) })
+        let raw = EventFields(raw);
+        Some(Self {
+            _event_code: raw.u8(0x00),
+            sequence_number: raw.u16(0x02),
+            event: raw.id(0x04),
+            window: raw.id(0x08),
+            place: raw.enum_u8(0x10)?,
+        })
     }
 }
 
@@ -604,51 +760,54 @@ impl_enum! {
 }
 
 #[derive(Debug, Clone)]
-#[repr(C)]
 pub struct PropertyNotify {
     _event_code: u8,
-    _unused: u8,
     pub sequence_number: u16,
     pub window: WindowId,
     pub atom: AtomId,
     pub time: u32,
     pub state: PropertyNotifyState,
-    _pad: [u8; 15],
 }
 
 impl PropertyNotify {
     pub(crate) fn from_le_bytes(raw: [u8; 32]) -> Option<Self> {
-        if raw[0x10] > 1 {
-            return None;
-        }
-
-        Some(unsafe { mem::transmute(raw) })
+        let raw = EventFields(raw);
+        Some(Self {
+            _event_code: raw.u8(0x00),
+            sequence_number: raw.u16(0x02),
+            window: raw.id(0x04),
+            atom: raw.id(0x08),
+            time: raw.u32(0x0c),
+            state: raw.enum_u8(0x10)?,
+        })
     }
 }
 
 #[derive(Debug, Clone)]
-#[repr(C)]
 pub struct SelectionClear {
     _event_code: u8,
-    _unused: u8,
     pub sequence_number: u16,
     pub time: u32,
     pub owner: WindowId,
     pub selection: AtomId,
-    _pad: [u8; 16],
 }
 
 impl SelectionClear {
     pub(crate) fn from_le_bytes(raw: [u8; 32]) -> Option<Self> {
-        Some(unsafe { mem::transmute(raw) })
+        let raw = EventFields(raw);
+        Some(Self {
+            _event_code: raw.u8(0x00),
+            sequence_number: raw.u16(0x02),
+            time: raw.u32(0x04),
+            owner: raw.id(0x08),
+            selection: raw.id(0x0c),
+        })
     }
 }
 
 #[derive(Debug, Clone)]
-#[repr(C)]
 pub struct SelectionRequest {
     _event_code: u8,
-    _unused: u8,
     pub sequence_number: u16,
     pub time: u32, // 0 for current
     pub owner: WindowId,
@@ -656,32 +815,79 @@ pub struct SelectionRequest {
     pub selection: AtomId,
     pub target: AtomId,
     pub property: OrNone<AtomId>,
-    _pad: [u8; 4],
 }
 
 impl SelectionRequest {
     pub(crate) fn from_le_bytes(raw: [u8; 32]) -> Option<Self> {
-        Some(unsafe { mem::transmute(raw) })
+        let raw = EventFields(raw);
+        Some(Self {
+            _event_code: raw.u8(0x00),
+            sequence_number: raw.u16(0x02),
+            time: raw.u32(0x04),
+            owner: raw.id(0x08),
+            requestor: raw.id(0x0c),
+            selection: raw.id(0x10),
+            target: raw.id(0x14),
+            property: raw.or_none(0x18),
+        })
     }
 }
 
 #[derive(Debug, Clone)]
-#[repr(C)]
 pub struct SelectionNotify {
     _event_code: u8,
-    _unused: u8,
     pub sequence_number: u16,
     pub time: u32, // 0 for current
     pub requestor: WindowId,
     pub selection: AtomId,
     pub target: AtomId,
     pub property: OrNone<AtomId>,
-    _pad: [u8; 8],
 }
 
 impl SelectionNotify {
+    /// Reply of selection owner to [`SelectionRequest`], with `property` none if the selection
+    /// could not be converted
+    pub fn new(
+        time: u32,
+        requestor: WindowId,
+        selection: AtomId,
+        target: AtomId,
+        property: OrNone<AtomId>,
+    ) -> Self {
+        Self {
+            _event_code: 31,
+            sequence_number: 0,
+            time,
+            requestor,
+            selection,
+            target,
+            property,
+        }
+    }
+
+    /// Event as sent with `SendEvent`
+    pub fn to_le_bytes(&self) -> [u8; 32] {
+        EventWriter::new(self._event_code)
+            .u16(0x02, self.sequence_number)
+            .u32(0x04, self.time)
+            .u32(0x08, self.requestor)
+            .u32(0x0c, self.selection)
+            .u32(0x10, self.target)
+            .u32(0x14, self.property)
+            .0
+    }
+
     pub(crate) fn from_le_bytes(raw: [u8; 32]) -> Option<Self> {
-        Some(unsafe { mem::transmute(raw) })
+        let raw = EventFields(raw);
+        Some(Self {
+            _event_code: raw.u8(0x00),
+            sequence_number: raw.u16(0x02),
+            time: raw.u32(0x04),
+            requestor: raw.id(0x08),
+            selection: raw.id(0x0c),
+            target: raw.id(0x10),
+            property: raw.or_none(0x14),
+        })
     }
 }
 
@@ -694,25 +900,26 @@ impl_enum! {
 }
 
 #[derive(Debug, Clone)]
-#[repr(C)]
 pub struct ColormapNotify {
     _event_code: u8,
-    _unused: u8,
     pub sequence_number: u16,
     pub window: WindowId,
     pub colormap: OrNone<ColormapId>,
     pub new: bool,
     pub state: ColormapNotifyState,
-    _pad: [u8; 18],
 }
 
 impl ColormapNotify {
     pub(crate) fn from_le_bytes(raw: [u8; 32]) -> Option<Self> {
-        if invalid_bool(raw[0x0c]) {
-            return None;
-        }
-
-        Some(unsafe { mem::transmute(raw) })
+        let raw = EventFields(raw);
+        Some(Self {
+            _event_code: raw.u8(0x00),
+            sequence_number: raw.u16(0x02),
+            window: raw.id(0x04),
+            colormap: raw.or_none(0x08),
+            new: raw.bool(0x0c)?,
+            state: raw.enum_u8(0x0d)?,
+        })
     }
 }
 
@@ -726,7 +933,6 @@ impl_enum! {
 }
 
 #[derive(Debug, Clone)]
-#[repr(C)]
 pub struct ClientMessage {
     pub event_code: u8,
     pub format: MessageFormat,
@@ -738,23 +944,47 @@ pub struct ClientMessage {
 
 impl ClientMessage {
     pub(crate) fn from_le_bytes(raw: [u8; 32]) -> Option<Self> {
-        if raw[1] != 8 && raw[1] != 16 && raw[1] != 32 {
-            return None;
-        }
+        let data = raw[0x0c..].try_into().ok()?;
+        let raw = EventFields(raw);
+        Some(Self {
+            event_code: raw.u8(0x00),
+            format: raw.enum_u8(0x01)?,
+            sequence_number: raw.u16(0x02),
+            window: raw.id(0x04),
+            type_message: raw.id(0x08),
+            data,
+        })
+    }
 
-        Some(unsafe { mem::transmute(raw) })
+    /// Event as sent with `SendEvent`
+    pub fn to_le_bytes(&self) -> [u8; 32] {
+        EventWriter::new(self.event_code)
+            .u8(0x01, self.format as u8)
+            .u16(0x02, self.sequence_number)
+            .u32(0x04, self.window)
+            .u32(0x08, self.type_message)
+            .bytes(0x0c, &self.data)
+            .0
     }
 
     pub fn data8(&self) -> &[u8; 20] {
         &self.data
     }
 
-    pub fn data16(&self) -> &[u16; 10] {
-        unsafe { mem::transmute(&self.data) }
+    pub fn data16(&self) -> [u16; 10] {
+        let mut res = [0; 10];
+        for (value, bytes) in res.iter_mut().zip(self.data.chunks_exact(2)) {
+            *value = u16::from_le_bytes([bytes[0], bytes[1]]);
+        }
+        res
     }
 
-    pub fn data32(&self) -> &[u32; 5] {
-        unsafe { mem::transmute(&self.data) }
+    pub fn data32(&self) -> [u32; 5] {
+        let mut res = [0; 5];
+        for (value, bytes) in res.iter_mut().zip(self.data.chunks_exact(4)) {
+            *value = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+        res
     }
 }
 
@@ -768,29 +998,28 @@ impl_enum! {
 }
 
 #[derive(Debug, Clone)]
-#[repr(C)]
 pub struct MappingNotify {
     _event_code: u8,
-    _unused: u8,
     pub sequence_number: u16,
     pub request: MappingNotifyRequest,
     pub first_keycode: u8,
     pub count: u8,
-    _pad: [u8; 25],
 }
 
 impl MappingNotify {
     pub(crate) fn from_le_bytes(raw: [u8; 32]) -> Option<Self> {
-        if raw[0x04] > 2 {
-            return None;
-        }
-
-        Some(unsafe { mem::transmute(raw) })
+        let raw = EventFields(raw);
+        Some(Self {
+            _event_code: raw.u8(0x00),
+            sequence_number: raw.u16(0x02),
+            request: raw.enum_u8(0x04)?,
+            first_keycode: raw.u8(0x05),
+            count: raw.u8(0x06),
+        })
     }
 }
 
 #[derive(Debug, Clone)]
-#[repr(C)]
 pub struct UnknownEvent {
     _event_code: u8,
     pub sequence_number: u16,
@@ -807,6 +1036,47 @@ impl UnknownEvent {
     }
 }
 
+/*
+┌───
+    GenericEvent
+        1       35                              code
+        1       CARD8                           extension
+        2       CARD16                          sequence number
+        4       CARD32                          length
+        2       CARD16                          evtype
+        22                                      unused or event specific
+        4n                                      event specific
+└───
+*/
+
+/// Extension event that can be longer than 32 bytes, e.g. from XInput 2
+#[derive(Debug, Clone)]
+pub struct GenericEvent {
+    /// Major opcode of the extension
+    pub extension: u8,
+    pub sequence_number: u16,
+    pub evtype: u16,
+    /// Whole event including the first 32 bytes
+    pub raw: Vec<u8>,
+}
+
+impl GenericEvent {
+    pub(crate) const CODE: u8 = 35;
+
+    pub(crate) fn from_le_bytes(raw: Vec<u8>) -> Option<Self> {
+        if raw.len() < 32 {
+            return None;
+        }
+
+        Some(Self {
+            extension: raw[1],
+            sequence_number: u16::from_le_bytes([raw[2], raw[3]]),
+            evtype: u16::from_le_bytes([raw[8], raw[9]]),
+            raw,
+        })
+    }
+}
+
 #[derive(Debug, Clone)]
 #[repr(u8)]
 pub enum SomeEvent {
@@ -843,10 +1113,52 @@ pub enum SomeEvent {
     ColormapNotify(ColormapNotify),
     ClientMessage(ClientMessage),
     MappingNotify(MappingNotify),
+    GenericEvent(GenericEvent),
     UnknownEvent(UnknownEvent),
 }
 
 impl SomeEvent {
+    /// Variant name, e.g. for logging or per event type counters
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::KeyPress(_) => "KeyPress",
+            Self::KeyRelease(_) => "KeyRelease",
+            Self::ButtonPress(_) => "ButtonPress",
+            Self::ButtonRelease(_) => "ButtonRelease",
+            Self::MotionNotify(_) => "MotionNotify",
+            Self::EnterNotify(_) => "EnterNotify",
+            Self::LeaveNotify(_) => "LeaveNotify",
+            Self::FocusIn(_) => "FocusIn",
+            Self::FocusOut(_) => "FocusOut",
+            Self::KeymapNotify(_) => "KeymapNotify",
+            Self::Expose(_) => "Expose",
+            Self::GraphicsExposure(_) => "GraphicsExposure",
+            Self::NoExposure(_) => "NoExposure",
+            Self::VisibilityNotify(_) => "VisibilityNotify",
+            Self::CreateNotify(_) => "CreateNotify",
+            Self::DestroyNotify(_) => "DestroyNotify",
+            Self::UnmapNotify(_) => "UnmapNotify",
+            Self::MapNotify(_) => "MapNotify",
+            Self::MapRequest(_) => "MapRequest",
+            Self::ReparentNotify(_) => "ReparentNotify",
+            Self::ConfigureNotify(_) => "ConfigureNotify",
+            Self::ConfigureRequest(_) => "ConfigureRequest",
+            Self::GravityNotify(_) => "GravityNotify",
+            Self::ResizeRequest(_) => "ResizeRequest",
+            Self::CirculateNotify(_) => "CirculateNotify",
+            Self::CirculateRequest(_) => "CirculateRequest",
+            Self::PropertyNotify(_) => "PropertyNotify",
+            Self::SelectionClear(_) => "SelectionClear",
+            Self::SelectionRequest(_) => "SelectionRequest",
+            Self::SelectionNotify(_) => "SelectionNotify",
+            Self::ColormapNotify(_) => "ColormapNotify",
+            Self::ClientMessage(_) => "ClientMessage",
+            Self::MappingNotify(_) => "MappingNotify",
+            Self::GenericEvent(_) => "GenericEvent",
+            Self::UnknownEvent(_) => "UnknownEvent",
+        }
+    }
+
     pub(crate) fn from_le_bytes(raw: [u8; 32]) -> Option<Self> {
         // TODO: Detect high upper bit set for extension events
         let event_code = raw[0];
@@ -913,6 +1225,49 @@ impl SomeEvent {
     }
 }
 
+/// Name of a core event with `event_code`, `SendEvent` flag is ignored
+pub fn event_name(event_code: u8) -> Option<&'static str> {
+    const NAMES: [&str; 34] = [
+        "KeyPress",
+        "KeyRelease",
+        "ButtonPress",
+        "ButtonRelease",
+        "MotionNotify",
+        "EnterNotify",
+        "LeaveNotify",
+        "FocusIn",
+        "FocusOut",
+        "KeymapNotify",
+        "Expose",
+        "GraphicsExposure",
+        "NoExposure",
+        "VisibilityNotify",
+        "CreateNotify",
+        "DestroyNotify",
+        "UnmapNotify",
+        "MapNotify",
+        "MapRequest",
+        "ReparentNotify",
+        "ConfigureNotify",
+        "ConfigureRequest",
+        "GravityNotify",
+        "ResizeRequest",
+        "CirculateNotify",
+        "CirculateRequest",
+        "PropertyNotify",
+        "SelectionClear",
+        "SelectionRequest",
+        "SelectionNotify",
+        "ColormapNotify",
+        "ClientMessage",
+        "MappingNotify",
+        "GenericEvent",
+    ];
+    NAMES
+        .get(((event_code & 0x7f) as usize).checked_sub(2)?)
+        .copied()
+}
+
 bitmask! {
     #[repr(u32)]
     bitmask EventType {
@@ -954,3 +1309,104 @@ impl_enum! {
         ScrollDown = 5,
     }
 }
+
+#[test]
+fn event_enums_roundtrip() {
+    fn check<T: TryFrom<u8>>(variants: usize, to_raw: impl Fn(T) -> u8) {
+        let mut valid = 0;
+        for raw in 0..=u8::MAX {
+            if let Ok(value) = T::try_from(raw) {
+                assert_eq!(to_raw(value), raw);
+                valid += 1;
+            }
+        }
+        assert_eq!(valid, variants);
+    }
+
+    check(2, |v: MotionNotifyDetail| v as u8);
+    check(3, |v: EnterLeaveNotifyMode| v as u8);
+    check(5, |v: EnterLeaveNotifyDetail| v as u8);
+    check(8, |v: FocusInOutDetail| v as u8);
+    check(4, |v: FocusInOutMode| v as u8);
+    check(3, |v: VisibilityNotifyState| v as u8);
+    check(5, |v: StackMode| v as u8);
+    check(2, |v: CirculateNotifyPlace| v as u8);
+    check(2, |v: PropertyNotifyState| v as u8);
+    check(2, |v: ColormapNotifyState| v as u8);
+    check(3, |v: MessageFormat| v as u8);
+    check(3, |v: MappingNotifyRequest| v as u8);
+}
+
+#[test]
+fn events_roundtrip() {
+    let mut raw = [0u8; 32];
+    raw[0] = 22;
+    raw[2..4].copy_from_slice(&7u16.to_le_bytes());
+    raw[4..8].copy_from_slice(&0x400001u32.to_le_bytes());
+    raw[8..12].copy_from_slice(&0x400002u32.to_le_bytes());
+    raw[16..18].copy_from_slice(&(-5i16).to_le_bytes());
+    raw[18..20].copy_from_slice(&10i16.to_le_bytes());
+    raw[20..22].copy_from_slice(&640u16.to_le_bytes());
+    raw[22..24].copy_from_slice(&480u16.to_le_bytes());
+    raw[24..26].copy_from_slice(&2u16.to_le_bytes());
+    raw[26] = 1;
+    let Some(SomeEvent::ConfigureNotify(event)) = SomeEvent::from_le_bytes(raw) else {
+        panic!("ConfigureNotify expected");
+    };
+    assert_eq!(event.sequence_number, 7);
+    assert_eq!(event.window, WindowId::from(0x400002));
+    assert!(event.above_sibling.value().is_none());
+    assert_eq!(
+        (event.x, event.y, event.width, event.height),
+        (-5, 10, 640, 480)
+    );
+    assert!(event.override_redirect);
+    assert_eq!(event.to_le_bytes(), raw);
+
+    // Bool that is neither 0 nor 1
+    raw[26] = 2;
+    assert!(SomeEvent::from_le_bytes(raw).is_none());
+
+    let mut raw = [0u8; 32];
+    raw[0] = 33;
+    raw[1] = 32;
+    raw[4..8].copy_from_slice(&0x400001u32.to_le_bytes());
+    raw[8..12].copy_from_slice(&AtomId::WM_HINTS.to_le_bytes());
+    for (idx, byte) in raw[12..].iter_mut().enumerate() {
+        *byte = idx as u8;
+    }
+    let Some(SomeEvent::ClientMessage(event)) = SomeEvent::from_le_bytes(raw) else {
+        panic!("ClientMessage expected");
+    };
+    assert_eq!(event.type_message, AtomId::WM_HINTS);
+    assert_eq!(event.data16()[1], 0x0302);
+    assert_eq!(event.data32()[4], 0x13121110);
+    assert_eq!(event.to_le_bytes(), raw);
+
+    // Invalid format
+    raw[1] = 7;
+    assert!(SomeEvent::from_le_bytes(raw).is_none());
+
+    let notify = SelectionNotify::new(
+        5,
+        WindowId::from(0x400001),
+        AtomId::PRIMARY,
+        AtomId::STRING,
+        OrNone::new(AtomId::WM_NAME),
+    );
+    let Some(SomeEvent::SelectionNotify(event)) = SomeEvent::from_le_bytes(notify.to_le_bytes())
+    else {
+        panic!("SelectionNotify expected");
+    };
+    assert_eq!((event.time, event.target), (5, AtomId::STRING));
+    assert_eq!(event.property.value(), Some(AtomId::WM_NAME));
+
+    // First byte may carry the SendEvent bit, no event code or byte pattern may panic
+    for event_code in 0..=u8::MAX {
+        for fill in [0x00, 0x01, 0x7f, 0xff] {
+            let mut raw = [fill; 32];
+            raw[0] = event_code;
+            let _ = SomeEvent::from_le_bytes(raw);
+        }
+    }
+}
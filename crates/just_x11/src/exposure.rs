@@ -0,0 +1,101 @@
+//! `GraphicsExposure` and `NoExposure` events generated by `CopyArea`, resolved into areas that
+//! have to be drawn again
+
+use crate::{
+    error::Error,
+    events::SomeEvent,
+    requests::{self, opcodes},
+    Rectangle, XDisplay,
+};
+use just_geometry::Rect;
+
+impl XDisplay {
+    /// Send `CopyArea` and wait for exposures it generates. Returns areas of the destination that
+    /// could not be copied because the source was obscured or outside of its drawable, with
+    /// overlapping ones merged. Exposures are generated only if `gc` has `graphics-exposures`
+    /// enabled, which is the default.
+    ///
+    /// Other events received meanwhile stay in the queue.
+    pub fn copy_area(&mut self, request: &requests::CopyArea) -> Result<Vec<Rectangle>, Error> {
+        self.send_request(request)?;
+        // Exposures are sent before the reply of any later request
+        let pending = self.send_request(&requests::GetInputFocus)?;
+        self.flush()?;
+        let _ = self.await_pending_reply(pending)?;
+
+        let drawable = request.dst_drawable.value();
+        let mut exposed = Vec::new();
+        self.event_queue.retain(|event| match event {
+            SomeEvent::GraphicsExposure(event)
+                if event.drawable.value() == drawable
+                    && event.major_opcode == opcodes::COPY_AREA =>
+            {
+                exposed.push(Rectangle {
+                    x: event.x as i16,
+                    y: event.y as i16,
+                    width: event.width,
+                    height: event.height,
+                });
+                false
+            }
+            SomeEvent::NoExposure(event)
+                if event.drawable.value() == drawable
+                    && event.major_opcode == opcodes::COPY_AREA =>
+            {
+                false
+            }
+            _ => true,
+        });
+
+        Ok(compress(exposed))
+    }
+}
+
+/// Merge overlapping rectangles into their bounding boxes until none of them overlap. Servers
+/// split exposed regions into many small rectangles, e.g. one per obscuring window edge.
+pub fn compress(mut rectangles: Vec<Rectangle>) -> Vec<Rectangle> {
+    rectangles.retain(|r| r.width != 0 && r.height != 0);
+
+    let mut merged = Vec::<Rectangle>::with_capacity(rectangles.len());
+    while let Some(mut rectangle) = rectangles.pop() {
+        // Grown rectangle can overlap ones that were already merged
+        while let Some(idx) = merged
+            .iter()
+            .position(|m| Rect::from(*m).intersection(rectangle.into()).is_some())
+        {
+            rectangle = Rect::from(merged.swap_remove(idx))
+                .union(rectangle.into())
+                .into();
+        }
+        merged.push(rectangle);
+    }
+    merged
+}
+
+#[test]
+fn compress_exposures() {
+    let rect = |x, y, width, height| Rectangle {
+        x,
+        y,
+        width,
+        height,
+    };
+
+    let mut compressed = compress(vec![
+        rect(0, 0, 10, 10),
+        rect(5, 5, 10, 10),
+        rect(30, 30, 5, 5),
+        // Touching edges don't overlap
+        rect(35, 30, 5, 5),
+        rect(50, 50, 0, 10),
+    ]);
+    compressed.sort_by_key(|r| (r.x, r.y));
+    assert_eq!(
+        compressed,
+        vec![rect(0, 0, 15, 15), rect(30, 30, 5, 5), rect(35, 30, 5, 5)]
+    );
+
+    // Middle one overlaps only the union of the other two
+    let compressed = compress(vec![rect(2, 2, 5, 2), rect(6, 5, 4, 4), rect(0, 0, 4, 8)]);
+    assert_eq!(compressed, vec![rect(0, 0, 10, 9)]);
+}
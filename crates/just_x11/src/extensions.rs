@@ -1,3 +1,101 @@
+use crate::{
+    error::Error,
+    replies::QueryExtension,
+    requests::{self, XExtensionRequest},
+    XDisplay, XReply,
+};
+use std::collections::HashMap;
+
 pub mod mit_shm;
+pub mod present;
 pub mod randr;
 pub mod render;
+pub mod shape;
+pub mod xinput;
+pub mod xkb;
+
+/// Opcodes of an extension and its version agreed on with the server
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExtensionVersion {
+    pub major_opcode: u8,
+    pub first_event: u8,
+    pub first_error: u8,
+    pub major_version: u32,
+    pub minor_version: u32,
+}
+
+impl ExtensionVersion {
+    /// Negotiated version is `major.minor` or newer
+    pub fn at_least(&self, major: u32, minor: u32) -> bool {
+        (self.major_version, self.minor_version) >= (major, minor)
+    }
+}
+
+/// Results of `negotiate` functions of extension modules, kept for the lifetime of the display
+#[derive(Debug, Clone, Default)]
+pub struct ExtensionRegistry {
+    /// `None` if the server doesn't have the extension or refused the version
+    negotiated: HashMap<&'static [u8], Option<ExtensionVersion>>,
+}
+
+impl ExtensionRegistry {
+    /// Version negotiated with the server. `None` if the extension is unavailable or was not
+    /// negotiated yet.
+    pub fn get(&self, name: &[u8]) -> Option<ExtensionVersion> {
+        self.negotiated.get(name).copied().flatten()
+    }
+
+    pub fn is_negotiated(&self, name: &[u8]) -> bool {
+        self.negotiated.contains_key(name)
+    }
+}
+
+/// Query the extension and send `request` asking for the version, `version` maps the reply to
+/// server version or `None` if it was refused. Result is cached in the registry of `display`.
+pub(crate) fn negotiate<Request>(
+    display: &mut XDisplay,
+    name: &'static [u8],
+    request: &Request,
+    version: impl FnOnce(Request::Reply) -> Option<(u32, u32)>,
+) -> Result<Option<ExtensionVersion>, Error>
+where
+    Request: XExtensionRequest,
+    Request::Reply: XReply,
+{
+    if let Some(&negotiated) = display.extensions().negotiated.get(name) {
+        return Ok(negotiated);
+    }
+
+    let pending = display.send_request(&requests::QueryExtension {
+        name: name.to_vec(),
+    })?;
+    display.flush()?;
+    let query: QueryExtension = match display.await_pending_reply(pending)? {
+        Ok(query) if query.present => query,
+        _ => return Ok(cache(display, name, None)),
+    };
+
+    let pending = display.send_extension_request(request, query.major_opcode)?;
+    display.flush()?;
+    let negotiated = display
+        .await_pending_reply(pending)?
+        .ok()
+        .and_then(version)
+        .map(|(major_version, minor_version)| ExtensionVersion {
+            major_opcode: query.major_opcode,
+            first_event: query.first_event,
+            first_error: query.first_error,
+            major_version,
+            minor_version,
+        });
+    Ok(cache(display, name, negotiated))
+}
+
+fn cache(
+    display: &mut XDisplay,
+    name: &'static [u8],
+    negotiated: Option<ExtensionVersion>,
+) -> Option<ExtensionVersion> {
+    display.extensions.negotiated.insert(name, negotiated);
+    negotiated
+}
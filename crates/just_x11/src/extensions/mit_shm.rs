@@ -1,8 +1,16 @@
-use crate::utils::impl_resource_id;
+use crate::{error::Error, extensions::ExtensionVersion, utils::impl_resource_id, XDisplay};
 
 pub mod replies;
 pub mod requests;
 
 pub const EXTENSION_NAME: [u8; 7] = *b"MIT-SHM";
 
+/// Query the extension and its version with [`requests::QueryVersion`], cached in
+/// [`XDisplay::extensions`]. `None` if the server doesn't have MIT-SHM.
+pub fn negotiate(display: &mut XDisplay) -> Result<Option<ExtensionVersion>, Error> {
+    crate::extensions::negotiate(display, &EXTENSION_NAME, &requests::QueryVersion, |reply| {
+        Some((reply.major_version as u32, reply.minor_version as u32))
+    })
+}
+
 impl_resource_id!(ShmSegId);
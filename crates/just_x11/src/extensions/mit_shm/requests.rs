@@ -195,6 +195,7 @@ impl ToLeBytes for CreatePixmap {
         write_le_bytes!(w, self.width);
         write_le_bytes!(w, self.height);
         write_le_bytes!(w, self.depth);
+        w.write_all(&[0u8; 3])?; // unused
         write_le_bytes!(w, self.shmseg);
         write_le_bytes!(w, self.offset);
 
@@ -216,8 +217,7 @@ impl ToLeBytes for AttachFd {
         write_le_bytes!(w, 3u16); // request length
         write_le_bytes!(w, self.shmseg);
         write_le_bytes!(w, self.read_only as u8);
-        write_le_bytes!(w, 0u8); // pad
-        write_le_bytes!(w, 16u8); // pad
+        w.write_all(&[0u8; 3])?; // unused
 
         Ok(())
     }
@@ -239,8 +239,7 @@ impl ToLeBytes for CreateSegment {
         write_le_bytes!(w, self.shmseg);
         write_le_bytes!(w, self.size);
         write_le_bytes!(w, self.read_only as u8);
-        write_le_bytes!(w, 0u8); // pad
-        write_le_bytes!(w, 16u8); // pad
+        w.write_all(&[0u8; 3])?; // unused
 
         Ok(())
     }
@@ -0,0 +1,171 @@
+//! Present extension
+//!
+//! Only the subset needed to pace drawing by vertical blanks is implemented: selecting
+//! completion events and asking to be notified at a media stream counter (MSC) value, which
+//! increments with every vertical blank of the CRTC showing the window.
+
+use crate::{
+    bitmask, error::Error, events::GenericEvent, extensions::ExtensionVersion, utils::impl_enum,
+    WindowId, XDisplay,
+};
+
+pub mod replies;
+pub mod requests;
+
+/// Name of the extension as returned by the X11 server. Can be used in [`crate::requests::QueryExtension`].
+pub const EXTENSION_NAME: [u8; 7] = *b"Present";
+
+pub const SUPPORTED_MAJOR: u32 = 1;
+pub const SUPPORTED_MINOR: u32 = 0;
+
+/// Query the extension and its version with [`requests::QueryVersion`], cached in
+/// [`XDisplay::extensions`]. `None` if the server doesn't have Present.
+pub fn negotiate(display: &mut XDisplay) -> Result<Option<ExtensionVersion>, Error> {
+    crate::extensions::negotiate(
+        display,
+        &EXTENSION_NAME,
+        &requests::QueryVersion {
+            major_version: SUPPORTED_MAJOR,
+            minor_version: SUPPORTED_MINOR,
+        },
+        |reply| {
+            (reply.major_version >= SUPPORTED_MAJOR)
+                .then_some((reply.major_version, reply.minor_version))
+        },
+    )
+}
+
+bitmask! {
+    #[repr(u32)]
+    /// Events selected with [`requests::SelectInput`]
+    bitmask EventMask {
+        CONFIGURE_NOTIFY = 0x0001,
+        COMPLETE_NOTIFY = 0x0002,
+        IDLE_NOTIFY = 0x0004,
+    }
+}
+
+/*
+┌───
+    PresentCompleteKind
+        0       PresentCompleteKindPixmap
+        1       PresentCompleteKindNotifyMSC
+
+    PresentCompleteMode
+        0       PresentCompleteModeCopy
+        1       PresentCompleteModeFlip
+        2       PresentCompleteModeSkip
+        3       PresentCompleteModeSuboptimalCopy
+└───
+*/
+
+impl_enum! {
+    #[repr(u8)]
+    enum CompleteKind {
+        Pixmap = 0,
+        NotifyMsc = 1,
+    }
+}
+
+impl_enum! {
+    #[repr(u8)]
+    enum CompleteMode {
+        Copy = 0,
+        Flip = 1,
+        Skip = 2,
+        SuboptimalCopy = 3,
+    }
+}
+
+/*
+┌───
+    PresentCompleteNotify
+        1       35                              GenericEvent
+        1       CARD8                           Present extension opcode
+        2       CARD16                          sequence number
+        4       2                               length
+        2       1                               PresentCompleteNotify
+        1       CARD8                           kind (PresentCompleteKind)
+        1       CARD8                           mode (PresentCompleteMode)
+        4       CARD32                          event id
+        4       WINDOW                          window
+        4       CARD32                          serial
+        8       CARD64                          ust
+        8       CARD64                          msc
+└───
+*/
+
+/// Sent when a presented pixmap is shown or a [`requests::NotifyMsc`] target is reached
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompleteNotify {
+    pub sequence_number: u16,
+    pub kind: CompleteKind,
+    pub mode: CompleteMode,
+    pub event_id: u32,
+    pub window: WindowId,
+    /// Serial of the request that caused the event
+    pub serial: u32,
+    /// Time of the vertical blank in microseconds, of the monotonic clock on Linux
+    pub ust: u64,
+    /// Media stream counter at the vertical blank
+    pub msc: u64,
+}
+
+impl CompleteNotify {
+    pub const EVTYPE: u16 = 1;
+
+    /// Decode event if it is a complete notification of Present with `major_opcode`, taken from
+    /// [`crate::replies::QueryExtension`] reply for [`EXTENSION_NAME`].
+    pub fn from_generic_event(event: &GenericEvent, major_opcode: u8) -> Option<Self> {
+        let raw = &event.raw;
+        if event.extension != major_opcode || event.evtype != Self::EVTYPE || raw.len() < 40 {
+            return None;
+        }
+
+        let u32_at =
+            |offset: usize| u32::from_le_bytes(raw[offset..offset + 4].try_into().unwrap());
+        let u64_at =
+            |offset: usize| u64::from_le_bytes(raw[offset..offset + 8].try_into().unwrap());
+
+        Some(Self {
+            sequence_number: event.sequence_number,
+            kind: CompleteKind::try_from(raw[10]).ok()?,
+            mode: CompleteMode::try_from(raw[11]).ok()?,
+            event_id: u32_at(12),
+            window: WindowId::from(u32_at(16)),
+            serial: u32_at(20),
+            ust: u64_at(24),
+            msc: u64_at(32),
+        })
+    }
+}
+
+#[test]
+fn complete_notify_from_generic_event() {
+    let mut raw = vec![35, 140];
+    raw.extend(7u16.to_le_bytes()); // sequence number
+    raw.extend(2u32.to_le_bytes()); // length
+    raw.extend(CompleteNotify::EVTYPE.to_le_bytes());
+    raw.extend([1, 2]); // NotifyMsc, Skip
+    raw.extend(0x400005u32.to_le_bytes()); // event id
+    raw.extend(0x400001u32.to_le_bytes()); // window
+    raw.extend(3u32.to_le_bytes()); // serial
+    raw.extend(16_666_667u64.to_le_bytes()); // ust
+    raw.extend(1000u64.to_le_bytes()); // msc
+    let event = GenericEvent::from_le_bytes(raw).unwrap();
+
+    assert_eq!(
+        CompleteNotify::from_generic_event(&event, 140),
+        Some(CompleteNotify {
+            sequence_number: 7,
+            kind: CompleteKind::NotifyMsc,
+            mode: CompleteMode::Skip,
+            event_id: 0x400005,
+            window: WindowId::from(0x400001),
+            serial: 3,
+            ust: 16_666_667,
+            msc: 1000,
+        })
+    );
+    assert_eq!(CompleteNotify::from_generic_event(&event, 141), None);
+}
@@ -0,0 +1,63 @@
+use crate::{connection::XConnection, error::Error, FromLeBytes};
+
+macro_rules! impl_xreply {
+    ($t:tt) => {
+        impl $crate::XReply for $t {
+            #[inline(always)]
+            fn from_reply(reply: $crate::replies::SomeReply) -> Option<Self> {
+                match reply {
+                    $crate::replies::SomeReply::ExtensionPresent(SomeReply::$t(r)) => Some(r),
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+/*
+┌───
+    PresentQueryVersion
+      ▶
+        1       1                               Reply
+        1                                       unused
+        2       CARD16                          sequence number
+        4       0                               reply length
+        4       CARD32                          major version
+        4       CARD32                          minor version
+        16                                      unused
+└───
+*/
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryVersion {
+    pub major_version: u32,
+    pub minor_version: u32,
+}
+
+impl FromLeBytes for QueryVersion {
+    fn from_le_bytes(conn: &mut XConnection) -> Result<Self, Error> {
+        let _unused = conn.read_u8()?;
+        let _sequence_number = conn.read_le_u16()?;
+        let _length = conn.read_le_u32()?;
+        let major_version = conn.read_le_u32()?;
+        let minor_version = conn.read_le_u32()?;
+        drop(conn.drain(16)?);
+
+        Ok(Self {
+            major_version,
+            minor_version,
+        })
+    }
+}
+
+impl_xreply!(QueryVersion);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SomeReply {
+    QueryVersion(QueryVersion),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplyType {
+    QueryVersion,
+}
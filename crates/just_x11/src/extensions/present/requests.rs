@@ -0,0 +1,146 @@
+use crate::{extensions::present::EventMask, requests::write_le_bytes, ToLeBytes, WindowId};
+
+pub mod opcodes;
+
+macro_rules! impl_xrequest_with_response {
+    ($r:tt) => {
+        impl $crate::requests::XRequestBase for $r {
+            type Reply = super::replies::$r;
+
+            #[inline(always)]
+            fn reply_type() -> Option<crate::replies::ReplyType> {
+                Some(crate::replies::ReplyType::ExtensionPresent(
+                    super::replies::ReplyType::$r,
+                ))
+            }
+        }
+
+        impl $crate::requests::XExtensionRequest for $r {}
+    };
+}
+
+macro_rules! impl_xrequest_without_response {
+    ($r:tt) => {
+        impl $crate::requests::XRequestBase for $r {
+            type Reply = $crate::requests::NoReply;
+
+            #[inline(always)]
+            fn reply_type() -> Option<$crate::replies::ReplyType> {
+                None
+            }
+        }
+
+        impl $crate::requests::XExtensionRequest for $r {}
+    };
+}
+
+/*
+┌───
+    PresentQueryVersion
+        1       CARD8                           major opcode
+        1       0                               Present opcode
+        2       3                               length
+        4       CARD32                          major version
+        4       CARD32                          minor version
+└───
+*/
+
+#[derive(Debug, Clone)]
+pub struct QueryVersion {
+    pub major_version: u32,
+    pub minor_version: u32,
+}
+
+impl ToLeBytes for QueryVersion {
+    fn to_le_bytes(&self, w: &mut impl std::io::Write) -> std::io::Result<()> {
+        write_le_bytes!(w, opcodes::QUERY_VERSION);
+        write_le_bytes!(w, 3u16); // request length
+        write_le_bytes!(w, self.major_version);
+        write_le_bytes!(w, self.minor_version);
+
+        Ok(())
+    }
+}
+
+impl_xrequest_with_response!(QueryVersion);
+
+/*
+┌───
+    PresentNotifyMSC
+        1       CARD8                           major opcode
+        1       2                               Present opcode
+        2       10                              length
+        4       WINDOW                          window
+        4       CARD32                          serial
+        4                                       padding
+        8       CARD64                          target-msc
+        8       CARD64                          divisor
+        8       CARD64                          remainder
+└───
+*/
+
+/// Ask for [`super::CompleteNotify`] when MSC of the CRTC showing `window` reaches
+/// `target_msc`. If it already passed and `divisor` is not zero, the event is sent at the next
+/// MSC for which `msc % divisor == remainder`, so `target_msc: 0, divisor: 1` means the next
+/// vertical blank.
+#[derive(Debug, Clone)]
+pub struct NotifyMsc {
+    pub window: WindowId,
+    /// Returned in [`super::CompleteNotify::serial`]
+    pub serial: u32,
+    pub target_msc: u64,
+    pub divisor: u64,
+    pub remainder: u64,
+}
+
+impl ToLeBytes for NotifyMsc {
+    fn to_le_bytes(&self, w: &mut impl std::io::Write) -> std::io::Result<()> {
+        write_le_bytes!(w, opcodes::NOTIFY_MSC);
+        write_le_bytes!(w, 10u16); // request length
+        write_le_bytes!(w, self.window);
+        write_le_bytes!(w, self.serial);
+        write_le_bytes!(w, 0u32); // padding
+        write_le_bytes!(w, self.target_msc);
+        write_le_bytes!(w, self.divisor);
+        write_le_bytes!(w, self.remainder);
+
+        Ok(())
+    }
+}
+
+impl_xrequest_without_response!(NotifyMsc);
+
+/*
+┌───
+    PresentSelectInput
+        1       CARD8                           major opcode
+        1       3                               Present opcode
+        2       4                               length
+        4       EVENTID                         event id
+        4       WINDOW                          window
+        4       SETofPRESENTEVENT               event-mask
+└───
+*/
+
+/// Select Present events of `window`. `event_id` is a new resource id of this client, events
+/// selected with an id can be changed by sending the request with the same id again.
+#[derive(Debug, Clone)]
+pub struct SelectInput {
+    pub event_id: u32,
+    pub window: WindowId,
+    pub event_mask: EventMask,
+}
+
+impl ToLeBytes for SelectInput {
+    fn to_le_bytes(&self, w: &mut impl std::io::Write) -> std::io::Result<()> {
+        write_le_bytes!(w, opcodes::SELECT_INPUT);
+        write_le_bytes!(w, 4u16); // request length
+        write_le_bytes!(w, self.event_id);
+        write_le_bytes!(w, self.window);
+        write_le_bytes!(w, self.event_mask.raw());
+
+        Ok(())
+    }
+}
+
+impl_xrequest_without_response!(SelectInput);
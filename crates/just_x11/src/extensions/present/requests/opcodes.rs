@@ -0,0 +1,3 @@
+pub const QUERY_VERSION: u8 = 0;
+pub const NOTIFY_MSC: u8 = 2;
+pub const SELECT_INPUT: u8 = 3;
@@ -8,21 +8,38 @@ use crate::{
     bitmask,
     connection::XConnection,
     error::Error,
+    extensions::ExtensionVersion,
     replies::read_vec,
     requests::write_le_bytes,
     utils::{impl_enum, impl_resource_id},
-    FromLeBytes, ToLeBytes,
+    FromLeBytes, ToLeBytes, XDisplay,
 };
 
 pub mod replies;
 pub mod requests;
 
+use replies::OutputId;
+
 /// Name of the extension as returned by the X11 server. Can be used in [`crate::requests::QueryExtension`].
 pub const EXTENSION_NAME: [u8; 5] = *b"RANDR";
 
 pub const SUPPORTED_MAJOR: u32 = 1;
 pub const SUPPORTED_MINOR: u32 = 6;
 
+/// Query the extension and its version with [`requests::QueryVersion`], cached in
+/// [`XDisplay::extensions`]. `None` if the server doesn't have RANDR.
+pub fn negotiate(display: &mut XDisplay) -> Result<Option<ExtensionVersion>, Error> {
+    crate::extensions::negotiate(
+        display,
+        &EXTENSION_NAME,
+        &requests::QueryVersion {
+            major_version: SUPPORTED_MAJOR,
+            minor_version: SUPPORTED_MINOR,
+        },
+        |reply| Some((reply.major_version, reply.minor_version)),
+    )
+}
+
 /* CRTC { XID } */
 
 impl_resource_id!(CrtcId);
@@ -316,7 +333,7 @@ bitmask! {
         2       CARD16          height in pixels
         4       CARD32          width in millimeters
         4       CARD32          height in millimeters
-        4*n     CRTC            crtcs
+        4*n     OUTPUT          outputs
 └───
  */
 
@@ -331,7 +348,7 @@ pub struct MonitorInfo {
     pub height_in_pixels: u16,
     pub width_in_millimeters: u32,
     pub height_in_millimeters: u32,
-    pub crtcs: Vec<CrtcId>,
+    pub outputs: Vec<OutputId>,
 }
 
 impl MonitorInfo {
@@ -339,14 +356,14 @@ impl MonitorInfo {
         let name = AtomId::unchecked_from(conn.read_le_u32()?);
         let primary = conn.read_bool()?;
         let automatic = conn.read_bool()?;
-        let ncrtcs = conn.read_le_u16()?;
+        let noutputs = conn.read_le_u16()?;
         let x = conn.read_le_i16()?;
         let y = conn.read_le_i16()?;
         let width_in_pixels = conn.read_le_u16()?;
         let height_in_pixels = conn.read_le_u16()?;
         let width_in_millimeters = conn.read_le_u32()?;
         let height_in_millimeters = conn.read_le_u32()?;
-        let crtcs = read_vec!(ncrtcs, CrtcId::unchecked_from(conn.read_le_u32()?));
+        let outputs = read_vec!(noutputs, OutputId::unchecked_from(conn.read_le_u32()?));
 
         Ok(Self {
             name,
@@ -358,7 +375,7 @@ impl MonitorInfo {
             height_in_pixels,
             width_in_millimeters,
             height_in_millimeters,
-            crtcs,
+            outputs,
         })
     }
 }
@@ -368,15 +385,15 @@ impl ToLeBytes for MonitorInfo {
         write_le_bytes!(w, self.name);
         write_le_bytes!(w, self.primary as u8);
         write_le_bytes!(w, self.automatic as u8);
-        write_le_bytes!(w, self.crtcs.len() as u16);
+        write_le_bytes!(w, self.outputs.len() as u16);
         write_le_bytes!(w, self.x);
         write_le_bytes!(w, self.y);
         write_le_bytes!(w, self.width_in_pixels);
         write_le_bytes!(w, self.height_in_pixels);
         write_le_bytes!(w, self.width_in_millimeters);
         write_le_bytes!(w, self.height_in_millimeters);
-        for crtc in &self.crtcs {
-            write_le_bytes!(w, crtc);
+        for output in &self.outputs {
+            write_le_bytes!(w, output);
         }
 
         Ok(())
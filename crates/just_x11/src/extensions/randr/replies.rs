@@ -2,7 +2,7 @@ use crate::{
     connection::XConnection,
     error::Error,
     extensions::{
-        randr::{ConfigStatus, MonitorInfo, PossibleRotation},
+        randr::{ConfigStatus, Connection, MonitorInfo, PossibleRotation},
         render::Subpixel,
     },
     replies::{read_vec, XReply},
@@ -298,6 +298,92 @@ impl FromLeBytes for GetScreenSizeRange {
 
 impl_xreply!(GetScreenSizeRange);
 
+/*
+┌───
+    RRGetOutputInfo
+      ▶
+        1       1                       Reply
+        1       RRCONFIGSTATUS          status
+        2       CARD16                  sequence number
+        4       1+c+m+(n+p)/4           reply length
+        4       TIMESTAMP               timestamp
+        4       CRTC                    current connected crtc
+        4       CARD32                  width in millimeters
+        4       CARD32                  height in millimeters
+        1       CONNECTION              connection
+        1       SUBPIXELORDER           subpixel-order
+        2       c                       number of CRTCs
+        2       m                       number of modes
+        2       p                       number of preferred modes
+        2       o                       number of clones
+        2       n                       length of name
+        4c      LISTofCRTC              crtcs
+        4m      LISTofMODE              modes
+        4o      LISTofOUTPUT            clones
+        n       STRING8                 name
+        p                               unused, p=pad(n)
+└───
+*/
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GetOutputInfo {
+    pub status: ConfigStatus,
+    pub timestamp: Timestamp,
+    /// Zero if the output is not connected to any CRTC
+    pub crtc: CrtcId,
+    pub width_in_millimeters: u32,
+    pub height_in_millimeters: u32,
+    pub connection: Connection,
+    pub subpixel_order: Subpixel,
+    pub crtcs: Vec<CrtcId>,
+    pub modes: Vec<u32>,
+    /// Number of modes from the start of `modes` that are preferred
+    pub preferred_modes: u16,
+    pub clones: Vec<OutputId>,
+    pub name: Vec<u8>,
+}
+
+impl FromLeBytes for GetOutputInfo {
+    fn from_le_bytes(conn: &mut XConnection) -> Result<Self, Error> {
+        let status = ConfigStatus::from_le_bytes(conn)?;
+        let _sequence_number = conn.read_le_u16()?;
+        let _reply_length = conn.read_le_u32()?;
+        let timestamp = Timestamp::from_le_bytes(conn)?;
+        let crtc = CrtcId::from_le_bytes(conn)?;
+        let width_in_millimeters = conn.read_le_u32()?;
+        let height_in_millimeters = conn.read_le_u32()?;
+        let connection = Connection::from_le_bytes(conn)?;
+        let subpixel_order = Subpixel::from_le_bytes(conn)?;
+        let crtcs_count = conn.read_le_u16()?;
+        let modes_count = conn.read_le_u16()?;
+        let preferred_modes = conn.read_le_u16()?;
+        let clones_count = conn.read_le_u16()?;
+        let name_length = conn.read_le_u16()?;
+        let crtcs = read_vec!(crtcs_count, CrtcId::from_le_bytes(conn)?);
+        let modes = read_vec!(modes_count, conn.read_le_u32()?);
+        let clones = read_vec!(clones_count, OutputId::from_le_bytes(conn)?);
+        let name = conn.read_n_bytes(name_length as usize)?;
+        drop(conn.drain(pad(name_length as usize))?);
+
+        Ok(Self {
+            status,
+            timestamp,
+            crtc,
+            width_in_millimeters,
+            height_in_millimeters,
+            connection,
+            subpixel_order,
+            crtcs,
+            modes,
+            preferred_modes,
+            clones,
+            name,
+        })
+    }
+}
+
+impl_xreply!(GetOutputInfo);
+
 /*
 ┌───
     RRGetCrtcInfo
@@ -493,6 +579,7 @@ pub enum SomeReply {
     SetScreenConfig(SetScreenConfig),
     GetScreenInfo(GetScreenInfo),
     GetScreenSizeRange(GetScreenSizeRange),
+    GetOutputInfo(GetOutputInfo),
     GetCrtcInfo(GetCrtcInfo),
     GetScreenResourcesCurrent(GetScreenResourcesCurrent),
     GetMonitors(GetMonitors),
@@ -504,6 +591,7 @@ pub enum ReplyType {
     SetScreenConfig,
     GetScreenInfo,
     GetScreenSizeRange,
+    GetOutputInfo,
     GetCrtcInfo,
     GetScreenResourcesCurrent,
     GetMonitors,
@@ -1,5 +1,6 @@
 use crate::{
-    extensions::randr::{CrtcId, Rotation, SelectMask, SizeId},
+    atoms::AtomId,
+    extensions::randr::{replies::OutputId, CrtcId, MonitorInfo, Rotation, SelectMask, SizeId},
     requests::{write_le_bytes, Timestamp},
     ToLeBytes, WindowId,
 };
@@ -205,6 +206,37 @@ impl ToLeBytes for GetScreenSizeRange {
 
 impl_xrequest_with_response!(GetScreenSizeRange);
 
+/*
+┌───
+    RRGetOutputInfo
+        1       CARD8                   major opcode
+        1       9                       RandR opcode
+        2       3                       length
+        4       OUTPUT                  output
+        4       TIMESTAMP               config-timestamp
+      ▶
+└───
+*/
+
+#[derive(Debug, Clone)]
+pub struct GetOutputInfo {
+    pub output: OutputId,
+    pub timestamp: Timestamp,
+}
+
+impl ToLeBytes for GetOutputInfo {
+    fn to_le_bytes(&self, w: &mut impl std::io::Write) -> std::io::Result<()> {
+        write_le_bytes!(w, opcodes::GET_OUTPUT_INFO);
+        write_le_bytes!(w, 3u16); // request length
+        write_le_bytes!(w, self.output);
+        write_le_bytes!(w, self.timestamp);
+
+        Ok(())
+    }
+}
+
+impl_xrequest_with_response!(GetOutputInfo);
+
 /*
 ┌───
     RRGetCrtcInfo
@@ -285,12 +317,10 @@ impl ToLeBytes for GetMonitors {
     fn to_le_bytes(&self, w: &mut impl std::io::Write) -> std::io::Result<()> {
         write_le_bytes!(w, opcodes::GET_MONITORS);
 
-        // HACK: I know that the spec says 2 not 3 but it doesn't work otherwise
+        // Spec says 2 and omits get_active, but servers expect both
         write_le_bytes!(w, 3u16); // request length
 
         write_le_bytes!(w, self.window);
-
-        // Why this is not in spec? idk.
         write_le_bytes!(w, self.get_active as u8);
         w.write_all(&[0u8; 3])?; // unused
 
@@ -299,3 +329,92 @@ impl ToLeBytes for GetMonitors {
 }
 
 impl_xrequest_with_response!(GetMonitors);
+
+/*
+┌───
+    RRSetMonitor
+        1       CARD8                   major opcode
+        1       43                      RandR opcode
+        2       8 + n                   request length
+        4       WINDOW                  window
+        24+4n   MONITORINFO             monitorinfo
+└───
+*/
+
+/// Create monitor or replace existing one with the same name. The `automatic` field of the
+/// monitor is ignored by the server.
+#[derive(Debug, Clone)]
+pub struct SetMonitor {
+    pub window: WindowId,
+    pub monitor: MonitorInfo,
+}
+
+impl ToLeBytes for SetMonitor {
+    fn to_le_bytes(&self, w: &mut impl std::io::Write) -> std::io::Result<()> {
+        write_le_bytes!(w, opcodes::SET_MONITOR);
+        write_le_bytes!(w, (8 + self.monitor.outputs.len()) as u16); // request length
+        write_le_bytes!(w, self.window);
+        self.monitor.to_le_bytes(w)?;
+
+        Ok(())
+    }
+}
+
+impl_xrequest_without_response!(SetMonitor);
+
+/*
+┌───
+    RRDeleteMonitor
+        1       CARD8                   major opcode
+        1       44                      RandR opcode
+        2       3                       request length
+        4       WINDOW                  window
+        4       ATOM                    name
+└───
+*/
+
+#[derive(Debug, Clone)]
+pub struct DeleteMonitor {
+    pub window: WindowId,
+    pub name: AtomId,
+}
+
+impl ToLeBytes for DeleteMonitor {
+    fn to_le_bytes(&self, w: &mut impl std::io::Write) -> std::io::Result<()> {
+        write_le_bytes!(w, opcodes::DELETE_MONITOR);
+        write_le_bytes!(w, 3u16); // request length
+        write_le_bytes!(w, self.window);
+        write_le_bytes!(w, self.name);
+
+        Ok(())
+    }
+}
+
+impl_xrequest_without_response!(DeleteMonitor);
+
+#[test]
+fn set_monitor_length() {
+    use crate::extensions::randr::MonitorInfo;
+
+    let request = SetMonitor {
+        window: WindowId::from(1),
+        monitor: MonitorInfo {
+            name: AtomId::from(2),
+            primary: true,
+            automatic: false,
+            x: 0,
+            y: 0,
+            width_in_pixels: 960,
+            height_in_pixels: 1080,
+            width_in_millimeters: 264,
+            height_in_millimeters: 297,
+            outputs: vec![OutputId::from(3), OutputId::from(4)],
+        },
+    };
+    let mut bytes = Vec::new();
+    request.to_le_bytes(&mut bytes).unwrap();
+    assert_eq!(
+        u16::from_le_bytes([bytes[1], bytes[2]]) as usize * 4,
+        bytes.len() + 1
+    );
+}
@@ -5,6 +5,9 @@ pub const SET_SCREEN_CONFIG: u8 = 2;
 pub const SELECT_INPUT: u8 = 4;
 pub const GET_SCREEN_INFO: u8 = 5;
 pub const GET_SCREEN_SIZE_RANGE: u8 = 6;
+pub const GET_OUTPUT_INFO: u8 = 9;
 pub const GET_CRTC_INFO: u8 = 20;
 pub const GET_SCREEN_RESOURCES_CURRENT: u8 = 25;
 pub const GET_MONITORS: u8 = 42;
+pub const SET_MONITOR: u8 = 43;
+pub const DELETE_MONITOR: u8 = 44;
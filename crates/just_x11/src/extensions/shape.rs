@@ -0,0 +1,51 @@
+//! SHAPE extension
+//!
+//! Only requests changing window shapes are implemented, they have no replies so the extension
+//! can be used right after [`crate::requests::QueryExtension`] reports it present.
+
+use crate::utils::impl_enum;
+
+pub mod requests;
+
+/// Name of the extension as returned by the X11 server. Can be used in [`crate::requests::QueryExtension`].
+pub const EXTENSION_NAME: [u8; 5] = *b"SHAPE";
+
+/*
+┌───
+    SHAPE_OP
+        0       Set
+        1       Union
+        2       Intersect
+        3       Subtract
+        4       Invert
+└───
+*/
+
+impl_enum! {
+    #[repr(u8)]
+    enum ShapeOperation {
+        Set = 0,
+        Union = 1,
+        Intersect = 2,
+        Subtract = 3,
+        Invert = 4,
+    }
+}
+
+/*
+┌───
+    SHAPE_KIND
+        0       Bounding
+        1       Clip
+        2       Input
+└───
+*/
+
+impl_enum! {
+    #[repr(u8)]
+    enum ShapeKind {
+        Bounding = 0,
+        Clip = 1,
+        Input = 2,
+    }
+}
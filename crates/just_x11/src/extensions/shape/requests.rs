@@ -0,0 +1,124 @@
+use crate::{
+    extensions::shape::{ShapeKind, ShapeOperation},
+    requests::{write_le_bytes, Ordering},
+    OrNone, PixmapId, Rectangle, ToLeBytes, WindowId,
+};
+
+mod opcodes {
+    pub const RECTANGLES: u8 = 1;
+    pub const MASK: u8 = 2;
+}
+
+macro_rules! impl_xrequest_without_response {
+    ($r:tt) => {
+        impl $crate::requests::XRequestBase for $r {
+            type Reply = $crate::requests::NoReply;
+
+            #[inline(always)]
+            fn reply_type() -> Option<$crate::replies::ReplyType> {
+                None
+            }
+        }
+
+        impl $crate::requests::XExtensionRequest for $r {}
+    };
+}
+
+/*
+┌───
+    ShapeRectangles
+        1       CARD8                   major opcode
+        1       1                       shape opcode
+        2       4+2n                    request length
+        1       SHAPE_OP                operation
+        1       SHAPE_KIND              destination kind
+        1                               ordering
+        1                               unused
+        4       WINDOW                  destination window
+        2       INT16                   x-offset
+        2       INT16                   y-offset
+        8n      LISTofRECTANGLE         rectangles
+└───
+*/
+
+/// Combine shape of `window` with union of `rectangles`. Bounding shape is relative to the
+/// outer corner of the border, i.e. starts at `-border_width`.
+#[derive(Debug, Clone)]
+pub struct Rectangles {
+    pub operation: ShapeOperation,
+    pub kind: ShapeKind,
+    pub ordering: Ordering,
+    pub window: WindowId,
+    pub x_offset: i16,
+    pub y_offset: i16,
+    pub rectangles: Vec<Rectangle>,
+}
+
+impl ToLeBytes for Rectangles {
+    fn to_le_bytes(&self, w: &mut impl std::io::Write) -> std::io::Result<()> {
+        let request_length = 4 + 2 * self.rectangles.len();
+
+        write_le_bytes!(w, opcodes::RECTANGLES);
+        write_le_bytes!(w, request_length as u16);
+        write_le_bytes!(w, self.operation as u8);
+        write_le_bytes!(w, self.kind as u8);
+        write_le_bytes!(w, self.ordering as u8);
+        write_le_bytes!(w, 0u8); // unused
+        write_le_bytes!(w, self.window);
+        write_le_bytes!(w, self.x_offset);
+        write_le_bytes!(w, self.y_offset);
+        for rectangle in &self.rectangles {
+            write_le_bytes!(w, rectangle);
+        }
+
+        Ok(())
+    }
+}
+
+impl_xrequest_without_response!(Rectangles);
+
+/*
+┌───
+    ShapeMask
+        1       CARD8                   major opcode
+        1       2                       shape opcode
+        2       5                       request length
+        1       SHAPE_OP                operation
+        1       SHAPE_KIND              destination kind
+        2                               unused
+        4       WINDOW                  destination window
+        2       INT16                   x-offset
+        2       INT16                   y-offset
+        4       PIXMAP                  source bitmap
+                0       None
+└───
+*/
+
+/// Combine shape of `window` with 1-bit `source`. `Set` with no source removes the shape.
+#[derive(Debug, Clone)]
+pub struct Mask {
+    pub operation: ShapeOperation,
+    pub kind: ShapeKind,
+    pub window: WindowId,
+    pub x_offset: i16,
+    pub y_offset: i16,
+    pub source: OrNone<PixmapId>,
+}
+
+impl ToLeBytes for Mask {
+    fn to_le_bytes(&self, w: &mut impl std::io::Write) -> std::io::Result<()> {
+        write_le_bytes!(w, opcodes::MASK);
+        write_le_bytes!(w, 5u16); // request length
+        write_le_bytes!(w, self.operation as u8);
+        write_le_bytes!(w, self.kind as u8);
+        write_le_bytes!(w, 0u16); // unused
+        write_le_bytes!(w, self.window);
+        write_le_bytes!(w, self.x_offset);
+        write_le_bytes!(w, self.y_offset);
+        write_le_bytes!(w, self.source.0);
+
+        Ok(())
+    }
+}
+
+impl_xrequest_without_response!(Mask);
@@ -0,0 +1,226 @@
+//! X Input extension, version 2
+//!
+//! Only the subset needed to tell apart master devices of multi-pointer setups and to read axes of
+//! tablets is implemented: selecting device events, decoding key, button and motion events with
+//! their valuators, and querying valuators of devices.
+
+use crate::{
+    bitmask, error::Error, events::GenericEvent, extensions::ExtensionVersion, utils::impl_enum,
+    WindowId, XDisplay,
+};
+
+pub mod replies;
+pub mod requests;
+
+/// Name of the extension as returned by the X11 server. Can be used in [`crate::requests::QueryExtension`].
+pub const EXTENSION_NAME: [u8; 15] = *b"XInputExtension";
+
+pub const SUPPORTED_MAJOR: u16 = 2;
+pub const SUPPORTED_MINOR: u16 = 0;
+
+/// Query the extension and its version with [`requests::XIQueryVersion`], cached in
+/// [`XDisplay::extensions`]. `None` if the server doesn't have XInput 2.
+pub fn negotiate(display: &mut XDisplay) -> Result<Option<ExtensionVersion>, Error> {
+    crate::extensions::negotiate(
+        display,
+        &EXTENSION_NAME,
+        &requests::XIQueryVersion {
+            major_version: SUPPORTED_MAJOR,
+            minor_version: SUPPORTED_MINOR,
+        },
+        |reply| {
+            (reply.major_version >= SUPPORTED_MAJOR)
+                .then_some((reply.major_version as u32, reply.minor_version as u32))
+        },
+    )
+}
+
+/*
+┌───
+    DEVICEID        CARD16
+        0       AllDevices
+        1       AllMasterDevices
+└───
+*/
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DeviceId {
+    inner: u16,
+}
+
+impl DeviceId {
+    pub const ALL_DEVICES: Self = Self { inner: 0 };
+    pub const ALL_MASTER_DEVICES: Self = Self { inner: 1 };
+
+    #[inline(always)]
+    fn to_le_bytes(self) -> [u8; 2] {
+        self.inner.to_le_bytes()
+    }
+
+    #[inline(always)]
+    pub fn raw(self) -> u16 {
+        self.inner
+    }
+}
+
+impl From<u16> for DeviceId {
+    fn from(inner: u16) -> Self {
+        Self { inner }
+    }
+}
+
+/*
+┌───
+    EVTYPE          CARD16
+        2       KeyPress
+        3       KeyRelease
+        4       ButtonPress
+        5       ButtonRelease
+        6       Motion
+└───
+*/
+
+impl_enum! {
+    #[repr(u16)]
+    enum DeviceEventType {
+        KeyPress = 2,
+        KeyRelease = 3,
+        ButtonPress = 4,
+        ButtonRelease = 5,
+        Motion = 6,
+    }
+}
+
+bitmask! {
+    #[repr(u32)]
+    /// Event types selected with [`requests::XISelectEvents`], bit of each type is `1 << evtype`
+    bitmask EventMask {
+        KEY_PRESS = 0x0004,
+        KEY_RELEASE = 0x0008,
+        BUTTON_PRESS = 0x0010,
+        BUTTON_RELEASE = 0x0020,
+        MOTION = 0x0040,
+    }
+}
+
+/*
+┌───
+    DeviceEvent
+        1       35                              GenericEvent
+        1       CARD8                           extension
+        2       CARD16                          sequenceNumber
+        4       6+n                             length
+        2       CARD16                          evtype
+        2       DEVICEID                        deviceid
+        4       TIMESTAMP                       time
+        4       CARD32                          detail
+        4       WINDOW                          root
+        4       WINDOW                          event
+        4       WINDOW                          child
+        4       FP1616                          root_x
+        4       FP1616                          root_y
+        4       FP1616                          event_x
+        4       FP1616                          event_y
+        2       CARD16                          buttons_len
+        2       CARD16                          valuators_len
+        2       DEVICEID                        sourceid
+        2                                       unused
+        4       SETofDEVICEEVENTFLAGS           flags
+        16      MODIFIERINFO                    mods
+        4       GROUPINFO                       group
+        4b      SETofBUTTONMASK                 buttons
+        4v      SETofVALUATORMASK               valuators
+        8n      LISTofFP3232                    axisvalues
+└───
+*/
+
+/// Fixed point number with 32 bit integral and 32 bit fractional part
+pub(crate) fn fp3232(integral: i32, frac: u32) -> f64 {
+    integral as f64 + frac as f64 / (1u64 << 32) as f64
+}
+
+/// Key, button or motion event
+#[derive(Debug, Clone)]
+pub struct DeviceEvent {
+    pub event_type: DeviceEventType,
+    pub sequence_number: u16,
+    /// Master device for events selected on master devices
+    pub device_id: DeviceId,
+    pub time: u32,
+    /// Keycode or button, zero for motion
+    pub detail: u32,
+    pub root: WindowId,
+    pub event: WindowId,
+    pub child: WindowId,
+    pub root_x: f32,
+    pub root_y: f32,
+    pub event_x: f32,
+    pub event_y: f32,
+    /// Physical device that generated the event
+    pub source_id: DeviceId,
+    pub flags: u32,
+    /// Effective modifiers, the same bits as in core events
+    pub mods: u32,
+    /// Effective keyboard group
+    pub group: u8,
+    /// Values of valuators present in the event, as valuator number and its value. Meaning of
+    /// the numbers is given by [`replies::ValuatorClass`] of [`Self::source_id`].
+    pub valuators: Vec<(u16, f64)>,
+}
+
+impl DeviceEvent {
+    /// Decode event if it is a device event of XInput with `major_opcode`, taken from
+    /// [`crate::replies::QueryExtension`] reply for [`EXTENSION_NAME`].
+    pub fn from_generic_event(event: &GenericEvent, major_opcode: u8) -> Option<Self> {
+        let raw = &event.raw;
+        if event.extension != major_opcode || raw.len() < 80 {
+            return None;
+        }
+
+        let u16_at = |offset: usize| u16::from_le_bytes([raw[offset], raw[offset + 1]]);
+        let u32_at = |offset: usize| {
+            u32::from_le_bytes([
+                raw[offset],
+                raw[offset + 1],
+                raw[offset + 2],
+                raw[offset + 3],
+            ])
+        };
+        let fp1616_at = |offset: usize| u32_at(offset) as i32 as f32 / 65536.0;
+
+        let buttons_len = u16_at(48) as usize * 4;
+        let valuators_len = u16_at(50) as usize * 4;
+        let valuators_mask = raw.get(80 + buttons_len..80 + buttons_len + valuators_len)?;
+        let mut values = raw[80 + buttons_len + valuators_len..].chunks_exact(8);
+        let mut valuators = Vec::new();
+        for number in 0..valuators_len * 8 {
+            if valuators_mask[number / 8] & (1 << (number % 8)) == 0 {
+                continue;
+            }
+            let value = values.next()?;
+            let integral = i32::from_le_bytes([value[0], value[1], value[2], value[3]]);
+            let frac = u32::from_le_bytes([value[4], value[5], value[6], value[7]]);
+            valuators.push((number as u16, fp3232(integral, frac)));
+        }
+
+        Some(Self {
+            event_type: DeviceEventType::try_from(event.evtype).ok()?,
+            sequence_number: event.sequence_number,
+            device_id: DeviceId::from(u16_at(10)),
+            time: u32_at(12),
+            detail: u32_at(16),
+            root: WindowId::from(u32_at(20)),
+            event: WindowId::from(u32_at(24)),
+            child: WindowId::from(u32_at(28)),
+            root_x: fp1616_at(32),
+            root_y: fp1616_at(36),
+            event_x: fp1616_at(40),
+            event_y: fp1616_at(44),
+            source_id: DeviceId::from(u16_at(52)),
+            flags: u32_at(56),
+            mods: u32_at(72),
+            group: raw[79],
+            valuators,
+        })
+    }
+}
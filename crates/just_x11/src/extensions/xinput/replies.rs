@@ -0,0 +1,230 @@
+use crate::{
+    atoms::AtomId,
+    connection::XConnection,
+    error::Error,
+    extensions::xinput::{fp3232, DeviceId},
+    replies::read_vec,
+    utils::pad,
+    FromLeBytes,
+};
+
+macro_rules! impl_xreply {
+    ($t:tt) => {
+        impl $crate::XReply for $t {
+            #[inline(always)]
+            fn from_reply(reply: $crate::replies::SomeReply) -> Option<Self> {
+                match reply {
+                    $crate::replies::SomeReply::ExtensionXInput(SomeReply::$t(r)) => Some(r),
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+/*
+┌───
+    XIQueryVersion
+      ▶
+        1       1                               Reply
+        1                                       unused
+        2       CARD16                          sequence number
+        4       0                               reply length
+        2       CARD16                          major_version
+        2       CARD16                          minor_version
+        20                                      unused
+└───
+*/
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XIQueryVersion {
+    pub major_version: u16,
+    pub minor_version: u16,
+}
+
+impl FromLeBytes for XIQueryVersion {
+    fn from_le_bytes(conn: &mut XConnection) -> Result<Self, Error> {
+        let _unused = conn.read_u8()?;
+        let _sequence_number = conn.read_le_u16()?;
+        let _length = conn.read_le_u32()?;
+        let major_version = conn.read_le_u16()?;
+        let minor_version = conn.read_le_u16()?;
+        drop(conn.drain(20)?);
+
+        Ok(Self {
+            major_version,
+            minor_version,
+        })
+    }
+}
+
+impl_xreply!(XIQueryVersion);
+
+/*
+┌───
+    XIQueryDevice
+      ▶
+        1       1                               Reply
+        1                                       unused
+        2       CARD16                          sequence number
+        4       n                               reply length
+        2       CARD16                          num_devices
+        22                                      pad
+        m       LISTofDEVICEINFO                info
+
+    DEVICEINFO
+        2       DEVICEID                        deviceid
+        2       CARD16                          use
+        2       DEVICEID                        attachment
+        2       CARD16                          num_classes
+        2       CARD16                          name_len
+        1       BOOL                            enabled
+        1                                       pad
+        n       LISTofCHAR8                     name
+        p                                       pad, p=pad(n)
+        o       LISTofDEVICECLASS               classes
+
+    DEVICECLASS
+        2       CARD16                          type
+        2       CARD16                          len
+        2       DEVICEID                        sourceid
+        2l      LISTofCARD8                     class specific data, l=len*2-3
+
+    VALUATORCLASS
+        2       CARD16                          type (2)
+        2       CARD16                          len
+        2       DEVICEID                        sourceid
+        2       CARD16                          number
+        4       ATOM                            label
+        8       FP3232                          min
+        8       FP3232                          max
+        8       FP3232                          value
+        4       CARD32                          resolution
+        1       CARD8                           mode
+        3                                       pad
+└───
+*/
+
+/// Axis of a device, e.g. pressure of a tablet pen. Only relative to its range, see
+/// [`ValuatorClass::normalize`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValuatorClass {
+    /// Index of the valuator in [`super::DeviceEvent::valuators`]
+    pub number: u16,
+    /// E.g. `Abs Pressure` or `Abs Tilt X`, set by the input driver
+    pub label: AtomId,
+    pub min: f64,
+    pub max: f64,
+    pub value: f64,
+    /// Units per meter
+    pub resolution: u32,
+    /// `0` for relative and `1` for absolute values
+    pub mode: u8,
+}
+
+impl ValuatorClass {
+    const TYPE: u16 = 2;
+
+    /// Position of `value` in the range from `0.0` to `1.0`, `None` if the range is empty
+    pub fn normalize(&self, value: f64) -> Option<f64> {
+        (self.max > self.min).then(|| ((value - self.min) / (self.max - self.min)).clamp(0.0, 1.0))
+    }
+}
+
+/// Device with its valuators, other classes are skipped
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceInfo {
+    pub device_id: DeviceId,
+    /// `1` master pointer, `2` master keyboard, `3` slave pointer, `4` slave keyboard,
+    /// `5` floating slave
+    pub use_: u16,
+    /// Paired master device of master devices, master of attached slaves
+    pub attachment: DeviceId,
+    pub enabled: bool,
+    pub name: Vec<u8>,
+    pub valuators: Vec<ValuatorClass>,
+}
+
+impl FromLeBytes for DeviceInfo {
+    fn from_le_bytes(conn: &mut XConnection) -> Result<Self, Error> {
+        let device_id = DeviceId::from(conn.read_le_u16()?);
+        let use_ = conn.read_le_u16()?;
+        let attachment = DeviceId::from(conn.read_le_u16()?);
+        let num_classes = conn.read_le_u16()?;
+        let name_len = conn.read_le_u16()? as usize;
+        let enabled = conn.read_bool()?;
+        drop(conn.drain(1)?);
+        let name = conn.read_n_bytes(name_len)?;
+        drop(conn.drain(pad(name_len))?);
+
+        let mut valuators = Vec::new();
+        for _ in 0..num_classes {
+            let type_ = conn.read_le_u16()?;
+            let len = conn.read_le_u16()? as usize * 4;
+            let _source_id = conn.read_le_u16()?;
+            if type_ != ValuatorClass::TYPE {
+                drop(conn.drain(len.saturating_sub(6))?);
+                continue;
+            }
+
+            let number = conn.read_le_u16()?;
+            let label = AtomId::unchecked_from(conn.read_le_u32()?);
+            let min = fp3232(conn.read_le_i32()?, conn.read_le_u32()?);
+            let max = fp3232(conn.read_le_i32()?, conn.read_le_u32()?);
+            let value = fp3232(conn.read_le_i32()?, conn.read_le_u32()?);
+            let resolution = conn.read_le_u32()?;
+            let mode = conn.read_u8()?;
+            drop(conn.drain(3)?);
+            valuators.push(ValuatorClass {
+                number,
+                label,
+                min,
+                max,
+                value,
+                resolution,
+                mode,
+            });
+        }
+
+        Ok(Self {
+            device_id,
+            use_,
+            attachment,
+            enabled,
+            name,
+            valuators,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct XIQueryDevice {
+    pub devices: Vec<DeviceInfo>,
+}
+
+impl FromLeBytes for XIQueryDevice {
+    fn from_le_bytes(conn: &mut XConnection) -> Result<Self, Error> {
+        let _unused = conn.read_u8()?;
+        let _sequence_number = conn.read_le_u16()?;
+        let _length = conn.read_le_u32()?;
+        let num_devices = conn.read_le_u16()?;
+        drop(conn.drain(22)?);
+        let devices = read_vec!(num_devices, DeviceInfo::from_le_bytes(conn)?);
+
+        Ok(Self { devices })
+    }
+}
+
+impl_xreply!(XIQueryDevice);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SomeReply {
+    XIQueryVersion(XIQueryVersion),
+    XIQueryDevice(XIQueryDevice),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplyType {
+    XIQueryVersion,
+    XIQueryDevice,
+}
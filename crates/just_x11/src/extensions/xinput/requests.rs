@@ -0,0 +1,144 @@
+use crate::{
+    extensions::xinput::{DeviceId, EventMask},
+    requests::write_le_bytes,
+    ToLeBytes, WindowId,
+};
+
+pub mod opcodes;
+
+macro_rules! impl_xrequest_with_response {
+    ($r:tt) => {
+        impl $crate::requests::XRequestBase for $r {
+            type Reply = super::replies::$r;
+
+            #[inline(always)]
+            fn reply_type() -> Option<crate::replies::ReplyType> {
+                Some(crate::replies::ReplyType::ExtensionXInput(
+                    super::replies::ReplyType::$r,
+                ))
+            }
+        }
+
+        impl $crate::requests::XExtensionRequest for $r {}
+    };
+}
+
+macro_rules! impl_xrequest_without_response {
+    ($r:tt) => {
+        impl $crate::requests::XRequestBase for $r {
+            type Reply = $crate::requests::NoReply;
+
+            #[inline(always)]
+            fn reply_type() -> Option<$crate::replies::ReplyType> {
+                None
+            }
+        }
+
+        impl $crate::requests::XExtensionRequest for $r {}
+    };
+}
+
+/*
+┌───
+    XISelectEvents
+        1       CARD8                           reqtype
+        1       46                              XI opcode
+        2       3+n                             length
+        4       WINDOW                          window
+        2       CARD16                          num_masks
+        2                                       pad
+        4n      LISTofEVMASK                    masks
+
+    EVMASK
+        2       DEVICEID                        deviceid
+        2       CARD16                          mask_len
+        4m      SETofEVENTMASK                  mask
+└───
+*/
+
+/// Select XInput 2 events of devices on `window`. Core and XInput 1 events of the same kind are
+/// not delivered to this client on the window anymore.
+#[derive(Debug, Clone)]
+pub struct XISelectEvents {
+    pub window: WindowId,
+    pub masks: Vec<(DeviceId, EventMask)>,
+}
+
+impl ToLeBytes for XISelectEvents {
+    fn to_le_bytes(&self, w: &mut impl std::io::Write) -> std::io::Result<()> {
+        write_le_bytes!(w, opcodes::XI_SELECT_EVENTS);
+        write_le_bytes!(w, 3u16 + self.masks.len() as u16 * 2); // request length
+        write_le_bytes!(w, self.window);
+        write_le_bytes!(w, self.masks.len() as u16);
+        write_le_bytes!(w, 0u16); // pad
+        for (device_id, mask) in &self.masks {
+            write_le_bytes!(w, device_id);
+            write_le_bytes!(w, 1u16); // mask_len
+            write_le_bytes!(w, mask.raw());
+        }
+
+        Ok(())
+    }
+}
+
+impl_xrequest_without_response!(XISelectEvents);
+
+/*
+┌───
+    XIQueryVersion
+        1       CARD8                           reqtype
+        1       47                              XI opcode
+        2       2                               length
+        2       CARD16                          major_version
+        2       CARD16                          minor_version
+└───
+*/
+
+#[derive(Debug, Clone)]
+pub struct XIQueryVersion {
+    pub major_version: u16,
+    pub minor_version: u16,
+}
+
+impl ToLeBytes for XIQueryVersion {
+    fn to_le_bytes(&self, w: &mut impl std::io::Write) -> std::io::Result<()> {
+        write_le_bytes!(w, opcodes::XI_QUERY_VERSION);
+        write_le_bytes!(w, 2u16); // request length
+        write_le_bytes!(w, self.major_version);
+        write_le_bytes!(w, self.minor_version);
+
+        Ok(())
+    }
+}
+
+impl_xrequest_with_response!(XIQueryVersion);
+
+/*
+┌───
+    XIQueryDevice
+        1       CARD8                           reqtype
+        1       48                              XI opcode
+        2       2                               length
+        2       DEVICEID                        deviceid
+        2                                       pad
+└───
+*/
+
+/// Details of `device_id`, or of all devices with [`DeviceId::ALL_DEVICES`]
+#[derive(Debug, Clone)]
+pub struct XIQueryDevice {
+    pub device_id: DeviceId,
+}
+
+impl ToLeBytes for XIQueryDevice {
+    fn to_le_bytes(&self, w: &mut impl std::io::Write) -> std::io::Result<()> {
+        write_le_bytes!(w, opcodes::XI_QUERY_DEVICE);
+        write_le_bytes!(w, 2u16); // request length
+        write_le_bytes!(w, self.device_id);
+        write_le_bytes!(w, 0u16); // pad
+
+        Ok(())
+    }
+}
+
+impl_xrequest_with_response!(XIQueryDevice);
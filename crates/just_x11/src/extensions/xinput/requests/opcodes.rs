@@ -0,0 +1,3 @@
+pub const XI_SELECT_EVENTS: u8 = 46;
+pub const XI_QUERY_VERSION: u8 = 47;
+pub const XI_QUERY_DEVICE: u8 = 48;
@@ -0,0 +1,146 @@
+//! XKEYBOARD extension
+//!
+//! Only the small subset needed to receive bell notifications is implemented
+
+use crate::{
+    atoms::AtomId, bitmask, error::Error, events::UnknownEvent, extensions::ExtensionVersion,
+    WindowId, XDisplay,
+};
+
+pub mod replies;
+pub mod requests;
+
+/// Name of the extension as returned by the X11 server. Can be used in [`crate::requests::QueryExtension`].
+pub const EXTENSION_NAME: [u8; 9] = *b"XKEYBOARD";
+
+pub const SUPPORTED_MAJOR: u16 = 1;
+pub const SUPPORTED_MINOR: u16 = 0;
+
+/// Query the extension and enable it with [`requests::UseExtension`], cached in
+/// [`XDisplay::extensions`]. `None` if the server doesn't have XKB or doesn't support
+/// [`SUPPORTED_MAJOR`].[`SUPPORTED_MINOR`].
+pub fn negotiate(display: &mut XDisplay) -> Result<Option<ExtensionVersion>, Error> {
+    crate::extensions::negotiate(
+        display,
+        &EXTENSION_NAME,
+        &requests::UseExtension {
+            wanted_major: SUPPORTED_MAJOR,
+            wanted_minor: SUPPORTED_MINOR,
+        },
+        |reply| {
+            reply
+                .supported
+                .then_some((reply.server_major as u32, reply.server_minor as u32))
+        },
+    )
+}
+
+/*
+┌───
+    KB_DEVICESPEC        CARD16
+        0x100   UseCoreKbd
+        0x200   UseCorePtr
+└───
+*/
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeviceSpec {
+    inner: u16,
+}
+
+impl DeviceSpec {
+    pub const USE_CORE_KBD: Self = Self { inner: 0x100 };
+    pub const USE_CORE_PTR: Self = Self { inner: 0x200 };
+
+    #[inline(always)]
+    fn to_le_bytes(self) -> [u8; 2] {
+        self.inner.to_le_bytes()
+    }
+}
+
+impl From<u16> for DeviceSpec {
+    fn from(inner: u16) -> Self {
+        Self { inner }
+    }
+}
+
+bitmask! {
+    #[repr(u16)]
+    bitmask EventMask {
+        NEW_KEYBOARD_NOTIFY = 0x0001,
+        MAP_NOTIFY = 0x0002,
+        STATE_NOTIFY = 0x0004,
+        CONTROLS_NOTIFY = 0x0008,
+        INDICATOR_STATE_NOTIFY = 0x0010,
+        INDICATOR_MAP_NOTIFY = 0x0020,
+        NAMES_NOTIFY = 0x0040,
+        COMPAT_MAP_NOTIFY = 0x0080,
+        BELL_NOTIFY = 0x0100,
+        ACTION_MESSAGE = 0x0200,
+        ACCESS_X_NOTIFY = 0x0400,
+        EXTENSION_DEVICE_NOTIFY = 0x0800,
+    }
+}
+
+/// All XKB events share one event code and are distinguished by the second byte
+pub(crate) const XKB_BELL_NOTIFY: u8 = 8;
+
+/*
+┌───
+    BellNotify
+        1       CARD8                   type
+        1       8                       xkb code
+        2       CARD16                  sequence number
+        4       TIMESTAMP               time
+        1       CARD8                   deviceID
+        1       CARD8                   bellClass
+        1       CARD8                   bellID
+        1       CARD8                   percent
+        2       CARD16                  pitch
+        2       CARD16                  duration
+        4       ATOM                    name
+        4       WINDOW                  window
+        1       BOOL                    eventOnly
+        7                               unused
+└───
+*/
+
+#[derive(Debug, Clone)]
+pub struct BellNotify {
+    pub sequence_number: u16,
+    pub time: u32,
+    pub device_id: u8,
+    pub bell_class: u8,
+    pub bell_id: u8,
+    pub percent: u8,
+    pub pitch: u16,
+    pub duration: u16,
+    pub name: AtomId,
+    pub window: WindowId,
+    pub event_only: bool,
+}
+
+impl BellNotify {
+    /// Decode event if it is XKB `BellNotify`. `first_event` is taken from
+    /// [`crate::replies::QueryExtension`] reply for [`EXTENSION_NAME`].
+    pub fn from_unknown_event(event: &UnknownEvent, first_event: u8) -> Option<Self> {
+        let raw = &event.raw;
+        if raw[0] & 0x7f != first_event || raw[1] != XKB_BELL_NOTIFY {
+            return None;
+        }
+
+        Some(Self {
+            sequence_number: u16::from_le_bytes([raw[2], raw[3]]),
+            time: u32::from_le_bytes([raw[4], raw[5], raw[6], raw[7]]),
+            device_id: raw[8],
+            bell_class: raw[9],
+            bell_id: raw[10],
+            percent: raw[11],
+            pitch: u16::from_le_bytes([raw[12], raw[13]]),
+            duration: u16::from_le_bytes([raw[14], raw[15]]),
+            name: AtomId::from(u32::from_le_bytes([raw[16], raw[17], raw[18], raw[19]])),
+            window: WindowId::from(u32::from_le_bytes([raw[20], raw[21], raw[22], raw[23]])),
+            event_only: raw[24] != 0,
+        })
+    }
+}
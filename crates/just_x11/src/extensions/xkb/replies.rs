@@ -0,0 +1,65 @@
+use crate::{connection::XConnection, error::Error, FromLeBytes};
+
+macro_rules! impl_xreply {
+    ($t:tt) => {
+        impl $crate::XReply for $t {
+            #[inline(always)]
+            fn from_reply(reply: $crate::replies::SomeReply) -> Option<Self> {
+                match reply {
+                    $crate::replies::SomeReply::ExtensionXkb(SomeReply::$t(r)) => Some(r),
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+/*
+┌───
+    UseExtension
+      ▶
+        1       1                       Reply
+        1       BOOL                    supported
+        2       CARD16                  sequence number
+        4       0                       reply length
+        2       CARD16                  serverMajor
+        2       CARD16                  serverMinor
+        20                              unused
+└───
+*/
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UseExtension {
+    pub supported: bool,
+    pub server_major: u16,
+    pub server_minor: u16,
+}
+
+impl FromLeBytes for UseExtension {
+    fn from_le_bytes(conn: &mut XConnection) -> Result<Self, Error> {
+        let supported = conn.read_bool()?;
+        let _sequence_number = conn.read_le_u16()?;
+        let _length = conn.read_le_u32()?;
+        let server_major = conn.read_le_u16()?;
+        let server_minor = conn.read_le_u16()?;
+        drop(conn.drain(20)?);
+
+        Ok(Self {
+            supported,
+            server_major,
+            server_minor,
+        })
+    }
+}
+
+impl_xreply!(UseExtension);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SomeReply {
+    UseExtension(UseExtension),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplyType {
+    UseExtension,
+}
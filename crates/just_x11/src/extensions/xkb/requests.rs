@@ -0,0 +1,170 @@
+use crate::{
+    atoms::AtomId,
+    extensions::xkb::{DeviceSpec, EventMask},
+    requests::write_le_bytes,
+    OrNone, ToLeBytes, WindowId,
+};
+
+pub mod opcodes;
+
+macro_rules! impl_xrequest_with_response {
+    ($r:tt) => {
+        impl $crate::requests::XRequestBase for $r {
+            type Reply = super::replies::$r;
+
+            #[inline(always)]
+            fn reply_type() -> Option<crate::replies::ReplyType> {
+                Some(crate::replies::ReplyType::ExtensionXkb(
+                    super::replies::ReplyType::$r,
+                ))
+            }
+        }
+
+        impl $crate::requests::XExtensionRequest for $r {}
+    };
+}
+
+macro_rules! impl_xrequest_without_response {
+    ($r:tt) => {
+        impl $crate::requests::XRequestBase for $r {
+            type Reply = $crate::requests::NoReply;
+
+            #[inline(always)]
+            fn reply_type() -> Option<$crate::replies::ReplyType> {
+                None
+            }
+        }
+
+        impl $crate::requests::XExtensionRequest for $r {}
+    };
+}
+
+/*
+┌───
+    UseExtension
+        1       ?                       opcode
+        1       0                       xkb-opcode
+        2       2                       request-length
+        2       CARD16                  wantedMajor
+        2       CARD16                  wantedMinor
+└───
+*/
+
+#[derive(Debug, Clone)]
+pub struct UseExtension {
+    pub wanted_major: u16,
+    pub wanted_minor: u16,
+}
+
+impl ToLeBytes for UseExtension {
+    fn to_le_bytes(&self, w: &mut impl std::io::Write) -> std::io::Result<()> {
+        write_le_bytes!(w, opcodes::USE_EXTENSION);
+        write_le_bytes!(w, 2u16); // request length
+        write_le_bytes!(w, self.wanted_major);
+        write_le_bytes!(w, self.wanted_minor);
+
+        Ok(())
+    }
+}
+
+impl_xrequest_with_response!(UseExtension);
+
+/*
+┌───
+    SelectEvents
+        1       ?                       opcode
+        1       1                       xkb-opcode
+        2       4+(V/4)                 request-length
+        2       KB_DEVICESPEC           deviceSpec
+        2       SETofEVENTTYPE          affectWhich
+        2       SETofEVENTTYPE          clear
+        2       SETofEVENTTYPE          selectAll
+        2       SETofMAPPART            affectMap
+        2       SETofMAPPART            map
+        V       LISTofITEMs             details
+└───
+*/
+
+/// Select or deselect whole event types. Per-event details are not supported, events are
+/// always selected with all of their details.
+#[derive(Debug, Clone)]
+pub struct SelectEvents {
+    pub device_spec: DeviceSpec,
+    pub select: EventMask,
+    pub clear: EventMask,
+}
+
+impl ToLeBytes for SelectEvents {
+    fn to_le_bytes(&self, w: &mut impl std::io::Write) -> std::io::Result<()> {
+        write_le_bytes!(w, opcodes::SELECT_EVENTS);
+        write_le_bytes!(w, 4u16); // request length
+        write_le_bytes!(w, self.device_spec);
+        write_le_bytes!(w, (self.select | self.clear).raw()); // affectWhich
+        write_le_bytes!(w, self.clear.raw());
+        write_le_bytes!(w, self.select.raw()); // selectAll
+        write_le_bytes!(w, 0u16); // affectMap
+        write_le_bytes!(w, 0u16); // map
+
+        Ok(())
+    }
+}
+
+impl_xrequest_without_response!(SelectEvents);
+
+/*
+┌───
+    Bell
+        1       ?                       opcode
+        1       3                       xkb-opcode
+        2       7                       request-length
+        2       KB_DEVICESPEC           deviceSpec
+        2       KB_BELLCLASSSPEC        bellClass
+        2       KB_IDSPEC               bellID
+        1       INT8                    percent
+        1       BOOL                    forceSound
+        1       BOOL                    eventOnly
+        1                               unused
+        2       INT16                   pitch
+        2       INT16                   duration
+        2                               unused
+        4       ATOM                    name
+        4       WINDOW                  window
+└───
+*/
+
+#[derive(Debug, Clone)]
+pub struct Bell {
+    pub device_spec: DeviceSpec,
+    pub bell_class: u16,
+    pub bell_id: u16,
+    pub percent: i8,
+    pub force_sound: bool,
+    pub event_only: bool,
+    pub pitch: i16,
+    pub duration: i16,
+    pub name: OrNone<AtomId>,
+    pub window: OrNone<WindowId>,
+}
+
+impl ToLeBytes for Bell {
+    fn to_le_bytes(&self, w: &mut impl std::io::Write) -> std::io::Result<()> {
+        write_le_bytes!(w, opcodes::BELL);
+        write_le_bytes!(w, 7u16); // request length
+        write_le_bytes!(w, self.device_spec);
+        write_le_bytes!(w, self.bell_class);
+        write_le_bytes!(w, self.bell_id);
+        write_le_bytes!(w, self.percent);
+        write_le_bytes!(w, self.force_sound as u8);
+        write_le_bytes!(w, self.event_only as u8);
+        write_le_bytes!(w, 0u8); // unused
+        write_le_bytes!(w, self.pitch);
+        write_le_bytes!(w, self.duration);
+        write_le_bytes!(w, 0u16); // unused
+        write_le_bytes!(w, self.name.0);
+        write_le_bytes!(w, self.window.0);
+
+        Ok(())
+    }
+}
+
+impl_xrequest_without_response!(Bell);
@@ -0,0 +1,3 @@
+pub const USE_EXTENSION: u8 = 0;
+pub const SELECT_EVENTS: u8 = 1;
+pub const BELL: u8 = 3;
@@ -0,0 +1,209 @@
+//! Pointer and keyboard grabs released when dropped
+//!
+//! Forgotten grab freezes input of the whole session, so [`XDisplay::grab_pointer`] and
+//! [`XDisplay::grab_keyboard`] return guards that ungrab on drop, including during unwinding.
+//! Guards dereference to the [`XDisplay`], so the connection can be used while the grab is held.
+//! Grabs that live across iterations of the event loop are leaked and released later with
+//! [`XDisplay::ungrab_pointer`] and [`XDisplay::ungrab_keyboard`].
+
+use crate::{
+    error::Error,
+    replies::{GrabKeyboardStatus, GrabPointerStatus},
+    requests::{self, Timestamp},
+    xerror::SomeError,
+    XDisplay,
+};
+use std::{
+    mem,
+    ops::{Deref, DerefMut},
+};
+
+#[derive(Debug)]
+pub enum GrabError<Status> {
+    /// Server refused the grab
+    Refused(Status),
+    Protocol(SomeError),
+}
+
+/// Active pointer grab, sends `UngrabPointer` and flushes when dropped
+pub struct PointerGrab<'a> {
+    display: &'a mut XDisplay,
+}
+
+impl PointerGrab<'_> {
+    /// Like drop but reports errors
+    pub fn ungrab(self) -> Result<(), Error> {
+        let mut this = mem::ManuallyDrop::new(self);
+        this.display.ungrab_pointer()
+    }
+
+    /// Keep the grab after the guard is gone, release it with [`XDisplay::ungrab_pointer`]
+    pub fn leak(self) {
+        mem::forget(self);
+    }
+}
+
+impl Deref for PointerGrab<'_> {
+    type Target = XDisplay;
+
+    fn deref(&self) -> &Self::Target {
+        self.display
+    }
+}
+
+impl DerefMut for PointerGrab<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.display
+    }
+}
+
+impl Drop for PointerGrab<'_> {
+    fn drop(&mut self) {
+        let _ = self.display.ungrab_pointer();
+    }
+}
+
+/// Active keyboard grab, sends `UngrabKeyboard` and flushes when dropped
+pub struct KeyboardGrab<'a> {
+    display: &'a mut XDisplay,
+}
+
+impl KeyboardGrab<'_> {
+    /// Like drop but reports errors
+    pub fn ungrab(self) -> Result<(), Error> {
+        let mut this = mem::ManuallyDrop::new(self);
+        this.display.ungrab_keyboard()
+    }
+
+    /// Keep the grab after the guard is gone, release it with [`XDisplay::ungrab_keyboard`]
+    pub fn leak(self) {
+        mem::forget(self);
+    }
+}
+
+impl Deref for KeyboardGrab<'_> {
+    type Target = XDisplay;
+
+    fn deref(&self) -> &Self::Target {
+        self.display
+    }
+}
+
+impl DerefMut for KeyboardGrab<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.display
+    }
+}
+
+impl Drop for KeyboardGrab<'_> {
+    fn drop(&mut self) {
+        let _ = self.display.ungrab_keyboard();
+    }
+}
+
+impl XDisplay {
+    /// Send `request` and wait for the reply, the grab is held as long as the returned guard
+    pub fn grab_pointer(
+        &mut self,
+        request: &requests::GrabPointer,
+    ) -> Result<Result<PointerGrab<'_>, GrabError<GrabPointerStatus>>, Error> {
+        let pending_reply = self.send_request(request)?;
+        self.flush()?;
+        match self.await_pending_reply(pending_reply)? {
+            Ok(reply) if matches!(reply.status, GrabPointerStatus::Success) => {
+                Ok(Ok(PointerGrab { display: self }))
+            }
+            Ok(reply) => Ok(Err(GrabError::Refused(reply.status))),
+            Err(err) => Ok(Err(GrabError::Protocol(err))),
+        }
+    }
+
+    /// Send `UngrabPointer` and flush, releases a grab whose guard was leaked
+    pub fn ungrab_pointer(&mut self) -> Result<(), Error> {
+        self.send_request(&requests::UngrabPointer {
+            time: Timestamp::CurrentTime,
+        })?;
+        self.flush()
+    }
+
+    /// Send `request` and wait for the reply, the grab is held as long as the returned guard
+    pub fn grab_keyboard(
+        &mut self,
+        request: &requests::GrabKeyboard,
+    ) -> Result<Result<KeyboardGrab<'_>, GrabError<GrabKeyboardStatus>>, Error> {
+        let pending_reply = self.send_request(request)?;
+        self.flush()?;
+        match self.await_pending_reply(pending_reply)? {
+            Ok(reply) if matches!(reply.status, GrabKeyboardStatus::Success) => {
+                Ok(Ok(KeyboardGrab { display: self }))
+            }
+            Ok(reply) => Ok(Err(GrabError::Refused(reply.status))),
+            Err(err) => Ok(Err(GrabError::Protocol(err))),
+        }
+    }
+
+    /// Send `UngrabKeyboard` and flush, releases a grab whose guard was leaked
+    pub fn ungrab_keyboard(&mut self) -> Result<(), Error> {
+        // CurrentTime
+        self.send_request(&requests::UngrabKeyboard { time: 0 })?;
+        self.flush()
+    }
+}
+
+#[test]
+fn grabs_are_released() {
+    use crate::{
+        connection::ScriptStep,
+        requests::{GrabMode, PointerEventMask},
+        scripted_display_with_writes, WindowId,
+    };
+
+    let grab_reply = |sequence_number: u16, status: u8| {
+        let mut reply = vec![1, status];
+        reply.extend(sequence_number.to_le_bytes());
+        reply.extend([0u8; 28]);
+        reply
+    };
+    let (mut display, written) = scripted_display_with_writes([
+        ScriptStep::data(grab_reply(1, 0)),
+        ScriptStep::data(grab_reply(3, 0)),
+        ScriptStep::data(grab_reply(5, 1)),
+    ])
+    .unwrap();
+    let window = WindowId::from(0x400001);
+    let grab_pointer = requests::GrabPointer::new(window, PointerEventMask::BUTTON_PRESS);
+    let grab_keyboard = requests::GrabKeyboard {
+        owner_events: false,
+        grab_window: window,
+        time: Timestamp::CurrentTime,
+        pointer_mode: GrabMode::Asynchronous,
+        keyboard_mode: GrabMode::Asynchronous,
+    };
+    const UNGRAB_POINTER: [u8; 8] = [27, 0, 2, 0, 0, 0, 0, 0];
+    const UNGRAB_KEYBOARD: [u8; 8] = [32, 0, 2, 0, 0, 0, 0, 0];
+
+    // Dropped guard ungrabs
+    let grab = display.grab_pointer(&grab_pointer).unwrap().unwrap();
+    written.borrow_mut().clear();
+    drop(grab);
+    assert_eq!(*written.borrow(), UNGRAB_POINTER);
+
+    // Leaked guard doesn't, the grab is released explicitly
+    display
+        .grab_keyboard(&grab_keyboard)
+        .unwrap()
+        .unwrap()
+        .leak();
+    written.borrow_mut().clear();
+    display.flush().unwrap();
+    assert!(written.borrow().is_empty());
+    display.ungrab_keyboard().unwrap();
+    assert_eq!(*written.borrow(), UNGRAB_KEYBOARD);
+
+    // Refused grab has nothing to release
+    let refused = display.grab_keyboard(&grab_keyboard).unwrap();
+    assert!(matches!(
+        refused,
+        Err(GrabError::Refused(GrabKeyboardStatus::AlreadyGrabbed))
+    ));
+}
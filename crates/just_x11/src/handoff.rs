@@ -0,0 +1,205 @@
+//! Passing a connection to a program started with `exec`, e.g. a window manager restarting in
+//! place without losing the resources, grabs and selections it owns
+//!
+//! The new program gets a duplicate of the socket and the client side state that can't be asked
+//! from the server again: sequence number of the next request, next resource id and the setup
+//! reply the server sent when the connection was opened. Everything is written into one line,
+//! so it can be passed in an environment variable or an argument:
+//!
+//! ```text
+//! <fd> <next sequence number> <next resource id> <default screen> <hex encoded setup reply>
+//! ```
+//!
+//! Extensions have to be negotiated again, events and errors that were received but not taken
+//! from the queues are lost.
+
+use crate::{
+    connection::{self, XConnection},
+    error::Error,
+    requests, FromLeBytes, InitializeConnectionResponse, SequenceNumber, XDisplay,
+};
+use std::{
+    fmt,
+    os::fd::{IntoRawFd, RawFd},
+    str::FromStr,
+};
+
+/// Connection state kept across `exec`, see [`XDisplay::handoff`] and [`XDisplay::resume`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectionHandoff {
+    /// Duplicate of the server socket without `FD_CLOEXEC`
+    pub fd: RawFd,
+    next_sequence_number: u16,
+    next_resource_id: u32,
+    default_screen: usize,
+    setup: Vec<u8>,
+}
+
+impl XDisplay {
+    /// Prepare the connection to be used by a program started with `exec`. Waits for replies
+    /// to all requests sent so far, so none of them arrive to the new program.
+    ///
+    /// The display must not send more requests if `exec` succeeds, the server would see them
+    /// mixed with requests of the new program. If `exec` fails the display can be used further,
+    /// [`ConnectionHandoff::fd`] should be closed then.
+    pub fn handoff(&mut self) -> Result<ConnectionHandoff, Error> {
+        let pending = self.send_request(&requests::GetInputFocus)?;
+        self.flush()?;
+        let _ = self.await_pending_reply(pending)?;
+        // Bytes read past the reply would not be read by the new program
+        while self.connection.has_unconsumed_data() {
+            self.decode_response_blocking()?;
+        }
+
+        let fd = self.connection.try_clone_stream()?.into_raw_fd();
+        connection::set_close_on_exec(fd, false)?;
+
+        Ok(ConnectionHandoff {
+            fd,
+            next_sequence_number: self.next_sequence_number.value,
+            next_resource_id: self.id_allocator.next_id,
+            default_screen: self.default_screen,
+            setup: self.setup.clone(),
+        })
+    }
+
+    /// Continue using a connection passed by [`Self::handoff`] before `exec`. The socket gets
+    /// `FD_CLOEXEC` again so programs spawned later don't inherit it.
+    ///
+    /// # Safety
+    ///
+    /// `handoff` must come from [`Self::handoff`] of the program that called `exec` and be
+    /// resumed at most once, [`ConnectionHandoff::fd`] is owned by the returned display.
+    pub unsafe fn resume(handoff: ConnectionHandoff) -> Result<Self, Error> {
+        connection::set_close_on_exec(handoff.fd, true)?;
+        let mut connection = XConnection::from_raw_fd(handoff.fd)?;
+        connection.unread(&handoff.setup);
+
+        let response = match InitializeConnectionResponse::from_le_bytes(&mut connection)? {
+            InitializeConnectionResponse::Refused(_) => {
+                return Err(Error::InvalidResponse(stringify!(ConnectionHandoff)))
+            }
+            InitializeConnectionResponse::Success(response) => response,
+        };
+
+        let mut display = Self::from_setup(connection, response, handoff.setup)?;
+        if handoff.default_screen >= display.screens.len().max(1) {
+            return Err(Error::NoSuchScreen(
+                handoff.default_screen as u32,
+                display.screens.len(),
+            ));
+        }
+        display.default_screen = handoff.default_screen;
+        display.next_sequence_number = SequenceNumber {
+            value: handoff.next_sequence_number,
+        };
+        display.id_allocator.next_id = handoff.next_resource_id;
+        Ok(display)
+    }
+}
+
+impl fmt::Display for ConnectionHandoff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} ",
+            self.fd, self.next_sequence_number, self.next_resource_id, self.default_screen
+        )?;
+        for byte in &self.setup {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidHandoff;
+
+impl fmt::Display for InvalidHandoff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid connection handoff")
+    }
+}
+
+impl FromStr for ConnectionHandoff {
+    type Err = InvalidHandoff;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = s.split(' ');
+        let mut next = || words.next().ok_or(InvalidHandoff);
+        let fd = next()?.parse().map_err(|_| InvalidHandoff)?;
+        let next_sequence_number = next()?.parse().map_err(|_| InvalidHandoff)?;
+        let next_resource_id = next()?.parse().map_err(|_| InvalidHandoff)?;
+        let default_screen = next()?.parse().map_err(|_| InvalidHandoff)?;
+        let setup = next()?;
+        if !setup.is_ascii() || setup.len() % 2 != 0 || words.next().is_some() {
+            return Err(InvalidHandoff);
+        }
+        let setup = (0..setup.len())
+            .step_by(2)
+            .map(|idx| u8::from_str_radix(&setup[idx..idx + 2], 16).map_err(|_| InvalidHandoff))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            fd,
+            next_sequence_number,
+            next_resource_id,
+            default_screen,
+            setup,
+        })
+    }
+}
+
+#[test]
+fn resume_handoff() {
+    use crate::replies;
+    use std::{io::Write, os::unix::net::UnixStream};
+
+    let mut setup = vec![1, 0];
+    setup.extend(11u16.to_le_bytes()); // protocol major version
+    setup.extend(0u16.to_le_bytes()); // protocol minor version
+    setup.extend(8u16.to_le_bytes()); // additional data length
+    setup.extend(0u32.to_le_bytes()); // release number
+    setup.extend(0x0400000u32.to_le_bytes()); // resource id base
+    setup.extend(0x01fffffu32.to_le_bytes()); // resource id mask
+    setup.extend(0u32.to_le_bytes()); // motion buffer size
+    setup.extend(0u16.to_le_bytes()); // vendor length
+    setup.extend(u16::MAX.to_le_bytes()); // maximum request length
+    setup.extend([0, 0, 0, 0, 32, 32, 8, 255]); // no screens and formats, image format, keycodes
+    setup.extend(0u32.to_le_bytes()); // unused
+
+    let (client, mut server) = UnixStream::pair().unwrap();
+    let handoff = ConnectionHandoff {
+        fd: client.into_raw_fd(),
+        next_sequence_number: 300,
+        next_resource_id: 7,
+        default_screen: 0,
+        setup,
+    };
+    let line = handoff.to_string();
+    assert!(line.starts_with(&format!("{} 300 7 0 01000b00", handoff.fd)));
+    assert_eq!(ConnectionHandoff::from_str(&line), Ok(handoff.clone()));
+    assert_eq!(
+        ConnectionHandoff::from_str("3 300 7 0 0"),
+        Err(InvalidHandoff)
+    );
+    assert_eq!(ConnectionHandoff::from_str("3 300 7"), Err(InvalidHandoff));
+
+    let mut display = unsafe { XDisplay::resume(handoff) }.unwrap();
+    assert_eq!(display.maximum_request_length(), u16::MAX);
+    assert_eq!(u32::from(display.id_allocator().allocate_id()), 0x0400007);
+
+    // Reply is matched by the resumed sequence number
+    let mut reply = vec![1, 2]; // Reply, RevertTo::Parent
+    reply.extend(300u16.to_le_bytes());
+    reply.extend(0u32.to_le_bytes()); // reply length
+    reply.extend(0x400001u32.to_le_bytes()); // focus
+    reply.extend([0u8; 20]);
+    server.write_all(&reply).unwrap();
+    let pending = display.send_request(&requests::GetInputFocus).unwrap();
+    let focus = display.await_pending_reply(pending).unwrap().unwrap();
+    assert!(matches!(
+        focus.focus,
+        replies::Focus::Window(window) if u32::from(window) == 0x400001
+    ));
+}
@@ -0,0 +1,2139 @@
+//! Static description of implemented requests
+//!
+//! Layouts are taken from the protocol specification comments next to each request so tracing
+//! and debugging tools can decode traffic symbolically.
+
+use crate::extensions::{mit_shm, present, randr, shape, xinput, xkb};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldInfo {
+    /// Size in bytes, may be an expression like `4n` for variable length fields
+    pub size: &'static str,
+    /// Protocol type or fixed value, empty for unused bytes
+    pub type_: &'static str,
+    pub name: &'static str,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestInfo {
+    /// Name of the request struct
+    pub name: &'static str,
+    /// Major opcode for core requests, minor opcode for extension requests
+    pub opcode: u8,
+    pub fields: &'static [FieldInfo],
+    /// Name of the reply struct, if request has a reply
+    pub reply: Option<&'static str>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExtensionInfo {
+    /// Name of the extension as returned by the X11 server
+    pub name: &'static [u8],
+    pub requests: &'static [RequestInfo],
+}
+
+macro_rules! field {
+    ($size:literal, $type_:literal, $name:literal) => {
+        FieldInfo {
+            size: $size,
+            type_: $type_,
+            name: $name,
+        }
+    };
+}
+
+pub fn core_request(opcode: u8) -> Option<&'static RequestInfo> {
+    CORE_REQUESTS
+        .iter()
+        .find(|request| request.opcode == opcode)
+}
+
+pub fn extension(name: &[u8]) -> Option<&'static ExtensionInfo> {
+    EXTENSIONS.iter().find(|extension| extension.name == name)
+}
+
+pub fn extension_request(extension_name: &[u8], minor_opcode: u8) -> Option<&'static RequestInfo> {
+    extension(extension_name)?
+        .requests
+        .iter()
+        .find(|request| request.opcode == minor_opcode)
+}
+
+pub const EXTENSIONS: &[ExtensionInfo] = &[
+    ExtensionInfo {
+        name: &mit_shm::EXTENSION_NAME,
+        requests: MIT_SHM_REQUESTS,
+    },
+    ExtensionInfo {
+        name: &present::EXTENSION_NAME,
+        requests: PRESENT_REQUESTS,
+    },
+    ExtensionInfo {
+        name: &randr::EXTENSION_NAME,
+        requests: RANDR_REQUESTS,
+    },
+    ExtensionInfo {
+        name: &shape::EXTENSION_NAME,
+        requests: SHAPE_REQUESTS,
+    },
+    ExtensionInfo {
+        name: &xinput::EXTENSION_NAME,
+        requests: XINPUT_REQUESTS,
+    },
+    ExtensionInfo {
+        name: &xkb::EXTENSION_NAME,
+        requests: XKB_REQUESTS,
+    },
+];
+
+pub const CORE_REQUESTS: &[RequestInfo] = &[
+    RequestInfo {
+        name: "CreateWindow",
+        opcode: 1,
+        fields: &[
+            field!("1", "1", "opcode"),
+            field!("1", "CARD8", "depth"),
+            field!("2", "8+n", "request length"),
+            field!("4", "WINDOW", "wid"),
+            field!("4", "WINDOW", "parent"),
+            field!("2", "INT16", "x"),
+            field!("2", "INT16", "y"),
+            field!("2", "CARD16", "width"),
+            field!("2", "CARD16", "height"),
+            field!("2", "CARD16", "border-width"),
+            field!("2", "", "class"),
+            field!("4", "VISUALID", "visual"),
+            field!("4", "BITMASK", "value-mask (has n bits set to 1)"),
+            field!("4n", "LISTofVALUE", "value-list"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "ChangeWindowAttributes",
+        opcode: 2,
+        fields: &[
+            field!("1", "2", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "3+n", "request length"),
+            field!("4", "WINDOW", "window"),
+            field!("4", "BITMASK", "value-mask (has n bits set to 1)"),
+            field!("4n", "LISTofVALUE", "value-list"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "GetWindowAttributes",
+        opcode: 3,
+        fields: &[
+            field!("1", "3", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "2", "request length"),
+            field!("4", "WINDOW", "window"),
+        ],
+        reply: Some("GetWindowAttributes"),
+    },
+    RequestInfo {
+        name: "DestroyWindow",
+        opcode: 4,
+        fields: &[
+            field!("1", "4", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "2", "request length"),
+            field!("4", "WINDOW", "window"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "DestroySubwindows",
+        opcode: 5,
+        fields: &[
+            field!("1", "5", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "2", "request length"),
+            field!("4", "WINDOW", "window"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "ChangeSaveSet",
+        opcode: 6,
+        fields: &[
+            field!("1", "6", "opcode"),
+            field!("1", "", "mode"),
+            field!("2", "2", "request length"),
+            field!("4", "WINDOW", "window"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "ReparentWindow",
+        opcode: 7,
+        fields: &[
+            field!("1", "7", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "4", "request length"),
+            field!("4", "WINDOW", "window"),
+            field!("4", "WINDOW", "parent"),
+            field!("2", "INT16", "x"),
+            field!("2", "INT16", "y"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "MapWindow",
+        opcode: 8,
+        fields: &[
+            field!("1", "8", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "2", "request length"),
+            field!("4", "WINDOW", "window"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "MapSubwindows",
+        opcode: 9,
+        fields: &[
+            field!("1", "9", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "2", "request length"),
+            field!("4", "WINDOW", "window"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "UnmapWindow",
+        opcode: 10,
+        fields: &[
+            field!("1", "10", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "2", "request length"),
+            field!("4", "WINDOW", "window"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "UnmapSubwindows",
+        opcode: 11,
+        fields: &[
+            field!("1", "11", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "2", "request length"),
+            field!("4", "WINDOW", "window"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "ConfigureWindow",
+        opcode: 12,
+        fields: &[
+            field!("1", "12", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "3+n", "request length"),
+            field!("4", "WINDOW", "window"),
+            field!("2", "BITMASK", "value-mask (has n bits set to 1)"),
+            field!("2", "", "unused"),
+            field!("4n", "LISTofVALUE", "value-list"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "CirculateWindow",
+        opcode: 13,
+        fields: &[
+            field!("1", "13", "opcode"),
+            field!("1", "", "direction"),
+            field!("2", "2", "request length"),
+            field!("4", "WINDOW", "window"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "GetGeometry",
+        opcode: 14,
+        fields: &[
+            field!("1", "14", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "2", "request length"),
+            field!("4", "DRAWABLE", "drawable"),
+        ],
+        reply: Some("GetGeometry"),
+    },
+    RequestInfo {
+        name: "QueryTree",
+        opcode: 15,
+        fields: &[
+            field!("1", "15", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "2", "request length"),
+            field!("4", "WINDOW", "window"),
+        ],
+        reply: Some("QueryTree"),
+    },
+    RequestInfo {
+        name: "InternAtom",
+        opcode: 16,
+        fields: &[
+            field!("1", "16", "opcode"),
+            field!("1", "BOOL", "only-if-exists"),
+            field!("2", "2+(n+p)/4", "request length"),
+            field!("2", "n", "length of name"),
+            field!("2", "", "unused"),
+            field!("n", "STRING8", "name"),
+            field!("p", "", "unused, p=pad(n)"),
+        ],
+        reply: Some("InternAtom"),
+    },
+    RequestInfo {
+        name: "GetAtomName",
+        opcode: 17,
+        fields: &[
+            field!("1", "17", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "2", "request length"),
+            field!("4", "ATOM", "atom"),
+        ],
+        reply: Some("GetAtomName"),
+    },
+    RequestInfo {
+        name: "ChangeProperty",
+        opcode: 18,
+        fields: &[
+            field!("1", "18", "opcode"),
+            field!("1", "", "mode"),
+            field!("2", "6+(n+p)/4", "request length"),
+            field!("4", "WINDOW", "window"),
+            field!("4", "ATOM", "property"),
+            field!("4", "ATOM", "type"),
+            field!("1", "CARD8", "format"),
+            field!("3", "", "unused"),
+            field!("4", "CARD32", "length of data in format units"),
+            field!("n", "LISTofBYTE", "data"),
+            field!("p", "", "unused, p=pad(n)"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "DeleteProperty",
+        opcode: 19,
+        fields: &[
+            field!("1", "19", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "3", "request length"),
+            field!("4", "WINDOW", "window"),
+            field!("4", "ATOM", "property"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "GetProperty",
+        opcode: 20,
+        fields: &[
+            field!("1", "20", "opcode"),
+            field!("1", "BOOL", "delete"),
+            field!("2", "6", "request length"),
+            field!("4", "WINDOW", "window"),
+            field!("4", "ATOM", "property"),
+            field!("4", "ATOM", "type"),
+            field!("4", "CARD32", "long-offset"),
+            field!("4", "CARD32", "long-length"),
+        ],
+        reply: Some("GetProperty"),
+    },
+    RequestInfo {
+        name: "ListProperties",
+        opcode: 21,
+        fields: &[
+            field!("1", "21", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "2", "request length"),
+            field!("4", "WINDOW", "window"),
+        ],
+        reply: Some("ListProperties"),
+    },
+    RequestInfo {
+        name: "SetSelectionOwner",
+        opcode: 22,
+        fields: &[
+            field!("1", "22", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "4", "request length"),
+            field!("4", "WINDOW", "owner"),
+            field!("4", "ATOM", "selection"),
+            field!("4", "TIMESTAMP", "time"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "GetSelectionOwner",
+        opcode: 23,
+        fields: &[
+            field!("1", "23", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "2", "request length"),
+            field!("4", "ATOM", "selection"),
+        ],
+        reply: Some("GetSelectionOwner"),
+    },
+    RequestInfo {
+        name: "ConvertSelection",
+        opcode: 24,
+        fields: &[
+            field!("1", "24", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "6", "request length"),
+            field!("4", "WINDOW", "requestor"),
+            field!("4", "ATOM", "selection"),
+            field!("4", "ATOM", "target"),
+            field!("4", "ATOM", "property"),
+            field!("4", "TIMESTAMP", "time"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "SendEvent",
+        opcode: 25,
+        fields: &[
+            field!("1", "25", "opcode"),
+            field!("1", "BOOL", "propagate"),
+            field!("2", "11", "request length"),
+            field!("4", "WINDOW", "destination"),
+            field!("4", "SETofEVENT", "event-mask"),
+            field!("32", "", "event"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "GrabPointer",
+        opcode: 26,
+        fields: &[
+            field!("1", "26", "opcode"),
+            field!("1", "BOOL", "owner-events"),
+            field!("2", "6", "request length"),
+            field!("4", "WINDOW", "grab-window"),
+            field!("2", "SETofPOINTEREVENT", "event-mask"),
+            field!("1", "", "pointer-mode"),
+            field!("1", "", "keyboard-mode"),
+            field!("4", "WINDOW", "confine-to"),
+            field!("4", "CURSOR", "cursor"),
+            field!("4", "TIMESTAMP", "time"),
+        ],
+        reply: Some("GrabPointer"),
+    },
+    RequestInfo {
+        name: "UngrabPointer",
+        opcode: 27,
+        fields: &[
+            field!("1", "27", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "2", "request length"),
+            field!("4", "TIMESTAMP", "time"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "GrabButton",
+        opcode: 28,
+        fields: &[
+            field!("1", "28", "opcode"),
+            field!("1", "BOOL", "owner-events"),
+            field!("2", "6", "request length"),
+            field!("4", "WINDOW", "grab-window"),
+            field!("2", "SETofPOINTEREVENT", "event-mask"),
+            field!("1", "", "pointer-mode"),
+            field!("1", "", "keyboard-mode"),
+            field!("4", "WINDOW", "confine-to"),
+            field!("4", "CURSOR", "cursor"),
+            field!("1", "BUTTON", "button"),
+            field!("1", "", "unused"),
+            field!("2", "SETofKEYMASK", "modifiers"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "UngrabButton",
+        opcode: 29,
+        fields: &[
+            field!("1", "29", "opcode"),
+            field!("1", "BUTTON", "button"),
+            field!("2", "3", "request length"),
+            field!("4", "WINDOW", "grab-window"),
+            field!("2", "SETofKEYMASK", "modifiers"),
+            field!("2", "", "unused"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "ChangeActivePointerGrab",
+        opcode: 30,
+        fields: &[
+            field!("1", "30", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "4", "request length"),
+            field!("4", "CURSOR", "cursor"),
+            field!("4", "TIMESTAMP", "time"),
+            field!("2", "SETofPOINTEREVENT", "event-mask"),
+            field!("2", "", "unused"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "GrabKeyboard",
+        opcode: 31,
+        fields: &[
+            field!("1", "31", "opcode"),
+            field!("1", "BOOL", "owner-events"),
+            field!("2", "4", "request length"),
+            field!("4", "WINDOW", "grab-window"),
+            field!("4", "TIMESTAMP", "time"),
+            field!("1", "", "pointer-mode"),
+            field!("1", "", "keyboard-mode"),
+            field!("2", "", "unused"),
+        ],
+        reply: Some("GrabKeyboard"),
+    },
+    RequestInfo {
+        name: "UngrabKeyboard",
+        opcode: 32,
+        fields: &[
+            field!("1", "32", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "2", "request length"),
+            field!("4", "TIMESTAMP", "time"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "GrabKey",
+        opcode: 33,
+        fields: &[
+            field!("1", "33", "opcode"),
+            field!("1", "BOOL", "owner-events"),
+            field!("2", "4", "request length"),
+            field!("4", "WINDOW", "grab-window"),
+            field!("2", "SETofKEYMASK", "modifiers"),
+            field!("1", "KEYCODE", "key"),
+            field!("1", "", "pointer-mode"),
+            field!("1", "", "keyboard-mode"),
+            field!("3", "", "unused"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "UngrabKey",
+        opcode: 34,
+        fields: &[
+            field!("1", "34", "opcode"),
+            field!("1", "KEYCODE", "key"),
+            field!("2", "3", "request length"),
+            field!("4", "WINDOW", "grab-window"),
+            field!("2", "SETofKEYMASK", "modifiers"),
+            field!("2", "", "unused"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "AllowEvents",
+        opcode: 35,
+        fields: &[
+            field!("1", "35", "opcode"),
+            field!("1", "", "mode"),
+            field!("2", "2", "request length"),
+            field!("4", "TIMESTAMP", "time"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "GrabServer",
+        opcode: 36,
+        fields: &[
+            field!("1", "36", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "1", "request length"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "UngrabServer",
+        opcode: 37,
+        fields: &[
+            field!("1", "37", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "1", "request length"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "QueryPointer",
+        opcode: 38,
+        fields: &[
+            field!("1", "38", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "2", "request length"),
+            field!("4", "WINDOW", "window"),
+        ],
+        reply: Some("QueryPointer"),
+    },
+    RequestInfo {
+        name: "GetMotionEvents",
+        opcode: 39,
+        fields: &[
+            field!("1", "39", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "4", "request length"),
+            field!("4", "WINDOW", "window"),
+            field!("4", "TIMESTAMP", "start"),
+            field!("4", "TIMESTAMP", "stop"),
+        ],
+        reply: Some("GetMotionEvents"),
+    },
+    RequestInfo {
+        name: "TranslateCoordinates",
+        opcode: 40,
+        fields: &[
+            field!("1", "40", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "4", "request length"),
+            field!("4", "WINDOW", "src-window"),
+            field!("4", "WINDOW", "dst-window"),
+            field!("2", "INT16", "src-x"),
+            field!("2", "INT16", "src-y"),
+        ],
+        reply: Some("TranslateCoordinates"),
+    },
+    RequestInfo {
+        name: "WarpPointer",
+        opcode: 41,
+        fields: &[
+            field!("1", "41", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "6", "request length"),
+            field!("4", "WINDOW", "src-window"),
+            field!("4", "WINDOW", "dst-window"),
+            field!("2", "INT16", "src-x"),
+            field!("2", "INT16", "src-y"),
+            field!("2", "CARD16", "src-width"),
+            field!("2", "CARD16", "src-height"),
+            field!("2", "INT16", "dst-x"),
+            field!("2", "INT16", "dst-y"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "SetInputFocus",
+        opcode: 42,
+        fields: &[
+            field!("1", "42", "opcode"),
+            field!("1", "", "revert-to"),
+            field!("2", "3", "request length"),
+            field!("4", "WINDOW", "focus"),
+            field!("4", "TIMESTAMP", "time"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "GetInputFocus",
+        opcode: 43,
+        fields: &[
+            field!("1", "43", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "1", "request length"),
+        ],
+        reply: Some("GetInputFocus"),
+    },
+    RequestInfo {
+        name: "QueryKeymap",
+        opcode: 44,
+        fields: &[
+            field!("1", "44", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "1", "request length"),
+        ],
+        reply: Some("QueryKeymap"),
+    },
+    RequestInfo {
+        name: "OpenFont",
+        opcode: 45,
+        fields: &[
+            field!("1", "45", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "3+(n+p)/4", "request length"),
+            field!("4", "FONT", "fid"),
+            field!("2", "n", "length of name"),
+            field!("2", "", "unused"),
+            field!("n", "STRING8", "name"),
+            field!("p", "", "unused, p=pad(n)"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "CloseFont",
+        opcode: 46,
+        fields: &[
+            field!("1", "46", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "2", "request length"),
+            field!("4", "FONT", "font"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "QueryFont",
+        opcode: 47,
+        fields: &[
+            field!("1", "47", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "2", "request length"),
+            field!("4", "FONTABLE", "font"),
+        ],
+        reply: Some("QueryFont"),
+    },
+    RequestInfo {
+        name: "QueryTextExtents",
+        opcode: 48,
+        fields: &[
+            field!("1", "48", "opcode"),
+            field!("1", "BOOL", "odd length, True if p = 2"),
+            field!("2", "2+(2n+p)/4", "request length"),
+            field!("4", "FONTABLE", "font"),
+            field!("2n", "STRING16", "string"),
+            field!("p", "", "unused, p=pad(2n)"),
+        ],
+        reply: Some("QueryTextExtents"),
+    },
+    RequestInfo {
+        name: "ListFonts",
+        opcode: 49,
+        fields: &[
+            field!("1", "49", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "2+(n+p)/4", "request length"),
+            field!("2", "CARD16", "max-names"),
+            field!("2", "n", "length of pattern"),
+            field!("n", "STRING8", "pattern"),
+            field!("p", "", "unused, p=pad(n)"),
+        ],
+        reply: Some("ListFonts"),
+    },
+    RequestInfo {
+        name: "ListFontsWithInfo",
+        opcode: 50,
+        fields: &[
+            field!("1", "50", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "2+(n+p)/4", "request length"),
+            field!("2", "CARD16", "max-names"),
+            field!("2", "n", "length of pattern"),
+            field!("n", "STRING8", "pattern"),
+            field!("p", "", "unused, p=pad(n)"),
+        ],
+        reply: Some("ListFontsWithInfo"),
+    },
+    RequestInfo {
+        name: "SetFontPath",
+        opcode: 51,
+        fields: &[
+            field!("1", "51", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "2+(n+p)/4", "request length"),
+            field!("2", "CARD16", "number of STRs in path"),
+            field!("2", "", "unused"),
+            field!("n", "LISTofSTR", "path"),
+            field!("p", "", "unused, p=pad(n)"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "GetFontPath",
+        opcode: 52,
+        fields: &[
+            field!("1", "52", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "1", "request length"),
+        ],
+        reply: Some("GetFontPath"),
+    },
+    RequestInfo {
+        name: "CreatePixmap",
+        opcode: 53,
+        fields: &[
+            field!("1", "53", "opcode"),
+            field!("1", "CARD8", "depth"),
+            field!("2", "4", "request length"),
+            field!("4", "PIXMAP", "pid"),
+            field!("4", "DRAWABLE", "drawable"),
+            field!("2", "CARD16", "width"),
+            field!("2", "CARD16", "height"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "FreePixmap",
+        opcode: 54,
+        fields: &[
+            field!("1", "54", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "2", "request length"),
+            field!("4", "PIXMAP", "pixmap"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "CreateGC",
+        opcode: 55,
+        fields: &[
+            field!("1", "55", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "4+n", "request length"),
+            field!("4", "GCONTEXT", "cid"),
+            field!("4", "DRAWABLE", "drawable"),
+            field!("4", "BITMASK", "value-mask (has n bits set to 1)"),
+            field!("4n", "LISTofVALUE", "value-list"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "ChangeGC",
+        opcode: 56,
+        fields: &[
+            field!("1", "56", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "3+n", "request length"),
+            field!("4", "GCONTEXT", "gc"),
+            field!("4", "BITMASK", "value-mask (has n bits set to 1)"),
+            field!("4n", "LISTofVALUE", "value-list"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "CopyGC",
+        opcode: 57,
+        fields: &[
+            field!("1", "57", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "4", "request length"),
+            field!("4", "GCONTEXT", "src-gc"),
+            field!("4", "GCONTEXT", "dst-gc"),
+            field!("4", "BITMASK", "value-mask"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "SetDashes",
+        opcode: 58,
+        fields: &[
+            field!("1", "58", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "3+(n+p)/4", "request length"),
+            field!("4", "GCONTEXT", "gc"),
+            field!("2", "CARD16", "dash-offset"),
+            field!("2", "n", "length of dashes"),
+            field!("n", "LISTofCARD8", "dashes"),
+            field!("p", "", "unused, p=pad(n)"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "SetClipRectangles",
+        opcode: 59,
+        fields: &[
+            field!("1", "59", "opcode"),
+            field!("1", "", "ordering"),
+            field!("2", "3+2n", "request length"),
+            field!("4", "GCONTEXT", "gc"),
+            field!("2", "INT16", "clip-x-origin"),
+            field!("2", "INT16", "clip-y-origin"),
+            field!("8n", "LISTofRECTANGLE", "rectangles"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "FreeGC",
+        opcode: 60,
+        fields: &[
+            field!("1", "60", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "2", "request length"),
+            field!("4", "GCONTEXT", "gc"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "ClearArea",
+        opcode: 61,
+        fields: &[
+            field!("1", "61", "opcode"),
+            field!("1", "BOOL", "exposures"),
+            field!("2", "4", "request length"),
+            field!("4", "WINDOW", "window"),
+            field!("2", "INT16", "x"),
+            field!("2", "INT16", "y"),
+            field!("2", "CARD16", "width"),
+            field!("2", "CARD16", "height"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "CopyArea",
+        opcode: 62,
+        fields: &[
+            field!("1", "62", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "7", "request length"),
+            field!("4", "DRAWABLE", "src-drawable"),
+            field!("4", "DRAWABLE", "dst-drawable"),
+            field!("4", "GCONTEXT", "gc"),
+            field!("2", "INT16", "src-x"),
+            field!("2", "INT16", "src-y"),
+            field!("2", "INT16", "dst-x"),
+            field!("2", "INT16", "dst-y"),
+            field!("2", "CARD16", "width"),
+            field!("2", "CARD16", "height"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "CopyPlane",
+        opcode: 63,
+        fields: &[
+            field!("1", "63", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "8", "request length"),
+            field!("4", "DRAWABLE", "src-drawable"),
+            field!("4", "DRAWABLE", "dst-drawable"),
+            field!("4", "GCONTEXT", "gc"),
+            field!("2", "INT16", "src-x"),
+            field!("2", "INT16", "src-y"),
+            field!("2", "INT16", "dst-x"),
+            field!("2", "INT16", "dst-y"),
+            field!("2", "CARD16", "width"),
+            field!("2", "CARD16", "height"),
+            field!("4", "CARD32", "bit-plane"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "PolyPoint",
+        opcode: 64,
+        fields: &[
+            field!("1", "64", "opcode"),
+            field!("1", "", "coordinate-mode"),
+            field!("2", "3+n", "request length"),
+            field!("4", "DRAWABLE", "drawable"),
+            field!("4", "GCONTEXT", "gc"),
+            field!("4n", "LISTofPOINT", "points"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "PolyLine",
+        opcode: 65,
+        fields: &[
+            field!("1", "65", "opcode"),
+            field!("1", "", "coordinate-mode"),
+            field!("2", "3+n", "request length"),
+            field!("4", "DRAWABLE", "drawable"),
+            field!("4", "GCONTEXT", "gc"),
+            field!("4n", "LISTofPOINT", "points"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "PolySegment",
+        opcode: 66,
+        fields: &[
+            field!("1", "66", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "3+2n", "request length"),
+            field!("4", "DRAWABLE", "drawable"),
+            field!("4", "GCONTEXT", "gc"),
+            field!("8n", "LISTofSEGMENT", "segments"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "PolyRectangle",
+        opcode: 67,
+        fields: &[
+            field!("1", "67", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "3+2n", "request length"),
+            field!("4", "DRAWABLE", "drawable"),
+            field!("4", "GCONTEXT", "gc"),
+            field!("8n", "LISTofRECTANGLE", "rectangles"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "PolyArc",
+        opcode: 68,
+        fields: &[
+            field!("1", "68", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "3+3n", "request length"),
+            field!("4", "DRAWABLE", "drawable"),
+            field!("4", "GCONTEXT", "gc"),
+            field!("12n", "LISTofARC", "arcs"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "FillPoly",
+        opcode: 69,
+        fields: &[
+            field!("1", "69", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "4+n", "request length"),
+            field!("4", "DRAWABLE", "drawable"),
+            field!("4", "GCONTEXT", "gc"),
+            field!("1", "", "shape"),
+            field!("1", "", "coordinate-mode"),
+            field!("2", "", "unused"),
+            field!("4n", "LISTofPOINT", "points"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "PolyFillRectangle",
+        opcode: 70,
+        fields: &[
+            field!("1", "70", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "3+2n", "request length"),
+            field!("4", "DRAWABLE", "drawable"),
+            field!("4", "GCONTEXT", "gc"),
+            field!("8n", "LISTofRECTANGLE", "rectangles"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "PolyFillArc",
+        opcode: 71,
+        fields: &[
+            field!("1", "71", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "3+3n", "request length"),
+            field!("4", "DRAWABLE", "drawable"),
+            field!("4", "GCONTEXT", "gc"),
+            field!("12n", "LISTofARC", "arcs"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "PutImage",
+        opcode: 72,
+        fields: &[
+            field!("1", "72", "opcode"),
+            field!("1", "", "format"),
+            field!("2", "6+(n+p)/4", "request length"),
+            field!("4", "DRAWABLE", "drawable"),
+            field!("4", "GCONTEXT", "gc"),
+            field!("2", "CARD16", "width"),
+            field!("2", "CARD16", "height"),
+            field!("2", "INT16", "dst-x"),
+            field!("2", "INT16", "dst-y"),
+            field!("1", "CARD8", "left-pad"),
+            field!("1", "CARD8", "depth"),
+            field!("2", "", "unused"),
+            field!("n", "LISTofBYTE", "data"),
+            field!("p", "", "unused, p=pad(n)"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "GetImage",
+        opcode: 73,
+        fields: &[
+            field!("1", "73", "opcode"),
+            field!("1", "", "format"),
+            field!("2", "5", "request length"),
+            field!("4", "DRAWABLE", "drawable"),
+            field!("2", "INT16", "x"),
+            field!("2", "INT16", "y"),
+            field!("2", "CARD16", "width"),
+            field!("2", "CARD16", "height"),
+            field!("4", "CARD32", "plane-mask"),
+        ],
+        reply: Some("GetImage"),
+    },
+    RequestInfo {
+        name: "PolyText8",
+        opcode: 74,
+        fields: &[
+            field!("1", "74", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "4+(n+p)/4", "request length"),
+            field!("4", "DRAWABLE", "drawable"),
+            field!("4", "GCONTEXT", "gc"),
+            field!("2", "INT16", "x"),
+            field!("2", "INT16", "y"),
+            field!("n", "LISTofTEXTITEM8", "items"),
+            field!("p", "unused, p=pad(n)", "(p is always 0"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "PolyText16",
+        opcode: 75,
+        fields: &[
+            field!("1", "75", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "4+(n+p)/4", "request length"),
+            field!("4", "DRAWABLE", "drawable"),
+            field!("4", "GCONTEXT", "gc"),
+            field!("2", "INT16", "x"),
+            field!("2", "INT16", "y"),
+            field!("n", "LISTofTEXTITEM16", "items"),
+            field!("p", "unused, p=pad(n)", "(p must be 0 or"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "ImageText8",
+        opcode: 76,
+        fields: &[
+            field!("1", "76", "opcode"),
+            field!("1", "n", "length of string"),
+            field!("2", "4+(n+p)/4", "request length"),
+            field!("4", "DRAWABLE", "drawable"),
+            field!("4", "GCONTEXT", "gc"),
+            field!("2", "INT16", "x"),
+            field!("2", "INT16", "y"),
+            field!("n", "STRING8", "string"),
+            field!("p", "", "unused, p=pad(n)"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "ImageText16",
+        opcode: 77,
+        fields: &[
+            field!("1", "77", "opcode"),
+            field!("1", "n", "number of CHAR2Bs in string"),
+            field!("2", "4+(2n+p)/4", "request length"),
+            field!("4", "DRAWABLE", "drawable"),
+            field!("4", "GCONTEXT", "gc"),
+            field!("2", "INT16", "x"),
+            field!("2", "INT16", "y"),
+            field!("2n", "STRING16", "string"),
+            field!("p", "", "unused, p=pad(2n)"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "CreateColormap",
+        opcode: 78,
+        fields: &[
+            field!("1", "78", "opcode"),
+            field!("1", "", "alloc"),
+            field!("2", "4", "request length"),
+            field!("4", "COLORMAP", "mid"),
+            field!("4", "WINDOW", "window"),
+            field!("4", "VISUALID", "visual"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "FreeColormap",
+        opcode: 79,
+        fields: &[
+            field!("1", "79", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "2", "request length"),
+            field!("4", "COLORMAP", "cmap"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "CopyColormapAndFree",
+        opcode: 80,
+        fields: &[
+            field!("1", "80", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "3", "request length"),
+            field!("4", "COLORMAP", "mid"),
+            field!("4", "COLORMAP", "src-cmap"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "InstallColormap",
+        opcode: 81,
+        fields: &[
+            field!("1", "81", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "2", "request length"),
+            field!("4", "COLORMAP", "cmap"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "UninstallColormap",
+        opcode: 82,
+        fields: &[
+            field!("1", "82", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "2", "request length"),
+            field!("4", "COLORMAP", "cmap"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "ListInstalledColormaps",
+        opcode: 83,
+        fields: &[
+            field!("1", "83", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "2", "request length"),
+            field!("4", "WINDOW", "window"),
+        ],
+        reply: Some("ListInstalledColormaps"),
+    },
+    RequestInfo {
+        name: "AllocColor",
+        opcode: 84,
+        fields: &[
+            field!("1", "84", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "4", "request length"),
+            field!("4", "COLORMAP", "cmap"),
+            field!("2", "CARD16", "red"),
+            field!("2", "CARD16", "green"),
+            field!("2", "CARD16", "blue"),
+            field!("2", "", "unused"),
+        ],
+        reply: Some("AllocColor"),
+    },
+    RequestInfo {
+        name: "AllocNamedColor",
+        opcode: 85,
+        fields: &[
+            field!("1", "85", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "3+(n+p)/4", "request length"),
+            field!("4", "COLORMAP", "cmap"),
+            field!("2", "n", "length of name"),
+            field!("2", "", "unused"),
+            field!("n", "STRING8", "name"),
+            field!("p", "", "unused, p=pad(n)"),
+        ],
+        reply: Some("AllocNamedColor"),
+    },
+    RequestInfo {
+        name: "AllocColorCells",
+        opcode: 86,
+        fields: &[
+            field!("1", "86", "opcode"),
+            field!("1", "BOOL", "contiguous"),
+            field!("2", "3", "request length"),
+            field!("4", "COLORMAP", "cmap"),
+            field!("2", "CARD16", "colors"),
+            field!("2", "CARD16", "planes"),
+        ],
+        reply: Some("AllocColorCells"),
+    },
+    RequestInfo {
+        name: "AllocColorPlanes",
+        opcode: 87,
+        fields: &[
+            field!("1", "87", "opcode"),
+            field!("1", "BOOL", "contiguous"),
+            field!("2", "4", "request length"),
+            field!("4", "COLORMAP", "cmap"),
+            field!("2", "CARD16", "colors"),
+            field!("2", "CARD16", "reds"),
+            field!("2", "CARD16", "greens"),
+            field!("2", "CARD16", "blues"),
+        ],
+        reply: Some("AllocColorPlanes"),
+    },
+    RequestInfo {
+        name: "FreeColors",
+        opcode: 88,
+        fields: &[
+            field!("1", "88", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "3+n", "request length"),
+            field!("4", "COLORMAP", "cmap"),
+            field!("4", "CARD32", "plane-mask"),
+            field!("4n", "LISTofCARD32", "pixels"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "StoreColors",
+        opcode: 89,
+        fields: &[
+            field!("1", "89", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "2+3n", "request length"),
+            field!("4", "COLORMAP", "cmap"),
+            field!("12n", "LISTofCOLORITEM", "items"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "StoreNamedColor",
+        opcode: 90,
+        fields: &[
+            field!("1", "90", "opcode"),
+            field!("1", "", "do-red, do-green, do-blue"),
+            field!("2", "4+(n+p)/4", "request length"),
+            field!("4", "COLORMAP", "cmap"),
+            field!("4", "CARD32", "pixel"),
+            field!("2", "n", "length of name"),
+            field!("2", "", "unused"),
+            field!("n", "STRING8", "name"),
+            field!("p", "", "unused, p=pad(n)"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "QueryColors",
+        opcode: 91,
+        fields: &[
+            field!("1", "91", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "2+n", "request length"),
+            field!("4", "COLORMAP", "cmap"),
+            field!("4n", "LISTofCARD32", "pixels"),
+        ],
+        reply: Some("QueryColors"),
+    },
+    RequestInfo {
+        name: "LookupColor",
+        opcode: 92,
+        fields: &[
+            field!("1", "92", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "3+(n+p)/4", "request length"),
+            field!("4", "COLORMAP", "cmap"),
+            field!("2", "n", "length of name"),
+            field!("2", "", "unused"),
+            field!("n", "STRING8", "name"),
+            field!("p", "", "unused, p=pad(n)"),
+        ],
+        reply: Some("LookupColor"),
+    },
+    RequestInfo {
+        name: "CreateCursor",
+        opcode: 93,
+        fields: &[
+            field!("1", "93", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "8", "request length"),
+            field!("4", "CURSOR", "cid"),
+            field!("4", "PIXMAP", "source"),
+            field!("4", "PIXMAP", "mask"),
+            field!("2", "CARD16", "fore-red"),
+            field!("2", "CARD16", "fore-green"),
+            field!("2", "CARD16", "fore-blue"),
+            field!("2", "CARD16", "back-red"),
+            field!("2", "CARD16", "back-green"),
+            field!("2", "CARD16", "back-blue"),
+            field!("2", "CARD16", "x"),
+            field!("2", "CARD16", "y"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "CreateGlyphCursor",
+        opcode: 94,
+        fields: &[
+            field!("1", "94", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "8", "request length"),
+            field!("4", "CURSOR", "cid"),
+            field!("4", "FONT", "source-font"),
+            field!("4", "FONT", "mask-font"),
+            field!("2", "CARD16", "source-char"),
+            field!("2", "CARD16", "mask-char"),
+            field!("2", "CARD16", "fore-red"),
+            field!("2", "CARD16", "fore-green"),
+            field!("2", "CARD16", "fore-blue"),
+            field!("2", "CARD16", "back-red"),
+            field!("2", "CARD16", "back-green"),
+            field!("2", "CARD16", "back-blue"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "FreeCursor",
+        opcode: 95,
+        fields: &[
+            field!("1", "95", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "2", "request length"),
+            field!("4", "CURSOR", "cursor"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "RecolorCursor",
+        opcode: 96,
+        fields: &[
+            field!("1", "96", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "5", "request length"),
+            field!("4", "CURSOR", "cursor"),
+            field!("2", "CARD16", "fore-red"),
+            field!("2", "CARD16", "fore-green"),
+            field!("2", "CARD16", "fore-blue"),
+            field!("2", "CARD16", "back-red"),
+            field!("2", "CARD16", "back-green"),
+            field!("2", "CARD16", "back-blue"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "QueryBestSize",
+        opcode: 97,
+        fields: &[
+            field!("1", "97", "opcode"),
+            field!("1", "", "class"),
+            field!("2", "3", "request length"),
+            field!("4", "DRAWABLE", "drawable"),
+            field!("2", "CARD16", "width"),
+            field!("2", "CARD16", "height"),
+        ],
+        reply: Some("QueryBestSize"),
+    },
+    RequestInfo {
+        name: "QueryExtension",
+        opcode: 98,
+        fields: &[
+            field!("1", "98", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "2+(n+p)/4", "request length"),
+            field!("2", "n", "length of name"),
+            field!("2", "", "unused"),
+            field!("n", "STRING8", "name"),
+            field!("p", "", "unused, p=pad(n)"),
+        ],
+        reply: Some("QueryExtension"),
+    },
+    RequestInfo {
+        name: "ListExtensions",
+        opcode: 99,
+        fields: &[
+            field!("1", "99", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "1", "request length"),
+        ],
+        reply: Some("ListExtensions"),
+    },
+    RequestInfo {
+        name: "ChangeKeyboardMapping",
+        opcode: 100,
+        fields: &[
+            field!("1", "100", "opcode"),
+            field!("1", "n", "keycode-count"),
+            field!("2", "2+nm", "request length"),
+            field!("1", "KEYCODE", "first-keycode"),
+            field!("1", "m", "keysyms-per-keycode"),
+            field!("2", "", "unused"),
+            field!("4nm", "LISTofKEYSYM", "keysyms"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "GetKeyboardMapping",
+        opcode: 101,
+        fields: &[
+            field!("1", "101", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "2", "request length"),
+            field!("1", "KEYCODE", "first-keycode"),
+            field!("1", "m", "count"),
+            field!("2", "", "unused"),
+        ],
+        reply: Some("GetKeyboardMapping"),
+    },
+    RequestInfo {
+        name: "ChangeKeyboardControl",
+        opcode: 102,
+        fields: &[
+            field!("1", "102", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "2+n", "request length"),
+            field!("4", "BITMASK", "value-mask (has n bits set to 1)"),
+            field!("4n", "LISTofVALUE", "value-list"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "GetKeyboardControl",
+        opcode: 103,
+        fields: &[
+            field!("1", "103", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "1", "request length"),
+        ],
+        reply: Some("GetKeyboardControl"),
+    },
+    RequestInfo {
+        name: "Bell",
+        opcode: 104,
+        fields: &[
+            field!("1", "104", "opcode"),
+            field!("1", "INT8", "percent"),
+            field!("2", "1", "request length"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "ChangePointerControl",
+        opcode: 105,
+        fields: &[
+            field!("1", "105", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "3", "request length"),
+            field!("2", "INT16", "acceleration-numerator"),
+            field!("2", "INT16", "acceleration-denominator"),
+            field!("2", "INT16", "threshold"),
+            field!("1", "BOOL", "do-acceleration"),
+            field!("1", "BOOL", "do-threshold"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "GetPointerControl",
+        opcode: 106,
+        fields: &[
+            field!("1", "106", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "1", "request length"),
+        ],
+        reply: Some("GetPointerControl"),
+    },
+    RequestInfo {
+        name: "SetScreenSaver",
+        opcode: 107,
+        fields: &[
+            field!("1", "107", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "3", "request length"),
+            field!("2", "INT16", "timeout"),
+            field!("2", "INT16", "interval"),
+            field!("1", "", "prefer-blanking"),
+            field!("1", "", "allow-exposures"),
+            field!("2", "", "unused"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "GetScreenSaver",
+        opcode: 108,
+        fields: &[
+            field!("1", "108", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "1", "request length"),
+        ],
+        reply: Some("GetScreenSaver"),
+    },
+    RequestInfo {
+        name: "ChangeHosts",
+        opcode: 109,
+        fields: &[
+            field!("1", "109", "opcode"),
+            field!("1", "", "mode"),
+            field!("2", "2+(n+p)/4", "request length"),
+            field!("1", "", "family"),
+            field!("1", "", "unused"),
+            field!("2", "n", "length of address"),
+            field!("n", "LISTofCARD8", "address"),
+            field!("p", "", "unused, p=pad(n)"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "ListHosts",
+        opcode: 110,
+        fields: &[
+            field!("1", "110", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "1", "request length"),
+        ],
+        reply: Some("ListHosts"),
+    },
+    RequestInfo {
+        name: "SetAccessControl",
+        opcode: 111,
+        fields: &[
+            field!("1", "111", "opcode"),
+            field!("1", "", "mode"),
+            field!("2", "1", "request length"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "SetCloseDownMode",
+        opcode: 112,
+        fields: &[
+            field!("1", "112", "opcode"),
+            field!("1", "", "mode"),
+            field!("2", "1", "request length"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "KillClient",
+        opcode: 113,
+        fields: &[
+            field!("1", "113", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "2", "request length"),
+            field!("4", "CARD32", "resource"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "RotateProperties",
+        opcode: 114,
+        fields: &[
+            field!("1", "114", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "3+n", "request length"),
+            field!("4", "WINDOW", "window"),
+            field!("2", "n", "number of properties"),
+            field!("2", "INT16", "delta"),
+            field!("4n", "LISTofATOM", "properties"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "ForceScreenSaver",
+        opcode: 115,
+        fields: &[
+            field!("1", "115", "opcode"),
+            field!("1", "", "mode"),
+            field!("2", "1", "request length"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "SetPointerMapping",
+        opcode: 116,
+        fields: &[
+            field!("1", "116", "opcode"),
+            field!("1", "n", "length of map"),
+            field!("2", "1+(n+p)/4", "request length"),
+            field!("n", "LISTofCARD8", "map"),
+            field!("p", "", "unused, p=pad(n)"),
+        ],
+        reply: Some("SetPointerMapping"),
+    },
+    RequestInfo {
+        name: "GetPointerMapping",
+        opcode: 117,
+        fields: &[
+            field!("1", "117", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "1", "request length"),
+        ],
+        reply: Some("GetPointerMapping"),
+    },
+    RequestInfo {
+        name: "SetModifierMapping",
+        opcode: 118,
+        fields: &[
+            field!("1", "118", "opcode"),
+            field!("1", "n", "keycodes-per-modifier"),
+            field!("2", "1+2n", "request length"),
+            field!("8n", "LISTofKEYCODE", "keycodes"),
+        ],
+        reply: Some("SetModifierMapping"),
+    },
+    RequestInfo {
+        name: "GetModifierMapping",
+        opcode: 119,
+        fields: &[
+            field!("1", "119", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "1", "request length"),
+        ],
+        reply: Some("GetModifierMapping"),
+    },
+    RequestInfo {
+        name: "NoOperation",
+        opcode: 127,
+        fields: &[
+            field!("1", "127", "opcode"),
+            field!("1", "", "unused"),
+            field!("2", "1+n", "request length"),
+            field!("4n", "", "unused"),
+        ],
+        reply: None,
+    },
+];
+
+pub const MIT_SHM_REQUESTS: &[RequestInfo] = &[
+    RequestInfo {
+        name: "QueryVersion",
+        opcode: 0,
+        fields: &[
+            field!("1", "CARD8", "major opcode"),
+            field!("1", "0", "shm opcode"),
+            field!("2", "1", "request length"),
+        ],
+        reply: Some("QueryVersion"),
+    },
+    RequestInfo {
+        name: "Attach",
+        opcode: 1,
+        fields: &[
+            field!("1", "CARD8", "major opcode"),
+            field!("1", "1", "shm opcode"),
+            field!("2", "4", "request length"),
+            field!("4", "SEG", "shmseg"),
+            field!("4", "CARD32", "shmid"),
+            field!("1", "BOOL", "read-only"),
+            field!("3", "", "unused"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "Detach",
+        opcode: 2,
+        fields: &[
+            field!("1", "CARD8", "major opcode"),
+            field!("1", "2", "shm opcode"),
+            field!("2", "2", "request length"),
+            field!("4", "SEG", "shmseg"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "PutImage",
+        opcode: 3,
+        fields: &[
+            field!("1", "CARD8", "major opcode"),
+            field!("1", "3", "shm opcode"),
+            field!("2", "10", "request length"),
+            field!("4", "DRAWABLE", "drawable"),
+            field!("4", "GCONTEXT", "gc"),
+            field!("2", "CARD16", "total-width"),
+            field!("2", "CARD16", "total-height"),
+            field!("2", "CARD16", "src-x"),
+            field!("2", "CARD16", "src-y"),
+            field!("2", "CARD16", "src-width"),
+            field!("2", "CARD16", "src-height"),
+            field!("2", "INT16", "dst-x"),
+            field!("2", "INT16", "dst-y"),
+            field!("1", "CARD8", "depth"),
+            field!("1", "CARD8", "format"),
+            field!("1", "BOOL", "send-event"),
+            field!("1", "", "unused"),
+            field!("4", "SEG", "shmseg"),
+            field!("4", "CARD32", "offset"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "GetImage",
+        opcode: 4,
+        fields: &[
+            field!("1", "CARD8", "major opcode"),
+            field!("1", "4", "shm opcode"),
+            field!("2", "8", "request length"),
+            field!("4", "DRAWABLE", "drawable"),
+            field!("2", "INT16", "x"),
+            field!("2", "INT16", "y"),
+            field!("2", "CARD16", "width"),
+            field!("2", "CARD16", "height"),
+            field!("4", "CARD32", "plane-mask"),
+            field!("1", "CARD8", "format"),
+            field!("3", "", "unused"),
+            field!("4", "SEG", "shmseg"),
+            field!("4", "CARD32", "offset"),
+        ],
+        reply: Some("GetImage"),
+    },
+    RequestInfo {
+        name: "CreatePixmap",
+        opcode: 5,
+        fields: &[
+            field!("1", "CARD8", "major opcode"),
+            field!("1", "5", "shm opcode"),
+            field!("2", "7", "request length"),
+            field!("4", "PIXMAP", "pid"),
+            field!("4", "DRAWABLE", "drawable"),
+            field!("2", "CARD16", "width"),
+            field!("2", "CARD16", "height"),
+            field!("1", "CARD8", "depth"),
+            field!("3", "", "unused"),
+            field!("4", "SEG", "shmseg"),
+            field!("4", "CARD32", "offset"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "AttachFd",
+        opcode: 6,
+        fields: &[
+            field!("1", "CARD8", "major opcode"),
+            field!("1", "6", "shm opcode"),
+            field!("2", "3", "request length"),
+            field!("4", "SEG", "shmseg"),
+            field!("1", "BOOL", "read-only"),
+            field!("3", "", "unused"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "CreateSegment",
+        opcode: 7,
+        fields: &[
+            field!("1", "CARD8", "major opcode"),
+            field!("1", "7", "shm opcode"),
+            field!("2", "4", "request length"),
+            field!("4", "SEG", "shmseg"),
+            field!("4", "CARD32", "size"),
+            field!("1", "BOOL", "read-only"),
+            field!("3", "", "unused"),
+        ],
+        reply: Some("CreateSegment"),
+    },
+];
+
+pub const PRESENT_REQUESTS: &[RequestInfo] = &[
+    RequestInfo {
+        name: "QueryVersion",
+        opcode: 0,
+        fields: &[
+            field!("1", "CARD8", "major opcode"),
+            field!("1", "0", "Present opcode"),
+            field!("2", "3", "length"),
+            field!("4", "CARD32", "major version"),
+            field!("4", "CARD32", "minor version"),
+        ],
+        reply: Some("QueryVersion"),
+    },
+    RequestInfo {
+        name: "NotifyMsc",
+        opcode: 2,
+        fields: &[
+            field!("1", "CARD8", "major opcode"),
+            field!("1", "2", "Present opcode"),
+            field!("2", "10", "length"),
+            field!("4", "WINDOW", "window"),
+            field!("4", "CARD32", "serial"),
+            field!("4", "", "padding"),
+            field!("8", "CARD64", "target-msc"),
+            field!("8", "CARD64", "divisor"),
+            field!("8", "CARD64", "remainder"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "SelectInput",
+        opcode: 3,
+        fields: &[
+            field!("1", "CARD8", "major opcode"),
+            field!("1", "3", "Present opcode"),
+            field!("2", "4", "length"),
+            field!("4", "EVENTID", "event id"),
+            field!("4", "WINDOW", "window"),
+            field!("4", "SETofPRESENTEVENT", "event-mask"),
+        ],
+        reply: None,
+    },
+];
+
+pub const RANDR_REQUESTS: &[RequestInfo] = &[
+    RequestInfo {
+        name: "QueryVersion",
+        opcode: 0,
+        fields: &[
+            field!("1", "CARD8", "major opcode"),
+            field!("1", "0", "RandR opcode"),
+            field!("2", "3", "length"),
+            field!("4", "CARD32", "major version"),
+            field!("4", "CARD32", "minor version"),
+        ],
+        reply: Some("QueryVersion"),
+    },
+    RequestInfo {
+        name: "SetScreenConfig",
+        opcode: 2,
+        fields: &[
+            field!("1", "CARD8", "major opcode"),
+            field!("1", "2", "RandR opcode"),
+            field!("2", "6", "length"),
+            field!("4", "WINDOW", "window on screen to be configured"),
+            field!("4", "TIMESTAMP", "timestamp"),
+            field!("4", "TIMESTAMP", "config timestamp"),
+            field!("2", "SIZEID", "size index"),
+            field!("2", "ROTATION", "rotation/reflection"),
+            field!("2", "CARD16", "refresh rate (1.1 only)"),
+            field!("2", "CARD16", "pad"),
+        ],
+        reply: Some("SetScreenConfig"),
+    },
+    RequestInfo {
+        name: "SelectInput",
+        opcode: 4,
+        fields: &[
+            field!("1", "CARD8", "major opcode"),
+            field!("1", "4", "RandR opcode"),
+            field!("2", "3", "length"),
+            field!("4", "WINDOW", "window"),
+            field!("2", "SETofRRSELECTMASK", "enable"),
+            field!("2", "CARD16", "pad"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "GetScreenInfo",
+        opcode: 5,
+        fields: &[
+            field!("1", "CARD8", "major opcode"),
+            field!("1", "5", "RandR opcode"),
+            field!("2", "2", "length"),
+            field!("4", "WINDOW", "window"),
+        ],
+        reply: Some("GetScreenInfo"),
+    },
+    RequestInfo {
+        name: "GetScreenSizeRange",
+        opcode: 6,
+        fields: &[
+            field!("1", "CARD8", "major opcode"),
+            field!("1", "6", "RandR opcode"),
+            field!("2", "2", "length"),
+            field!("4", "WINDOW", "window"),
+        ],
+        reply: Some("GetScreenSizeRange"),
+    },
+    RequestInfo {
+        name: "GetOutputInfo",
+        opcode: 9,
+        fields: &[
+            field!("1", "CARD8", "major opcode"),
+            field!("1", "9", "RandR opcode"),
+            field!("2", "3", "length"),
+            field!("4", "OUTPUT", "output"),
+            field!("4", "TIMESTAMP", "config-timestamp"),
+        ],
+        reply: Some("GetOutputInfo"),
+    },
+    RequestInfo {
+        name: "GetCrtcInfo",
+        opcode: 20,
+        fields: &[
+            field!("1", "CARD8", "major opcode"),
+            field!("1", "20", "RandR opcode"),
+            field!("2", "3", "length"),
+            field!("4", "CRTC", "crtc"),
+            field!("4", "TIMESTAMP", "config-timestamp"),
+        ],
+        reply: Some("GetCrtcInfo"),
+    },
+    RequestInfo {
+        name: "GetScreenResourcesCurrent",
+        opcode: 25,
+        fields: &[
+            field!("1", "CARD8", "major opcode"),
+            field!("1", "25", "RandR opcode"),
+            field!("2", "2", "length"),
+            field!("4", "WINDOW", "window"),
+        ],
+        reply: Some("GetScreenResourcesCurrent"),
+    },
+    RequestInfo {
+        name: "GetMonitors",
+        opcode: 42,
+        fields: &[
+            field!("1", "CARD8", "major opcode"),
+            field!("1", "42", "RandR opcode"),
+            // Spec says 2 and omits get-active, servers expect both
+            field!("2", "3", "request length"),
+            field!("4", "WINDOW", "window"),
+            field!("1", "BOOL", "get-active"),
+            field!("3", "", "unused"),
+        ],
+        reply: Some("GetMonitors"),
+    },
+    RequestInfo {
+        name: "SetMonitor",
+        opcode: 43,
+        fields: &[
+            field!("1", "CARD8", "major opcode"),
+            field!("1", "43", "RandR opcode"),
+            field!("2", "8 + n", "request length"),
+            field!("4", "WINDOW", "window"),
+            field!("24+4n", "MONITORINFO", "monitorinfo"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "DeleteMonitor",
+        opcode: 44,
+        fields: &[
+            field!("1", "CARD8", "major opcode"),
+            field!("1", "44", "RandR opcode"),
+            field!("2", "3", "request length"),
+            field!("4", "WINDOW", "window"),
+            field!("4", "ATOM", "name"),
+        ],
+        reply: None,
+    },
+];
+
+pub const SHAPE_REQUESTS: &[RequestInfo] = &[
+    RequestInfo {
+        name: "Rectangles",
+        opcode: 1,
+        fields: &[
+            field!("1", "CARD8", "major opcode"),
+            field!("1", "1", "shape opcode"),
+            field!("2", "4+2n", "request length"),
+            field!("1", "SHAPE_OP", "operation"),
+            field!("1", "SHAPE_KIND", "destination kind"),
+            field!("1", "", "ordering"),
+            field!("1", "", "unused"),
+            field!("4", "WINDOW", "destination window"),
+            field!("2", "INT16", "x-offset"),
+            field!("2", "INT16", "y-offset"),
+            field!("8n", "LISTofRECTANGLE", "rectangles"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "Mask",
+        opcode: 2,
+        fields: &[
+            field!("1", "CARD8", "major opcode"),
+            field!("1", "2", "shape opcode"),
+            field!("2", "5", "request length"),
+            field!("1", "SHAPE_OP", "operation"),
+            field!("1", "SHAPE_KIND", "destination kind"),
+            field!("2", "", "unused"),
+            field!("4", "WINDOW", "destination window"),
+            field!("2", "INT16", "x-offset"),
+            field!("2", "INT16", "y-offset"),
+            field!("4", "PIXMAP", "source bitmap"),
+        ],
+        reply: None,
+    },
+];
+
+pub const XINPUT_REQUESTS: &[RequestInfo] = &[
+    RequestInfo {
+        name: "XISelectEvents",
+        opcode: 46,
+        fields: &[
+            field!("1", "CARD8", "reqtype"),
+            field!("1", "46", "XI opcode"),
+            field!("2", "3+n", "length"),
+            field!("4", "WINDOW", "window"),
+            field!("2", "CARD16", "num_masks"),
+            field!("2", "", "pad"),
+            field!("4n", "LISTofEVMASK", "masks"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "XIQueryVersion",
+        opcode: 47,
+        fields: &[
+            field!("1", "CARD8", "reqtype"),
+            field!("1", "47", "XI opcode"),
+            field!("2", "2", "length"),
+            field!("2", "CARD16", "major_version"),
+            field!("2", "CARD16", "minor_version"),
+        ],
+        reply: Some("XIQueryVersion"),
+    },
+    RequestInfo {
+        name: "XIQueryDevice",
+        opcode: 48,
+        fields: &[
+            field!("1", "CARD8", "reqtype"),
+            field!("1", "48", "XI opcode"),
+            field!("2", "2", "length"),
+            field!("2", "DEVICEID", "deviceid"),
+            field!("2", "", "pad"),
+        ],
+        reply: Some("XIQueryDevice"),
+    },
+];
+
+pub const XKB_REQUESTS: &[RequestInfo] = &[
+    RequestInfo {
+        name: "UseExtension",
+        opcode: 0,
+        fields: &[
+            field!("1", "?", "opcode"),
+            field!("1", "0", "xkb-opcode"),
+            field!("2", "2", "request-length"),
+            field!("2", "CARD16", "wantedMajor"),
+            field!("2", "CARD16", "wantedMinor"),
+        ],
+        reply: Some("UseExtension"),
+    },
+    RequestInfo {
+        name: "SelectEvents",
+        opcode: 1,
+        fields: &[
+            field!("1", "?", "opcode"),
+            field!("1", "1", "xkb-opcode"),
+            field!("2", "4+(V/4)", "request-length"),
+            field!("2", "KB_DEVICESPEC", "deviceSpec"),
+            field!("2", "SETofEVENTTYPE", "affectWhich"),
+            field!("2", "SETofEVENTTYPE", "clear"),
+            field!("2", "SETofEVENTTYPE", "selectAll"),
+            field!("2", "SETofMAPPART", "affectMap"),
+            field!("2", "SETofMAPPART", "map"),
+            field!("V", "LISTofITEMs", "details"),
+        ],
+        reply: None,
+    },
+    RequestInfo {
+        name: "Bell",
+        opcode: 3,
+        fields: &[
+            field!("1", "?", "opcode"),
+            field!("1", "3", "xkb-opcode"),
+            field!("2", "7", "request-length"),
+            field!("2", "KB_DEVICESPEC", "deviceSpec"),
+            field!("2", "KB_BELLCLASSSPEC", "bellClass"),
+            field!("2", "KB_IDSPEC", "bellID"),
+            field!("1", "INT8", "percent"),
+            field!("1", "BOOL", "forceSound"),
+            field!("1", "BOOL", "eventOnly"),
+            field!("1", "", "unused"),
+            field!("2", "INT16", "pitch"),
+            field!("2", "INT16", "duration"),
+            field!("2", "", "unused"),
+            field!("4", "ATOM", "name"),
+            field!("4", "WINDOW", "window"),
+        ],
+        reply: None,
+    },
+];
+
+#[test]
+fn introspection_opcodes_unique() {
+    let mut tables = vec![CORE_REQUESTS];
+    tables.extend(EXTENSIONS.iter().map(|extension| extension.requests));
+
+    for table in tables {
+        for (idx, request) in table.iter().enumerate() {
+            assert!(
+                table[idx + 1..]
+                    .iter()
+                    .all(|other| other.opcode != request.opcode),
+                "duplicate opcode {} for {}",
+                request.opcode,
+                request.name,
+            );
+        }
+    }
+
+    assert_eq!(core_request(1).unwrap().name, "CreateWindow");
+    assert_eq!(
+        extension_request(b"RANDR", 0).unwrap().reply,
+        Some("QueryVersion")
+    );
+}
@@ -0,0 +1,738 @@
+//! Minimal JSON encoding and decoding of protocol model types for diagnostics and state dumps
+
+use crate::{
+    atoms::AtomId,
+    extensions::randr::{self, replies::OutputId, CrtcId},
+    replies, BackingStore, ColormapId, CursorId, Depth, FontId, GContextId, PixmapId, Rectangle,
+    Screen, Visual, VisualClass, VisualId, WindowId,
+};
+use std::{
+    fmt::{self, Write},
+    str::FromStr,
+};
+
+pub trait ToJson {
+    fn write_json(&self, w: &mut dyn Write) -> fmt::Result;
+
+    fn to_json(&self) -> String {
+        let mut res = String::new();
+        // Writing to String cannot fail
+        let _ = self.write_json(&mut res);
+        res
+    }
+}
+
+/// Write `s` as JSON string literal
+pub fn write_json_str(w: &mut dyn Write, s: &str) -> fmt::Result {
+    w.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => w.write_str("\\\"")?,
+            '\\' => w.write_str("\\\\")?,
+            '\n' => w.write_str("\\n")?,
+            '\r' => w.write_str("\\r")?,
+            '\t' => w.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(w, "\\u{:04x}", c as u32)?,
+            c => w.write_char(c)?,
+        }
+    }
+    w.write_char('"')
+}
+
+macro_rules! impl_to_json_display {
+    ($($t:ty),*) => {
+        $(
+            impl ToJson for $t {
+                fn write_json(&self, w: &mut dyn Write) -> fmt::Result {
+                    write!(w, "{}", self)
+                }
+            }
+        )*
+    };
+}
+
+impl_to_json_display!(bool, u8, u16, u32, u64, i8, i16, i32, i64, usize);
+
+impl ToJson for f32 {
+    fn write_json(&self, w: &mut dyn Write) -> fmt::Result {
+        if self.is_finite() {
+            write!(w, "{}", self)
+        } else {
+            w.write_str("null")
+        }
+    }
+}
+
+impl ToJson for str {
+    fn write_json(&self, w: &mut dyn Write) -> fmt::Result {
+        write_json_str(w, self)
+    }
+}
+
+impl ToJson for String {
+    fn write_json(&self, w: &mut dyn Write) -> fmt::Result {
+        write_json_str(w, self)
+    }
+}
+
+impl<T: ToJson> ToJson for Option<T> {
+    fn write_json(&self, w: &mut dyn Write) -> fmt::Result {
+        match self {
+            Some(value) => value.write_json(w),
+            None => w.write_str("null"),
+        }
+    }
+}
+
+impl<T: ToJson> ToJson for [T] {
+    fn write_json(&self, w: &mut dyn Write) -> fmt::Result {
+        w.write_char('[')?;
+        for (idx, value) in self.iter().enumerate() {
+            if idx != 0 {
+                w.write_char(',')?;
+            }
+            value.write_json(w)?;
+        }
+        w.write_char(']')
+    }
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+    fn write_json(&self, w: &mut dyn Write) -> fmt::Result {
+        self.as_slice().write_json(w)
+    }
+}
+
+/// Implement [`ToJson`] for a struct as JSON object with given fields
+#[macro_export]
+macro_rules! impl_to_json_struct {
+    ($name:ty { $($field:ident),* $(,)? }) => {
+        impl $crate::json::ToJson for $name {
+            fn write_json(&self, w: &mut dyn ::std::fmt::Write) -> ::std::fmt::Result {
+                w.write_char('{')?;
+                let mut _first = true;
+                $(
+                    if !_first {
+                        w.write_char(',')?;
+                    }
+                    _first = false;
+                    $crate::json::write_json_str(w, stringify!($field))?;
+                    w.write_char(':')?;
+                    $crate::json::ToJson::write_json(&self.$field, w)?;
+                )*
+                w.write_char('}')
+            }
+        }
+    };
+}
+
+/// Implement [`ToJson`] for a fieldless enum as string with variant name
+macro_rules! impl_to_json_enum {
+    ($($name:ty),*) => {
+        $(
+            impl ToJson for $name {
+                fn write_json(&self, w: &mut dyn Write) -> fmt::Result {
+                    write_json_str(w, &format!("{:?}", self))
+                }
+            }
+        )*
+    };
+}
+
+impl_to_json_enum!(VisualClass, BackingStore, randr::Connection);
+
+macro_rules! impl_to_json_resource_id {
+    ($($name:ty),*) => {
+        $(
+            impl ToJson for $name {
+                fn write_json(&self, w: &mut dyn Write) -> fmt::Result {
+                    write!(w, "{}", u32::from(*self))
+                }
+            }
+        )*
+    };
+}
+
+impl_to_json_resource_id!(
+    PixmapId, VisualId, FontId, ColormapId, CursorId, WindowId, GContextId, AtomId, OutputId,
+    CrtcId
+);
+
+impl_to_json_struct!(Rectangle {
+    x,
+    y,
+    width,
+    height
+});
+
+impl_to_json_struct!(Visual {
+    id,
+    class,
+    bits_per_rgb_value,
+    colormap_entries,
+    red_mask,
+    green_mask,
+    blue_mask,
+});
+
+impl_to_json_struct!(Depth { depth, visuals });
+
+impl_to_json_struct!(Screen {
+    root,
+    default_colormat,
+    white_pixel,
+    black_pixel,
+    current_input_masks,
+    width_in_pixels,
+    height_in_pixels,
+    width_in_millimeters,
+    height_in_millimeters,
+    min_installed_maps,
+    max_installed_maps,
+    root_visual,
+    backing_stores,
+    save_unders,
+    root_depth,
+    allowed_depths,
+});
+
+impl_to_json_struct!(replies::GetGeometry {
+    depth,
+    root,
+    x,
+    y,
+    width,
+    height,
+    border_width,
+});
+
+impl_to_json_struct!(replies::TimeCoord { time, x, y });
+
+impl ToJson for crate::requests::Timestamp {
+    fn write_json(&self, w: &mut dyn Write) -> fmt::Result {
+        write!(w, "{}", u32::from(*self))
+    }
+}
+
+/// Parsed JSON value, numbers are kept as written so 32-bit ids don't go through `f64`
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<JsonValue>),
+    /// Fields in the order they were written
+    Object(Vec<(String, JsonValue)>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonError {
+    /// Invalid JSON at byte offset
+    Syntax(usize),
+    /// Value of other type, with the name of the expected type
+    Expected(&'static str),
+    MissingField(String),
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonError::Syntax(offset) => write!(f, "Invalid JSON at byte {}", offset),
+            JsonError::Expected(name) => write!(f, "Expected JSON value of type {}", name),
+            JsonError::MissingField(name) => write!(f, "Missing JSON field '{}'", name),
+        }
+    }
+}
+
+/// Nesting deeper than this is rejected instead of overflowing the stack
+const MAX_DEPTH: usize = 128;
+
+struct Parser<'a> {
+    input: &'a [u8],
+    offset: usize,
+}
+
+impl Parser<'_> {
+    fn error<T>(&self) -> Result<T, JsonError> {
+        Err(JsonError::Syntax(self.offset))
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.input.get(self.offset) {
+            self.offset += 1;
+        }
+        self.input.get(self.offset).copied()
+    }
+
+    fn keyword(&mut self, keyword: &str, value: JsonValue) -> Result<JsonValue, JsonError> {
+        if !self.input[self.offset..].starts_with(keyword.as_bytes()) {
+            return self.error();
+        }
+        self.offset += keyword.len();
+        Ok(value)
+    }
+
+    /// Comma separated items until `end`, the opening bracket is already consumed
+    fn items(
+        &mut self,
+        end: u8,
+        mut item: impl FnMut(&mut Self) -> Result<(), JsonError>,
+    ) -> Result<(), JsonError> {
+        if self.peek() == Some(end) {
+            self.offset += 1;
+            return Ok(());
+        }
+        loop {
+            item(self)?;
+            match self.peek() {
+                Some(b',') => self.offset += 1,
+                Some(byte) if byte == end => {
+                    self.offset += 1;
+                    return Ok(());
+                }
+                _ => return self.error(),
+            }
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> Result<JsonValue, JsonError> {
+        if depth > MAX_DEPTH {
+            return self.error();
+        }
+        match self.peek() {
+            Some(b'n') => self.keyword("null", JsonValue::Null),
+            Some(b't') => self.keyword("true", JsonValue::Bool(true)),
+            Some(b'f') => self.keyword("false", JsonValue::Bool(false)),
+            Some(b'"') => self.string().map(JsonValue::String),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(b'[') => {
+                self.offset += 1;
+                let mut values = Vec::new();
+                self.items(b']', |parser| {
+                    values.push(parser.value(depth + 1)?);
+                    Ok(())
+                })?;
+                Ok(JsonValue::Array(values))
+            }
+            Some(b'{') => {
+                self.offset += 1;
+                let mut fields = Vec::new();
+                self.items(b'}', |parser| {
+                    if parser.peek() != Some(b'"') {
+                        return parser.error();
+                    }
+                    let name = parser.string()?;
+                    if parser.peek() != Some(b':') {
+                        return parser.error();
+                    }
+                    parser.offset += 1;
+                    fields.push((name, parser.value(depth + 1)?));
+                    Ok(())
+                })?;
+                Ok(JsonValue::Object(fields))
+            }
+            _ => self.error(),
+        }
+    }
+
+    fn number(&mut self) -> Result<JsonValue, JsonError> {
+        let start = self.offset;
+        while let Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9') = self.input.get(self.offset)
+        {
+            self.offset += 1;
+        }
+        // Only ASCII bytes were taken
+        let number = std::str::from_utf8(&self.input[start..self.offset]).unwrap();
+        if number.parse::<f64>().is_err() {
+            self.offset = start;
+            return self.error();
+        }
+        Ok(JsonValue::Number(number.to_string()))
+    }
+
+    /// Code unit of `\uXXXX` escape after the `\u`
+    fn code_unit(&mut self) -> Result<u32, JsonError> {
+        let code_unit = self
+            .input
+            .get(self.offset..self.offset + 4)
+            .and_then(|digits| std::str::from_utf8(digits).ok())
+            .and_then(|digits| u32::from_str_radix(digits, 16).ok());
+        match code_unit {
+            Some(code_unit) => {
+                self.offset += 4;
+                Ok(code_unit)
+            }
+            None => self.error(),
+        }
+    }
+
+    fn escaped_char(&mut self) -> Result<char, JsonError> {
+        let Some(&escape) = self.input.get(self.offset) else {
+            return self.error();
+        };
+        self.offset += 1;
+        let c = match escape {
+            b'"' => '"',
+            b'\\' => '\\',
+            b'/' => '/',
+            b'b' => '\u{8}',
+            b'f' => '\u{c}',
+            b'n' => '\n',
+            b'r' => '\r',
+            b't' => '\t',
+            b'u' => {
+                let mut code = self.code_unit()?;
+                // Characters outside of the BMP are written as UTF-16 surrogate pairs
+                if (0xd800..0xdc00).contains(&code) && self.input[self.offset..].starts_with(b"\\u")
+                {
+                    self.offset += 2;
+                    let low = self.code_unit()?;
+                    if !(0xdc00..0xe000).contains(&low) {
+                        return self.error();
+                    }
+                    code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+                }
+                match char::from_u32(code) {
+                    Some(c) => c,
+                    None => return self.error(),
+                }
+            }
+            _ => return self.error(),
+        };
+        Ok(c)
+    }
+
+    fn string(&mut self) -> Result<String, JsonError> {
+        // Opening quote
+        self.offset += 1;
+        let mut string = String::new();
+        loop {
+            let start = self.offset;
+            while let Some(&byte) = self.input.get(self.offset) {
+                if byte == b'"' || byte == b'\\' || byte < 0x20 {
+                    break;
+                }
+                self.offset += 1;
+            }
+            // Stops only at ASCII bytes, so the input is split on character boundaries
+            string.push_str(std::str::from_utf8(&self.input[start..self.offset]).unwrap());
+
+            match self.input.get(self.offset) {
+                Some(b'"') => {
+                    self.offset += 1;
+                    return Ok(string);
+                }
+                Some(b'\\') => {
+                    self.offset += 1;
+                    string.push(self.escaped_char()?);
+                }
+                _ => return self.error(),
+            }
+        }
+    }
+}
+
+impl FromStr for JsonValue {
+    type Err = JsonError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            input: s.as_bytes(),
+            offset: 0,
+        };
+        let value = parser.value(0)?;
+        if parser.peek().is_some() {
+            return parser.error();
+        }
+        Ok(value)
+    }
+}
+
+impl JsonValue {
+    /// Field `name` of an object
+    pub fn field(&self, name: &str) -> Result<&JsonValue, JsonError> {
+        let JsonValue::Object(fields) = self else {
+            return Err(JsonError::Expected("object"));
+        };
+        fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value)
+            .ok_or_else(|| JsonError::MissingField(name.to_string()))
+    }
+}
+
+pub trait FromJson: Sized {
+    fn from_json(value: &JsonValue) -> Result<Self, JsonError>;
+
+    fn parse_json(s: &str) -> Result<Self, JsonError> {
+        Self::from_json(&JsonValue::from_str(s)?)
+    }
+}
+
+macro_rules! impl_from_json_number {
+    ($($t:ty),*) => {
+        $(
+            impl FromJson for $t {
+                fn from_json(value: &JsonValue) -> Result<Self, JsonError> {
+                    match value {
+                        JsonValue::Number(number) => number
+                            .parse()
+                            .map_err(|_| JsonError::Expected(stringify!($t))),
+                        _ => Err(JsonError::Expected(stringify!($t))),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_json_number!(u8, u16, u32, u64, i8, i16, i32, i64, usize);
+
+impl FromJson for f32 {
+    fn from_json(value: &JsonValue) -> Result<Self, JsonError> {
+        match value {
+            // Non-finite numbers are written as `null`
+            JsonValue::Null => Ok(f32::NAN),
+            JsonValue::Number(number) => number.parse().map_err(|_| JsonError::Expected("f32")),
+            _ => Err(JsonError::Expected("f32")),
+        }
+    }
+}
+
+impl FromJson for bool {
+    fn from_json(value: &JsonValue) -> Result<Self, JsonError> {
+        match value {
+            JsonValue::Bool(value) => Ok(*value),
+            _ => Err(JsonError::Expected("bool")),
+        }
+    }
+}
+
+impl FromJson for String {
+    fn from_json(value: &JsonValue) -> Result<Self, JsonError> {
+        match value {
+            JsonValue::String(value) => Ok(value.clone()),
+            _ => Err(JsonError::Expected("string")),
+        }
+    }
+}
+
+impl<T: FromJson> FromJson for Option<T> {
+    fn from_json(value: &JsonValue) -> Result<Self, JsonError> {
+        match value {
+            JsonValue::Null => Ok(None),
+            value => T::from_json(value).map(Some),
+        }
+    }
+}
+
+impl<T: FromJson> FromJson for Vec<T> {
+    fn from_json(value: &JsonValue) -> Result<Self, JsonError> {
+        match value {
+            JsonValue::Array(values) => values.iter().map(T::from_json).collect(),
+            _ => Err(JsonError::Expected("array")),
+        }
+    }
+}
+
+/// Implement [`FromJson`] for a struct from JSON object written by [`impl_to_json_struct`]
+#[macro_export]
+macro_rules! impl_from_json_struct {
+    ($name:ty { $($field:ident),* $(,)? }) => {
+        impl $crate::json::FromJson for $name {
+            fn from_json(
+                value: &$crate::json::JsonValue,
+            ) -> ::std::result::Result<Self, $crate::json::JsonError> {
+                Ok(Self {
+                    $(
+                        $field: $crate::json::FromJson::from_json(
+                            value.field(stringify!($field))?,
+                        )?,
+                    )*
+                })
+            }
+        }
+    };
+}
+
+/// Implement [`FromJson`] for a fieldless enum from string with variant name
+macro_rules! impl_from_json_enum {
+    ($($name:ty { $($variant:ident),* $(,)? }),* $(,)?) => {
+        $(
+            impl FromJson for $name {
+                fn from_json(value: &JsonValue) -> Result<Self, JsonError> {
+                    match value {
+                        $(JsonValue::String(name) if name == stringify!($variant) => {
+                            Ok(<$name>::$variant)
+                        })*
+                        _ => Err(JsonError::Expected(stringify!($name))),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_json_enum!(
+    VisualClass {
+        StaticGray,
+        GrayScale,
+        StaticColor,
+        PseudoColor,
+        TrueColor,
+        DirectColor,
+    },
+    BackingStore {
+        NotUseful,
+        WhenMapped,
+        Always,
+    },
+    randr::Connection {
+        Connected,
+        Disconnected,
+        UnknownConnection,
+    },
+);
+
+macro_rules! impl_from_json_resource_id {
+    ($($name:ty),*) => {
+        $(
+            impl FromJson for $name {
+                fn from_json(value: &JsonValue) -> Result<Self, JsonError> {
+                    u32::from_json(value).map(<$name>::from)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_json_resource_id!(
+    PixmapId, VisualId, FontId, ColormapId, CursorId, WindowId, GContextId, AtomId, OutputId,
+    CrtcId
+);
+
+impl FromJson for crate::requests::Timestamp {
+    fn from_json(value: &JsonValue) -> Result<Self, JsonError> {
+        u32::from_json(value).map(Self::from)
+    }
+}
+
+impl_from_json_struct!(Rectangle {
+    x,
+    y,
+    width,
+    height
+});
+
+impl_from_json_struct!(Visual {
+    id,
+    class,
+    bits_per_rgb_value,
+    colormap_entries,
+    red_mask,
+    green_mask,
+    blue_mask,
+});
+
+impl_from_json_struct!(Depth { depth, visuals });
+
+impl_from_json_struct!(Screen {
+    root,
+    default_colormat,
+    white_pixel,
+    black_pixel,
+    current_input_masks,
+    width_in_pixels,
+    height_in_pixels,
+    width_in_millimeters,
+    height_in_millimeters,
+    min_installed_maps,
+    max_installed_maps,
+    root_visual,
+    backing_stores,
+    save_unders,
+    root_depth,
+    allowed_depths,
+});
+
+impl_from_json_struct!(replies::GetGeometry {
+    depth,
+    root,
+    x,
+    y,
+    width,
+    height,
+    border_width,
+});
+
+impl_from_json_struct!(replies::TimeCoord { time, x, y });
+
+#[test]
+fn json_encoding() {
+    let rect = Rectangle {
+        x: -1,
+        y: 2,
+        width: 3,
+        height: 4,
+    };
+    assert_eq!(
+        vec![Some(rect)].to_json(),
+        r#"[{"x":-1,"y":2,"width":3,"height":4}]"#
+    );
+    assert_eq!("a\"b\n".to_json(), r#""a\"b\n""#);
+}
+
+#[test]
+fn json_decoding() {
+    let screen = Screen {
+        root: WindowId::from(0x123),
+        default_colormat: 0x20,
+        white_pixel: 0xffffff,
+        black_pixel: 0,
+        current_input_masks: 0x1a0000,
+        width_in_pixels: 1920,
+        height_in_pixels: 1080,
+        width_in_millimeters: 508,
+        height_in_millimeters: 285,
+        min_installed_maps: 1,
+        max_installed_maps: 1,
+        root_visual: 0x21,
+        backing_stores: BackingStore::WhenMapped,
+        save_unders: false,
+        root_depth: 24,
+        allowed_depths: vec![Depth {
+            depth: 24,
+            visuals: vec![Visual {
+                id: VisualId::from(0x21),
+                class: VisualClass::TrueColor,
+                bits_per_rgb_value: 8,
+                colormap_entries: 256,
+                red_mask: 0xff0000,
+                green_mask: 0xff00,
+                blue_mask: 0xff,
+            }],
+        }],
+    };
+    let json = screen.to_json();
+    assert_eq!(Screen::parse_json(&json).unwrap().to_json(), json);
+
+    assert_eq!(
+        Vec::<Option<String>>::parse_json(r#" [ "a\"\u00e9\ud83d\ude00" , null ] "#).unwrap(),
+        [Some("a\"\u{e9}\u{1f600}".to_string()), None]
+    );
+    assert_eq!(
+        Rectangle::parse_json(r#"{"x":1,"y":2,"width":3}"#).unwrap_err(),
+        JsonError::MissingField("height".to_string())
+    );
+    assert_eq!(
+        u8::parse_json("256").unwrap_err(),
+        JsonError::Expected("u8")
+    );
+    assert_eq!(
+        JsonValue::from_str("[1,]").unwrap_err(),
+        JsonError::Syntax(3)
+    );
+    assert!(JsonValue::from_str(&"[".repeat(MAX_DEPTH + 2)).is_err());
+}
@@ -0,0 +1,294 @@
+//! Changing keyboard and modifier mappings, e.g. swapping `Caps_Lock` and `Escape`
+//!
+//! Mappings are global to the server, changes are seen by all clients and stay after the
+//! connection closes. Clients are notified with `MappingNotify` and have to refresh their copies.
+
+use crate::{
+    error::Error,
+    keysym::KeySym,
+    replies::SetModifierMappingStatus,
+    requests::{self, KeyCode},
+    XDisplay,
+};
+
+/// `Shift`, `Lock`, `Control` and `Mod1` to `Mod5`
+pub const MODIFIER_COUNT: usize = 8;
+
+/// Keysyms of all keycodes from `first_keycode`, `keysyms_per_keycode` for each
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyboardMapping {
+    pub first_keycode: u8,
+    pub keysyms_per_keycode: u8,
+    pub keysyms: Vec<KeySym>,
+}
+
+impl KeyboardMapping {
+    /// Keycodes described by the mapping
+    pub fn keycodes(&self) -> impl Iterator<Item = KeyCode> {
+        let count = self.keysyms.len() / (self.keysyms_per_keycode.max(1) as usize);
+        (self.first_keycode..).take(count).map(KeyCode::from)
+    }
+
+    /// All keysyms of `keycode`, empty if the keycode is outside of the mapping
+    pub fn keysyms(&self, keycode: KeyCode) -> &[KeySym] {
+        let per_keycode = self.keysyms_per_keycode as usize;
+        let Some(idx) = keycode.raw().checked_sub(self.first_keycode) else {
+            return &[];
+        };
+        let start = idx as usize * per_keycode;
+        self.keysyms
+            .get(start..start + per_keycode)
+            .unwrap_or_default()
+    }
+
+    pub fn keysyms_mut(&mut self, keycode: KeyCode) -> &mut [KeySym] {
+        let per_keycode = self.keysyms_per_keycode as usize;
+        let Some(idx) = keycode.raw().checked_sub(self.first_keycode) else {
+            return &mut [];
+        };
+        let start = idx as usize * per_keycode;
+        self.keysyms
+            .get_mut(start..start + per_keycode)
+            .unwrap_or_default()
+    }
+
+    /// Keysym produced without modifiers, [`KeySym::NO_SYMBOL`] if there is none
+    pub fn primary_keysym(&self, keycode: KeyCode) -> KeySym {
+        self.keysyms(keycode)
+            .first()
+            .copied()
+            .unwrap_or(KeySym::NO_SYMBOL)
+    }
+
+    /// Keycodes producing `keysym` in any column
+    pub fn keycodes_of(&self, keysym: KeySym) -> impl Iterator<Item = KeyCode> + '_ {
+        self.keycodes()
+            .filter(move |keycode| self.keysyms(*keycode).contains(&keysym))
+    }
+}
+
+/// Keycodes bound to each modifier, the same number for each modifier with unused slots set to
+/// keycode 0
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModifierMapping {
+    pub keycodes_per_modifier: u8,
+    /// `keycodes_per_modifier` keycodes of `Shift`, then of `Lock` and so on
+    pub keycodes: Vec<KeyCode>,
+}
+
+impl ModifierMapping {
+    /// Keycodes of modifier `idx`, e.g. 1 for `Lock`, without unused slots
+    pub fn modifier(&self, idx: usize) -> impl Iterator<Item = KeyCode> + '_ {
+        let per_modifier = self.keycodes_per_modifier as usize;
+        self.keycodes
+            .iter()
+            .skip(idx * per_modifier)
+            .take(per_modifier)
+            .copied()
+            .filter(|keycode| keycode.raw() != 0)
+    }
+
+    /// Replace keycodes of all modifiers, growing `keycodes_per_modifier` when needed
+    pub fn set_modifiers(&mut self, modifiers: [Vec<KeyCode>; MODIFIER_COUNT]) {
+        let per_modifier = modifiers.iter().map(Vec::len).max().unwrap_or(0);
+        self.keycodes_per_modifier = per_modifier as u8;
+        self.keycodes = modifiers
+            .into_iter()
+            .flat_map(|keycodes| {
+                let unused = per_modifier - keycodes.len();
+                keycodes
+                    .into_iter()
+                    .chain(std::iter::repeat_n(KeyCode::from(0u8), unused))
+            })
+            .collect();
+    }
+}
+
+/// Keysym substitutions applied to all keys at once, like `keysym` lines of `xmodmap`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KeyRemap {
+    substitutions: Vec<(KeySym, KeySym)>,
+}
+
+impl KeyRemap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keys producing `from` produce `to` instead
+    pub fn map(mut self, from: KeySym, to: KeySym) -> Self {
+        self.substitutions.retain(|(other, _)| *other != from);
+        self.substitutions.push((from, to));
+        self
+    }
+
+    /// Keys producing `a` produce `b` and the other way around
+    pub fn swap(self, a: KeySym, b: KeySym) -> Self {
+        self.map(a, b).map(b, a)
+    }
+
+    fn substitute(&self, keysym: KeySym) -> KeySym {
+        self.substitutions
+            .iter()
+            .find_map(|(from, to)| (*from == keysym).then_some(*to))
+            .unwrap_or(keysym)
+    }
+
+    /// Remap keysyms of `keyboard` and move modifiers along with the keysyms they were bound to,
+    /// e.g. `Lock` follows `Caps_Lock` to its new key. Returns keycodes whose keysyms changed.
+    pub fn apply(
+        &self,
+        keyboard: &mut KeyboardMapping,
+        modifiers: &mut ModifierMapping,
+    ) -> Vec<KeyCode> {
+        let old_keyboard = keyboard.clone();
+        let mut changed = Vec::new();
+        for keycode in old_keyboard.keycodes() {
+            let mut key_changed = false;
+            for keysym in keyboard.keysyms_mut(keycode) {
+                let new = self.substitute(*keysym);
+                key_changed |= new != *keysym;
+                *keysym = new;
+            }
+            if key_changed {
+                changed.push(keycode);
+            }
+        }
+
+        // Modifiers are bound to keycodes, so only the changed keys move between modifiers
+        let new_modifiers =
+            std::array::from_fn(|idx| {
+                let bound_keysyms = modifiers
+                    .modifier(idx)
+                    .map(|keycode| old_keyboard.primary_keysym(keycode))
+                    .filter(|keysym| *keysym != KeySym::NO_SYMBOL)
+                    .collect::<Vec<_>>();
+                modifiers
+                    .modifier(idx)
+                    .filter(|keycode| !changed.contains(keycode))
+                    .chain(changed.iter().copied().filter(|keycode| {
+                        bound_keysyms.contains(&keyboard.primary_keysym(*keycode))
+                    }))
+                    .collect()
+            });
+        modifiers.set_modifiers(new_modifiers);
+
+        changed
+    }
+}
+
+impl XDisplay {
+    /// Keysyms of all keycodes of the server
+    pub fn get_keyboard_mapping(&mut self) -> Result<KeyboardMapping, Error> {
+        let pending = self.send_request(&requests::GetKeyboardMapping {
+            first_keycode: KeyCode::from(self.min_keycode),
+            count: self.max_keycode - self.min_keycode + 1,
+        })?;
+        self.flush()?;
+        let reply = self.await_pending_reply(pending)?.unwrap();
+        Ok(KeyboardMapping {
+            first_keycode: self.min_keycode,
+            keysyms_per_keycode: reply.keysyms_per_keycode,
+            keysyms: reply.keysyms,
+        })
+    }
+
+    /// Replace keysyms of keycodes in `mapping`, without flushing
+    pub fn change_keyboard_mapping(&mut self, mapping: &KeyboardMapping) -> Result<(), Error> {
+        self.send_request(&requests::ChangeKeyboardMapping {
+            keycode_count: mapping.keycodes().count() as u8,
+            first_keycode: KeyCode::from(mapping.first_keycode),
+            keysyms_per_keycode: mapping.keysyms_per_keycode,
+            keysyms: mapping.keysyms.clone(),
+        })?;
+        Ok(())
+    }
+
+    pub fn get_modifier_mapping(&mut self) -> Result<ModifierMapping, Error> {
+        let pending = self.send_request(&requests::GetModifierMapping)?;
+        self.flush()?;
+        let reply = self.await_pending_reply(pending)?.unwrap();
+        Ok(ModifierMapping {
+            keycodes_per_modifier: reply.keycodes_per_modifier,
+            keycodes: reply.keycodes.into_iter().flatten().collect(),
+        })
+    }
+
+    /// Server refuses with [`SetModifierMappingStatus::Busy`] while any of the old or new
+    /// modifier keys is held down, the caller can retry after the keys are released
+    pub fn set_modifier_mapping(
+        &mut self,
+        mapping: &ModifierMapping,
+    ) -> Result<SetModifierMappingStatus, Error> {
+        let pending = self.send_request(&requests::SetModifierMapping {
+            keycodes: mapping.keycodes.clone(),
+        })?;
+        self.flush()?;
+        Ok(self.await_pending_reply(pending)?.unwrap().status)
+    }
+
+    /// Apply `remap` to the keyboard and modifier mappings of the server. Keyboard mapping is
+    /// changed only if the modifier mapping was accepted, so a busy server leaves both as they
+    /// were.
+    pub fn remap_keys(&mut self, remap: &KeyRemap) -> Result<SetModifierMappingStatus, Error> {
+        let mut keyboard = self.get_keyboard_mapping()?;
+        let mut modifiers = self.get_modifier_mapping()?;
+        let old_modifiers = modifiers.clone();
+        if remap.apply(&mut keyboard, &mut modifiers).is_empty() {
+            return Ok(SetModifierMappingStatus::Success);
+        }
+
+        if modifiers != old_modifiers {
+            let status = self.set_modifier_mapping(&modifiers)?;
+            if status != SetModifierMappingStatus::Success {
+                return Ok(status);
+            }
+        }
+        self.change_keyboard_mapping(&keyboard)?;
+        self.flush()?;
+        Ok(SetModifierMappingStatus::Success)
+    }
+}
+
+#[test]
+fn swap_caps_lock_and_escape() {
+    let [escape, caps_lock, a] = [9u8, 66, 38].map(KeyCode::from);
+    let mut keyboard = KeyboardMapping {
+        first_keycode: 8,
+        keysyms_per_keycode: 2,
+        keysyms: vec![KeySym::NO_SYMBOL; 2 * 60],
+    };
+    keyboard.keysyms_mut(escape)[0] = KeySym::Escape;
+    keyboard.keysyms_mut(caps_lock)[0] = KeySym::Caps_Lock;
+    keyboard
+        .keysyms_mut(a)
+        .copy_from_slice(&[KeySym::a, KeySym::A]);
+    let mut modifiers = ModifierMapping {
+        keycodes_per_modifier: 1,
+        keycodes: vec![KeyCode::from(0u8); MODIFIER_COUNT],
+    };
+    modifiers.keycodes[1] = caps_lock;
+
+    let remap = KeyRemap::new().swap(KeySym::Caps_Lock, KeySym::Escape);
+    assert_eq!(
+        remap.apply(&mut keyboard, &mut modifiers),
+        [escape, caps_lock]
+    );
+    assert_eq!(keyboard.primary_keysym(escape), KeySym::Caps_Lock);
+    assert_eq!(keyboard.primary_keysym(caps_lock), KeySym::Escape);
+    assert_eq!(keyboard.keysyms(a), [KeySym::a, KeySym::A]);
+    assert_eq!(
+        keyboard.keycodes_of(KeySym::Escape).collect::<Vec<_>>(),
+        [caps_lock]
+    );
+    // Lock moved to the key producing Caps_Lock
+    assert_eq!(modifiers.modifier(1).collect::<Vec<_>>(), [escape]);
+
+    // Remapped key gets no modifier if nothing produces the bound keysym anymore
+    KeyRemap::new()
+        .map(KeySym::Caps_Lock, KeySym::a)
+        .apply(&mut keyboard, &mut modifiers);
+    assert_eq!(modifiers.modifier(1).count(), 0);
+    assert_eq!(keyboard.keycodes().count(), 60);
+    assert_eq!(keyboard.keysyms(KeyCode::from(7u8)), []);
+}
@@ -3,6 +3,8 @@
 use crate::{connection::XConnection, error::Error, FromLeBytes};
 use std::ops::{Add, AddAssign, Sub, SubAssign};
 
+mod names;
+
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(transparent)]
 pub struct KeySym {
@@ -57,6 +59,44 @@ impl FromLeBytes for KeySym {
     }
 }
 
+/// Offset of keysyms of Unicode characters outside of Latin-1, e.g. `U20AC`
+const UNICODE_OFFSET: u32 = 0x01000000;
+
+/// Keysym with name from `keysymdef.h` without the `XK_` prefix, e.g. `grave`, like
+/// `XStringToKeysym`. `U` followed by a hex code point and `0x` followed by a hex value are
+/// accepted too.
+pub fn keysym_from_name(name: &str) -> Option<KeySym> {
+    if let Some((_, keysym)) = names::NAMES.iter().find(|(other, _)| *other == name) {
+        return Some(*keysym);
+    }
+
+    if let Some(hex) = name.strip_prefix("0x") {
+        return u32::from_str_radix(hex, 16)
+            .ok()
+            .map(|inner| KeySym { inner });
+    }
+    let code_point = u32::from_str_radix(name.strip_prefix('U')?, 16).ok()?;
+    match code_point {
+        // Control characters have no keysyms
+        0..0x20 | 0x7f..0xa0 => None,
+        // Latin-1 keysyms are their code points
+        0x20..0x7f | 0xa0..0x100 => Some(KeySym { inner: code_point }),
+        0x100..=0x10ffff => Some(KeySym {
+            inner: UNICODE_OFFSET + code_point,
+        }),
+        _ => None,
+    }
+}
+
+/// First name of `keysym` in `keysymdef.h`, like `XKeysymToString` but without names made up
+/// for keysyms that have none
+pub fn name_from_keysym(keysym: KeySym) -> Option<&'static str> {
+    names::NAMES
+        .iter()
+        .find(|(_, other)| *other == keysym)
+        .map(|(name, _)| *name)
+}
+
 impl KeySym {
     #[inline(always)]
     pub(crate) fn to_le_bytes(self) -> [u8; 4] {
@@ -4758,3 +4798,26 @@ impl KeySym {
     /// U+20AC EURO SIGN
     pub const EuroSign: Self = Self { inner: 0x20ac };
 }
+
+#[test]
+fn keysym_names() {
+    assert_eq!(keysym_from_name("grave"), Some(KeySym::grave));
+    assert_eq!(keysym_from_name("1"), Some(KeySym::DIGIT_1));
+    assert_eq!(keysym_from_name("3270_PA1"), Some(KeySym::IBM_3270_PA1));
+    assert_eq!(keysym_from_name("Grave"), None);
+    assert_eq!(keysym_from_name("0xff1b"), Some(KeySym::Escape));
+    assert_eq!(keysym_from_name("U20AC"), Some(KeySym { inner: 0x10020ac }));
+    assert_eq!(keysym_from_name("U0041"), Some(KeySym::A));
+    assert_eq!(keysym_from_name("U0007"), None);
+
+    assert_eq!(name_from_keysym(KeySym::Escape), Some("Escape"));
+    assert_eq!(name_from_keysym(KeySym::DIGIT_0), Some("0"));
+    // Alias defined later
+    assert_eq!(name_from_keysym(KeySym::Henkan), Some("Henkan_Mode"));
+    assert_eq!(name_from_keysym(KeySym { inner: 0x10020ac }), None);
+
+    // No name shadows another one
+    for (name, keysym) in names::NAMES {
+        assert_eq!(keysym_from_name(name), Some(*keysym), "{}", name);
+    }
+}
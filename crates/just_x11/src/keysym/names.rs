@@ -0,0 +1,1710 @@
+//! Names of [`KeySym`] constants as spelled in `keysymdef.h`, generated from `keysym.rs`
+
+use super::KeySym;
+
+/// In order of `keysymdef.h`, so aliases come after the name the value was defined with
+pub(super) const NAMES: &[(&str, KeySym)] = &[
+    ("VoidSymbol", KeySym::VOID_SYMBOL),
+    ("BackSpace", KeySym::BackSpace),
+    ("Tab", KeySym::Tab),
+    ("Linefeed", KeySym::Linefeed),
+    ("Clear", KeySym::Clear),
+    ("Return", KeySym::Return),
+    ("Pause", KeySym::Pause),
+    ("Scroll_Lock", KeySym::Scroll_Lock),
+    ("Sys_Req", KeySym::Sys_Req),
+    ("Escape", KeySym::Escape),
+    ("Delete", KeySym::Delete),
+    ("Multi_key", KeySym::Multi_key),
+    ("Codeinput", KeySym::Codeinput),
+    ("SingleCandidate", KeySym::SingleCandidate),
+    ("MultipleCandidate", KeySym::MultipleCandidate),
+    ("PreviousCandidate", KeySym::PreviousCandidate),
+    ("Kanji", KeySym::Kanji),
+    ("Muhenkan", KeySym::Muhenkan),
+    ("Henkan_Mode", KeySym::Henkan_Mode),
+    ("Henkan", KeySym::Henkan),
+    ("Romaji", KeySym::Romaji),
+    ("Hiragana", KeySym::Hiragana),
+    ("Katakana", KeySym::Katakana),
+    ("Hiragana_Katakana", KeySym::Hiragana_Katakana),
+    ("Zenkaku", KeySym::Zenkaku),
+    ("Hankaku", KeySym::Hankaku),
+    ("Zenkaku_Hankaku", KeySym::Zenkaku_Hankaku),
+    ("Touroku", KeySym::Touroku),
+    ("Massyo", KeySym::Massyo),
+    ("Kana_Lock", KeySym::Kana_Lock),
+    ("Kana_Shift", KeySym::Kana_Shift),
+    ("Eisu_Shift", KeySym::Eisu_Shift),
+    ("Eisu_toggle", KeySym::Eisu_toggle),
+    ("Kanji_Bangou", KeySym::Kanji_Bangou),
+    ("Zen_Koho", KeySym::Zen_Koho),
+    ("Mae_Koho", KeySym::Mae_Koho),
+    ("Home", KeySym::Home),
+    ("Left", KeySym::Left),
+    ("Up", KeySym::Up),
+    ("Right", KeySym::Right),
+    ("Down", KeySym::Down),
+    ("Prior", KeySym::Prior),
+    ("Page_Up", KeySym::Page_Up),
+    ("Next", KeySym::Next),
+    ("Page_Down", KeySym::Page_Down),
+    ("End", KeySym::End),
+    ("Begin", KeySym::Begin),
+    ("Select", KeySym::Select),
+    ("Print", KeySym::Print),
+    ("Execute", KeySym::Execute),
+    ("Insert", KeySym::Insert),
+    ("Undo", KeySym::Undo),
+    ("Redo", KeySym::Redo),
+    ("Menu", KeySym::Menu),
+    ("Find", KeySym::Find),
+    ("Cancel", KeySym::Cancel),
+    ("Help", KeySym::Help),
+    ("Break", KeySym::Break),
+    ("Mode_switch", KeySym::Mode_switch),
+    ("script_switch", KeySym::script_switch),
+    ("Num_Lock", KeySym::Num_Lock),
+    ("KP_Space", KeySym::KP_Space),
+    ("KP_Tab", KeySym::KP_Tab),
+    ("KP_Enter", KeySym::KP_Enter),
+    ("KP_F1", KeySym::KP_F1),
+    ("KP_F2", KeySym::KP_F2),
+    ("KP_F3", KeySym::KP_F3),
+    ("KP_F4", KeySym::KP_F4),
+    ("KP_Home", KeySym::KP_Home),
+    ("KP_Left", KeySym::KP_Left),
+    ("KP_Up", KeySym::KP_Up),
+    ("KP_Right", KeySym::KP_Right),
+    ("KP_Down", KeySym::KP_Down),
+    ("KP_Prior", KeySym::KP_Prior),
+    ("KP_Page_Up", KeySym::KP_Page_Up),
+    ("KP_Next", KeySym::KP_Next),
+    ("KP_Page_Down", KeySym::KP_Page_Down),
+    ("KP_End", KeySym::KP_End),
+    ("KP_Begin", KeySym::KP_Begin),
+    ("KP_Insert", KeySym::KP_Insert),
+    ("KP_Delete", KeySym::KP_Delete),
+    ("KP_Equal", KeySym::KP_Equal),
+    ("KP_Multiply", KeySym::KP_Multiply),
+    ("KP_Add", KeySym::KP_Add),
+    ("KP_Separator", KeySym::KP_Separator),
+    ("KP_Subtract", KeySym::KP_Subtract),
+    ("KP_Decimal", KeySym::KP_Decimal),
+    ("KP_Divide", KeySym::KP_Divide),
+    ("KP_0", KeySym::KP_0),
+    ("KP_1", KeySym::KP_1),
+    ("KP_2", KeySym::KP_2),
+    ("KP_3", KeySym::KP_3),
+    ("KP_4", KeySym::KP_4),
+    ("KP_5", KeySym::KP_5),
+    ("KP_6", KeySym::KP_6),
+    ("KP_7", KeySym::KP_7),
+    ("KP_8", KeySym::KP_8),
+    ("KP_9", KeySym::KP_9),
+    ("F1", KeySym::F1),
+    ("F2", KeySym::F2),
+    ("F3", KeySym::F3),
+    ("F4", KeySym::F4),
+    ("F5", KeySym::F5),
+    ("F6", KeySym::F6),
+    ("F7", KeySym::F7),
+    ("F8", KeySym::F8),
+    ("F9", KeySym::F9),
+    ("F10", KeySym::F10),
+    ("F11", KeySym::F11),
+    ("L1", KeySym::L1),
+    ("F12", KeySym::F12),
+    ("L2", KeySym::L2),
+    ("F13", KeySym::F13),
+    ("L3", KeySym::L3),
+    ("F14", KeySym::F14),
+    ("L4", KeySym::L4),
+    ("F15", KeySym::F15),
+    ("L5", KeySym::L5),
+    ("F16", KeySym::F16),
+    ("L6", KeySym::L6),
+    ("F17", KeySym::F17),
+    ("L7", KeySym::L7),
+    ("F18", KeySym::F18),
+    ("L8", KeySym::L8),
+    ("F19", KeySym::F19),
+    ("L9", KeySym::L9),
+    ("F20", KeySym::F20),
+    ("L10", KeySym::L10),
+    ("F21", KeySym::F21),
+    ("R1", KeySym::R1),
+    ("F22", KeySym::F22),
+    ("R2", KeySym::R2),
+    ("F23", KeySym::F23),
+    ("R3", KeySym::R3),
+    ("F24", KeySym::F24),
+    ("R4", KeySym::R4),
+    ("F25", KeySym::F25),
+    ("R5", KeySym::R5),
+    ("F26", KeySym::F26),
+    ("R6", KeySym::R6),
+    ("F27", KeySym::F27),
+    ("R7", KeySym::R7),
+    ("F28", KeySym::F28),
+    ("R8", KeySym::R8),
+    ("F29", KeySym::F29),
+    ("R9", KeySym::R9),
+    ("F30", KeySym::F30),
+    ("R10", KeySym::R10),
+    ("F31", KeySym::F31),
+    ("R11", KeySym::R11),
+    ("F32", KeySym::F32),
+    ("R12", KeySym::R12),
+    ("F33", KeySym::F33),
+    ("R13", KeySym::R13),
+    ("F34", KeySym::F34),
+    ("R14", KeySym::R14),
+    ("F35", KeySym::F35),
+    ("R15", KeySym::R15),
+    ("Shift_L", KeySym::Shift_L),
+    ("Shift_R", KeySym::Shift_R),
+    ("Control_L", KeySym::Control_L),
+    ("Control_R", KeySym::Control_R),
+    ("Caps_Lock", KeySym::Caps_Lock),
+    ("Shift_Lock", KeySym::Shift_Lock),
+    ("Meta_L", KeySym::Meta_L),
+    ("Meta_R", KeySym::Meta_R),
+    ("Alt_L", KeySym::Alt_L),
+    ("Alt_R", KeySym::Alt_R),
+    ("Super_L", KeySym::Super_L),
+    ("Super_R", KeySym::Super_R),
+    ("Hyper_L", KeySym::Hyper_L),
+    ("Hyper_R", KeySym::Hyper_R),
+    ("ISO_Lock", KeySym::ISO_Lock),
+    ("ISO_Level2_Latch", KeySym::ISO_Level2_Latch),
+    ("ISO_Level3_Shift", KeySym::ISO_Level3_Shift),
+    ("ISO_Level3_Latch", KeySym::ISO_Level3_Latch),
+    ("ISO_Level3_Lock", KeySym::ISO_Level3_Lock),
+    ("ISO_Level5_Shift", KeySym::ISO_Level5_Shift),
+    ("ISO_Level5_Latch", KeySym::ISO_Level5_Latch),
+    ("ISO_Level5_Lock", KeySym::ISO_Level5_Lock),
+    ("ISO_Group_Shift", KeySym::ISO_Group_Shift),
+    ("ISO_Group_Latch", KeySym::ISO_Group_Latch),
+    ("ISO_Group_Lock", KeySym::ISO_Group_Lock),
+    ("ISO_Next_Group", KeySym::ISO_Next_Group),
+    ("ISO_Next_Group_Lock", KeySym::ISO_Next_Group_Lock),
+    ("ISO_Prev_Group", KeySym::ISO_Prev_Group),
+    ("ISO_Prev_Group_Lock", KeySym::ISO_Prev_Group_Lock),
+    ("ISO_First_Group", KeySym::ISO_First_Group),
+    ("ISO_First_Group_Lock", KeySym::ISO_First_Group_Lock),
+    ("ISO_Last_Group", KeySym::ISO_Last_Group),
+    ("ISO_Last_Group_Lock", KeySym::ISO_Last_Group_Lock),
+    ("ISO_Left_Tab", KeySym::ISO_Left_Tab),
+    ("ISO_Move_Line_Up", KeySym::ISO_Move_Line_Up),
+    ("ISO_Move_Line_Down", KeySym::ISO_Move_Line_Down),
+    ("ISO_Partial_Line_Up", KeySym::ISO_Partial_Line_Up),
+    ("ISO_Partial_Line_Down", KeySym::ISO_Partial_Line_Down),
+    ("ISO_Partial_Space_Left", KeySym::ISO_Partial_Space_Left),
+    ("ISO_Partial_Space_Right", KeySym::ISO_Partial_Space_Right),
+    ("ISO_Set_Margin_Left", KeySym::ISO_Set_Margin_Left),
+    ("ISO_Set_Margin_Right", KeySym::ISO_Set_Margin_Right),
+    ("ISO_Release_Margin_Left", KeySym::ISO_Release_Margin_Left),
+    ("ISO_Release_Margin_Right", KeySym::ISO_Release_Margin_Right),
+    ("ISO_Release_Both_Margins", KeySym::ISO_Release_Both_Margins),
+    ("ISO_Fast_Cursor_Left", KeySym::ISO_Fast_Cursor_Left),
+    ("ISO_Fast_Cursor_Right", KeySym::ISO_Fast_Cursor_Right),
+    ("ISO_Fast_Cursor_Up", KeySym::ISO_Fast_Cursor_Up),
+    ("ISO_Fast_Cursor_Down", KeySym::ISO_Fast_Cursor_Down),
+    ("ISO_Continuous_Underline", KeySym::ISO_Continuous_Underline),
+    (
+        "ISO_Discontinuous_Underline",
+        KeySym::ISO_Discontinuous_Underline,
+    ),
+    ("ISO_Emphasize", KeySym::ISO_Emphasize),
+    ("ISO_Center_Object", KeySym::ISO_Center_Object),
+    ("ISO_Enter", KeySym::ISO_Enter),
+    ("dead_grave", KeySym::dead_grave),
+    ("dead_acute", KeySym::dead_acute),
+    ("dead_circumflex", KeySym::dead_circumflex),
+    ("dead_tilde", KeySym::dead_tilde),
+    ("dead_macron", KeySym::dead_macron),
+    ("dead_breve", KeySym::dead_breve),
+    ("dead_abovedot", KeySym::dead_abovedot),
+    ("dead_diaeresis", KeySym::dead_diaeresis),
+    ("dead_abovering", KeySym::dead_abovering),
+    ("dead_doubleacute", KeySym::dead_doubleacute),
+    ("dead_caron", KeySym::dead_caron),
+    ("dead_cedilla", KeySym::dead_cedilla),
+    ("dead_ogonek", KeySym::dead_ogonek),
+    ("dead_iota", KeySym::dead_iota),
+    ("dead_voiced_sound", KeySym::dead_voiced_sound),
+    ("dead_semivoiced_sound", KeySym::dead_semivoiced_sound),
+    ("dead_belowdot", KeySym::dead_belowdot),
+    ("dead_hook", KeySym::dead_hook),
+    ("dead_horn", KeySym::dead_horn),
+    ("First_Virtual_Screen", KeySym::First_Virtual_Screen),
+    ("Prev_Virtual_Screen", KeySym::Prev_Virtual_Screen),
+    ("Next_Virtual_Screen", KeySym::Next_Virtual_Screen),
+    ("Last_Virtual_Screen", KeySym::Last_Virtual_Screen),
+    ("Terminate_Server", KeySym::Terminate_Server),
+    ("AccessX_Enable", KeySym::AccessX_Enable),
+    ("AccessX_Feedback_Enable", KeySym::AccessX_Feedback_Enable),
+    ("RepeatKeys_Enable", KeySym::RepeatKeys_Enable),
+    ("SlowKeys_Enable", KeySym::SlowKeys_Enable),
+    ("BounceKeys_Enable", KeySym::BounceKeys_Enable),
+    ("StickyKeys_Enable", KeySym::StickyKeys_Enable),
+    ("MouseKeys_Enable", KeySym::MouseKeys_Enable),
+    ("MouseKeys_Accel_Enable", KeySym::MouseKeys_Accel_Enable),
+    ("Overlay1_Enable", KeySym::Overlay1_Enable),
+    ("Overlay2_Enable", KeySym::Overlay2_Enable),
+    ("AudibleBell_Enable", KeySym::AudibleBell_Enable),
+    ("Pointer_Left", KeySym::Pointer_Left),
+    ("Pointer_Right", KeySym::Pointer_Right),
+    ("Pointer_Up", KeySym::Pointer_Up),
+    ("Pointer_Down", KeySym::Pointer_Down),
+    ("Pointer_UpLeft", KeySym::Pointer_UpLeft),
+    ("Pointer_UpRight", KeySym::Pointer_UpRight),
+    ("Pointer_DownLeft", KeySym::Pointer_DownLeft),
+    ("Pointer_DownRight", KeySym::Pointer_DownRight),
+    ("Pointer_Button_Dflt", KeySym::Pointer_Button_Dflt),
+    ("Pointer_Button1", KeySym::Pointer_Button1),
+    ("Pointer_Button2", KeySym::Pointer_Button2),
+    ("Pointer_Button3", KeySym::Pointer_Button3),
+    ("Pointer_Button4", KeySym::Pointer_Button4),
+    ("Pointer_Button5", KeySym::Pointer_Button5),
+    ("Pointer_DblClick_Dflt", KeySym::Pointer_DblClick_Dflt),
+    ("Pointer_DblClick1", KeySym::Pointer_DblClick1),
+    ("Pointer_DblClick2", KeySym::Pointer_DblClick2),
+    ("Pointer_DblClick3", KeySym::Pointer_DblClick3),
+    ("Pointer_DblClick4", KeySym::Pointer_DblClick4),
+    ("Pointer_DblClick5", KeySym::Pointer_DblClick5),
+    ("Pointer_Drag_Dflt", KeySym::Pointer_Drag_Dflt),
+    ("Pointer_Drag1", KeySym::Pointer_Drag1),
+    ("Pointer_Drag2", KeySym::Pointer_Drag2),
+    ("Pointer_Drag3", KeySym::Pointer_Drag3),
+    ("Pointer_Drag4", KeySym::Pointer_Drag4),
+    ("Pointer_Drag5", KeySym::Pointer_Drag5),
+    ("Pointer_EnableKeys", KeySym::Pointer_EnableKeys),
+    ("Pointer_Accelerate", KeySym::Pointer_Accelerate),
+    ("Pointer_DfltBtnNext", KeySym::Pointer_DfltBtnNext),
+    ("Pointer_DfltBtnPrev", KeySym::Pointer_DfltBtnPrev),
+    ("3270_Duplicate", KeySym::IBM_3270_Duplicate),
+    ("3270_FieldMark", KeySym::IBM_3270_FieldMark),
+    ("3270_Right2", KeySym::IBM_3270_Right2),
+    ("3270_Left2", KeySym::IBM_3270_Left2),
+    ("3270_BackTab", KeySym::IBM_3270_BackTab),
+    ("3270_EraseEOF", KeySym::IBM_3270_EraseEOF),
+    ("3270_EraseInput", KeySym::IBM_3270_EraseInput),
+    ("3270_Reset", KeySym::IBM_3270_Reset),
+    ("3270_Quit", KeySym::IBM_3270_Quit),
+    ("3270_PA1", KeySym::IBM_3270_PA1),
+    ("3270_PA2", KeySym::IBM_3270_PA2),
+    ("3270_PA3", KeySym::IBM_3270_PA3),
+    ("3270_Test", KeySym::IBM_3270_Test),
+    ("3270_Attn", KeySym::IBM_3270_Attn),
+    ("3270_CursorBlink", KeySym::IBM_3270_CursorBlink),
+    ("3270_AltCursor", KeySym::IBM_3270_AltCursor),
+    ("3270_KeyClick", KeySym::IBM_3270_KeyClick),
+    ("3270_Jump", KeySym::IBM_3270_Jump),
+    ("3270_Ident", KeySym::IBM_3270_Ident),
+    ("3270_Rule", KeySym::IBM_3270_Rule),
+    ("3270_Copy", KeySym::IBM_3270_Copy),
+    ("3270_Play", KeySym::IBM_3270_Play),
+    ("3270_Setup", KeySym::IBM_3270_Setup),
+    ("3270_Record", KeySym::IBM_3270_Record),
+    ("3270_ChangeScreen", KeySym::IBM_3270_ChangeScreen),
+    ("3270_DeleteWord", KeySym::IBM_3270_DeleteWord),
+    ("3270_ExSelect", KeySym::IBM_3270_ExSelect),
+    ("3270_CursorSelect", KeySym::IBM_3270_CursorSelect),
+    ("3270_PrintScreen", KeySym::IBM_3270_PrintScreen),
+    ("3270_Enter", KeySym::IBM_3270_Enter),
+    ("space", KeySym::space),
+    ("exclam", KeySym::exclam),
+    ("quotedbl", KeySym::quotedbl),
+    ("numbersign", KeySym::numbersign),
+    ("dollar", KeySym::dollar),
+    ("percent", KeySym::percent),
+    ("ampersand", KeySym::ampersand),
+    ("apostrophe", KeySym::apostrophe),
+    ("quoteright", KeySym::quoteright),
+    ("parenleft", KeySym::parenleft),
+    ("parenright", KeySym::parenright),
+    ("asterisk", KeySym::asterisk),
+    ("plus", KeySym::plus),
+    ("comma", KeySym::comma),
+    ("minus", KeySym::minus),
+    ("period", KeySym::period),
+    ("slash", KeySym::slash),
+    ("0", KeySym::DIGIT_0),
+    ("1", KeySym::DIGIT_1),
+    ("2", KeySym::DIGIT_2),
+    ("3", KeySym::DIGIT_3),
+    ("4", KeySym::DIGIT_4),
+    ("5", KeySym::DIGIT_5),
+    ("6", KeySym::DIGIT_6),
+    ("7", KeySym::DIGIT_7),
+    ("8", KeySym::DIGIT_8),
+    ("9", KeySym::DIGIT_9),
+    ("colon", KeySym::colon),
+    ("semicolon", KeySym::semicolon),
+    ("less", KeySym::less),
+    ("equal", KeySym::equal),
+    ("greater", KeySym::greater),
+    ("question", KeySym::question),
+    ("at", KeySym::at),
+    ("A", KeySym::A),
+    ("B", KeySym::B),
+    ("C", KeySym::C),
+    ("D", KeySym::D),
+    ("E", KeySym::E),
+    ("F", KeySym::F),
+    ("G", KeySym::G),
+    ("H", KeySym::H),
+    ("I", KeySym::I),
+    ("J", KeySym::J),
+    ("K", KeySym::K),
+    ("L", KeySym::L),
+    ("M", KeySym::M),
+    ("N", KeySym::N),
+    ("O", KeySym::O),
+    ("P", KeySym::P),
+    ("Q", KeySym::Q),
+    ("R", KeySym::R),
+    ("S", KeySym::S),
+    ("T", KeySym::T),
+    ("U", KeySym::U),
+    ("V", KeySym::V),
+    ("W", KeySym::W),
+    ("X", KeySym::X),
+    ("Y", KeySym::Y),
+    ("Z", KeySym::Z),
+    ("bracketleft", KeySym::bracketleft),
+    ("backslash", KeySym::backslash),
+    ("bracketright", KeySym::bracketright),
+    ("asciicircum", KeySym::asciicircum),
+    ("underscore", KeySym::underscore),
+    ("grave", KeySym::grave),
+    ("quoteleft", KeySym::quoteleft),
+    ("a", KeySym::a),
+    ("b", KeySym::b),
+    ("c", KeySym::c),
+    ("d", KeySym::d),
+    ("e", KeySym::e),
+    ("f", KeySym::f),
+    ("g", KeySym::g),
+    ("h", KeySym::h),
+    ("i", KeySym::i),
+    ("j", KeySym::j),
+    ("k", KeySym::k),
+    ("l", KeySym::l),
+    ("m", KeySym::m),
+    ("n", KeySym::n),
+    ("o", KeySym::o),
+    ("p", KeySym::p),
+    ("q", KeySym::q),
+    ("r", KeySym::r),
+    ("s", KeySym::s),
+    ("t", KeySym::t),
+    ("u", KeySym::u),
+    ("v", KeySym::v),
+    ("w", KeySym::w),
+    ("x", KeySym::x),
+    ("y", KeySym::y),
+    ("z", KeySym::z),
+    ("braceleft", KeySym::braceleft),
+    ("bar", KeySym::bar),
+    ("braceright", KeySym::braceright),
+    ("asciitilde", KeySym::asciitilde),
+    ("nobreakspace", KeySym::nobreakspace),
+    ("exclamdown", KeySym::exclamdown),
+    ("cent", KeySym::cent),
+    ("sterling", KeySym::sterling),
+    ("currency", KeySym::currency),
+    ("yen", KeySym::yen),
+    ("brokenbar", KeySym::brokenbar),
+    ("section", KeySym::section),
+    ("diaeresis", KeySym::diaeresis),
+    ("copyright", KeySym::copyright),
+    ("ordfeminine", KeySym::ordfeminine),
+    ("guillemotleft", KeySym::guillemotleft),
+    ("notsign", KeySym::notsign),
+    ("hyphen", KeySym::hyphen),
+    ("registered", KeySym::registered),
+    ("macron", KeySym::macron),
+    ("degree", KeySym::degree),
+    ("plusminus", KeySym::plusminus),
+    ("twosuperior", KeySym::twosuperior),
+    ("threesuperior", KeySym::threesuperior),
+    ("acute", KeySym::acute),
+    ("mu", KeySym::mu),
+    ("paragraph", KeySym::paragraph),
+    ("periodcentered", KeySym::periodcentered),
+    ("cedilla", KeySym::cedilla),
+    ("onesuperior", KeySym::onesuperior),
+    ("masculine", KeySym::masculine),
+    ("guillemotright", KeySym::guillemotright),
+    ("onequarter", KeySym::onequarter),
+    ("onehalf", KeySym::onehalf),
+    ("threequarters", KeySym::threequarters),
+    ("questiondown", KeySym::questiondown),
+    ("Agrave", KeySym::Agrave),
+    ("Aacute", KeySym::Aacute),
+    ("Acircumflex", KeySym::Acircumflex),
+    ("Atilde", KeySym::Atilde),
+    ("Adiaeresis", KeySym::Adiaeresis),
+    ("Aring", KeySym::Aring),
+    ("AE", KeySym::AE),
+    ("Ccedilla", KeySym::Ccedilla),
+    ("Egrave", KeySym::Egrave),
+    ("Eacute", KeySym::Eacute),
+    ("Ecircumflex", KeySym::Ecircumflex),
+    ("Ediaeresis", KeySym::Ediaeresis),
+    ("Igrave", KeySym::Igrave),
+    ("Iacute", KeySym::Iacute),
+    ("Icircumflex", KeySym::Icircumflex),
+    ("Idiaeresis", KeySym::Idiaeresis),
+    ("ETH", KeySym::ETH),
+    ("Eth", KeySym::Eth),
+    ("Ntilde", KeySym::Ntilde),
+    ("Ograve", KeySym::Ograve),
+    ("Oacute", KeySym::Oacute),
+    ("Ocircumflex", KeySym::Ocircumflex),
+    ("Otilde", KeySym::Otilde),
+    ("Odiaeresis", KeySym::Odiaeresis),
+    ("multiply", KeySym::multiply),
+    ("Oslash", KeySym::Oslash),
+    ("Ooblique", KeySym::Ooblique),
+    ("Ugrave", KeySym::Ugrave),
+    ("Uacute", KeySym::Uacute),
+    ("Ucircumflex", KeySym::Ucircumflex),
+    ("Udiaeresis", KeySym::Udiaeresis),
+    ("Yacute", KeySym::Yacute),
+    ("THORN", KeySym::THORN),
+    ("Thorn", KeySym::Thorn),
+    ("ssharp", KeySym::ssharp),
+    ("agrave", KeySym::agrave),
+    ("aacute", KeySym::aacute),
+    ("acircumflex", KeySym::acircumflex),
+    ("atilde", KeySym::atilde),
+    ("adiaeresis", KeySym::adiaeresis),
+    ("aring", KeySym::aring),
+    ("ae", KeySym::ae),
+    ("ccedilla", KeySym::ccedilla),
+    ("egrave", KeySym::egrave),
+    ("eacute", KeySym::eacute),
+    ("ecircumflex", KeySym::ecircumflex),
+    ("ediaeresis", KeySym::ediaeresis),
+    ("igrave", KeySym::igrave),
+    ("iacute", KeySym::iacute),
+    ("icircumflex", KeySym::icircumflex),
+    ("idiaeresis", KeySym::idiaeresis),
+    ("eth", KeySym::eth),
+    ("ntilde", KeySym::ntilde),
+    ("ograve", KeySym::ograve),
+    ("oacute", KeySym::oacute),
+    ("ocircumflex", KeySym::ocircumflex),
+    ("otilde", KeySym::otilde),
+    ("odiaeresis", KeySym::odiaeresis),
+    ("division", KeySym::division),
+    ("oslash", KeySym::oslash),
+    ("ooblique", KeySym::ooblique),
+    ("ugrave", KeySym::ugrave),
+    ("uacute", KeySym::uacute),
+    ("ucircumflex", KeySym::ucircumflex),
+    ("udiaeresis", KeySym::udiaeresis),
+    ("yacute", KeySym::yacute),
+    ("thorn", KeySym::thorn),
+    ("ydiaeresis", KeySym::ydiaeresis),
+    ("Aogonek", KeySym::Aogonek),
+    ("breve", KeySym::breve),
+    ("Lstroke", KeySym::Lstroke),
+    ("Lcaron", KeySym::Lcaron),
+    ("Sacute", KeySym::Sacute),
+    ("Scaron", KeySym::Scaron),
+    ("Scedilla", KeySym::Scedilla),
+    ("Tcaron", KeySym::Tcaron),
+    ("Zacute", KeySym::Zacute),
+    ("Zcaron", KeySym::Zcaron),
+    ("Zabovedot", KeySym::Zabovedot),
+    ("aogonek", KeySym::aogonek),
+    ("ogonek", KeySym::ogonek),
+    ("lstroke", KeySym::lstroke),
+    ("lcaron", KeySym::lcaron),
+    ("sacute", KeySym::sacute),
+    ("caron", KeySym::caron),
+    ("scaron", KeySym::scaron),
+    ("scedilla", KeySym::scedilla),
+    ("tcaron", KeySym::tcaron),
+    ("zacute", KeySym::zacute),
+    ("doubleacute", KeySym::doubleacute),
+    ("zcaron", KeySym::zcaron),
+    ("zabovedot", KeySym::zabovedot),
+    ("Racute", KeySym::Racute),
+    ("Abreve", KeySym::Abreve),
+    ("Lacute", KeySym::Lacute),
+    ("Cacute", KeySym::Cacute),
+    ("Ccaron", KeySym::Ccaron),
+    ("Eogonek", KeySym::Eogonek),
+    ("Ecaron", KeySym::Ecaron),
+    ("Dcaron", KeySym::Dcaron),
+    ("Dstroke", KeySym::Dstroke),
+    ("Nacute", KeySym::Nacute),
+    ("Ncaron", KeySym::Ncaron),
+    ("Odoubleacute", KeySym::Odoubleacute),
+    ("Rcaron", KeySym::Rcaron),
+    ("Uring", KeySym::Uring),
+    ("Udoubleacute", KeySym::Udoubleacute),
+    ("Tcedilla", KeySym::Tcedilla),
+    ("racute", KeySym::racute),
+    ("abreve", KeySym::abreve),
+    ("lacute", KeySym::lacute),
+    ("cacute", KeySym::cacute),
+    ("ccaron", KeySym::ccaron),
+    ("eogonek", KeySym::eogonek),
+    ("ecaron", KeySym::ecaron),
+    ("dcaron", KeySym::dcaron),
+    ("dstroke", KeySym::dstroke),
+    ("nacute", KeySym::nacute),
+    ("ncaron", KeySym::ncaron),
+    ("odoubleacute", KeySym::odoubleacute),
+    ("udoubleacute", KeySym::udoubleacute),
+    ("rcaron", KeySym::rcaron),
+    ("uring", KeySym::uring),
+    ("tcedilla", KeySym::tcedilla),
+    ("abovedot", KeySym::abovedot),
+    ("Hstroke", KeySym::Hstroke),
+    ("Hcircumflex", KeySym::Hcircumflex),
+    ("Iabovedot", KeySym::Iabovedot),
+    ("Gbreve", KeySym::Gbreve),
+    ("Jcircumflex", KeySym::Jcircumflex),
+    ("hstroke", KeySym::hstroke),
+    ("hcircumflex", KeySym::hcircumflex),
+    ("idotless", KeySym::idotless),
+    ("gbreve", KeySym::gbreve),
+    ("jcircumflex", KeySym::jcircumflex),
+    ("Cabovedot", KeySym::Cabovedot),
+    ("Ccircumflex", KeySym::Ccircumflex),
+    ("Gabovedot", KeySym::Gabovedot),
+    ("Gcircumflex", KeySym::Gcircumflex),
+    ("Ubreve", KeySym::Ubreve),
+    ("Scircumflex", KeySym::Scircumflex),
+    ("cabovedot", KeySym::cabovedot),
+    ("ccircumflex", KeySym::ccircumflex),
+    ("gabovedot", KeySym::gabovedot),
+    ("gcircumflex", KeySym::gcircumflex),
+    ("ubreve", KeySym::ubreve),
+    ("scircumflex", KeySym::scircumflex),
+    ("kra", KeySym::kra),
+    ("kappa", KeySym::kappa),
+    ("Rcedilla", KeySym::Rcedilla),
+    ("Itilde", KeySym::Itilde),
+    ("Lcedilla", KeySym::Lcedilla),
+    ("Emacron", KeySym::Emacron),
+    ("Gcedilla", KeySym::Gcedilla),
+    ("Tslash", KeySym::Tslash),
+    ("rcedilla", KeySym::rcedilla),
+    ("itilde", KeySym::itilde),
+    ("lcedilla", KeySym::lcedilla),
+    ("emacron", KeySym::emacron),
+    ("gcedilla", KeySym::gcedilla),
+    ("tslash", KeySym::tslash),
+    ("ENG", KeySym::ENG),
+    ("eng", KeySym::eng),
+    ("Amacron", KeySym::Amacron),
+    ("Iogonek", KeySym::Iogonek),
+    ("Eabovedot", KeySym::Eabovedot),
+    ("Imacron", KeySym::Imacron),
+    ("Ncedilla", KeySym::Ncedilla),
+    ("Omacron", KeySym::Omacron),
+    ("Kcedilla", KeySym::Kcedilla),
+    ("Uogonek", KeySym::Uogonek),
+    ("Utilde", KeySym::Utilde),
+    ("Umacron", KeySym::Umacron),
+    ("amacron", KeySym::amacron),
+    ("iogonek", KeySym::iogonek),
+    ("eabovedot", KeySym::eabovedot),
+    ("imacron", KeySym::imacron),
+    ("ncedilla", KeySym::ncedilla),
+    ("omacron", KeySym::omacron),
+    ("kcedilla", KeySym::kcedilla),
+    ("uogonek", KeySym::uogonek),
+    ("utilde", KeySym::utilde),
+    ("umacron", KeySym::umacron),
+    ("Babovedot", KeySym::Babovedot),
+    ("babovedot", KeySym::babovedot),
+    ("Dabovedot", KeySym::Dabovedot),
+    ("Wgrave", KeySym::Wgrave),
+    ("Wacute", KeySym::Wacute),
+    ("dabovedot", KeySym::dabovedot),
+    ("Ygrave", KeySym::Ygrave),
+    ("Fabovedot", KeySym::Fabovedot),
+    ("fabovedot", KeySym::fabovedot),
+    ("Mabovedot", KeySym::Mabovedot),
+    ("mabovedot", KeySym::mabovedot),
+    ("Pabovedot", KeySym::Pabovedot),
+    ("wgrave", KeySym::wgrave),
+    ("pabovedot", KeySym::pabovedot),
+    ("wacute", KeySym::wacute),
+    ("Sabovedot", KeySym::Sabovedot),
+    ("ygrave", KeySym::ygrave),
+    ("Wdiaeresis", KeySym::Wdiaeresis),
+    ("wdiaeresis", KeySym::wdiaeresis),
+    ("sabovedot", KeySym::sabovedot),
+    ("Wcircumflex", KeySym::Wcircumflex),
+    ("Tabovedot", KeySym::Tabovedot),
+    ("Ycircumflex", KeySym::Ycircumflex),
+    ("wcircumflex", KeySym::wcircumflex),
+    ("tabovedot", KeySym::tabovedot),
+    ("ycircumflex", KeySym::ycircumflex),
+    ("OE", KeySym::OE),
+    ("oe", KeySym::oe),
+    ("Ydiaeresis", KeySym::Ydiaeresis),
+    ("overline", KeySym::overline),
+    ("kana_fullstop", KeySym::kana_fullstop),
+    ("kana_openingbracket", KeySym::kana_openingbracket),
+    ("kana_closingbracket", KeySym::kana_closingbracket),
+    ("kana_comma", KeySym::kana_comma),
+    ("kana_conjunctive", KeySym::kana_conjunctive),
+    ("kana_middledot", KeySym::kana_middledot),
+    ("kana_WO", KeySym::kana_WO),
+    ("kana_a", KeySym::kana_a),
+    ("kana_i", KeySym::kana_i),
+    ("kana_u", KeySym::kana_u),
+    ("kana_e", KeySym::kana_e),
+    ("kana_o", KeySym::kana_o),
+    ("kana_ya", KeySym::kana_ya),
+    ("kana_yu", KeySym::kana_yu),
+    ("kana_yo", KeySym::kana_yo),
+    ("kana_tsu", KeySym::kana_tsu),
+    ("kana_tu", KeySym::kana_tu),
+    ("prolongedsound", KeySym::prolongedsound),
+    ("kana_A", KeySym::kana_A),
+    ("kana_I", KeySym::kana_I),
+    ("kana_U", KeySym::kana_U),
+    ("kana_E", KeySym::kana_E),
+    ("kana_O", KeySym::kana_O),
+    ("kana_KA", KeySym::kana_KA),
+    ("kana_KI", KeySym::kana_KI),
+    ("kana_KU", KeySym::kana_KU),
+    ("kana_KE", KeySym::kana_KE),
+    ("kana_KO", KeySym::kana_KO),
+    ("kana_SA", KeySym::kana_SA),
+    ("kana_SHI", KeySym::kana_SHI),
+    ("kana_SU", KeySym::kana_SU),
+    ("kana_SE", KeySym::kana_SE),
+    ("kana_SO", KeySym::kana_SO),
+    ("kana_TA", KeySym::kana_TA),
+    ("kana_CHI", KeySym::kana_CHI),
+    ("kana_TI", KeySym::kana_TI),
+    ("kana_TSU", KeySym::kana_TSU),
+    ("kana_TU", KeySym::kana_TU),
+    ("kana_TE", KeySym::kana_TE),
+    ("kana_TO", KeySym::kana_TO),
+    ("kana_NA", KeySym::kana_NA),
+    ("kana_NI", KeySym::kana_NI),
+    ("kana_NU", KeySym::kana_NU),
+    ("kana_NE", KeySym::kana_NE),
+    ("kana_NO", KeySym::kana_NO),
+    ("kana_HA", KeySym::kana_HA),
+    ("kana_HI", KeySym::kana_HI),
+    ("kana_FU", KeySym::kana_FU),
+    ("kana_HU", KeySym::kana_HU),
+    ("kana_HE", KeySym::kana_HE),
+    ("kana_HO", KeySym::kana_HO),
+    ("kana_MA", KeySym::kana_MA),
+    ("kana_MI", KeySym::kana_MI),
+    ("kana_MU", KeySym::kana_MU),
+    ("kana_ME", KeySym::kana_ME),
+    ("kana_MO", KeySym::kana_MO),
+    ("kana_YA", KeySym::kana_YA),
+    ("kana_YU", KeySym::kana_YU),
+    ("kana_YO", KeySym::kana_YO),
+    ("kana_RA", KeySym::kana_RA),
+    ("kana_RI", KeySym::kana_RI),
+    ("kana_RU", KeySym::kana_RU),
+    ("kana_RE", KeySym::kana_RE),
+    ("kana_RO", KeySym::kana_RO),
+    ("kana_WA", KeySym::kana_WA),
+    ("kana_N", KeySym::kana_N),
+    ("voicedsound", KeySym::voicedsound),
+    ("semivoicedsound", KeySym::semivoicedsound),
+    ("kana_switch", KeySym::kana_switch),
+    ("Farsi_0", KeySym::Farsi_0),
+    ("Farsi_1", KeySym::Farsi_1),
+    ("Farsi_2", KeySym::Farsi_2),
+    ("Farsi_3", KeySym::Farsi_3),
+    ("Farsi_4", KeySym::Farsi_4),
+    ("Farsi_5", KeySym::Farsi_5),
+    ("Farsi_6", KeySym::Farsi_6),
+    ("Farsi_7", KeySym::Farsi_7),
+    ("Farsi_8", KeySym::Farsi_8),
+    ("Farsi_9", KeySym::Farsi_9),
+    ("Arabic_percent", KeySym::Arabic_percent),
+    ("Arabic_superscript_alef", KeySym::Arabic_superscript_alef),
+    ("Arabic_tteh", KeySym::Arabic_tteh),
+    ("Arabic_peh", KeySym::Arabic_peh),
+    ("Arabic_tcheh", KeySym::Arabic_tcheh),
+    ("Arabic_ddal", KeySym::Arabic_ddal),
+    ("Arabic_rreh", KeySym::Arabic_rreh),
+    ("Arabic_comma", KeySym::Arabic_comma),
+    ("Arabic_fullstop", KeySym::Arabic_fullstop),
+    ("Arabic_0", KeySym::Arabic_0),
+    ("Arabic_1", KeySym::Arabic_1),
+    ("Arabic_2", KeySym::Arabic_2),
+    ("Arabic_3", KeySym::Arabic_3),
+    ("Arabic_4", KeySym::Arabic_4),
+    ("Arabic_5", KeySym::Arabic_5),
+    ("Arabic_6", KeySym::Arabic_6),
+    ("Arabic_7", KeySym::Arabic_7),
+    ("Arabic_8", KeySym::Arabic_8),
+    ("Arabic_9", KeySym::Arabic_9),
+    ("Arabic_semicolon", KeySym::Arabic_semicolon),
+    ("Arabic_question_mark", KeySym::Arabic_question_mark),
+    ("Arabic_hamza", KeySym::Arabic_hamza),
+    ("Arabic_maddaonalef", KeySym::Arabic_maddaonalef),
+    ("Arabic_hamzaonalef", KeySym::Arabic_hamzaonalef),
+    ("Arabic_hamzaonwaw", KeySym::Arabic_hamzaonwaw),
+    ("Arabic_hamzaunderalef", KeySym::Arabic_hamzaunderalef),
+    ("Arabic_hamzaonyeh", KeySym::Arabic_hamzaonyeh),
+    ("Arabic_alef", KeySym::Arabic_alef),
+    ("Arabic_beh", KeySym::Arabic_beh),
+    ("Arabic_tehmarbuta", KeySym::Arabic_tehmarbuta),
+    ("Arabic_teh", KeySym::Arabic_teh),
+    ("Arabic_theh", KeySym::Arabic_theh),
+    ("Arabic_jeem", KeySym::Arabic_jeem),
+    ("Arabic_hah", KeySym::Arabic_hah),
+    ("Arabic_khah", KeySym::Arabic_khah),
+    ("Arabic_dal", KeySym::Arabic_dal),
+    ("Arabic_thal", KeySym::Arabic_thal),
+    ("Arabic_ra", KeySym::Arabic_ra),
+    ("Arabic_zain", KeySym::Arabic_zain),
+    ("Arabic_seen", KeySym::Arabic_seen),
+    ("Arabic_sheen", KeySym::Arabic_sheen),
+    ("Arabic_sad", KeySym::Arabic_sad),
+    ("Arabic_dad", KeySym::Arabic_dad),
+    ("Arabic_tah", KeySym::Arabic_tah),
+    ("Arabic_zah", KeySym::Arabic_zah),
+    ("Arabic_ain", KeySym::Arabic_ain),
+    ("Arabic_ghain", KeySym::Arabic_ghain),
+    ("Arabic_tatweel", KeySym::Arabic_tatweel),
+    ("Arabic_feh", KeySym::Arabic_feh),
+    ("Arabic_qaf", KeySym::Arabic_qaf),
+    ("Arabic_kaf", KeySym::Arabic_kaf),
+    ("Arabic_lam", KeySym::Arabic_lam),
+    ("Arabic_meem", KeySym::Arabic_meem),
+    ("Arabic_noon", KeySym::Arabic_noon),
+    ("Arabic_ha", KeySym::Arabic_ha),
+    ("Arabic_heh", KeySym::Arabic_heh),
+    ("Arabic_waw", KeySym::Arabic_waw),
+    ("Arabic_alefmaksura", KeySym::Arabic_alefmaksura),
+    ("Arabic_yeh", KeySym::Arabic_yeh),
+    ("Arabic_fathatan", KeySym::Arabic_fathatan),
+    ("Arabic_dammatan", KeySym::Arabic_dammatan),
+    ("Arabic_kasratan", KeySym::Arabic_kasratan),
+    ("Arabic_fatha", KeySym::Arabic_fatha),
+    ("Arabic_damma", KeySym::Arabic_damma),
+    ("Arabic_kasra", KeySym::Arabic_kasra),
+    ("Arabic_shadda", KeySym::Arabic_shadda),
+    ("Arabic_sukun", KeySym::Arabic_sukun),
+    ("Arabic_madda_above", KeySym::Arabic_madda_above),
+    ("Arabic_hamza_above", KeySym::Arabic_hamza_above),
+    ("Arabic_hamza_below", KeySym::Arabic_hamza_below),
+    ("Arabic_jeh", KeySym::Arabic_jeh),
+    ("Arabic_veh", KeySym::Arabic_veh),
+    ("Arabic_keheh", KeySym::Arabic_keheh),
+    ("Arabic_gaf", KeySym::Arabic_gaf),
+    ("Arabic_noon_ghunna", KeySym::Arabic_noon_ghunna),
+    ("Arabic_heh_doachashmee", KeySym::Arabic_heh_doachashmee),
+    ("Farsi_yeh", KeySym::Farsi_yeh),
+    ("Arabic_farsi_yeh", KeySym::Arabic_farsi_yeh),
+    ("Arabic_yeh_baree", KeySym::Arabic_yeh_baree),
+    ("Arabic_heh_goal", KeySym::Arabic_heh_goal),
+    ("Arabic_switch", KeySym::Arabic_switch),
+    ("Cyrillic_GHE_bar", KeySym::Cyrillic_GHE_bar),
+    ("Cyrillic_ghe_bar", KeySym::Cyrillic_ghe_bar),
+    ("Cyrillic_ZHE_descender", KeySym::Cyrillic_ZHE_descender),
+    ("Cyrillic_zhe_descender", KeySym::Cyrillic_zhe_descender),
+    ("Cyrillic_KA_descender", KeySym::Cyrillic_KA_descender),
+    ("Cyrillic_ka_descender", KeySym::Cyrillic_ka_descender),
+    ("Cyrillic_KA_vertstroke", KeySym::Cyrillic_KA_vertstroke),
+    ("Cyrillic_ka_vertstroke", KeySym::Cyrillic_ka_vertstroke),
+    ("Cyrillic_EN_descender", KeySym::Cyrillic_EN_descender),
+    ("Cyrillic_en_descender", KeySym::Cyrillic_en_descender),
+    ("Cyrillic_U_straight", KeySym::Cyrillic_U_straight),
+    ("Cyrillic_u_straight", KeySym::Cyrillic_u_straight),
+    ("Cyrillic_U_straight_bar", KeySym::Cyrillic_U_straight_bar),
+    ("Cyrillic_u_straight_bar", KeySym::Cyrillic_u_straight_bar),
+    ("Cyrillic_HA_descender", KeySym::Cyrillic_HA_descender),
+    ("Cyrillic_ha_descender", KeySym::Cyrillic_ha_descender),
+    ("Cyrillic_CHE_descender", KeySym::Cyrillic_CHE_descender),
+    ("Cyrillic_che_descender", KeySym::Cyrillic_che_descender),
+    ("Cyrillic_CHE_vertstroke", KeySym::Cyrillic_CHE_vertstroke),
+    ("Cyrillic_che_vertstroke", KeySym::Cyrillic_che_vertstroke),
+    ("Cyrillic_SHHA", KeySym::Cyrillic_SHHA),
+    ("Cyrillic_shha", KeySym::Cyrillic_shha),
+    ("Cyrillic_SCHWA", KeySym::Cyrillic_SCHWA),
+    ("Cyrillic_schwa", KeySym::Cyrillic_schwa),
+    ("Cyrillic_I_macron", KeySym::Cyrillic_I_macron),
+    ("Cyrillic_i_macron", KeySym::Cyrillic_i_macron),
+    ("Cyrillic_O_bar", KeySym::Cyrillic_O_bar),
+    ("Cyrillic_o_bar", KeySym::Cyrillic_o_bar),
+    ("Cyrillic_U_macron", KeySym::Cyrillic_U_macron),
+    ("Cyrillic_u_macron", KeySym::Cyrillic_u_macron),
+    ("Serbian_dje", KeySym::Serbian_dje),
+    ("Macedonia_gje", KeySym::Macedonia_gje),
+    ("Cyrillic_io", KeySym::Cyrillic_io),
+    ("Ukrainian_ie", KeySym::Ukrainian_ie),
+    ("Ukranian_je", KeySym::Ukranian_je),
+    ("Macedonia_dse", KeySym::Macedonia_dse),
+    ("Ukrainian_i", KeySym::Ukrainian_i),
+    ("Ukranian_i", KeySym::Ukranian_i),
+    ("Ukrainian_yi", KeySym::Ukrainian_yi),
+    ("Ukranian_yi", KeySym::Ukranian_yi),
+    ("Cyrillic_je", KeySym::Cyrillic_je),
+    ("Serbian_je", KeySym::Serbian_je),
+    ("Cyrillic_lje", KeySym::Cyrillic_lje),
+    ("Serbian_lje", KeySym::Serbian_lje),
+    ("Cyrillic_nje", KeySym::Cyrillic_nje),
+    ("Serbian_nje", KeySym::Serbian_nje),
+    ("Serbian_tshe", KeySym::Serbian_tshe),
+    ("Macedonia_kje", KeySym::Macedonia_kje),
+    (
+        "Ukrainian_ghe_with_upturn",
+        KeySym::Ukrainian_ghe_with_upturn,
+    ),
+    ("Byelorussian_shortu", KeySym::Byelorussian_shortu),
+    ("Cyrillic_dzhe", KeySym::Cyrillic_dzhe),
+    ("Serbian_dze", KeySym::Serbian_dze),
+    ("numerosign", KeySym::numerosign),
+    ("Serbian_DJE", KeySym::Serbian_DJE),
+    ("Macedonia_GJE", KeySym::Macedonia_GJE),
+    ("Cyrillic_IO", KeySym::Cyrillic_IO),
+    ("Ukrainian_IE", KeySym::Ukrainian_IE),
+    ("Ukranian_JE", KeySym::Ukranian_JE),
+    ("Macedonia_DSE", KeySym::Macedonia_DSE),
+    ("Ukrainian_I", KeySym::Ukrainian_I),
+    ("Ukranian_I", KeySym::Ukranian_I),
+    ("Ukrainian_YI", KeySym::Ukrainian_YI),
+    ("Ukranian_YI", KeySym::Ukranian_YI),
+    ("Cyrillic_JE", KeySym::Cyrillic_JE),
+    ("Serbian_JE", KeySym::Serbian_JE),
+    ("Cyrillic_LJE", KeySym::Cyrillic_LJE),
+    ("Serbian_LJE", KeySym::Serbian_LJE),
+    ("Cyrillic_NJE", KeySym::Cyrillic_NJE),
+    ("Serbian_NJE", KeySym::Serbian_NJE),
+    ("Serbian_TSHE", KeySym::Serbian_TSHE),
+    ("Macedonia_KJE", KeySym::Macedonia_KJE),
+    (
+        "Ukrainian_GHE_WITH_UPTURN",
+        KeySym::Ukrainian_GHE_WITH_UPTURN,
+    ),
+    ("Byelorussian_SHORTU", KeySym::Byelorussian_SHORTU),
+    ("Cyrillic_DZHE", KeySym::Cyrillic_DZHE),
+    ("Serbian_DZE", KeySym::Serbian_DZE),
+    ("Cyrillic_yu", KeySym::Cyrillic_yu),
+    ("Cyrillic_a", KeySym::Cyrillic_a),
+    ("Cyrillic_be", KeySym::Cyrillic_be),
+    ("Cyrillic_tse", KeySym::Cyrillic_tse),
+    ("Cyrillic_de", KeySym::Cyrillic_de),
+    ("Cyrillic_ie", KeySym::Cyrillic_ie),
+    ("Cyrillic_ef", KeySym::Cyrillic_ef),
+    ("Cyrillic_ghe", KeySym::Cyrillic_ghe),
+    ("Cyrillic_ha", KeySym::Cyrillic_ha),
+    ("Cyrillic_i", KeySym::Cyrillic_i),
+    ("Cyrillic_shorti", KeySym::Cyrillic_shorti),
+    ("Cyrillic_ka", KeySym::Cyrillic_ka),
+    ("Cyrillic_el", KeySym::Cyrillic_el),
+    ("Cyrillic_em", KeySym::Cyrillic_em),
+    ("Cyrillic_en", KeySym::Cyrillic_en),
+    ("Cyrillic_o", KeySym::Cyrillic_o),
+    ("Cyrillic_pe", KeySym::Cyrillic_pe),
+    ("Cyrillic_ya", KeySym::Cyrillic_ya),
+    ("Cyrillic_er", KeySym::Cyrillic_er),
+    ("Cyrillic_es", KeySym::Cyrillic_es),
+    ("Cyrillic_te", KeySym::Cyrillic_te),
+    ("Cyrillic_u", KeySym::Cyrillic_u),
+    ("Cyrillic_zhe", KeySym::Cyrillic_zhe),
+    ("Cyrillic_ve", KeySym::Cyrillic_ve),
+    ("Cyrillic_softsign", KeySym::Cyrillic_softsign),
+    ("Cyrillic_yeru", KeySym::Cyrillic_yeru),
+    ("Cyrillic_ze", KeySym::Cyrillic_ze),
+    ("Cyrillic_sha", KeySym::Cyrillic_sha),
+    ("Cyrillic_e", KeySym::Cyrillic_e),
+    ("Cyrillic_shcha", KeySym::Cyrillic_shcha),
+    ("Cyrillic_che", KeySym::Cyrillic_che),
+    ("Cyrillic_hardsign", KeySym::Cyrillic_hardsign),
+    ("Cyrillic_YU", KeySym::Cyrillic_YU),
+    ("Cyrillic_A", KeySym::Cyrillic_A),
+    ("Cyrillic_BE", KeySym::Cyrillic_BE),
+    ("Cyrillic_TSE", KeySym::Cyrillic_TSE),
+    ("Cyrillic_DE", KeySym::Cyrillic_DE),
+    ("Cyrillic_IE", KeySym::Cyrillic_IE),
+    ("Cyrillic_EF", KeySym::Cyrillic_EF),
+    ("Cyrillic_GHE", KeySym::Cyrillic_GHE),
+    ("Cyrillic_HA", KeySym::Cyrillic_HA),
+    ("Cyrillic_I", KeySym::Cyrillic_I),
+    ("Cyrillic_SHORTI", KeySym::Cyrillic_SHORTI),
+    ("Cyrillic_KA", KeySym::Cyrillic_KA),
+    ("Cyrillic_EL", KeySym::Cyrillic_EL),
+    ("Cyrillic_EM", KeySym::Cyrillic_EM),
+    ("Cyrillic_EN", KeySym::Cyrillic_EN),
+    ("Cyrillic_O", KeySym::Cyrillic_O),
+    ("Cyrillic_PE", KeySym::Cyrillic_PE),
+    ("Cyrillic_YA", KeySym::Cyrillic_YA),
+    ("Cyrillic_ER", KeySym::Cyrillic_ER),
+    ("Cyrillic_ES", KeySym::Cyrillic_ES),
+    ("Cyrillic_TE", KeySym::Cyrillic_TE),
+    ("Cyrillic_U", KeySym::Cyrillic_U),
+    ("Cyrillic_ZHE", KeySym::Cyrillic_ZHE),
+    ("Cyrillic_VE", KeySym::Cyrillic_VE),
+    ("Cyrillic_SOFTSIGN", KeySym::Cyrillic_SOFTSIGN),
+    ("Cyrillic_YERU", KeySym::Cyrillic_YERU),
+    ("Cyrillic_ZE", KeySym::Cyrillic_ZE),
+    ("Cyrillic_SHA", KeySym::Cyrillic_SHA),
+    ("Cyrillic_E", KeySym::Cyrillic_E),
+    ("Cyrillic_SHCHA", KeySym::Cyrillic_SHCHA),
+    ("Cyrillic_CHE", KeySym::Cyrillic_CHE),
+    ("Cyrillic_HARDSIGN", KeySym::Cyrillic_HARDSIGN),
+    ("Greek_ALPHAaccent", KeySym::Greek_ALPHAaccent),
+    ("Greek_EPSILONaccent", KeySym::Greek_EPSILONaccent),
+    ("Greek_ETAaccent", KeySym::Greek_ETAaccent),
+    ("Greek_IOTAaccent", KeySym::Greek_IOTAaccent),
+    ("Greek_IOTAdieresis", KeySym::Greek_IOTAdieresis),
+    ("Greek_IOTAdiaeresis", KeySym::Greek_IOTAdiaeresis),
+    ("Greek_OMICRONaccent", KeySym::Greek_OMICRONaccent),
+    ("Greek_UPSILONaccent", KeySym::Greek_UPSILONaccent),
+    ("Greek_UPSILONdieresis", KeySym::Greek_UPSILONdieresis),
+    ("Greek_OMEGAaccent", KeySym::Greek_OMEGAaccent),
+    ("Greek_accentdieresis", KeySym::Greek_accentdieresis),
+    ("Greek_horizbar", KeySym::Greek_horizbar),
+    ("Greek_alphaaccent", KeySym::Greek_alphaaccent),
+    ("Greek_epsilonaccent", KeySym::Greek_epsilonaccent),
+    ("Greek_etaaccent", KeySym::Greek_etaaccent),
+    ("Greek_iotaaccent", KeySym::Greek_iotaaccent),
+    ("Greek_iotadieresis", KeySym::Greek_iotadieresis),
+    ("Greek_iotaaccentdieresis", KeySym::Greek_iotaaccentdieresis),
+    ("Greek_omicronaccent", KeySym::Greek_omicronaccent),
+    ("Greek_upsilonaccent", KeySym::Greek_upsilonaccent),
+    ("Greek_upsilondieresis", KeySym::Greek_upsilondieresis),
+    (
+        "Greek_upsilonaccentdieresis",
+        KeySym::Greek_upsilonaccentdieresis,
+    ),
+    ("Greek_omegaaccent", KeySym::Greek_omegaaccent),
+    ("Greek_ALPHA", KeySym::Greek_ALPHA),
+    ("Greek_BETA", KeySym::Greek_BETA),
+    ("Greek_GAMMA", KeySym::Greek_GAMMA),
+    ("Greek_DELTA", KeySym::Greek_DELTA),
+    ("Greek_EPSILON", KeySym::Greek_EPSILON),
+    ("Greek_ZETA", KeySym::Greek_ZETA),
+    ("Greek_ETA", KeySym::Greek_ETA),
+    ("Greek_THETA", KeySym::Greek_THETA),
+    ("Greek_IOTA", KeySym::Greek_IOTA),
+    ("Greek_KAPPA", KeySym::Greek_KAPPA),
+    ("Greek_LAMDA", KeySym::Greek_LAMDA),
+    ("Greek_LAMBDA", KeySym::Greek_LAMBDA),
+    ("Greek_MU", KeySym::Greek_MU),
+    ("Greek_NU", KeySym::Greek_NU),
+    ("Greek_XI", KeySym::Greek_XI),
+    ("Greek_OMICRON", KeySym::Greek_OMICRON),
+    ("Greek_PI", KeySym::Greek_PI),
+    ("Greek_RHO", KeySym::Greek_RHO),
+    ("Greek_SIGMA", KeySym::Greek_SIGMA),
+    ("Greek_TAU", KeySym::Greek_TAU),
+    ("Greek_UPSILON", KeySym::Greek_UPSILON),
+    ("Greek_PHI", KeySym::Greek_PHI),
+    ("Greek_CHI", KeySym::Greek_CHI),
+    ("Greek_PSI", KeySym::Greek_PSI),
+    ("Greek_OMEGA", KeySym::Greek_OMEGA),
+    ("Greek_alpha", KeySym::Greek_alpha),
+    ("Greek_beta", KeySym::Greek_beta),
+    ("Greek_gamma", KeySym::Greek_gamma),
+    ("Greek_delta", KeySym::Greek_delta),
+    ("Greek_epsilon", KeySym::Greek_epsilon),
+    ("Greek_zeta", KeySym::Greek_zeta),
+    ("Greek_eta", KeySym::Greek_eta),
+    ("Greek_theta", KeySym::Greek_theta),
+    ("Greek_iota", KeySym::Greek_iota),
+    ("Greek_kappa", KeySym::Greek_kappa),
+    ("Greek_lamda", KeySym::Greek_lamda),
+    ("Greek_lambda", KeySym::Greek_lambda),
+    ("Greek_mu", KeySym::Greek_mu),
+    ("Greek_nu", KeySym::Greek_nu),
+    ("Greek_xi", KeySym::Greek_xi),
+    ("Greek_omicron", KeySym::Greek_omicron),
+    ("Greek_pi", KeySym::Greek_pi),
+    ("Greek_rho", KeySym::Greek_rho),
+    ("Greek_sigma", KeySym::Greek_sigma),
+    ("Greek_finalsmallsigma", KeySym::Greek_finalsmallsigma),
+    ("Greek_tau", KeySym::Greek_tau),
+    ("Greek_upsilon", KeySym::Greek_upsilon),
+    ("Greek_phi", KeySym::Greek_phi),
+    ("Greek_chi", KeySym::Greek_chi),
+    ("Greek_psi", KeySym::Greek_psi),
+    ("Greek_omega", KeySym::Greek_omega),
+    ("Greek_switch", KeySym::Greek_switch),
+    ("leftradical", KeySym::leftradical),
+    ("topleftradical", KeySym::topleftradical),
+    ("horizconnector", KeySym::horizconnector),
+    ("topintegral", KeySym::topintegral),
+    ("botintegral", KeySym::botintegral),
+    ("vertconnector", KeySym::vertconnector),
+    ("topleftsqbracket", KeySym::topleftsqbracket),
+    ("botleftsqbracket", KeySym::botleftsqbracket),
+    ("toprightsqbracket", KeySym::toprightsqbracket),
+    ("botrightsqbracket", KeySym::botrightsqbracket),
+    ("topleftparens", KeySym::topleftparens),
+    ("botleftparens", KeySym::botleftparens),
+    ("toprightparens", KeySym::toprightparens),
+    ("botrightparens", KeySym::botrightparens),
+    ("leftmiddlecurlybrace", KeySym::leftmiddlecurlybrace),
+    ("rightmiddlecurlybrace", KeySym::rightmiddlecurlybrace),
+    ("topleftsummation", KeySym::topleftsummation),
+    ("botleftsummation", KeySym::botleftsummation),
+    (
+        "topvertsummationconnector",
+        KeySym::topvertsummationconnector,
+    ),
+    (
+        "botvertsummationconnector",
+        KeySym::botvertsummationconnector,
+    ),
+    ("toprightsummation", KeySym::toprightsummation),
+    ("botrightsummation", KeySym::botrightsummation),
+    ("rightmiddlesummation", KeySym::rightmiddlesummation),
+    ("lessthanequal", KeySym::lessthanequal),
+    ("notequal", KeySym::notequal),
+    ("greaterthanequal", KeySym::greaterthanequal),
+    ("integral", KeySym::integral),
+    ("therefore", KeySym::therefore),
+    ("variation", KeySym::variation),
+    ("infinity", KeySym::infinity),
+    ("nabla", KeySym::nabla),
+    ("approximate", KeySym::approximate),
+    ("similarequal", KeySym::similarequal),
+    ("ifonlyif", KeySym::ifonlyif),
+    ("implies", KeySym::implies),
+    ("identical", KeySym::identical),
+    ("radical", KeySym::radical),
+    ("includedin", KeySym::includedin),
+    ("includes", KeySym::includes),
+    ("intersection", KeySym::intersection),
+    ("union", KeySym::union),
+    ("logicaland", KeySym::logicaland),
+    ("logicalor", KeySym::logicalor),
+    ("partialderivative", KeySym::partialderivative),
+    ("function", KeySym::function),
+    ("leftarrow", KeySym::leftarrow),
+    ("uparrow", KeySym::uparrow),
+    ("rightarrow", KeySym::rightarrow),
+    ("downarrow", KeySym::downarrow),
+    ("blank", KeySym::blank),
+    ("soliddiamond", KeySym::soliddiamond),
+    ("checkerboard", KeySym::checkerboard),
+    ("ht", KeySym::ht),
+    ("ff", KeySym::ff),
+    ("cr", KeySym::cr),
+    ("lf", KeySym::lf),
+    ("nl", KeySym::nl),
+    ("vt", KeySym::vt),
+    ("lowrightcorner", KeySym::lowrightcorner),
+    ("uprightcorner", KeySym::uprightcorner),
+    ("upleftcorner", KeySym::upleftcorner),
+    ("lowleftcorner", KeySym::lowleftcorner),
+    ("crossinglines", KeySym::crossinglines),
+    ("horizlinescan1", KeySym::horizlinescan1),
+    ("horizlinescan3", KeySym::horizlinescan3),
+    ("horizlinescan5", KeySym::horizlinescan5),
+    ("horizlinescan7", KeySym::horizlinescan7),
+    ("horizlinescan9", KeySym::horizlinescan9),
+    ("leftt", KeySym::leftt),
+    ("rightt", KeySym::rightt),
+    ("bott", KeySym::bott),
+    ("topt", KeySym::topt),
+    ("vertbar", KeySym::vertbar),
+    ("emspace", KeySym::emspace),
+    ("enspace", KeySym::enspace),
+    ("em3space", KeySym::em3space),
+    ("em4space", KeySym::em4space),
+    ("digitspace", KeySym::digitspace),
+    ("punctspace", KeySym::punctspace),
+    ("thinspace", KeySym::thinspace),
+    ("hairspace", KeySym::hairspace),
+    ("emdash", KeySym::emdash),
+    ("endash", KeySym::endash),
+    ("signifblank", KeySym::signifblank),
+    ("ellipsis", KeySym::ellipsis),
+    ("doubbaselinedot", KeySym::doubbaselinedot),
+    ("onethird", KeySym::onethird),
+    ("twothirds", KeySym::twothirds),
+    ("onefifth", KeySym::onefifth),
+    ("twofifths", KeySym::twofifths),
+    ("threefifths", KeySym::threefifths),
+    ("fourfifths", KeySym::fourfifths),
+    ("onesixth", KeySym::onesixth),
+    ("fivesixths", KeySym::fivesixths),
+    ("careof", KeySym::careof),
+    ("figdash", KeySym::figdash),
+    ("leftanglebracket", KeySym::leftanglebracket),
+    ("decimalpoint", KeySym::decimalpoint),
+    ("rightanglebracket", KeySym::rightanglebracket),
+    ("marker", KeySym::marker),
+    ("oneeighth", KeySym::oneeighth),
+    ("threeeighths", KeySym::threeeighths),
+    ("fiveeighths", KeySym::fiveeighths),
+    ("seveneighths", KeySym::seveneighths),
+    ("trademark", KeySym::trademark),
+    ("signaturemark", KeySym::signaturemark),
+    ("trademarkincircle", KeySym::trademarkincircle),
+    ("leftopentriangle", KeySym::leftopentriangle),
+    ("rightopentriangle", KeySym::rightopentriangle),
+    ("emopencircle", KeySym::emopencircle),
+    ("emopenrectangle", KeySym::emopenrectangle),
+    ("leftsinglequotemark", KeySym::leftsinglequotemark),
+    ("rightsinglequotemark", KeySym::rightsinglequotemark),
+    ("leftdoublequotemark", KeySym::leftdoublequotemark),
+    ("rightdoublequotemark", KeySym::rightdoublequotemark),
+    ("prescription", KeySym::prescription),
+    ("minutes", KeySym::minutes),
+    ("seconds", KeySym::seconds),
+    ("latincross", KeySym::latincross),
+    ("hexagram", KeySym::hexagram),
+    ("filledrectbullet", KeySym::filledrectbullet),
+    ("filledlefttribullet", KeySym::filledlefttribullet),
+    ("filledrighttribullet", KeySym::filledrighttribullet),
+    ("emfilledcircle", KeySym::emfilledcircle),
+    ("emfilledrect", KeySym::emfilledrect),
+    ("enopencircbullet", KeySym::enopencircbullet),
+    ("enopensquarebullet", KeySym::enopensquarebullet),
+    ("openrectbullet", KeySym::openrectbullet),
+    ("opentribulletup", KeySym::opentribulletup),
+    ("opentribulletdown", KeySym::opentribulletdown),
+    ("openstar", KeySym::openstar),
+    ("enfilledcircbullet", KeySym::enfilledcircbullet),
+    ("enfilledsqbullet", KeySym::enfilledsqbullet),
+    ("filledtribulletup", KeySym::filledtribulletup),
+    ("filledtribulletdown", KeySym::filledtribulletdown),
+    ("leftpointer", KeySym::leftpointer),
+    ("rightpointer", KeySym::rightpointer),
+    ("club", KeySym::club),
+    ("diamond", KeySym::diamond),
+    ("heart", KeySym::heart),
+    ("maltesecross", KeySym::maltesecross),
+    ("dagger", KeySym::dagger),
+    ("doubledagger", KeySym::doubledagger),
+    ("checkmark", KeySym::checkmark),
+    ("ballotcross", KeySym::ballotcross),
+    ("musicalsharp", KeySym::musicalsharp),
+    ("musicalflat", KeySym::musicalflat),
+    ("malesymbol", KeySym::malesymbol),
+    ("femalesymbol", KeySym::femalesymbol),
+    ("telephone", KeySym::telephone),
+    ("telephonerecorder", KeySym::telephonerecorder),
+    ("phonographcopyright", KeySym::phonographcopyright),
+    ("caret", KeySym::caret),
+    ("singlelowquotemark", KeySym::singlelowquotemark),
+    ("doublelowquotemark", KeySym::doublelowquotemark),
+    ("cursor", KeySym::cursor),
+    ("leftcaret", KeySym::leftcaret),
+    ("rightcaret", KeySym::rightcaret),
+    ("downcaret", KeySym::downcaret),
+    ("upcaret", KeySym::upcaret),
+    ("overbar", KeySym::overbar),
+    ("downtack", KeySym::downtack),
+    ("upshoe", KeySym::upshoe),
+    ("downstile", KeySym::downstile),
+    ("underbar", KeySym::underbar),
+    ("jot", KeySym::jot),
+    ("quad", KeySym::quad),
+    ("uptack", KeySym::uptack),
+    ("circle", KeySym::circle),
+    ("upstile", KeySym::upstile),
+    ("downshoe", KeySym::downshoe),
+    ("rightshoe", KeySym::rightshoe),
+    ("leftshoe", KeySym::leftshoe),
+    ("lefttack", KeySym::lefttack),
+    ("righttack", KeySym::righttack),
+    ("hebrew_doublelowline", KeySym::hebrew_doublelowline),
+    ("hebrew_aleph", KeySym::hebrew_aleph),
+    ("hebrew_bet", KeySym::hebrew_bet),
+    ("hebrew_beth", KeySym::hebrew_beth),
+    ("hebrew_gimel", KeySym::hebrew_gimel),
+    ("hebrew_gimmel", KeySym::hebrew_gimmel),
+    ("hebrew_dalet", KeySym::hebrew_dalet),
+    ("hebrew_daleth", KeySym::hebrew_daleth),
+    ("hebrew_he", KeySym::hebrew_he),
+    ("hebrew_waw", KeySym::hebrew_waw),
+    ("hebrew_zain", KeySym::hebrew_zain),
+    ("hebrew_zayin", KeySym::hebrew_zayin),
+    ("hebrew_chet", KeySym::hebrew_chet),
+    ("hebrew_het", KeySym::hebrew_het),
+    ("hebrew_tet", KeySym::hebrew_tet),
+    ("hebrew_teth", KeySym::hebrew_teth),
+    ("hebrew_yod", KeySym::hebrew_yod),
+    ("hebrew_finalkaph", KeySym::hebrew_finalkaph),
+    ("hebrew_kaph", KeySym::hebrew_kaph),
+    ("hebrew_lamed", KeySym::hebrew_lamed),
+    ("hebrew_finalmem", KeySym::hebrew_finalmem),
+    ("hebrew_mem", KeySym::hebrew_mem),
+    ("hebrew_finalnun", KeySym::hebrew_finalnun),
+    ("hebrew_nun", KeySym::hebrew_nun),
+    ("hebrew_samech", KeySym::hebrew_samech),
+    ("hebrew_samekh", KeySym::hebrew_samekh),
+    ("hebrew_ayin", KeySym::hebrew_ayin),
+    ("hebrew_finalpe", KeySym::hebrew_finalpe),
+    ("hebrew_pe", KeySym::hebrew_pe),
+    ("hebrew_finalzade", KeySym::hebrew_finalzade),
+    ("hebrew_finalzadi", KeySym::hebrew_finalzadi),
+    ("hebrew_zade", KeySym::hebrew_zade),
+    ("hebrew_zadi", KeySym::hebrew_zadi),
+    ("hebrew_qoph", KeySym::hebrew_qoph),
+    ("hebrew_kuf", KeySym::hebrew_kuf),
+    ("hebrew_resh", KeySym::hebrew_resh),
+    ("hebrew_shin", KeySym::hebrew_shin),
+    ("hebrew_taw", KeySym::hebrew_taw),
+    ("hebrew_taf", KeySym::hebrew_taf),
+    ("Hebrew_switch", KeySym::Hebrew_switch),
+    ("Thai_kokai", KeySym::Thai_kokai),
+    ("Thai_khokhai", KeySym::Thai_khokhai),
+    ("Thai_khokhuat", KeySym::Thai_khokhuat),
+    ("Thai_khokhwai", KeySym::Thai_khokhwai),
+    ("Thai_khokhon", KeySym::Thai_khokhon),
+    ("Thai_khorakhang", KeySym::Thai_khorakhang),
+    ("Thai_ngongu", KeySym::Thai_ngongu),
+    ("Thai_chochan", KeySym::Thai_chochan),
+    ("Thai_choching", KeySym::Thai_choching),
+    ("Thai_chochang", KeySym::Thai_chochang),
+    ("Thai_soso", KeySym::Thai_soso),
+    ("Thai_chochoe", KeySym::Thai_chochoe),
+    ("Thai_yoying", KeySym::Thai_yoying),
+    ("Thai_dochada", KeySym::Thai_dochada),
+    ("Thai_topatak", KeySym::Thai_topatak),
+    ("Thai_thothan", KeySym::Thai_thothan),
+    ("Thai_thonangmontho", KeySym::Thai_thonangmontho),
+    ("Thai_thophuthao", KeySym::Thai_thophuthao),
+    ("Thai_nonen", KeySym::Thai_nonen),
+    ("Thai_dodek", KeySym::Thai_dodek),
+    ("Thai_totao", KeySym::Thai_totao),
+    ("Thai_thothung", KeySym::Thai_thothung),
+    ("Thai_thothahan", KeySym::Thai_thothahan),
+    ("Thai_thothong", KeySym::Thai_thothong),
+    ("Thai_nonu", KeySym::Thai_nonu),
+    ("Thai_bobaimai", KeySym::Thai_bobaimai),
+    ("Thai_popla", KeySym::Thai_popla),
+    ("Thai_phophung", KeySym::Thai_phophung),
+    ("Thai_fofa", KeySym::Thai_fofa),
+    ("Thai_phophan", KeySym::Thai_phophan),
+    ("Thai_fofan", KeySym::Thai_fofan),
+    ("Thai_phosamphao", KeySym::Thai_phosamphao),
+    ("Thai_moma", KeySym::Thai_moma),
+    ("Thai_yoyak", KeySym::Thai_yoyak),
+    ("Thai_rorua", KeySym::Thai_rorua),
+    ("Thai_ru", KeySym::Thai_ru),
+    ("Thai_loling", KeySym::Thai_loling),
+    ("Thai_lu", KeySym::Thai_lu),
+    ("Thai_wowaen", KeySym::Thai_wowaen),
+    ("Thai_sosala", KeySym::Thai_sosala),
+    ("Thai_sorusi", KeySym::Thai_sorusi),
+    ("Thai_sosua", KeySym::Thai_sosua),
+    ("Thai_hohip", KeySym::Thai_hohip),
+    ("Thai_lochula", KeySym::Thai_lochula),
+    ("Thai_oang", KeySym::Thai_oang),
+    ("Thai_honokhuk", KeySym::Thai_honokhuk),
+    ("Thai_paiyannoi", KeySym::Thai_paiyannoi),
+    ("Thai_saraa", KeySym::Thai_saraa),
+    ("Thai_maihanakat", KeySym::Thai_maihanakat),
+    ("Thai_saraaa", KeySym::Thai_saraaa),
+    ("Thai_saraam", KeySym::Thai_saraam),
+    ("Thai_sarai", KeySym::Thai_sarai),
+    ("Thai_saraii", KeySym::Thai_saraii),
+    ("Thai_saraue", KeySym::Thai_saraue),
+    ("Thai_sarauee", KeySym::Thai_sarauee),
+    ("Thai_sarau", KeySym::Thai_sarau),
+    ("Thai_sarauu", KeySym::Thai_sarauu),
+    ("Thai_phinthu", KeySym::Thai_phinthu),
+    ("Thai_maihanakat_maitho", KeySym::Thai_maihanakat_maitho),
+    ("Thai_baht", KeySym::Thai_baht),
+    ("Thai_sarae", KeySym::Thai_sarae),
+    ("Thai_saraae", KeySym::Thai_saraae),
+    ("Thai_sarao", KeySym::Thai_sarao),
+    ("Thai_saraaimaimuan", KeySym::Thai_saraaimaimuan),
+    ("Thai_saraaimaimalai", KeySym::Thai_saraaimaimalai),
+    ("Thai_lakkhangyao", KeySym::Thai_lakkhangyao),
+    ("Thai_maiyamok", KeySym::Thai_maiyamok),
+    ("Thai_maitaikhu", KeySym::Thai_maitaikhu),
+    ("Thai_maiek", KeySym::Thai_maiek),
+    ("Thai_maitho", KeySym::Thai_maitho),
+    ("Thai_maitri", KeySym::Thai_maitri),
+    ("Thai_maichattawa", KeySym::Thai_maichattawa),
+    ("Thai_thanthakhat", KeySym::Thai_thanthakhat),
+    ("Thai_nikhahit", KeySym::Thai_nikhahit),
+    ("Thai_leksun", KeySym::Thai_leksun),
+    ("Thai_leknung", KeySym::Thai_leknung),
+    ("Thai_leksong", KeySym::Thai_leksong),
+    ("Thai_leksam", KeySym::Thai_leksam),
+    ("Thai_leksi", KeySym::Thai_leksi),
+    ("Thai_lekha", KeySym::Thai_lekha),
+    ("Thai_lekhok", KeySym::Thai_lekhok),
+    ("Thai_lekchet", KeySym::Thai_lekchet),
+    ("Thai_lekpaet", KeySym::Thai_lekpaet),
+    ("Thai_lekkao", KeySym::Thai_lekkao),
+    ("Hangul", KeySym::Hangul),
+    ("Hangul_Start", KeySym::Hangul_Start),
+    ("Hangul_End", KeySym::Hangul_End),
+    ("Hangul_Hanja", KeySym::Hangul_Hanja),
+    ("Hangul_Jamo", KeySym::Hangul_Jamo),
+    ("Hangul_Romaja", KeySym::Hangul_Romaja),
+    ("Hangul_Codeinput", KeySym::Hangul_Codeinput),
+    ("Hangul_Jeonja", KeySym::Hangul_Jeonja),
+    ("Hangul_Banja", KeySym::Hangul_Banja),
+    ("Hangul_PreHanja", KeySym::Hangul_PreHanja),
+    ("Hangul_PostHanja", KeySym::Hangul_PostHanja),
+    ("Hangul_SingleCandidate", KeySym::Hangul_SingleCandidate),
+    ("Hangul_MultipleCandidate", KeySym::Hangul_MultipleCandidate),
+    ("Hangul_PreviousCandidate", KeySym::Hangul_PreviousCandidate),
+    ("Hangul_Special", KeySym::Hangul_Special),
+    ("Hangul_switch", KeySym::Hangul_switch),
+    ("Hangul_Kiyeog", KeySym::Hangul_Kiyeog),
+    ("Hangul_SsangKiyeog", KeySym::Hangul_SsangKiyeog),
+    ("Hangul_KiyeogSios", KeySym::Hangul_KiyeogSios),
+    ("Hangul_Nieun", KeySym::Hangul_Nieun),
+    ("Hangul_NieunJieuj", KeySym::Hangul_NieunJieuj),
+    ("Hangul_NieunHieuh", KeySym::Hangul_NieunHieuh),
+    ("Hangul_Dikeud", KeySym::Hangul_Dikeud),
+    ("Hangul_SsangDikeud", KeySym::Hangul_SsangDikeud),
+    ("Hangul_Rieul", KeySym::Hangul_Rieul),
+    ("Hangul_RieulKiyeog", KeySym::Hangul_RieulKiyeog),
+    ("Hangul_RieulMieum", KeySym::Hangul_RieulMieum),
+    ("Hangul_RieulPieub", KeySym::Hangul_RieulPieub),
+    ("Hangul_RieulSios", KeySym::Hangul_RieulSios),
+    ("Hangul_RieulTieut", KeySym::Hangul_RieulTieut),
+    ("Hangul_RieulPhieuf", KeySym::Hangul_RieulPhieuf),
+    ("Hangul_RieulHieuh", KeySym::Hangul_RieulHieuh),
+    ("Hangul_Mieum", KeySym::Hangul_Mieum),
+    ("Hangul_Pieub", KeySym::Hangul_Pieub),
+    ("Hangul_SsangPieub", KeySym::Hangul_SsangPieub),
+    ("Hangul_PieubSios", KeySym::Hangul_PieubSios),
+    ("Hangul_Sios", KeySym::Hangul_Sios),
+    ("Hangul_SsangSios", KeySym::Hangul_SsangSios),
+    ("Hangul_Ieung", KeySym::Hangul_Ieung),
+    ("Hangul_Jieuj", KeySym::Hangul_Jieuj),
+    ("Hangul_SsangJieuj", KeySym::Hangul_SsangJieuj),
+    ("Hangul_Cieuc", KeySym::Hangul_Cieuc),
+    ("Hangul_Khieuq", KeySym::Hangul_Khieuq),
+    ("Hangul_Tieut", KeySym::Hangul_Tieut),
+    ("Hangul_Phieuf", KeySym::Hangul_Phieuf),
+    ("Hangul_Hieuh", KeySym::Hangul_Hieuh),
+    ("Hangul_A", KeySym::Hangul_A),
+    ("Hangul_AE", KeySym::Hangul_AE),
+    ("Hangul_YA", KeySym::Hangul_YA),
+    ("Hangul_YAE", KeySym::Hangul_YAE),
+    ("Hangul_EO", KeySym::Hangul_EO),
+    ("Hangul_E", KeySym::Hangul_E),
+    ("Hangul_YEO", KeySym::Hangul_YEO),
+    ("Hangul_YE", KeySym::Hangul_YE),
+    ("Hangul_O", KeySym::Hangul_O),
+    ("Hangul_WA", KeySym::Hangul_WA),
+    ("Hangul_WAE", KeySym::Hangul_WAE),
+    ("Hangul_OE", KeySym::Hangul_OE),
+    ("Hangul_YO", KeySym::Hangul_YO),
+    ("Hangul_U", KeySym::Hangul_U),
+    ("Hangul_WEO", KeySym::Hangul_WEO),
+    ("Hangul_WE", KeySym::Hangul_WE),
+    ("Hangul_WI", KeySym::Hangul_WI),
+    ("Hangul_YU", KeySym::Hangul_YU),
+    ("Hangul_EU", KeySym::Hangul_EU),
+    ("Hangul_YI", KeySym::Hangul_YI),
+    ("Hangul_I", KeySym::Hangul_I),
+    ("Hangul_J_Kiyeog", KeySym::Hangul_J_Kiyeog),
+    ("Hangul_J_SsangKiyeog", KeySym::Hangul_J_SsangKiyeog),
+    ("Hangul_J_KiyeogSios", KeySym::Hangul_J_KiyeogSios),
+    ("Hangul_J_Nieun", KeySym::Hangul_J_Nieun),
+    ("Hangul_J_NieunJieuj", KeySym::Hangul_J_NieunJieuj),
+    ("Hangul_J_NieunHieuh", KeySym::Hangul_J_NieunHieuh),
+    ("Hangul_J_Dikeud", KeySym::Hangul_J_Dikeud),
+    ("Hangul_J_Rieul", KeySym::Hangul_J_Rieul),
+    ("Hangul_J_RieulKiyeog", KeySym::Hangul_J_RieulKiyeog),
+    ("Hangul_J_RieulMieum", KeySym::Hangul_J_RieulMieum),
+    ("Hangul_J_RieulPieub", KeySym::Hangul_J_RieulPieub),
+    ("Hangul_J_RieulSios", KeySym::Hangul_J_RieulSios),
+    ("Hangul_J_RieulTieut", KeySym::Hangul_J_RieulTieut),
+    ("Hangul_J_RieulPhieuf", KeySym::Hangul_J_RieulPhieuf),
+    ("Hangul_J_RieulHieuh", KeySym::Hangul_J_RieulHieuh),
+    ("Hangul_J_Mieum", KeySym::Hangul_J_Mieum),
+    ("Hangul_J_Pieub", KeySym::Hangul_J_Pieub),
+    ("Hangul_J_PieubSios", KeySym::Hangul_J_PieubSios),
+    ("Hangul_J_Sios", KeySym::Hangul_J_Sios),
+    ("Hangul_J_SsangSios", KeySym::Hangul_J_SsangSios),
+    ("Hangul_J_Ieung", KeySym::Hangul_J_Ieung),
+    ("Hangul_J_Jieuj", KeySym::Hangul_J_Jieuj),
+    ("Hangul_J_Cieuc", KeySym::Hangul_J_Cieuc),
+    ("Hangul_J_Khieuq", KeySym::Hangul_J_Khieuq),
+    ("Hangul_J_Tieut", KeySym::Hangul_J_Tieut),
+    ("Hangul_J_Phieuf", KeySym::Hangul_J_Phieuf),
+    ("Hangul_J_Hieuh", KeySym::Hangul_J_Hieuh),
+    ("Hangul_RieulYeorinHieuh", KeySym::Hangul_RieulYeorinHieuh),
+    ("Hangul_SunkyeongeumMieum", KeySym::Hangul_SunkyeongeumMieum),
+    ("Hangul_SunkyeongeumPieub", KeySym::Hangul_SunkyeongeumPieub),
+    ("Hangul_PanSios", KeySym::Hangul_PanSios),
+    ("Hangul_KkogjiDalrinIeung", KeySym::Hangul_KkogjiDalrinIeung),
+    (
+        "Hangul_SunkyeongeumPhieuf",
+        KeySym::Hangul_SunkyeongeumPhieuf,
+    ),
+    ("Hangul_YeorinHieuh", KeySym::Hangul_YeorinHieuh),
+    ("Hangul_AraeA", KeySym::Hangul_AraeA),
+    ("Hangul_AraeAE", KeySym::Hangul_AraeAE),
+    ("Hangul_J_PanSios", KeySym::Hangul_J_PanSios),
+    (
+        "Hangul_J_KkogjiDalrinIeung",
+        KeySym::Hangul_J_KkogjiDalrinIeung,
+    ),
+    ("Hangul_J_YeorinHieuh", KeySym::Hangul_J_YeorinHieuh),
+    ("Korean_Won", KeySym::Korean_Won),
+    ("Armenian_ligature_ew", KeySym::Armenian_ligature_ew),
+    ("Armenian_full_stop", KeySym::Armenian_full_stop),
+    ("Armenian_verjaket", KeySym::Armenian_verjaket),
+    ("Armenian_separation_mark", KeySym::Armenian_separation_mark),
+    ("Armenian_but", KeySym::Armenian_but),
+    ("Armenian_hyphen", KeySym::Armenian_hyphen),
+    ("Armenian_yentamna", KeySym::Armenian_yentamna),
+    ("Armenian_exclam", KeySym::Armenian_exclam),
+    ("Armenian_amanak", KeySym::Armenian_amanak),
+    ("Armenian_accent", KeySym::Armenian_accent),
+    ("Armenian_shesht", KeySym::Armenian_shesht),
+    ("Armenian_question", KeySym::Armenian_question),
+    ("Armenian_paruyk", KeySym::Armenian_paruyk),
+    ("Armenian_AYB", KeySym::Armenian_AYB),
+    ("Armenian_ayb", KeySym::Armenian_ayb),
+    ("Armenian_BEN", KeySym::Armenian_BEN),
+    ("Armenian_ben", KeySym::Armenian_ben),
+    ("Armenian_GIM", KeySym::Armenian_GIM),
+    ("Armenian_gim", KeySym::Armenian_gim),
+    ("Armenian_DA", KeySym::Armenian_DA),
+    ("Armenian_da", KeySym::Armenian_da),
+    ("Armenian_YECH", KeySym::Armenian_YECH),
+    ("Armenian_yech", KeySym::Armenian_yech),
+    ("Armenian_ZA", KeySym::Armenian_ZA),
+    ("Armenian_za", KeySym::Armenian_za),
+    ("Armenian_E", KeySym::Armenian_E),
+    ("Armenian_e", KeySym::Armenian_e),
+    ("Armenian_AT", KeySym::Armenian_AT),
+    ("Armenian_at", KeySym::Armenian_at),
+    ("Armenian_TO", KeySym::Armenian_TO),
+    ("Armenian_to", KeySym::Armenian_to),
+    ("Armenian_ZHE", KeySym::Armenian_ZHE),
+    ("Armenian_zhe", KeySym::Armenian_zhe),
+    ("Armenian_INI", KeySym::Armenian_INI),
+    ("Armenian_ini", KeySym::Armenian_ini),
+    ("Armenian_LYUN", KeySym::Armenian_LYUN),
+    ("Armenian_lyun", KeySym::Armenian_lyun),
+    ("Armenian_KHE", KeySym::Armenian_KHE),
+    ("Armenian_khe", KeySym::Armenian_khe),
+    ("Armenian_TSA", KeySym::Armenian_TSA),
+    ("Armenian_tsa", KeySym::Armenian_tsa),
+    ("Armenian_KEN", KeySym::Armenian_KEN),
+    ("Armenian_ken", KeySym::Armenian_ken),
+    ("Armenian_HO", KeySym::Armenian_HO),
+    ("Armenian_ho", KeySym::Armenian_ho),
+    ("Armenian_DZA", KeySym::Armenian_DZA),
+    ("Armenian_dza", KeySym::Armenian_dza),
+    ("Armenian_GHAT", KeySym::Armenian_GHAT),
+    ("Armenian_ghat", KeySym::Armenian_ghat),
+    ("Armenian_TCHE", KeySym::Armenian_TCHE),
+    ("Armenian_tche", KeySym::Armenian_tche),
+    ("Armenian_MEN", KeySym::Armenian_MEN),
+    ("Armenian_men", KeySym::Armenian_men),
+    ("Armenian_HI", KeySym::Armenian_HI),
+    ("Armenian_hi", KeySym::Armenian_hi),
+    ("Armenian_NU", KeySym::Armenian_NU),
+    ("Armenian_nu", KeySym::Armenian_nu),
+    ("Armenian_SHA", KeySym::Armenian_SHA),
+    ("Armenian_sha", KeySym::Armenian_sha),
+    ("Armenian_VO", KeySym::Armenian_VO),
+    ("Armenian_vo", KeySym::Armenian_vo),
+    ("Armenian_CHA", KeySym::Armenian_CHA),
+    ("Armenian_cha", KeySym::Armenian_cha),
+    ("Armenian_PE", KeySym::Armenian_PE),
+    ("Armenian_pe", KeySym::Armenian_pe),
+    ("Armenian_JE", KeySym::Armenian_JE),
+    ("Armenian_je", KeySym::Armenian_je),
+    ("Armenian_RA", KeySym::Armenian_RA),
+    ("Armenian_ra", KeySym::Armenian_ra),
+    ("Armenian_SE", KeySym::Armenian_SE),
+    ("Armenian_se", KeySym::Armenian_se),
+    ("Armenian_VEV", KeySym::Armenian_VEV),
+    ("Armenian_vev", KeySym::Armenian_vev),
+    ("Armenian_TYUN", KeySym::Armenian_TYUN),
+    ("Armenian_tyun", KeySym::Armenian_tyun),
+    ("Armenian_RE", KeySym::Armenian_RE),
+    ("Armenian_re", KeySym::Armenian_re),
+    ("Armenian_TSO", KeySym::Armenian_TSO),
+    ("Armenian_tso", KeySym::Armenian_tso),
+    ("Armenian_VYUN", KeySym::Armenian_VYUN),
+    ("Armenian_vyun", KeySym::Armenian_vyun),
+    ("Armenian_PYUR", KeySym::Armenian_PYUR),
+    ("Armenian_pyur", KeySym::Armenian_pyur),
+    ("Armenian_KE", KeySym::Armenian_KE),
+    ("Armenian_ke", KeySym::Armenian_ke),
+    ("Armenian_O", KeySym::Armenian_O),
+    ("Armenian_o", KeySym::Armenian_o),
+    ("Armenian_FE", KeySym::Armenian_FE),
+    ("Armenian_fe", KeySym::Armenian_fe),
+    ("Armenian_apostrophe", KeySym::Armenian_apostrophe),
+    ("Georgian_an", KeySym::Georgian_an),
+    ("Georgian_ban", KeySym::Georgian_ban),
+    ("Georgian_gan", KeySym::Georgian_gan),
+    ("Georgian_don", KeySym::Georgian_don),
+    ("Georgian_en", KeySym::Georgian_en),
+    ("Georgian_vin", KeySym::Georgian_vin),
+    ("Georgian_zen", KeySym::Georgian_zen),
+    ("Georgian_tan", KeySym::Georgian_tan),
+    ("Georgian_in", KeySym::Georgian_in),
+    ("Georgian_kan", KeySym::Georgian_kan),
+    ("Georgian_las", KeySym::Georgian_las),
+    ("Georgian_man", KeySym::Georgian_man),
+    ("Georgian_nar", KeySym::Georgian_nar),
+    ("Georgian_on", KeySym::Georgian_on),
+    ("Georgian_par", KeySym::Georgian_par),
+    ("Georgian_zhar", KeySym::Georgian_zhar),
+    ("Georgian_rae", KeySym::Georgian_rae),
+    ("Georgian_san", KeySym::Georgian_san),
+    ("Georgian_tar", KeySym::Georgian_tar),
+    ("Georgian_un", KeySym::Georgian_un),
+    ("Georgian_phar", KeySym::Georgian_phar),
+    ("Georgian_khar", KeySym::Georgian_khar),
+    ("Georgian_ghan", KeySym::Georgian_ghan),
+    ("Georgian_qar", KeySym::Georgian_qar),
+    ("Georgian_shin", KeySym::Georgian_shin),
+    ("Georgian_chin", KeySym::Georgian_chin),
+    ("Georgian_can", KeySym::Georgian_can),
+    ("Georgian_jil", KeySym::Georgian_jil),
+    ("Georgian_cil", KeySym::Georgian_cil),
+    ("Georgian_char", KeySym::Georgian_char),
+    ("Georgian_xan", KeySym::Georgian_xan),
+    ("Georgian_jhan", KeySym::Georgian_jhan),
+    ("Georgian_hae", KeySym::Georgian_hae),
+    ("Georgian_he", KeySym::Georgian_he),
+    ("Georgian_hie", KeySym::Georgian_hie),
+    ("Georgian_we", KeySym::Georgian_we),
+    ("Georgian_har", KeySym::Georgian_har),
+    ("Georgian_hoe", KeySym::Georgian_hoe),
+    ("Georgian_fi", KeySym::Georgian_fi),
+    ("Xabovedot", KeySym::Xabovedot),
+    ("Ibreve", KeySym::Ibreve),
+    ("Zstroke", KeySym::Zstroke),
+    ("Gcaron", KeySym::Gcaron),
+    ("Ocaron", KeySym::Ocaron),
+    ("Obarred", KeySym::Obarred),
+    ("xabovedot", KeySym::xabovedot),
+    ("ibreve", KeySym::ibreve),
+    ("zstroke", KeySym::zstroke),
+    ("gcaron", KeySym::gcaron),
+    ("ocaron", KeySym::ocaron),
+    ("obarred", KeySym::obarred),
+    ("SCHWA", KeySym::SCHWA),
+    ("schwa", KeySym::schwa),
+    ("Lbelowdot", KeySym::Lbelowdot),
+    ("lbelowdot", KeySym::lbelowdot),
+    ("Abelowdot", KeySym::Abelowdot),
+    ("abelowdot", KeySym::abelowdot),
+    ("Ahook", KeySym::Ahook),
+    ("ahook", KeySym::ahook),
+    ("Acircumflexacute", KeySym::Acircumflexacute),
+    ("acircumflexacute", KeySym::acircumflexacute),
+    ("Acircumflexgrave", KeySym::Acircumflexgrave),
+    ("acircumflexgrave", KeySym::acircumflexgrave),
+    ("Acircumflexhook", KeySym::Acircumflexhook),
+    ("acircumflexhook", KeySym::acircumflexhook),
+    ("Acircumflextilde", KeySym::Acircumflextilde),
+    ("acircumflextilde", KeySym::acircumflextilde),
+    ("Acircumflexbelowdot", KeySym::Acircumflexbelowdot),
+    ("acircumflexbelowdot", KeySym::acircumflexbelowdot),
+    ("Abreveacute", KeySym::Abreveacute),
+    ("abreveacute", KeySym::abreveacute),
+    ("Abrevegrave", KeySym::Abrevegrave),
+    ("abrevegrave", KeySym::abrevegrave),
+    ("Abrevehook", KeySym::Abrevehook),
+    ("abrevehook", KeySym::abrevehook),
+    ("Abrevetilde", KeySym::Abrevetilde),
+    ("abrevetilde", KeySym::abrevetilde),
+    ("Abrevebelowdot", KeySym::Abrevebelowdot),
+    ("abrevebelowdot", KeySym::abrevebelowdot),
+    ("Ebelowdot", KeySym::Ebelowdot),
+    ("ebelowdot", KeySym::ebelowdot),
+    ("Ehook", KeySym::Ehook),
+    ("ehook", KeySym::ehook),
+    ("Etilde", KeySym::Etilde),
+    ("etilde", KeySym::etilde),
+    ("Ecircumflexacute", KeySym::Ecircumflexacute),
+    ("ecircumflexacute", KeySym::ecircumflexacute),
+    ("Ecircumflexgrave", KeySym::Ecircumflexgrave),
+    ("ecircumflexgrave", KeySym::ecircumflexgrave),
+    ("Ecircumflexhook", KeySym::Ecircumflexhook),
+    ("ecircumflexhook", KeySym::ecircumflexhook),
+    ("Ecircumflextilde", KeySym::Ecircumflextilde),
+    ("ecircumflextilde", KeySym::ecircumflextilde),
+    ("Ecircumflexbelowdot", KeySym::Ecircumflexbelowdot),
+    ("ecircumflexbelowdot", KeySym::ecircumflexbelowdot),
+    ("Ihook", KeySym::Ihook),
+    ("ihook", KeySym::ihook),
+    ("Ibelowdot", KeySym::Ibelowdot),
+    ("ibelowdot", KeySym::ibelowdot),
+    ("Obelowdot", KeySym::Obelowdot),
+    ("obelowdot", KeySym::obelowdot),
+    ("Ohook", KeySym::Ohook),
+    ("ohook", KeySym::ohook),
+    ("Ocircumflexacute", KeySym::Ocircumflexacute),
+    ("ocircumflexacute", KeySym::ocircumflexacute),
+    ("Ocircumflexgrave", KeySym::Ocircumflexgrave),
+    ("ocircumflexgrave", KeySym::ocircumflexgrave),
+    ("Ocircumflexhook", KeySym::Ocircumflexhook),
+    ("ocircumflexhook", KeySym::ocircumflexhook),
+    ("Ocircumflextilde", KeySym::Ocircumflextilde),
+    ("ocircumflextilde", KeySym::ocircumflextilde),
+    ("Ocircumflexbelowdot", KeySym::Ocircumflexbelowdot),
+    ("ocircumflexbelowdot", KeySym::ocircumflexbelowdot),
+    ("Ohornacute", KeySym::Ohornacute),
+    ("ohornacute", KeySym::ohornacute),
+    ("Ohorngrave", KeySym::Ohorngrave),
+    ("ohorngrave", KeySym::ohorngrave),
+    ("Ohornhook", KeySym::Ohornhook),
+    ("ohornhook", KeySym::ohornhook),
+    ("Ohorntilde", KeySym::Ohorntilde),
+    ("ohorntilde", KeySym::ohorntilde),
+    ("Ohornbelowdot", KeySym::Ohornbelowdot),
+    ("ohornbelowdot", KeySym::ohornbelowdot),
+    ("Ubelowdot", KeySym::Ubelowdot),
+    ("ubelowdot", KeySym::ubelowdot),
+    ("Uhook", KeySym::Uhook),
+    ("uhook", KeySym::uhook),
+    ("Uhornacute", KeySym::Uhornacute),
+    ("uhornacute", KeySym::uhornacute),
+    ("Uhorngrave", KeySym::Uhorngrave),
+    ("uhorngrave", KeySym::uhorngrave),
+    ("Uhornhook", KeySym::Uhornhook),
+    ("uhornhook", KeySym::uhornhook),
+    ("Uhorntilde", KeySym::Uhorntilde),
+    ("uhorntilde", KeySym::uhorntilde),
+    ("Uhornbelowdot", KeySym::Uhornbelowdot),
+    ("uhornbelowdot", KeySym::uhornbelowdot),
+    ("Ybelowdot", KeySym::Ybelowdot),
+    ("ybelowdot", KeySym::ybelowdot),
+    ("Yhook", KeySym::Yhook),
+    ("yhook", KeySym::yhook),
+    ("Ytilde", KeySym::Ytilde),
+    ("ytilde", KeySym::ytilde),
+    ("Ohorn", KeySym::Ohorn),
+    ("ohorn", KeySym::ohorn),
+    ("Uhorn", KeySym::Uhorn),
+    ("uhorn", KeySym::uhorn),
+    ("EcuSign", KeySym::EcuSign),
+    ("ColonSign", KeySym::ColonSign),
+    ("CruzeiroSign", KeySym::CruzeiroSign),
+    ("FFrancSign", KeySym::FFrancSign),
+    ("LiraSign", KeySym::LiraSign),
+    ("MillSign", KeySym::MillSign),
+    ("NairaSign", KeySym::NairaSign),
+    ("PesetaSign", KeySym::PesetaSign),
+    ("RupeeSign", KeySym::RupeeSign),
+    ("WonSign", KeySym::WonSign),
+    ("NewSheqelSign", KeySym::NewSheqelSign),
+    ("DongSign", KeySym::DongSign),
+    ("EuroSign", KeySym::EuroSign),
+];
@@ -8,12 +8,13 @@
 use requests::{XExtensionRequest, XRequestBase};
 
 use crate::{
-    connection::{ConnectionKind, XConnection},
+    connection::{ConnectionKind, DisplayVar, XConnection},
     error::Error,
     events::SomeEvent,
-    extensions::{mit_shm, randr},
+    extensions::{mit_shm, present, randr, xinput, xkb, ExtensionRegistry},
     replies::{AwaitingReply, ReceivedReply, ReplyType, SomeReply, XReply},
     requests::{InitializeConnection, XProtocolVersion, XRequest},
+    stats::Stats,
     utils::*,
     xauth::XAuth,
     xerror::SomeError,
@@ -23,20 +24,40 @@ use std::{
     fmt::Display,
     io::{self, Write},
     marker::PhantomData,
-    mem,
+    os::unix::net::UnixStream,
 };
 
 pub mod atoms;
+pub mod checked;
 pub mod connection;
+pub mod display;
 pub mod error;
 pub mod events;
+pub mod exposure;
 pub mod extensions;
+pub mod grab;
+pub mod handoff;
+#[cfg(feature = "introspection")]
+pub mod introspection;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod keymap;
 pub mod keysym;
 pub mod replies;
 pub mod requests;
+pub mod single_instance;
+pub mod stats;
 mod utils;
 pub mod xauth;
 pub mod xerror;
+#[cfg(feature = "xlib-interop")]
+pub mod xlib;
+
+extern crate self as just_x11;
+
+use just_geometry::{Rect, Vector2};
+/// Derive macros reading and writing fields in order, see [`just_x11_derive`]
+pub use just_x11_derive::{FromLeBytes, ToLeBytes};
 
 pub trait ToLeBytes: Sized {
     fn to_le_bytes(&self, w: &mut impl Write) -> io::Result<()>;
@@ -64,12 +85,80 @@ impl FromLeBytes for u32 {
     }
 }
 
+impl FromLeBytes for i16 {
+    fn from_le_bytes(conn: &mut XConnection) -> Result<Self, Error> {
+        conn.read_le_i16()
+    }
+}
+
 impl FromLeBytes for i32 {
     fn from_le_bytes(conn: &mut XConnection) -> Result<Self, Error> {
         conn.read_le_i32()
     }
 }
 
+/// Support for code generated by `#[derive(FromLeBytes, ToLeBytes)]`
+#[doc(hidden)]
+pub mod derive {
+    use crate::{connection::XConnection, error::Error, FromLeBytes, OrNone, ToLeBytes};
+    use std::io::{self, Write};
+
+    /// Field of a derived [`ToLeBytes`]. Separate from it so types with inherent `to_le_bytes`
+    /// returning arrays keep resolving to that through references.
+    pub trait WriteLe {
+        fn write_le(&self, w: &mut impl Write) -> io::Result<()>;
+    }
+
+    impl<T: ToLeBytes> WriteLe for T {
+        fn write_le(&self, w: &mut impl Write) -> io::Result<()> {
+            self.to_le_bytes(w)
+        }
+    }
+
+    impl<T: WriteLe> WriteLe for OrNone<T> {
+        fn write_le(&self, w: &mut impl Write) -> io::Result<()> {
+            self.0.write_le(w)
+        }
+    }
+
+    macro_rules! impl_write_le_primitive {
+        ($($ty:ty),*) => {
+            $(
+                impl WriteLe for $ty {
+                    fn write_le(&self, w: &mut impl Write) -> io::Result<()> {
+                        w.write_all(&<$ty>::to_le_bytes(*self))
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_write_le_primitive!(u8, u16, u32, i16, i32);
+
+    pub fn skip(conn: &mut XConnection, count: usize) -> Result<(), Error> {
+        drop(conn.drain(count)?);
+        Ok(())
+    }
+
+    pub fn read_list<T: FromLeBytes>(
+        conn: &mut XConnection,
+        count: usize,
+    ) -> Result<Vec<T>, Error> {
+        let mut list = Vec::with_capacity(count);
+        for _ in 0..count {
+            list.push(T::from_le_bytes(conn)?);
+        }
+        Ok(list)
+    }
+}
+
+/// Server-wide identifier of a resource.
+///
+/// Ids are not tied to the connection that allocated them, so ids created by another library
+/// (x11rb, xcb, Xlib) on a separate connection to the same server can be converted with
+/// [`From<u32>`] and used in requests. Such ids are never handed out by [`IdAllocator`] as each
+/// client gets disjoint id range, but freeing them stays responsibility of their owner.
+/// See [`IdAllocator::owns`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct ResourceId {
@@ -94,6 +183,61 @@ impl From<ResourceId> for u32 {
     }
 }
 
+/// Top three bits of resource ids are always zero
+const RESOURCE_ID_MASK: u32 = 0x1fffffff;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseResourceIdError {
+    InvalidNumber(String),
+    /// Zero means `None` in the protocol and is never a valid resource
+    Zero,
+    /// Top three bits are set
+    OutOfRange(u32),
+}
+
+impl Display for ParseResourceIdError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseResourceIdError::InvalidNumber(s) => write!(f, "Invalid resource id: '{}'", s),
+            ParseResourceIdError::Zero => write!(f, "Resource id cannot be zero"),
+            ParseResourceIdError::OutOfRange(value) => {
+                write!(f, "Resource id 0x{:x} does not fit in 29 bits", value)
+            }
+        }
+    }
+}
+
+/// Parses hexadecimal with `0x` prefix, as printed by `xwininfo` and [`Display`], or decimal
+impl std::str::FromStr for ResourceId {
+    type Err = ParseResourceIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        let value = match trimmed
+            .strip_prefix("0x")
+            .or_else(|| trimmed.strip_prefix("0X"))
+        {
+            Some(hex) => u32::from_str_radix(hex, 16),
+            None => trimmed.parse::<u32>(),
+        }
+        .map_err(|_| ParseResourceIdError::InvalidNumber(s.to_string()))?;
+
+        if value == 0 {
+            return Err(ParseResourceIdError::Zero);
+        }
+        if value & !RESOURCE_ID_MASK != 0 {
+            return Err(ParseResourceIdError::OutOfRange(value));
+        }
+        Ok(ResourceId { value })
+    }
+}
+
+impl Display for ResourceId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "0x{:x}", self.value)
+    }
+}
+
 impl_resource_id!(PixmapId);
 impl_resource_id!(VisualId);
 impl_resource_id!(FontId);
@@ -127,6 +271,12 @@ where
     }
 }
 
+impl<T: FromLeBytes> FromLeBytes for OrNone<T> {
+    fn from_le_bytes(conn: &mut XConnection) -> Result<Self, Error> {
+        Ok(Self(T::from_le_bytes(conn)?))
+    }
+}
+
 impl<T> From<OrNone<T>> for u32
 where
     T: Into<u32>,
@@ -167,6 +317,11 @@ impl IdAllocator {
             value: self.id_base | new_part,
         }
     }
+
+    /// Whether `id` belongs to this client's range, i.e. it was not allocated by some other client
+    pub fn owns(&self, id: ResourceId) -> bool {
+        id.value & !self.id_mask == self.id_base
+    }
 }
 
 #[derive(Debug)]
@@ -185,7 +340,10 @@ impl FromLeBytes for InitializeConnectionResponse {
             1 => Ok(Self::Success(
                 InitializeConnectionResponseSuccess::from_le_bytes(conn)?,
             )),
-            2 => todo!("InitializeConnectionResponseAuthenticate"),
+            // Only sent by authorization protocols needing more data, none of which is supported
+            2 => Err(Error::InvalidResponse(
+                "InitializeConnectionResponseAuthenticate",
+            )),
             _ => Err(Error::InvalidResponse(stringify!(
                 InitializeConnectionResponse
             ))),
@@ -350,6 +508,14 @@ impl Screen {
             allowed_depths,
         })
     }
+
+    /// Visual of the root window, its masks tell where channels are in a pixel
+    pub fn root_visual_type(&self) -> Option<&Visual> {
+        self.allowed_depths
+            .iter()
+            .flat_map(|depth| depth.visuals.iter())
+            .find(|visual| visual.id.id().value() == self.root_visual)
+    }
 }
 
 #[derive(Debug)]
@@ -438,6 +604,40 @@ impl FromLeBytes for InitializeConnectionResponseSuccess {
     }
 }
 
+/// Why the server refused the connection, guessed from the reason string since its format is not
+/// specified by the protocol
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefusalReason {
+    /// Server speaks protocol version this crate does not support
+    ProtocolVersion,
+    /// Missing or invalid Xauthority cookie
+    Authorization,
+    TooManyClients,
+    Other,
+}
+
+impl InitializeConnectionResponseRefused {
+    /// Protocol version advertised by the server, `None` if not supported by this crate
+    pub fn server_version(&self) -> Option<XProtocolVersion> {
+        XProtocolVersion::from_numbers(self.protocol_major_version, self.protocol_minor_version)
+    }
+
+    pub fn reason_kind(&self, requested: XProtocolVersion) -> RefusalReason {
+        if self.server_version() != Some(requested) {
+            return RefusalReason::ProtocolVersion;
+        }
+
+        let reason = String::from_utf8_lossy(&self.reason).to_lowercase();
+        if reason.contains("authoriz") || reason.contains("no protocol specified") {
+            RefusalReason::Authorization
+        } else if reason.contains("maximum number of clients") {
+            RefusalReason::TooManyClients
+        } else {
+            RefusalReason::Other
+        }
+    }
+}
+
 impl Display for InitializeConnectionResponseRefused {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -504,11 +704,22 @@ pub struct Point {
 
 impl Point {
     pub(crate) fn to_le_bytes(self) -> [u8; 4] {
-        unsafe { mem::transmute(self) }
+        let [x0, x1] = self.x.to_le_bytes();
+        let [y0, y1] = self.y.to_le_bytes();
+        [x0, x1, y0, y1]
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+impl From<Point> for Vector2<i32> {
+    fn from(point: Point) -> Self {
+        Vector2 {
+            x: point.x as i32,
+            y: point.y as i32,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(C)]
 pub struct Rectangle {
     pub x: i16,
@@ -519,45 +730,233 @@ pub struct Rectangle {
 
 impl Rectangle {
     fn to_le_bytes(self) -> [u8; 8] {
-        unsafe { mem::transmute(self) }
+        let [x0, x1] = self.x.to_le_bytes();
+        let [y0, y1] = self.y.to_le_bytes();
+        let [w0, w1] = self.width.to_le_bytes();
+        let [h0, h1] = self.height.to_le_bytes();
+        [x0, x1, y0, y1, w0, w1, h0, h1]
     }
 }
 
+impl From<Rectangle> for Rect {
+    fn from(rectangle: Rectangle) -> Self {
+        Rect {
+            position: Vector2 {
+                x: rectangle.x as i32,
+                y: rectangle.y as i32,
+            },
+            size: Vector2 {
+                x: rectangle.width as u32,
+                y: rectangle.height as u32,
+            },
+        }
+    }
+}
+
+impl From<Rect> for Rectangle {
+    /// Position and size are clamped to the range representable in requests
+    fn from(rect: Rect) -> Self {
+        Rectangle {
+            x: rect.position.x.clamp(i16::MIN as i32, i16::MAX as i32) as i16,
+            y: rect.position.y.clamp(i16::MIN as i32, i16::MAX as i32) as i16,
+            width: rect.size.x.min(u16::MAX as u32) as u16,
+            height: rect.size.y.min(u16::MAX as u32) as u16,
+        }
+    }
+}
+
+/// Resources shared by all users of the connection, created on first use
+#[derive(Debug, Clone, Copy, Default)]
+struct SharedScreenResources {
+    default_gc: Option<GContextId>,
+    bitmap: Option<PixmapId>,
+    invisible_cursor: Option<CursorId>,
+}
+
+impl_enum! {
+    #[repr(u8)]
+    enum ImageByteOrder {
+        LsbFirst = 0,
+        MsbFirst = 1,
+    }
+}
+
+impl_enum! {
+    #[repr(u8)]
+    enum BitmapBitOrder {
+        LeastSignificant = 0,
+        MostSignificant = 1,
+    }
+}
+
+/// Layout of image data expected by the server. This library, like most clients, prepares images
+/// in LSB first byte and bit order, use [`ImageFormat::convert_zpixmap`] and
+/// [`ImageFormat::convert_xy_bitmap`] to convert between it and server's order. Conversions are
+/// their own inverse so they work for both `PutImage` and `GetImage` data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageFormat {
+    pub image_byte_order: ImageByteOrder,
+    pub bitmap_bit_order: BitmapBitOrder,
+    pub bitmap_scanline_unit: u8,
+    pub bitmap_scanline_pad: u8,
+}
+
+impl ImageFormat {
+    fn from_setup(setup: &InitializeConnectionResponseSuccess) -> Result<Self, Error> {
+        Ok(Self {
+            image_byte_order: ImageByteOrder::try_from(setup.image_byte_order)
+                .map_err(|invalid| Error::InvalidEnum("ImageByteOrder", invalid as u64))?,
+            bitmap_bit_order: BitmapBitOrder::try_from(setup.bitmap_format_byte_order)
+                .map_err(|invalid| Error::InvalidEnum("BitmapBitOrder", invalid as u64))?,
+            bitmap_scanline_unit: setup.bitmap_format_scanline_unit,
+            bitmap_scanline_pad: setup.bitmap_format_scanline_pad,
+        })
+    }
+
+    /// Whether server uses the same layout as this library so no conversion is needed
+    pub fn is_lsb_first(&self) -> bool {
+        self.image_byte_order == ImageByteOrder::LsbFirst
+            && self.bitmap_bit_order == BitmapBitOrder::LeastSignificant
+    }
+
+    /// Convert Z format pixels with `bits_per_pixel` between LSB first and server's byte order
+    pub fn convert_zpixmap(&self, data: &mut [u8], bits_per_pixel: u8) {
+        if self.image_byte_order == ImageByteOrder::LsbFirst {
+            return;
+        }
+
+        match bits_per_pixel {
+            16 => data.chunks_exact_mut(2).for_each(|pixel| pixel.reverse()),
+            24 => data.chunks_exact_mut(3).for_each(|pixel| pixel.reverse()),
+            32 => data.chunks_exact_mut(4).for_each(|pixel| pixel.reverse()),
+            // Byte order only matters for multi-byte pixels, 4bpp nibble order is not handled
+            _ => {}
+        }
+    }
+
+    /// Convert XY format bitmap data, each scanline padded to `bitmap_scanline_pad`, between LSB
+    /// first and server's bit and byte order
+    pub fn convert_xy_bitmap(&self, data: &mut [u8]) {
+        if self.bitmap_bit_order == BitmapBitOrder::MostSignificant {
+            data.iter_mut().for_each(|byte| *byte = byte.reverse_bits());
+        }
+
+        // Bit order applies to the whole scanline unit, so its bytes are swapped only when byte
+        // order differs from bit order. With both MSB first the leftmost pixel is still in the
+        // first byte.
+        let msb_bytes = self.image_byte_order == ImageByteOrder::MsbFirst;
+        let msb_bits = self.bitmap_bit_order == BitmapBitOrder::MostSignificant;
+        let unit_bytes = self.bitmap_scanline_unit as usize / 8;
+        if msb_bytes != msb_bits && unit_bytes > 1 {
+            data.chunks_exact_mut(unit_bytes)
+                .for_each(|unit| unit.reverse());
+        }
+    }
+}
+
+/// What [`XDisplay::close`] does with requests that the server may not have processed yet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PendingReplies {
+    /// Wait until the server processes all sent requests
+    Await,
+    /// Close right after flushing
+    Discard,
+}
+
 pub struct XDisplay {
     id_allocator: IdAllocator,
     screens: Vec<Screen>,
+    default_screen: usize,
+    shared_resources: Vec<SharedScreenResources>,
     connection: XConnection,
     awaiting_replies: HashMap<SequenceNumber, AwaitingReply>,
+    /// Requests sent with [`XDisplay::send_request_checked`], with their error once it arrives
+    checked_requests: checked::CheckedRequests,
     next_sequence_number: SequenceNumber,
     event_queue: VecDeque<SomeEvent>,
     error_queue: VecDeque<SomeError>,
     maximum_request_length: u16,
+    image_format: ImageFormat,
+    extensions: ExtensionRegistry,
+    stats: Stats,
+    /// Connection setup reply as sent by the server, see [`handoff`]
+    setup: Vec<u8>,
     pub min_keycode: u8,
     pub max_keycode: u8,
 }
 
 impl XDisplay {
+    /// Opens connection with the newest supported protocol version, reconnecting with older one
+    /// if server refuses it and advertises a version this crate also supports
     pub fn open() -> Result<Self, Error> {
-        let connection = XConnection::open()?;
-        Self::with_connection(connection)
+        Self::open_display(DisplayVar::from_env()?)
     }
 
-    pub fn with_connection(mut connection: XConnection) -> Result<Self, Error> {
+    /// Like [`XDisplay::open`] but connects to `display` instead of `$DISPLAY`. Screen of the
+    /// display name, e.g. `1` in `:0.1`, becomes [`XDisplay::default_screen_index`].
+    pub fn open_display(display: DisplayVar) -> Result<Self, Error> {
+        let mut version = XProtocolVersion::SUPPORTED[0];
+        loop {
+            let connection = XConnection::with_display(display.clone())?;
+            match Self::with_connection_version(connection, version) {
+                Err(Error::CouldNotOpenDisplay(response))
+                    if response.reason_kind(version) == RefusalReason::ProtocolVersion =>
+                {
+                    match response.server_version() {
+                        Some(server_version) if server_version != version => {
+                            version = server_version;
+                        }
+                        _ => return Err(Error::CouldNotOpenDisplay(response)),
+                    }
+                }
+                Ok(mut res) => {
+                    if let Some(screen) = display.screen {
+                        if screen as usize >= res.screens.len() {
+                            return Err(Error::NoSuchScreen(screen, res.screens.len()));
+                        }
+                        res.default_screen = screen as usize;
+                    }
+                    return Ok(res);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    pub fn with_connection(connection: XConnection) -> Result<Self, Error> {
+        Self::with_connection_version(connection, XProtocolVersion::SUPPORTED[0])
+    }
+
+    pub fn with_connection_version(
+        mut connection: XConnection,
+        protocol_version: XProtocolVersion,
+    ) -> Result<Self, Error> {
         let (authorization_protocol_name, authorization_protocol_data) = match connection.kind() {
             ConnectionKind::UnixStream => {
                 let auth = XAuth::from_env()?;
                 (auth.name, auth.data)
             }
+            #[cfg(test)]
+            ConnectionKind::Test => (Vec::new(), Vec::new()),
         };
 
         let init = InitializeConnection::new(
-            XProtocolVersion::V11_0,
+            protocol_version,
             authorization_protocol_name,
             authorization_protocol_data,
         );
         connection.send_request(&init)?;
         connection.flush()?;
 
+        // Kept so the connection can be passed to other process, see `handoff`
+        let header = connection.peek_n_bytes(8)?;
+        let setup = if header[0] == 1 {
+            let additional_data_length = u16::from_le_bytes([header[6], header[7]]) as usize * 4;
+            connection.peek_n_bytes(8 + additional_data_length)?
+        } else {
+            Vec::new()
+        };
+
         let response = InitializeConnectionResponse::from_le_bytes(&mut connection)?;
         let response = match response {
             InitializeConnectionResponse::Refused(response) => {
@@ -565,18 +964,33 @@ impl XDisplay {
             }
             InitializeConnectionResponse::Success(response) => response,
         };
+        Self::from_setup(connection, response, setup)
+    }
 
+    fn from_setup(
+        connection: XConnection,
+        response: InitializeConnectionResponseSuccess,
+        setup: Vec<u8>,
+    ) -> Result<Self, Error> {
         let id_allocator = IdAllocator::new(response.resource_id_base, response.resource_id_mask);
+        let image_format = ImageFormat::from_setup(&response)?;
 
         Ok(Self {
             id_allocator,
+            shared_resources: vec![SharedScreenResources::default(); response.screens.len()],
             screens: response.screens,
+            default_screen: 0,
             connection,
             awaiting_replies: HashMap::new(),
+            checked_requests: Default::default(),
             next_sequence_number: SequenceNumber { value: 1 }, // InitializeConnection request was 0
             event_queue: VecDeque::new(),
             error_queue: VecDeque::new(),
             maximum_request_length: response.maximum_request_length,
+            image_format,
+            extensions: ExtensionRegistry::default(),
+            stats: Stats::default(),
+            setup,
             max_keycode: response.max_keycode,
             min_keycode: response.min_keycode,
         })
@@ -586,6 +1000,32 @@ impl XDisplay {
         &mut self.id_allocator
     }
 
+    /// Extension versions negotiated so far, e.g. with [`randr::negotiate`]
+    pub fn extensions(&self) -> &ExtensionRegistry {
+        &self.extensions
+    }
+
+    /// Second handle to the server socket. Writing to it bypasses the request buffer and sequence
+    /// numbers of this display, so it is only meant for last resort cleanup, e.g. in panic hooks.
+    /// Requests are buffered whole, the socket always ends at a request boundary and requests
+    /// written to it are not mixed with partially sent ones.
+    pub fn try_clone_socket(&self) -> Result<UnixStream, Error> {
+        Ok(self.connection.try_clone_stream()?)
+    }
+
+    /// Traffic counters, see [`Stats`]
+    pub fn stats(&self) -> Stats {
+        let mut stats = self.stats.clone();
+        (stats.bytes_in, stats.bytes_out) = self.connection.bytes_transferred();
+        stats
+    }
+
+    /// Start counting [`Self::stats`] from zero, e.g. to measure a time window
+    pub fn reset_stats(&mut self) {
+        self.stats = Stats::default();
+        self.connection.reset_bytes_transferred();
+    }
+
     pub fn maximum_request_length(&self) -> u16 {
         self.maximum_request_length
     }
@@ -594,6 +1034,135 @@ impl XDisplay {
         &self.screens
     }
 
+    /// Index into [`XDisplay::screens`] selected by the display name, `0` if it has no screen
+    pub fn default_screen_index(&self) -> usize {
+        self.default_screen
+    }
+
+    /// Screen selected by the display name, e.g. the second one with `:0.1`
+    pub fn default_screen(&self) -> &Screen {
+        &self.screens[self.default_screen]
+    }
+
+    pub fn image_format(&self) -> ImageFormat {
+        self.image_format
+    }
+
+    /// Error if the display has no `screen`, instead of panicking on indexing its resources
+    fn check_screen(&self, screen: usize) -> Result<(), Error> {
+        if screen >= self.screens.len() {
+            return Err(Error::NoSuchScreen(screen as u32, self.screens.len()));
+        }
+        Ok(())
+    }
+
+    /// Graphics context on the root window of `screen` with screen's black foreground and white
+    /// background. Created on first use and shared, so it must not be modified with `ChangeGC`.
+    pub fn default_gc(&mut self, screen: usize) -> Result<GContextId, Error> {
+        self.check_screen(screen)?;
+        if let Some(gc) = self.shared_resources[screen].default_gc {
+            return Ok(gc);
+        }
+
+        let gc = GContextId::from_resource(self.id_allocator.allocate_id());
+        self.send_request(&requests::CreateGC {
+            cid: gc,
+            drawable: Drawable::Window(self.screens[screen].root),
+            values: requests::GContextSettings::new()
+                .set_foreground(self.screens[screen].black_pixel)
+                .set_background(self.screens[screen].white_pixel),
+        })?;
+        self.shared_resources[screen].default_gc = Some(gc);
+
+        Ok(gc)
+    }
+
+    /// Cleared 1x1 pixmap of depth 1 on `screen`. Created on first use and shared, so it must not
+    /// be drawn to.
+    pub fn bitmap(&mut self, screen: usize) -> Result<PixmapId, Error> {
+        self.check_screen(screen)?;
+        if let Some(bitmap) = self.shared_resources[screen].bitmap {
+            return Ok(bitmap);
+        }
+
+        let bitmap = PixmapId::from_resource(self.id_allocator.allocate_id());
+        self.send_request(&requests::CreatePixmap {
+            depth: 1,
+            pid: bitmap,
+            drawable: Drawable::Window(self.screens[screen].root),
+            width: 1,
+            height: 1,
+        })?;
+
+        // Pixmap contents are undefined after creation
+        let gc = GContextId::from_resource(self.id_allocator.allocate_id());
+        self.send_request(&requests::CreateGC {
+            cid: gc,
+            drawable: Drawable::Pixmap(bitmap),
+            values: requests::GContextSettings::new().set_foreground(0),
+        })?;
+        self.send_request(&requests::PolyFillRectangle {
+            drawable: Drawable::Pixmap(bitmap),
+            gc,
+            rectangles: vec![Rectangle {
+                x: 0,
+                y: 0,
+                width: 1,
+                height: 1,
+            }],
+        })?;
+        self.send_request(&requests::FreeGC { gc })?;
+        self.shared_resources[screen].bitmap = Some(bitmap);
+
+        Ok(bitmap)
+    }
+
+    /// Fill all `rectangles` with as few `PolyFillRectangle` requests as the maximum request
+    /// length allows
+    pub fn fill_rectangles(
+        &mut self,
+        drawable: Drawable,
+        gc: GContextId,
+        rectangles: &[Rectangle],
+    ) -> Result<(), Error> {
+        for request in requests::PolyFillRectangle::batched(
+            drawable,
+            gc,
+            rectangles,
+            self.maximum_request_length,
+        ) {
+            self.send_request(&request)?;
+        }
+        Ok(())
+    }
+
+    /// Fully transparent cursor, useful for hiding the pointer. Created on first use and shared.
+    pub fn invisible_cursor(&mut self, screen: usize) -> Result<CursorId, Error> {
+        self.check_screen(screen)?;
+        if let Some(cursor) = self.shared_resources[screen].invisible_cursor {
+            return Ok(cursor);
+        }
+
+        let bitmap = self.bitmap(screen)?;
+        let cursor = CursorId::from_resource(self.id_allocator.allocate_id());
+        self.send_request(&requests::CreateCursor {
+            cid: cursor,
+            source: bitmap,
+            mask: OrNone::new(bitmap),
+            fore_red: 0,
+            fore_green: 0,
+            fore_blue: 0,
+            back_red: 0,
+            back_green: 0,
+            back_blue: 0,
+            x: 0,
+            y: 0,
+        })?;
+        self.shared_resources[screen].invisible_cursor = Some(cursor);
+
+        Ok(cursor)
+    }
+
     fn next_sequence_number(&mut self) -> Result<SequenceNumber, Error> {
         let this_sequence_number = self.next_sequence_number.value;
         self.next_sequence_number = SequenceNumber {
@@ -653,6 +1222,21 @@ impl XDisplay {
         Ok(())
     }
 
+    /// Flush buffered requests and shut down the connection. Returns errors received for
+    /// requests without replies that were not taken with [`Self::errors`] yet.
+    ///
+    /// With [`PendingReplies::Await`] blocks until the server has processed all requests, so
+    /// their errors are returned too. Replies that were not awaited are dropped either way.
+    pub fn close(mut self, pending: PendingReplies) -> Result<Vec<SomeError>, Error> {
+        if pending == PendingReplies::Await {
+            // Replies come in order, so once this one arrives all previous requests are done
+            let sync = self.send_request(&requests::GetInputFocus)?;
+            let _ = self.await_pending_reply(sync)?;
+        }
+        self.connection.shutdown()?;
+        Ok(self.error_queue.drain(..).collect())
+    }
+
     /// Get reply to previously sent request. Block until reply arrives
     pub fn await_pending_reply<Reply>(
         &mut self,
@@ -743,6 +1327,7 @@ impl XDisplay {
             0 => {
                 let error_code: u8 = self.connection.read_u8()?;
                 let error = SomeError::from_le_bytes(&mut self.connection, error_code)?;
+                self.stats.errors += 1;
 
                 match self.awaiting_replies.remove(&error.sequence_number()) {
                     Some(AwaitingReply::NotReceived(reply_type)) => {
@@ -757,13 +1342,22 @@ impl XDisplay {
                     }
                     Some(AwaitingReply::Discarded(_)) => { /* do nothing */ }
                     Some(AwaitingReply::Received(_)) => Err(Error::UnexpectedReply)?,
-                    None => self.error_queue.push_back(error),
+                    None => match self
+                        .checked_requests
+                        .borrow_mut()
+                        .get_mut(&error.sequence_number())
+                    {
+                        Some(checked) => *checked = Some(error),
+                        None => self.error_queue.push_back(error),
+                    },
                 }
             }
             1 => {
+                self.stats.replies += 1;
                 self.handle_reply_blocking()?;
             }
             event_code => {
+                self.stats.count_event(event_code);
                 let event = self.decode_event_blocking(event_code)?;
                 self.event_queue.push_back(event);
             }
@@ -778,10 +1372,17 @@ impl XDisplay {
             value: ((self.connection.peek(2)? as u16) << 8) + self.connection.peek(1)? as u16,
         };
 
-        let (_, awaiting_reply) = self
-            .awaiting_replies
-            .remove_entry(&sequence_number)
-            .expect("Sequence number must be known");
+        let Some(awaiting_reply) = self.awaiting_replies.remove(&sequence_number) else {
+            // Skip the reply so the stream stays in sync with the server
+            let length = u32::from_le_bytes([
+                self.connection.peek(3)?,
+                self.connection.peek(4)?,
+                self.connection.peek(5)?,
+                self.connection.peek(6)?,
+            ]);
+            drop(self.connection.drain(31 + length as usize * 4)?);
+            return Err(Error::InvalidResponse("reply to unknown request"));
+        };
 
         let reply_type = awaiting_reply.reply_type();
         let reply = self.decode_reply_blocking(reply_type)?;
@@ -829,7 +1430,7 @@ impl XDisplay {
                     self.awaiting_replies
                         .insert(sequence_number, AwaitingReply::Received(old_reply));
                 } else {
-                    todo!("Unmatched response types with pending data not handled yet")
+                    return Err(Error::UnexpectedReply);
                 }
             }
         };
@@ -907,6 +1508,7 @@ impl XDisplay {
                     ReplyType::SetScreenConfig => handle_randr_reply!(SetScreenConfig),
                     ReplyType::GetScreenInfo => handle_randr_reply!(GetScreenInfo),
                     ReplyType::GetScreenSizeRange => handle_randr_reply!(GetScreenSizeRange),
+                    ReplyType::GetOutputInfo => handle_randr_reply!(GetOutputInfo),
                     ReplyType::GetCrtcInfo => handle_randr_reply!(GetCrtcInfo),
                     ReplyType::GetScreenResourcesCurrent => {
                         handle_randr_reply!(GetScreenResourcesCurrent)
@@ -931,6 +1533,47 @@ impl XDisplay {
                     ReplyType::CreateSegment => handle_mit_shm_reply!(CreateSegment),
                 }
             }
+            ReplyType::ExtensionXkb(xkb_reply) => {
+                macro_rules! handle_xkb_reply {
+                    ($t:tt) => {{
+                        let reply = xkb::replies::$t::from_le_bytes(&mut self.connection)?;
+                        Ok(SomeReply::ExtensionXkb(xkb::replies::SomeReply::$t(reply)))
+                    }};
+                }
+
+                use xkb::replies::ReplyType;
+                match xkb_reply {
+                    ReplyType::UseExtension => handle_xkb_reply!(UseExtension),
+                }
+            }
+            ReplyType::ExtensionXInput(xinput_reply) => {
+                macro_rules! handle_xinput_reply {
+                    ($t:tt) => {{
+                        let reply = xinput::replies::$t::from_le_bytes(&mut self.connection)?;
+                        Ok(SomeReply::ExtensionXInput(xinput::replies::SomeReply::$t(
+                            reply,
+                        )))
+                    }};
+                }
+
+                use xinput::replies::ReplyType;
+                match xinput_reply {
+                    ReplyType::XIQueryVersion => handle_xinput_reply!(XIQueryVersion),
+                    ReplyType::XIQueryDevice => handle_xinput_reply!(XIQueryDevice),
+                }
+            }
+            ReplyType::ExtensionPresent(present_reply) => {
+                use present::replies::ReplyType;
+                match present_reply {
+                    ReplyType::QueryVersion => {
+                        let reply =
+                            present::replies::QueryVersion::from_le_bytes(&mut self.connection)?;
+                        Ok(SomeReply::ExtensionPresent(
+                            present::replies::SomeReply::QueryVersion(reply),
+                        ))
+                    }
+                }
+            }
         }
     }
 
@@ -938,6 +1581,17 @@ impl XDisplay {
         let mut raw = [0u8; 32];
         raw[0] = event_code;
         self.connection.read_exact(&mut raw[1..])?;
+
+        if event_code & 0x7f == events::GenericEvent::CODE {
+            let length = u32::from_le_bytes([raw[4], raw[5], raw[6], raw[7]]);
+            let mut raw = raw.to_vec();
+            raw.resize(32 + length as usize * 4, 0);
+            self.connection.read_exact(&mut raw[32..])?;
+            return events::GenericEvent::from_le_bytes(raw)
+                .map(SomeEvent::GenericEvent)
+                .ok_or(Error::InvalidResponse(stringify!(GenericEvent)));
+        }
+
         SomeEvent::from_le_bytes(raw).ok_or(Error::InvalidResponse(stringify!(SomeEvent)))
     }
 
@@ -963,7 +1617,8 @@ impl XDisplay {
     }
 
     /// Drain all errors from queue. Queue contains only errors for requests without replies.
-    /// If error is associated with a reply will be returned in [`Self::await_pending_reply`]
+    /// If error is associated with a reply will be returned in [`Self::await_pending_reply`],
+    /// errors of checked requests in [`checked::CheckedRequest::check`]
     pub fn errors(&mut self) -> Drain<'_, SomeError> {
         self.error_queue.drain(..)
     }
@@ -994,6 +1649,13 @@ impl<Reply> std::fmt::Debug for PendingReply<Reply> {
     }
 }
 
+/// Flushes buffered requests, use [`XDisplay::close`] to also wait for them and see the errors
+impl Drop for XDisplay {
+    fn drop(&mut self) {
+        let _ = self.connection.flush();
+    }
+}
+
 impl<Reply> PendingReply<Reply> {
     pub fn sequence_number(&self) -> SequenceNumber {
         self.sequence_number
@@ -1063,3 +1725,717 @@ fn list_of_str_roundtrip() {
     };
     assert_eq!(encoded, raw_data.to_vec());
 }
+
+#[test]
+fn refusal_reason_kind() {
+    let refused = |major, reason: &str| InitializeConnectionResponseRefused {
+        protocol_major_version: major,
+        protocol_minor_version: 0,
+        reason: reason.as_bytes().to_vec(),
+    };
+
+    assert_eq!(
+        refused(12, "").reason_kind(XProtocolVersion::V11_0),
+        RefusalReason::ProtocolVersion
+    );
+    assert_eq!(
+        refused(
+            11,
+            "Authorization required, but no authorization protocol specified\n"
+        )
+        .reason_kind(XProtocolVersion::V11_0),
+        RefusalReason::Authorization
+    );
+    assert_eq!(
+        refused(11, "Maximum number of clients reached").reason_kind(XProtocolVersion::V11_0),
+        RefusalReason::TooManyClients
+    );
+}
+
+#[test]
+fn resource_id_from_str() {
+    let window: WindowId = "0x2a00003".parse().unwrap();
+    assert_eq!(u32::from(window), 0x2a00003);
+    assert_eq!(window.to_string(), "0x2a00003");
+    assert_eq!("44040195".parse::<WindowId>(), Ok(window));
+    assert_eq!("0".parse::<PixmapId>(), Err(ParseResourceIdError::Zero));
+    assert_eq!(
+        "0xe0000001".parse::<PixmapId>(),
+        Err(ParseResourceIdError::OutOfRange(0xe0000001))
+    );
+    assert!(matches!(
+        "window".parse::<ResourceId>(),
+        Err(ParseResourceIdError::InvalidNumber(_))
+    ));
+}
+
+/// Display connected to scripted server that accepts the connection without any screens
+#[cfg(test)]
+fn scripted_display(
+    script: impl IntoIterator<Item = connection::ScriptStep>,
+) -> Result<XDisplay, Error> {
+    scripted_display_with_writes(script).map(|(display, _)| display)
+}
+
+/// Bytes written by the client to a scripted connection
+#[cfg(test)]
+type Written = std::rc::Rc<std::cell::RefCell<Vec<u8>>>;
+
+/// [`scripted_display`] and bytes of requests sent after the connection setup
+#[cfg(test)]
+fn scripted_display_with_writes(
+    script: impl IntoIterator<Item = connection::ScriptStep>,
+) -> Result<(XDisplay, Written), Error> {
+    use connection::{ScriptStep, TestConnection};
+
+    let mut setup = vec![1, 0];
+    setup.extend(11u16.to_le_bytes()); // protocol major version
+    setup.extend(0u16.to_le_bytes()); // protocol minor version
+    setup.extend(8u16.to_le_bytes()); // additional data length
+    setup.extend(0u32.to_le_bytes()); // release number
+    setup.extend(0x0400000u32.to_le_bytes()); // resource id base
+    setup.extend(0x01fffffu32.to_le_bytes()); // resource id mask
+    setup.extend(0u32.to_le_bytes()); // motion buffer size
+    setup.extend(0u16.to_le_bytes()); // vendor length
+    setup.extend(u16::MAX.to_le_bytes()); // maximum request length
+    setup.extend([0, 0, 0, 0, 32, 32, 8, 255]); // no screens and formats, image format, keycodes
+    setup.extend(0u32.to_le_bytes()); // unused
+
+    let server = TestConnection::new([ScriptStep::data(setup)].into_iter().chain(script));
+    let written = server.written();
+    let display = XDisplay::with_connection(XConnection::scripted(server))?;
+    written.borrow_mut().clear();
+    Ok((display, written))
+}
+
+#[cfg(test)]
+fn input_focus_reply(sequence_number: u16, focus: u32) -> Vec<u8> {
+    let mut reply = vec![1, 2]; // Reply, RevertTo::Parent
+    reply.extend(sequence_number.to_le_bytes());
+    reply.extend(0u32.to_le_bytes()); // reply length
+    reply.extend(focus.to_le_bytes());
+    reply.extend([0u8; 20]);
+    reply
+}
+
+/// Deterministic xorshift generator for randomized tests, failing cases report their seed
+#[cfg(test)]
+struct TestRng(u64);
+
+#[cfg(test)]
+impl TestRng {
+    fn new(seed: u64) -> Self {
+        // Zero state would stay zero
+        Self(seed.wrapping_mul(0x9e3779b97f4a7c15) | 1)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Uniform in `range`, with negligible bias for the small ranges used in tests
+    fn range(&mut self, range: std::ops::Range<u64>) -> u64 {
+        range.start + self.next_u64() % (range.end - range.start)
+    }
+
+    fn chance(&mut self, percent: u64) -> bool {
+        self.range(0..100) < percent
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for idx in (1..items.len()).rev() {
+            items.swap(idx, self.range(0..idx as u64 + 1) as usize);
+        }
+    }
+}
+
+#[test]
+fn scripted_short_reads() {
+    use connection::ScriptStep;
+
+    let reply = input_focus_reply(1, 0x400001);
+    let mut display = scripted_display([
+        ScriptStep::WouldBlock,
+        ScriptStep::Data {
+            bytes: reply[..5].to_vec(),
+            chunk: 1,
+        },
+        ScriptStep::WouldBlock,
+        ScriptStep::Data {
+            bytes: reply[5..].to_vec(),
+            chunk: 7,
+        },
+    ])
+    .unwrap();
+
+    let pending = display.send_request(&requests::GetInputFocus).unwrap();
+    let reply = display.await_pending_reply(pending).unwrap().unwrap();
+    assert!(matches!(reply.focus, replies::Focus::Window(window) if u32::from(window) == 0x400001));
+}
+
+#[test]
+fn scripted_stream_failures() {
+    use connection::ScriptStep;
+
+    // Server goes away in the middle of a reply
+    let mut display = scripted_display([ScriptStep::data(&input_focus_reply(1, 0)[..12])]).unwrap();
+    let pending = display.send_request(&requests::GetInputFocus).unwrap();
+    assert!(matches!(
+        display.await_pending_reply(pending),
+        Err(Error::ConnectionClosed)
+    ));
+
+    // Events are not awaited but closed connection must still be noticed
+    let mut display = scripted_display([]).unwrap();
+    assert!(matches!(display.next_event(), Err(Error::ConnectionClosed)));
+
+    let mut display =
+        scripted_display([ScriptStep::Error(io::ErrorKind::ConnectionReset)]).unwrap();
+    let pending = display.send_request(&requests::GetInputFocus).unwrap();
+    assert!(matches!(
+        display.await_pending_reply(pending),
+        Err(Error::IOError(err)) if err.kind() == io::ErrorKind::ConnectionReset
+    ));
+
+    // Authentication is not supported
+    let server = connection::TestConnection::new([ScriptStep::data([2u8; 8])]);
+    assert!(matches!(
+        XDisplay::with_connection(XConnection::scripted(server)),
+        Err(Error::InvalidResponse(_))
+    ));
+}
+
+#[test]
+fn shared_resources_of_missing_screen() {
+    // Scripted server has no screens
+    let (mut display, written) = scripted_display_with_writes([]).unwrap();
+    assert!(matches!(
+        display.default_gc(0),
+        Err(Error::NoSuchScreen(0, 0))
+    ));
+    assert!(matches!(display.bitmap(0), Err(Error::NoSuchScreen(0, 0))));
+    assert!(matches!(
+        display.invisible_cursor(1),
+        Err(Error::NoSuchScreen(1, 0))
+    ));
+    assert!(written.take().is_empty());
+}
+
+#[test]
+fn requests_are_flushed_whole() {
+    let (mut display, written) = scripted_display_with_writes([]).unwrap();
+    // Together larger than the request buffer, so the first one is flushed on its own
+    let request = requests::ChangeProperty {
+        mode: requests::ChangePropertyMode::Replace,
+        window: WindowId::from(0x400001),
+        property: atoms::AtomId::WM_NAME,
+        type_: atoms::AtomId::STRING,
+        format: requests::ChangePropertyFormat::Format8,
+        data: vec![b'x'; 6000],
+    };
+    display.send_request(&request).unwrap();
+    display.send_request(&request).unwrap();
+    assert_eq!(written.borrow().len(), 24 + 6000);
+
+    display.flush().unwrap();
+    assert_eq!(written.take().len(), 2 * (24 + 6000));
+}
+
+#[test]
+fn image_format_conversions() {
+    let format = |image_byte_order, bitmap_bit_order, bitmap_scanline_unit| ImageFormat {
+        image_byte_order,
+        bitmap_bit_order,
+        bitmap_scanline_unit,
+        bitmap_scanline_pad: 32,
+    };
+
+    let pixels = (1..=12).collect::<Vec<u8>>();
+    for (byte_order, bits_per_pixel, expected) in [
+        (
+            ImageByteOrder::LsbFirst,
+            32,
+            vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12],
+        ),
+        (
+            ImageByteOrder::MsbFirst,
+            8,
+            vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12],
+        ),
+        (
+            ImageByteOrder::MsbFirst,
+            16,
+            vec![2, 1, 4, 3, 6, 5, 8, 7, 10, 9, 12, 11],
+        ),
+        (
+            ImageByteOrder::MsbFirst,
+            24,
+            vec![3, 2, 1, 6, 5, 4, 9, 8, 7, 12, 11, 10],
+        ),
+        (
+            ImageByteOrder::MsbFirst,
+            32,
+            vec![4, 3, 2, 1, 8, 7, 6, 5, 12, 11, 10, 9],
+        ),
+    ] {
+        let format = format(byte_order, BitmapBitOrder::LeastSignificant, 32);
+        let mut data = pixels.clone();
+        format.convert_zpixmap(&mut data, bits_per_pixel);
+        assert_eq!(data, expected, "{:?} {}", byte_order, bits_per_pixel);
+        format.convert_zpixmap(&mut data, bits_per_pixel);
+        assert_eq!(data, pixels);
+    }
+
+    // Byte and bit holding pixel `x` as described in section 8 "Connection Setup" of the protocol
+    let locate = |format: ImageFormat, x: usize| {
+        let unit = format.bitmap_scanline_unit as usize;
+        let bit = match format.bitmap_bit_order {
+            BitmapBitOrder::LeastSignificant => x % unit,
+            BitmapBitOrder::MostSignificant => unit - 1 - x % unit,
+        };
+        let byte = match format.image_byte_order {
+            ImageByteOrder::LsbFirst => bit / 8,
+            ImageByteOrder::MsbFirst => unit / 8 - 1 - bit / 8,
+        };
+        (x / unit * unit / 8 + byte, bit % 8)
+    };
+    let lsb_first = format(
+        ImageByteOrder::LsbFirst,
+        BitmapBitOrder::LeastSignificant,
+        32,
+    );
+    for byte_order in [ImageByteOrder::LsbFirst, ImageByteOrder::MsbFirst] {
+        for bit_order in [
+            BitmapBitOrder::LeastSignificant,
+            BitmapBitOrder::MostSignificant,
+        ] {
+            for unit in [8, 16, 32] {
+                let format = format(byte_order, bit_order, unit);
+                for x in 0..64 {
+                    let mut data = [0u8; 8];
+                    let (byte, bit) = locate(lsb_first, x);
+                    data[byte] = 1 << bit;
+
+                    format.convert_xy_bitmap(&mut data);
+                    let (byte, bit) = locate(format, x);
+                    let mut expected = [0u8; 8];
+                    expected[byte] = 1 << bit;
+                    assert_eq!(
+                        data, expected,
+                        "{:?} {:?} {} {}",
+                        byte_order, bit_order, unit, x
+                    );
+
+                    format.convert_xy_bitmap(&mut data);
+                    assert_eq!(data.iter().map(|b| b.count_ones()).sum::<u32>(), 1);
+                    assert_eq!(locate(lsb_first, x), {
+                        let byte = data.iter().position(|b| *b != 0).unwrap();
+                        (byte, data[byte].trailing_zeros() as usize)
+                    });
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn scripted_malformed_replies() {
+    use connection::ScriptStep;
+
+    let mut unknown_error = vec![0, 200];
+    unknown_error.extend(1u16.to_le_bytes());
+    unknown_error.extend([0u8; 28]);
+    let mut display = scripted_display([ScriptStep::data(unknown_error)]).unwrap();
+    let pending = display.send_request(&requests::GetInputFocus).unwrap();
+    assert!(matches!(
+        display.await_pending_reply(pending),
+        Err(Error::UnknownErrorCode(200))
+    ));
+
+    // Reply to a request that was never sent is skipped with its extra data, so the following
+    // reply still decodes
+    let mut stray = input_focus_reply(7, 0);
+    stray[4] = 2; // reply length
+    stray.extend([0u8; 8]);
+    let mut display = scripted_display([
+        ScriptStep::data(stray),
+        ScriptStep::data(input_focus_reply(2, 1)),
+    ])
+    .unwrap();
+    let pending = display.send_request(&requests::GetInputFocus).unwrap();
+    assert!(matches!(
+        display.await_pending_reply(pending),
+        Err(Error::InvalidResponse(_))
+    ));
+    let pending = display.send_request(&requests::GetInputFocus).unwrap();
+    assert!(matches!(
+        display.await_pending_reply(pending),
+        Ok(Ok(replies::GetInputFocus {
+            focus: replies::Focus::PointerRoot,
+            ..
+        }))
+    ));
+}
+
+#[test]
+fn scripted_generic_events() {
+    use connection::ScriptStep;
+    use xinput::{DeviceEvent, DeviceEventType};
+
+    let mut motion = vec![events::GenericEvent::CODE, 131];
+    motion.extend(0u16.to_le_bytes()); // sequence number
+    motion.extend(15u32.to_le_bytes()); // length
+    motion.extend(6u16.to_le_bytes()); // Motion
+    motion.extend(2u16.to_le_bytes()); // deviceid
+    motion.resize(40, 0);
+    motion.extend(0x000a8000u32.to_le_bytes()); // event_x = 10.5
+    motion.extend(0x00140000u32.to_le_bytes()); // event_y = 20
+    motion.resize(48, 0);
+    motion.extend(0u16.to_le_bytes()); // buttons_len
+    motion.extend(1u16.to_le_bytes()); // valuators_len
+    motion.extend(11u16.to_le_bytes()); // sourceid
+    motion.resize(80, 0);
+    motion.extend(0b100u32.to_le_bytes()); // valuator 2
+    motion.extend(3i32.to_le_bytes());
+    motion.extend(0x80000000u32.to_le_bytes()); // 3.5
+                                                // Following core event is decoded only if the whole generic event was consumed
+    let mut unknown = vec![64u8];
+    unknown.resize(32, 0);
+
+    let mut display = scripted_display([
+        ScriptStep::data(motion),
+        ScriptStep::data(unknown),
+        ScriptStep::WouldBlock,
+    ])
+    .unwrap();
+    let events = display.events().unwrap().collect::<Vec<_>>();
+    assert_eq!(events.len(), 2);
+    let SomeEvent::GenericEvent(generic) = &events[0] else {
+        panic!("Expected generic event, got {:?}", events[0]);
+    };
+    assert!(DeviceEvent::from_generic_event(generic, 130).is_none());
+    let motion = DeviceEvent::from_generic_event(generic, 131).unwrap();
+    assert_eq!(motion.event_type, DeviceEventType::Motion);
+    assert_eq!(motion.device_id.raw(), 2);
+    assert_eq!(motion.source_id.raw(), 11);
+    assert_eq!((motion.event_x, motion.event_y), (10.5, 20.0));
+    assert_eq!(motion.valuators, [(2, 3.5)]);
+    assert!(matches!(events[1], SomeEvent::UnknownEvent(_)));
+}
+
+#[test]
+fn scripted_stats() {
+    use connection::ScriptStep;
+
+    let mut map_request = [0u8; 32];
+    map_request[0] = 20;
+    let mut client_message = [0u8; 32];
+    client_message[0] = 33 | 0x80;
+    client_message[1] = 32; // format
+                            // BadWindow error of a request without reply
+    let mut error = vec![0, 3];
+    error.extend(1u16.to_le_bytes());
+    error.extend([0u8; 28]);
+    let mut events = map_request.to_vec();
+    events.extend(client_message);
+    events.extend(error);
+
+    let mut display = scripted_display([
+        ScriptStep::data(events),
+        ScriptStep::data(input_focus_reply(2, 0)),
+    ])
+    .unwrap();
+    display.reset_stats();
+
+    display
+        .send_request(&requests::MapWindow {
+            window: WindowId::from(0x400001),
+        })
+        .unwrap();
+    let pending = display.send_request(&requests::GetInputFocus).unwrap();
+    let _ = display.await_pending_reply(pending).unwrap().unwrap();
+
+    let stats = display.stats();
+    assert_eq!(stats.replies, 1);
+    assert_eq!(stats.errors, 1);
+    assert_eq!(stats.total_events(), 2);
+    assert_eq!(
+        stats.named_events().collect::<Vec<_>>(),
+        vec![
+            ("MapRequest".to_string(), 1),
+            ("ClientMessage".to_string(), 1)
+        ]
+    );
+    assert_eq!(stats.bytes_in, 4 * 32);
+    assert_eq!(stats.bytes_out, 8 + 4);
+
+    display.reset_stats();
+    assert_eq!(display.stats(), Stats::default());
+}
+
+#[test]
+fn derived_le_bytes_roundtrip() {
+    use events::StackMode;
+
+    #[derive(Debug, PartialEq, FromLeBytes, ToLeBytes)]
+    #[le(pad = 3)]
+    struct Derived {
+        depth: u8,
+        #[le(pad = 2)]
+        window: OrNone<WindowId>,
+        x: i16,
+        mode: StackMode,
+        #[le(length = u16, pad = 1)]
+        children: Vec<WindowId>,
+    }
+
+    let derived = Derived {
+        depth: 24,
+        window: OrNone::none(),
+        x: -2,
+        mode: StackMode::Below,
+        children: vec![WindowId::from(0x400001), WindowId::from(0x400002)],
+    };
+    let mut encoded = Vec::new();
+    derived.to_le_bytes(&mut encoded).unwrap();
+    assert_eq!(
+        encoded,
+        [
+            &[24, 0, 0][..],
+            &[0, 0, 0, 0],
+            &[0xfe, 0xff],
+            &[1],
+            &[2, 0, 0],
+            &[1, 0, 0x40, 0, 2, 0, 0x40, 0],
+            &[0, 0, 0],
+        ]
+        .concat()
+    );
+
+    let mut conn = XConnection::dummy(VecDeque::from(encoded));
+    assert_eq!(Derived::from_le_bytes(&mut conn).unwrap(), derived);
+    assert!(!conn.has_unconsumed_data());
+}
+
+#[test]
+fn id_allocator_properties() {
+    use std::collections::HashSet;
+
+    for seed in 0..64 {
+        let mut rng = TestRng::new(seed);
+        // Servers give at least 18 contiguous bits, base is above them and top 3 bits are unused
+        let mask_bits = if seed == 0 {
+            18
+        } else {
+            rng.range(18..25) as u32
+        };
+        let shift = rng.range(0..(29 - mask_bits) as u64 + 1) as u32;
+        let id_mask = ((1u32 << mask_bits) - 1) << shift;
+        let id_base = (rng.next_u64() as u32 & 0x1fffffff) & !id_mask & !((1 << shift) - 1);
+        let mut allocator = IdAllocator::new(id_base, id_mask);
+
+        let capacity = (1u32 << mask_bits) - 1;
+        let count = if seed == 0 {
+            capacity
+        } else {
+            rng.range(1..4096) as u32
+        };
+        let mut seen = HashSet::new();
+        for _ in 0..count {
+            let id = allocator.allocate_id();
+            assert!(
+                allocator.owns(id),
+                "seed {}: {:#x} not owned",
+                seed,
+                id.value
+            );
+            assert_ne!(id.value & id_mask, 0, "seed {}: empty id", seed);
+            assert!(
+                seen.insert(id.value),
+                "seed {}: duplicate {:#x}",
+                seed,
+                id.value
+            );
+        }
+    }
+
+    // Other clients' ids are not owned
+    let allocator = IdAllocator::new(0x0400000, 0x01fffff);
+    assert!(!allocator.owns(ResourceId { value: 0x0600001 }));
+}
+
+#[test]
+fn scripted_sequence_tracking_properties() {
+    use connection::ScriptStep;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Outcome {
+        Reply(u32),
+        Error,
+        Nothing,
+    }
+
+    enum Operation {
+        Await(usize),
+        Discard(usize),
+        Sync,
+    }
+
+    let error = |sequence_number: u16| {
+        let mut error = vec![0, 3]; // BadWindow
+        error.extend(sequence_number.to_le_bytes());
+        error.extend([0u8; 28]);
+        error
+    };
+    let map_request = |sequence_number: u16, window: u32| {
+        let mut event = vec![20, 0];
+        event.extend(sequence_number.to_le_bytes());
+        event.extend(0u32.to_le_bytes()); // parent
+        event.extend(window.to_le_bytes());
+        event.resize(32, 0);
+        event
+    };
+
+    for seed in 0..256 {
+        let mut rng = TestRng::new(seed);
+        let first = rng.range(0..u16::MAX as u64 + 1) as u16;
+        let count = rng.range(1..48) as usize;
+        let outcomes = (0..count)
+            .map(|idx| match rng.range(0..3) {
+                0 => Outcome::Reply(0x400000 + idx as u32),
+                1 => Outcome::Error,
+                _ => Outcome::Nothing,
+            })
+            .collect::<Vec<_>>();
+        // Requests without reply are the ones that may get nothing back
+        let has_reply = outcomes
+            .iter()
+            .map(|outcome| match outcome {
+                Outcome::Reply(_) => true,
+                Outcome::Nothing => false,
+                Outcome::Error => rng.chance(50),
+            })
+            .collect::<Vec<_>>();
+
+        let mut stream = Vec::new();
+        let mut expected_events = Vec::new();
+        let mut expected_errors = Vec::new();
+        let sequence_number = |idx: usize| first.wrapping_add(idx as u16);
+        for (idx, outcome) in outcomes.iter().enumerate() {
+            while rng.chance(25) {
+                let window = 0x600000 + expected_events.len() as u32;
+                stream.extend(map_request(sequence_number(idx), window));
+                expected_events.push(window);
+            }
+            match outcome {
+                Outcome::Reply(focus) => {
+                    stream.extend(input_focus_reply(sequence_number(idx), *focus))
+                }
+                Outcome::Error => {
+                    stream.extend(error(sequence_number(idx)));
+                    if !has_reply[idx] {
+                        expected_errors.push(sequence_number(idx));
+                    }
+                }
+                Outcome::Nothing => {}
+            }
+        }
+        stream.extend(input_focus_reply(sequence_number(count), 0));
+
+        let mut script = Vec::new();
+        while !stream.is_empty() {
+            let len = (rng.range(1..97) as usize).min(stream.len());
+            script.push(ScriptStep::Data {
+                bytes: stream.drain(..len).collect(),
+                chunk: rng.range(1..33) as usize,
+            });
+            if rng.chance(20) {
+                script.push(ScriptStep::WouldBlock);
+            }
+        }
+        let mut display = scripted_display(script).unwrap();
+        display.next_sequence_number = SequenceNumber { value: first };
+
+        let mut pending = Vec::new();
+        let mut operations = vec![Operation::Sync];
+        for (idx, &has_reply) in has_reply.iter().enumerate() {
+            if has_reply {
+                pending.push(Some(
+                    display.send_request(&requests::GetInputFocus).unwrap(),
+                ));
+                operations.push(if rng.chance(30) {
+                    Operation::Discard(idx)
+                } else {
+                    Operation::Await(idx)
+                });
+            } else {
+                display
+                    .send_request(&requests::MapWindow {
+                        window: WindowId::from(0x400001),
+                    })
+                    .unwrap();
+                pending.push(None);
+            }
+        }
+        let mut sync = Some(display.send_request(&requests::GetInputFocus).unwrap());
+        rng.shuffle(&mut operations);
+
+        for operation in operations {
+            match operation {
+                Operation::Await(idx) => {
+                    let reply = display
+                        .await_pending_reply(pending[idx].take().unwrap())
+                        .unwrap();
+                    match (outcomes[idx], reply) {
+                        (Outcome::Reply(focus), Ok(reply)) => assert!(
+                            matches!(reply.focus, replies::Focus::Window(window) if u32::from(window) == focus),
+                            "seed {}: wrong reply of request {}",
+                            seed,
+                            idx
+                        ),
+                        (Outcome::Error, Err(err)) => {
+                            assert_eq!(err.sequence_number().value, sequence_number(idx))
+                        }
+                        (outcome, reply) => panic!(
+                            "seed {}: request {} expected {:?}, got {:?}",
+                            seed, idx, outcome, reply
+                        ),
+                    }
+                }
+                Operation::Discard(idx) => {
+                    display.discard_reply(pending[idx].take().unwrap()).unwrap()
+                }
+                Operation::Sync => {
+                    let _ = display
+                        .await_pending_reply(sync.take().unwrap())
+                        .unwrap()
+                        .unwrap();
+                }
+            }
+        }
+
+        assert!(
+            display.awaiting_replies.is_empty(),
+            "seed {}: replies left {:?}",
+            seed,
+            display.awaiting_replies.keys().collect::<Vec<_>>()
+        );
+        let errors = display
+            .errors()
+            .map(|err| err.sequence_number().value)
+            .collect::<Vec<_>>();
+        assert_eq!(errors, expected_errors, "seed {}", seed);
+        let events = display
+            .event_queue
+            .drain(..)
+            .map(|event| match event {
+                SomeEvent::MapRequest(event) => u32::from(event.window),
+                event => panic!("seed {}: unexpected {:?}", seed, event),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(events, expected_events, "seed {}", seed);
+    }
+}
@@ -182,9 +182,11 @@ GetGeometry
      2     CARD16                          border-width
      10                                    unused
 */
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, FromLeBytes)]
+#[le(pad = 10)]
 pub struct GetGeometry {
     pub depth: u8,
+    #[le(pad = 2 + 4)]
     pub root: WindowId,
     pub x: i16,
     pub y: i16,
@@ -193,33 +195,6 @@ pub struct GetGeometry {
     pub border_width: u16,
 }
 
-impl FromLeBytes for GetGeometry {
-    fn from_le_bytes(conn: &mut XConnection) -> Result<Self, Error> {
-        let depth = conn.read_u8()?;
-        let _sequence_code = conn.read_le_u16()?;
-        let _reply_length = conn.read_le_u32()?;
-        let root = WindowId(ResourceId {
-            value: conn.read_le_u32()?,
-        });
-        let x = conn.read_le_i16()?;
-        let y = conn.read_le_i16()?;
-        let width = conn.read_le_u16()?;
-        let height = conn.read_le_u16()?;
-        let border_width = conn.read_le_u16()?;
-        conn.drain(10)?;
-
-        Ok(Self {
-            depth,
-            root,
-            x,
-            y,
-            width,
-            height,
-            border_width,
-        })
-    }
-}
-
 impl_xreply!(GetGeometry);
 
 /*
@@ -237,10 +212,12 @@ QueryTree
      4n     LISTofWINDOW                   children
 */
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, FromLeBytes)]
 pub struct QueryTree {
+    #[le(pad = 1 + 2 + 4)]
     pub root: WindowId,
     pub parent: OrNone<WindowId>,
+    #[le(length = u16, pad = 14)]
     pub children: Vec<WindowId>,
 }
 
@@ -256,34 +233,6 @@ macro_rules! read_vec {
 }
 pub(crate) use read_vec;
 
-impl FromLeBytes for QueryTree {
-    fn from_le_bytes(conn: &mut XConnection) -> Result<Self, Error> {
-        let _unused = conn.read_u8()?;
-        let _sequence_code = conn.read_le_u16()?;
-        let _reply_length = conn.read_le_u32()?;
-        let root = WindowId(ResourceId {
-            value: conn.read_le_u32()?,
-        });
-        let parent = OrNone(WindowId(ResourceId {
-            value: conn.read_le_u32()?,
-        }));
-        let children_count = conn.read_le_u16()?;
-        drop(conn.drain(14)?);
-        let children = read_vec!(
-            children_count,
-            WindowId(ResourceId {
-                value: conn.read_le_u32()?,
-            })
-        );
-
-        Ok(Self {
-            root,
-            parent,
-            children,
-        })
-    }
-}
-
 impl_xreply!(QueryTree);
 
 /*
@@ -454,6 +403,50 @@ impl FromLeBytes for GetProperty {
 
 impl_xreply!(GetProperty);
 
+/// [`GetProperty`] reply interpreted according to the requested type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PropertyValue {
+    /// Window has no such property
+    Missing,
+    /// Property exists but has other type than requested, so its value was not sent
+    TypeMismatch {
+        actual_type: AtomId,
+        format: u8,
+        /// Length of the whole property in bytes
+        bytes_after: u32,
+    },
+    Value {
+        type_: AtomId,
+        format: u8,
+        /// Length of the property past the returned value in bytes
+        bytes_after: u32,
+        value: Vec<u8>,
+    },
+}
+
+impl GetProperty {
+    /// Tell apart outcomes of a request for `requested_type`, which is needed to recognize type
+    /// mismatch of empty properties
+    pub fn into_value(self, requested_type: AtomId) -> PropertyValue {
+        if self.format == 0 {
+            PropertyValue::Missing
+        } else if requested_type != AtomId::ANY_PROPERTY_TYPE && self.type_ != requested_type {
+            PropertyValue::TypeMismatch {
+                actual_type: self.type_,
+                format: self.format,
+                bytes_after: self.bytes_after,
+            }
+        } else {
+            PropertyValue::Value {
+                type_: self.type_,
+                format: self.format,
+                bytes_after: self.bytes_after,
+                value: self.value,
+            }
+        }
+    }
+}
+
 /*
 ListProperties
 ▶
@@ -498,25 +491,13 @@ GetSelectionOwner
      20                                    unused
 */
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, FromLeBytes)]
+#[le(pad = 20)]
 pub struct GetSelectionOwner {
+    #[le(pad = 1 + 2 + 4)]
     pub owner: WindowId,
 }
 
-impl FromLeBytes for GetSelectionOwner {
-    fn from_le_bytes(conn: &mut XConnection) -> Result<Self, Error> {
-        let _unused = conn.read_u8()?;
-        let _sequence_code = conn.read_le_u16()?;
-        let _reply_length = conn.read_le_u32()?;
-        let owner = WindowId(ResourceId {
-            value: conn.read_le_u32()?,
-        });
-        drop(conn.drain(20)?);
-
-        Ok(Self { owner })
-    }
-}
-
 impl_xreply!(GetSelectionOwner);
 
 /*
@@ -683,7 +664,7 @@ GetMotionEvents
      2     INT16                           y
 */
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy)]
 pub struct TimeCoord {
     pub time: Timestamp,
     pub x: i16,
@@ -715,6 +696,40 @@ impl FromLeBytes for GetMotionEvents {
     }
 }
 
+impl GetMotionEvents {
+    /// Events from the last `duration_ms` milliseconds, counting back from the newest event
+    pub fn last(&self, duration_ms: u32) -> &[TimeCoord] {
+        let Some(newest) = self.events.last() else {
+            return &[];
+        };
+        let newest = u32::from(newest.time);
+
+        let start = self
+            .events
+            .iter()
+            .position(|event| newest.wrapping_sub(u32::from(event.time)) <= duration_ms)
+            .unwrap_or(self.events.len());
+        &self.events[start..]
+    }
+
+    /// Average pointer velocity in pixels per second over the last `duration_ms` milliseconds.
+    /// Useful for detecting flicks and swipes. Returns `None` if there is not enough history.
+    pub fn velocity(&self, duration_ms: u32) -> Option<(f32, f32)> {
+        let events = self.last(duration_ms);
+        let (first, last) = (events.first()?, events.last()?);
+        let elapsed = u32::from(last.time).wrapping_sub(u32::from(first.time));
+        if elapsed == 0 {
+            return None;
+        }
+
+        let seconds = elapsed as f32 / 1000.0;
+        Some((
+            (last.x as f32 - first.x as f32) / seconds,
+            (last.y as f32 - first.y as f32) / seconds,
+        ))
+    }
+}
+
 impl_xreply!(GetMotionEvents);
 
 /*
@@ -2155,6 +2170,9 @@ pub enum SomeReply {
     GetModifierMapping(GetModifierMapping),
     ExtensionRandr(crate::extensions::randr::replies::SomeReply),
     ExtensionMitShm(crate::extensions::mit_shm::replies::SomeReply),
+    ExtensionXkb(crate::extensions::xkb::replies::SomeReply),
+    ExtensionXInput(crate::extensions::xinput::replies::SomeReply),
+    ExtensionPresent(crate::extensions::present::replies::SomeReply),
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -2201,6 +2219,9 @@ pub enum ReplyType {
     GetModifierMapping,
     ExtensionRandr(crate::extensions::randr::replies::ReplyType),
     ExtensionMitShm(crate::extensions::mit_shm::replies::ReplyType),
+    ExtensionXkb(crate::extensions::xkb::replies::ReplyType),
+    ExtensionXInput(crate::extensions::xinput::replies::ReplyType),
+    ExtensionPresent(crate::extensions::present::replies::ReplyType),
 }
 
 #[derive(Debug, Clone)]
@@ -2259,3 +2280,126 @@ impl AwaitingReply {
         }
     }
 }
+
+/// Requests and replies written by hand from the encoding section of the protocol spec, so the
+/// decoders are not only checked against themselves as in the `conformance` tests
+#[test]
+fn replies_match_reference_bytes() {
+    use crate::{connection::ScriptStep, requests, scripted_display_with_writes, Drawable};
+
+    let root = WindowId::from(0x123);
+    #[rustfmt::skip]
+    let (mut display, written) = scripted_display_with_writes([
+        // GetGeometry: depth 24, root, x -5, y 7, 640x480, border 2
+        ScriptStep::data([
+            1, 24, 1, 0, 0, 0, 0, 0,
+            0x23, 0x01, 0, 0, 0xfb, 0xff, 7, 0, 0x80, 0x02, 0xe0, 0x01, 2, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        ]),
+        // InternAtom: atom 0x1a2
+        ScriptStep::data([
+            1, 0, 2, 0, 0, 0, 0, 0,
+            0xa2, 0x01, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        ]),
+        // QueryPointer: same screen, root, no child, root -1,300, window 10,-20, Button1 mask
+        ScriptStep::data([
+            1, 1, 3, 0, 0, 0, 0, 0,
+            0x23, 0x01, 0, 0, 0, 0, 0, 0,
+            0xff, 0xff, 0x2c, 0x01, 10, 0, 0xec, 0xff, 0, 1,
+            0, 0, 0, 0, 0, 0,
+        ]),
+        // GetProperty: format 32, CARDINAL, 4 bytes after, values 1 and 0x10000
+        ScriptStep::data([
+            1, 32, 4, 0, 2, 0, 0, 0,
+            6, 0, 0, 0, 4, 0, 0, 0, 2, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            1, 0, 0, 0, 0, 0, 1, 0,
+        ]),
+        // ListExtensions: "SHAPE" and "RANDR"
+        ScriptStep::data([
+            1, 2, 5, 0, 3, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            5, b'S', b'H', b'A', b'P', b'E', 5, b'R', b'A', b'N', b'D', b'R',
+        ]),
+    ])
+    .unwrap();
+
+    let pending = display
+        .send_request(&requests::GetGeometry {
+            drawable: Drawable::Window(root),
+        })
+        .unwrap();
+    let geometry = display.await_pending_reply(pending).unwrap().unwrap();
+    assert_eq!(written.take(), [14, 0, 2, 0, 0x23, 0x01, 0, 0]);
+    assert_eq!(
+        (
+            geometry.depth,
+            u32::from(geometry.root),
+            geometry.x,
+            geometry.y,
+            geometry.width,
+            geometry.height,
+            geometry.border_width
+        ),
+        (24, 0x123, -5, 7, 640, 480, 2)
+    );
+
+    let pending = display
+        .send_request(&requests::InternAtom {
+            only_if_exists: true,
+            name: String8::from_str("WM_NAME").unwrap(),
+        })
+        .unwrap();
+    let intern = display.await_pending_reply(pending).unwrap().unwrap();
+    assert_eq!(
+        written.take(),
+        [16, 1, 4, 0, 7, 0, 0, 0, b'W', b'M', b'_', b'N', b'A', b'M', b'E', 0]
+    );
+    assert_eq!(u32::from(intern.atom), 0x1a2);
+
+    let pending = display
+        .send_request(&requests::QueryPointer { window: root })
+        .unwrap();
+    let pointer = display.await_pending_reply(pending).unwrap().unwrap();
+    assert_eq!(written.take(), [38, 0, 2, 0, 0x23, 0x01, 0, 0]);
+    assert!(pointer.same_screen);
+    assert_eq!(u32::from(pointer.root), 0x123);
+    assert!(pointer.child.value().is_none());
+    assert_eq!(
+        (pointer.root_x, pointer.root_y, pointer.win_x, pointer.win_y),
+        (-1, 300, 10, -20)
+    );
+    assert_eq!(pointer.mask, 0x100);
+
+    let pending = display
+        .send_request(&requests::GetProperty {
+            delete: false,
+            window: root,
+            property: AtomId::WM_NAME,
+            type_: AtomId::CARDINAL,
+            long_offset: 0,
+            long_length: 2,
+        })
+        .unwrap();
+    let property = display.await_pending_reply(pending).unwrap().unwrap();
+    #[rustfmt::skip]
+    assert_eq!(
+        written.take(),
+        [20, 0, 6, 0, 0x23, 0x01, 0, 0, 39, 0, 0, 0, 6, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0]
+    );
+    assert_eq!(
+        (property.format, u32::from(property.type_)),
+        (32, u32::from(AtomId::CARDINAL))
+    );
+    assert_eq!((property.bytes_after, property.length_of_value), (4, 2));
+    assert_eq!(property.value, [1, 0, 0, 0, 0, 0, 1, 0]);
+
+    let pending = display.send_request(&requests::ListExtensions).unwrap();
+    let extensions = display.await_pending_reply(pending).unwrap().unwrap();
+    assert_eq!(written.take(), [99, 0, 1, 0]);
+    assert_eq!(
+        extensions.names.strings,
+        [b"SHAPE".to_vec(), b"RANDR".to_vec()]
+    );
+}
@@ -5,12 +5,11 @@ use crate::{
     replies::{ReplyType, String8},
     utils::{bitmask, impl_enum, pad},
     ColormapId, CursorId, Drawable, FontId, FromLeBytes, GContextId, ListOfStr, OrNone, PixmapId,
-    Point, Rectangle, ToLeBytes, VisualId, WindowClass, WindowId, WindowVisual,
+    Point, Rectangle, ResourceId, ToLeBytes, VisualId, WindowClass, WindowId, WindowVisual,
 };
 use std::{
     fmt,
     io::{self, Write},
-    mem,
 };
 
 pub(crate) mod opcodes;
@@ -267,11 +266,30 @@ impl<const N: usize> ListOfValues<N> {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-pub(crate) enum XProtocolVersion {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XProtocolVersion {
     V11_0,
 }
 
+impl XProtocolVersion {
+    /// Versions this crate can speak, newest first
+    pub const SUPPORTED: &'static [Self] = &[Self::V11_0];
+
+    pub fn from_numbers(major: u16, minor: u16) -> Option<Self> {
+        Self::SUPPORTED
+            .iter()
+            .copied()
+            .find(|version| version.numbers() == (major, minor))
+    }
+
+    /// Major and minor version numbers
+    pub fn numbers(self) -> (u16, u16) {
+        match self {
+            Self::V11_0 => (11, 0),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct InitializeConnection {
     pub major_version: u16,
@@ -286,13 +304,12 @@ impl InitializeConnection {
         authorization_protocol_name: Vec<u8>,
         authorization_protocol_data: Vec<u8>,
     ) -> Self {
-        match protocol_version {
-            XProtocolVersion::V11_0 => Self {
-                major_version: 11,
-                minor_version: 0,
-                authorization_protocol_name,
-                authorization_protocol_data,
-            },
+        let (major_version, minor_version) = protocol_version.numbers();
+        Self {
+            major_version,
+            minor_version,
+            authorization_protocol_name,
+            authorization_protocol_data,
         }
     }
 }
@@ -416,6 +433,32 @@ impl_raw_fields! {
     }
 }
 
+impl WindowCreationAttributes {
+    /// Application window receiving `event_mask`. With `background` the server fills exposed
+    /// areas with that pixel before `Expose` is sent, without it contents below stay until the
+    /// client draws over them, which avoids flicker of windows that redraw everything.
+    pub fn for_top_level(event_mask: EventType, background: Option<u32>) -> Self {
+        let attributes = Self::new().set_event_mask(event_mask);
+        match background {
+            Some(pixel) => attributes.set_background_pixel(pixel),
+            None => attributes,
+        }
+    }
+
+    /// Menu, tooltip or other short lived window that bypasses the window manager. Server may
+    /// save contents below it, so windows don't have to redraw when it goes away.
+    pub fn override_redirect_popup() -> Self {
+        Self::new().set_override_redirect(1).set_save_under(1)
+    }
+
+    /// `InputOnly` window that bypasses the window manager, e.g. selection owner, EWMH check
+    /// window or transparent overlay catching pointer input. `InputOnly` windows accept only
+    /// `win-gravity`, `event-mask`, `do-not-propagate-mask`, `override-redirect` and `cursor`.
+    pub fn input_only_overlay() -> Self {
+        Self::new().set_override_redirect(1)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct CreateWindow {
     pub depth: u8,
@@ -1303,7 +1346,7 @@ GrabPointer
 pub struct GrabPointer {
     pub owner_events: bool,
     pub grab_window: WindowId,
-    pub event_mask: u16, // TODO: Type
+    pub event_mask: PointerEventMask,
     pub pointer_mode: GrabMode,
     pub keyboard_mode: GrabMode,
     pub confine_to: OrNone<WindowId>,
@@ -1317,7 +1360,7 @@ impl ToLeBytes for GrabPointer {
         write_le_bytes!(w, self.owner_events as u8);
         write_le_bytes!(w, 6u16); // length
         write_le_bytes!(w, self.grab_window);
-        write_le_bytes!(w, self.event_mask);
+        write_le_bytes!(w, self.event_mask.raw());
         write_le_bytes!(w, self.pointer_mode);
         write_le_bytes!(w, self.keyboard_mode);
         write_le_bytes!(w, self.confine_to.0);
@@ -1330,6 +1373,47 @@ impl ToLeBytes for GrabPointer {
 
 impl_xrequest_with_response!(GrabPointer);
 
+impl GrabPointer {
+    /// Asynchronous grab of `grab_window` without confining the pointer or changing the cursor
+    pub fn new(grab_window: WindowId, event_mask: PointerEventMask) -> Self {
+        Self {
+            owner_events: false,
+            grab_window,
+            event_mask,
+            pointer_mode: GrabMode::Asynchronous,
+            keyboard_mode: GrabMode::Asynchronous,
+            confine_to: OrNone::none(),
+            cursor: OrNone::none(),
+            time: Timestamp::CurrentTime,
+        }
+    }
+
+    /// Show `cursor` while the grab is active, e.g. a move or resize cursor when dragging
+    pub fn with_cursor(self, cursor: CursorId) -> Self {
+        Self {
+            cursor: OrNone::new(cursor),
+            ..self
+        }
+    }
+
+    /// Keep the pointer inside `window` while the grab is active
+    pub fn confined_to(self, window: WindowId) -> Self {
+        Self {
+            confine_to: OrNone::new(window),
+            ..self
+        }
+    }
+
+    /// Report events to the windows of this client as usual, only events outside of them go to
+    /// the grab window
+    pub fn with_owner_events(self) -> Self {
+        Self {
+            owner_events: true,
+            ..self
+        }
+    }
+}
+
 /*
 UngrabPointer
      1     27                              opcode
@@ -1385,7 +1469,7 @@ GrabButton
 pub struct GrabButton {
     pub owner_events: bool,
     pub grab_window: WindowId,
-    pub event_mask: u16, // TODO: type
+    pub event_mask: PointerEventMask,
     pub pointer_mode: GrabMode,
     pub keyboard_mode: GrabMode,
     pub confine_to: OrNone<WindowId>,
@@ -1400,7 +1484,7 @@ impl ToLeBytes for GrabButton {
         write_le_bytes!(w, self.owner_events as u8);
         write_le_bytes!(w, 6u16); // length
         write_le_bytes!(w, self.grab_window);
-        write_le_bytes!(w, self.event_mask);
+        write_le_bytes!(w, self.event_mask.raw());
         write_le_bytes!(w, self.pointer_mode);
         write_le_bytes!(w, self.keyboard_mode);
         write_le_bytes!(w, self.confine_to.0);
@@ -1466,7 +1550,7 @@ ChangeActivePointerGrab
 pub struct ChangeActivePointerGrab {
     pub cursor: OrNone<CursorId>,
     pub time: Timestamp,
-    pub event_mask: u16, // TODO: TYpe
+    pub event_mask: PointerEventMask,
 }
 
 impl ToLeBytes for ChangeActivePointerGrab {
@@ -1476,7 +1560,7 @@ impl ToLeBytes for ChangeActivePointerGrab {
         write_le_bytes!(w, 4u16); // length
         write_le_bytes!(w, self.cursor.0);
         write_le_bytes!(w, self.time);
-        write_le_bytes!(w, self.event_mask);
+        write_le_bytes!(w, self.event_mask.raw());
         write_le_bytes!(w, 0u16); // unused
 
         Ok(())
@@ -1624,9 +1708,9 @@ UngrabKey
 
 #[derive(Debug, Clone, Copy)]
 pub struct UngrabKey {
-    pub key: u8, // TODO: Type
+    pub key: KeyCode,
     pub grab_window: WindowId,
-    pub modifiers: u16, // TODO: Tyep
+    pub modifiers: KeyModifier,
 }
 
 impl ToLeBytes for UngrabKey {
@@ -1635,7 +1719,7 @@ impl ToLeBytes for UngrabKey {
         write_le_bytes!(w, self.key);
         write_le_bytes!(w, 3u16); // length
         write_le_bytes!(w, self.grab_window);
-        write_le_bytes!(w, self.modifiers);
+        write_le_bytes!(w, self.modifiers.raw() as u16);
         w.write_all(&[0u8; 2])?; // unused
 
         Ok(())
@@ -2546,7 +2630,6 @@ impl ToLeBytes for SetDashes {
         write_le_bytes!(w, self.gc);
         write_le_bytes!(w, self.dash_offset);
         write_le_bytes!(w, n as u16);
-        write_le_bytes!(w, 0u16); // unused
         w.write_all(&self.dashes)?;
         w.write_all(&vec![0u8; p])?; // pad
 
@@ -2995,7 +3078,18 @@ pub struct Arc {
 
 impl Arc {
     fn to_le_bytes(self) -> [u8; 12] {
-        unsafe { mem::transmute(self) }
+        let mut raw = [0; 12];
+        for (bytes, value) in raw.chunks_exact_mut(2).zip([
+            self.x,
+            self.y,
+            self.width as i16,
+            self.height as i16,
+            self.angle1,
+            self.angle2,
+        ]) {
+            bytes.copy_from_slice(&value.to_le_bytes());
+        }
+        raw
     }
 }
 
@@ -3124,6 +3218,32 @@ impl ToLeBytes for PolyFillRectangle {
 
 impl_xrequest_without_response!(PolyFillRectangle);
 
+impl PolyFillRectangle {
+    /// Length of the request without rectangles, in 4 byte units
+    const HEADER_LENGTH: usize = 3;
+
+    /// Most rectangles that fit into one request
+    pub fn max_rectangles(maximum_request_length: u16) -> usize {
+        (maximum_request_length as usize).saturating_sub(Self::HEADER_LENGTH) / 2
+    }
+
+    /// Fewest requests filling all `rectangles`, each fitting into `maximum_request_length`
+    pub fn batched<'a>(
+        drawable: Drawable,
+        gc: GContextId,
+        rectangles: &'a [Rectangle],
+        maximum_request_length: u16,
+    ) -> impl Iterator<Item = Self> + 'a {
+        rectangles
+            .chunks(Self::max_rectangles(maximum_request_length).max(1))
+            .map(move |chunk| Self {
+                drawable,
+                gc,
+                rectangles: chunk.to_vec(),
+            })
+    }
+}
+
 /*
 PolyFillArc
      1     71                              opcode
@@ -3284,6 +3404,36 @@ impl<'data> XRequestBase for PutImage<'data> {
 
 impl<'data> XRequest for PutImage<'data> {}
 
+impl<'data> PutImage<'data> {
+    /// Length of the request without data, in 4 byte units
+    const HEADER_LENGTH: usize = 6;
+
+    /// Split into bands of whole rows, each fitting into `maximum_request_length`. Rows are
+    /// `data.len() / height` bytes long, so `data` must not continue after the last row.
+    pub fn banded(self, maximum_request_length: u16) -> impl Iterator<Item = Self> + 'data {
+        let row_bytes = self.data.len() / (self.height as usize).max(1);
+        let max_bytes = (maximum_request_length as usize).saturating_sub(Self::HEADER_LENGTH) * 4;
+        let rows_per_band = (max_bytes / row_bytes.max(1)).max(1);
+        let band_bytes = (rows_per_band * row_bytes).max(1);
+
+        let data = self.data;
+        // Empty image is still sent once
+        let band_count = data.len().div_ceil(band_bytes).max(1);
+        (0..band_count).map(move |idx| {
+            let band = &data[idx * band_bytes..((idx + 1) * band_bytes).min(data.len())];
+            Self {
+                height: band
+                    .len()
+                    .checked_div(row_bytes)
+                    .map_or(self.height, |rows| rows as u16),
+                dst_y: self.dst_y + (idx * rows_per_band) as i16,
+                data: band,
+                ..self.clone()
+            }
+        })
+    }
+}
+
 /*
 GetImage
      1     73                              opcode
@@ -3367,6 +3517,16 @@ pub enum TextItem8 {
     FontShift { font_bytes: [u8; 4] },
 }
 
+impl TextItem8 {
+    /// Length in bytes
+    fn len(&self) -> usize {
+        match self {
+            TextItem8::Text { string, .. } => 2 + string.len(),
+            TextItem8::FontShift { .. } => 5,
+        }
+    }
+}
+
 impl ToLeBytes for TextItem8 {
     fn to_le_bytes(&self, w: &mut impl Write) -> io::Result<()> {
         match self {
@@ -3398,7 +3558,7 @@ pub struct PolyText8 {
 
 impl ToLeBytes for PolyText8 {
     fn to_le_bytes(&self, w: &mut impl Write) -> io::Result<()> {
-        let n = self.items.len();
+        let n = self.items.iter().map(|item| item.len()).sum::<usize>();
         let p = pad(n);
         let request_length = 4 + (n + p) / 4;
 
@@ -3414,10 +3574,7 @@ impl ToLeBytes for PolyText8 {
             item.to_le_bytes(w)?;
         }
 
-        debug_assert!([0, 1].contains(&p));
-        if p == 1 {
-            write_le_bytes!(w, 0u8); // pad
-        }
+        w.write_all(&vec![0u8; p])?; // pad
 
         Ok(())
     }
@@ -3466,6 +3623,16 @@ pub enum TextItem16 {
     FontShift { font_bytes: [u8; 4] },
 }
 
+impl TextItem16 {
+    /// Length in bytes
+    fn len(&self) -> usize {
+        match self {
+            TextItem16::Text { string, .. } => 2 + string.len() * 2,
+            TextItem16::FontShift { .. } => 5,
+        }
+    }
+}
+
 impl ToLeBytes for TextItem16 {
     fn to_le_bytes(&self, w: &mut impl Write) -> io::Result<()> {
         match self {
@@ -3491,7 +3658,7 @@ impl ToLeBytes for TextItem16 {
 
 impl ToLeBytes for PolyText16 {
     fn to_le_bytes(&self, w: &mut impl Write) -> io::Result<()> {
-        let n = self.items.len();
+        let n = self.items.iter().map(|item| item.len()).sum::<usize>();
         let p = pad(n);
         let request_length = 4 + (n + p) / 4;
 
@@ -4212,7 +4379,6 @@ impl ToLeBytes for CreateCursor {
         write_le_bytes!(w, self.cid);
         write_le_bytes!(w, self.source);
         write_le_bytes!(w, self.mask.0);
-        write_le_bytes!(w, 0u32); // None
         write_le_bytes!(w, self.fore_red);
         write_le_bytes!(w, self.fore_green);
         write_le_bytes!(w, self.fore_blue);
@@ -4270,7 +4436,6 @@ impl ToLeBytes for CreateGlyphCursor {
         write_le_bytes!(w, self.cid);
         write_le_bytes!(w, self.source_font);
         write_le_bytes!(w, self.mask_font.0);
-        write_le_bytes!(w, 0u32); // None
         write_le_bytes!(w, self.source_char);
         write_le_bytes!(w, self.mask_char);
         write_le_bytes!(w, self.fore_red);
@@ -4481,7 +4646,7 @@ pub struct ChangeKeyboardMapping {
 impl ToLeBytes for ChangeKeyboardMapping {
     fn to_le_bytes(&self, w: &mut impl Write) -> io::Result<()> {
         let nm = self.keycode_count as usize * self.keysyms_per_keycode as usize;
-        let request_length = 2 + nm / 4;
+        let request_length = 2 + nm;
 
         write_le_bytes!(w, opcodes::CHANGE_KEYBOARD_MAPPING);
         write_le_bytes!(w, self.keycode_count);
@@ -4940,9 +5105,89 @@ KillClient
           0     AllTemporary
 */
 
+/// Resource whose creator is killed by [`KillClient`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KillTarget {
+    Window(WindowId),
+    Pixmap(PixmapId),
+    Cursor(CursorId),
+    Font(FontId),
+    GContext(GContextId),
+    Colormap(ColormapId),
+    /// Resource of an extension or one without known kind
+    Other(ResourceId),
+}
+
+impl KillTarget {
+    fn id(self) -> ResourceId {
+        match self {
+            KillTarget::Window(id) => id.id(),
+            KillTarget::Pixmap(id) => id.id(),
+            KillTarget::Cursor(id) => id.id(),
+            KillTarget::Font(id) => id.id(),
+            KillTarget::GContext(id) => id.id(),
+            KillTarget::Colormap(id) => id.id(),
+            KillTarget::Other(id) => id,
+        }
+    }
+}
+
+macro_rules! impl_kill_target_from {
+    ($($variant:ident($ty:ident),)*) => {
+        $(
+            impl From<$ty> for KillTarget {
+                fn from(id: $ty) -> Self {
+                    Self::$variant(id)
+                }
+            }
+
+            impl From<$ty> for KillClientResource {
+                fn from(id: $ty) -> Self {
+                    Self::Resource(KillTarget::$variant(id))
+                }
+            }
+        )*
+    };
+}
+
+impl_kill_target_from! {
+    Window(WindowId),
+    Pixmap(PixmapId),
+    Cursor(CursorId),
+    Font(FontId),
+    GContext(GContextId),
+    Colormap(ColormapId),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KillClientResource {
+    /// Destroy all resources of clients that terminated in
+    /// [`SetCloseDownModeMode::RetainTemporary`] mode
+    AllTemporary,
+
+    /// Kill the client that created this resource
+    Resource(KillTarget),
+}
+
+impl KillClientResource {
+    #[inline(always)]
+    fn to_le_bytes(self) -> [u8; 4] {
+        match self {
+            KillClientResource::AllTemporary => 0u32.to_le_bytes(),
+            KillClientResource::Resource(target) => target.id().value().to_le_bytes(),
+        }
+    }
+}
+
+impl From<KillTarget> for KillClientResource {
+    fn from(target: KillTarget) -> Self {
+        Self::Resource(target)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct KillClient {
-    pub resource: u32,
+    pub resource: KillClientResource,
 }
 
 impl ToLeBytes for KillClient {
@@ -5096,12 +5341,14 @@ SetModifierMapping
 
 #[derive(Debug, Clone)]
 pub struct SetModifierMapping {
+    /// Keycodes of `Shift`, `Lock`, `Control` and `Mod1` to `Mod5` in order, same number for
+    /// each modifier
     pub keycodes: Vec<KeyCode>,
 }
 
 impl ToLeBytes for SetModifierMapping {
     fn to_le_bytes(&self, w: &mut impl Write) -> io::Result<()> {
-        let n = self.keycodes.len();
+        let n = self.keycodes.len() / 8;
         let request_length = 1 + 2 * n;
 
         write_le_bytes!(w, opcodes::SET_MODIFIER_MAPPING);
@@ -5174,6 +5421,26 @@ impl ToLeBytes for NoOperation {
 
 impl_xrequest_without_response!(NoOperation);
 
+bitmask! {
+    #[repr(u16)]
+    /// Events selected by pointer grabs, same bits as in [`EventType`]
+    bitmask PointerEventMask {
+        BUTTON_PRESS = 0x0004,
+        BUTTON_RELEASE = 0x0008,
+        ENTER_WINDOW = 0x0010,
+        LEAVE_WINDOW = 0x0020,
+        POINTER_MOTION = 0x0040,
+        POINTER_MOTION_HINT = 0x0080,
+        BUTTON1_MOTION = 0x0100,
+        BUTTON2_MOTION = 0x0200,
+        BUTTON3_MOTION = 0x0400,
+        BUTTON4_MOTION = 0x0800,
+        BUTTON5_MOTION = 0x1000,
+        BUTTON_MOTION = 0x2000,
+        KEYMAP_STATE = 0x4000,
+    }
+}
+
 bitmask! {
     #[repr(u16)]
     bitmask KeyModifier {
@@ -5190,3 +5457,181 @@ bitmask! {
         ANY = 0x8000,
     }
 }
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseKeyModifierError {
+    pub name: String,
+}
+
+impl fmt::Display for ParseKeyModifierError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unknown key modifier: '{}'", self.name)
+    }
+}
+
+/// Parses modifiers joined with `+`, e.g. `Mod4+Shift`, case insensitive.
+///
+/// `Alt` is an alias of `Mod1`, `Super` and `Hyper` are aliases of `Mod4` as in the default XKB
+/// modifier mapping. `Any` is [`KeyModifier::ANY`] and an empty string is no modifiers.
+impl std::str::FromStr for KeyModifier {
+    type Err = ParseKeyModifierError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().is_empty() {
+            return Ok(Self::EMPTY_MASK);
+        }
+
+        s.split('+')
+            .map(|name| match name.trim().to_ascii_lowercase().as_str() {
+                "shift" => Ok(Self::SHIFT),
+                "lock" => Ok(Self::LOCK),
+                "control" | "ctrl" => Ok(Self::CONTROL),
+                "mod1" | "alt" => Ok(Self::MOD_1),
+                "mod2" => Ok(Self::MOD_2),
+                "mod3" => Ok(Self::MOD_3),
+                "mod4" | "super" | "hyper" => Ok(Self::MOD_4),
+                "mod5" => Ok(Self::MOD_5),
+                "any" => Ok(Self::ANY),
+                _ => Err(ParseKeyModifierError {
+                    name: name.to_string(),
+                }),
+            })
+            .try_fold(Self::EMPTY_MASK, |acc, modifier| Ok(acc | modifier?))
+    }
+}
+
+#[test]
+fn poly_fill_rectangle_batches() {
+    let rectangle = Rectangle {
+        x: 0,
+        y: 0,
+        width: 1,
+        height: 1,
+    };
+    let rectangles = vec![rectangle; 10];
+    let batches = PolyFillRectangle::batched(
+        Drawable::Window(WindowId::from(1)),
+        GContextId::from(2),
+        &rectangles,
+        3 + 2 * 4,
+    )
+    .map(|request| request.rectangles.len())
+    .collect::<Vec<_>>();
+    assert_eq!(batches, vec![4, 4, 2]);
+
+    let mut buf = Vec::new();
+    PolyFillRectangle::batched(
+        Drawable::Window(WindowId::from(1)),
+        GContextId::from(2),
+        &rectangles,
+        u16::MAX,
+    )
+    .for_each(|request| request.to_le_bytes(&mut buf).unwrap());
+    assert_eq!(buf.len(), (3 + 2 * 10) * 4);
+    assert_eq!(u16::from_le_bytes([buf[2], buf[3]]), 3 + 2 * 10);
+}
+
+#[test]
+fn put_image_bands() {
+    // 100 rows of 400 bytes, 10 rows fit into a band
+    let data = vec![0u8; 400 * 100];
+    let maximum_request_length = (6 + 1000 + 50) as u16;
+    let image = PutImage {
+        format: PutImageFormat::ZPixmap,
+        drawable: Drawable::Window(WindowId::from(1)),
+        gc: GContextId::from(2),
+        width: 100,
+        height: 100,
+        dst_x: 5,
+        dst_y: 7,
+        left_pad: 0,
+        depth: 24,
+        data: &data,
+    };
+    let bands = image.banded(maximum_request_length).collect::<Vec<_>>();
+    assert_eq!(bands.len(), 10);
+    assert_eq!((bands[3].dst_y, bands[3].height), (7 + 30, 10));
+    for band in &bands {
+        let mut buf = Vec::new();
+        band.to_le_bytes(&mut buf).unwrap();
+        let length = u16::from_le_bytes([buf[2], buf[3]]);
+        assert!(length <= maximum_request_length);
+        assert_eq!(buf.len(), length as usize * 4);
+    }
+    assert_eq!(bands.iter().map(|band| band.height).sum::<u16>(), 100);
+}
+
+#[test]
+fn kill_client_typed_target() {
+    let window = WindowId::unchecked_from(0x400002);
+    let mut buf = Vec::new();
+    KillClient {
+        resource: KillClientResource::from(window),
+    }
+    .to_le_bytes(&mut buf)
+    .unwrap();
+    assert_eq!(buf[4..8], 0x400002u32.to_le_bytes());
+    assert_eq!(
+        KillClientResource::from(KillTarget::Window(window)),
+        KillClientResource::from(window)
+    );
+}
+
+#[test]
+fn window_creation_attributes_presets() {
+    let mask_and_values = |attributes: WindowCreationAttributes| {
+        let mut buf = Vec::new();
+        attributes.values.to_le_bytes_if_set(&mut buf).unwrap();
+        (attributes.values.mask_and_count().0, buf)
+    };
+
+    let (mask, values) = mask_and_values(WindowCreationAttributes::for_top_level(
+        EventType::EXPOSURE,
+        Some(0xffffff),
+    ));
+    assert_eq!(mask, 0x00000002 | 0x00000800);
+    assert_eq!(values[..4], 0xffffffu32.to_le_bytes());
+    assert_eq!(
+        values[4..],
+        EventType::EXPOSURE.to_raw_value().to_le_bytes()
+    );
+    assert_eq!(
+        mask_and_values(WindowCreationAttributes::for_top_level(
+            EventType::EXPOSURE,
+            None
+        ))
+        .0,
+        0x00000800
+    );
+
+    assert_eq!(
+        mask_and_values(WindowCreationAttributes::override_redirect_popup()).0,
+        0x00000200 | 0x00000400
+    );
+    assert_eq!(
+        mask_and_values(WindowCreationAttributes::input_only_overlay()),
+        (0x00000200, 1u32.to_le_bytes().to_vec())
+    );
+}
+
+#[test]
+fn key_modifier_from_str() {
+    use std::str::FromStr;
+
+    assert_eq!(
+        KeyModifier::from_str("Mod4+Shift"),
+        Ok(KeyModifier::MOD_4 | KeyModifier::SHIFT)
+    );
+    assert_eq!(
+        KeyModifier::from_str("ctrl + alt"),
+        Ok(KeyModifier::CONTROL | KeyModifier::MOD_1)
+    );
+    assert_eq!(KeyModifier::from_str("Super"), Ok(KeyModifier::MOD_4));
+    assert_eq!(KeyModifier::from_str(""), Ok(KeyModifier::EMPTY_MASK));
+    assert_eq!(
+        KeyModifier::from_str("Mod4+Meta"),
+        Err(ParseKeyModifierError {
+            name: "Meta".to_string()
+        })
+    );
+}
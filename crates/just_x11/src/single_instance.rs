@@ -0,0 +1,203 @@
+//! Single instance applications, e.g. a launcher started again from a key binding while it is
+//! already open
+//!
+//! The running instance owns selection `_JUST_APP_<name>`. Later launches find the owner and
+//! send it a `_JUST_APP_RAISE` client message instead of starting again, the running instance
+//! should then show or focus its window.
+
+use crate::{
+    atoms::AtomId,
+    error::Error,
+    events::{ClientMessage, MessageFormat, SomeEvent},
+    replies::String8,
+    requests::{self, Timestamp, WindowCreationAttributes},
+    OrNone, WindowClass, WindowId, WindowVisual, XDisplay,
+};
+use std::str::FromStr;
+
+/// Result of [`XDisplay::claim_single_instance`]
+#[derive(Debug, Clone, Copy)]
+pub enum SingleInstance {
+    /// No other instance is running, this one holds the lock as long as the display is open
+    Primary(InstanceLock),
+    /// Instance owning the selection was asked to raise itself, this one should exit
+    Forwarded(WindowId),
+}
+
+/// Selection owned by the running instance
+#[derive(Debug, Clone, Copy)]
+pub struct InstanceLock {
+    pub selection: AtomId,
+    /// Window owning the selection, raise requests are sent to it
+    pub owner: WindowId,
+    raise: AtomId,
+}
+
+impl InstanceLock {
+    /// Whether `event` is a request from another launch to show this instance
+    pub fn is_raise_request(&self, event: &SomeEvent) -> bool {
+        matches!(
+            event,
+            SomeEvent::ClientMessage(message)
+                if message.window == self.owner && message.type_message == self.raise
+        )
+    }
+
+    /// Whether other client took the selection, e.g. with the lock released by a hung instance
+    pub fn is_lost(&self, event: &SomeEvent) -> bool {
+        matches!(
+            event,
+            SomeEvent::SelectionClear(clear)
+                if clear.selection == self.selection && clear.owner == self.owner
+        )
+    }
+
+    /// Give up the selection so the next launch starts a new instance
+    pub fn release(self, display: &mut XDisplay) -> Result<(), Error> {
+        display.send_request(&requests::DestroyWindow { window: self.owner })?;
+        display.flush()
+    }
+}
+
+impl XDisplay {
+    /// Claim selection `_JUST_APP_<name>` with a window created on `root`, or ask its current
+    /// owner to raise itself. Blocks for a few round trips.
+    ///
+    /// Selection is taken with `CurrentTime`, as there is nothing to order it with but other
+    /// launches and they only take it when it has no owner.
+    pub fn claim_single_instance(
+        &mut self,
+        root: WindowId,
+        name: &str,
+    ) -> Result<SingleInstance, Error> {
+        let selection = self.intern_atom(&format!("_JUST_APP_{}", name))?;
+        let raise = self.intern_atom("_JUST_APP_RAISE")?;
+
+        if let Some(owner) = self.selection_owner(selection)? {
+            self.send_raise(owner, raise)?;
+            return Ok(SingleInstance::Forwarded(owner));
+        }
+
+        let owner = WindowId::from_resource(self.id_allocator().allocate_id());
+        self.send_request(&requests::CreateWindow {
+            depth: 0,
+            wid: owner,
+            parent: root,
+            x: -1,
+            y: -1,
+            width: 1,
+            height: 1,
+            border_width: 0,
+            window_class: WindowClass::InputOnly,
+            visual: WindowVisual::CopyFromParent,
+            attributes: WindowCreationAttributes::input_only_overlay(),
+        })?;
+        self.send_request(&requests::SetSelectionOwner {
+            owner: OrNone::new(owner),
+            selection,
+            time: Timestamp::CurrentTime,
+        })?;
+
+        match self.selection_owner(selection)? {
+            Some(current) if current == owner => Ok(SingleInstance::Primary(InstanceLock {
+                selection,
+                owner,
+                raise,
+            })),
+            // Other instance starting at the same time was faster
+            current => {
+                self.send_request(&requests::DestroyWindow { window: owner })?;
+                let current = current.unwrap_or(owner);
+                if current != owner {
+                    self.send_raise(current, raise)?;
+                }
+                self.flush()?;
+                Ok(SingleInstance::Forwarded(current))
+            }
+        }
+    }
+
+    fn intern_atom(&mut self, name: &str) -> Result<AtomId, Error> {
+        let pending = self.send_request(&requests::InternAtom {
+            only_if_exists: false,
+            name: String8::from_str(name).unwrap(),
+        })?;
+        self.flush()?;
+        Ok(self.await_pending_reply(pending)?.unwrap().atom)
+    }
+
+    fn selection_owner(&mut self, selection: AtomId) -> Result<Option<WindowId>, Error> {
+        let pending = self.send_request(&requests::GetSelectionOwner { selection })?;
+        self.flush()?;
+        let owner = self.await_pending_reply(pending)?.unwrap().owner;
+        Ok((u32::from(owner) != 0).then_some(owner))
+    }
+
+    /// Empty event mask delivers the message to the client that created `owner`
+    fn send_raise(&mut self, owner: WindowId, raise: AtomId) -> Result<(), Error> {
+        let event = ClientMessage {
+            event_code: 33,
+            format: MessageFormat::Format32,
+            sequence_number: 0,
+            window: owner,
+            type_message: raise,
+            data: [0; 20],
+        };
+        self.send_request(&requests::SendEvent {
+            propagate: false,
+            destination: owner,
+            event_mask: 0,
+            event: event.to_le_bytes(),
+        })?;
+        self.flush()
+    }
+}
+
+#[test]
+fn second_instance_is_forwarded() {
+    use crate::{connection::ScriptStep, scripted_display};
+
+    let reply = |sequence_number: u16, value: u32| {
+        let mut reply = vec![1, 0];
+        reply.extend(sequence_number.to_le_bytes());
+        reply.extend(0u32.to_le_bytes()); // reply length
+        reply.extend(value.to_le_bytes());
+        reply.extend([0u8; 20]);
+        reply
+    };
+    let root = WindowId::from(0x100);
+
+    // Selection has no owner until our window takes it
+    let mut display = scripted_display([
+        ScriptStep::data(reply(1, 300)),
+        ScriptStep::data(reply(2, 301)),
+        ScriptStep::data(reply(3, 0)),
+        ScriptStep::data(reply(6, 0x400001)),
+    ])
+    .unwrap();
+    let SingleInstance::Primary(lock) = display.claim_single_instance(root, "test").unwrap() else {
+        panic!("instance is not primary");
+    };
+    assert_eq!(u32::from(lock.owner), 0x400001);
+    let raise = SomeEvent::ClientMessage(ClientMessage {
+        event_code: 33,
+        format: MessageFormat::Format32,
+        sequence_number: 0,
+        window: lock.owner,
+        type_message: AtomId::from(301),
+        data: [0; 20],
+    });
+    assert!(lock.is_raise_request(&raise));
+
+    // Other instance owns it already
+    let mut display = scripted_display([
+        ScriptStep::data(reply(1, 300)),
+        ScriptStep::data(reply(2, 301)),
+        ScriptStep::data(reply(3, 0x800001)),
+    ])
+    .unwrap();
+    assert!(matches!(
+        display.claim_single_instance(root, "test").unwrap(),
+        SingleInstance::Forwarded(owner) if u32::from(owner) == 0x800001
+    ));
+}
@@ -0,0 +1,61 @@
+//! Counters of traffic on a connection, to diagnose event storms and chatty clients
+
+use crate::events;
+
+/// Totals since the connection was opened or [`crate::XDisplay::reset_stats`] was called
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stats {
+    /// Decoded events by code, with the `SendEvent` flag cleared
+    events: [u64; 128],
+    pub replies: u64,
+    /// Errors of all requests, both returned instead of replies and queued
+    pub errors: u64,
+    pub bytes_in: u64,
+    pub bytes_out: u64,
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Self {
+            events: [0; 128],
+            replies: 0,
+            errors: 0,
+            bytes_in: 0,
+            bytes_out: 0,
+        }
+    }
+}
+
+impl Stats {
+    pub(crate) fn count_event(&mut self, event_code: u8) {
+        self.events[(event_code & 0x7f) as usize] += 1;
+    }
+
+    /// Number of decoded events with `event_code`. Extension events, e.g. RandR notifications,
+    /// have codes assigned by the server.
+    pub fn event_count(&self, event_code: u8) -> u64 {
+        self.events[(event_code & 0x7f) as usize]
+    }
+
+    /// Codes of received events with their counts, in order of codes
+    pub fn events(&self) -> impl Iterator<Item = (u8, u64)> + '_ {
+        (0u8..)
+            .zip(self.events.iter().copied())
+            .filter(|(_, count)| *count != 0)
+    }
+
+    pub fn total_events(&self) -> u64 {
+        self.events.iter().sum()
+    }
+
+    /// Events by name, extension events are named `event <code>`
+    pub fn named_events(&self) -> impl Iterator<Item = (String, u64)> + '_ {
+        self.events().map(|(code, count)| {
+            let name = match events::event_name(code) {
+                Some(name) => name.to_string(),
+                None => format!("event {}", code),
+            };
+            (name, count)
+        })
+    }
+}
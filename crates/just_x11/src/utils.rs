@@ -132,6 +132,14 @@ macro_rules! bitmask {
                 Ok(Self {value})
             }
         }
+
+        #[automatically_derived]
+        impl $crate::derive::WriteLe for $ty {
+            #[inline]
+            fn write_le(&self, w: &mut impl ::std::io::Write) -> ::std::io::Result<()> {
+                $crate::derive::WriteLe::write_le(&self.value, w)
+            }
+        }
     };
     ($($stuff:tt)*) => {
         compile_error!("Bitmask must contain #[repr(type)] at the very top (even above doc comments)");
@@ -196,6 +204,14 @@ macro_rules! impl_enum {
                 (self as $inner).to_le_bytes()
             }
         }
+
+        #[automatically_derived]
+        impl $crate::derive::WriteLe for $name {
+            #[inline]
+            fn write_le(&self, w: &mut impl ::std::io::Write) -> ::std::io::Result<()> {
+                $crate::derive::WriteLe::write_le(&(*self as $inner), w)
+            }
+        }
     };
     ($($stuff:tt)*) => {
         compile_error!("Enum must contain #[repr(type)] at the very top (even above doc comments)");
@@ -204,6 +220,8 @@ macro_rules! impl_enum {
 
 pub(crate) use impl_enum;
 
+/// Typed resource id. Every kind converts into [`ResourceId`](crate::ResourceId) but not back,
+/// untyped ids must be given a kind explicitly with `from_resource`
 macro_rules! impl_resource_id {
     ($name:ident) => {
         #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -216,12 +234,18 @@ macro_rules! impl_resource_id {
                 self.0
             }
 
+            /// Id known to be of this kind, usually a fresh one from
+            /// [`IdAllocator::allocate_id`](crate::IdAllocator::allocate_id)
+            pub const fn from_resource(id: $crate::ResourceId) -> Self {
+                Self(id)
+            }
+
             pub fn to_le_bytes(self) -> [u8; 4] {
                 let raw: u32 = self.into();
                 raw.to_le_bytes()
             }
 
-            pub fn unchecked_from(value: u32) -> Self {
+            pub const fn unchecked_from(value: u32) -> Self {
                 Self($crate::ResourceId { value })
             }
         }
@@ -238,9 +262,9 @@ macro_rules! impl_resource_id {
             }
         }
 
-        impl From<$crate::ResourceId> for $name {
-            fn from(value: $crate::ResourceId) -> Self {
-                Self(value)
+        impl From<$name> for $crate::ResourceId {
+            fn from(value: $name) -> $crate::ResourceId {
+                value.0
             }
         }
 
@@ -251,6 +275,26 @@ macro_rules! impl_resource_id {
             }
         }
 
+        impl $crate::derive::WriteLe for $name {
+            fn write_le(&self, w: &mut impl ::std::io::Write) -> ::std::io::Result<()> {
+                w.write_all(&$name::to_le_bytes(*self))
+            }
+        }
+
+        impl std::str::FromStr for $name {
+            type Err = $crate::ParseResourceIdError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                s.parse::<$crate::ResourceId>().map(Self)
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                std::fmt::Display::fmt(&self.0, f)
+            }
+        }
+
         $crate::requests::impl_value!($name into);
     };
 }
@@ -1,5 +1,5 @@
 use crate::{connection::XConnection, error::Error, SequenceNumber};
-use std::{fmt, mem};
+use std::fmt;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SomeError {
@@ -78,7 +78,6 @@ impl SomeError {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-#[repr(C)]
 struct XGenericError {
     error: u8,
     code: u8,
@@ -86,12 +85,18 @@ struct XGenericError {
     generic_value: u32,
     minor_opcode: u16,
     major_opcode: u8,
-    _pad: [u8; 21],
 }
 
 impl XGenericError {
     fn from_le_bytes(raw: [u8; 32]) -> Self {
-        unsafe { mem::transmute(raw) }
+        Self {
+            error: raw[0],
+            code: raw[1],
+            sequence_number: u16::from_le_bytes([raw[2], raw[3]]),
+            generic_value: u32::from_le_bytes([raw[4], raw[5], raw[6], raw[7]]),
+            minor_opcode: u16::from_le_bytes([raw[8], raw[9]]),
+            major_opcode: raw[10],
+        }
     }
 }
 
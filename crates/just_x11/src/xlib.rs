@@ -0,0 +1,199 @@
+//! Conversions between this crate's types and their Xlib counterparts, so code using `x11-dl` or
+//! `xlib` can be migrated piece by piece.
+//!
+//! Resource ids are global to the X server so ids created through Xlib can be used with
+//! [`crate::XDisplay`] and the other way around. Connections however must not be shared - Xlib
+//! keeps its own sequence numbers and reply queue, so writing to its socket would desynchronize
+//! it. Open a separate connection with [`crate::XDisplay::open`] instead.
+
+use crate::{
+    atoms::AtomId,
+    events::{KeyPressRelease, MotionNotify, MotionNotifyDetail, SomeEvent},
+    ColormapId, CursorId, FontId, GContextId, PixmapId, VisualId, WindowId,
+};
+use std::{
+    ffi::{c_char, c_int, c_long, c_uint, c_ulong, c_void},
+    ptr,
+};
+
+/// Xlib's `XID`
+pub type RawXid = c_ulong;
+
+/// Xlib's `Time`
+pub type RawTime = c_ulong;
+
+/// Top three bits of resource ids are always zero
+const XID_MASK: RawXid = 0x1fffffff;
+
+pub trait Xid: Sized {
+    fn to_xid(self) -> RawXid;
+
+    /// Returns `None` if `xid` is not valid resource id
+    fn from_xid(xid: RawXid) -> Option<Self>;
+}
+
+macro_rules! impl_xid {
+    ($name:ident) => {
+        impl Xid for $name {
+            #[inline]
+            fn to_xid(self) -> RawXid {
+                u32::from(self) as RawXid
+            }
+
+            #[inline]
+            fn from_xid(xid: RawXid) -> Option<Self> {
+                (xid & !XID_MASK == 0).then(|| Self::from(xid as u32))
+            }
+        }
+    };
+}
+
+impl_xid!(PixmapId);
+impl_xid!(VisualId);
+impl_xid!(FontId);
+impl_xid!(ColormapId);
+impl_xid!(CursorId);
+impl_xid!(WindowId);
+impl_xid!(GContextId);
+impl_xid!(AtomId);
+
+/// Xlib's `XKeyEvent`, also used as `XButtonEvent` with `button` in place of `keycode`
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct XKeyEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: c_int,
+    /// Always null when converted from this crate's events
+    pub display: *mut c_void,
+    pub window: RawXid,
+    pub root: RawXid,
+    pub subwindow: RawXid,
+    pub time: RawTime,
+    pub x: c_int,
+    pub y: c_int,
+    pub x_root: c_int,
+    pub y_root: c_int,
+    pub state: c_uint,
+    pub keycode: c_uint,
+    pub same_screen: c_int,
+}
+
+pub type XButtonEvent = XKeyEvent;
+
+/// Xlib's `XMotionEvent`
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct XMotionEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: c_int,
+    /// Always null when converted from this crate's events
+    pub display: *mut c_void,
+    pub window: RawXid,
+    pub root: RawXid,
+    pub subwindow: RawXid,
+    pub time: RawTime,
+    pub x: c_int,
+    pub y: c_int,
+    pub x_root: c_int,
+    pub y_root: c_int,
+    pub state: c_uint,
+    pub is_hint: c_char,
+    pub same_screen: c_int,
+}
+
+/// Xlib's `XEvent`, only input events are supported
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub union XEvent {
+    pub type_: c_int,
+    pub xkey: XKeyEvent,
+    pub xbutton: XButtonEvent,
+    pub xmotion: XMotionEvent,
+    pad: [c_long; 24],
+}
+
+impl XKeyEvent {
+    fn from_key_press_release(type_: c_int, event: &KeyPressRelease) -> Self {
+        Self {
+            type_,
+            serial: 0,
+            send_event: 0,
+            display: ptr::null_mut(),
+            window: event.event.to_xid(),
+            root: event.root.to_xid(),
+            subwindow: event.child.value().map_or(0, Xid::to_xid),
+            time: event.time as RawTime,
+            x: event.event_x as c_int,
+            y: event.event_y as c_int,
+            x_root: event.root_x as c_int,
+            y_root: event.root_y as c_int,
+            state: event.state.raw() as c_uint,
+            keycode: event.detail.raw() as c_uint,
+            same_screen: event.same_screen as c_int,
+        }
+    }
+}
+
+impl From<&MotionNotify> for XMotionEvent {
+    fn from(event: &MotionNotify) -> Self {
+        Self {
+            type_: 6,
+            serial: event.sequence_number as c_ulong,
+            send_event: 0,
+            display: ptr::null_mut(),
+            window: event.event.to_xid(),
+            root: event.root.to_xid(),
+            subwindow: event.child.value().map_or(0, Xid::to_xid),
+            time: event.time as RawTime,
+            x: event.event_x as c_int,
+            y: event.event_y as c_int,
+            x_root: event.root_x as c_int,
+            y_root: event.root_y as c_int,
+            state: event.state as c_uint,
+            is_hint: matches!(event.detail, MotionNotifyDetail::Hint) as c_char,
+            same_screen: event.same_screen as c_int,
+        }
+    }
+}
+
+impl XEvent {
+    /// Converts input event to Xlib's representation. Returns `None` for unsupported events.
+    pub fn from_event(event: &SomeEvent) -> Option<Self> {
+        match event {
+            SomeEvent::KeyPress(event) => Some(Self {
+                xkey: XKeyEvent::from_key_press_release(2, event),
+            }),
+            SomeEvent::KeyRelease(event) => Some(Self {
+                xkey: XKeyEvent::from_key_press_release(3, event),
+            }),
+            SomeEvent::ButtonPress(event) => Some(Self {
+                xbutton: XKeyEvent::from_key_press_release(4, event),
+            }),
+            SomeEvent::ButtonRelease(event) => Some(Self {
+                xbutton: XKeyEvent::from_key_press_release(5, event),
+            }),
+            SomeEvent::MotionNotify(event) => Some(Self {
+                xmotion: XMotionEvent::from(event),
+            }),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn type_(&self) -> c_int {
+        // SAFETY: All variants start with `type_`
+        unsafe { self.type_ }
+    }
+}
+
+#[test]
+#[cfg(target_pointer_width = "64")]
+fn xlib_event_layout() {
+    use std::mem;
+
+    assert_eq!(mem::size_of::<XKeyEvent>(), 96);
+    assert_eq!(mem::size_of::<XMotionEvent>(), 96);
+    assert_eq!(mem::size_of::<XEvent>(), 192);
+}
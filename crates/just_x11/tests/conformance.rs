@@ -0,0 +1,410 @@
+//! Core protocol conformance tests against a live server, e.g.
+//! `xvfb-run cargo test -p just_x11 --test conformance`
+//!
+//! Each request with a reply is sent twice back to back. A decoder reading too much or too little
+//! desynchronizes the connection, so the second reply fails to decode or differs from the first.
+//! That only checks decoders against themselves, field values are checked against bytes from the
+//! protocol spec in `replies_match_reference_bytes` of `replies.rs`.
+//! Requests changing server state are checked by reading the state back with other requests.
+
+use just_x11::{
+    atoms::AtomId,
+    replies::{PropertyValue, String8},
+    requests::{
+        self, ChangePropertyFormat, ChangePropertyMode, GetImageImageFormat,
+        WindowCreationAttributes,
+    },
+    Drawable, PixmapId, WindowClass, WindowId, WindowVisual, XDisplay,
+};
+use std::str::FromStr;
+
+/// Send `request` twice and return the first reply after checking that both decoded the same
+macro_rules! double_request {
+    ($display:expr, $request:expr) => {{
+        let display: &mut XDisplay = $display;
+        let request = $request;
+        let pending_1 = display.send_request(&request).unwrap();
+        let pending_2 = display.send_request(&request).unwrap();
+        display.flush().unwrap();
+
+        let reply_1 = display.await_pending_reply(pending_1).unwrap();
+        let reply_2 = display.await_pending_reply(pending_2).unwrap();
+        assert_eq!(
+            format!("{:?}", reply_1),
+            format!("{:?}", reply_2),
+            "{:?}",
+            request
+        );
+        reply_1.unwrap()
+    }};
+}
+
+fn assert_no_errors(display: &mut XDisplay) {
+    // Round trip so errors of requests without replies are received
+    double_request!(display, requests::GetInputFocus);
+    let errors = display.errors().collect::<Vec<_>>();
+    assert!(errors.is_empty(), "{:?}", errors);
+}
+
+fn intern_atom(display: &mut XDisplay, name: &str) -> AtomId {
+    double_request!(
+        display,
+        requests::InternAtom {
+            only_if_exists: false,
+            name: String8::from_str(name).unwrap(),
+        }
+    )
+    .atom
+}
+
+fn create_window(display: &mut XDisplay, x: i16, y: i16, width: u16, height: u16) -> WindowId {
+    let window = WindowId::from_resource(display.id_allocator().allocate_id());
+    let root = display.default_screen().root;
+    display
+        .send_request(&requests::CreateWindow {
+            depth: 0,
+            wid: window,
+            parent: root,
+            x,
+            y,
+            width,
+            height,
+            border_width: 2,
+            window_class: WindowClass::InputOutput,
+            visual: WindowVisual::CopyFromParent,
+            attributes: WindowCreationAttributes::new().set_override_redirect(1),
+        })
+        .unwrap();
+    window
+}
+
+#[test]
+fn replies_decode_consistently() {
+    let mut display = XDisplay::open().unwrap();
+    let root = display.default_screen().root;
+    let min_keycode = display.min_keycode;
+    let max_keycode = display.max_keycode;
+
+    double_request!(&mut display, requests::GetWindowAttributes { window: root });
+    double_request!(
+        &mut display,
+        requests::GetGeometry {
+            drawable: Drawable::Window(root),
+        }
+    );
+    double_request!(&mut display, requests::QueryTree { window: root });
+    double_request!(&mut display, requests::ListProperties { window: root });
+    double_request!(
+        &mut display,
+        requests::GetAtomName {
+            atom: AtomId::WM_NAME,
+        }
+    );
+    double_request!(
+        &mut display,
+        requests::GetSelectionOwner {
+            selection: AtomId::PRIMARY,
+        }
+    );
+    double_request!(&mut display, requests::QueryPointer { window: root });
+    double_request!(
+        &mut display,
+        requests::TranslateCoordinates {
+            src_window: root,
+            dst_window: root,
+            src_x: 1,
+            src_y: 2,
+        }
+    );
+    double_request!(&mut display, requests::GetInputFocus);
+    double_request!(&mut display, requests::QueryKeymap);
+    double_request!(
+        &mut display,
+        requests::ListFonts {
+            max_names: 16,
+            pattern: b"*".to_vec(),
+        }
+    );
+    double_request!(&mut display, requests::GetFontPath);
+    double_request!(
+        &mut display,
+        requests::ListInstalledColormaps { window: root }
+    );
+    double_request!(&mut display, requests::ListExtensions);
+    double_request!(
+        &mut display,
+        requests::GetKeyboardMapping {
+            first_keycode: min_keycode.into(),
+            count: max_keycode - min_keycode + 1,
+        }
+    );
+    double_request!(&mut display, requests::GetKeyboardControl);
+    double_request!(&mut display, requests::GetPointerControl);
+    double_request!(&mut display, requests::GetScreenSaver);
+    double_request!(&mut display, requests::ListHosts);
+    double_request!(&mut display, requests::GetPointerMapping);
+    double_request!(&mut display, requests::GetModifierMapping);
+
+    assert_no_errors(&mut display);
+}
+
+#[test]
+fn window_state_roundtrip() {
+    let mut display = XDisplay::open().unwrap();
+    let root = display.default_screen().root;
+    let window = create_window(&mut display, 10, 20, 30, 40);
+
+    let geometry = double_request!(
+        &mut display,
+        requests::GetGeometry {
+            drawable: Drawable::Window(window),
+        }
+    );
+    assert_eq!(
+        (geometry.x, geometry.y, geometry.width, geometry.height),
+        (10, 20, 30, 40)
+    );
+    assert_eq!(geometry.border_width, 2);
+    assert_eq!(geometry.root, root);
+
+    let tree = double_request!(&mut display, requests::QueryTree { window });
+    assert_eq!(tree.root, root);
+    assert_eq!(tree.parent.value(), Some(root));
+    assert!(tree.children.is_empty());
+    let root_tree = double_request!(&mut display, requests::QueryTree { window: root });
+    assert!(root_tree.children.contains(&window));
+
+    // Origin of the window is inside of the border
+    let translated = double_request!(
+        &mut display,
+        requests::TranslateCoordinates {
+            src_window: window,
+            dst_window: root,
+            src_x: 0,
+            src_y: 0,
+        }
+    );
+    assert_eq!((translated.dst_x, translated.dst_y), (12, 22));
+
+    let attributes = double_request!(&mut display, requests::GetWindowAttributes { window });
+    assert!(attributes.override_redirect);
+    // Unmapped
+    assert_eq!(attributes.map_state, 0);
+
+    display
+        .send_request(&requests::MapWindow { window })
+        .unwrap();
+    let attributes = double_request!(&mut display, requests::GetWindowAttributes { window });
+    // Viewable
+    assert_eq!(attributes.map_state, 2);
+
+    display
+        .send_request(&requests::DestroyWindow { window })
+        .unwrap();
+    let pending = display
+        .send_request(&requests::GetGeometry {
+            drawable: Drawable::Window(window),
+        })
+        .unwrap();
+    display.flush().unwrap();
+    assert!(display.await_pending_reply(pending).unwrap().is_err());
+
+    assert_no_errors(&mut display);
+}
+
+#[test]
+fn property_roundtrip() {
+    let mut display = XDisplay::open().unwrap();
+    let window = create_window(&mut display, 0, 0, 1, 1);
+    let property = intern_atom(&mut display, "JUST_X11_CONFORMANCE");
+
+    for (format, data) in [
+        (ChangePropertyFormat::Format8, b"abc".to_vec()),
+        (ChangePropertyFormat::Format16, vec![1, 2, 3, 4]),
+        (ChangePropertyFormat::Format32, vec![1, 2, 3, 4, 5, 6, 7, 8]),
+    ] {
+        display
+            .send_request(&requests::ChangeProperty {
+                mode: ChangePropertyMode::Replace,
+                window,
+                property,
+                type_: AtomId::INTEGER,
+                format,
+                data: data.clone(),
+            })
+            .unwrap();
+        let reply = double_request!(
+            &mut display,
+            requests::GetProperty {
+                delete: false,
+                window,
+                property,
+                type_: AtomId::INTEGER,
+                long_offset: 0,
+                long_length: 1024,
+            }
+        );
+        assert_eq!(reply.format, format as u8);
+        assert_eq!(reply.type_, AtomId::INTEGER);
+        assert_eq!(reply.bytes_after, 0);
+        assert_eq!(reply.value, data);
+    }
+
+    display
+        .send_request(&requests::ChangeProperty {
+            mode: ChangePropertyMode::Append,
+            window,
+            property,
+            type_: AtomId::INTEGER,
+            format: ChangePropertyFormat::Format32,
+            data: vec![9, 0, 0, 0],
+        })
+        .unwrap();
+    // Partial read leaves the rest in `bytes_after`
+    let reply = double_request!(
+        &mut display,
+        requests::GetProperty {
+            delete: false,
+            window,
+            property,
+            type_: AtomId::INTEGER,
+            long_offset: 1,
+            long_length: 1,
+        }
+    );
+    assert_eq!(reply.value, vec![5, 6, 7, 8]);
+    assert_eq!(reply.bytes_after, 4);
+
+    // Only metadata is sent when the type does not match
+    let reply = double_request!(
+        &mut display,
+        requests::GetProperty {
+            delete: false,
+            window,
+            property,
+            type_: AtomId::STRING,
+            long_offset: 0,
+            long_length: 1024,
+        }
+    );
+    assert_eq!(
+        reply.into_value(AtomId::STRING),
+        PropertyValue::TypeMismatch {
+            actual_type: AtomId::INTEGER,
+            format: 32,
+            bytes_after: 12,
+        }
+    );
+
+    let properties = double_request!(&mut display, requests::ListProperties { window });
+    assert!(properties.atoms.contains(&property));
+
+    display
+        .send_request(&requests::DeleteProperty { window, property })
+        .unwrap();
+    let reply = double_request!(
+        &mut display,
+        requests::GetProperty {
+            delete: false,
+            window,
+            property,
+            type_: AtomId::INTEGER,
+            long_offset: 0,
+            long_length: 1024,
+        }
+    );
+    assert_eq!(reply.into_value(AtomId::INTEGER), PropertyValue::Missing);
+
+    display
+        .send_request(&requests::DestroyWindow { window })
+        .unwrap();
+    assert_no_errors(&mut display);
+}
+
+#[test]
+fn pixmap_roundtrip() {
+    let mut display = XDisplay::open().unwrap();
+    let root = display.default_screen().root;
+    let depth = display.default_screen().root_depth;
+    let pixmap = PixmapId::from_resource(display.id_allocator().allocate_id());
+
+    display
+        .send_request(&requests::CreatePixmap {
+            depth,
+            pid: pixmap,
+            drawable: Drawable::Window(root),
+            width: 17,
+            height: 9,
+        })
+        .unwrap();
+
+    let geometry = double_request!(
+        &mut display,
+        requests::GetGeometry {
+            drawable: Drawable::Pixmap(pixmap),
+        }
+    );
+    assert_eq!(geometry.depth, depth);
+    assert_eq!((geometry.width, geometry.height), (17, 9));
+
+    let image = double_request!(
+        &mut display,
+        requests::GetImage {
+            format: GetImageImageFormat::ZPixmap,
+            drawable: Drawable::Pixmap(pixmap),
+            x: 0,
+            y: 0,
+            width: 17,
+            height: 9,
+            plane_mask: u32::MAX,
+        }
+    );
+    assert_eq!(image.depth, depth);
+    assert!(image.data.len() >= 17 * 9);
+
+    display
+        .send_request(&requests::FreePixmap { pixmap })
+        .unwrap();
+    assert_no_errors(&mut display);
+}
+
+#[test]
+fn listed_extensions_are_present() {
+    let mut display = XDisplay::open().unwrap();
+
+    let extensions = double_request!(&mut display, requests::ListExtensions);
+    assert!(!extensions.names.strings.is_empty());
+    for name in extensions.names.strings {
+        let query = double_request!(
+            &mut display,
+            requests::QueryExtension { name: name.clone() }
+        );
+        assert!(query.present, "{}", String::from_utf8_lossy(&name));
+    }
+
+    let query = double_request!(
+        &mut display,
+        requests::QueryExtension {
+            name: b"JUST-X11-NOT-AN-EXTENSION".to_vec(),
+        }
+    );
+    assert!(!query.present);
+
+    assert_no_errors(&mut display);
+}
+
+#[test]
+fn close_returns_pending_errors() {
+    let mut display = XDisplay::open().unwrap();
+    let window = create_window(&mut display, 0, 0, 1, 1);
+    display
+        .send_request(&requests::DestroyWindow { window })
+        .unwrap();
+    // Already destroyed, error arrives only after the server processes it
+    display
+        .send_request(&requests::MapWindow { window })
+        .unwrap();
+
+    let errors = display.close(just_x11::PendingReplies::Await).unwrap();
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+}
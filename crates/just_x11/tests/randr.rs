@@ -1,4 +1,4 @@
-use justshow_x11::{error::Error, extensions::randr, replies, requests, XDisplay};
+use just_x11::{error::Error, extensions::randr, replies, requests, XDisplay};
 
 /// Send the same request twice and assert that replies are the same
 /// This checks that reply decoder is not consuming too much/too little data
@@ -62,19 +62,19 @@ fn query_version() {
 #[test]
 fn get_monitor_info() {
     randr_double_request!(|display: &mut XDisplay| randr::requests::GetScreenInfo {
-        window: display.screens()[0].root,
+        window: display.default_screen().root,
     });
 }
 
 #[test]
 fn get_monitors() {
     randr_double_request!(|display: &mut XDisplay| randr::requests::GetMonitors {
-        window: display.screens()[0].root,
+        window: display.default_screen().root,
         get_active: true,
     });
 
     randr_double_request!(|display: &mut XDisplay| randr::requests::GetMonitors {
-        window: display.screens()[0].root,
+        window: display.default_screen().root,
         get_active: false,
     });
 }
@@ -83,7 +83,7 @@ fn get_monitors() {
 fn get_get_screen_size_range() {
     randr_double_request!(
         |display: &mut XDisplay| randr::requests::GetScreenSizeRange {
-            window: display.screens()[0].root,
+            window: display.default_screen().root,
         }
     );
 }
@@ -0,0 +1,956 @@
+//! Serialization of every implemented request checked against layouts in
+//! [`just_x11::introspection`], without a server, e.g.
+//! `cargo test -p just_x11 --features introspection --test serialization`
+//!
+//! Catches wrong opcodes, missing padding and request length fields that don't match the
+//! written bytes, which otherwise desynchronize the connection far from the broken request.
+
+use just_x11::{
+    atoms::AtomId,
+    introspection::{self, RequestInfo},
+    ColormapId, CursorId, Drawable, FontId, GContextId, OrNone, PixmapId, Point, Rectangle,
+    ToLeBytes, VisualId, WindowId,
+};
+
+/// Leading number of a length expression like `3+(n+p)/4`, and whether it is the whole
+/// expression, i.e. the request has fixed length
+fn base_length(expression: &str) -> (usize, bool) {
+    let digits = expression
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect::<String>();
+    let base = digits
+        .parse()
+        .unwrap_or_else(|_| panic!("length expression '{}'", expression));
+    (base, digits.len() == expression.len())
+}
+
+/// Problems of serialized `bytes` of the request, with the major opcode first for extension
+/// requests
+fn check_layout(info: &RequestInfo, bytes: &[u8], is_extension: bool) -> Vec<String> {
+    let mut problems = Vec::new();
+    let mut check = |ok: bool, problem: String| {
+        if !ok {
+            problems.push(format!("{}: {}", info.name, problem));
+        }
+    };
+
+    check(
+        bytes.len().is_multiple_of(4),
+        format!("{} bytes are not padded", bytes.len()),
+    );
+
+    let opcode = if is_extension { bytes[1] } else { bytes[0] };
+    check(opcode == info.opcode, format!("opcode {}", opcode));
+
+    let length = u16::from_le_bytes([bytes[2], bytes[3]]) as usize;
+    check(
+        length * 4 == bytes.len(),
+        format!("length field {} for {} bytes", length, bytes.len()),
+    );
+
+    // Length is always the third field, second one is used by some requests for other lengths
+    let (base, is_fixed) = base_length(info.fields[2].type_);
+    if is_fixed {
+        check(
+            length == base,
+            format!("length field {}, {} in spec", length, base),
+        );
+        let spec_bytes = info
+            .fields
+            .iter()
+            .map(|field| field.size.parse::<usize>().unwrap())
+            .sum::<usize>();
+        check(
+            spec_bytes == base * 4,
+            format!("spec fields have {} bytes", spec_bytes),
+        );
+    } else {
+        check(
+            length >= base,
+            format!("length field {} below {} in spec", length, base),
+        );
+    }
+    problems
+}
+
+/// Serialize each request and check it against the layout with the same name, or the one given
+/// with `as`. Requests in `table` without a sample fail too, so new requests get covered.
+macro_rules! check_requests {
+    ($table:expr, $is_extension:expr; $($name:ident $(as $info:literal)? $({ $($field:tt)* })?),* $(,)?) => {{
+        let table: &[RequestInfo] = $table;
+        let mut checked = Vec::<&str>::new();
+        let mut problems = Vec::<String>::new();
+        $(
+            let name = [$($info,)? stringify!($name)][0];
+            let info = table
+                .iter()
+                .find(|info| info.name == name)
+                .unwrap_or_else(|| panic!("{} is not in the table", name));
+
+            let mut bytes = Vec::new();
+            if $is_extension {
+                // Written by the connection
+                bytes.push(0);
+            }
+            $name $({ $($field)* })?.to_le_bytes(&mut bytes).unwrap();
+            problems.extend(check_layout(info, &bytes, $is_extension));
+            checked.push(name);
+        )*
+
+        for info in table {
+            if !checked.contains(&info.name) {
+                problems.push(format!("{}: no sample", info.name));
+            }
+        }
+        assert!(problems.is_empty(), "{:#?}", problems);
+    }};
+}
+
+const WINDOW: WindowId = WindowId::unchecked_from(1);
+const DRAWABLE: Drawable = Drawable::Window(WINDOW);
+const GC: GContextId = GContextId::unchecked_from(2);
+const PIXMAP: PixmapId = PixmapId::unchecked_from(3);
+const COLORMAP: ColormapId = ColormapId::unchecked_from(4);
+const FONT: FontId = FontId::unchecked_from(5);
+const CURSOR: CursorId = CursorId::unchecked_from(6);
+
+/// Odd lengths so missing padding shows up
+const POINTS: [Point; 3] = [Point { x: 1, y: 2 }; 3];
+const RECTANGLES: [Rectangle; 3] = [Rectangle {
+    x: 1,
+    y: 2,
+    width: 3,
+    height: 4,
+}; 3];
+const NAME: &[u8] = b"fixed";
+
+#[test]
+fn core_requests_match_spec() {
+    use just_x11::{keysym::KeySym, requests::*, ListOfStr, WindowClass, WindowVisual};
+    use std::str::FromStr;
+
+    let arc = Arc {
+        x: 1,
+        y: 2,
+        width: 3,
+        height: 4,
+        angle1: 5,
+        angle2: 6,
+    };
+
+    check_requests!(introspection::CORE_REQUESTS, false;
+        CreateWindow {
+            depth: 0,
+            wid: WINDOW,
+            parent: WINDOW,
+            x: 1,
+            y: 2,
+            width: 3,
+            height: 4,
+            border_width: 5,
+            window_class: WindowClass::InputOutput,
+            visual: WindowVisual::CopyFromParent,
+            attributes: WindowCreationAttributes::new()
+                .set_background_pixel(1)
+                .set_override_redirect(1),
+        },
+        ChangeWindowAttributes {
+            window: WINDOW,
+            attributes: WindowCreationAttributes::new().set_border_pixel(1),
+        },
+        GetWindowAttributes { window: WINDOW },
+        DestroyWindow { window: WINDOW },
+        DestroySubwindows { window: WINDOW },
+        ChangeSaveSet {
+            mode: ChangeSaveSetMode::Insert,
+            window: WINDOW,
+        },
+        ReparentWindow {
+            window: WINDOW,
+            parent: WINDOW,
+            x: 1,
+            y: 2,
+        },
+        MapWindow { window: WINDOW },
+        MapSubwindows { window: WINDOW },
+        UnmapWindow { window: WINDOW },
+        UnmapSubwindows { window: WINDOW },
+        ConfigureWindow {
+            window: WINDOW,
+            attributes: ConfigureWindowAttributes::new().set_x(1).set_width(2),
+        },
+        CirculateWindow {
+            direction: CirculateWindowDirection::RaiseLowest,
+            window: WINDOW,
+        },
+        GetGeometry { drawable: DRAWABLE },
+        QueryTree { window: WINDOW },
+        InternAtom {
+            only_if_exists: false,
+            name: just_x11::replies::String8::from_str("WM_NAME").unwrap(),
+        },
+        GetAtomName { atom: AtomId::WM_NAME },
+        ChangeProperty {
+            mode: ChangePropertyMode::Replace,
+            window: WINDOW,
+            property: AtomId::WM_NAME,
+            type_: AtomId::STRING,
+            format: ChangePropertyFormat::Format8,
+            data: NAME.to_vec(),
+        },
+        DeleteProperty {
+            window: WINDOW,
+            property: AtomId::WM_NAME,
+        },
+        GetProperty {
+            delete: false,
+            window: WINDOW,
+            property: AtomId::WM_NAME,
+            type_: AtomId::STRING,
+            long_offset: 0,
+            long_length: 1,
+        },
+        ListProperties { window: WINDOW },
+        SetSelectionOwner {
+            owner: OrNone::new(WINDOW),
+            selection: AtomId::PRIMARY,
+            time: Timestamp::CurrentTime,
+        },
+        GetSelectionOwner {
+            selection: AtomId::PRIMARY,
+        },
+        ConvertSelection {
+            requestor: WINDOW,
+            selection: AtomId::PRIMARY,
+            target: AtomId::STRING,
+            property: OrNone::none(),
+            time: Timestamp::CurrentTime,
+        },
+        SendEvent {
+            propagate: false,
+            destination: WINDOW,
+            event_mask: 0,
+            event: [0; 32],
+        },
+        GrabPointer {
+            owner_events: false,
+            grab_window: WINDOW,
+            event_mask: PointerEventMask::EMPTY_MASK,
+            pointer_mode: GrabMode::Asynchronous,
+            keyboard_mode: GrabMode::Asynchronous,
+            confine_to: OrNone::none(),
+            cursor: OrNone::new(CURSOR),
+            time: Timestamp::CurrentTime,
+        },
+        UngrabPointer {
+            time: Timestamp::CurrentTime,
+        },
+        GrabButton {
+            owner_events: false,
+            grab_window: WINDOW,
+            event_mask: PointerEventMask::EMPTY_MASK,
+            pointer_mode: GrabMode::Asynchronous,
+            keyboard_mode: GrabMode::Asynchronous,
+            confine_to: OrNone::none(),
+            cursor: OrNone::none(),
+            button: 1,
+            modifiers: 0,
+        },
+        UngrabButton {
+            button: 1,
+            grab_window: WINDOW,
+            modifiers: 0,
+        },
+        ChangeActivePointerGrab {
+            cursor: OrNone::none(),
+            time: Timestamp::CurrentTime,
+            event_mask: PointerEventMask::EMPTY_MASK,
+        },
+        GrabKeyboard {
+            owner_events: false,
+            grab_window: WINDOW,
+            time: Timestamp::CurrentTime,
+            pointer_mode: GrabMode::Asynchronous,
+            keyboard_mode: GrabMode::Asynchronous,
+        },
+        UngrabKeyboard { time: 0 },
+        GrabKey {
+            owner_events: false,
+            grab_window: WINDOW,
+            modifiers: KeyModifier::ANY,
+            key: KeyCode::from(10u8),
+            pointer_mode: GrabMode::Asynchronous,
+            keyboard_mode: GrabMode::Asynchronous,
+        },
+        UngrabKey {
+            key: KeyCode::from(10u8),
+            grab_window: WINDOW,
+            modifiers: KeyModifier::ANY,
+        },
+        AllowEvents {
+            mode: AllowEventsMode::AsyncPointer,
+            time: 0,
+        },
+        GrabServer,
+        UngrabServer,
+        QueryPointer { window: WINDOW },
+        GetMotionEvents {
+            window: WINDOW,
+            start: Timestamp::CurrentTime,
+            stop: Timestamp::CurrentTime,
+        },
+        TranslateCoordinates {
+            src_window: WINDOW,
+            dst_window: WINDOW,
+            src_x: 1,
+            src_y: 2,
+        },
+        WarpPointer {
+            src_window: OrNone::none(),
+            dst_window: OrNone::new(WINDOW),
+            src_x: 0,
+            src_y: 0,
+            src_width: 0,
+            src_height: 0,
+            dst_x: 1,
+            dst_y: 2,
+        },
+        SetInputFocus {
+            revert_to: RevertTo::None,
+            focus: WINDOW,
+            time: Timestamp::CurrentTime,
+        },
+        GetInputFocus,
+        QueryKeymap,
+        OpenFont {
+            fid: FONT,
+            name: NAME.to_vec(),
+        },
+        CloseFont { font: FONT },
+        QueryFont { font: FONT },
+        QueryTextExtents {
+            font: FONT,
+            string: vec![1, 2, 3],
+        },
+        ListFonts {
+            max_names: 1,
+            pattern: NAME.to_vec(),
+        },
+        ListFontsWithInfo {
+            max_names: 1,
+            pattern: NAME.to_vec(),
+        },
+        SetFontPath {
+            paths: ListOfStr {
+                strings: vec![NAME.to_vec(), b"built-ins".to_vec()],
+            },
+        },
+        GetFontPath,
+        CreatePixmap {
+            depth: 24,
+            pid: PIXMAP,
+            drawable: DRAWABLE,
+            width: 1,
+            height: 2,
+        },
+        FreePixmap { pixmap: PIXMAP },
+        CreateGC {
+            cid: GC,
+            drawable: DRAWABLE,
+            values: GContextSettings::new().set_foreground(1).set_line_width(2),
+        },
+        ChangeGC {
+            gcontext: GC,
+            values: GContextSettings::new().set_background(1),
+        },
+        CopyGC {
+            src_gc: GC,
+            dst_gc: GC,
+            value_mask: 1,
+        },
+        SetDashes {
+            gc: GC,
+            dash_offset: 0,
+            dashes: vec![1, 2, 3],
+        },
+        SetClipRectangles {
+            ordering: Ordering::UnSorted,
+            gc: GC,
+            clip_x_origin: 0,
+            clip_y_origin: 0,
+            rectangles: RECTANGLES.to_vec(),
+        },
+        FreeGC { gc: GC },
+        ClearArea {
+            exposures: false,
+            window: WINDOW,
+            x: 1,
+            y: 2,
+            width: 3,
+            height: 4,
+        },
+        CopyArea {
+            src_drawable: DRAWABLE,
+            dst_drawable: DRAWABLE,
+            gc: GC,
+            src_x: 1,
+            src_y: 2,
+            dst_x: 3,
+            dst_y: 4,
+            width: 5,
+            height: 6,
+        },
+        CopyPlane {
+            src_drawable: DRAWABLE,
+            dst_drawable: DRAWABLE,
+            gc: GC,
+            src_x: 1,
+            src_y: 2,
+            dst_x: 3,
+            dst_y: 4,
+            width: 5,
+            height: 6,
+            bit_plane: 1,
+        },
+        PolyPoint {
+            coordinate_mode: CoordinateMode::Origin,
+            drawable: DRAWABLE,
+            gc: GC,
+            points: POINTS.to_vec(),
+        },
+        PolyLine {
+            coordinate_mode: CoordinateMode::Origin,
+            drawable: DRAWABLE,
+            gc: GC,
+            points: POINTS.to_vec(),
+        },
+        PolySegment {
+            drawable: DRAWABLE,
+            gc: GC,
+            segments: vec![
+                Segment {
+                    x1: 1,
+                    y1: 2,
+                    x2: 3,
+                    y2: 4,
+                };
+                3
+            ],
+        },
+        PolyRectangle {
+            drawable: DRAWABLE,
+            gc: GC,
+            rectangles: RECTANGLES.to_vec(),
+        },
+        PolyArc {
+            drawable: DRAWABLE,
+            gc: GC,
+            arcs: vec![arc; 3],
+        },
+        FillPoly {
+            drawable: DRAWABLE,
+            gc: GC,
+            shape: FillPolyShape::Complex,
+            coordinate_mode: CoordinateMode::Origin,
+            points: POINTS.to_vec(),
+        },
+        PolyFillRectangle {
+            drawable: DRAWABLE,
+            gc: GC,
+            rectangles: RECTANGLES.to_vec(),
+        },
+        PolyFillArc {
+            drawable: DRAWABLE,
+            gc: GC,
+            arcs: vec![arc; 3],
+        },
+        PutImage {
+            format: PutImageFormat::ZPixmap,
+            drawable: DRAWABLE,
+            gc: GC,
+            width: 1,
+            height: 1,
+            dst_x: 0,
+            dst_y: 0,
+            left_pad: 0,
+            depth: 8,
+            data: &[1, 2, 3],
+        },
+        PutImageOwned as "PutImage" {
+            format: PutImageFormat::ZPixmap,
+            drawable: DRAWABLE,
+            gc: GC,
+            width: 1,
+            height: 1,
+            dst_x: 0,
+            dst_y: 0,
+            left_pad: 0,
+            depth: 8,
+            data: vec![1, 2, 3],
+        },
+        GetImage {
+            format: GetImageImageFormat::ZPixmap,
+            drawable: DRAWABLE,
+            x: 0,
+            y: 0,
+            width: 1,
+            height: 1,
+            plane_mask: u32::MAX,
+        },
+        PolyText8 {
+            drawable: DRAWABLE,
+            gc: GC,
+            x: 1,
+            y: 2,
+            items: vec![TextItem8::Text {
+                delta: 0,
+                string: NAME.to_vec(),
+            }],
+        },
+        PolyText16 {
+            drawable: DRAWABLE,
+            gc: GC,
+            x: 1,
+            y: 2,
+            items: vec![TextItem16::Text {
+                delta: 0,
+                string: vec![1, 2, 3],
+            }],
+        },
+        ImageText8 {
+            drawable: DRAWABLE,
+            gc: GC,
+            x: 1,
+            y: 2,
+            string: NAME.to_vec(),
+        },
+        ImageText16 {
+            drawable: DRAWABLE,
+            gc: GC,
+            x: 1,
+            y: 2,
+            string: vec![1, 2, 3],
+        },
+        CreateColormap {
+            alloc: CreateColormapAlloc::None,
+            mid: COLORMAP,
+            window: WINDOW,
+            visual: VisualId::from(1),
+        },
+        FreeColormap { cmap: COLORMAP },
+        CopyColormapAndFree {
+            mid: COLORMAP,
+            src_cmap: COLORMAP,
+        },
+        InstallColormap { cmap: COLORMAP },
+        UninstallColormap { cmap: COLORMAP },
+        ListInstalledColormaps { window: WINDOW },
+        AllocColor {
+            cmap: COLORMAP,
+            red: 1,
+            green: 2,
+            blue: 3,
+        },
+        AllocNamedColor {
+            cmap: COLORMAP,
+            name: b"red".to_vec(),
+        },
+        AllocColorCells {
+            contiguous: false,
+            cmap: COLORMAP,
+            colors: 1,
+            planes: 0,
+        },
+        AllocColorPlanes {
+            contiguous: false,
+            cmap: COLORMAP,
+            colors: 1,
+            reds: 0,
+            greens: 0,
+            blues: 0,
+        },
+        FreeColors {
+            cmap: COLORMAP,
+            plane_mask: 0,
+            pixels: vec![1, 2, 3],
+        },
+        StoreColors {
+            cmap: COLORMAP,
+            items: vec![
+                ColorItem {
+                    pixel: 1,
+                    red: 2,
+                    green: 3,
+                    blue: 4,
+                    do_red: true,
+                    do_green: true,
+                    do_blue: false,
+                };
+                3
+            ],
+        },
+        StoreNamedColor {
+            cmap: COLORMAP,
+            do_red: true,
+            do_green: true,
+            do_blue: true,
+            pixel: 1,
+            name: b"red".to_vec(),
+        },
+        QueryColors {
+            cmap: COLORMAP,
+            pixels: vec![1, 2, 3],
+        },
+        LookupColor {
+            cmap: COLORMAP,
+            name: b"red".to_vec(),
+        },
+        CreateCursor {
+            cid: CURSOR,
+            source: PIXMAP,
+            mask: OrNone::none(),
+            fore_red: 1,
+            fore_green: 2,
+            fore_blue: 3,
+            back_red: 4,
+            back_green: 5,
+            back_blue: 6,
+            x: 0,
+            y: 0,
+        },
+        CreateGlyphCursor {
+            cid: CURSOR,
+            source_font: FONT,
+            mask_font: OrNone::new(FONT),
+            source_char: 1,
+            mask_char: 2,
+            fore_red: 1,
+            fore_green: 2,
+            fore_blue: 3,
+            back_red: 4,
+            back_green: 5,
+            back_blue: 6,
+        },
+        FreeCursor { cursor: CURSOR },
+        RecolorCursor {
+            cursor: CURSOR,
+            fore_red: 1,
+            fore_green: 2,
+            fore_blue: 3,
+            back_red: 4,
+            back_green: 5,
+            back_blue: 6,
+        },
+        QueryBestSize {
+            class: QueryBestSizeClass::Cursor,
+            drawable: DRAWABLE,
+            width: 1,
+            height: 2,
+        },
+        QueryExtension {
+            name: b"RANDR".to_vec(),
+        },
+        ListExtensions,
+        ChangeKeyboardMapping {
+            keycode_count: 1,
+            first_keycode: KeyCode::from(10u8),
+            keysyms_per_keycode: 3,
+            keysyms: vec![KeySym { inner: 0x61 }; 3],
+        },
+        GetKeyboardMapping {
+            first_keycode: KeyCode::from(10u8),
+            count: 1,
+        },
+        ChangeKeyboardControl {
+            values: ChangeKeyboardControlValues::new().set_bell_percent(50),
+        },
+        GetKeyboardControl,
+        Bell { percent: 0 },
+        ChangePointerControl {
+            acceleration_numerator: 1,
+            acceleration_denominator: 1,
+            threshold: 1,
+            do_acceleration: true,
+            do_threshold: true,
+        },
+        GetPointerControl,
+        SetScreenSaver {
+            timeout: 1,
+            interval: 2,
+            prefer_blanking: NoYesDefault::No,
+            allow_exposures: NoYesDefault::No,
+        },
+        GetScreenSaver,
+        ChangeHosts {
+            mode: ChangeHostsMode::Insert,
+            family: ChangeHostsFamily::Internet,
+            address: vec![127, 0, 0, 1, 2],
+        },
+        ListHosts,
+        SetAccessControl {
+            mode: SetAccessControlMode::Disable,
+        },
+        SetCloseDownMode {
+            mode: SetCloseDownModeMode::Destroy,
+        },
+        KillClient {
+            resource: KillClientResource::AllTemporary,
+        },
+        KillClient {
+            resource: KillClientResource::from(WINDOW),
+        },
+        RotateProperties {
+            window: WINDOW,
+            number_of_properties: 3,
+            delta: 1,
+            properties: vec![AtomId::WM_NAME; 3],
+        },
+        ForceScreenSaver {
+            mode: ForceScreenSaverMode::Reset,
+        },
+        SetPointerMapping {
+            map: vec![1, 2, 3],
+        },
+        GetPointerMapping,
+        SetModifierMapping {
+            keycodes: vec![KeyCode::from(10u8); 8],
+        },
+        GetModifierMapping,
+        NoOperation {
+            unused: vec![0; 3],
+        },
+    );
+}
+
+#[test]
+fn mit_shm_requests_match_spec() {
+    use just_x11::{
+        extensions::mit_shm::{requests::*, ShmSegId},
+        requests::PutImageFormat,
+    };
+
+    let shmseg = ShmSegId::unchecked_from(7);
+    check_requests!(introspection::MIT_SHM_REQUESTS, true;
+        QueryVersion,
+        Attach {
+            shmseg,
+            shmid: 1,
+            read_only: false,
+        },
+        Detach { shmseg },
+        PutImage {
+            drawable: DRAWABLE,
+            gc: GC,
+            total_width: 1,
+            total_height: 1,
+            src_x: 0,
+            src_y: 0,
+            src_width: 1,
+            src_height: 1,
+            dst_x: 0,
+            dst_y: 0,
+            depth: 24,
+            format: PutImageFormat::ZPixmap,
+            send_event: false,
+            bpad: 0,
+            shmseg,
+            offset: 0,
+        },
+        GetImage {
+            drawable: DRAWABLE,
+            x: 0,
+            y: 0,
+            width: 1,
+            height: 1,
+            plane_mask: -1,
+            format: 2,
+            shmseg,
+            offset: 0,
+        },
+        CreatePixmap {
+            pid: PIXMAP,
+            drawable: DRAWABLE,
+            width: 1,
+            height: 1,
+            depth: 24,
+            shmseg,
+            offset: 0,
+        },
+        AttachFd {
+            shmseg,
+            read_only: false,
+        },
+        CreateSegment {
+            shmseg,
+            size: 4096,
+            read_only: false,
+        },
+    );
+}
+
+#[test]
+fn present_requests_match_spec() {
+    use just_x11::extensions::present::{requests::*, EventMask};
+
+    check_requests!(introspection::PRESENT_REQUESTS, true;
+        QueryVersion {
+            major_version: 1,
+            minor_version: 0,
+        },
+        NotifyMsc {
+            window: WINDOW,
+            serial: 1,
+            target_msc: 0,
+            divisor: 1,
+            remainder: 0,
+        },
+        SelectInput {
+            event_id: 7,
+            window: WINDOW,
+            event_mask: EventMask::COMPLETE_NOTIFY,
+        },
+    );
+}
+
+#[test]
+fn randr_requests_match_spec() {
+    use just_x11::{
+        extensions::randr::{
+            replies::OutputId, requests::*, CrtcId, MonitorInfo, Rotation, SelectMask, SizeId,
+        },
+        requests::Timestamp,
+    };
+
+    let output = OutputId::unchecked_from(8);
+    check_requests!(introspection::RANDR_REQUESTS, true;
+        QueryVersion {
+            major_version: 1,
+            minor_version: 5,
+        },
+        SetScreenConfig {
+            window: WINDOW,
+            timestamp: Timestamp::CurrentTime,
+            config_timestamp: Timestamp::CurrentTime,
+            size_index: SizeId::from(0),
+            rotation: Rotation::Rotate0,
+        },
+        SelectInput {
+            window: WINDOW,
+            enable: SelectMask::SCREEN_CHANGE_NOTIFY_MASK,
+        },
+        GetScreenInfo { window: WINDOW },
+        GetScreenSizeRange { window: WINDOW },
+        GetOutputInfo {
+            output,
+            timestamp: Timestamp::CurrentTime,
+        },
+        GetCrtcInfo {
+            crtc: CrtcId::unchecked_from(9),
+            timestamp: Timestamp::CurrentTime,
+        },
+        GetScreenResourcesCurrent { window: WINDOW },
+        GetMonitors {
+            window: WINDOW,
+            get_active: true,
+        },
+        SetMonitor {
+            window: WINDOW,
+            monitor: MonitorInfo {
+                name: AtomId::WM_NAME,
+                primary: false,
+                automatic: false,
+                x: 0,
+                y: 0,
+                width_in_pixels: 1,
+                height_in_pixels: 1,
+                width_in_millimeters: 1,
+                height_in_millimeters: 1,
+                outputs: vec![output; 3],
+            },
+        },
+        DeleteMonitor {
+            window: WINDOW,
+            name: AtomId::WM_NAME,
+        },
+    );
+}
+
+#[test]
+fn shape_requests_match_spec() {
+    use just_x11::{
+        extensions::shape::{requests::*, ShapeKind, ShapeOperation},
+        requests::Ordering,
+    };
+
+    check_requests!(introspection::SHAPE_REQUESTS, true;
+        Rectangles {
+            operation: ShapeOperation::Set,
+            kind: ShapeKind::Bounding,
+            ordering: Ordering::UnSorted,
+            window: WINDOW,
+            x_offset: 0,
+            y_offset: 0,
+            rectangles: RECTANGLES.to_vec(),
+        },
+        Mask {
+            operation: ShapeOperation::Set,
+            kind: ShapeKind::Bounding,
+            window: WINDOW,
+            x_offset: 0,
+            y_offset: 0,
+            source: OrNone::new(PIXMAP),
+        },
+    );
+}
+
+#[test]
+fn xinput_requests_match_spec() {
+    use just_x11::extensions::xinput::{requests::*, DeviceId, EventMask};
+
+    check_requests!(introspection::XINPUT_REQUESTS, true;
+        XISelectEvents {
+            window: WINDOW,
+            masks: vec![
+                (DeviceId::ALL_MASTER_DEVICES, EventMask::MOTION),
+                (DeviceId::ALL_DEVICES, EventMask::BUTTON_PRESS),
+            ],
+        },
+        XIQueryVersion {
+            major_version: 2,
+            minor_version: 0,
+        },
+        XIQueryDevice {
+            device_id: DeviceId::ALL_DEVICES,
+        },
+    );
+}
+
+#[test]
+fn xkb_requests_match_spec() {
+    use just_x11::extensions::xkb::{requests::*, DeviceSpec, EventMask};
+
+    check_requests!(introspection::XKB_REQUESTS, true;
+        UseExtension {
+            wanted_major: 1,
+            wanted_minor: 0,
+        },
+        SelectEvents {
+            device_spec: DeviceSpec::USE_CORE_KBD,
+            select: EventMask::BELL_NOTIFY,
+            clear: EventMask::EMPTY_MASK,
+        },
+        Bell {
+            device_spec: DeviceSpec::USE_CORE_KBD,
+            bell_class: 0,
+            bell_id: 0,
+            percent: 0,
+            force_sound: false,
+            event_only: true,
+            pitch: 0,
+            duration: 0,
+            name: OrNone::none(),
+            window: OrNone::none(),
+        },
+    );
+}
@@ -0,0 +1,319 @@
+//! Derive macros for `FromLeBytes` and `ToLeBytes` of `just_x11`, re-exported from there
+//!
+//! Fields of a plain struct are read and written in order of declaration. `#[le(...)]` attributes
+//! describe bytes of the wire format that are not stored in the struct, in the order they are
+//! listed:
+//!
+//! - `#[le(pad = N)]` on a field: `N` unused bytes before the field, on the struct: `N` unused
+//!   bytes after the last field
+//! - `#[le(length = T)]` on a `Vec` field: number of elements as `T` before the list
+//!
+//! ```ignore
+//! /*
+//!      4     WINDOW                          root
+//!      2     n                               number of WINDOWs in children
+//!      14                                    unused
+//!      4n     LISTofWINDOW                   children
+//! */
+//! #[derive(FromLeBytes, ToLeBytes)]
+//! struct Tree {
+//!     root: WindowId,
+//!     #[le(length = u16, pad = 14)]
+//!     children: Vec<WindowId>,
+//! }
+//! ```
+
+use proc_macro::{Delimiter, TokenStream, TokenTree};
+use std::fmt::Write;
+
+/// Bytes before a field that are not stored in the struct
+enum Extra {
+    Pad(String),
+    Length(String),
+}
+
+struct Field {
+    name: String,
+    /// Element type of `Vec` fields
+    list_of: Option<String>,
+    extras: Vec<Extra>,
+}
+
+struct Struct {
+    name: String,
+    fields: Vec<Field>,
+    trailing_pad: Option<String>,
+}
+
+#[proc_macro_derive(FromLeBytes, attributes(le))]
+pub fn derive_from_le_bytes(input: TokenStream) -> TokenStream {
+    match parse_struct(input) {
+        Ok(parsed) => generate_from_le_bytes(&parsed),
+        Err(err) => compile_error(&err),
+    }
+}
+
+#[proc_macro_derive(ToLeBytes, attributes(le))]
+pub fn derive_to_le_bytes(input: TokenStream) -> TokenStream {
+    match parse_struct(input) {
+        Ok(parsed) => generate_to_le_bytes(&parsed),
+        Err(err) => compile_error(&err),
+    }
+}
+
+fn compile_error(message: &str) -> TokenStream {
+    format!("::std::compile_error!({:?});", message)
+        .parse()
+        .unwrap()
+}
+
+fn generate_from_le_bytes(parsed: &Struct) -> TokenStream {
+    let mut body = String::new();
+    for field in &parsed.fields {
+        for extra in &field.extras {
+            match extra {
+                Extra::Pad(count) => {
+                    writeln!(body, "::just_x11::derive::skip(conn, {})?;", count).unwrap()
+                }
+                Extra::Length(ty) => writeln!(
+                    body,
+                    "let __{}_length: {} = ::just_x11::FromLeBytes::from_le_bytes(conn)?;",
+                    field.name, ty
+                )
+                .unwrap(),
+            }
+        }
+        match &field.list_of {
+            Some(element) => writeln!(
+                body,
+                "let {name} = ::just_x11::derive::read_list::<{element}>(conn, __{name}_length as usize)?;",
+                name = field.name,
+            )
+            .unwrap(),
+            None => writeln!(
+                body,
+                "let {} = ::just_x11::FromLeBytes::from_le_bytes(conn)?;",
+                field.name
+            )
+            .unwrap(),
+        }
+    }
+    if let Some(count) = &parsed.trailing_pad {
+        writeln!(body, "::just_x11::derive::skip(conn, {})?;", count).unwrap();
+    }
+    let names = parsed
+        .fields
+        .iter()
+        .map(|field| field.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!(
+        "#[automatically_derived]
+        impl ::just_x11::FromLeBytes for {} {{
+            fn from_le_bytes(
+                conn: &mut ::just_x11::XConnection,
+            ) -> ::std::result::Result<Self, ::just_x11::error::Error> {{
+                {}
+                ::std::result::Result::Ok(Self {{ {} }})
+            }}
+        }}",
+        parsed.name, body, names
+    )
+    .parse()
+    .unwrap()
+}
+
+fn generate_to_le_bytes(parsed: &Struct) -> TokenStream {
+    let mut body = String::new();
+    for field in &parsed.fields {
+        for extra in &field.extras {
+            match extra {
+                Extra::Pad(count) => writeln!(body, "w.write_all(&[0u8; {}])?;", count).unwrap(),
+                Extra::Length(ty) => writeln!(
+                    body,
+                    "::just_x11::derive::WriteLe::write_le(&(self.{}.len() as {}), w)?;",
+                    field.name, ty
+                )
+                .unwrap(),
+            }
+        }
+        match &field.list_of {
+            Some(_) => writeln!(
+                body,
+                "for item in &self.{} {{ ::just_x11::derive::WriteLe::write_le(item, w)?; }}",
+                field.name
+            )
+            .unwrap(),
+            None => writeln!(
+                body,
+                "::just_x11::derive::WriteLe::write_le(&self.{}, w)?;",
+                field.name
+            )
+            .unwrap(),
+        }
+    }
+    if let Some(count) = &parsed.trailing_pad {
+        writeln!(body, "w.write_all(&[0u8; {}])?;", count).unwrap();
+    }
+
+    format!(
+        "#[automatically_derived]
+        impl ::just_x11::ToLeBytes for {} {{
+            fn to_le_bytes(&self, w: &mut impl ::std::io::Write) -> ::std::io::Result<()> {{
+                {}
+                ::std::result::Result::Ok(())
+            }}
+        }}",
+        parsed.name, body
+    )
+    .parse()
+    .unwrap()
+}
+
+/// Split `tokens` on commas that are not inside of `<...>`
+fn split_commas(tokens: Vec<TokenTree>) -> Vec<Vec<TokenTree>> {
+    let mut parts = vec![Vec::new()];
+    let mut depth = 0usize;
+    for token in tokens {
+        if let TokenTree::Punct(punct) = &token {
+            match punct.as_char() {
+                '<' => depth += 1,
+                '>' => depth = depth.saturating_sub(1),
+                ',' if depth == 0 => {
+                    parts.push(Vec::new());
+                    continue;
+                }
+                _ => {}
+            }
+        }
+        parts.last_mut().unwrap().push(token);
+    }
+    parts.retain(|part| !part.is_empty());
+    parts
+}
+
+fn to_string(tokens: &[TokenTree]) -> String {
+    tokens.iter().cloned().collect::<TokenStream>().to_string()
+}
+
+/// Take leading `#[...]` attributes from `tokens`, returning contents of `le` ones in order
+fn take_attributes(tokens: &mut Vec<TokenTree>) -> Result<Vec<(String, String)>, String> {
+    let mut items = Vec::new();
+    while let [TokenTree::Punct(hash), TokenTree::Group(group), ..] = tokens.as_slice() {
+        if hash.as_char() != '#' || group.delimiter() != Delimiter::Bracket {
+            break;
+        }
+        let attribute = group.stream().into_iter().collect::<Vec<_>>();
+        tokens.drain(..2);
+
+        let [TokenTree::Ident(name), TokenTree::Group(arguments)] = attribute.as_slice() else {
+            continue;
+        };
+        if name.to_string() != "le" {
+            continue;
+        }
+        for item in split_commas(arguments.stream().into_iter().collect()) {
+            match item.as_slice() {
+                [TokenTree::Ident(key), TokenTree::Punct(eq), value @ ..]
+                    if eq.as_char() == '=' && !value.is_empty() =>
+                {
+                    items.push((key.to_string(), to_string(value)))
+                }
+                _ => {
+                    return Err(format!(
+                        "Expected `key = value` in #[le], got `{}`",
+                        to_string(&item)
+                    ))
+                }
+            }
+        }
+    }
+    Ok(items)
+}
+
+/// Drop leading `pub`, `pub(crate)` etc.
+fn skip_visibility(tokens: &mut Vec<TokenTree>) {
+    if matches!(tokens.first(), Some(TokenTree::Ident(ident)) if ident.to_string() == "pub") {
+        tokens.remove(0);
+        if matches!(tokens.first(), Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis)
+        {
+            tokens.remove(0);
+        }
+    }
+}
+
+fn parse_struct(input: TokenStream) -> Result<Struct, String> {
+    let mut tokens = input.into_iter().collect::<Vec<_>>();
+    let mut trailing_pad = None;
+    for (key, value) in take_attributes(&mut tokens)? {
+        match key.as_str() {
+            "pad" => trailing_pad = Some(value),
+            _ => return Err(format!("Unknown #[le] attribute of a struct: `{}`", key)),
+        }
+    }
+    skip_visibility(&mut tokens);
+
+    let (name, fields) = match tokens.as_slice() {
+        [TokenTree::Ident(keyword), TokenTree::Ident(name), TokenTree::Group(fields)]
+            if keyword.to_string() == "struct" && fields.delimiter() == Delimiter::Brace =>
+        {
+            (name.to_string(), fields.stream())
+        }
+        _ => {
+            return Err(
+                "Only structs with named fields and without generics can be derived".to_string(),
+            )
+        }
+    };
+
+    let fields = split_commas(fields.into_iter().collect())
+        .into_iter()
+        .map(|mut field| {
+            let mut extras = Vec::new();
+            for (key, value) in take_attributes(&mut field)? {
+                match key.as_str() {
+                    "pad" => extras.push(Extra::Pad(value)),
+                    "length" => extras.push(Extra::Length(value)),
+                    _ => return Err(format!("Unknown #[le] attribute of a field: `{}`", key)),
+                }
+            }
+            skip_visibility(&mut field);
+
+            let [TokenTree::Ident(name), TokenTree::Punct(colon), ty @ ..] = field.as_slice() else {
+                return Err(format!("Could not parse field `{}`", to_string(&field)));
+            };
+            if colon.as_char() != ':' {
+                return Err(format!("Could not parse field `{}`", to_string(&field)));
+            }
+            let list_of = match ty {
+                [TokenTree::Ident(vec), TokenTree::Punct(open), element @ .., TokenTree::Punct(close)]
+                    if vec.to_string() == "Vec" && open.as_char() == '<' && close.as_char() == '>' =>
+                {
+                    Some(to_string(element))
+                }
+                _ => None,
+            };
+
+            let has_length = extras.iter().any(|extra| matches!(extra, Extra::Length(_)));
+            if list_of.is_some() != has_length {
+                return Err(format!(
+                    "Field `{}` must be a `Vec` if and only if it has #[le(length = ...)]",
+                    name
+                ));
+            }
+
+            Ok(Field {
+                name: name.to_string(),
+                list_of,
+                extras,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    Ok(Struct {
+        name,
+        fields,
+        trailing_pad,
+    })
+}
@@ -3,13 +3,10 @@ use just_x11::{
     events::EventType,
     events::SomeEvent,
     keysym::KeySym,
-    requests::{
-        self, GContextSettings, GetGeometry, KeyCode, PutImage, PutImageFormat,
-        WindowCreationAttributes,
-    },
+    requests::{self, GetGeometry, KeyCode, PutImage, PutImageFormat, WindowCreationAttributes},
     Drawable, GContextId, WindowClass, WindowId, WindowVisual, XDisplay,
 };
-use just_x11_simple::{keys::KeySymbols, X11Connection};
+use just_x11_simple::{display, keys::KeySymbols, X11Connection};
 use std::{
     collections::HashMap,
     time::{Duration, SystemTime},
@@ -53,8 +50,8 @@ impl KeyBindings {
     }
 }
 
-pub fn go() -> Result<(), Error> {
-    let mut conn = X11Connection::new(XDisplay::open()?);
+pub fn go(display: XDisplay) -> Result<(), Error> {
+    let mut conn = X11Connection::new(display);
 
     let mut bindings = KeyBindings::new(&mut conn)?;
     bindings.add_binding(KeySym::d, KeyAction::LeftDown);
@@ -65,9 +62,10 @@ pub fn go() -> Result<(), Error> {
     bindings.add_binding(KeySym::q, KeyAction::Quit);
 
     let window = {
-        let window_id = WindowId::from(conn.display_mut().id_allocator().allocate_id());
-        let window_attributes = WindowCreationAttributes::new().set_event_mask(
+        let window_id = WindowId::from_resource(conn.display_mut().id_allocator().allocate_id());
+        let window_attributes = WindowCreationAttributes::for_top_level(
             EventType::KEY_PRESS | EventType::KEY_RELEASE | EventType::STRUCTURE_NOTIFY,
+            None,
         );
         let create_window = requests::CreateWindow {
             depth: conn.default_screen().root_depth,
@@ -89,15 +87,8 @@ pub fn go() -> Result<(), Error> {
     conn.display_mut()
         .send_request(&requests::MapWindow { window })?;
 
-    let gc = {
-        let gc_id = GContextId::from(conn.display_mut().id_allocator().allocate_id());
-        conn.display_mut().send_request(&requests::CreateGC {
-            cid: gc_id,
-            drawable: Drawable::Window(window),
-            values: GContextSettings::new(),
-        })?;
-        gc_id
-    };
+    let screen = conn.display().default_screen_index();
+    let gc = conn.display_mut().default_gc(screen)?;
 
     let pad_size = V2 { x: 20, y: 150 };
 
@@ -211,7 +202,18 @@ pub fn go() -> Result<(), Error> {
 }
 
 fn main() {
-    match go() {
+    let mut args = std::env::args().skip(1).collect::<Vec<_>>();
+    let display = match display::take_display_arg(&mut args)
+        .and_then(|name| display::open(name.as_deref()))
+    {
+        Ok(display) => display,
+        Err(err) => {
+            eprintln!("pong: error: {}", err);
+            return;
+        }
+    };
+
+    match go(display) {
         Ok(()) => {}
         Err(err) => {
             eprintln!("pong: error: {}", err);
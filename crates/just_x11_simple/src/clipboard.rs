@@ -0,0 +1,346 @@
+//! `CLIPBOARD` selection owner and requester, see section 2 "Peer-to-Peer Communication by Means
+//! of Selections" of ICCCM
+//!
+//! Both sides wait for events of their own window and drop all other events, so they should run
+//! on a connection used only for the clipboard. The owner offers data in several targets, e.g.
+//! MIME types of image formats, and lists them in `TARGETS`. Owned data is written in one
+//! property even if it is large, requested data may also come in `INCR` chunks.
+
+use crate::{panic_hook, X11Connection};
+use just_x11::{
+    atoms::AtomId,
+    error::Error,
+    events::{EventType, PropertyNotifyState, SelectionNotify, SelectionRequest, SomeEvent},
+    replies::{PropertyValue, String8},
+    requests::{
+        self, ChangePropertyFormat, ChangePropertyMode, Timestamp, WindowCreationAttributes,
+    },
+    OrNone, WindowClass, WindowId, WindowVisual,
+};
+use std::{
+    str::FromStr,
+    thread,
+    time::{Duration, Instant},
+};
+
+/// How long the requester waits for the owner, and for each `INCR` chunk
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Data offered in `target`, e.g. `image/png`
+#[derive(Debug, Clone)]
+pub struct Offer {
+    pub target: String,
+    pub data: Vec<u8>,
+}
+
+struct Atoms {
+    clipboard: AtomId,
+    targets: AtomId,
+    incr: AtomId,
+}
+
+impl Atoms {
+    fn intern(conn: &mut X11Connection) -> Result<Self, Error> {
+        Ok(Self {
+            clipboard: conn.get_atom_id(String8::from_str("CLIPBOARD").unwrap())?,
+            targets: conn.get_atom_id(String8::from_str("TARGETS").unwrap())?,
+            incr: conn.get_atom_id(String8::from_str("INCR").unwrap())?,
+        })
+    }
+}
+
+/// Property of `SelectionNotify` answering a request for `target`, with its type, format and
+/// value. `None` if the target is not offered.
+fn convert(
+    offers: &[(AtomId, Vec<u8>)],
+    targets: AtomId,
+    target: AtomId,
+) -> Option<(AtomId, ChangePropertyFormat, Vec<u8>)> {
+    if target == targets {
+        let atoms = [targets]
+            .into_iter()
+            .chain(offers.iter().map(|&(target, _)| target))
+            .flat_map(|atom| atom.to_le_bytes())
+            .collect();
+        return Some((AtomId::ATOM, ChangePropertyFormat::Format32, atoms));
+    }
+    offers
+        .iter()
+        .find(|(offered, _)| *offered == target)
+        .map(|(_, data)| (target, ChangePropertyFormat::Format8, data.clone()))
+}
+
+/// Hidden window receiving `PropertyNotify` of its properties
+fn create_window(conn: &mut X11Connection) -> Result<WindowId, Error> {
+    let window = WindowId::from_resource(conn.display_mut().id_allocator().allocate_id());
+    let root = conn.default_screen().root;
+    conn.display_mut().send_request(&requests::CreateWindow {
+        depth: 0,
+        wid: window,
+        parent: root,
+        x: -1,
+        y: -1,
+        width: 1,
+        height: 1,
+        border_width: 0,
+        window_class: WindowClass::InputOnly,
+        visual: WindowVisual::CopyFromParent,
+        attributes: WindowCreationAttributes::input_only_overlay()
+            .set_event_mask(EventType::PROPERTY_CHANGE),
+    })?;
+    Ok(window)
+}
+
+/// Next event or `None` after `deadline`
+fn next_event(
+    conn: &mut X11Connection,
+    deadline: Option<Instant>,
+) -> Result<Option<SomeEvent>, Error> {
+    loop {
+        if let Some(event) = conn.display_mut().next_event()? {
+            return Ok(Some(event));
+        }
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            return Ok(None);
+        }
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+/// Own `CLIPBOARD` with `offers` and answer requests until other client takes it. Returns
+/// `false` if other client took it before it was acquired.
+pub fn serve(conn: &mut X11Connection, offers: Vec<Offer>) -> Result<bool, Error> {
+    let atoms = Atoms::intern(conn)?;
+    let offers = offers
+        .into_iter()
+        .map(|offer| {
+            let target = conn.get_atom_id(String8::from_str(&offer.target).unwrap())?;
+            Ok((target, offer.data))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    let owner = create_window(conn)?;
+    panic_hook::destroy_on_panic(owner);
+
+    // Selections must not be acquired with CurrentTime, zero-length append tells the server time
+    conn.display_mut().send_request(&requests::ChangeProperty {
+        mode: ChangePropertyMode::Append,
+        window: owner,
+        property: AtomId::WM_NAME,
+        type_: AtomId::STRING,
+        format: ChangePropertyFormat::Format8,
+        data: Vec::new(),
+    })?;
+    conn.flush()?;
+    let time = loop {
+        match next_event(conn, None)? {
+            Some(SomeEvent::PropertyNotify(event)) if event.window == owner => break event.time,
+            _ => {}
+        }
+    };
+
+    conn.display_mut()
+        .send_request(&requests::SetSelectionOwner {
+            owner: OrNone::new(owner),
+            selection: atoms.clipboard,
+            time: Timestamp::Timestamp(time),
+        })?;
+    let acquired = request_blocking!(
+        conn.display_mut(),
+        requests::GetSelectionOwner {
+            selection: atoms.clipboard
+        }
+    )?
+    .unwrap()
+    .owner
+        == owner;
+
+    if acquired {
+        loop {
+            match next_event(conn, None)? {
+                Some(SomeEvent::SelectionClear(event)) if event.selection == atoms.clipboard => {
+                    break;
+                }
+                Some(SomeEvent::SelectionRequest(request)) if request.owner == owner => {
+                    answer(conn, &atoms, &offers, time, &request)?;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    conn.display_mut()
+        .send_request(&requests::DestroyWindow { window: owner })?;
+    panic_hook::forget_on_panic(owner);
+    conn.flush()?;
+    Ok(acquired)
+}
+
+fn answer(
+    conn: &mut X11Connection,
+    atoms: &Atoms,
+    offers: &[(AtomId, Vec<u8>)],
+    acquired: u32,
+    request: &SelectionRequest,
+) -> Result<(), Error> {
+    // Obsolete requesters leave property none, the target is used instead
+    let property = request.property.value().unwrap_or(request.target);
+    // Requests from before the selection was acquired are for the previous owner
+    let converted = (request.selection == atoms.clipboard
+        && (request.time == 0 || request.time >= acquired))
+        .then(|| convert(offers, atoms.targets, request.target))
+        .flatten();
+
+    let property = match converted {
+        Some((type_, format, value)) => {
+            conn.change_property(request.requestor, property, type_, format, &value)?;
+            OrNone::new(property)
+        }
+        None => OrNone::none(),
+    };
+    let notify = SelectionNotify::new(
+        request.time,
+        request.requestor,
+        request.selection,
+        request.target,
+        property,
+    );
+    conn.display_mut().send_request(&requests::SendEvent {
+        propagate: false,
+        destination: request.requestor,
+        event_mask: 0,
+        event: notify.to_le_bytes(),
+    })?;
+    conn.flush()
+}
+
+/// Read and delete `property` of `window`
+fn take_property(
+    conn: &mut X11Connection,
+    window: WindowId,
+    property: AtomId,
+) -> Result<PropertyValue, Error> {
+    let reply = request_blocking!(
+        conn.display_mut(),
+        requests::GetProperty {
+            delete: true,
+            window,
+            property,
+            type_: AtomId::ANY_PROPERTY_TYPE,
+            long_offset: 0,
+            long_length: u32::MAX / 4,
+        }
+    )?;
+    Ok(match reply {
+        Ok(reply) => reply.into_value(AtomId::ANY_PROPERTY_TYPE),
+        Err(_) => PropertyValue::Missing,
+    })
+}
+
+/// `CLIPBOARD` converted to `target`, `None` if it has no owner, the owner can't convert it to
+/// `target` or doesn't answer in time
+pub fn request(conn: &mut X11Connection, target: &str) -> Result<Option<Vec<u8>>, Error> {
+    let atoms = Atoms::intern(conn)?;
+    let target = conn.get_atom_id(String8::from_str(target).unwrap())?;
+    let property = conn.get_atom_id(String8::from_str("JUST_CLIPBOARD").unwrap())?;
+    let window = create_window(conn)?;
+    let data = request_on(conn, &atoms, window, target, property);
+    conn.display_mut()
+        .send_request(&requests::DestroyWindow { window })?;
+    conn.flush()?;
+    data
+}
+
+fn request_on(
+    conn: &mut X11Connection,
+    atoms: &Atoms,
+    window: WindowId,
+    target: AtomId,
+    property: AtomId,
+) -> Result<Option<Vec<u8>>, Error> {
+    conn.display_mut()
+        .send_request(&requests::ConvertSelection {
+            requestor: window,
+            selection: atoms.clipboard,
+            target,
+            property: OrNone::new(property),
+            time: Timestamp::CurrentTime,
+        })?;
+    conn.flush()?;
+
+    let deadline = Instant::now() + TIMEOUT;
+    let converted = loop {
+        match next_event(conn, Some(deadline))? {
+            Some(SomeEvent::SelectionNotify(event)) if event.requestor == window => {
+                break event.property.value().is_some();
+            }
+            Some(_) => {}
+            None => return Ok(None),
+        }
+    };
+    if !converted {
+        return Ok(None);
+    }
+
+    match take_property(conn, window, property)? {
+        PropertyValue::Value { type_, .. } if type_ == atoms.incr => {}
+        PropertyValue::Value { value, .. } => return Ok(Some(value)),
+        _ => return Ok(None),
+    }
+
+    // Deleting the `INCR` property asks for the first chunk, empty chunk ends the transfer
+    let mut data = Vec::new();
+    loop {
+        let deadline = Instant::now() + TIMEOUT;
+        loop {
+            match next_event(conn, Some(deadline))? {
+                Some(SomeEvent::PropertyNotify(event))
+                    if event.window == window
+                        && event.atom == property
+                        && event.state == PropertyNotifyState::NewValue =>
+                {
+                    break;
+                }
+                Some(_) => {}
+                None => return Ok(None),
+            }
+        }
+        match take_property(conn, window, property)? {
+            PropertyValue::Value { value, .. } if value.is_empty() => return Ok(Some(data)),
+            PropertyValue::Value { value, .. } => data.extend(value),
+            _ => return Ok(None),
+        }
+    }
+}
+
+/// Targets `CLIPBOARD` can be converted to, empty if it has no owner
+pub fn targets(conn: &mut X11Connection) -> Result<Vec<String>, Error> {
+    let Some(value) = request(conn, "TARGETS")? else {
+        return Ok(Vec::new());
+    };
+    let atoms = value
+        .chunks_exact(4)
+        .map(|atom| AtomId::from(u32::from_le_bytes([atom[0], atom[1], atom[2], atom[3]])))
+        .collect::<Vec<_>>();
+    Ok(conn
+        .get_atom_names(&atoms)?
+        .iter()
+        .map(ToString::to_string)
+        .collect())
+}
+
+#[test]
+fn clipboard_conversions() {
+    let png = AtomId::from(0x200);
+    let bmp = AtomId::from(0x201);
+    let targets = AtomId::from(0x100);
+    let offers = [(png, vec![1, 2]), (bmp, vec![3])];
+
+    let (type_, format, value) = convert(&offers, targets, targets).unwrap();
+    assert_eq!(type_, AtomId::ATOM);
+    assert!(matches!(format, ChangePropertyFormat::Format32));
+    assert_eq!(value, [0, 1, 0, 0, 0, 2, 0, 0, 1, 2, 0, 0]);
+
+    let (type_, _, value) = convert(&offers, targets, bmp).unwrap();
+    assert_eq!((type_, value), (bmp, vec![3]));
+    assert!(convert(&offers, targets, AtomId::STRING).is_none());
+}
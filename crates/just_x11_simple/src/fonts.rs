@@ -0,0 +1,148 @@
+//! X Logical Font Description patterns of core fonts used in `ListFonts`. Listed names are
+//! parsed by [`just_bdf::xlfd`], shared with fonts loaded from BDF files.
+//!
+//! ```text
+//! -misc-fixed-medium-r-semicondensed--13-120-75-75-c-60-iso10646-1
+//! ```
+
+pub use just_bdf::xlfd::{Xlfd, XlfdError};
+use std::{fmt, str::FromStr};
+
+/// XLFD pattern, fields that are `None` match any value. Sizes of scalable fonts are `0` in names
+/// listed by the server.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct XlfdPattern {
+    /// e.g. `misc`, `adobe`
+    pub foundry: Option<String>,
+    /// e.g. `fixed`, `terminus`
+    pub family: Option<String>,
+    /// e.g. `medium`, `bold`
+    pub weight: Option<String>,
+    /// `r` (roman), `i` (italic), `o` (oblique), `ri`, `ro` or `ot`
+    pub slant: Option<String>,
+    /// e.g. `normal`, `semicondensed`
+    pub set_width: Option<String>,
+    /// Usually empty
+    pub add_style: Option<String>,
+    /// Height in pixels
+    pub pixel_size: Option<u32>,
+    /// Height in tenths of a point
+    pub point_size: Option<u32>,
+    /// Horizontal DPI the font was designed for
+    pub resolution_x: Option<u32>,
+    /// Vertical DPI the font was designed for
+    pub resolution_y: Option<u32>,
+    /// `p` (proportional), `m` (monospaced) or `c` (character cell)
+    pub spacing: Option<String>,
+    /// Average glyph width in tenths of a pixel, negative for right-to-left fonts
+    pub average_width: Option<i32>,
+    /// e.g. `iso10646`, `iso8859`
+    pub charset_registry: Option<String>,
+    /// e.g. `1` for `iso10646-1`
+    pub charset_encoding: Option<String>,
+}
+
+impl XlfdPattern {
+    /// Pattern matching every font
+    pub fn any() -> Self {
+        Self::default()
+    }
+
+    /// Pattern bytes of `ListFonts` and `OpenFont` requests
+    pub fn to_pattern(&self) -> Vec<u8> {
+        self.to_string().into_bytes()
+    }
+}
+
+impl fmt::Display for XlfdPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let number = |value: Option<u32>| value.map(|value| value.to_string());
+        // `~` is used instead of `-` that would be taken as field separator
+        let average_width = self
+            .average_width
+            .map(|value| value.to_string().replace('-', "~"));
+        let fields = [
+            self.foundry.clone(),
+            self.family.clone(),
+            self.weight.clone(),
+            self.slant.clone(),
+            self.set_width.clone(),
+            self.add_style.clone(),
+            number(self.pixel_size),
+            number(self.point_size),
+            number(self.resolution_x),
+            number(self.resolution_y),
+            self.spacing.clone(),
+            average_width,
+            self.charset_registry.clone(),
+            self.charset_encoding.clone(),
+        ];
+        for field in fields {
+            write!(f, "-{}", field.as_deref().unwrap_or("*"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Names listed by `ListFonts`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FontList {
+    pub fonts: Vec<Xlfd>,
+    /// Aliases, e.g. `fixed`, and other names not in XLFD form, with the reason
+    pub other: Vec<(String, XlfdError)>,
+}
+
+impl FontList {
+    pub fn from_names<'a>(names: impl IntoIterator<Item = &'a [u8]>) -> Self {
+        let mut list = Self::default();
+        for name in names {
+            let name = String::from_utf8_lossy(name);
+            match Xlfd::from_str(&name) {
+                Ok(font) => list.fonts.push(font),
+                Err(err) => list.other.push((name.into_owned(), err)),
+            }
+        }
+        list
+    }
+}
+
+#[test]
+fn xlfd_patterns_and_names() {
+    let pattern = XlfdPattern {
+        family: Some("terminus".to_string()),
+        weight: Some("medium".to_string()),
+        slant: Some("r".to_string()),
+        add_style: Some(String::new()),
+        pixel_size: Some(14),
+        ..XlfdPattern::any()
+    };
+    assert_eq!(
+        pattern.to_string(),
+        "-*-terminus-medium-r-*--14-*-*-*-*-*-*-*"
+    );
+    let right_to_left = XlfdPattern {
+        average_width: Some(-70),
+        ..XlfdPattern::any()
+    };
+    assert_eq!(right_to_left.to_string(), "-*-*-*-*-*-*-*-*-*-*-*-~70-*-*");
+
+    let list = FontList::from_names([
+        &b"-misc-fixed-medium-r-semicondensed--13-120-75-75-c-60-iso10646-1"[..],
+        b"-misc-fixed-medium-r-normal--13-120-75-75-c-~70-iso10646-1",
+        b"fixed",
+        b"-misc-fixed-medium-r-normal--x-120-75-75-c-60-iso10646-1",
+    ]);
+    assert_eq!(list.fonts[0].family_name, "fixed");
+    assert_eq!(list.fonts[0].average_width, Some(60));
+    assert_eq!(list.fonts[1].average_width, Some(-70));
+    assert_eq!(
+        list.other,
+        [
+            ("fixed".to_string(), XlfdError::NotXlfd),
+            (
+                "-misc-fixed-medium-r-normal--x-120-75-75-c-60-iso10646-1".to_string(),
+                XlfdError::InvalidNumber("PIXEL_SIZE", "x".to_string())
+            ),
+        ]
+    );
+}
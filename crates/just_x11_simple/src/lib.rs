@@ -10,12 +10,13 @@ use just_x11::{
     bitmask,
     error::Error,
     events::{self, EventType},
-    replies::{self, String8},
+    replies::{self, PropertyValue, String8},
     requests::{
-        self, ChangePropertyFormat, ChangePropertyMode, ConfigureWindowAttributes, NoReply,
-        WindowCreationAttributes,
+        self, ChangePropertyFormat, ChangePropertyMode, ConfigureWindowAttributes,
+        KillClientResource, NoReply, SetCloseDownModeMode, Timestamp, WindowCreationAttributes,
     },
-    Drawable, OrNone, PendingReply, PixmapId, ResourceId, WindowId, XDisplay,
+    Drawable, OrNone, PendingReply, PixmapId, ResourceId, WindowClass, WindowId, WindowVisual,
+    XDisplay,
 };
 use std::{
     collections::HashMap,
@@ -24,6 +25,8 @@ use std::{
     str::FromStr,
 };
 
+pub use just_x11::display;
+pub mod fonts;
 pub mod keys;
 
 macro_rules! request_blocking {
@@ -37,10 +40,16 @@ macro_rules! request_blocking {
     }};
 }
 
+pub mod clipboard;
+pub mod monitors;
+pub mod owned;
+pub mod panic_hook;
+
 pub struct X11Connection {
     display: XDisplay,
     known_atoms_names: HashMap<AtomId, String8>,
     known_atoms_ids: HashMap<String8, AtomId>,
+    dropped: owned::DroppedResources,
 }
 
 impl X11Connection {
@@ -49,6 +58,7 @@ impl X11Connection {
             display,
             known_atoms_names: HashMap::new(),
             known_atoms_ids: HashMap::new(),
+            dropped: owned::DroppedResources::default(),
         }
     }
 
@@ -78,7 +88,7 @@ impl X11Connection {
     }
 
     pub fn default_screen(&self) -> just_x11::Screen {
-        self.display.screens()[0].clone()
+        self.display.default_screen().clone()
     }
 
     pub fn set_border_width(
@@ -119,6 +129,49 @@ impl X11Connection {
         Ok(r.name)
     }
 
+    /// Names of all `atoms` in the same order. Requests for atoms missing in the cache are sent
+    /// together with one flush, so it takes at most one round-trip.
+    pub fn get_atom_names(&mut self, atoms: &[AtomId]) -> Result<Vec<String8>, Error> {
+        let mut pending = Vec::new();
+        for &atom in atoms {
+            if !self.known_atoms_names.contains_key(&atom)
+                && !pending
+                    .iter()
+                    .any(|(pending_atom, _)| *pending_atom == atom)
+            {
+                let pending_reply = self.display.send_request(&requests::GetAtomName { atom })?;
+                pending.push((atom, pending_reply));
+            }
+        }
+        self.display.flush()?;
+
+        for (atom, pending_reply) in pending {
+            let r = self.display.await_pending_reply(pending_reply)?.unwrap();
+            self.insert_atom(r.name, atom);
+        }
+
+        Ok(atoms
+            .iter()
+            .map(|atom| self.known_atoms_names[atom].clone())
+            .collect())
+    }
+
+    /// Atoms known to this connection. Atoms are shared by all clients on the server, so these can
+    /// be passed to other libraries connected to the same server to avoid interning them again.
+    pub fn known_atoms(&self) -> impl Iterator<Item = (&String8, AtomId)> {
+        self.known_atoms_ids
+            .iter()
+            .map(|(name, atom)| (name, *atom))
+    }
+
+    /// Add atoms interned elsewhere, e.g. by another library connected to the same server, to the
+    /// cache. Atoms must come from the same server as this connection.
+    pub fn import_atoms(&mut self, atoms: impl IntoIterator<Item = (String8, AtomId)>) {
+        for (name, atom) in atoms {
+            self.insert_atom(name, atom);
+        }
+    }
+
     pub fn get_atom_id(&mut self, atom_name: String8) -> Result<AtomId, Error> {
         if let Some(atom_id) = self.known_atoms_ids.get(&atom_name) {
             return Ok(*atom_id);
@@ -137,7 +190,9 @@ impl X11Connection {
         Ok(r.atom)
     }
 
+    /// Flush pending requests, including ones freeing dropped [`owned`] resources
     pub fn flush(&mut self) -> Result<(), Error> {
+        self.release_dropped()?;
         self.display.flush()
     }
 
@@ -157,31 +212,93 @@ impl X11Connection {
         .unwrap())
     }
 
+    /// Pointer position relative to the root and to `window`
+    pub fn query_pointer(&mut self, window: WindowId) -> Result<replies::QueryPointer, Error> {
+        Ok(request_blocking!(self.display, requests::QueryPointer { window })?.unwrap())
+    }
+
     pub fn query_tree(&mut self, window: WindowId) -> Result<replies::QueryTree, Error> {
         Ok(request_blocking!(self.display, requests::QueryTree { window })?.unwrap())
     }
 
-    pub fn get_wm_protocols(&mut self, window: WindowId) -> Result<Vec<AtomId>, Error> {
-        let wm_protocols = self.get_atom_id(String8::from_str("WM_PROTOCOLS").unwrap())?;
-        let props = request_blocking!(
+    /// Names of up to `max_names` core fonts matching `pattern`. Aliases, e.g. `fixed`, and other
+    /// names not in XLFD form are listed separately.
+    pub fn list_fonts(
+        &mut self,
+        pattern: &fonts::XlfdPattern,
+        max_names: u16,
+    ) -> Result<fonts::FontList, Error> {
+        let reply = request_blocking!(
+            self.display,
+            requests::ListFonts {
+                max_names,
+                pattern: pattern.to_pattern(),
+            }
+        )?
+        .unwrap();
+
+        Ok(fonts::FontList::from_names(
+            reply.names.strings.iter().map(Vec::as_slice),
+        ))
+    }
+
+    /// Pointer motion history of `window` since `start` as kept by the server. Size of the server's
+    /// motion buffer is limited, and may be zero, so history can be shorter than requested.
+    pub fn get_motion_history(
+        &mut self,
+        window: WindowId,
+        start: Timestamp,
+    ) -> Result<replies::GetMotionEvents, Error> {
+        Ok(request_blocking!(
+            self.display,
+            requests::GetMotionEvents {
+                window,
+                start,
+                stop: Timestamp::CurrentTime,
+            }
+        )?
+        .unwrap())
+    }
+
+    /// Read up to `long_length` units of 4 bytes of `property` of `window` of `type_`.
+    /// [`PropertyValue::Missing`] also if the window is already gone.
+    pub fn get_property(
+        &mut self,
+        window: WindowId,
+        property: AtomId,
+        type_: AtomId,
+        long_length: u32,
+    ) -> Result<PropertyValue, Error> {
+        let Ok(reply) = request_blocking!(
             self.display,
             requests::GetProperty {
                 delete: false,
                 window,
-                property: wm_protocols,
-                type_: AtomId::ATOM,
-                long_offset: 0, // Xlib uses these magic values
-                long_length: 1000000,
+                property,
+                type_,
+                long_offset: 0,
+                long_length,
             }
         )?
-        .unwrap();
+        else {
+            return Ok(PropertyValue::Missing);
+        };
+
+        Ok(reply.into_value(type_))
+    }
 
-        if props.format != 32 || props.type_ != AtomId::ATOM {
+    pub fn get_wm_protocols(&mut self, window: WindowId) -> Result<Vec<AtomId>, Error> {
+        let wm_protocols = self.get_atom_id(String8::from_str("WM_PROTOCOLS").unwrap())?;
+        // Xlib uses this magic value
+        let PropertyValue::Value {
+            format: 32, value, ..
+        } = self.get_property(window, wm_protocols, AtomId::ATOM, 1000000)?
+        else {
             return Ok(Vec::new());
-        }
+        };
 
-        let mut res = Vec::with_capacity(props.value.len() / 4);
-        for raw_atom_id in props.value.windows(4) {
+        let mut res = Vec::with_capacity(value.len() / 4);
+        for raw_atom_id in value.windows(4) {
             res.push(AtomId::unchecked_from(u32::from_le_bytes([
                 raw_atom_id[0],
                 raw_atom_id[1],
@@ -193,7 +310,9 @@ impl X11Connection {
         Ok(res)
     }
 
-    pub fn kill_window(&mut self, window: WindowId) -> Result<(), Error> {
+    /// Ask the client to close `window` with `WM_DELETE_WINDOW` if it supports it, otherwise
+    /// close the client connection
+    pub fn kill_window(&mut self, window: WindowId) -> Result<WindowKill, Error> {
         let wm_delete_window = self.get_atom_id(String8::from_str("WM_DELETE_WINDOW").unwrap())?;
         let wm_protocols = self.get_atom_id(String8::from_str("WM_PROTOCOLS").unwrap())?;
 
@@ -211,57 +330,105 @@ impl X11Connection {
                 type_message: wm_protocols,
                 data: event_data,
             };
-            let raw_event: [u8; 32] = unsafe { std::mem::transmute(event) };
-
             let request = requests::SendEvent {
                 propagate: false,
                 destination: window,
                 event_mask: 0,
-                event: raw_event,
+                event: event.to_le_bytes(),
             };
             self.display_mut().send_request(&request)?;
+            Ok(WindowKill::DeleteRequested)
         } else {
-            self.display_mut().send_request(&requests::KillClient {
-                resource: window.into(),
-            })?;
+            self.kill_client(KillClientResource::from(window))?;
+            Ok(WindowKill::ClientKilled)
         }
+    }
 
-        Ok(())
+    /// Decide what happens to resources created by this client when the connection closes
+    pub fn set_close_down_mode(
+        &mut self,
+        mode: SetCloseDownModeMode,
+    ) -> Result<PendingReply<NoReply>, Error> {
+        self.display
+            .send_request(&requests::SetCloseDownMode { mode })
+    }
+
+    /// Forcibly close connection of the client owning `resource`
+    pub fn kill_client(
+        &mut self,
+        resource: KillClientResource,
+    ) -> Result<PendingReply<NoReply>, Error> {
+        self.display
+            .send_request(&requests::KillClient { resource })
     }
 
     pub fn get_wm_hints(&mut self, window: WindowId) -> Result<Option<WindowManagerHints>, Error> {
         const NUM_PROP_WMHINTS_ELEMENTS: usize = mem::size_of::<WindowManagerHints>() / 4;
 
-        let reply = request_blocking!(
-            self.display,
-            requests::GetProperty {
-                delete: false,
-                window,
-                property: AtomId::WM_HINTS,
-                type_: AtomId::WM_HINTS,
-                long_offset: 0,
-                long_length: NUM_PROP_WMHINTS_ELEMENTS as u32,
-            }
+        let PropertyValue::Value { value, .. } = self.get_property(
+            window,
+            AtomId::WM_HINTS,
+            AtomId::WM_HINTS,
+            NUM_PROP_WMHINTS_ELEMENTS as u32,
         )?
-        .unwrap();
-
-        if reply.type_ != AtomId::WM_HINTS {
+        else {
             return Ok(None);
-        }
+        };
 
-        assert_eq!(reply.length_of_value, NUM_PROP_WMHINTS_ELEMENTS as u32);
+        assert_eq!(value.len(), NUM_PROP_WMHINTS_ELEMENTS * 4);
 
-        let raw: [u8; NUM_PROP_WMHINTS_ELEMENTS * 4] = reply.value.try_into().unwrap();
-        let raw: [u32; NUM_PROP_WMHINTS_ELEMENTS] = unsafe { mem::transmute(raw) };
+        let raw = value
+            .chunks_exact(4)
+            .map(|value| u32::from_le_bytes([value[0], value[1], value[2], value[3]]))
+            .collect::<Vec<_>>();
 
         // Check if bool invariant holds
         assert!(raw[1] == 0 || raw[1] == 1);
 
-        let hints: WindowManagerHints = unsafe { mem::transmute(raw) };
-        Ok(Some(hints))
+        Ok(Some(WindowManagerHints {
+            flags: WindowManagerHintsFlags::from(raw[0]),
+            input: raw[1] == 1,
+            initial_state: raw[2] as i32,
+            icon_pixmap: OrNone::new(PixmapId::from(raw[3])),
+            icon_window: OrNone::new(WindowId::from(raw[4])),
+            icon_x: raw[5] as i32,
+            icon_y: raw[6] as i32,
+            icon_mask: OrNone::new(ResourceId::from(raw[7])),
+            window_group: raw[8],
+        }))
     }
 
-    pub fn set_supported(&mut self) -> Result<(), Error> {
+    /// Replace `property` of `window` with `data`, split into multiple requests if it does not fit
+    /// into the maximum request length. The first chunk replaces the property and the rest is
+    /// appended, so other clients may see it partially written.
+    pub fn change_property(
+        &mut self,
+        window: WindowId,
+        property: AtomId,
+        type_: AtomId,
+        format: ChangePropertyFormat,
+        data: &[u8],
+    ) -> Result<(), Error> {
+        let max_request_length = self.display.maximum_request_length();
+        for (idx, chunk) in property_chunks(data, max_request_length).enumerate() {
+            self.display.send_request(&requests::ChangeProperty {
+                mode: if idx == 0 {
+                    ChangePropertyMode::Replace
+                } else {
+                    ChangePropertyMode::Append
+                },
+                window,
+                property,
+                type_,
+                format,
+                data: chunk.to_vec(),
+            })?;
+        }
+
+        Ok(())
+    }
+
+    pub fn set_supported(&mut self, root: WindowId) -> Result<(), Error> {
         let net_supported = self.get_atom_id(String8::from_str("_NET_SUPPORTED").unwrap())?;
 
         let mut data = Vec::new();
@@ -269,8 +436,16 @@ impl X11Connection {
         for atom_name in &[
             "_NET_SUPPORTED",
             "_NET_SUPPORTING_WM_CHECK",
+            "_NET_WM_NAME",
             "_NET_ACTIVE_WINDOW",
             "_NET_WM_STATE",
+            "_NET_WM_STATE_FULLSCREEN",
+            "_NET_CLIENT_LIST",
+            "_NET_SHOWING_DESKTOP",
+            "_NET_NUMBER_OF_DESKTOPS",
+            "_NET_DESKTOP_NAMES",
+            "_NET_CURRENT_DESKTOP",
+            "_NET_WM_DESKTOP",
         ] {
             data.extend(
                 self.get_atom_id(String8::from_str(atom_name).unwrap())?
@@ -278,19 +453,245 @@ impl X11Connection {
             );
         }
 
-        let _request = requests::ChangeProperty {
+        self.display.send_request(&requests::ChangeProperty {
             mode: ChangePropertyMode::Replace,
-            window: self.default_screen().root, // TODO: take as parameter
+            window: root,
             property: net_supported,
             type_: AtomId::ATOM,
             format: ChangePropertyFormat::Format32,
             data,
+        })?;
+
+        Ok(())
+    }
+
+    /// Create EWMH `_NET_SUPPORTING_WM_CHECK` window so clients and session tools can detect
+    /// a running window manager and its `name`. Returned window must live as long as the window
+    /// manager is running.
+    pub fn set_supporting_wm_check(
+        &mut self,
+        root: WindowId,
+        name: &str,
+    ) -> Result<WindowId, Error> {
+        let net_supporting_wm_check =
+            self.get_atom_id(String8::from_str("_NET_SUPPORTING_WM_CHECK").unwrap())?;
+        let net_wm_name = self.get_atom_id(String8::from_str("_NET_WM_NAME").unwrap())?;
+        let utf8_string = self.get_atom_id(String8::from_str("UTF8_STRING").unwrap())?;
+
+        let check_window = WindowId::from_resource(self.display.id_allocator().allocate_id());
+        self.display.send_request(&requests::CreateWindow {
+            depth: 0,
+            wid: check_window,
+            parent: root,
+            x: -1,
+            y: -1,
+            width: 1,
+            height: 1,
+            border_width: 0,
+            window_class: WindowClass::InputOnly,
+            visual: WindowVisual::CopyFromParent,
+            attributes: WindowCreationAttributes::input_only_overlay(),
+        })?;
+        panic_hook::destroy_on_panic(check_window);
+
+        for window in [root, check_window] {
+            self.display.send_request(&requests::ChangeProperty {
+                mode: ChangePropertyMode::Replace,
+                window,
+                property: net_supporting_wm_check,
+                type_: AtomId::WINDOW,
+                format: ChangePropertyFormat::Format32,
+                data: check_window.to_le_bytes().to_vec(),
+            })?;
+        }
+
+        self.display.send_request(&requests::ChangeProperty {
+            mode: ChangePropertyMode::Replace,
+            window: check_window,
+            property: net_wm_name,
+            type_: utf8_string,
+            format: ChangePropertyFormat::Format8,
+            data: name.as_bytes().to_vec(),
+        })?;
+
+        Ok(check_window)
+    }
+
+    /// Host the client owning `window` runs on from `WM_CLIENT_MACHINE`
+    pub fn get_wm_client_machine(&mut self, window: WindowId) -> Result<Option<String>, Error> {
+        match self.get_property(window, AtomId::WM_CLIENT_MACHINE, AtomId::STRING, 64)? {
+            PropertyValue::Value {
+                format: 8, value, ..
+            } if !value.is_empty() => Ok(Some(String::from_utf8_lossy(&value).into_owned())),
+            _ => Ok(None),
+        }
+    }
+
+    /// Title of `window` from `_NET_WM_NAME`, falling back to `WM_NAME`. `None` if neither is set
+    /// or the window is already gone.
+    pub fn get_wm_name(&mut self, window: WindowId) -> Result<Option<String>, Error> {
+        let net_wm_name = self.get_atom_id(String8::from_str("_NET_WM_NAME").unwrap())?;
+        let utf8_string = self.get_atom_id(String8::from_str("UTF8_STRING").unwrap())?;
+
+        for (property, type_) in [
+            (net_wm_name, utf8_string),
+            (AtomId::WM_NAME, AtomId::STRING),
+        ] {
+            if let PropertyValue::Value {
+                format: 8, value, ..
+            } = self.get_property(window, property, type_, 256)?
+            {
+                return Ok(Some(String::from_utf8_lossy(&value).into_owned()));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Process id of the client owning `window` from `_NET_WM_PID`. Note that the process may run
+    /// on a different machine.
+    pub fn get_wm_pid(&mut self, window: WindowId) -> Result<Option<u32>, Error> {
+        let net_wm_pid = self.get_atom_id(String8::from_str("_NET_WM_PID").unwrap())?;
+        let value = self.get_property(window, net_wm_pid, AtomId::CARDINAL, 1)?;
+        Ok(property_u32(&value))
+    }
+
+    /// States of `window` from EWMH `_NET_WM_STATE`, e.g. `_NET_WM_STATE_FULLSCREEN`, set by
+    /// clients before mapping and by the window manager after
+    pub fn get_net_wm_state(&mut self, window: WindowId) -> Result<Vec<AtomId>, Error> {
+        let net_wm_state = self.get_atom_id(String8::from_str("_NET_WM_STATE").unwrap())?;
+        let PropertyValue::Value {
+            format: 32, value, ..
+        } = self.get_property(window, net_wm_state, AtomId::ATOM, 64)?
+        else {
+            return Ok(Vec::new());
         };
+        Ok(value
+            .chunks_exact(4)
+            .map(|raw| AtomId::unchecked_from(u32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]])))
+            .collect())
+    }
 
-        todo!();
+    /// Replace EWMH `_NET_WM_STATE` of `window` with `states`
+    pub fn set_net_wm_state(&mut self, window: WindowId, states: &[AtomId]) -> Result<(), Error> {
+        let net_wm_state = self.get_atom_id(String8::from_str("_NET_WM_STATE").unwrap())?;
+        self.display.send_request(&requests::ChangeProperty {
+            mode: ChangePropertyMode::Replace,
+            window,
+            property: net_wm_state,
+            type_: AtomId::ATOM,
+            format: ChangePropertyFormat::Format32,
+            data: states
+                .iter()
+                .flat_map(|state| state.to_le_bytes())
+                .collect(),
+        })?;
+        Ok(())
+    }
+
+    /// Window that `window` is a dialog or other transient window for, from `WM_TRANSIENT_FOR`
+    pub fn get_transient_for(&mut self, window: WindowId) -> Result<Option<WindowId>, Error> {
+        let value = self.get_property(window, AtomId::WM_TRANSIENT_FOR, AtomId::WINDOW, 1)?;
+        Ok(property_u32(&value)
+            .filter(|id| *id != 0)
+            .map(WindowId::from))
+    }
+
+    /// Whether `window` has urgency flag set in `WM_HINTS`. Unlike [`X11Connection::get_wm_hints`]
+    /// it accepts truncated hints and returns `false` if the window is already gone.
+    pub fn get_wm_urgency(&mut self, window: WindowId) -> Result<bool, Error> {
+        const URGENCY_HINT: u32 = 1 << 8;
+
+        let value = self.get_property(window, AtomId::WM_HINTS, AtomId::WM_HINTS, 1)?;
+        Ok(property_u32(&value).is_some_and(|flags| flags & URGENCY_HINT != 0))
+    }
+
+    /// Size and position hints from `WM_NORMAL_HINTS`. Accepts both the current 18 element
+    /// format and the pre-ICCCM 15 element one, returns `None` if the window is gone or has no
+    /// hints.
+    pub fn get_wm_normal_hints(
+        &mut self,
+        window: WindowId,
+    ) -> Result<Option<WindowSizeHints>, Error> {
+        const NUM_PROP_SIZE_HINTS_ELEMENTS: u32 = 18;
+
+        let value = match self.get_property(
+            window,
+            AtomId::WM_NORMAL_HINTS,
+            AtomId::WM_SIZE_HINTS,
+            NUM_PROP_SIZE_HINTS_ELEMENTS,
+        )? {
+            PropertyValue::Value {
+                format: 32, value, ..
+            } if value.len() >= 15 * 4 => value,
+            _ => return Ok(None),
+        };
+
+        let raw: Vec<u32> = value
+            .chunks_exact(4)
+            .map(|v| u32::from_le_bytes([v[0], v[1], v[2], v[3]]))
+            .collect();
+        let pair = |idx: usize| (raw[idx] as i32, raw[idx + 1] as i32);
+
+        // Elements 1 to 4 are obsolete x, y, width and height
+        Ok(Some(WindowSizeHints {
+            flags: WindowManagerHintsFlags::from(raw[0]),
+            min_size: pair(5),
+            max_size: pair(7),
+            resize_increments: pair(9),
+            min_aspect: pair(11),
+            max_aspect: pair(13),
+            base_size: raw.get(15..17).map_or((0, 0), |_| pair(15)),
+            window_gravity: raw.get(17).copied().unwrap_or(0),
+        }))
+    }
+
+    /// Hint compositor to unredirect `window` with `_NET_WM_BYPASS_COMPOSITOR`,
+    /// `false` removes the hint
+    pub fn set_bypass_compositor(&mut self, window: WindowId, bypass: bool) -> Result<(), Error> {
+        let net_wm_bypass_compositor =
+            self.get_atom_id(String8::from_str("_NET_WM_BYPASS_COMPOSITOR").unwrap())?;
+
+        if bypass {
+            self.display.send_request(&requests::ChangeProperty {
+                mode: ChangePropertyMode::Replace,
+                window,
+                property: net_wm_bypass_compositor,
+                type_: AtomId::CARDINAL,
+                format: ChangePropertyFormat::Format32,
+                data: 1u32.to_le_bytes().to_vec(),
+            })?;
+        } else {
+            self.display.send_request(&requests::DeleteProperty {
+                window,
+                property: net_wm_bypass_compositor,
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Single 32 bit item of a property, `None` if it is missing or has other format or length
+fn property_u32(value: &PropertyValue) -> Option<u32> {
+    match value {
+        PropertyValue::Value {
+            format: 32, value, ..
+        } => Some(u32::from_le_bytes(value.as_slice().try_into().ok()?)),
+        _ => None,
     }
 }
 
+/// Split property `data` into chunks fitting into ChangeProperty requests of at most
+/// `max_request_length` units. Chunks are multiples of 4 bytes so items of any format are not
+/// split, and there is always at least one chunk so empty data still replaces the property.
+fn property_chunks(data: &[u8], max_request_length: u16) -> impl Iterator<Item = &[u8]> {
+    // ChangeProperty header is 6 units of 4 bytes
+    let chunk_len = (max_request_length as usize).saturating_sub(6).max(1) * 4;
+    let empty = data.is_empty().then_some(data);
+    data.chunks(chunk_len).chain(empty)
+}
+
 bitmask! {
     #[repr(u32)]
     bitmask WindowManagerHintsFlags {
@@ -326,6 +727,29 @@ bitmask! {
     }
 }
 
+/// `WM_NORMAL_HINTS` of a window, sizes are `(width, height)` and aspects are
+/// `(numerator, denominator)`. Only fields with the matching flag set are meaningful.
+#[derive(Debug, Clone, Copy)]
+pub struct WindowSizeHints {
+    pub flags: WindowManagerHintsFlags,
+    pub min_size: (i32, i32),
+    pub max_size: (i32, i32),
+    pub resize_increments: (i32, i32),
+    pub min_aspect: (i32, i32),
+    pub max_aspect: (i32, i32),
+    pub base_size: (i32, i32),
+    pub window_gravity: u32,
+}
+
+/// How [`X11Connection::kill_window`] closed the window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowKill {
+    /// Client was asked to close the window and may ignore it
+    DeleteRequested,
+    /// Connection of the client was closed with `KillClient`
+    ClientKilled,
+}
+
 #[repr(C)]
 #[derive(Debug)]
 pub struct WindowManagerHints {
@@ -339,3 +763,16 @@ pub struct WindowManagerHints {
     pub icon_mask: OrNone<ResourceId>,
     pub window_group: u32,
 }
+
+#[test]
+fn change_property_chunks() {
+    let data = (0..40).collect::<Vec<u8>>();
+    let chunks = property_chunks(&data, 6 + 4).collect::<Vec<_>>();
+    assert_eq!(chunks, [&data[0..16], &data[16..32], &data[32..40]]);
+
+    assert_eq!(property_chunks(&data, u16::MAX).count(), 1);
+    assert_eq!(
+        property_chunks(&[], u16::MAX).collect::<Vec<_>>(),
+        [&[] as &[u8]]
+    );
+}
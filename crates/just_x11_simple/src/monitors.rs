@@ -0,0 +1,224 @@
+//! Monitors of a screen, from RandR 1.5 when available and from the core protocol otherwise.
+//!
+//! Xinerama is not implemented in `just_x11` yet, servers without RandR 1.5 are treated as having
+//! a single monitor covering whole screen.
+
+use crate::X11Connection;
+use just_x11::{
+    error::Error,
+    events::{EventType, SomeEvent},
+    extensions::randr,
+    requests, Rectangle, WindowId,
+};
+
+/// DPI at which [`Monitor::scale`] is 1.0
+const BASE_DPI: f32 = 96.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Monitor {
+    pub name: String,
+    /// Position relative to the root window
+    pub geometry: Rectangle,
+    pub primary: bool,
+    /// Suggested UI scale from physical size of the monitor, rounded to quarters
+    pub scale: f32,
+}
+
+impl Monitor {
+    fn scale_from_physical_size(width_in_pixels: u16, width_in_millimeters: u32) -> f32 {
+        if width_in_millimeters == 0 {
+            return 1.0;
+        }
+        let dpi = width_in_pixels as f32 * 25.4 / width_in_millimeters as f32;
+        let scale = (dpi / BASE_DPI * 4.0).round() / 4.0;
+        if scale > 0.0 {
+            scale
+        } else {
+            1.0
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Source {
+    RandR { major_opcode: u8, first_event: u8 },
+    Core,
+}
+
+#[derive(Debug, Clone)]
+pub struct Monitors {
+    screen: usize,
+    root: WindowId,
+    source: Source,
+    monitors: Vec<Monitor>,
+}
+
+impl Monitors {
+    /// Query monitors of `screen` and select events needed by [`Self::handle_event`]
+    pub fn new(conn: &mut X11Connection, screen: usize) -> Result<Self, Error> {
+        let root = conn.display().screens()[screen].root;
+        let source = Self::query_source(conn)?;
+
+        match source {
+            Source::RandR { major_opcode, .. } => {
+                conn.display_mut().send_extension_request(
+                    &randr::requests::SelectInput {
+                        window: root,
+                        enable: randr::SelectMask::SCREEN_CHANGE_NOTIFY_MASK
+                            | randr::SelectMask::CRTC_CHANGE_NOTIFY_MASK
+                            | randr::SelectMask::OUTPUT_CHANGE_NOTIFY_MASK,
+                    },
+                    major_opcode,
+                )?;
+            }
+            Source::Core => {
+                // Must not replace the mask of a window manager selecting on the root
+                let attributes = request_blocking!(
+                    conn.display_mut(),
+                    requests::GetWindowAttributes { window: root }
+                )?
+                .unwrap();
+                let events = EventType::from(attributes.your_even_masks);
+                conn.select_input(root, events | EventType::STRUCTURE_NOTIFY)?;
+            }
+        }
+
+        let mut monitors = Self {
+            screen,
+            root,
+            source,
+            monitors: Vec::new(),
+        };
+        monitors.refresh(conn)?;
+        Ok(monitors)
+    }
+
+    fn query_source(conn: &mut X11Connection) -> Result<Source, Error> {
+        match randr::negotiate(conn.display_mut())? {
+            Some(randr) if randr.at_least(1, 5) => Ok(Source::RandR {
+                major_opcode: randr.major_opcode,
+                first_event: randr.first_event,
+            }),
+            _ => Ok(Source::Core),
+        }
+    }
+
+    pub fn monitors(&self) -> &[Monitor] {
+        &self.monitors
+    }
+
+    /// Primary monitor, or the first one if none is marked as primary
+    pub fn primary(&self) -> Option<&Monitor> {
+        self.monitors
+            .iter()
+            .find(|monitor| monitor.primary)
+            .or_else(|| self.monitors.first())
+    }
+
+    /// Query monitors again, returns `true` if they changed
+    pub fn refresh(&mut self, conn: &mut X11Connection) -> Result<bool, Error> {
+        let monitors = match self.source {
+            Source::RandR { major_opcode, .. } => {
+                let display = conn.display_mut();
+                let pending = display.send_extension_request(
+                    &randr::requests::GetMonitors {
+                        window: self.root,
+                        get_active: true,
+                    },
+                    major_opcode,
+                )?;
+                display.flush()?;
+                let reply = display.await_pending_reply(pending)?.unwrap();
+
+                let names = conn.get_atom_names(
+                    &reply
+                        .monitors
+                        .iter()
+                        .map(|monitor| monitor.name)
+                        .collect::<Vec<_>>(),
+                )?;
+                reply
+                    .monitors
+                    .into_iter()
+                    .zip(names)
+                    .map(|(monitor, name)| Monitor {
+                        name: name.to_string(),
+                        geometry: Rectangle {
+                            x: monitor.x,
+                            y: monitor.y,
+                            width: monitor.width_in_pixels,
+                            height: monitor.height_in_pixels,
+                        },
+                        primary: monitor.primary,
+                        scale: Monitor::scale_from_physical_size(
+                            monitor.width_in_pixels,
+                            monitor.width_in_millimeters,
+                        ),
+                    })
+                    .collect()
+            }
+            Source::Core => {
+                let screen = &conn.display().screens()[self.screen];
+                vec![Monitor {
+                    name: String::from("default"),
+                    geometry: Rectangle {
+                        x: 0,
+                        y: 0,
+                        width: screen.width_in_pixels,
+                        height: screen.height_in_pixels,
+                    },
+                    primary: true,
+                    scale: Monitor::scale_from_physical_size(
+                        screen.width_in_pixels,
+                        screen.width_in_millimeters as u32,
+                    ),
+                }]
+            }
+        };
+
+        let changed = monitors != self.monitors;
+        self.monitors = monitors;
+        Ok(changed)
+    }
+
+    /// Refresh monitors if `event` announces a change, returns `true` if they changed
+    pub fn handle_event(
+        &mut self,
+        conn: &mut X11Connection,
+        event: &SomeEvent,
+    ) -> Result<bool, Error> {
+        match (self.source, event) {
+            (Source::RandR { first_event, .. }, SomeEvent::UnknownEvent(event)) => {
+                // ScreenChangeNotify and RRNotify, ignoring the sent bit
+                let code = event.raw[0] & 0x7f;
+                if code == first_event || code == first_event + 1 {
+                    self.refresh(conn)
+                } else {
+                    Ok(false)
+                }
+            }
+            (Source::Core, SomeEvent::ConfigureNotify(event)) if event.window == self.root => {
+                // Screen size from the connection setup is not updated on resize
+                let geometry = Rectangle {
+                    x: 0,
+                    y: 0,
+                    width: event.width,
+                    height: event.height,
+                };
+                let changed = self.monitors[0].geometry != geometry;
+                self.monitors[0].geometry = geometry;
+                Ok(changed)
+            }
+            _ => Ok(false),
+        }
+    }
+}
+
+#[test]
+fn monitor_scale() {
+    assert_eq!(Monitor::scale_from_physical_size(1920, 0), 1.0);
+    // 24" 1080p
+    assert_eq!(Monitor::scale_from_physical_size(1920, 531), 1.0);
+    // 27" 4k
+    assert_eq!(Monitor::scale_from_physical_size(3840, 597), 1.75);
+}
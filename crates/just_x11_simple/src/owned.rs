@@ -0,0 +1,178 @@
+//! Windows, pixmaps and graphics contexts freed when dropped
+//!
+//! Drop can't send requests without the connection, so dropped resources are queued and the
+//! `DestroyWindow`, `FreePixmap` and `FreeGC` requests are sent by the next
+//! [`X11Connection::flush`] or [`X11Connection::release_dropped`], or when the connection is
+//! dropped. Resources are freed by the server anyway when the connection closes, unless the
+//! close-down mode retains them, but long running clients would leak them until then.
+
+use crate::X11Connection;
+use just_x11::{
+    error::Error,
+    requests::{self, GContextSettings},
+    Drawable, GContextId, PixmapId, WindowId, XDisplay,
+};
+use std::{cell::RefCell, rc::Rc};
+
+/// Owned window, destroyed when dropped
+pub type OwnedWindow = Owned<WindowId>;
+/// Owned pixmap, freed when dropped
+pub type OwnedPixmap = Owned<PixmapId>;
+/// Owned graphics context, freed when dropped
+pub type OwnedGc = Owned<GContextId>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Resource {
+    Window(WindowId),
+    Pixmap(PixmapId),
+    Gc(GContextId),
+}
+
+impl Resource {
+    fn free(self, display: &mut XDisplay) -> Result<(), Error> {
+        match self {
+            Resource::Window(window) => display.send_request(&requests::DestroyWindow { window }),
+            Resource::Pixmap(pixmap) => display.send_request(&requests::FreePixmap { pixmap }),
+            Resource::Gc(gc) => display.send_request(&requests::FreeGC { gc }),
+        }?;
+        Ok(())
+    }
+}
+
+/// Resources dropped since the last flush, shared by the connection and its owned resources
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DroppedResources(Rc<RefCell<Vec<Resource>>>);
+
+impl DroppedResources {
+    fn take(&self) -> Vec<Resource> {
+        std::mem::take(&mut *self.0.borrow_mut())
+    }
+}
+
+/// Resource of type `T` freed when dropped, see [`crate::owned`]
+#[derive(Debug)]
+#[must_use = "resource is freed immediately if the owner is not kept"]
+pub struct Owned<T> {
+    id: T,
+    resource: Resource,
+    dropped: DroppedResources,
+}
+
+impl<T: Copy> Owned<T> {
+    fn new(id: T, resource: Resource, dropped: &DroppedResources) -> Self {
+        Self {
+            id,
+            resource,
+            dropped: dropped.clone(),
+        }
+    }
+
+    pub fn id(&self) -> T {
+        self.id
+    }
+
+    /// Stop owning the resource, the caller becomes responsible for freeing it
+    pub fn into_raw(self) -> T {
+        let id = self.id;
+        std::mem::forget(self);
+        id
+    }
+
+    /// Never free the resource, e.g. a pixmap that has to outlive the connection with
+    /// `RetainPermanent` close-down mode
+    pub fn leak(self) {
+        self.into_raw();
+    }
+}
+
+impl<T> Drop for Owned<T> {
+    fn drop(&mut self) {
+        self.dropped.0.borrow_mut().push(self.resource);
+    }
+}
+
+impl X11Connection {
+    /// Take ownership of `window` created elsewhere
+    pub fn own_window(&self, window: WindowId) -> OwnedWindow {
+        Owned::new(window, Resource::Window(window), &self.dropped)
+    }
+
+    /// Take ownership of `pixmap` created elsewhere
+    pub fn own_pixmap(&self, pixmap: PixmapId) -> OwnedPixmap {
+        Owned::new(pixmap, Resource::Pixmap(pixmap), &self.dropped)
+    }
+
+    /// Take ownership of `gc` created elsewhere
+    pub fn own_gc(&self, gc: GContextId) -> OwnedGc {
+        Owned::new(gc, Resource::Gc(gc), &self.dropped)
+    }
+
+    pub fn create_pixmap(
+        &mut self,
+        depth: u8,
+        drawable: Drawable,
+        width: u16,
+        height: u16,
+    ) -> Result<OwnedPixmap, Error> {
+        let pixmap = PixmapId::from_resource(self.display.id_allocator().allocate_id());
+        self.display.send_request(&requests::CreatePixmap {
+            depth,
+            pid: pixmap,
+            drawable,
+            width,
+            height,
+        })?;
+        Ok(self.own_pixmap(pixmap))
+    }
+
+    pub fn create_gc(
+        &mut self,
+        drawable: Drawable,
+        values: GContextSettings,
+    ) -> Result<OwnedGc, Error> {
+        let gc = GContextId::from_resource(self.display.id_allocator().allocate_id());
+        self.display.send_request(&requests::CreateGC {
+            cid: gc,
+            drawable,
+            values,
+        })?;
+        Ok(self.own_gc(gc))
+    }
+
+    /// Send requests freeing resources dropped since the last call, without flushing
+    pub fn release_dropped(&mut self) -> Result<(), Error> {
+        for resource in self.dropped.take() {
+            resource.free(&mut self.display)?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for X11Connection {
+    fn drop(&mut self) {
+        let _ = self.release_dropped();
+        let _ = self.display.flush();
+    }
+}
+
+#[test]
+fn dropped_resources_are_queued() {
+    let dropped = DroppedResources::default();
+    let window = WindowId::from(1);
+    let pixmap = PixmapId::from(2);
+    let gc = GContextId::from(3);
+
+    let owned_window = Owned::new(window, Resource::Window(window), &dropped);
+    let owned_pixmap = Owned::new(pixmap, Resource::Pixmap(pixmap), &dropped);
+    let owned_gc = Owned::new(gc, Resource::Gc(gc), &dropped);
+    assert_eq!(owned_gc.id(), gc);
+    drop(owned_gc);
+    drop(owned_window);
+    assert_eq!(dropped.take(), [Resource::Gc(gc), Resource::Window(window)]);
+    assert!(dropped.take().is_empty());
+
+    // Escape hatches don't free anything
+    assert_eq!(owned_pixmap.into_raw(), pixmap);
+    Owned::new(window, Resource::Window(window), &dropped).leak();
+    assert!(dropped.take().is_empty());
+}
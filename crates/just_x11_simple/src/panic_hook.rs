@@ -0,0 +1,129 @@
+//! Panic hook that leaves the session usable when the program crashes while holding grabs
+//!
+//! On panic the hook ungrabs pointer and keyboard, gives focus back to `PointerRoot` and destroys
+//! windows registered with [`destroy_on_panic`]. Only panics of the thread that installed the hook
+//! run the cleanup, other threads don't own the connection. Requests are written directly to the
+//! socket after the last whole request that was flushed, requests still in the buffer of the
+//! connection are lost. The server processes them even if the process exits right after, but the
+//! connection is out of sync afterwards and should not be used anymore.
+
+use crate::X11Connection;
+use just_x11::{
+    error::Error,
+    requests::{self, RevertTo, Timestamp},
+    ToLeBytes, WindowId,
+};
+use std::{
+    io::Write,
+    os::unix::net::UnixStream,
+    panic,
+    sync::Mutex,
+    thread::{self, ThreadId},
+    time::Duration,
+};
+
+/// `PointerRoot` value of `focus` in `SetInputFocus`
+const POINTER_ROOT: u32 = 1;
+
+struct Cleanup {
+    socket: Option<UnixStream>,
+    /// Thread that installed the hook
+    owner: Option<ThreadId>,
+    windows: Vec<WindowId>,
+}
+
+static CLEANUP: Mutex<Cleanup> = Mutex::new(Cleanup {
+    socket: None,
+    owner: None,
+    windows: Vec::new(),
+});
+
+/// Destroy `window` if the program panics after [`X11Connection::install_panic_hook`]. Meant for
+/// override redirect windows that would otherwise stay on the screen.
+pub fn destroy_on_panic(window: WindowId) {
+    if let Ok(mut cleanup) = CLEANUP.lock() {
+        cleanup.windows.push(window);
+    }
+}
+
+/// Stop destroying `window` on panic, once it was destroyed by the program
+pub fn forget_on_panic(window: WindowId) {
+    if let Ok(mut cleanup) = CLEANUP.lock() {
+        cleanup.windows.retain(|&registered| registered != window);
+    }
+}
+
+impl X11Connection {
+    /// Run cleanup described in [`crate::panic_hook`] before the previously installed hook.
+    /// Calling it again switches the hook to this connection and the calling thread.
+    pub fn install_panic_hook(&mut self) -> Result<(), Error> {
+        let socket = self.display.try_clone_socket()?;
+        let mut cleanup = CLEANUP.lock().unwrap();
+        let installed = cleanup.socket.replace(socket).is_some();
+        cleanup.owner = Some(thread::current().id());
+        drop(cleanup);
+
+        if !installed {
+            let previous_hook = panic::take_hook();
+            panic::set_hook(Box::new(move |info| {
+                if owns_connection() {
+                    run_cleanup();
+                }
+                previous_hook(info);
+            }));
+        }
+
+        Ok(())
+    }
+}
+
+fn owns_connection() -> bool {
+    let cleanup = CLEANUP
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    cleanup.owner == Some(thread::current().id())
+}
+
+fn run_cleanup() {
+    // Panic while holding the lock must not prevent the cleanup
+    let mut cleanup = CLEANUP
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let Some(mut socket) = cleanup.socket.take() else {
+        return;
+    };
+
+    let mut buf = Vec::new();
+    let _ = requests::UngrabPointer {
+        time: Timestamp::CurrentTime,
+    }
+    .to_le_bytes(&mut buf);
+    // CurrentTime
+    let _ = requests::UngrabKeyboard { time: 0 }.to_le_bytes(&mut buf);
+    let _ = requests::SetInputFocus {
+        revert_to: RevertTo::PointerRoot,
+        focus: WindowId::from(POINTER_ROOT),
+        time: Timestamp::CurrentTime,
+    }
+    .to_le_bytes(&mut buf);
+    for window in cleanup.windows.drain(..) {
+        let _ = requests::DestroyWindow { window }.to_le_bytes(&mut buf);
+    }
+
+    // Socket is non-blocking, don't hang forever if the server stopped reading
+    let _ = socket.set_nonblocking(false);
+    let _ = socket.set_write_timeout(Some(Duration::from_secs(1)));
+    let _ = socket.write_all(&buf);
+    let _ = socket.flush();
+}
+
+#[test]
+fn cleanup_only_on_owning_thread() {
+    CLEANUP.lock().unwrap().owner = Some(thread::current().id());
+    assert!(owns_connection());
+    assert!(!thread::spawn(owns_connection).join().unwrap());
+
+    destroy_on_panic(WindowId::from(0x400001));
+    forget_on_panic(WindowId::from(0x400001));
+    assert!(CLEANUP.lock().unwrap().windows.is_empty());
+}
@@ -58,6 +58,7 @@ pub struct Args {
     pub list_active_monitors: bool,
     pub monitors: Vec<Monitor>,
     pub monitorit: bool,
+    pub json: bool,
 }
 
 impl Args {
@@ -153,6 +154,7 @@ impl Args {
             list_active_monitors: false,
             monitors: Vec::new(),
             monitorit: false,
+            json: false,
             modeit: false,
         };
 
@@ -250,6 +252,10 @@ impl Args {
                 "--query" | "-q" => {
                     args.query = true;
                 }
+                "--json" => {
+                    args.json = true;
+                    args.action_requested = true;
+                }
                 "--orientation" | "-o" => {
                     let dirind = get_next_arg!();
                     args.rot = Some(parse!(Rotation, dirind));
@@ -629,7 +635,7 @@ impl Args {
                             monitor.width = u32::from_str(width).ok()?;
 
                             let (mmwidth, geom) = geom.split_once('x')?;
-                            monitor.mmheight = u32::from_str(mmwidth).ok()?;
+                            monitor.mmwidth = u32::from_str(mmwidth).ok()?;
 
                             let (height, geom) = geom.split_once('/')?;
                             monitor.height = u32::from_str(height).ok()?;
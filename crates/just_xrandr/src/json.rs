@@ -0,0 +1,250 @@
+//! Screen configuration model printed by `--json`
+
+use just_x11::{
+    error::Error,
+    extensions::randr::{self, replies::OutputId, CrtcId, ModeFlag},
+    impl_to_json_struct, requests, XDisplay,
+};
+
+pub struct ScreenModel {
+    screen: u32,
+    width: u16,
+    height: u16,
+    min_width: u16,
+    min_height: u16,
+    max_width: u16,
+    max_height: u16,
+    outputs: Vec<OutputModel>,
+    crtcs: Vec<CrtcModel>,
+    modes: Vec<ModeModel>,
+    monitors: Vec<MonitorModel>,
+}
+
+impl_to_json_struct!(ScreenModel {
+    screen,
+    width,
+    height,
+    min_width,
+    min_height,
+    max_width,
+    max_height,
+    outputs,
+    crtcs,
+    modes,
+    monitors,
+});
+
+struct OutputModel {
+    id: OutputId,
+    name: String,
+    connection: randr::Connection,
+    /// `None` if disabled
+    crtc: Option<CrtcId>,
+    width_in_millimeters: u32,
+    height_in_millimeters: u32,
+    /// Ids of supported modes
+    modes: Vec<u32>,
+    /// Number of modes from the start of `modes` that are preferred
+    preferred_modes: u16,
+}
+
+impl_to_json_struct!(OutputModel {
+    id,
+    name,
+    connection,
+    crtc,
+    width_in_millimeters,
+    height_in_millimeters,
+    modes,
+    preferred_modes,
+});
+
+struct CrtcModel {
+    id: CrtcId,
+    x: i16,
+    y: i16,
+    width: u16,
+    height: u16,
+    /// `None` if disabled
+    mode: Option<u32>,
+    rotation: u16,
+    outputs: Vec<OutputId>,
+}
+
+impl_to_json_struct!(CrtcModel {
+    id,
+    x,
+    y,
+    width,
+    height,
+    mode,
+    rotation,
+    outputs,
+});
+
+struct ModeModel {
+    id: u32,
+    name: String,
+    width: u16,
+    height: u16,
+    /// Vertical refresh rate in Hz, `None` if timings are unknown
+    refresh: Option<f32>,
+}
+
+impl_to_json_struct!(ModeModel {
+    id,
+    name,
+    width,
+    height,
+    refresh,
+});
+
+struct MonitorModel {
+    name: String,
+    primary: bool,
+    automatic: bool,
+    x: i16,
+    y: i16,
+    width: u16,
+    height: u16,
+    width_in_millimeters: u32,
+    height_in_millimeters: u32,
+    outputs: Vec<OutputId>,
+}
+
+impl_to_json_struct!(MonitorModel {
+    name,
+    primary,
+    automatic,
+    x,
+    y,
+    width,
+    height,
+    width_in_millimeters,
+    height_in_millimeters,
+    outputs,
+});
+
+impl ScreenModel {
+    pub fn query(display: &mut XDisplay, screen: u32, randr_opcode: u8) -> Result<Self, Error> {
+        macro_rules! send_randr_request {
+            ($request:expr) => {{
+                let pending = display.send_extension_request($request, randr_opcode)?;
+                display.flush()?;
+                display.await_pending_reply(pending)?.unwrap()
+            }};
+        }
+
+        let root = display.screens()[screen as usize].root;
+        let width = display.screens()[screen as usize].width_in_pixels;
+        let height = display.screens()[screen as usize].height_in_pixels;
+
+        let size_range = send_randr_request!(&randr::requests::GetScreenSizeRange { window: root });
+        let resources =
+            send_randr_request!(&randr::requests::GetScreenResourcesCurrent { window: root });
+
+        let mut outputs = Vec::with_capacity(resources.outputs.len());
+        for id in resources.outputs.iter().copied() {
+            let info = send_randr_request!(&randr::requests::GetOutputInfo {
+                output: id,
+                timestamp: resources.config_timestamp,
+            });
+            outputs.push(OutputModel {
+                id,
+                name: String::from_utf8_lossy(&info.name).into_owned(),
+                connection: info.connection,
+                crtc: (u32::from(info.crtc) != 0).then_some(info.crtc),
+                width_in_millimeters: info.width_in_millimeters,
+                height_in_millimeters: info.height_in_millimeters,
+                modes: info.modes,
+                preferred_modes: info.preferred_modes,
+            });
+        }
+
+        let mut crtcs = Vec::with_capacity(resources.crtcs.len());
+        for id in resources.crtcs.iter().copied() {
+            let info = send_randr_request!(&randr::requests::GetCrtcInfo {
+                crtc: id,
+                timestamp: resources.config_timestamp,
+            });
+            crtcs.push(CrtcModel {
+                id,
+                x: info.x,
+                y: info.y,
+                width: info.width,
+                height: info.height,
+                mode: (info.mode != 0).then_some(info.mode),
+                rotation: info.current_rotation,
+                outputs: info.outputs,
+            });
+        }
+
+        // Names of all modes are concatenated in order of modeinfos
+        let mut names = resources.mode_names.as_slice();
+        let modes = resources
+            .modeinfos
+            .iter()
+            .map(|mode| {
+                let (name, rest) = names.split_at((mode.name_length as usize).min(names.len()));
+                names = rest;
+                ModeModel {
+                    id: mode.id,
+                    name: String::from_utf8_lossy(name).into_owned(),
+                    width: mode.width_in_pixels,
+                    height: mode.height_in_pixels,
+                    refresh: refresh_rate(mode),
+                }
+            })
+            .collect();
+
+        let monitors_reply = send_randr_request!(&randr::requests::GetMonitors {
+            window: root,
+            get_active: false,
+        });
+        let mut monitors = Vec::with_capacity(monitors_reply.monitors.len());
+        for monitor in monitors_reply.monitors {
+            let pending = display.send_request(&requests::GetAtomName { atom: monitor.name })?;
+            display.flush()?;
+            let name = display.await_pending_reply(pending)?.unwrap().name;
+            monitors.push(MonitorModel {
+                name: name.to_string(),
+                primary: monitor.primary,
+                automatic: monitor.automatic,
+                x: monitor.x,
+                y: monitor.y,
+                width: monitor.width_in_pixels,
+                height: monitor.height_in_pixels,
+                width_in_millimeters: monitor.width_in_millimeters,
+                height_in_millimeters: monitor.height_in_millimeters,
+                outputs: monitor.outputs,
+            });
+        }
+
+        Ok(Self {
+            screen,
+            width,
+            height,
+            min_width: size_range.min_width,
+            min_height: size_range.min_height,
+            max_width: size_range.max_width,
+            max_height: size_range.max_height,
+            outputs,
+            crtcs,
+            modes,
+            monitors,
+        })
+    }
+}
+
+fn refresh_rate(mode: &randr::ModeInfo) -> Option<f32> {
+    let mut v_total = mode.v_total as f32;
+    if mode.mode_flags.has(ModeFlag::DOUBLE_SCAN) {
+        v_total *= 2.0;
+    }
+    if mode.mode_flags.has(ModeFlag::INTERLACE) {
+        v_total /= 2.0;
+    }
+
+    let pixels = mode.h_total as f32 * v_total;
+    (mode.dot_closk != 0 && pixels != 0.0).then(|| mode.dot_closk as f32 / pixels)
+}
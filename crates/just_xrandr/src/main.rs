@@ -9,17 +9,20 @@
 use crate::arguments::Args;
 use just_x11::{
     bitmask,
-    connection::{DisplayVar, XConnection},
     error::Error,
     extensions::{
         randr::{self},
         render::{self, Fixed},
     },
+    json::ToJson,
+    replies::String8,
     requests, OrNone, ResourceId, XDisplay,
 };
-use std::{collections::HashMap, env, process::ExitCode, str::FromStr};
+use just_x11_simple::display as x11_display;
+use std::{collections::HashMap, env, fmt::Display, process::ExitCode, str::FromStr};
 
 mod arguments;
+mod json;
 
 #[derive(Debug, Clone)]
 struct Monitor {
@@ -277,10 +280,9 @@ impl Name {
     }
 
     fn set(&mut self, name: String, valid: NameKind) {
-        if valid.has(NameKind::XID) && name.len() > 2 && &name[..2] == "0x" {
-            let name = &name[2..];
-            if let Ok(xid) = u32::from_str_radix(name, 16) {
-                self.xid = OrNone::new(ResourceId::from(xid));
+        if valid.has(NameKind::XID) && name.starts_with("0x") {
+            if let Ok(xid) = ResourceId::from_str(&name) {
+                self.xid = OrNone::new(xid);
                 self.kind |= NameKind::XID;
                 return;
             }
@@ -304,6 +306,21 @@ impl Name {
     }
 }
 
+impl Display for Name {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.kind.has(NameKind::XID) {
+            match self.xid.value() {
+                Some(xid) => write!(f, "{}", xid),
+                None => write!(f, "None"),
+            }
+        } else if self.kind.has(NameKind::INDEX) {
+            write!(f, "{}", self.index)
+        } else {
+            write!(f, "{}", self.string)
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 enum Relation {
     LeftOf(String),
@@ -366,23 +383,16 @@ struct Mode {
     mode: XRRModeInfo,
 }
 
-fn run(args: Args) -> Result<(), Error> {
+fn run(args: Args, mut display: XDisplay) -> Result<(), Error> {
     // dbg!(&args);
 
     if args.version {
         println!("xrandr program version       {}", env!("CARGO_PKG_VERSION"))
     }
 
-    let mut display = if let Some(display_name) = args.display_name {
-        let connection = XConnection::with_display(DisplayVar::from_str(&display_name)?)?;
-        XDisplay::with_connection(connection)?
-    } else {
-        XDisplay::open()?
-    };
-
     let screen = if let Some(screen) = args.screen {
         let no_screens = display.screens().len();
-        if screen as usize > no_screens {
+        if screen as usize >= no_screens {
             eprintln!(
                 "Invalid screen number {} (display has {})",
                 screen, no_screens
@@ -391,7 +401,7 @@ fn run(args: Args) -> Result<(), Error> {
         }
         screen
     } else {
-        0
+        display.default_screen_index() as _
     };
 
     let root = display.screens()[screen as usize].root;
@@ -415,6 +425,13 @@ fn run(args: Args) -> Result<(), Error> {
         }};
     }
 
+    macro_rules! send_request {
+        ($request:expr) => {{
+            let pending = display.send_request($request)?;
+            display.await_pending_reply(pending)?.unwrap()
+        }};
+    }
+
     let randr_version = send_randr_request!(&randr::requests::QueryVersion {
         major_version: randr::SUPPORTED_MAJOR,
         minor_version: randr::SUPPORTED_MINOR,
@@ -431,6 +448,104 @@ fn run(args: Args) -> Result<(), Error> {
         || (randr_version.major_version == 1 && randr_version.minor_version >= 5);
     assert!(has_1_5, "RandR version below 1.5 not supported"); // TODO: Add support
 
+    if args.monitorit || args.list_monitors || args.list_active_monitors {
+        let screen_resources =
+            send_randr_request!(&randr::requests::GetScreenResourcesCurrent { window: root });
+        let mut outputs = Vec::with_capacity(screen_resources.outputs.len());
+        for output in screen_resources.outputs.iter().copied() {
+            let output_info = send_randr_request!(&randr::requests::GetOutputInfo {
+                output,
+                timestamp: screen_resources.config_timestamp,
+            });
+            outputs.push((
+                output,
+                String::from_utf8_lossy(&output_info.name).into_owned(),
+            ));
+        }
+
+        for monitor in &args.monitors {
+            let name = send_request!(&requests::InternAtom {
+                only_if_exists: false,
+                name: String8::from_str(&monitor.name).unwrap(),
+            })
+            .atom;
+
+            if !monitor.set {
+                display.send_extension_request(
+                    &randr::requests::DeleteMonitor { window: root, name },
+                    randr_query.major_opcode,
+                )?;
+                continue;
+            }
+
+            let mut monitor_outputs = Vec::with_capacity(monitor.outputs.len());
+            for output in &monitor.outputs {
+                let Some(output_id) = find_output(&outputs, &output.output) else {
+                    eprintln!("xrandr: cannot find output {}", output.output);
+                    std::process::exit(1);
+                };
+                monitor_outputs.push(output_id);
+            }
+
+            display.send_extension_request(
+                &randr::requests::SetMonitor {
+                    window: root,
+                    monitor: randr::MonitorInfo {
+                        name,
+                        primary: monitor.primary,
+                        automatic: false,
+                        x: monitor.x as i16,
+                        y: monitor.y as i16,
+                        width_in_pixels: monitor.width as u16,
+                        height_in_pixels: monitor.height as u16,
+                        width_in_millimeters: monitor.mmwidth,
+                        height_in_millimeters: monitor.mmheight,
+                        outputs: monitor_outputs,
+                    },
+                },
+                randr_query.major_opcode,
+            )?;
+        }
+        display.flush()?;
+
+        if args.list_monitors || args.list_active_monitors {
+            let monitors = send_randr_request!(&randr::requests::GetMonitors {
+                window: root,
+                get_active: args.list_active_monitors,
+            });
+
+            println!("Monitors: {}", monitors.monitors.len());
+            for (idx, monitor) in monitors.monitors.iter().enumerate() {
+                let name = send_request!(&requests::GetAtomName { atom: monitor.name }).name;
+                print!(
+                    " {}: {}{}{} {}/{}x{}/{}{:+}{:+} ",
+                    idx,
+                    if monitor.automatic { "+" } else { "" },
+                    if monitor.primary { "*" } else { "" },
+                    name,
+                    monitor.width_in_pixels,
+                    monitor.width_in_millimeters,
+                    monitor.height_in_pixels,
+                    monitor.height_in_millimeters,
+                    monitor.x,
+                    monitor.y,
+                );
+                for output in &monitor.outputs {
+                    match outputs.iter().find(|(id, _)| id == output) {
+                        Some((_, output_name)) => print!(" {}", output_name),
+                        None => print!(" {}", output),
+                    }
+                }
+                println!();
+            }
+        }
+    }
+
+    if args.json {
+        let model = json::ScreenModel::query(&mut display, screen, randr_query.major_opcode)?;
+        println!("{}", model.to_json());
+    }
+
     // TODO: has_1_2 check
     if args.modeit || true {
         let _screen_size_range =
@@ -460,15 +575,42 @@ fn run(args: Args) -> Result<(), Error> {
     Ok(())
 }
 
+/// Output matching `name` as XID, index into `outputs` or output name
+fn find_output(
+    outputs: &[(randr::replies::OutputId, String)],
+    name: &Name,
+) -> Option<randr::replies::OutputId> {
+    if name.kind.has(NameKind::XID) {
+        let xid = name.xid.value()?;
+        return outputs.iter().map(|(id, _)| *id).find(|id| id.id() == xid);
+    }
+    if name.kind.has(NameKind::INDEX) {
+        return outputs.get(name.index as usize).map(|(id, _)| *id);
+    }
+    outputs
+        .iter()
+        .find(|(_, output_name)| *output_name == name.string)
+        .map(|(id, _)| *id)
+}
+
 fn main() -> ExitCode {
     match Args::from_cli(env::args()) {
-        Ok(args) => match run(args) {
-            Ok(()) => ExitCode::SUCCESS,
-            Err(err) => {
-                eprintln!("xrandr: {}", err);
-                ExitCode::FAILURE
+        Ok(args) => {
+            let display = match x11_display::open(args.display_name.as_deref()) {
+                Ok(display) => display,
+                Err(err) => {
+                    eprintln!("xrandr: {}", err);
+                    return ExitCode::FAILURE;
+                }
+            };
+            match run(args, display) {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(err) => {
+                    eprintln!("xrandr: {}", err);
+                    ExitCode::FAILURE
+                }
             }
-        },
+        }
         Err(err) => {
             eprintln!("xrandr: {:?}", err); // TODO: Formatter
             ExitCode::FAILURE